@@ -4,11 +4,13 @@ use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
 };
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{encode, EncodingKey, Header};
 
 use crate::models::{TokenClaims, UserInfo};
 use reticulum_core::{Config, Error, Result};
 
+pub use reticulum_core::jwt::validate_token;
+
 /// Hash a password using Argon2
 pub fn hash_password(password: &str) -> Result<String> {
     let salt = SaltString::generate(&mut OsRng);
@@ -71,17 +73,6 @@ pub fn generate_refresh_token(config: &Config, user: &UserInfo) -> Result<String
     .map_err(|e| Error::internal(format!("Failed to generate refresh token: {}", e)))
 }
 
-/// Validate JWT token and return claims
-pub fn validate_token(config: &Config, token: &str) -> Result<TokenClaims> {
-    decode::<TokenClaims>(
-        token,
-        &DecodingKey::from_secret(config.auth.jwt_secret.as_ref()),
-        &Validation::default(),
-    )
-    .map(|data| data.claims)
-    .map_err(|_| Error::auth("Invalid or expired token"))
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
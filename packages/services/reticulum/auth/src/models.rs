@@ -43,13 +43,7 @@ pub struct RefreshTokenRequest {
     pub refresh_token: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct TokenClaims {
-    pub sub: String, // user id
-    pub email: String,
-    pub exp: usize,
-    pub iat: usize,
-}
+pub use reticulum_core::jwt::TokenClaims;
 
 #[cfg(test)]
 mod tests {
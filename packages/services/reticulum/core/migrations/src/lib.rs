@@ -8,6 +8,9 @@ mod m20250101_000005_add_oauth_and_sessions;
 mod m20250101_000006_add_profile_settings;
 mod m20250101_000007_create_assets;
 mod m20250101_000008_create_upload_sessions;
+mod m20250101_000009_create_room_presence_events;
+mod m20250101_000010_create_moderation_actions;
+mod m20250101_000011_create_chat_messages;
 
 pub mod runner;
 
@@ -25,6 +28,9 @@ impl MigratorTrait for Migrator {
             Box::new(m20250101_000006_add_profile_settings::Migration),
             Box::new(m20250101_000007_create_assets::Migration),
             Box::new(m20250101_000008_create_upload_sessions::Migration),
+            Box::new(m20250101_000009_create_room_presence_events::Migration),
+            Box::new(m20250101_000010_create_moderation_actions::Migration),
+            Box::new(m20250101_000011_create_chat_messages::Migration),
             ]
         }
 }
@@ -0,0 +1,67 @@
+use sea_orm_migration::prelude::*;
+use sea_query::{ColumnDef, Index, Table};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RoomPresenceEvents::Table)
+                    .if_not_exists()
+                    .col(&mut ColumnDef::new(RoomPresenceEvents::Id).integer().auto_increment().primary_key())
+                    .col(&mut ColumnDef::new(RoomPresenceEvents::RoomId).string().not_null())
+                    .col(&mut ColumnDef::new(RoomPresenceEvents::InstanceId).string().not_null())
+                    .col(&mut ColumnDef::new(RoomPresenceEvents::UserId).string().not_null())
+                    .col(&mut ColumnDef::new(RoomPresenceEvents::EventType).string().not_null())
+                    .col(&mut ColumnDef::new(RoomPresenceEvents::OccurredAt).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create index on room_id and occurred_at for time-range queries per room
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_room_presence_events_room_time")
+                    .table(RoomPresenceEvents::Table)
+                    .col(RoomPresenceEvents::RoomId)
+                    .col(RoomPresenceEvents::OccurredAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create index on user_id for returning-user lookups
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_room_presence_events_user_id")
+                    .table(RoomPresenceEvents::Table)
+                    .col(RoomPresenceEvents::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RoomPresenceEvents::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RoomPresenceEvents {
+    Table,
+    Id,
+    RoomId,
+    InstanceId,
+    UserId,
+    EventType,
+    OccurredAt,
+}
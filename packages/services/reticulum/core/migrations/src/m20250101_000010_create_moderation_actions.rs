@@ -0,0 +1,71 @@
+use sea_orm_migration::prelude::*;
+use sea_query::{ColumnDef, Index, Table};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ModerationActions::Table)
+                    .if_not_exists()
+                    .col(&mut ColumnDef::new(ModerationActions::Id).integer().auto_increment().primary_key())
+                    .col(&mut ColumnDef::new(ModerationActions::RoomId).string().not_null())
+                    .col(&mut ColumnDef::new(ModerationActions::Action).string().not_null())
+                    .col(&mut ColumnDef::new(ModerationActions::TargetUserId).string())
+                    .col(&mut ColumnDef::new(ModerationActions::ActorUserId).string().not_null())
+                    .col(&mut ColumnDef::new(ModerationActions::Reason).text())
+                    .col(&mut ColumnDef::new(ModerationActions::ExpiresAt).timestamp())
+                    .col(&mut ColumnDef::new(ModerationActions::CreatedAt).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create index on room_id and created_at for replaying a room's actions
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_moderation_actions_room_time")
+                    .table(ModerationActions::Table)
+                    .col(ModerationActions::RoomId)
+                    .col(ModerationActions::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create index on target_user_id for per-user audit queries
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_moderation_actions_target_user_id")
+                    .table(ModerationActions::Table)
+                    .col(ModerationActions::TargetUserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ModerationActions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ModerationActions {
+    Table,
+    Id,
+    RoomId,
+    Action,
+    TargetUserId,
+    ActorUserId,
+    Reason,
+    ExpiresAt,
+    CreatedAt,
+}
@@ -0,0 +1,68 @@
+use sea_orm_migration::prelude::*;
+use sea_query::{ColumnDef, Index, Table};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChatMessages::Table)
+                    .if_not_exists()
+                    .col(&mut ColumnDef::new(ChatMessages::Id).integer().auto_increment().primary_key())
+                    .col(&mut ColumnDef::new(ChatMessages::ChatId).string().not_null().unique_key())
+                    .col(&mut ColumnDef::new(ChatMessages::RoomId).string().not_null())
+                    .col(&mut ColumnDef::new(ChatMessages::FromClientId).string().not_null())
+                    .col(&mut ColumnDef::new(ChatMessages::FromUserId).string())
+                    .col(&mut ColumnDef::new(ChatMessages::Kind).string().not_null())
+                    .col(&mut ColumnDef::new(ChatMessages::ToUserId).string())
+                    .col(&mut ColumnDef::new(ChatMessages::Channel).string())
+                    .col(&mut ColumnDef::new(ChatMessages::Body).text().not_null())
+                    .col(&mut ColumnDef::new(ChatMessages::CreatedAt).timestamp().not_null())
+                    .col(&mut ColumnDef::new(ChatMessages::DeletedAt).timestamp())
+                    .col(&mut ColumnDef::new(ChatMessages::DeletedBy).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create index on room_id and id for paging through a room's history
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_chat_messages_room_id")
+                    .table(ChatMessages::Table)
+                    .col(ChatMessages::RoomId)
+                    .col(ChatMessages::Id)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ChatMessages::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ChatMessages {
+    Table,
+    Id,
+    ChatId,
+    RoomId,
+    FromClientId,
+    FromUserId,
+    Kind,
+    ToUserId,
+    Channel,
+    Body,
+    CreatedAt,
+    DeletedAt,
+    DeletedBy,
+}
@@ -0,0 +1,114 @@
+//! Entity synchronization between presence and hub
+//!
+//! Presence publishes entity changes made by clients on
+//! [`ENTITY_REQUESTS_CHANNEL`]. The hub applies them to its room state, the
+//! single source of truth for entities, and publishes every applied change,
+//! whatever its source, on [`ENTITY_EVENTS_CHANNEL`]. Presence instances
+//! deliver those events to the clients in the room.
+//!
+//! Undo and redo requests from clients go to the hub on
+//! [`ENTITY_HISTORY_CHANNEL`]; the changes they apply are published like any
+//! other.
+
+use crate::models::EntityData;
+use serde::{Deserialize, Serialize};
+
+/// Channel carrying entity changes from presence to the hub
+pub const ENTITY_REQUESTS_CHANNEL: &str = "graphwiz:entities:requests";
+
+/// Channel carrying changes applied by the hub to presence
+pub const ENTITY_EVENTS_CHANNEL: &str = "graphwiz:entities:events";
+
+/// Channel carrying undo and redo requests from presence to the hub
+pub const ENTITY_HISTORY_CHANNEL: &str = "graphwiz:entities:history";
+
+/// A change to one entity of a room
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EntityChange {
+    /// Create or replace an entity
+    Spawn { entity: EntityData },
+    /// Merge components into an existing entity; in events, all of its components
+    Update {
+        entity_id: String,
+        components: serde_json::Map<String, serde_json::Value>,
+    },
+    /// Remove an entity
+    Despawn { entity_id: String },
+}
+
+impl EntityChange {
+    pub fn entity_id(&self) -> &str {
+        match self {
+            EntityChange::Spawn { entity } => &entity.entity_id,
+            EntityChange::Update { entity_id, .. } | EntityChange::Despawn { entity_id } => entity_id,
+        }
+    }
+}
+
+/// Entity change requested by a client, for the hub to apply
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntitySyncRequest {
+    /// Room instance the entity lives in
+    pub room_id: String,
+    pub author_id: Option<String>,
+    pub change: EntityChange,
+}
+
+/// Direction of a step through a user's entity changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryStep {
+    Undo,
+    Redo,
+}
+
+/// Undo or redo requested by a client, for the hub to apply
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityHistoryRequest {
+    /// Room instance whose history is stepped through
+    pub room_id: String,
+    /// User whose own changes are undone or redone
+    pub user_id: String,
+    pub step: HistoryStep,
+}
+
+/// Entity change applied by the hub
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntitySyncEvent {
+    /// Room instance the entity lives in
+    pub room_id: String,
+    /// Sequence number of the operation in the room's history
+    pub seq: u64,
+    pub author_id: String,
+    pub change: EntityChange,
+    pub timestamp: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Quaternion, Vector3};
+
+    #[test]
+    fn test_change_wire_format() {
+        let change = EntityChange::Spawn {
+            entity: EntityData {
+                entity_id: "entity-1".to_string(),
+                room_id: "room-1".to_string(),
+                template_id: "cube".to_string(),
+                owner_id: "alice".to_string(),
+                position: Vector3 { x: 0.0, y: 1.0, z: 0.0 },
+                rotation: Quaternion { x: 0.0, y: 0.0, z: 0.0, w: 1.0 },
+                components: serde_json::json!({ "color": "red" }),
+            },
+        };
+        let json = serde_json::to_value(&change).unwrap();
+        assert_eq!(json["type"], "spawn");
+        assert_eq!(json["entity"]["entity_id"], "entity-1");
+
+        let despawn: EntityChange =
+            serde_json::from_value(serde_json::json!({ "type": "despawn", "entity_id": "entity-1" })).unwrap();
+        assert_eq!(despawn.entity_id(), "entity-1");
+    }
+}
@@ -21,6 +21,12 @@ pub enum Error {
     #[error("Not found: {0}")]
     NotFound(String),
 
+    #[error("Rate limited: {0}")]
+    RateLimited(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 
@@ -45,6 +51,14 @@ impl Error {
         Error::NotFound(msg.into())
     }
 
+    pub fn rate_limited(msg: impl Into<String>) -> Self {
+        Error::RateLimited(msg.into())
+    }
+
+    pub fn conflict(msg: impl Into<String>) -> Self {
+        Error::Conflict(msg.into())
+    }
+
     pub fn internal(msg: impl Into<String>) -> Self {
         Error::Internal(msg.into())
     }
@@ -70,6 +84,8 @@ impl ResponseError for Error {
             Error::Authorization(msg) => (StatusCode::FORBIDDEN, "authorization_error", msg.clone()),
             Error::Validation(msg) => (StatusCode::BAD_REQUEST, "validation_error", msg.clone()),
             Error::NotFound(msg) => (StatusCode::NOT_FOUND, "not_found", msg.clone()),
+            Error::RateLimited(msg) => (StatusCode::TOO_MANY_REQUESTS, "rate_limited", msg.clone()),
+            Error::Conflict(msg) => (StatusCode::CONFLICT, "conflict", msg.clone()),
             Error::Internal(msg) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "internal_error",
@@ -5,7 +5,7 @@ use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation}
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::Error;
+use crate::{Config, Error};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
@@ -142,6 +142,26 @@ pub struct TokenPair {
     pub refresh_token: String,
 }
 
+/// Claims of the tokens issued by the auth service
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TokenClaims {
+    pub sub: String, // user id
+    pub email: String,
+    pub exp: usize,
+    pub iat: usize,
+}
+
+/// Validate a token issued by the auth service and return its claims
+pub fn validate_token(config: &Config, token: &str) -> Result<TokenClaims, Error> {
+    decode::<TokenClaims>(
+        token,
+        &DecodingKey::from_secret(config.auth.jwt_secret.as_ref()),
+        &Validation::default(),
+    )
+    .map(|data| data.claims)
+    .map_err(|_| Error::auth("Invalid or expired token"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -216,4 +236,27 @@ mod tests {
         assert_eq!(access_claims.sub, user_id.to_string());
         assert_eq!(refresh_claims.sub, user_id.to_string());
     }
+
+    #[test]
+    fn test_validate_auth_service_token() {
+        let config = Config::load_or_default().unwrap();
+        let now = Utc::now().timestamp() as usize;
+        let claims = TokenClaims {
+            sub: "1".to_string(),
+            email: "test@example.com".to_string(),
+            exp: now + 60,
+            iat: now,
+        };
+        let token = encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(config.auth.jwt_secret.as_ref()),
+        )
+        .unwrap();
+
+        let claims = validate_token(&config, &token).unwrap();
+        assert_eq!(claims.sub, "1");
+        assert_eq!(claims.email, "test@example.com");
+        assert!(validate_token(&config, "not-a-token").is_err());
+    }
 }
@@ -6,12 +6,14 @@ pub mod auth;
 pub mod cache;
 pub mod config;
 pub mod db;
+pub mod entity_sync;
 pub mod error;
 pub mod jwt;
 pub mod log_store;
 pub mod middleware;
 pub mod models;
 pub mod metrics;
+pub mod occupancy;
 
 pub use auth::{hash_password, verify_password, validate_password_strength, PasswordHasherWrapper};
 pub use config::Config;
@@ -1,10 +1,13 @@
 //! Database models using SeaORM
 
 pub mod assets;
+pub mod chat_messages;
 pub mod entities;
 pub mod magic_link_tokens;
+pub mod moderation_actions;
 pub mod oauth_accounts;
 pub mod roles;
+pub mod room_presence_events;
 pub mod room_states;
 pub mod rooms;
 pub mod sessions;
@@ -12,13 +15,18 @@ pub mod upload_sessions;
 pub mod users;
 
 pub use assets::{Asset, AssetModel, AssetType};
+pub use chat_messages::{ChatKind, ChatRecord, ChatRecordModel};
 pub use entities::{EntityData, EntityModel, Quaternion, Vector3};
 pub use magic_link_tokens::{
     ActiveModel as MagicLinkTokenActiveModel, Entity as MagicLinkTokenEntity, MagicLinkTokenModel,
     Model as MagicLinkToken,
 };
+pub use moderation_actions::{ModerationAction, ModerationActionModel, ModerationActionType};
 pub use oauth_accounts::{OAuthAccountModel, OAuthProvider};
 pub use roles::{RoleAssignment, RoleModel, UserRole};
+pub use room_presence_events::{
+    PresenceEventType, RoomPresenceEvent, RoomPresenceEventModel,
+};
 pub use room_states::Model;
 pub use rooms::{Room, RoomModel};
 pub use sessions::{Session, SessionModel};
@@ -0,0 +1,211 @@
+//! Chat message model
+//!
+//! Room chat history. Whispers and team messages are stored alongside public
+//! messages and filtered by reader, so a client replaying history sees what it
+//! would have seen live. Whispers are addressed to and read by verified user
+//! ids, never by the client ids connections pick for themselves. Deleted messages keep their row for the audit trail
+//! and are hidden from history.
+
+use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{Condition, QueryOrder, QuerySelect};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "chat_messages")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub chat_id: String,
+    pub room_id: String,
+    pub from_client_id: String,
+    pub from_user_id: Option<String>,
+    pub kind: String,
+    pub to_user_id: Option<String>,
+    pub channel: Option<String>,
+    pub body: String,
+    pub created_at: DateTime,
+    pub deleted_at: Option<DateTime>,
+    pub deleted_by: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatKind {
+    Normal,
+    Whisper,
+    Shout,
+    Team,
+}
+
+impl ChatKind {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ChatKind::Normal => "normal",
+            ChatKind::Whisper => "whisper",
+            ChatKind::Shout => "shout",
+            ChatKind::Team => "team",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "normal" => Ok(ChatKind::Normal),
+            "whisper" => Ok(ChatKind::Whisper),
+            "shout" => Ok(ChatKind::Shout),
+            "team" => Ok(ChatKind::Team),
+            _ => Err(format!("Invalid chat kind: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChatRecord {
+    /// Row id; orders a room's history and pages it
+    pub id: i32,
+    /// Id sent to clients and used to delete the message
+    pub chat_id: String,
+    pub room_id: String,
+    pub from_client_id: String,
+    pub from_user_id: Option<String>,
+    pub kind: ChatKind,
+    /// Recipient of a whisper
+    pub to_user_id: Option<String>,
+    /// Team of a team message
+    pub channel: Option<String>,
+    pub body: String,
+    pub created_at: DateTime,
+}
+
+impl ChatRecord {
+    /// Whether the verified user `user_id` on team `team` may read this message
+    pub fn is_visible_to(&self, user_id: Option<&str>, team: Option<&str>) -> bool {
+        match self.kind {
+            ChatKind::Normal | ChatKind::Shout => true,
+            ChatKind::Whisper => {
+                user_id.is_some()
+                    && (user_id == self.from_user_id.as_deref() || user_id == self.to_user_id.as_deref())
+            }
+            ChatKind::Team => team.is_some() && team == self.channel.as_deref(),
+        }
+    }
+}
+
+impl TryFrom<Model> for ChatRecord {
+    type Error = crate::Error;
+
+    fn try_from(model: Model) -> crate::Result<Self> {
+        Ok(Self {
+            id: model.id,
+            kind: ChatKind::from_str(&model.kind).map_err(crate::Error::internal)?,
+            chat_id: model.chat_id,
+            room_id: model.room_id,
+            from_client_id: model.from_client_id,
+            from_user_id: model.from_user_id,
+            to_user_id: model.to_user_id,
+            channel: model.channel,
+            body: model.body,
+            created_at: model.created_at,
+        })
+    }
+}
+
+pub struct ChatRecordModel;
+
+impl ChatRecordModel {
+    /// Store a message; the returned record carries its row id
+    pub async fn record(db: &DatabaseConnection, record: &ChatRecord) -> crate::Result<ChatRecord> {
+        let model = ActiveModel {
+            chat_id: Set(record.chat_id.clone()),
+            room_id: Set(record.room_id.clone()),
+            from_client_id: Set(record.from_client_id.clone()),
+            from_user_id: Set(record.from_user_id.clone()),
+            kind: Set(record.kind.as_str().to_string()),
+            to_user_id: Set(record.to_user_id.clone()),
+            channel: Set(record.channel.clone()),
+            body: Set(record.body.clone()),
+            created_at: Set(record.created_at),
+            ..Default::default()
+        };
+
+        let result = model.insert(db).await?;
+        ChatRecord::try_from(result)
+    }
+
+    /// The newest `limit` messages of a room older than `before_id` that the
+    /// reader may see, oldest first. Pass `None` for both reader fields to get
+    /// public messages only.
+    pub async fn find_visible(
+        db: &DatabaseConnection,
+        room_id: &str,
+        user_id: Option<&str>,
+        team: Option<&str>,
+        before_id: Option<i32>,
+        limit: u64,
+    ) -> crate::Result<Vec<ChatRecord>> {
+        let mut visible = Condition::any().add(
+            Column::Kind.is_in([ChatKind::Normal.as_str(), ChatKind::Shout.as_str()]),
+        );
+        if let Some(user_id) = user_id {
+            visible = visible.add(
+                Condition::all()
+                    .add(Column::Kind.eq(ChatKind::Whisper.as_str()))
+                    .add(
+                        Condition::any()
+                            .add(Column::FromUserId.eq(user_id))
+                            .add(Column::ToUserId.eq(user_id)),
+                    ),
+            );
+        }
+        if let Some(team) = team {
+            visible = visible.add(
+                Condition::all()
+                    .add(Column::Kind.eq(ChatKind::Team.as_str()))
+                    .add(Column::Channel.eq(team)),
+            );
+        }
+
+        let mut query = Entity::find()
+            .filter(Column::RoomId.eq(room_id))
+            .filter(Column::DeletedAt.is_null())
+            .filter(visible);
+        if let Some(before_id) = before_id {
+            query = query.filter(Column::Id.lt(before_id));
+        }
+
+        let results = query.order_by_desc(Column::Id).limit(limit).all(db).await?;
+        results.into_iter().rev().map(ChatRecord::try_from).collect()
+    }
+
+    /// Hide a message from history. Returns `None` when the room has no such
+    /// message or it was already deleted.
+    pub async fn mark_deleted(
+        db: &DatabaseConnection,
+        room_id: &str,
+        chat_id: &str,
+        deleted_by: &str,
+    ) -> crate::Result<Option<ChatRecord>> {
+        let model = match Entity::find()
+            .filter(Column::RoomId.eq(room_id))
+            .filter(Column::ChatId.eq(chat_id))
+            .filter(Column::DeletedAt.is_null())
+            .one(db)
+            .await?
+        {
+            Some(model) => model,
+            None => return Ok(None),
+        };
+
+        let mut active: ActiveModel = model.into();
+        active.deleted_at = Set(Some(chrono::Utc::now().naive_utc()));
+        active.deleted_by = Set(Some(deleted_by.to_string()));
+        let result = active.update(db).await?;
+        ChatRecord::try_from(result).map(Some)
+    }
+}
+
@@ -0,0 +1,153 @@
+//! Moderation action model
+//!
+//! Every moderation action taken in a room is appended here and never changed.
+//! The log is the audit trail, and replaying a room's actions in order gives
+//! its current mutes, bans and lock state.
+
+use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{Condition, QueryOrder};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "moderation_actions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub room_id: String,
+    pub action: String,
+    pub target_user_id: Option<String>,
+    pub actor_user_id: String,
+    pub reason: Option<String>,
+    pub expires_at: Option<DateTime>,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationActionType {
+    Kick,
+    Mute,
+    Unmute,
+    Ban,
+    Unban,
+    Lock,
+    Unlock,
+}
+
+impl ModerationActionType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ModerationActionType::Kick => "kick",
+            ModerationActionType::Mute => "mute",
+            ModerationActionType::Unmute => "unmute",
+            ModerationActionType::Ban => "ban",
+            ModerationActionType::Unban => "unban",
+            ModerationActionType::Lock => "lock",
+            ModerationActionType::Unlock => "unlock",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "kick" => Ok(ModerationActionType::Kick),
+            "mute" => Ok(ModerationActionType::Mute),
+            "unmute" => Ok(ModerationActionType::Unmute),
+            "ban" => Ok(ModerationActionType::Ban),
+            "unban" => Ok(ModerationActionType::Unban),
+            "lock" => Ok(ModerationActionType::Lock),
+            "unlock" => Ok(ModerationActionType::Unlock),
+            _ => Err(format!("Invalid moderation action: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModerationAction {
+    pub id: i32,
+    pub room_id: String,
+    pub action: ModerationActionType,
+    /// User the action applies to; `None` for room-wide actions such as locks
+    pub target_user_id: Option<String>,
+    pub actor_user_id: String,
+    pub reason: Option<String>,
+    /// End of a timed mute or ban; `None` lasts until lifted
+    pub expires_at: Option<DateTime>,
+    pub created_at: DateTime,
+}
+
+impl TryFrom<Model> for ModerationAction {
+    type Error = crate::Error;
+
+    fn try_from(model: Model) -> crate::Result<Self> {
+        Ok(Self {
+            id: model.id,
+            action: ModerationActionType::from_str(&model.action).map_err(crate::Error::internal)?,
+            room_id: model.room_id,
+            target_user_id: model.target_user_id,
+            actor_user_id: model.actor_user_id,
+            reason: model.reason,
+            expires_at: model.expires_at,
+            created_at: model.created_at,
+        })
+    }
+}
+
+pub struct ModerationActionModel;
+
+impl ModerationActionModel {
+    pub async fn record(
+        db: &DatabaseConnection,
+        room_id: &str,
+        action: ModerationActionType,
+        target_user_id: Option<&str>,
+        actor_user_id: &str,
+        reason: Option<&str>,
+        expires_at: Option<DateTime>,
+    ) -> crate::Result<ModerationAction> {
+        let model = ActiveModel {
+            room_id: Set(room_id.to_string()),
+            action: Set(action.as_str().to_string()),
+            target_user_id: Set(target_user_id.map(str::to_string)),
+            actor_user_id: Set(actor_user_id.to_string()),
+            reason: Set(reason.map(str::to_string)),
+            expires_at: Set(expires_at),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        };
+
+        let result = model.insert(db).await?;
+        ModerationAction::try_from(result)
+    }
+
+    /// Actions taken in a room, oldest first
+    pub async fn find_by_room(db: &DatabaseConnection, room_id: &str) -> crate::Result<Vec<ModerationAction>> {
+        let results = Entity::find()
+            .filter(Column::RoomId.eq(room_id))
+            .order_by_asc(Column::CreatedAt)
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await?;
+        results.into_iter().map(ModerationAction::try_from).collect()
+    }
+
+    /// Actions taken against or by a user in any room, oldest first
+    pub async fn find_by_user(db: &DatabaseConnection, user_id: &str) -> crate::Result<Vec<ModerationAction>> {
+        let results = Entity::find()
+            .filter(
+                Condition::any()
+                    .add(Column::TargetUserId.eq(user_id))
+                    .add(Column::ActorUserId.eq(user_id)),
+            )
+            .order_by_asc(Column::CreatedAt)
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await?;
+        results.into_iter().map(ModerationAction::try_from).collect()
+    }
+}
@@ -0,0 +1,114 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue::Set;
+use sea_orm::QueryOrder;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "room_presence_events")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub room_id: String,
+    pub instance_id: String,
+    pub user_id: String,
+    pub event_type: String,
+    pub occurred_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PresenceEventType {
+    Join,
+    Leave,
+}
+
+impl PresenceEventType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            PresenceEventType::Join => "join",
+            PresenceEventType::Leave => "leave",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "leave" => PresenceEventType::Leave,
+            _ => PresenceEventType::Join,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RoomPresenceEvent {
+    pub id: i32,
+    pub room_id: String,
+    pub instance_id: String,
+    pub user_id: String,
+    pub event_type: PresenceEventType,
+    pub occurred_at: DateTime,
+}
+
+impl From<Model> for RoomPresenceEvent {
+    fn from(model: Model) -> Self {
+        Self {
+            id: model.id,
+            room_id: model.room_id,
+            instance_id: model.instance_id,
+            user_id: model.user_id,
+            event_type: PresenceEventType::from_str(&model.event_type),
+            occurred_at: model.occurred_at,
+        }
+    }
+}
+
+pub struct RoomPresenceEventModel;
+
+impl RoomPresenceEventModel {
+    pub async fn record(
+        db: &DatabaseConnection,
+        room_id: &str,
+        instance_id: &str,
+        user_id: &str,
+        event_type: PresenceEventType,
+    ) -> crate::Result<RoomPresenceEvent> {
+        let model = ActiveModel {
+            room_id: Set(room_id.to_string()),
+            instance_id: Set(instance_id.to_string()),
+            user_id: Set(user_id.to_string()),
+            event_type: Set(event_type.as_str().to_string()),
+            occurred_at: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        };
+
+        let result = model.insert(db).await?;
+        Ok(RoomPresenceEvent::from(result))
+    }
+
+    /// Events for a room ordered by time, optionally bounded to `[from, to]`
+    pub async fn find_by_room(
+        db: &DatabaseConnection,
+        room_id: &str,
+        from: Option<DateTime>,
+        to: Option<DateTime>,
+    ) -> crate::Result<Vec<RoomPresenceEvent>> {
+        let mut query = Entity::find().filter(Column::RoomId.eq(room_id));
+        if let Some(from) = from {
+            query = query.filter(Column::OccurredAt.gte(from));
+        }
+        if let Some(to) = to {
+            query = query.filter(Column::OccurredAt.lte(to));
+        }
+
+        let results = query
+            .order_by_asc(Column::OccurredAt)
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await?;
+        Ok(results.into_iter().map(RoomPresenceEvent::from).collect())
+    }
+}
@@ -82,6 +82,11 @@ impl RoomModel {
         Ok(result.map(Room::from))
     }
 
+    pub async fn find_by_id(db: &DatabaseConnection, id: i32) -> crate::Result<Option<Room>> {
+        let result = Entity::find_by_id(id).one(db).await?;
+        Ok(result.map(Room::from))
+    }
+
     pub async fn list_active(db: &DatabaseConnection) -> crate::Result<Vec<Room>> {
         let results = Entity::find()
             .filter(Column::IsActive.eq(true))
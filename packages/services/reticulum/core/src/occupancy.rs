@@ -0,0 +1,54 @@
+//! Room occupancy updates from presence to hub
+//!
+//! Clients join and leave hub room instances over HTTP. A client that
+//! disconnects or dies often never leaves, so when presence closes a user's
+//! last connection to a room it publishes a [`SeatRelease`] on
+//! [`OCCUPANCY_CHANNEL`] and the hub removes the user from the instance as if
+//! they had left.
+//!
+//! Clients may also connect to presence without joining over HTTP. When a
+//! user's first connection to a room opens, presence publishes a
+//! [`SeatClaim`] on [`SEAT_CLAIM_CHANNEL`] and the hub seats the user in the
+//! instance unless they already hold a seat there.
+//!
+//! When the host of a room leaves, presence elects the next one and publishes
+//! a [`HostHandoff`] on [`HOST_CHANNEL`], so the hub hands the previous host's
+//! entities to them.
+
+use serde::{Deserialize, Serialize};
+
+/// Channel carrying occupancy changes from presence to the hub
+pub const OCCUPANCY_CHANNEL: &str = "graphwiz:rooms:occupancy";
+
+/// A user no longer connected to a room instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeatRelease {
+    /// Room instance the user was connected to
+    pub instance_id: String,
+    pub user_id: String,
+    /// Why presence let the user go, for the logs
+    pub reason: String,
+}
+
+/// Channel carrying new room connections from presence to the hub
+pub const SEAT_CLAIM_CHANNEL: &str = "graphwiz:rooms:claims";
+
+/// A user newly connected to a room instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeatClaim {
+    /// Room instance the user connected to
+    pub instance_id: String,
+    pub user_id: String,
+}
+
+/// Channel carrying host changes from presence to the hub
+pub const HOST_CHANNEL: &str = "graphwiz:rooms:host";
+
+/// Presence elected a new host for a room instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostHandoff {
+    /// Room instance whose host changed
+    pub instance_id: String,
+    /// User id of the new host
+    pub new_host: String,
+}
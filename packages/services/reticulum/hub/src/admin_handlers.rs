@@ -1,10 +1,14 @@
 //! Admin handlers for room management
 
-use actix_web::{web, HttpResponse, HttpRequest};
+use actix_web::{web, HttpResponse};
+use sea_orm::{ActiveModelTrait, ActiveValue};
+use serde::Deserialize;
 use serde_json::json;
 
 use reticulum_core::{Config, db, models as core_models};
 
+use crate::analytics::{RoomAnalytics, DEFAULT_BUCKET_SECS};
+
 /// List all rooms (admin view)
 pub async fn list_rooms(
     config: web::Data<Config>,
@@ -120,24 +124,27 @@ pub async fn update_room_config(
     let now = chrono::Utc::now().naive_utc();
 
     match core_models::RoomModel::find_by_id(&db, room_id).await {
-        Ok(Some(_room)) => {
-            let mut active_model: core_models::rooms::ActiveModel = _room.into();
+        Ok(Some(room)) => {
+            let mut active_model = core_models::rooms::ActiveModel {
+                id: ActiveValue::Unchanged(room.id),
+                ..Default::default()
+            };
 
             // Update only allowed fields
             if let Some(name) = body.name.clone() {
-                active_model.name = sea_orm::ActiveValue::Set(name);
+                active_model.name = ActiveValue::Set(name);
             }
             if let Some(description) = body.description.clone() {
-                active_model.description = sea_orm::ActiveValue::Set(description);
+                active_model.description = ActiveValue::Set(Some(description));
             }
             if let Some(max_players) = body.max_players {
-                active_model.max_players = sea_orm::ActiveValue::Set(max_players);
+                active_model.max_players = ActiveValue::Set(max_players);
             }
             if let Some(is_private) = body.is_private {
-                active_model.is_private = sea_orm::ActiveValue::Set(is_private);
+                active_model.is_private = ActiveValue::Set(is_private);
             }
 
-            active_model.updated_at = sea_orm::ActiveValue::Set(now);
+            active_model.updated_at = ActiveValue::Set(now);
 
             match active_model.update(&db).await {
                 Ok(updated_room) => {
@@ -194,10 +201,13 @@ pub async fn close_room(
     let now = chrono::Utc::now().naive_utc();
 
     match core_models::RoomModel::find_by_id(&db, room_id).await {
-        Ok(Some(_room)) => {
-            let mut active_model: core_models::rooms::ActiveModel = _room.into();
-            active_model.is_active = sea_orm::ActiveValue::Set(false);
-            active_model.updated_at = sea_orm::ActiveValue::Set(now);
+        Ok(Some(room)) => {
+            let mut active_model = core_models::rooms::ActiveModel {
+                id: ActiveValue::Unchanged(room.id),
+                ..Default::default()
+            };
+            active_model.is_active = ActiveValue::Set(false);
+            active_model.updated_at = ActiveValue::Set(now);
 
             match active_model.update(&db).await {
                 Ok(_) => {
@@ -267,6 +277,63 @@ pub async fn delete_room(
     }
 }
 
+/// Occupancy and session analytics for a room
+///
+/// Defaults to the last 24 hours; `format=csv` returns the occupancy timeline as CSV.
+pub async fn room_analytics(
+    config: web::Data<Config>,
+    path: web::Path<String>,
+    query: web::Query<AnalyticsQuery>,
+) -> HttpResponse {
+    let room_id = path.into_inner();
+
+    // Connect to database
+    let db = match db::connect(&config).await {
+        Ok(db) => db,
+        Err(e) => {
+            log::error!("Database connection failed: {}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "error": "database_error",
+                "message": "Failed to connect to database"
+            }));
+        }
+    };
+
+    let to = query.to.unwrap_or_else(|| chrono::Utc::now().naive_utc());
+    let from = query.from.unwrap_or(to - chrono::Duration::hours(24));
+    if from > to {
+        return HttpResponse::BadRequest().json(json!({
+            "error": "validation_error",
+            "message": "'from' must be before 'to'"
+        }));
+    }
+
+    let events = match core_models::RoomPresenceEventModel::find_by_room(&db, &room_id, None, Some(to)).await {
+        Ok(events) => events,
+        Err(e) => {
+            log::error!("Failed to load presence events: {}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "error": "internal_error",
+                "message": "Failed to retrieve room analytics"
+            }));
+        }
+    };
+
+    let bucket_secs = query.bucket_secs.unwrap_or(DEFAULT_BUCKET_SECS);
+    let analytics = RoomAnalytics::compute(&room_id, &events, from, to, bucket_secs);
+
+    match query.format.as_deref() {
+        Some("csv") => HttpResponse::Ok()
+            .content_type("text/csv")
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"room-{}-analytics.csv\"", room_id),
+            ))
+            .body(analytics.to_csv()),
+        _ => HttpResponse::Ok().json(analytics),
+    }
+}
+
 // Query parameter types for room listing
 #[derive(Debug, Deserialize)]
 pub struct RoomListQuery {
@@ -282,3 +349,12 @@ pub struct UpdateRoomRequest {
     pub max_players: Option<i32>,
     pub is_private: Option<bool>,
 }
+
+// Query parameter types for room analytics
+#[derive(Debug, Deserialize)]
+pub struct AnalyticsQuery {
+    pub from: Option<chrono::NaiveDateTime>,
+    pub to: Option<chrono::NaiveDateTime>,
+    pub bucket_secs: Option<i64>,
+    pub format: Option<String>,
+}
@@ -0,0 +1,281 @@
+//! Room analytics derived from recorded join/leave events
+
+use chrono::{Duration, NaiveDateTime};
+use reticulum_core::models::{PresenceEventType, RoomPresenceEvent};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// Default width of an occupancy timeline bucket
+pub const DEFAULT_BUCKET_SECS: i64 = 300;
+
+/// Cap on timeline length so a wide window with tiny buckets stays bounded
+pub const MAX_BUCKETS: i64 = 2_000;
+
+/// Highest concurrency observed within one timeline bucket
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct OccupancyPoint {
+    pub bucket_start: NaiveDateTime,
+    pub concurrent_users: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RoomAnalytics {
+    pub room_id: String,
+    pub from: NaiveDateTime,
+    pub to: NaiveDateTime,
+    pub bucket_secs: i64,
+    pub timeline: Vec<OccupancyPoint>,
+    pub peak_concurrency: usize,
+    pub peak_at: Option<NaiveDateTime>,
+    pub total_sessions: usize,
+    pub average_session_secs: f64,
+    pub unique_users: usize,
+    pub returning_users: usize,
+}
+
+impl RoomAnalytics {
+    /// Compute analytics for `[from, to]` from the room's events up to `to`.
+    ///
+    /// Events before `from` tell who was already in the room when the window
+    /// opened; their sessions count from their real start. A leave inside the
+    /// window whose join was never recorded counts as a session open since
+    /// `from`. Users present in the window with more than one session overall
+    /// are returning users. Sessions still open at `to` are closed there for
+    /// duration purposes.
+    pub fn compute(
+        room_id: &str,
+        events: &[RoomPresenceEvent],
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        bucket_secs: i64,
+    ) -> Self {
+        let bucket_secs = bucket_secs.max(1);
+        let to = to.max(from);
+
+        let mut events: Vec<&RoomPresenceEvent> = events.iter().filter(|e| e.occurred_at <= to).collect();
+        events.sort_by_key(|e| (e.occurred_at, e.id));
+        let (before, events) = events.split_at(events.partition_point(|e| e.occurred_at < from));
+
+        // Open sessions keyed by (user, instance)
+        let mut open: HashMap<(&str, &str), NaiveDateTime> = HashMap::new();
+        let mut sessions_per_user: HashMap<&str, usize> = HashMap::new();
+        for event in before {
+            let key = (event.user_id.as_str(), event.instance_id.as_str());
+            match event.event_type {
+                PresenceEventType::Join => {
+                    if open.insert(key, event.occurred_at).is_none() {
+                        *sessions_per_user.entry(key.0).or_default() += 1;
+                    }
+                }
+                PresenceEventType::Leave => {
+                    open.remove(&key);
+                }
+            }
+        }
+
+        // Users leaving without a recorded join were in the room at `from`
+        let mut replay: HashSet<(&str, &str)> = open.keys().copied().collect();
+        for event in events {
+            let key = (event.user_id.as_str(), event.instance_id.as_str());
+            match event.event_type {
+                PresenceEventType::Join => {
+                    replay.insert(key);
+                }
+                PresenceEventType::Leave => {
+                    if !replay.remove(&key) {
+                        open.insert(key, from);
+                        *sessions_per_user.entry(key.0).or_default() += 1;
+                    }
+                }
+            }
+        }
+
+        let mut present: HashSet<&str> = open.keys().map(|(user_id, _)| *user_id).collect();
+        let mut durations: Vec<i64> = Vec::new();
+        let mut peak_concurrency = open.len();
+        let mut peak_at = (!open.is_empty()).then_some(from);
+
+        let mut timeline = Vec::new();
+        let mut bucket_start = from;
+        let mut bucket_peak = 0;
+        let mut next = events.iter().peekable();
+
+        while bucket_start <= to && (timeline.len() as i64) < MAX_BUCKETS {
+            let bucket_end = bucket_start + Duration::seconds(bucket_secs);
+            bucket_peak = bucket_peak.max(open.len());
+
+            while let Some(event) = next.next_if(|e| e.occurred_at < bucket_end) {
+                let key = (event.user_id.as_str(), event.instance_id.as_str());
+                match event.event_type {
+                    PresenceEventType::Join => {
+                        present.insert(key.0);
+                        if open.insert(key, event.occurred_at).is_none() {
+                            *sessions_per_user.entry(key.0).or_default() += 1;
+                        }
+                    }
+                    PresenceEventType::Leave => {
+                        if let Some(started) = open.remove(&key) {
+                            durations.push((event.occurred_at - started).num_seconds());
+                        }
+                    }
+                }
+
+                if open.len() > peak_concurrency {
+                    peak_concurrency = open.len();
+                    peak_at = Some(event.occurred_at);
+                }
+                bucket_peak = bucket_peak.max(open.len());
+            }
+
+            timeline.push(OccupancyPoint {
+                bucket_start,
+                concurrent_users: bucket_peak,
+            });
+            bucket_peak = open.len();
+            bucket_start = bucket_end;
+        }
+
+        durations.extend(open.values().map(|started| (to - *started).num_seconds()));
+
+        let average_session_secs = if durations.is_empty() {
+            0.0
+        } else {
+            durations.iter().sum::<i64>() as f64 / durations.len() as f64
+        };
+
+        let returning_users = present
+            .iter()
+            .filter(|user_id| sessions_per_user.get(*user_id).is_some_and(|count| *count > 1))
+            .count();
+
+        Self {
+            room_id: room_id.to_string(),
+            from,
+            to,
+            bucket_secs,
+            timeline,
+            peak_concurrency,
+            peak_at,
+            total_sessions: durations.len(),
+            average_session_secs,
+            unique_users: present.len(),
+            returning_users,
+        }
+    }
+
+    /// Render the occupancy timeline as CSV
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("bucket_start,concurrent_users\n");
+        for point in &self.timeline {
+            csv.push_str(&format!(
+                "{},{}\n",
+                point.bucket_start.format("%Y-%m-%dT%H:%M:%S"),
+                point.concurrent_users
+            ));
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: i64) -> NaiveDateTime {
+        chrono::DateTime::from_timestamp(1_700_000_000 + secs, 0)
+            .unwrap()
+            .naive_utc()
+    }
+
+    fn event(id: i32, user_id: &str, event_type: PresenceEventType, secs: i64) -> RoomPresenceEvent {
+        RoomPresenceEvent {
+            id,
+            room_id: "room".to_string(),
+            instance_id: "room".to_string(),
+            user_id: user_id.to_string(),
+            event_type,
+            occurred_at: at(secs),
+        }
+    }
+
+    #[test]
+    fn test_peak_and_average_session() {
+        let events = vec![
+            event(1, "alice", PresenceEventType::Join, 0),
+            event(2, "bob", PresenceEventType::Join, 30),
+            event(3, "alice", PresenceEventType::Leave, 60),
+            event(4, "bob", PresenceEventType::Leave, 150),
+        ];
+
+        let analytics = RoomAnalytics::compute("room", &events, at(0), at(300), 60);
+
+        assert_eq!(analytics.peak_concurrency, 2);
+        assert_eq!(analytics.peak_at, Some(at(30)));
+        assert_eq!(analytics.total_sessions, 2);
+        assert_eq!(analytics.average_session_secs, 90.0);
+        assert_eq!(analytics.unique_users, 2);
+        assert_eq!(analytics.returning_users, 0);
+    }
+
+    #[test]
+    fn test_timeline_buckets() {
+        let events = vec![
+            event(1, "alice", PresenceEventType::Join, 10),
+            event(2, "bob", PresenceEventType::Join, 70),
+            event(3, "alice", PresenceEventType::Leave, 80),
+            event(4, "bob", PresenceEventType::Leave, 130),
+        ];
+
+        let analytics = RoomAnalytics::compute("room", &events, at(0), at(179), 60);
+        let counts: Vec<usize> = analytics.timeline.iter().map(|p| p.concurrent_users).collect();
+
+        assert_eq!(counts, vec![1, 2, 1]);
+        assert_eq!(analytics.to_csv().lines().count(), 4);
+    }
+
+    #[test]
+    fn test_returning_users_and_open_sessions() {
+        let events = vec![
+            // Bob's earlier visit, before the window
+            event(1, "bob", PresenceEventType::Join, -500),
+            event(2, "bob", PresenceEventType::Leave, -400),
+            event(3, "alice", PresenceEventType::Join, 0),
+            event(4, "alice", PresenceEventType::Leave, 10),
+            event(5, "alice", PresenceEventType::Join, 20),
+            event(6, "bob", PresenceEventType::Join, 40),
+            event(7, "carol", PresenceEventType::Join, 50),
+        ];
+
+        let analytics = RoomAnalytics::compute("room", &events, at(0), at(100), 60);
+
+        assert_eq!(analytics.unique_users, 3);
+        assert_eq!(analytics.returning_users, 2);
+        // One closed session plus three still open at the window end
+        assert_eq!(analytics.total_sessions, 4);
+    }
+
+    #[test]
+    fn test_users_already_in_the_room_are_counted() {
+        let events = vec![
+            // Alice joined before the window and leaves inside it
+            event(1, "alice", PresenceEventType::Join, -100),
+            event(2, "alice", PresenceEventType::Leave, 50),
+            // Bob's join was never recorded
+            event(3, "bob", PresenceEventType::Leave, 30),
+            // Dave came and went before the window
+            event(4, "dave", PresenceEventType::Join, -300),
+            event(5, "dave", PresenceEventType::Leave, -200),
+        ];
+
+        let analytics = RoomAnalytics::compute("room", &events, at(0), at(119), 60);
+        let counts: Vec<usize> = analytics.timeline.iter().map(|p| p.concurrent_users).collect();
+
+        assert_eq!(counts, vec![2, 0]);
+        assert_eq!(analytics.peak_concurrency, 2);
+        assert_eq!(analytics.peak_at, Some(at(0)));
+        assert_eq!(analytics.unique_users, 2);
+        assert_eq!(analytics.total_sessions, 2);
+        // Alice's session started at her real join, Bob's at the window start
+        assert_eq!(analytics.average_session_secs, 90.0);
+    }
+}
@@ -0,0 +1,95 @@
+//! Callers of the hub HTTP API
+//!
+//! Requests are authenticated with the access tokens issued by the auth
+//! service, signed with the shared JWT secret.
+
+use actix_web::HttpRequest;
+use reticulum_core::jwt::validate_token;
+use reticulum_core::models::{RoleModel, UserModel, UserRole};
+use reticulum_core::{Config, DatabaseConnection, Error, Result};
+
+use crate::room::RoomState;
+
+/// Authenticated caller of a hub endpoint
+#[derive(Debug, Clone)]
+pub struct Caller {
+    pub user_id: String,
+    pub email: String,
+}
+
+/// The caller of a request, or `None` when it carries no token
+pub fn caller(config: &Config, req: &HttpRequest) -> Result<Option<Caller>> {
+    let Some(header) = req.headers().get("Authorization") else {
+        return Ok(None);
+    };
+    let token = header
+        .to_str()
+        .ok()
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .ok_or_else(|| Error::auth("Invalid authorization header format"))?;
+
+    let claims = validate_token(config, token)?;
+    Ok(Some(Caller {
+        user_id: claims.sub,
+        email: claims.email,
+    }))
+}
+
+/// The caller of a request that must carry a token
+pub fn authenticate(config: &Config, req: &HttpRequest) -> Result<Caller> {
+    caller(config, req)?.ok_or_else(|| Error::auth("Missing authorization header"))
+}
+
+/// Role of a user, from their role assignment or their account
+pub async fn user_role(db: &DatabaseConnection, user_id: &str) -> Result<UserRole> {
+    let Ok(id) = user_id.parse::<i32>() else {
+        return Ok(UserRole::User);
+    };
+
+    if let Some(assignment) = RoleModel::get_user_role(db, id).await? {
+        return UserRole::from_str(&assignment.role).map_err(Error::internal);
+    }
+    let role = UserModel::find_by_id(db, id).await?.and_then(|user| user.role);
+    Ok(role
+        .and_then(|role| UserRole::from_str(&role).ok())
+        .unwrap_or(UserRole::User))
+}
+
+/// Check that `caller` created `room`, or is an admin
+pub async fn authorize_room_owner(db: &DatabaseConnection, caller: &Caller, room: &RoomState) -> Result<()> {
+    if room.created_by == caller.user_id {
+        return Ok(());
+    }
+    match user_role(db, &caller.user_id).await? {
+        UserRole::Admin => Ok(()),
+        _ => Err(Error::authorization("Only the room owner or an admin can do this")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::token;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn test_callers_come_from_bearer_tokens() {
+        let config = Config::load_or_default().unwrap();
+
+        let req = TestRequest::default()
+            .insert_header(("Authorization", format!("Bearer {}", token(&config, "42"))))
+            .to_http_request();
+        let user = authenticate(&config, &req).unwrap();
+        assert_eq!(user.user_id, "42");
+        assert_eq!(user.email, "42@example.com");
+
+        let anonymous = TestRequest::default().to_http_request();
+        assert!(caller(&config, &anonymous).unwrap().is_none());
+        assert!(matches!(authenticate(&config, &anonymous), Err(Error::Auth(_))));
+
+        let forged = TestRequest::default()
+            .insert_header(("Authorization", "Bearer not-a-token"))
+            .to_http_request();
+        assert!(matches!(caller(&config, &forged), Err(Error::Auth(_))));
+    }
+}
@@ -0,0 +1,208 @@
+//! Entity sync with presence over Redis
+//!
+//! Entity changes made by clients arrive from presence as requests. They are
+//! applied like HTTP changes: recorded in the room history and passed to room
+//! scripts. Undo and redo requests step through the sender's history like the
+//! HTTP endpoints. Every change the `RoomManager` applies, whatever its source,
+//! is published back for presence to deliver to the room.
+
+use futures_util::StreamExt;
+use reticulum_core::entity_sync::{
+    EntityHistoryRequest, EntitySyncEvent, EntitySyncRequest, HistoryStep, ENTITY_EVENTS_CHANNEL,
+    ENTITY_HISTORY_CHANNEL, ENTITY_REQUESTS_CHANNEL,
+};
+use reticulum_core::{Error, Result};
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::JoinHandle;
+
+use crate::room::RoomManager;
+use crate::scripting::{ScriptEvent, ScriptManager};
+
+/// Delay before reconnecting to Redis
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Bridges the hub's entity state and presence
+pub struct EntitySync {
+    client: redis::Client,
+}
+
+impl EntitySync {
+    pub fn new(redis_url: &str) -> Result<Self> {
+        let client = redis::Client::open(redis_url)
+            .map_err(|e| Error::internal(format!("Failed to create Redis client: {}", e)))?;
+        Ok(Self { client })
+    }
+
+    /// Apply requests from presence and publish applied changes until the
+    /// returned tasks are aborted
+    pub fn start(&self, room_manager: RoomManager, script_manager: ScriptManager) -> Vec<JoinHandle<()>> {
+        let publisher = tokio::spawn(publish_changes(self.client.clone(), room_manager.subscribe_changes()));
+        let subscriber = tokio::spawn(apply_requests(self.client.clone(), room_manager, script_manager));
+        vec![publisher, subscriber]
+    }
+}
+
+async fn publish_changes(client: redis::Client, mut changes: broadcast::Receiver<EntitySyncEvent>) {
+    let mut conn = loop {
+        match client.get_connection_manager().await {
+            Ok(conn) => break conn,
+            Err(e) => {
+                log::error!("Failed to connect to Redis for entity events: {}", e);
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        }
+    };
+
+    loop {
+        let event = match changes.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(missed)) => {
+                log::warn!("Entity sync fell behind, {} changes were not published", missed);
+                continue;
+            }
+            Err(RecvError::Closed) => return,
+        };
+
+        let payload = match serde_json::to_vec(&event) {
+            Ok(payload) => payload,
+            Err(e) => {
+                log::error!("Failed to encode entity event: {}", e);
+                continue;
+            }
+        };
+
+        // The connection manager reconnects by itself, so a failed publish only
+        // loses this event
+        if let Err(e) = redis::cmd("PUBLISH")
+            .arg(ENTITY_EVENTS_CHANNEL)
+            .arg(payload)
+            .query_async::<_, ()>(&mut conn)
+            .await
+        {
+            log::error!("Failed to publish entity event for room {}: {}", event.room_id, e);
+        }
+    }
+}
+
+async fn apply_requests(client: redis::Client, room_manager: RoomManager, script_manager: ScriptManager) {
+    loop {
+        let mut pubsub = match client.get_async_connection().await {
+            Ok(conn) => conn.into_pubsub(),
+            Err(e) => {
+                log::error!("Failed to connect to Redis for entity requests: {}", e);
+                tokio::time::sleep(RECONNECT_DELAY).await;
+                continue;
+            }
+        };
+
+        if let Err(e) = pubsub.subscribe(&[ENTITY_REQUESTS_CHANNEL, ENTITY_HISTORY_CHANNEL]).await {
+            log::error!("Failed to subscribe to {} and {}: {}", ENTITY_REQUESTS_CHANNEL, ENTITY_HISTORY_CHANNEL, e);
+            tokio::time::sleep(RECONNECT_DELAY).await;
+            continue;
+        }
+        log::info!("Applying entity changes from presence");
+
+        let mut messages = pubsub.on_message();
+        while let Some(msg) = messages.next().await {
+            if msg.get_channel_name() == ENTITY_HISTORY_CHANNEL {
+                match serde_json::from_slice::<EntityHistoryRequest>(msg.get_payload_bytes()) {
+                    Ok(request) => apply_history_request(&room_manager, request).await,
+                    Err(e) => log::warn!("Invalid history request: {}", e),
+                }
+                continue;
+            }
+            match serde_json::from_slice::<EntitySyncRequest>(msg.get_payload_bytes()) {
+                Ok(request) => apply_request(&room_manager, &script_manager, request).await,
+                Err(e) => log::warn!("Invalid entity request: {}", e),
+            }
+        }
+
+        log::warn!(
+            "Redis subscription to {} and {} lost, reconnecting",
+            ENTITY_REQUESTS_CHANNEL,
+            ENTITY_HISTORY_CHANNEL
+        );
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+/// Apply a change requested by a client and let room scripts react to it
+pub async fn apply_request(room_manager: &RoomManager, script_manager: &ScriptManager, request: EntitySyncRequest) {
+    let EntitySyncRequest { room_id, author_id, change } = request;
+    let entity_id = change.entity_id().to_string();
+
+    match room_manager.apply_change(&room_id, author_id.as_deref(), change).await {
+        Ok(Some(operation)) => {
+            script_manager
+                .dispatch(room_manager, &room_id, ScriptEvent::from_operation(&operation))
+                .await;
+        }
+        Ok(None) => log::debug!("Entity change for {} in room {} changed nothing", entity_id, room_id),
+        Err(e) => log::error!("Failed to apply entity change for {} in room {}: {}", entity_id, room_id, e),
+    }
+}
+
+/// Undo or redo a change of the requesting client; the applied change is
+/// published like any other
+pub async fn apply_history_request(room_manager: &RoomManager, request: EntityHistoryRequest) {
+    let EntityHistoryRequest { room_id, user_id, step } = request;
+
+    let result = match step {
+        HistoryStep::Undo => room_manager.undo(&room_id, &user_id).await,
+        HistoryStep::Redo => room_manager.redo(&room_id, &user_id).await,
+    };
+    match result {
+        Ok(Some(operation)) => log::debug!("{:?} of operation {} in room {} by {}", step, operation.seq, room_id, user_id),
+        Ok(None) => log::debug!("Nothing for {} to {:?} in room {}", user_id, step, room_id),
+        Err(e) => log::info!("Refused {:?} by {} in room {}: {}", step, user_id, room_id, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::EntityMutation;
+    use crate::room::SpawnEntityRequest;
+    use crate::test_support::{manager_with, room};
+    use reticulum_core::entity_sync::EntityChange;
+    use reticulum_core::models::{Quaternion, Vector3};
+
+    #[tokio::test]
+    async fn test_history_requests_undo_and_redo_the_senders_changes() {
+        let room_manager = manager_with([room("room-1", "alice", &[])]).await;
+
+        let entity = SpawnEntityRequest {
+            entity_id: "box".to_string(),
+            template_id: "cube".to_string(),
+            owner_id: "alice".to_string(),
+            position: Vector3 { x: 0.0, y: 0.0, z: 0.0 },
+            rotation: Quaternion { x: 0.0, y: 0.0, z: 0.0, w: 1.0 },
+            components: serde_json::json!({}),
+        }
+        .to_entity_data("room-1".to_string());
+        room_manager
+            .apply_mutation("room-1", Some("alice"), EntityMutation::Upsert { entity })
+            .await
+            .unwrap();
+        let mut changes = room_manager.subscribe_changes();
+
+        let request = |user_id: &str, step| EntityHistoryRequest {
+            room_id: "room-1".to_string(),
+            user_id: user_id.to_string(),
+            step,
+        };
+
+        // Bob has nothing to undo
+        apply_history_request(&room_manager, request("bob", HistoryStep::Undo)).await;
+        assert_eq!(room_manager.get_entities("room-1").await.unwrap().len(), 1);
+
+        apply_history_request(&room_manager, request("alice", HistoryStep::Undo)).await;
+        assert!(room_manager.get_entities("room-1").await.unwrap().is_empty());
+        assert!(matches!(changes.try_recv().unwrap().change, EntityChange::Despawn { .. }));
+
+        apply_history_request(&room_manager, request("alice", HistoryStep::Redo)).await;
+        assert_eq!(room_manager.get_entities("room-1").await.unwrap().len(), 1);
+        assert!(matches!(changes.try_recv().unwrap().change, EntityChange::Spawn { .. }));
+    }
+}
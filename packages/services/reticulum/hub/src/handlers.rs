@@ -1,12 +1,16 @@
 //! HTTP handlers for hub service
 
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
-use reticulum_core::{db, models as core_models, Config};
+use reticulum_core::models::PresenceEventType;
+use reticulum_core::{db, models as core_models, Config, DatabaseConnection, Error, Result};
 
-use crate::room::{RoomManager, RoomState};
+use crate::auth;
+use crate::history::{EntityMutation, Operation};
+use crate::room::{anonymous_player_id, PlayerDeparture, RoomManager, RoomState, ANONYMOUS_PLAYER_PREFIX};
+use crate::scripting::{ScriptEvent, ScriptManager};
 
 #[derive(Debug, Deserialize, Validate)]
 pub struct CreateRoomRequest {
@@ -32,6 +36,36 @@ pub struct JoinRoomRequest {
     pub room_id: String,
 }
 
+/// Optional body for joining a room instance; the player is the caller, or a
+/// new anonymous player without a token
+#[derive(Debug, Default, Deserialize)]
+pub struct JoinInstanceRequest {
+    #[serde(default)]
+    pub friend_ids: Vec<String>,
+}
+
+/// Query parameters for listing room history
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    pub limit: Option<usize>,
+}
+
+/// Optional body for leaving a room instance
+#[derive(Debug, Default, Deserialize)]
+pub struct LeaveInstanceRequest {
+    pub instance_id: Option<String>,
+    /// Anonymous player id from the join; ignored when the caller has a token
+    pub user_id: Option<String>,
+}
+
+/// Request body for handing the host role to another player
+#[derive(Debug, Deserialize, Validate)]
+pub struct MigrateHostRequest {
+    pub instance_id: Option<String>,
+    #[validate(length(min = 1))]
+    pub new_host_id: String,
+}
+
 /// Create a new room
 pub async fn create_room(
     config: web::Data<Config>,
@@ -90,6 +124,9 @@ pub async fn create_room(
         current_players: 0,
         created_by: room.created_by.clone(),
         entities: Default::default(),
+        host_client_id: None,
+        instance_id: room_id.clone(),
+        players: Vec::new(),
     };
 
     let mut rooms: tokio::sync::RwLockWriteGuard<'_, std::collections::HashMap<String, crate::room::RoomState>> = room_manager.rooms.write().await;
@@ -169,10 +206,16 @@ pub async fn list_rooms(
 }
 
 /// Join a room
+///
+/// Routes the player to the instance holding their friends or the least-full
+/// instance, spawning a new instance when all existing ones are full.
 pub async fn join_room(
+    http: HttpRequest,
     config: web::Data<Config>,
     room_manager: web::Data<RoomManager>,
+    script_manager: web::Data<ScriptManager>,
     room_id: web::Path<String>,
+    body: Option<web::Json<JoinInstanceRequest>>,
 ) -> HttpResponse {
     let db = match db::connect(&config).await {
         Ok(db) => db,
@@ -186,10 +229,15 @@ pub async fn join_room(
     };
 
     let room_id = room_id.into_inner();
+    let body = body.map(|b| b.into_inner()).unwrap_or_default();
+    let caller = match auth::caller(&config, &http) {
+        Ok(caller) => caller,
+        Err(e) => return e.error_response(),
+    };
 
-    // Check if room exists and has space
-    let room = match room_manager.get_room(&room_id, &db).await {
-        Ok(Some(room)) => room,
+    // Check if room exists
+    match room_manager.get_room(&room_id, &db).await {
+        Ok(Some(_)) => {}
         Ok(None) => {
             return HttpResponse::NotFound().json(serde_json::json!({
                 "error": "not_found",
@@ -205,33 +253,173 @@ pub async fn join_room(
         }
     };
 
-    if room.current_players >= room.max_players {
-        return HttpResponse::Conflict().json(serde_json::json!({
+    let user_id = caller.map(|caller| caller.user_id).unwrap_or_else(anonymous_player_id);
+
+    // Add player to an instance of the room
+    match room_manager.join_instance(&room_id, &user_id, &body.friend_ids).await {
+        Ok(Some(instance_id)) => {
+            record_presence_event(&db, &room_id, &instance_id, &user_id, PresenceEventType::Join).await;
+            script_manager
+                .dispatch(&room_manager, &instance_id, ScriptEvent::PlayerJoined { user_id: user_id.clone() })
+                .await;
+            let host_client_id = room_manager.get_host(&instance_id).await;
+
+            HttpResponse::Ok().json(serde_json::json!({
+                "room_id": room_id,
+                "instance_id": instance_id,
+                "user_id": user_id,
+                "host_client_id": host_client_id,
+                "message": "Successfully joined room"
+            }))
+        }
+        Ok(None) => HttpResponse::Conflict().json(serde_json::json!({
             "error": "room_full",
             "message": "Room is full"
+        })),
+        Err(e) => {
+            log::error!("Failed to add player: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "internal_error",
+                "message": "Failed to join room"
+            }))
+        }
+    }
+}
+
+/// List live instances of a room
+pub async fn list_instances(
+    room_manager: web::Data<RoomManager>,
+    room_id: web::Path<String>,
+) -> HttpResponse {
+    let room_id = room_id.into_inner();
+    let instances = room_manager.list_instances(&room_id).await;
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "room_id": room_id,
+        "instances": instances
+    }))
+}
+
+/// Check that the caller hosts `instance_id`, owns the room `room_id` or is
+/// an admin
+async fn authorize_host_change(
+    config: &Config,
+    db: &DatabaseConnection,
+    room_manager: &RoomManager,
+    http: &HttpRequest,
+    room_id: &str,
+    instance_id: &str,
+) -> Result<()> {
+    let caller = auth::authenticate(config, http)?;
+    // Authority over one room says nothing about another room's instances
+    if room_manager.instance_room_id(instance_id).await.as_deref() != Some(room_id) {
+        return Err(Error::not_found(format!("Instance {} not found in room {}", instance_id, room_id)));
+    }
+    if room_manager.get_host(instance_id).await.as_deref() == Some(caller.user_id.as_str()) {
+        return Ok(());
+    }
+    let room = room_manager
+        .get_room(room_id, db)
+        .await?
+        .ok_or_else(|| Error::not_found(format!("Room {} not found", room_id)))?;
+    auth::authorize_room_owner(db, &caller, &room).await
+}
+
+/// Refuse a live instance of another room named under `room_id`'s path;
+/// instances that are not live have nothing to refuse
+async fn instance_of_room(room_manager: &RoomManager, room_id: &str, instance_id: &str) -> Result<()> {
+    match room_manager.instance_room_id(instance_id).await {
+        Some(instance_room_id) if instance_room_id != room_id => {
+            Err(Error::not_found(format!("Instance {} not found in room {}", instance_id, room_id)))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Hand the host role of a room instance to another player
+///
+/// Presence hands the host role on over Redis instead when it elects a host
+/// by role; see [`crate::occupancy`].
+pub async fn migrate_host(
+    config: web::Data<Config>,
+    room_manager: web::Data<RoomManager>,
+    room_id: web::Path<String>,
+    http: HttpRequest,
+    req: web::Json<MigrateHostRequest>,
+) -> HttpResponse {
+    if let Err(errors) = req.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "validation_error",
+            "message": errors.to_string()
         }));
     }
 
-    // Add player to room
-    match room_manager.add_player(&room_id).await {
-        Ok(true) => HttpResponse::Ok().json(serde_json::json!({
+    // Connect to database
+    let db = match db::connect(&config).await {
+        Ok(db) => db,
+        Err(e) => {
+            log::error!("Database connection failed: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "database_error",
+                "message": "Failed to connect to database"
+            }));
+        }
+    };
+
+    let room_id = room_id.into_inner();
+    let instance_id = req.instance_id.clone().unwrap_or_else(|| room_id.clone());
+    if let Err(e) = authorize_host_change(&config, &db, &room_manager, &http, &room_id, &instance_id).await {
+        return e.error_response();
+    }
+
+    match room_manager.migrate_host(&instance_id, &req.new_host_id).await {
+        Ok(Some(migration)) => HttpResponse::Ok().json(serde_json::json!({
             "room_id": room_id,
-            "message": "Successfully joined room"
+            "host_migration": migration
         })),
-        Ok(false) => HttpResponse::Conflict().json(serde_json::json!({
-            "error": "room_full",
-            "message": "Room is full"
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "not_found",
+            "message": "Instance or player not found"
         })),
         Err(e) => {
-            log::error!("Failed to add player: {}", e);
+            log::error!("Failed to migrate host: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
                 "error": "internal_error",
-                "message": "Failed to join room"
+                "message": "Failed to migrate host"
             }))
         }
     }
 }
 
+/// Let room scripts react to a recorded entity operation
+async fn notify_scripts(
+    script_manager: &ScriptManager,
+    room_manager: &RoomManager,
+    room_id: &str,
+    operation: Option<&Operation>,
+) {
+    if let Some(operation) = operation {
+        script_manager
+            .dispatch(room_manager, room_id, ScriptEvent::from_operation(operation))
+            .await;
+    }
+}
+
+/// Record a join/leave for analytics; failures are logged and never block the caller
+pub(crate) async fn record_presence_event(
+    db: &DatabaseConnection,
+    room_id: &str,
+    instance_id: &str,
+    user_id: &str,
+    event_type: PresenceEventType,
+) {
+    if let Err(e) =
+        core_models::RoomPresenceEventModel::record(db, room_id, instance_id, user_id, event_type).await
+    {
+        log::warn!("Failed to record {} event for room {}: {}", event_type.as_str(), room_id, e);
+    }
+}
+
 /// Health check for hub service
 pub async fn health() -> HttpResponse {
     HttpResponse::Ok().json(serde_json::json!({
@@ -242,18 +430,79 @@ pub async fn health() -> HttpResponse {
 
 /// Leave a room
 pub async fn leave_room(
+    http: HttpRequest,
     config: web::Data<Config>,
     room_manager: web::Data<RoomManager>,
+    script_manager: web::Data<ScriptManager>,
     room_id: web::Path<String>,
+    body: Option<web::Json<LeaveInstanceRequest>>,
 ) -> HttpResponse {
     let room_id = room_id.into_inner();
+    let body = body.map(|b| b.into_inner()).unwrap_or_default();
+    let caller = match auth::caller(&config, &http) {
+        Ok(caller) => caller,
+        Err(e) => return e.error_response(),
+    };
 
-    // Remove player from room
-    match room_manager.remove_player(&room_id).await {
-        Ok(_) => HttpResponse::Ok().json(serde_json::json!({
-            "room_id": room_id,
-            "message": "Successfully left room"
-        })),
+    // Callers with a token leave as themselves; without one only an anonymous
+    // player may be named, by the id it was given on join
+    let user_id = match (caller, body.user_id) {
+        (Some(caller), _) => Some(caller.user_id),
+        (None, Some(user_id)) if user_id.starts_with(ANONYMOUS_PLAYER_PREFIX) => Some(user_id),
+        (None, Some(_)) => return Error::auth("Sign in to leave as a user").error_response(),
+        (None, None) => None,
+    };
+
+    // Remove player from their instance; legacy leaves that do not say who
+    // left remove an anonymous player
+    let result = match user_id {
+        Some(user_id) => {
+            let instance_id = body.instance_id.unwrap_or_else(|| room_id.clone());
+            if let Err(e) = instance_of_room(&room_manager, &room_id, &instance_id).await {
+                return e.error_response();
+            }
+            room_manager
+                .leave_instance(&instance_id, &user_id)
+                .await
+                .map(|host_migration| {
+                    Some(PlayerDeparture {
+                        instance_id,
+                        user_id,
+                        host_migration,
+                    })
+                })
+        }
+        None => room_manager.remove_player(&room_id).await,
+    };
+
+    match result {
+        Ok(departure) => {
+            let host_migration = match departure {
+                Some(PlayerDeparture {
+                    instance_id,
+                    user_id,
+                    host_migration,
+                }) => {
+                    script_manager
+                        .dispatch(&room_manager, &instance_id, ScriptEvent::PlayerLeft { user_id: user_id.clone() })
+                        .await;
+                    match db::connect(&config).await {
+                        Ok(db) => {
+                            record_presence_event(&db, &room_id, &instance_id, &user_id, PresenceEventType::Leave)
+                                .await
+                        }
+                        Err(e) => log::warn!("Skipping leave event, database unavailable: {}", e),
+                    }
+                    host_migration
+                }
+                None => None,
+            };
+            HttpResponse::Ok().json(serde_json::json!({
+                "room_id": room_id,
+                "host_migration": host_migration,
+                "message": "Successfully left room"
+            }))
+        }
         Err(e) => {
             log::error!("Failed to remove player: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
@@ -268,16 +517,31 @@ pub async fn leave_room(
 pub async fn spawn_entity(
     config: web::Data<Config>,
     room_manager: web::Data<RoomManager>,
+    script_manager: web::Data<ScriptManager>,
     room_id: web::Path<String>,
+    http: HttpRequest,
     req: web::Json<crate::room::SpawnEntityRequest>,
 ) -> HttpResponse {
     let room_id = room_id.into_inner();
+    let entity = req.into_inner().to_entity_data(room_id.clone());
+    let author = match auth::caller(&config, &http) {
+        Ok(author) => author,
+        Err(e) => return e.error_response(),
+    };
 
-    match room_manager.spawn_entity(&room_id, req.into_inner()).await {
-        Ok(_) => HttpResponse::Created().json(serde_json::json!({
-            "room_id": room_id,
-            "message": "Entity spawned successfully"
-        })),
+    match room_manager
+        .apply_mutation(&room_id, author.as_ref().map(|a| a.user_id.as_str()), EntityMutation::Upsert { entity })
+        .await
+    {
+        Ok(operation) => {
+            notify_scripts(&script_manager, &room_manager, &room_id, operation.as_ref()).await;
+
+            HttpResponse::Created().json(serde_json::json!({
+                "room_id": room_id,
+                "operation_seq": operation.map(|op| op.seq),
+                "message": "Entity spawned successfully"
+            }))
+        }
         Err(e) => {
             log::error!("Failed to spawn entity: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
@@ -312,15 +576,33 @@ pub async fn list_entities(
 pub async fn update_entity(
     config: web::Data<Config>,
     room_manager: web::Data<RoomManager>,
+    script_manager: web::Data<ScriptManager>,
     path: web::Path<(String, String)>,
+    http: HttpRequest,
     req: web::Json<core_models::EntityData>,
 ) -> HttpResponse {
     let (room_id, entity_id) = path.into_inner();
+    let entity = core_models::EntityData {
+        entity_id,
+        ..req.into_inner()
+    };
+    let author = match auth::caller(&config, &http) {
+        Ok(author) => author,
+        Err(e) => return e.error_response(),
+    };
 
-    match room_manager.update_entity(&room_id, &entity_id, req.into_inner()).await {
-        Ok(_) => HttpResponse::Ok().json(serde_json::json!({
-            "message": "Entity updated successfully"
-        })),
+    match room_manager
+        .apply_mutation(&room_id, author.as_ref().map(|a| a.user_id.as_str()), EntityMutation::Upsert { entity })
+        .await
+    {
+        Ok(operation) => {
+            notify_scripts(&script_manager, &room_manager, &room_id, operation.as_ref()).await;
+
+            HttpResponse::Ok().json(serde_json::json!({
+                "operation_seq": operation.map(|op| op.seq),
+                "message": "Entity updated successfully"
+            }))
+        }
         Err(e) => {
             log::error!("Failed to update entity: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
@@ -335,14 +617,28 @@ pub async fn update_entity(
 pub async fn despawn_entity(
     config: web::Data<Config>,
     room_manager: web::Data<RoomManager>,
+    script_manager: web::Data<ScriptManager>,
     path: web::Path<(String, String)>,
+    http: HttpRequest,
 ) -> HttpResponse {
     let (room_id, entity_id) = path.into_inner();
+    let author = match auth::caller(&config, &http) {
+        Ok(author) => author,
+        Err(e) => return e.error_response(),
+    };
 
-    match room_manager.despawn_entity(&room_id, &entity_id).await {
-        Ok(_) => HttpResponse::Ok().json(serde_json::json!({
-            "message": "Entity despawned successfully"
-        })),
+    match room_manager
+        .apply_mutation(&room_id, author.as_ref().map(|a| a.user_id.as_str()), EntityMutation::Remove { entity_id })
+        .await
+    {
+        Ok(operation) => {
+            notify_scripts(&script_manager, &room_manager, &room_id, operation.as_ref()).await;
+
+            HttpResponse::Ok().json(serde_json::json!({
+                "operation_seq": operation.map(|op| op.seq),
+                "message": "Entity despawned successfully"
+            }))
+        }
         Err(e) => {
             log::error!("Failed to despawn entity: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
@@ -352,3 +648,147 @@ pub async fn despawn_entity(
         }
     }
 }
+
+/// List the operation history of a room
+pub async fn get_history(
+    room_manager: web::Data<RoomManager>,
+    room_id: web::Path<String>,
+    query: web::Query<HistoryQuery>,
+) -> HttpResponse {
+    let room_id = room_id.into_inner();
+    let limit = query.limit.unwrap_or(50).min(500);
+    let operations = room_manager.get_history(&room_id, limit).await;
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "room_id": room_id,
+        "operations": operations
+    }))
+}
+
+/// Undo the caller's most recent entity change in a room
+pub async fn undo(
+    config: web::Data<Config>,
+    room_manager: web::Data<RoomManager>,
+    room_id: web::Path<String>,
+    http: HttpRequest,
+) -> HttpResponse {
+    let caller = match auth::authenticate(&config, &http) {
+        Ok(caller) => caller,
+        Err(e) => return e.error_response(),
+    };
+    let room_id = room_id.into_inner();
+
+    match room_manager.undo(&room_id, &caller.user_id).await {
+        Ok(Some(operation)) => HttpResponse::Ok().json(serde_json::json!({
+            "room_id": room_id,
+            "operation": operation
+        })),
+        Ok(None) => HttpResponse::Conflict().json(serde_json::json!({
+            "error": "nothing_to_undo",
+            "message": "No operation to undo"
+        })),
+        Err(e @ Error::Conflict(_)) => e.error_response(),
+        Err(e) => {
+            log::error!("Failed to undo operation: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "internal_error",
+                "message": "Failed to undo operation"
+            }))
+        }
+    }
+}
+
+/// Redo the caller's most recently undone entity change in a room
+pub async fn redo(
+    config: web::Data<Config>,
+    room_manager: web::Data<RoomManager>,
+    room_id: web::Path<String>,
+    http: HttpRequest,
+) -> HttpResponse {
+    let caller = match auth::authenticate(&config, &http) {
+        Ok(caller) => caller,
+        Err(e) => return e.error_response(),
+    };
+    let room_id = room_id.into_inner();
+
+    match room_manager.redo(&room_id, &caller.user_id).await {
+        Ok(Some(operation)) => HttpResponse::Ok().json(serde_json::json!({
+            "room_id": room_id,
+            "operation": operation
+        })),
+        Ok(None) => HttpResponse::Conflict().json(serde_json::json!({
+            "error": "nothing_to_redo",
+            "message": "No operation to redo"
+        })),
+        Err(e @ Error::Conflict(_)) => e.error_response(),
+        Err(e) => {
+            log::error!("Failed to redo operation: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "internal_error",
+                "message": "Failed to redo operation"
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{manager_with, request_from, room};
+    use actix_web::test::TestRequest;
+
+    #[tokio::test]
+    async fn test_only_the_host_or_room_owner_may_migrate_the_host() {
+        let config = Config::load_or_default().unwrap();
+        // No caller has a numeric id, so no role is looked up
+        let db = DatabaseConnection::Disconnected;
+        let room_manager = manager_with([room("room-1", "carol", &["alice", "bob"])]).await;
+
+        for user_id in ["alice", "carol"] {
+            let http = request_from(&config, user_id);
+            assert!(authorize_host_change(&config, &db, &room_manager, &http, "room-1", "room-1")
+                .await
+                .is_ok());
+        }
+
+        let player = request_from(&config, "bob");
+        assert!(matches!(
+            authorize_host_change(&config, &db, &room_manager, &player, "room-1", "room-1").await,
+            Err(Error::Authorization(_))
+        ));
+
+        let anonymous = TestRequest::default().to_http_request();
+        assert!(matches!(
+            authorize_host_change(&config, &db, &room_manager, &anonymous, "room-1", "room-1").await,
+            Err(Error::Auth(_))
+        ));
+
+        // Owning or hosting room-1 gives no say over another room's instance
+        let other = room("room-2", "dave", &["dave"]);
+        room_manager.rooms.write().await.insert(other.instance_id.clone(), other);
+        for user_id in ["alice", "carol"] {
+            let http = request_from(&config, user_id);
+            assert!(matches!(
+                authorize_host_change(&config, &db, &room_manager, &http, "room-1", "room-2").await,
+                Err(Error::NotFound(_))
+            ));
+        }
+        let http = request_from(&config, "carol");
+        assert!(matches!(
+            authorize_host_change(&config, &db, &room_manager, &http, "room-1", "missing").await,
+            Err(Error::NotFound(_))
+        ));
+    }
+    #[tokio::test]
+    async fn test_leaves_name_an_instance_of_the_path_room() {
+        let room_manager = manager_with([room("room-2", "dave", &["dave"])]).await;
+
+        assert!(instance_of_room(&room_manager, "room-2", "room-2").await.is_ok());
+        assert!(matches!(
+            instance_of_room(&room_manager, "room-1", "room-2").await,
+            Err(Error::NotFound(_))
+        ));
+        // Leaving an instance that is gone changes nothing
+        assert!(instance_of_room(&room_manager, "room-1", "room-1~gone").await.is_ok());
+    }
+}
@@ -0,0 +1,358 @@
+//! Per-room operation log with per-user undo/redo
+
+use chrono::{DateTime, Utc};
+use reticulum_core::entity_sync::EntityChange;
+use reticulum_core::models as core_models;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// Default number of operations kept per room
+pub const DEFAULT_MAX_OPERATIONS: usize = 500;
+
+/// A single entity change that can be applied to a room
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EntityMutation {
+    /// Insert or replace an entity
+    Upsert { entity: core_models::EntityData },
+    /// Remove an entity
+    Remove { entity_id: String },
+}
+
+impl EntityMutation {
+    pub fn entity_id(&self) -> &str {
+        match self {
+            EntityMutation::Upsert { entity } => &entity.entity_id,
+            EntityMutation::Remove { entity_id } => entity_id,
+        }
+    }
+
+    /// Apply the mutation, returning the entity state it replaced
+    pub fn apply(
+        &self,
+        entities: &mut HashMap<String, core_models::EntityData>,
+    ) -> Option<core_models::EntityData> {
+        match self {
+            EntityMutation::Upsert { entity } => {
+                entities.insert(entity.entity_id.clone(), entity.clone())
+            }
+            EntityMutation::Remove { entity_id } => entities.remove(entity_id),
+        }
+    }
+
+    /// Describe the mutation for presence, given the entity state it replaced.
+    ///
+    /// Updates carry no transform, so an upsert that moves the entity is
+    /// described as a spawn replacing it.
+    pub fn to_change(&self, previous: Option<&core_models::EntityData>) -> EntityChange {
+        match (self, previous) {
+            (EntityMutation::Upsert { entity }, Some(previous))
+                if entity.position == previous.position && entity.rotation == previous.rotation =>
+            {
+                EntityChange::Update {
+                    entity_id: entity.entity_id.clone(),
+                    components: entity.components.as_object().cloned().unwrap_or_default(),
+                }
+            }
+            (EntityMutation::Upsert { entity }, _) => EntityChange::Spawn {
+                entity: entity.clone(),
+            },
+            (EntityMutation::Remove { entity_id }, _) => EntityChange::Despawn {
+                entity_id: entity_id.clone(),
+            },
+        }
+    }
+
+    /// Whether `current` is the state this mutation left its entity in
+    pub fn produced(&self, current: Option<&core_models::EntityData>) -> bool {
+        match self {
+            EntityMutation::Upsert { entity } => current == Some(entity),
+            EntityMutation::Remove { .. } => current.is_none(),
+        }
+    }
+
+    /// Build the mutation that restores `previous` for this mutation's entity
+    pub fn inverse(&self, previous: Option<core_models::EntityData>) -> EntityMutation {
+        match previous {
+            Some(entity) => EntityMutation::Upsert { entity },
+            None => EntityMutation::Remove {
+                entity_id: self.entity_id().to_string(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OperationKind {
+    Spawn,
+    Update,
+    Despawn,
+}
+
+impl OperationKind {
+    /// Classify a mutation by the state it replaced
+    pub fn classify(mutation: &EntityMutation, existed: bool) -> Self {
+        match (mutation, existed) {
+            (EntityMutation::Upsert { .. }, false) => OperationKind::Spawn,
+            (EntityMutation::Upsert { .. }, true) => OperationKind::Update,
+            (EntityMutation::Remove { .. }, _) => OperationKind::Despawn,
+        }
+    }
+}
+
+/// Recorded mutation together with its inverse
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Operation {
+    pub seq: u64,
+    pub author_id: String,
+    pub kind: OperationKind,
+    pub entity_id: String,
+    pub forward: EntityMutation,
+    pub inverse: EntityMutation,
+    pub timestamp: DateTime<Utc>,
+    pub undone: bool,
+}
+
+/// Bounded operation log for a single room
+pub struct RoomHistory {
+    operations: VecDeque<Operation>,
+    undo_stacks: HashMap<String, Vec<u64>>, // author_id -> seqs
+    redo_stacks: HashMap<String, Vec<u64>>, // author_id -> seqs
+    next_seq: u64,
+    max_operations: usize,
+}
+
+impl RoomHistory {
+    pub fn new(max_operations: usize) -> Self {
+        Self {
+            operations: VecDeque::new(),
+            undo_stacks: HashMap::new(),
+            redo_stacks: HashMap::new(),
+            next_seq: 1,
+            max_operations: max_operations.max(1),
+        }
+    }
+
+    /// Record a new operation; clears the author's redo stack. Operations
+    /// without an author are kept in the log but nobody can undo them.
+    pub fn record(
+        &mut self,
+        author_id: &str,
+        kind: OperationKind,
+        forward: EntityMutation,
+        inverse: EntityMutation,
+    ) -> Operation {
+        let operation = Operation {
+            seq: self.next_seq,
+            author_id: author_id.to_string(),
+            kind,
+            entity_id: forward.entity_id().to_string(),
+            forward,
+            inverse,
+            timestamp: Utc::now(),
+            undone: false,
+        };
+        self.next_seq += 1;
+
+        if self.operations.len() >= self.max_operations {
+            if let Some(evicted) = self.operations.pop_front() {
+                for stack in self.undo_stacks.values_mut().chain(self.redo_stacks.values_mut()) {
+                    stack.retain(|seq| *seq != evicted.seq);
+                }
+            }
+        }
+
+        if !author_id.is_empty() {
+            self.undo_stacks
+                .entry(author_id.to_string())
+                .or_default()
+                .push(operation.seq);
+            self.redo_stacks.remove(author_id);
+        }
+        self.operations.push_back(operation.clone());
+
+        operation
+    }
+
+    /// Pop the author's most recent operation and return it for undoing
+    pub fn undo(&mut self, author_id: &str) -> Option<Operation> {
+        let seq = self.undo_stacks.get_mut(author_id)?.pop()?;
+        let operation = self.find_mut(seq)?;
+        operation.undone = true;
+        let operation = operation.clone();
+
+        self.redo_stacks
+            .entry(author_id.to_string())
+            .or_default()
+            .push(seq);
+        Some(operation)
+    }
+
+    /// Pop the author's most recently undone operation and return it for redoing
+    pub fn redo(&mut self, author_id: &str) -> Option<Operation> {
+        let seq = self.redo_stacks.get_mut(author_id)?.pop()?;
+        let operation = self.find_mut(seq)?;
+        operation.undone = false;
+        let operation = operation.clone();
+
+        self.undo_stacks
+            .entry(author_id.to_string())
+            .or_default()
+            .push(seq);
+        Some(operation)
+    }
+
+    /// The operation `undo` would return next for the author
+    pub fn next_undo(&self, author_id: &str) -> Option<&Operation> {
+        let seq = *self.undo_stacks.get(author_id)?.last()?;
+        self.operations.iter().find(|op| op.seq == seq)
+    }
+
+    /// The operation `redo` would return next for the author
+    pub fn next_redo(&self, author_id: &str) -> Option<&Operation> {
+        let seq = *self.redo_stacks.get(author_id)?.last()?;
+        self.operations.iter().find(|op| op.seq == seq)
+    }
+
+    /// Forget the author's next undo, which later changes superseded
+    pub fn discard_undo(&mut self, author_id: &str) {
+        if let Some(stack) = self.undo_stacks.get_mut(author_id) {
+            stack.pop();
+        }
+    }
+
+    /// Forget the author's next redo, which later changes superseded
+    pub fn discard_redo(&mut self, author_id: &str) {
+        if let Some(stack) = self.redo_stacks.get_mut(author_id) {
+            stack.pop();
+        }
+    }
+
+    /// Most recent operations, newest first
+    pub fn recent(&self, limit: usize) -> Vec<Operation> {
+        self.operations.iter().rev().take(limit).cloned().collect()
+    }
+
+    pub fn can_undo(&self, author_id: &str) -> bool {
+        self.undo_stacks.get(author_id).is_some_and(|s| !s.is_empty())
+    }
+
+    pub fn can_redo(&self, author_id: &str) -> bool {
+        self.redo_stacks.get(author_id).is_some_and(|s| !s.is_empty())
+    }
+
+    fn find_mut(&mut self, seq: u64) -> Option<&mut Operation> {
+        self.operations.iter_mut().find(|op| op.seq == seq)
+    }
+}
+
+impl Default for RoomHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_OPERATIONS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reticulum_core::models::{Quaternion, Vector3};
+
+    fn entity(entity_id: &str, x: f32) -> core_models::EntityData {
+        core_models::EntityData {
+            entity_id: entity_id.to_string(),
+            room_id: "room".to_string(),
+            template_id: "cube".to_string(),
+            owner_id: "user_1".to_string(),
+            position: Vector3 { x, y: 0.0, z: 0.0 },
+            rotation: Quaternion { x: 0.0, y: 0.0, z: 0.0, w: 1.0 },
+            components: serde_json::json!({}),
+        }
+    }
+
+    fn upsert(entity_id: &str, x: f32) -> EntityMutation {
+        EntityMutation::Upsert { entity: entity(entity_id, x) }
+    }
+
+    #[test]
+    fn test_inverse_of_spawn_is_remove() {
+        let mut entities = HashMap::new();
+        let forward = upsert("e1", 1.0);
+        let previous = forward.apply(&mut entities);
+        let inverse = forward.inverse(previous);
+
+        inverse.apply(&mut entities);
+        assert!(entities.is_empty());
+    }
+
+    #[test]
+    fn test_undo_redo_scoped_per_user() {
+        let mut history = RoomHistory::default();
+        history.record("alice", OperationKind::Spawn, upsert("e1", 1.0), EntityMutation::Remove { entity_id: "e1".to_string() });
+        history.record("bob", OperationKind::Spawn, upsert("e2", 2.0), EntityMutation::Remove { entity_id: "e2".to_string() });
+
+        let undone = history.undo("alice").unwrap();
+        assert_eq!(undone.entity_id, "e1");
+        assert!(undone.undone);
+        assert!(!history.can_undo("alice"));
+        assert!(history.can_undo("bob"));
+
+        let redone = history.redo("alice").unwrap();
+        assert_eq!(redone.entity_id, "e1");
+        assert!(!redone.undone);
+        assert!(history.undo("carol").is_none());
+    }
+
+    #[test]
+    fn test_unattributed_operations_cannot_be_undone() {
+        let mut history = RoomHistory::default();
+        history.record("", OperationKind::Spawn, upsert("e1", 1.0), EntityMutation::Remove { entity_id: "e1".to_string() });
+
+        assert_eq!(history.recent(10).len(), 1);
+        assert!(history.undo("").is_none());
+    }
+
+    #[test]
+    fn test_moves_are_described_as_spawns() {
+        let before = entity("e1", 0.0);
+        let mut recolored = before.clone();
+        recolored.components = serde_json::json!({ "color": "red" });
+
+        assert!(matches!(
+            EntityMutation::Upsert { entity: recolored }.to_change(Some(&before)),
+            EntityChange::Update { .. }
+        ));
+        assert!(matches!(upsert("e1", 1.0).to_change(Some(&before)), EntityChange::Spawn { .. }));
+        assert!(upsert("e1", 1.0).produced(Some(&entity("e1", 1.0))));
+        assert!(!upsert("e1", 1.0).produced(Some(&before)));
+    }
+
+    #[test]
+    fn test_new_operation_clears_redo() {
+        let mut history = RoomHistory::default();
+        history.record("alice", OperationKind::Spawn, upsert("e1", 1.0), EntityMutation::Remove { entity_id: "e1".to_string() });
+        history.undo("alice").unwrap();
+        assert!(history.can_redo("alice"));
+
+        history.record("alice", OperationKind::Spawn, upsert("e2", 1.0), EntityMutation::Remove { entity_id: "e2".to_string() });
+        assert!(!history.can_redo("alice"));
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = RoomHistory::new(2);
+        for i in 0..3 {
+            let id = format!("e{}", i);
+            history.record("alice", OperationKind::Spawn, upsert(&id, 0.0), EntityMutation::Remove { entity_id: id.clone() });
+        }
+
+        let recent = history.recent(10);
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].entity_id, "e2");
+
+        // Evicted operations can no longer be undone
+        assert!(history.undo("alice").is_some());
+        assert!(history.undo("alice").is_some());
+        assert!(history.undo("alice").is_none());
+    }
+}
@@ -2,20 +2,32 @@
 //!
 //! Manages rooms, entities, and game state
 
+pub mod auth;
 pub mod room;
 pub mod entity;
+pub mod entity_sync;
+pub mod occupancy;
+pub mod history;
+pub mod analytics;
+pub mod scripting;
 pub mod handlers;
 pub mod admin_handlers;
 pub mod room_persistence;
 pub mod persistence_handlers;
+pub mod scripting_handlers;
 pub mod routes;
+#[cfg(test)]
+mod test_support;
 // pub mod optimization;  // Disabled: Agent Looper dependency removed
 
 use actix_web::{web, App, HttpServer};
 use reticulum_core::Config;
 
+use entity_sync::EntitySync;
+use occupancy::OccupancySync;
 use routes::configure_routes;
 use room::RoomManager;
+use scripting::ScriptManager;
 
 pub struct HubService {
     config: Config,
@@ -53,11 +65,38 @@ impl HubService {
 
         // Create shared room manager
         let room_manager = RoomManager::new();
+        let script_manager = ScriptManager::new()
+            .map_err(|e| std::io::Error::other(e.to_string()))?;
 
+        // Apply entity changes from presence clients and push ours to them
+        let redis_url = self
+            .config
+            .redis
+            .as_ref()
+            .map(|redis| redis.url.clone())
+            .or_else(|| std::env::var("REDIS_URL").ok());
+        match redis_url.as_deref().map(EntitySync::new) {
+            Some(Ok(entity_sync)) => {
+                entity_sync.start(room_manager.clone(), script_manager.clone());
+            }
+            Some(Err(e)) => log::warn!("Entity sync with presence unavailable: {}", e),
+            None => log::info!("No Redis configured, entity changes are not synced with presence"),
+        }
+
+        // Seat clients as they connect to presence and free them when they go
+        match redis_url.as_deref().map(OccupancySync::new) {
+            Some(Ok(occupancy)) => {
+                occupancy.start(self.config.clone(), room_manager.clone(), script_manager.clone());
+            }
+            Some(Err(e)) => log::warn!("Seats from presence unavailable: {}", e),
+            None => {}
+        }
+
         HttpServer::new(move || {
             App::new()
                 .app_data(web::Data::new(self.config.clone()))
                 .app_data(web::Data::new(room_manager.clone()))
+                .app_data(web::Data::new(script_manager.clone()))
                 // .app_data(web::Data::new(self.optimization.clone())) // Disabled
                 .wrap(actix_cors::Cors::permissive())
                 .wrap(reticulum_core::middleware::LoggingMiddleware)
@@ -69,3 +108,4 @@ impl HubService {
         .await
     }
 }
+
\ No newline at end of file
@@ -0,0 +1,226 @@
+//! Seat claims, seat releases and host handoffs from presence over Redis
+//!
+//! Presence publishes a claim when a user's first connection to a room
+//! opens, since clients may connect without calling the join endpoint. A user
+//! without a seat in that instance takes one, and room scripts and analytics
+//! see them join.
+//!
+//! Presence publishes a release when a user's last connection to a room
+//! closes, since clients that disconnect rarely call the leave endpoint. The user leaves their instance exactly as
+//! through that endpoint, so occupancy, host migration, room scripts and
+//! analytics all see the departure.
+//!
+//! Presence also publishes a handoff when it elects a new room host, so the
+//! entities of the previous host move to the one presence chose.
+
+use futures_util::StreamExt;
+use reticulum_core::models::PresenceEventType;
+use reticulum_core::occupancy::{HostHandoff, SeatClaim, SeatRelease, HOST_CHANNEL, OCCUPANCY_CHANNEL, SEAT_CLAIM_CHANNEL};
+use reticulum_core::{db, Config, Error, Result};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use crate::handlers::record_presence_event;
+use crate::room::RoomManager;
+use crate::scripting::{ScriptEvent, ScriptManager};
+
+/// Delay before reconnecting to Redis
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Applies presence's seat claims, seat releases and host handoffs to the hub's rooms
+pub struct OccupancySync {
+    client: redis::Client,
+}
+
+impl OccupancySync {
+    pub fn new(redis_url: &str) -> Result<Self> {
+        let client = redis::Client::open(redis_url)
+            .map_err(|e| Error::internal(format!("Failed to create Redis client: {}", e)))?;
+        Ok(Self { client })
+    }
+
+    /// Apply claims, releases and handoffs until the returned task is aborted
+    pub fn start(&self, config: Config, room_manager: RoomManager, script_manager: ScriptManager) -> JoinHandle<()> {
+        let client = self.client.clone();
+        tokio::spawn(async move {
+            loop {
+                let mut pubsub = match client.get_async_connection().await {
+                    Ok(conn) => conn.into_pubsub(),
+                    Err(e) => {
+                        log::error!("Failed to connect to Redis for seat releases: {}", e);
+                        tokio::time::sleep(RECONNECT_DELAY).await;
+                        continue;
+                    }
+                };
+
+                if let Err(e) = pubsub.subscribe(&[SEAT_CLAIM_CHANNEL, OCCUPANCY_CHANNEL, HOST_CHANNEL]).await {
+                    log::error!("Failed to subscribe to the occupancy channels: {}", e);
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                    continue;
+                }
+
+                let mut messages = pubsub.on_message();
+                while let Some(msg) = messages.next().await {
+                    if msg.get_channel_name() == SEAT_CLAIM_CHANNEL {
+                        match serde_json::from_slice::<SeatClaim>(msg.get_payload_bytes()) {
+                            Ok(claim) => claim_seat(&config, &room_manager, &script_manager, claim).await,
+                            Err(e) => log::warn!("Invalid seat claim: {}", e),
+                        }
+                        continue;
+                    }
+                    if msg.get_channel_name() == HOST_CHANNEL {
+                        match serde_json::from_slice::<HostHandoff>(msg.get_payload_bytes()) {
+                            Ok(handoff) => hand_off_host(&room_manager, handoff).await,
+                            Err(e) => log::warn!("Invalid host handoff: {}", e),
+                        }
+                        continue;
+                    }
+                    match serde_json::from_slice::<SeatRelease>(msg.get_payload_bytes()) {
+                        Ok(release) => release_seat(&config, &room_manager, &script_manager, release).await,
+                        Err(e) => log::warn!("Invalid seat release: {}", e),
+                    }
+                }
+
+                log::warn!("Redis subscription to the occupancy channels lost, reconnecting");
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        })
+    }
+}
+
+/// Seat a newly connected user in their instance
+pub async fn claim_seat(
+    config: &Config,
+    room_manager: &RoomManager,
+    script_manager: &ScriptManager,
+    claim: SeatClaim,
+) {
+    let SeatClaim { instance_id, user_id } = claim;
+
+    // Nothing to do if the user already joined over HTTP
+    let Some(room_id) = room_manager.claim_seat(&instance_id, &user_id).await else {
+        return;
+    };
+    log::info!("Seated {} in {} on connect", user_id, instance_id);
+
+    match db::connect(config).await {
+        Ok(db) => record_presence_event(&db, &room_id, &instance_id, &user_id, PresenceEventType::Join).await,
+        Err(e) => log::warn!("Skipping join event, database unavailable: {}", e),
+    }
+    script_manager
+        .dispatch(room_manager, &instance_id, ScriptEvent::PlayerJoined { user_id })
+        .await;
+}
+
+/// Remove a released user from their instance
+pub async fn release_seat(
+    config: &Config,
+    room_manager: &RoomManager,
+    script_manager: &ScriptManager,
+    release: SeatRelease,
+) {
+    let SeatRelease { instance_id, user_id, reason } = release;
+
+    // Nothing to do if the user already left over HTTP
+    let Some(room_id) = room_manager.player_room_id(&instance_id, &user_id).await else {
+        return;
+    };
+    if let Err(e) = room_manager.leave_instance(&instance_id, &user_id).await {
+        log::error!("Failed to release {} from {}: {}", user_id, instance_id, e);
+        return;
+    }
+    log::info!("Released {} from {} ({})", user_id, instance_id, reason);
+
+    script_manager
+        .dispatch(room_manager, &instance_id, ScriptEvent::PlayerLeft { user_id: user_id.clone() })
+        .await;
+    match db::connect(config).await {
+        Ok(db) => record_presence_event(&db, &room_id, &instance_id, &user_id, PresenceEventType::Leave).await,
+        Err(e) => log::warn!("Skipping leave event, database unavailable: {}", e),
+    }
+}
+
+/// Make presence's elected host the instance's host, handing them the
+/// previous host's entities
+pub async fn hand_off_host(room_manager: &RoomManager, handoff: HostHandoff) {
+    let HostHandoff { instance_id, new_host } = handoff;
+
+    match room_manager.migrate_host(&instance_id, &new_host).await {
+        Ok(Some(migration)) => log::info!(
+            "Host of {} handed to {} by presence ({} entities transferred)",
+            instance_id,
+            new_host,
+            migration.transferred_entities.len()
+        ),
+        // The new host has not joined the instance over HTTP
+        Ok(None) => log::debug!("Ignoring host handoff to {} in {}: not a player", new_host, instance_id),
+        Err(e) => log::error!("Failed to hand the host of {} to {}: {}", instance_id, new_host, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::room::SpawnEntityRequest;
+    use crate::test_support::{manager_with, room};
+    use reticulum_core::models::{Quaternion, Vector3};
+
+    /// Room manager with one instance hosted by alice, who owns a box, and joined by bob
+    async fn manager_with_host() -> RoomManager {
+        let entity = SpawnEntityRequest {
+            entity_id: "box".to_string(),
+            template_id: "cube".to_string(),
+            owner_id: "alice".to_string(),
+            position: Vector3 { x: 0.0, y: 0.0, z: 0.0 },
+            rotation: Quaternion { x: 0.0, y: 0.0, z: 0.0, w: 1.0 },
+            components: serde_json::json!({}),
+        }
+        .to_entity_data("room-1".to_string());
+        let mut room = room("room-1", "carol", &["alice", "bob"]);
+        room.entities.insert("box".to_string(), entity);
+        manager_with([room]).await
+    }
+
+    async fn owner_of(room_manager: &RoomManager, entity_id: &str) -> String {
+        let entities = room_manager.get_entities("room-1").await.unwrap();
+        entities.into_iter().find(|e| e.entity_id == entity_id).unwrap().owner_id
+    }
+
+    #[tokio::test]
+    async fn test_handoffs_move_the_host_entities() {
+        let room_manager = manager_with_host().await;
+
+        // Presence may elect a user who has not joined over HTTP
+        let handoff = |new_host: &str| HostHandoff {
+            instance_id: "room-1".to_string(),
+            new_host: new_host.to_string(),
+        };
+        hand_off_host(&room_manager, handoff("dave")).await;
+        assert_eq!(room_manager.get_host("room-1").await.as_deref(), Some("alice"));
+        assert_eq!(owner_of(&room_manager, "box").await, "alice");
+
+        hand_off_host(&room_manager, handoff("bob")).await;
+        assert_eq!(room_manager.get_host("room-1").await.as_deref(), Some("bob"));
+        assert_eq!(owner_of(&room_manager, "box").await, "bob");
+    }
+
+    #[tokio::test]
+    async fn test_releasing_the_host_migrates_the_host() {
+        let mut config = Config::load_or_default().unwrap();
+        // Leave events are skipped rather than waiting for a database
+        config.database.url = "unsupported://".to_string();
+        let room_manager = manager_with_host().await;
+        let script_manager = ScriptManager::new().unwrap();
+
+        let release = SeatRelease {
+            instance_id: "room-1".to_string(),
+            user_id: "alice".to_string(),
+            reason: "disconnected".to_string(),
+        };
+        release_seat(&config, &room_manager, &script_manager, release).await;
+
+        assert!(room_manager.player_room_id("room-1", "alice").await.is_none());
+        assert_eq!(room_manager.get_host("room-1").await.as_deref(), Some("bob"));
+        assert_eq!(owner_of(&room_manager, "box").await, "bob");
+    }
+}
@@ -1,16 +1,17 @@
 //! Room persistence handlers
 
 use actix_web::{web, HttpResponse};
+use sea_orm::{ActiveModelTrait, ActiveValue};
 use serde_json::json;
 use reticulum_core::{Config, db, models as core_models};
+use reticulum_core::models::room_states::RoomStateModel;
 
 use super::room_persistence::{
     RoomState,
     EnvironmentSettings,
     SaveRoomRequest,
-    LoadRoomResponse,
     get_default_templates,
-    create_room_from_template,
+    create_room_from_template as room_state_from_template,
 };
 
 /// Save room state
@@ -39,7 +40,7 @@ pub async fn save_room(
             let description = body.description.clone().or(room.description.clone());
 
             // Get all entities for this room
-            let entities_json = match core_models::EntityModel::find_by_room_id(&db, &room_id).await {
+            let entities_json = match core_models::EntityModel::find_by_room(&db, &room_id).await {
                 Ok(entities) => {
                     serde_json::to_value(&entities).unwrap_or_else(|_| serde_json::json!([]))
                 }
@@ -60,7 +61,7 @@ pub async fn save_room(
             });
 
             // Save room state to database
-            match core_models::RoomStateDbModel::save_room_state(
+            match RoomStateModel::save_room_state(
                 &db,
                 &room_id,
                 name.clone(),
@@ -83,17 +84,20 @@ pub async fn save_room(
             // Update room metadata if provided
             if body.name.is_some() || body.description.is_some() {
                 let now = chrono::Utc::now().naive_utc();
-                let mut active_model: core_models::rooms::ActiveModel = room.into();
+                let mut active_model = core_models::rooms::ActiveModel {
+                    id: ActiveValue::Unchanged(room.id),
+                    ..Default::default()
+                };
 
-                if let Some(name) = body.name {
-                    active_model.name = sea_orm::ActiveValue::Set(name);
+                if let Some(name) = body.name.clone() {
+                    active_model.name = ActiveValue::Set(name);
                 }
 
-                if let Some(description) = body.description {
-                    active_model.description = sea_orm::ActiveValue::Set(description);
+                if let Some(description) = body.description.clone() {
+                    active_model.description = ActiveValue::Set(Some(description));
                 }
 
-                active_model.updated_at = sea_orm::ActiveValue::Set(now);
+                active_model.updated_at = ActiveValue::Set(now);
 
                 match active_model.update(&db).await {
                     Ok(_) => log::info!("Room metadata updated for {}", room_id),
@@ -143,7 +147,7 @@ pub async fn load_room(
     };
 
     // Fetch room state from room_states table
-    match core_models::RoomStateDbModel::find_by_room_id(&db, &room_id).await {
+    match RoomStateModel::find_by_room_id(&db, &room_id).await {
         Ok(Some(room_state)) => {
             let entities: Vec<super::room_persistence::EntityState> =
                 serde_json::from_value(room_state.entities).unwrap_or_default();
@@ -167,7 +171,7 @@ pub async fn load_room(
                     description: room_state.description,
                     entities,
                     environment,
-                    last_modified: room_state.last_modified.naive_utc(),
+                    last_modified: room_state.last_modified.into(),
                 }
             }))
         }
@@ -189,7 +193,7 @@ pub async fn load_room(
                         fog_color: [0.5, 0.5, 0.5],
                         fog_density: 0.0,
                     },
-                    last_modified: chrono::Utc::now().naive_utc(),
+                    last_modified: chrono::Utc::now(),
                 }
             }))
         }
@@ -231,8 +235,8 @@ pub async fn create_room_from_template(
     };
 
     // Create new room from template
-    let room_state = create_room_from_template(template, user_id.into_inner());
-    let now = chrono::Utc::now().naive_utc();
+    let user_id = user_id.into_inner();
+    let room_state = room_state_from_template(template, user_id.clone());
 
     let db = match db::connect(&config).await {
         Ok(db) => db,
@@ -249,8 +253,8 @@ pub async fn create_room_from_template(
         &db,
         room_state.room_id.clone(),
         room_state.name.clone(),
-        Some(room_state.description),
-        user_id.clone(),
+        room_state.description,
+        user_id,
     )
     .await
     {
@@ -270,8 +274,9 @@ pub async fn create_room_from_template(
             }))
         }
     }
+}
 
-    /// Clone existing room
+/// Clone existing room
 pub async fn clone_room(
     config: web::Data<Config>,
     user_id: web::ReqData<String>,
@@ -295,7 +300,6 @@ pub async fn clone_room(
         Ok(Some(source_room)) => {
             // Create clone with new ID
             let new_room_id = uuid::Uuid::new_v4().to_string();
-            let now = chrono::Utc::now().naive_utc();
 
             match core_models::RoomModel::create(
                 &db,
@@ -1,22 +1,111 @@
 //! Room state management
 
-use reticulum_core::{models as core_models, Result};
+use reticulum_core::entity_sync::{EntityChange, EntitySyncEvent};
+use reticulum_core::{models as core_models, Error, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
-
-#TH|#[derive(Clone, Serialize, Deserialize)]
-#NZ|pub struct RoomState {
-#WT|    pub room_id: String,
-#PZ|    pub name: String,
-#SR|    pub description: Option<String>,
-#VK|    pub max_players: i32,
-#YV|    pub current_players: i32,
-#BN|    pub created_by: String,
-#TN|    pub entities: HashMap<String, core_models::EntityData>,
-#WJ|    pub host_client_id: Option<String>,  // First client who joins becomes host
-#YK|}
+use tokio::sync::{broadcast, RwLock};
+
+use crate::history::{EntityMutation, Operation, OperationKind, RoomHistory};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RoomState {
+    pub room_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub max_players: i32,
+    pub current_players: i32,
+    pub created_by: String,
+    pub entities: HashMap<String, core_models::EntityData>,
+    pub host_client_id: Option<String>,  // First client who joins becomes host
+    #[serde(default)]
+    pub instance_id: String,  // Equals room_id for the primary instance
+    #[serde(default)]
+    pub players: Vec<String>,  // User IDs currently in this instance
+}
+
+impl RoomState {
+    /// Whether this is the room's primary instance
+    pub fn is_primary_instance(&self) -> bool {
+        self.instance_id == self.room_id
+    }
+
+    /// Whether another player fits into this instance
+    pub fn has_space(&self) -> bool {
+        self.current_players < self.max_players
+    }
+
+    /// Add a player, making them host if the instance has none
+    fn seat(&mut self, user_id: String) {
+        self.current_players += 1;
+        if self.host_client_id.is_none() {
+            self.host_client_id = Some(user_id.clone());
+        }
+        self.players.push(user_id);
+    }
+
+    /// Hand the host role to `new_host`, transferring entities owned by the previous host
+    fn hand_off_host(&mut self, new_host: Option<String>) -> HostMigration {
+        let previous_host = self.host_client_id.take();
+        let mut transferred_entities = Vec::new();
+
+        if let (Some(previous), Some(next)) = (&previous_host, &new_host) {
+            for entity in self.entities.values_mut().filter(|e| &e.owner_id == previous) {
+                entity.owner_id = next.clone();
+                transferred_entities.push(entity.entity_id.clone());
+            }
+            transferred_entities.sort();
+        }
+
+        self.host_client_id = new_host.clone();
+        HostMigration {
+            instance_id: self.instance_id.clone(),
+            previous_host,
+            new_host,
+            transferred_entities,
+        }
+    }
+}
+
+/// Outcome of reassigning a room instance's host
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostMigration {
+    pub instance_id: String,
+    pub previous_host: Option<String>,
+    pub new_host: Option<String>,
+    pub transferred_entities: Vec<String>,
+}
+
+/// A player removed from their instance by a leave
+#[derive(Debug, Clone)]
+pub struct PlayerDeparture {
+    pub instance_id: String,
+    pub user_id: String,
+    pub host_migration: Option<HostMigration>,
+}
+
+/// Summary of a live room instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomInstanceSummary {
+    pub instance_id: String,
+    pub room_id: String,
+    pub current_players: i32,
+    pub max_players: i32,
+    pub is_primary: bool,
+}
+
+impl From<&RoomState> for RoomInstanceSummary {
+    fn from(room: &RoomState) -> Self {
+        Self {
+            instance_id: room.instance_id.clone(),
+            room_id: room.room_id.clone(),
+            current_players: room.current_players,
+            max_players: room.max_players,
+            is_primary: room.is_primary_instance(),
+        }
+    }
+}
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct SpawnEntityRequest {
@@ -43,17 +132,63 @@ impl SpawnEntityRequest {
     }
 }
 
+/// Default upper bound on live instances per room
+pub const DEFAULT_MAX_INSTANCES_PER_ROOM: usize = 10;
+
+/// Prefix of the player IDs given to joiners that did not say who they are
+pub const ANONYMOUS_PLAYER_PREFIX: &str = "anon-";
+
+/// New player ID for a joiner that did not say who they are
+pub fn anonymous_player_id() -> String {
+    format!("{}{}", ANONYMOUS_PLAYER_PREFIX, uuid::Uuid::new_v4())
+}
+
+/// Applied entity changes buffered for slow subscribers
+const CHANGE_BUFFER: usize = 1024;
+
+#[derive(Clone)]
 pub struct RoomManager {
-    pub rooms: Arc<RwLock<HashMap<String, RoomState>>>,
+    pub rooms: Arc<RwLock<HashMap<String, RoomState>>>, // instance_id -> live state
+    instances: Arc<RwLock<HashMap<String, Vec<String>>>>, // room_id -> instance_ids
+    history: Arc<RwLock<HashMap<String, RoomHistory>>>, // instance_id -> operation log
+    scenes: Arc<RwLock<HashMap<String, HashMap<String, core_models::EntityData>>>>, // room_id -> persisted entities instances start from
+    changes: broadcast::Sender<EntitySyncEvent>, // every applied entity change
+    max_instances_per_room: usize,
 }
 
 impl RoomManager {
     pub fn new() -> Self {
+        Self::with_max_instances(DEFAULT_MAX_INSTANCES_PER_ROOM)
+    }
+
+    /// Create RoomManager with a custom instance limit per room
+    pub fn with_max_instances(max_instances_per_room: usize) -> Self {
         Self {
             rooms: Arc::new(RwLock::new(HashMap::new())),
+            instances: Arc::new(RwLock::new(HashMap::new())),
+            history: Arc::new(RwLock::new(HashMap::new())),
+            scenes: Arc::new(RwLock::new(HashMap::new())),
+            changes: broadcast::channel(CHANGE_BUFFER).0,
+            max_instances_per_room: max_instances_per_room.max(1),
         }
     }
 
+    /// Receive every entity change applied from now on, including undo and redo
+    pub fn subscribe_changes(&self) -> broadcast::Receiver<EntitySyncEvent> {
+        self.changes.subscribe()
+    }
+
+    fn emit_change(&self, room_id: &str, operation: &Operation, change: EntityChange) {
+        // Having no subscribers is fine, entity sync is optional
+        let _ = self.changes.send(EntitySyncEvent {
+            room_id: room_id.to_string(),
+            seq: operation.seq,
+            author_id: operation.author_id.clone(),
+            change,
+            timestamp: operation.timestamp.timestamp_millis(),
+        });
+    }
+
     /// Get a room state, loading from database if not in memory
     pub async fn get_room(&self, room_id: &str, db: &reticulum_core::DatabaseConnection) -> Result<Option<RoomState>> {
         {
@@ -63,18 +198,27 @@ impl RoomManager {
             }
         }
 
-        // Load from database
+        // Load from database, along with the persisted scene every instance starts from
         if let Some(room) = core_models::RoomModel::find_by_room_id(db, room_id).await? {
-            #TB|            let state = RoomState {
-#BZ|                room_id: room.room_id.clone(),
-#XS|                name: room.name.clone(),
-#QV|                description: room.description,
-#JP|                max_players: room.max_players,
-#VN|                current_players: 0,
-#HY|                created_by: room.created_by,
-#KH|                entities: HashMap::new(),
-#JT|                host_client_id: None,  // First player to join becomes host
-#WK|            };
+            let scene: HashMap<String, core_models::EntityData> = core_models::EntityModel::find_by_room(db, room_id)
+                .await?
+                .into_iter()
+                .map(|entity| (entity.entity_id.clone(), entity))
+                .collect();
+            self.scenes.write().await.insert(room_id.to_string(), scene.clone());
+
+            let state = RoomState {
+                room_id: room.room_id.clone(),
+                name: room.name.clone(),
+                description: room.description,
+                max_players: room.max_players,
+                current_players: 0,
+                created_by: room.created_by,
+                entities: scene,
+                host_client_id: None,  // First player to join becomes host
+                instance_id: room.room_id,
+                players: Vec::new(),
+            };
 
             let mut rooms = self.rooms.write().await;
             rooms.insert(room_id.to_string(), state.clone());
@@ -84,12 +228,12 @@ impl RoomManager {
         }
     }
 
-    /// Add a player to the room
+    /// Add an anonymous player to the room
     pub async fn add_player(&self, room_id: &str) -> Result<bool> {
         let mut rooms = self.rooms.write().await;
         if let Some(room) = rooms.get_mut(room_id) {
-            if room.current_players < room.max_players {
-                room.current_players += 1;
+            if room.has_space() {
+                room.seat(anonymous_player_id());
                 Ok(true)
             } else {
                 Ok(false)
@@ -99,41 +243,408 @@ impl RoomManager {
         }
     }
 
-    /// Remove a player from the room
-    pub async fn remove_player(&self, room_id: &str) -> Result<()> {
+    /// Remove the most recent anonymous player from any instance of the room,
+    /// for leaves that do not say who left.
+    ///
+    /// Returns `None` when no instance of the room holds an anonymous player.
+    pub async fn remove_player(&self, room_id: &str) -> Result<Option<PlayerDeparture>> {
+        let found = {
+            let instances = self.instances.read().await;
+            let rooms = self.rooms.read().await;
+            let instance_ids = instances
+                .get(room_id)
+                .cloned()
+                .unwrap_or_else(|| vec![room_id.to_string()]);
+
+            // Newest instances first, so secondary instances empty out and are torn down
+            instance_ids.iter().rev().find_map(|instance_id| {
+                let player = rooms
+                    .get(instance_id)?
+                    .players
+                    .iter()
+                    .rev()
+                    .find(|p| p.starts_with(ANONYMOUS_PLAYER_PREFIX))?;
+                Some((instance_id.clone(), player.clone()))
+            })
+        };
+
+        let Some((instance_id, user_id)) = found else {
+            return Ok(None);
+        };
+        let host_migration = self.leave_instance(&instance_id, &user_id).await?;
+        Ok(Some(PlayerDeparture {
+            instance_id,
+            user_id,
+            host_migration,
+        }))
+    }
+
+    /// Join a user to a live instance of a room, spawning a new instance when all are full.
+    ///
+    /// Routing prefers the instance holding most of `friend_ids`, then the least-full
+    /// instance. Returns the joined instance ID, or `None` if the room is not loaded or
+    /// every instance is full and the instance limit is reached.
+    pub async fn join_instance(
+        &self,
+        room_id: &str,
+        user_id: &str,
+        friend_ids: &[String],
+    ) -> Result<Option<String>> {
+        let mut instances = self.instances.write().await;
         let mut rooms = self.rooms.write().await;
-        if let Some(room) = rooms.get_mut(room_id) {
-            room.current_players = room.current_players.saturating_sub(1).max(0);
+
+        let primary = match rooms.get(room_id) {
+            Some(room) => room.clone(),
+            None => return Ok(None),
+        };
+
+        let instance_ids = instances
+            .entry(room_id.to_string())
+            .or_insert_with(|| vec![room_id.to_string()]);
+
+        // Rejoining users stay in their current instance
+        if let Some(instance_id) = instance_ids.iter().find(|id| {
+            rooms
+                .get(id.as_str())
+                .map(|r| r.players.iter().any(|p| p == user_id))
+                .unwrap_or(false)
+        }) {
+            return Ok(Some(instance_id.clone()));
         }
-        Ok(())
+
+        let open: Vec<&RoomState> = instance_ids
+            .iter()
+            .filter_map(|id| rooms.get(id))
+            .filter(|r| r.has_space())
+            .collect();
+
+        let friend_instance = open
+            .iter()
+            .map(|r| (r, r.players.iter().filter(|p| friend_ids.contains(p)).count()))
+            .filter(|(_, friends)| *friends > 0)
+            .max_by_key(|(_, friends)| *friends)
+            .map(|(r, _)| r.instance_id.clone());
+
+        let target = match friend_instance
+            .or_else(|| open.iter().min_by_key(|r| r.current_players).map(|r| r.instance_id.clone()))
+        {
+            Some(instance_id) => instance_id,
+            None if instance_ids.len() < self.max_instances_per_room => {
+                let instance_id = format!("{}~{}", room_id, uuid::Uuid::new_v4().simple());
+                // Live state is separate per instance, the persisted scene is shared
+                let scene = self.scenes.read().await.get(room_id).cloned().unwrap_or_default();
+                let instance = RoomState {
+                    current_players: 0,
+                    entities: scene,
+                    host_client_id: None,
+                    instance_id: instance_id.clone(),
+                    players: Vec::new(),
+                    ..primary
+                };
+                rooms.insert(instance_id.clone(), instance);
+                instance_ids.push(instance_id.clone());
+                log::info!("Spawned instance {} for full room {}", instance_id, room_id);
+                instance_id
+            }
+            None => return Ok(None),
+        };
+
+        if let Some(room) = rooms.get_mut(&target) {
+            room.seat(user_id.to_string());
+        }
+
+        Ok(Some(target))
     }
 
-    /// Spawn an entity in a room
-    pub async fn spawn_entity(&self, room_id: &str, request: SpawnEntityRequest) -> Result<()> {
+    /// Seat a user in a specific live instance they connected to directly.
+    ///
+    /// Returns the instance's room ID when the user was seated, or `None` if the
+    /// instance is not loaded, is full, or already seats the user.
+    pub async fn claim_seat(&self, instance_id: &str, user_id: &str) -> Option<String> {
         let mut rooms = self.rooms.write().await;
-        if let Some(room) = rooms.get_mut(room_id) {
-            let entity_data = request.to_entity_data(room_id.to_string());
-            room.entities.insert(entity_data.entity_id.clone(), entity_data);
+        let room = rooms.get_mut(instance_id)?;
+        if room.players.iter().any(|p| p == user_id) || !room.has_space() {
+            return None;
+        }
+        room.seat(user_id.to_string());
+        Some(room.room_id.clone())
+    }
+
+    /// Remove a user from an instance, tearing down secondary instances once empty.
+    ///
+    /// When the departing user was the host, the longest-present remaining player is
+    /// elected and inherits the host's entities; the migration is returned.
+    pub async fn leave_instance(&self, instance_id: &str, user_id: &str) -> Result<Option<HostMigration>> {
+        let mut instances = self.instances.write().await;
+        let mut rooms = self.rooms.write().await;
+
+        let (room_id, now_empty, migration) = match rooms.get_mut(instance_id) {
+            Some(room) => {
+                let before = room.players.len();
+                room.players.retain(|p| p != user_id);
+                if room.players.len() < before {
+                    room.current_players = room.current_players.saturating_sub(1).max(0);
+                }
+
+                // Players are kept in join order, so the first one is the next host
+                let migration = if room.host_client_id.as_deref() == Some(user_id) {
+                    let migration = room.hand_off_host(room.players.first().cloned());
+                    log::info!(
+                        "Host of {} migrated from {} to {:?}",
+                        instance_id, user_id, migration.new_host
+                    );
+                    Some(migration)
+                } else {
+                    None
+                };
+
+                (room.room_id.clone(), room.current_players == 0 && !room.is_primary_instance(), migration)
+            }
+            None => return Ok(None),
+        };
+
+        if now_empty {
+            rooms.remove(instance_id);
+            if let Some(ids) = instances.get_mut(&room_id) {
+                ids.retain(|id| id != instance_id);
+            }
+            // Mutations lock history before rooms, so release rooms first
+            drop(rooms);
+            drop(instances);
+            self.history.write().await.remove(instance_id);
+            log::info!("Tore down empty instance {} of room {}", instance_id, room_id);
+        }
+
+        Ok(migration)
+    }
+
+    /// Explicitly hand the host role to a player already in the instance.
+    ///
+    /// Used when the host is chosen by role rather than join order. Returns `None`
+    /// if the instance is not loaded or `new_host` is not one of its players.
+    pub async fn migrate_host(&self, instance_id: &str, new_host: &str) -> Result<Option<HostMigration>> {
+        let mut rooms = self.rooms.write().await;
+
+        match rooms.get_mut(instance_id) {
+            Some(room) if room.players.iter().any(|p| p == new_host) => {
+                if room.host_client_id.as_deref() == Some(new_host) {
+                    return Ok(Some(HostMigration {
+                        instance_id: instance_id.to_string(),
+                        previous_host: room.host_client_id.clone(),
+                        new_host: room.host_client_id.clone(),
+                        transferred_entities: Vec::new(),
+                    }));
+                }
+                Ok(Some(room.hand_off_host(Some(new_host.to_string()))))
+            }
+            _ => Ok(None),
         }
+    }
+
+    /// Room a live instance belongs to
+    pub async fn instance_room_id(&self, instance_id: &str) -> Option<String> {
+        let rooms = self.rooms.read().await;
+        rooms.get(instance_id).map(|room| room.room_id.clone())
+    }
+
+    /// Room of a live instance, if `user_id` is one of its players
+    pub async fn player_room_id(&self, instance_id: &str, user_id: &str) -> Option<String> {
+        let rooms = self.rooms.read().await;
+        rooms
+            .get(instance_id)
+            .filter(|room| room.players.iter().any(|p| p == user_id))
+            .map(|room| room.room_id.clone())
+    }
+
+    /// Get the current host of a room instance
+    pub async fn get_host(&self, instance_id: &str) -> Option<String> {
+        let rooms = self.rooms.read().await;
+        rooms.get(instance_id).and_then(|room| room.host_client_id.clone())
+    }
+
+    /// List live instances of a room
+    pub async fn list_instances(&self, room_id: &str) -> Vec<RoomInstanceSummary> {
+        let instances = self.instances.read().await;
+        let rooms = self.rooms.read().await;
+
+        match instances.get(room_id) {
+            Some(ids) => ids
+                .iter()
+                .filter_map(|id| rooms.get(id))
+                .map(RoomInstanceSummary::from)
+                .collect(),
+            None => rooms
+                .get(room_id)
+                .map(|room| vec![RoomInstanceSummary::from(room)])
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Spawn an entity in a room
+    pub async fn spawn_entity(&self, room_id: &str, request: SpawnEntityRequest) -> Result<()> {
+        let entity = request.to_entity_data(room_id.to_string());
+        self.apply_mutation(room_id, None, EntityMutation::Upsert { entity }).await?;
         Ok(())
     }
 
     /// Update an entity in a room
     pub async fn update_entity(&self, room_id: &str, entity_id: &str, entity: core_models::EntityData) -> Result<()> {
-        let mut rooms = self.rooms.write().await;
-        if let Some(room) = rooms.get_mut(room_id) {
-            room.entities.insert(entity_id.to_string(), entity);
-        }
+        let entity = core_models::EntityData {
+            entity_id: entity_id.to_string(),
+            ..entity
+        };
+        self.apply_mutation(room_id, None, EntityMutation::Upsert { entity }).await?;
         Ok(())
     }
 
     /// Despawn an entity from a room
     pub async fn despawn_entity(&self, room_id: &str, entity_id: &str) -> Result<()> {
+        let mutation = EntityMutation::Remove {
+            entity_id: entity_id.to_string(),
+        };
+        self.apply_mutation(room_id, None, mutation).await?;
+        Ok(())
+    }
+
+    /// Apply an entity mutation and record it with its inverse in the room's history.
+    ///
+    /// Changes without an author are recorded but nobody can undo them. Returns `None`
+    /// when the room does not exist or the mutation changed nothing.
+    pub async fn apply_mutation(
+        &self,
+        room_id: &str,
+        author_id: Option<&str>,
+        mutation: EntityMutation,
+    ) -> Result<Option<Operation>> {
+        let mut history = self.history.write().await;
         let mut rooms = self.rooms.write().await;
-        if let Some(room) = rooms.get_mut(room_id) {
-            room.entities.remove(entity_id);
+
+        let room = match rooms.get_mut(room_id) {
+            Some(room) => room,
+            None => return Ok(None),
+        };
+
+        let previous = mutation.apply(&mut room.entities);
+        if previous.is_none() && matches!(mutation, EntityMutation::Remove { .. }) {
+            return Ok(None);
         }
-        Ok(())
+
+        let kind = OperationKind::classify(&mutation, previous.is_some());
+        let change = mutation.to_change(previous.as_ref());
+        let inverse = mutation.inverse(previous);
+        let operation = history
+            .entry(room_id.to_string())
+            .or_default()
+            .record(author_id.unwrap_or_default(), kind, mutation, inverse);
+        self.emit_change(room_id, &operation, change);
+
+        Ok(Some(operation))
+    }
+
+    /// Apply an entity change requested through presence.
+    ///
+    /// Updates merge their components into the existing entity. Returns `None`
+    /// when the room or the updated entity does not exist, or nothing changed.
+    pub async fn apply_change(
+        &self,
+        room_id: &str,
+        author_id: Option<&str>,
+        change: EntityChange,
+    ) -> Result<Option<Operation>> {
+        let mutation = match change {
+            EntityChange::Spawn { entity } => EntityMutation::Upsert {
+                entity: core_models::EntityData {
+                    room_id: room_id.to_string(),
+                    ..entity
+                },
+            },
+            EntityChange::Update { entity_id, components } => {
+                let rooms = self.rooms.read().await;
+                let mut entity = match rooms.get(room_id).and_then(|room| room.entities.get(&entity_id)) {
+                    Some(entity) => entity.clone(),
+                    None => return Ok(None),
+                };
+                drop(rooms);
+
+                match entity.components.as_object_mut() {
+                    Some(existing) => existing.extend(components),
+                    None => entity.components = serde_json::Value::Object(components),
+                }
+                EntityMutation::Upsert { entity }
+            }
+            EntityChange::Despawn { entity_id } => EntityMutation::Remove { entity_id },
+        };
+
+        self.apply_mutation(room_id, author_id, mutation).await
+    }
+
+    /// Undo the user's most recent operation in a room.
+    ///
+    /// Fails with a conflict, and forgets the operation, when the entity
+    /// changed since the operation was applied.
+    pub async fn undo(&self, room_id: &str, user_id: &str) -> Result<Option<Operation>> {
+        let mut history = self.history.write().await;
+        let mut rooms = self.rooms.write().await;
+
+        let (room, log) = match (rooms.get_mut(room_id), history.get_mut(room_id)) {
+            (Some(room), Some(log)) => (room, log),
+            _ => return Ok(None),
+        };
+
+        let Some(next) = log.next_undo(user_id) else {
+            return Ok(None);
+        };
+        if !next.forward.produced(room.entities.get(&next.entity_id)) {
+            let entity_id = next.entity_id.clone();
+            log.discard_undo(user_id);
+            return Err(Error::conflict(format!("Entity {} changed since the operation to undo", entity_id)));
+        }
+
+        let operation = log.undo(user_id);
+        if let Some(operation) = &operation {
+            let previous = operation.inverse.apply(&mut room.entities);
+            self.emit_change(room_id, operation, operation.inverse.to_change(previous.as_ref()));
+        }
+        Ok(operation)
+    }
+
+    /// Redo the user's most recently undone operation in a room.
+    ///
+    /// Fails with a conflict, and forgets the operation, when the entity
+    /// changed since the operation was undone.
+    pub async fn redo(&self, room_id: &str, user_id: &str) -> Result<Option<Operation>> {
+        let mut history = self.history.write().await;
+        let mut rooms = self.rooms.write().await;
+
+        let (room, log) = match (rooms.get_mut(room_id), history.get_mut(room_id)) {
+            (Some(room), Some(log)) => (room, log),
+            _ => return Ok(None),
+        };
+
+        let Some(next) = log.next_redo(user_id) else {
+            return Ok(None);
+        };
+        if !next.inverse.produced(room.entities.get(&next.entity_id)) {
+            let entity_id = next.entity_id.clone();
+            log.discard_redo(user_id);
+            return Err(Error::conflict(format!("Entity {} changed since the operation to redo was undone", entity_id)));
+        }
+
+        let operation = log.redo(user_id);
+        if let Some(operation) = &operation {
+            let previous = operation.forward.apply(&mut room.entities);
+            self.emit_change(room_id, operation, operation.forward.to_change(previous.as_ref()));
+        }
+        Ok(operation)
+    }
+
+    /// Get the most recent operations in a room, newest first
+    pub async fn get_history(&self, room_id: &str, limit: usize) -> Vec<Operation> {
+        let history = self.history.read().await;
+        history
+            .get(room_id)
+            .map(|log| log.recent(limit))
+            .unwrap_or_default()
     }
 
     /// Get all entities in a room
@@ -159,15 +670,7 @@ mod tests {
     use reticulum_core::models::{Vector3, Quaternion};
 
     fn create_test_room(room_id: &str) -> RoomState {
-        RoomState {
-            room_id: room_id.to_string(),
-            name: format!("Test Room {}", room_id),
-            description: Some("A test room".to_string()),
-            max_players: 10,
-            current_players: 0,
-            created_by: "test_user".to_string(),
-            entities: HashMap::new(),
-        }
+        crate::test_support::room(room_id, "test_user", &[])
     }
 
     fn create_test_spawn_request(entity_id: &str, owner_id: &str) -> SpawnEntityRequest {
@@ -493,4 +996,416 @@ mod tests {
         assert_eq!(room.name, deserialized.name);
         assert_eq!(room.max_players, deserialized.max_players);
     }
+
+    #[tokio::test]
+    async fn test_join_instance_spawns_when_full() {
+        let manager = RoomManager::new();
+        let room_id = "test_room_instances";
+
+        let mut room = create_test_room(room_id);
+        room.max_players = 2;
+        manager.rooms.write().await.insert(room_id.to_string(), room);
+
+        assert_eq!(manager.join_instance(room_id, "user_1", &[]).await.unwrap().as_deref(), Some(room_id));
+        assert_eq!(manager.join_instance(room_id, "user_2", &[]).await.unwrap().as_deref(), Some(room_id));
+
+        // Primary is full, third player lands in a new instance
+        let instance_id = manager.join_instance(room_id, "user_3", &[]).await.unwrap().unwrap();
+        assert_ne!(instance_id, room_id);
+
+        let instances = manager.list_instances(room_id).await;
+        assert_eq!(instances.len(), 2);
+        assert!(instances[0].is_primary);
+        assert_eq!(instances[1].current_players, 1);
+    }
+
+    #[tokio::test]
+    async fn test_join_instance_prefers_friends_then_least_full() {
+        let manager = RoomManager::new();
+        let room_id = "test_room_friends";
+
+        let mut room = create_test_room(room_id);
+        room.max_players = 2;
+        manager.rooms.write().await.insert(room_id.to_string(), room);
+
+        manager.join_instance(room_id, "user_1", &[]).await.unwrap();
+        manager.join_instance(room_id, "user_2", &[]).await.unwrap();
+        let second = manager.join_instance(room_id, "friend", &[]).await.unwrap().unwrap();
+
+        // Free a slot in the primary so both instances have space
+        manager.leave_instance(room_id, "user_2").await.unwrap();
+
+        let joined = manager
+            .join_instance(room_id, "user_4", &["friend".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(joined.as_deref(), Some(second.as_str()));
+
+        // The friend instance is full now, so the primary takes the next player
+        let joined = manager.join_instance(room_id, "user_5", &[]).await.unwrap();
+        assert_eq!(joined.as_deref(), Some(room_id));
+    }
+
+    #[tokio::test]
+    async fn test_join_instance_respects_instance_limit() {
+        let manager = RoomManager::with_max_instances(1);
+        let room_id = "test_room_limit";
+
+        let mut room = create_test_room(room_id);
+        room.max_players = 1;
+        manager.rooms.write().await.insert(room_id.to_string(), room);
+
+        assert!(manager.join_instance(room_id, "user_1", &[]).await.unwrap().is_some());
+        assert!(manager.join_instance(room_id, "user_2", &[]).await.unwrap().is_none());
+
+        // Rejoining returns the existing instance
+        let rejoined = manager.join_instance(room_id, "user_1", &[]).await.unwrap();
+        assert_eq!(rejoined.as_deref(), Some(room_id));
+    }
+
+    #[tokio::test]
+    async fn test_leave_instance_tears_down_empty_instance() {
+        let manager = RoomManager::new();
+        let room_id = "test_room_teardown";
+
+        let mut room = create_test_room(room_id);
+        room.max_players = 1;
+        manager.rooms.write().await.insert(room_id.to_string(), room);
+
+        manager.join_instance(room_id, "user_1", &[]).await.unwrap();
+        let instance_id = manager.join_instance(room_id, "user_2", &[]).await.unwrap().unwrap();
+        assert_eq!(manager.list_instances(room_id).await.len(), 2);
+
+        manager.leave_instance(&instance_id, "user_2").await.unwrap();
+        assert_eq!(manager.list_instances(room_id).await.len(), 1);
+        assert!(!manager.rooms.read().await.contains_key(&instance_id));
+
+        // The primary instance survives even when empty
+        manager.leave_instance(room_id, "user_1").await.unwrap();
+        assert_eq!(manager.list_instances(room_id).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_leave_instance_does_not_deadlock_with_mutations() {
+        let manager = RoomManager::new();
+        let room_id = "test_room_leave_race";
+
+        let mut room = create_test_room(room_id);
+        room.max_players = 1;
+        manager.rooms.write().await.insert(room_id.to_string(), room);
+        manager.join_instance(room_id, "user_1", &[]).await.unwrap();
+        let instance_id = manager.join_instance(room_id, "user_2", &[]).await.unwrap().unwrap();
+        let entity = create_test_spawn_request("box", "user_2").to_entity_data(instance_id.clone());
+
+        // Hold the rooms while the leave and then the mutation queue up on
+        // them, so the leave tears the instance down while the mutation holds
+        // the history
+        let rooms = manager.rooms.write().await;
+        let leaving = {
+            let manager = manager.clone();
+            let instance_id = instance_id.clone();
+            tokio::spawn(async move { manager.leave_instance(&instance_id, "user_2").await })
+        };
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        let editing = {
+            let manager = manager.clone();
+            tokio::spawn(async move {
+                manager
+                    .apply_mutation(&instance_id, Some("user_1"), EntityMutation::Upsert { entity })
+                    .await
+            })
+        };
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        drop(rooms);
+
+        let both = async { (leaving.await.unwrap(), editing.await.unwrap()) };
+        let (left, edited) = tokio::time::timeout(std::time::Duration::from_secs(1), both)
+            .await
+            .expect("leave and mutation deadlocked");
+        left.unwrap();
+        // The instance was gone by the time the mutation got to it
+        assert!(edited.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_claim_seat_seats_direct_connections_once() {
+        let manager = RoomManager::new();
+        let room_id = "test_room_claim";
+        let mut room = create_test_room(room_id);
+        room.max_players = 2;
+        manager.rooms.write().await.insert(room_id.to_string(), room);
+
+        manager.join_instance(room_id, "alice", &[]).await.unwrap();
+        // Already seated over HTTP
+        assert!(manager.claim_seat(room_id, "alice").await.is_none());
+
+        assert_eq!(manager.claim_seat(room_id, "bob").await.as_deref(), Some(room_id));
+        assert!(manager.claim_seat(room_id, "carol").await.is_none());
+        assert!(manager.claim_seat("unknown", "bob").await.is_none());
+
+        let rooms = manager.rooms.read().await;
+        let room = rooms.get(room_id).unwrap();
+        assert_eq!(room.players, vec!["alice".to_string(), "bob".to_string()]);
+        assert_eq!(room.current_players, 2);
+    }
+
+    #[tokio::test]
+    async fn test_legacy_leave_removes_an_anonymous_player() {
+        let manager = RoomManager::new();
+        let room_id = "test_room_legacy_leave";
+        manager.rooms.write().await.insert(room_id.to_string(), create_test_room(room_id));
+
+        assert!(manager.add_player(room_id).await.unwrap());
+        manager.join_instance(room_id, "alice", &[]).await.unwrap();
+
+        let departure = manager.remove_player(room_id).await.unwrap().unwrap();
+        assert_eq!(departure.instance_id, room_id);
+        assert!(departure.user_id.starts_with(ANONYMOUS_PLAYER_PREFIX));
+
+        // Named players are never removed by a leave that does not name them
+        assert!(manager.remove_player(room_id).await.unwrap().is_none());
+        let rooms = manager.rooms.read().await;
+        let room = rooms.get(room_id).unwrap();
+        assert_eq!(room.players, vec!["alice".to_string()]);
+        assert_eq!(room.current_players, 1);
+    }
+
+    #[tokio::test]
+    async fn test_new_instances_start_from_persisted_scene() {
+        let manager = RoomManager::new();
+        let room_id = "test_room_scene";
+
+        let mut room = create_test_room(room_id);
+        room.max_players = 1;
+        manager.rooms.write().await.insert(room_id.to_string(), room);
+        let chair = create_test_spawn_request("chair", "creator").to_entity_data(room_id.to_string());
+        manager
+            .scenes
+            .write()
+            .await
+            .insert(room_id.to_string(), HashMap::from([("chair".to_string(), chair)]));
+
+        manager.join_instance(room_id, "user_1", &[]).await.unwrap();
+        let instance_id = manager.join_instance(room_id, "user_2", &[]).await.unwrap().unwrap();
+
+        let entities = manager.get_entities(&instance_id).await.unwrap();
+        assert_eq!(entities.len(), 1);
+        assert_eq!(entities[0].entity_id, "chair");
+    }
+
+    #[tokio::test]
+    async fn test_undo_redo_entity_changes() {
+        let manager = RoomManager::new();
+        let room_id = "test_room_undo";
+
+        {
+            let mut rooms = manager.rooms.write().await;
+            rooms.insert(room_id.to_string(), create_test_room(room_id));
+        }
+
+        let entity = create_test_spawn_request("entity_1", "user_1").to_entity_data(room_id.to_string());
+        manager
+            .apply_mutation(room_id, Some("user_1"), EntityMutation::Upsert { entity })
+            .await
+            .unwrap();
+
+        let mut moved = manager.get_entities(room_id).await.unwrap().remove(0);
+        moved.position = Vector3 { x: 5.0, y: 0.0, z: 0.0 };
+        let operation = manager
+            .apply_mutation(room_id, Some("user_2"), EntityMutation::Upsert { entity: moved })
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(operation.kind, OperationKind::Update);
+
+        // user_2 undoes the move, restoring the original position
+        let undone = manager.undo(room_id, "user_2").await.unwrap().unwrap();
+        assert_eq!(undone.seq, operation.seq);
+        let entities = manager.get_entities(room_id).await.unwrap();
+        assert_eq!(entities[0].position.x, 0.0);
+
+        // user_1 undoes the spawn
+        manager.undo(room_id, "user_1").await.unwrap().unwrap();
+        assert!(manager.get_entities(room_id).await.unwrap().is_empty());
+
+        // user_1 redoes the spawn
+        manager.redo(room_id, "user_1").await.unwrap().unwrap();
+        assert_eq!(manager.get_entities(room_id).await.unwrap().len(), 1);
+
+        let history = manager.get_history(room_id, 10).await;
+        assert_eq!(history.len(), 2);
+        assert!(history[0].undone);
+        assert!(!history[1].undone);
+    }
+
+    #[tokio::test]
+    async fn test_despawn_records_inverse_spawn() {
+        let manager = RoomManager::new();
+        let room_id = "test_room_undo_despawn";
+
+        {
+            let mut rooms = manager.rooms.write().await;
+            rooms.insert(room_id.to_string(), create_test_room(room_id));
+        }
+
+        manager.spawn_entity(room_id, create_test_spawn_request("entity_1", "user_1")).await.unwrap();
+        let despawn = EntityMutation::Remove { entity_id: "entity_1".to_string() };
+        manager.apply_mutation(room_id, Some("user_1"), despawn).await.unwrap();
+        assert!(manager.get_entities(room_id).await.unwrap().is_empty());
+
+        let undone = manager.undo(room_id, "user_1").await.unwrap().unwrap();
+        assert_eq!(undone.kind, OperationKind::Despawn);
+        assert_eq!(manager.get_entities(room_id).await.unwrap().len(), 1);
+
+        // Despawning a missing entity is not recorded
+        manager.despawn_entity(room_id, "missing").await.unwrap();
+        assert_eq!(manager.get_history(room_id, 10).await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_applied_changes_are_published() {
+        let manager = RoomManager::new();
+        let room_id = "test_room_sync";
+        manager.rooms.write().await.insert(room_id.to_string(), create_test_room(room_id));
+        let mut changes = manager.subscribe_changes();
+
+        let entity = create_test_spawn_request("entity_1", "user_1").to_entity_data("elsewhere".to_string());
+        manager
+            .apply_change(room_id, Some("user_1"), EntityChange::Spawn { entity })
+            .await
+            .unwrap()
+            .unwrap();
+        let event = changes.try_recv().unwrap();
+        assert!(matches!(event.change, EntityChange::Spawn { ref entity } if entity.room_id == room_id));
+        assert_eq!(event.author_id, "user_1");
+
+        // Updates merge into the existing components and publish all of them
+        let mut components = serde_json::Map::new();
+        components.insert("color".to_string(), serde_json::json!("red"));
+        manager
+            .apply_change(
+                room_id,
+                Some("user_2"),
+                EntityChange::Update { entity_id: "entity_1".to_string(), components: components.clone() },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        match changes.try_recv().unwrap().change {
+            EntityChange::Update { components: published, .. } => assert_eq!(published, components),
+            other => panic!("expected update, got {:?}", other),
+        }
+
+        // Undo publishes the restored state too
+        manager.undo(room_id, "user_2").await.unwrap().unwrap();
+        match changes.try_recv().unwrap().change {
+            EntityChange::Update { components: published, .. } => assert!(published.is_empty()),
+            other => panic!("expected update, got {:?}", other),
+        }
+
+        // Updating a missing entity changes nothing
+        let missing = EntityChange::Update { entity_id: "missing".to_string(), components };
+        assert!(manager.apply_change(room_id, None, missing).await.unwrap().is_none());
+        assert!(changes.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_undo_is_rejected_after_a_later_change() {
+        let manager = RoomManager::new();
+        let room_id = "test_room_undo_conflict";
+        manager.rooms.write().await.insert(room_id.to_string(), create_test_room(room_id));
+
+        let entity = create_test_spawn_request("entity_1", "user_1").to_entity_data(room_id.to_string());
+        let mut moved = entity.clone();
+        manager
+            .apply_mutation(room_id, Some("user_1"), EntityMutation::Upsert { entity })
+            .await
+            .unwrap();
+        moved.position = Vector3 { x: 5.0, y: 0.0, z: 0.0 };
+        manager
+            .apply_mutation(room_id, Some("user_2"), EntityMutation::Upsert { entity: moved })
+            .await
+            .unwrap();
+
+        // Undoing user_1's spawn would throw away user_2's move
+        assert!(matches!(manager.undo(room_id, "user_1").await, Err(Error::Conflict(_))));
+        assert_eq!(manager.get_entities(room_id).await.unwrap()[0].position.x, 5.0);
+        assert!(manager.undo(room_id, "user_1").await.unwrap().is_none());
+
+        // The despawn supersedes user_2's move as well
+        manager.despawn_entity(room_id, "entity_1").await.unwrap();
+        assert!(matches!(manager.undo(room_id, "user_2").await, Err(Error::Conflict(_))));
+        assert!(manager.get_entities(room_id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_undoing_a_move_publishes_the_restored_transform() {
+        let manager = RoomManager::new();
+        let room_id = "test_room_undo_move";
+        manager.rooms.write().await.insert(room_id.to_string(), create_test_room(room_id));
+
+        manager.spawn_entity(room_id, create_test_spawn_request("entity_1", "user_1")).await.unwrap();
+        let mut moved = manager.get_entities(room_id).await.unwrap().remove(0);
+        moved.position = Vector3 { x: 5.0, y: 0.0, z: 0.0 };
+        manager
+            .apply_mutation(room_id, Some("user_2"), EntityMutation::Upsert { entity: moved })
+            .await
+            .unwrap();
+
+        let mut changes = manager.subscribe_changes();
+        manager.undo(room_id, "user_2").await.unwrap().unwrap();
+        match changes.try_recv().unwrap().change {
+            EntityChange::Spawn { entity } => assert_eq!(entity.position.x, 0.0),
+            other => panic!("expected spawn, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_host_migrates_to_next_joiner_with_entities() {
+        let manager = RoomManager::new();
+        let room_id = "test_room_host";
+        manager.rooms.write().await.insert(room_id.to_string(), create_test_room(room_id));
+
+        for user_id in ["user_1", "user_2", "user_3"] {
+            manager.join_instance(room_id, user_id, &[]).await.unwrap();
+        }
+        assert_eq!(manager.get_host(room_id).await.as_deref(), Some("user_1"));
+
+        manager.spawn_entity(room_id, create_test_spawn_request("scoreboard", "user_1")).await.unwrap();
+        manager.spawn_entity(room_id, create_test_spawn_request("ball", "user_3")).await.unwrap();
+
+        // A non-host leaving does not migrate the host
+        assert!(manager.leave_instance(room_id, "user_3").await.unwrap().is_none());
+
+        let migration = manager.leave_instance(room_id, "user_1").await.unwrap().unwrap();
+        assert_eq!(migration.previous_host.as_deref(), Some("user_1"));
+        assert_eq!(migration.new_host.as_deref(), Some("user_2"));
+        assert_eq!(migration.transferred_entities, vec!["scoreboard".to_string()]);
+
+        let entities = manager.get_entities(room_id).await.unwrap();
+        let scoreboard = entities.iter().find(|e| e.entity_id == "scoreboard").unwrap();
+        assert_eq!(scoreboard.owner_id, "user_2");
+
+        // The last player leaving clears the host
+        let migration = manager.leave_instance(room_id, "user_2").await.unwrap().unwrap();
+        assert!(migration.new_host.is_none());
+        assert!(manager.get_host(room_id).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_explicit_host_migration() {
+        let manager = RoomManager::new();
+        let room_id = "test_room_host_explicit";
+        manager.rooms.write().await.insert(room_id.to_string(), create_test_room(room_id));
+
+        manager.join_instance(room_id, "user_1", &[]).await.unwrap();
+        manager.join_instance(room_id, "moderator", &[]).await.unwrap();
+
+        let migration = manager.migrate_host(room_id, "moderator").await.unwrap().unwrap();
+        assert_eq!(migration.new_host.as_deref(), Some("moderator"));
+        assert_eq!(manager.get_host(room_id).await.as_deref(), Some("moderator"));
+
+        // Only players in the instance can become host
+        assert!(manager.migrate_host(room_id, "stranger").await.unwrap().is_none());
+    }
 }
@@ -5,6 +5,7 @@ use actix_web::web;
 use crate::handlers;
 use crate::admin_handlers;
 use crate::persistence_handlers;
+use crate::scripting_handlers;
 
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
     cfg
@@ -16,15 +17,25 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
         .route("/rooms/{room_id}", web::get().to(handlers::get_room))
         .route("/rooms/{room_id}/join", web::post().to(handlers::join_room))
         .route("/rooms/{room_id}/leave", web::post().to(handlers::leave_room))
+        .route("/rooms/{room_id}/instances", web::get().to(handlers::list_instances))
+        .route("/rooms/{room_id}/host", web::post().to(handlers::migrate_host))
         .route("/rooms/{room_id}/entities", web::post().to(handlers::spawn_entity))
         .route("/rooms/{room_id}/entities", web::get().to(handlers::list_entities))
         .route("/rooms/{room_id}/entities/{entity_id}", web::put().to(handlers::update_entity))
         .route("/rooms/{room_id}/entities/{entity_id}", web::delete().to(handlers::despawn_entity))
+        .route("/rooms/{room_id}/history", web::get().to(handlers::get_history))
+        .route("/rooms/{room_id}/history/undo", web::post().to(handlers::undo))
+        .route("/rooms/{room_id}/history/redo", web::post().to(handlers::redo))
+        // Room scripting routes
+        .route("/rooms/{room_id}/scripts", web::post().to(scripting_handlers::load_script))
+        .route("/rooms/{room_id}/scripts", web::get().to(scripting_handlers::list_scripts))
+        .route("/rooms/{room_id}/scripts/{script_id}", web::delete().to(scripting_handlers::unload_script))
         // Admin routes
         .route("/admin/rooms", web::get().to(admin_handlers::list_rooms))
         .route("/admin/rooms/{room_id}", web::get().to(admin_handlers::get_room_details))
         .route("/admin/rooms/{room_id}", web::put().to(admin_handlers::update_room_config))
         .route("/admin/rooms/{room_id}/close", web::post().to(admin_handlers::close_room))
+        .route("/admin/rooms/{room_id}/analytics", web::get().to(admin_handlers::room_analytics))
         .route("/admin/rooms/{room_id}", web::delete().to(admin_handlers::delete_room))
         // Room persistence routes
         .route("/rooms/{room_id}/save", web::post().to(persistence_handlers::save_room))
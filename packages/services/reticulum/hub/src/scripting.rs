@@ -0,0 +1,509 @@
+//! Sandboxed WASM scripting for authoritative room behaviors
+//!
+//! A room script is a WASM module exporting:
+//! - `memory`
+//! - `alloc(len: i32) -> i32`: reserve `len` bytes for the event payload
+//! - `on_event(ptr: i32, len: i32) -> i64`: handle a JSON `ScriptEvent` and return
+//!   `(out_ptr << 32) | out_len` pointing at a JSON array of `EntityMutation`s, or 0
+//!
+//! Scripts may import `env.log(ptr: i32, len: i32)`. Each event runs with a fuel
+//! budget and the instance's memory is capped, so a misbehaving script traps
+//! instead of stalling the hub. Compiling and running scripts happens on the
+//! blocking thread pool, off the async executor. Scripts that fault repeatedly
+//! are unloaded.
+
+use reticulum_core::{models as core_models, Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+use wasmtime::{Caller, Config, Engine, Instance, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, TypedFunc};
+
+use crate::history::{EntityMutation, Operation, OperationKind};
+use crate::room::RoomManager;
+
+/// Consecutive faults after which a script is unloaded
+pub const MAX_CONSECUTIVE_FAULTS: u32 = 3;
+
+/// Resource limits applied to every script
+#[derive(Debug, Clone)]
+pub struct ScriptLimits {
+    pub fuel_per_event: u64,
+    pub max_memory_bytes: usize,
+    pub max_output_bytes: usize,
+    pub max_commands_per_event: usize,
+}
+
+impl Default for ScriptLimits {
+    fn default() -> Self {
+        Self {
+            fuel_per_event: 10_000_000,
+            max_memory_bytes: 16 * 1024 * 1024,
+            max_output_bytes: 64 * 1024,
+            max_commands_per_event: 64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScriptEventKind {
+    EntitySpawned,
+    EntityUpdated,
+    EntityDespawned,
+    PlayerJoined,
+    PlayerLeft,
+}
+
+/// Event delivered to room scripts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScriptEvent {
+    EntitySpawned { entity: core_models::EntityData },
+    EntityUpdated { entity: core_models::EntityData },
+    EntityDespawned { entity_id: String },
+    PlayerJoined { user_id: String },
+    PlayerLeft { user_id: String },
+}
+
+impl ScriptEvent {
+    pub fn kind(&self) -> ScriptEventKind {
+        match self {
+            ScriptEvent::EntitySpawned { .. } => ScriptEventKind::EntitySpawned,
+            ScriptEvent::EntityUpdated { .. } => ScriptEventKind::EntityUpdated,
+            ScriptEvent::EntityDespawned { .. } => ScriptEventKind::EntityDespawned,
+            ScriptEvent::PlayerJoined { .. } => ScriptEventKind::PlayerJoined,
+            ScriptEvent::PlayerLeft { .. } => ScriptEventKind::PlayerLeft,
+        }
+    }
+
+    /// Build the event describing a recorded entity operation
+    pub fn from_operation(operation: &Operation) -> Self {
+        match (&operation.kind, &operation.forward) {
+            (OperationKind::Spawn, EntityMutation::Upsert { entity }) => {
+                ScriptEvent::EntitySpawned { entity: entity.clone() }
+            }
+            (_, EntityMutation::Upsert { entity }) => ScriptEvent::EntityUpdated { entity: entity.clone() },
+            (_, EntityMutation::Remove { entity_id }) => ScriptEvent::EntityDespawned {
+                entity_id: entity_id.clone(),
+            },
+        }
+    }
+}
+
+/// Public description of a loaded script
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptInfo {
+    pub script_id: String,
+    pub room_id: String,
+    pub subscriptions: Vec<ScriptEventKind>,
+    pub faults: u32,
+    pub loaded_at: chrono::DateTime<chrono::Utc>,
+}
+
+struct ScriptState {
+    script_id: String,
+    limits: StoreLimits,
+}
+
+struct RoomScript {
+    info: ScriptInfo,
+    subscriptions: HashSet<ScriptEventKind>, // empty = all events
+    store: Store<ScriptState>,
+    instance: Instance,
+    alloc: TypedFunc<i32, i32>,
+    on_event: TypedFunc<(i32, i32), i64>,
+}
+
+impl RoomScript {
+    /// Compile and instantiate a module; blocks while the module compiles
+    fn instantiate(
+        engine: &Engine,
+        limits: &ScriptLimits,
+        info: ScriptInfo,
+        subscriptions: HashSet<ScriptEventKind>,
+        wasm: &[u8],
+    ) -> Result<Self> {
+        let module =
+            Module::new(engine, wasm).map_err(|e| Error::validation(format!("Invalid script module: {}", e)))?;
+
+        let mut store = Store::new(
+            engine,
+            ScriptState {
+                script_id: info.script_id.clone(),
+                limits: StoreLimitsBuilder::new()
+                    .memory_size(limits.max_memory_bytes)
+                    .instances(1)
+                    .memories(1)
+                    .tables(1)
+                    .build(),
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(limits.fuel_per_event).map_err(script_error)?;
+
+        let mut linker = Linker::new(engine);
+        linker
+            .func_wrap("env", "log", |mut caller: Caller<'_, ScriptState>, ptr: i32, len: i32| {
+                let Some(memory) = caller.get_export("memory").and_then(|e| e.into_memory()) else {
+                    return;
+                };
+                let mut buf = vec![0u8; (len.max(0) as usize).min(1024)];
+                if memory.read(&caller, ptr as u32 as usize, &mut buf).is_ok() {
+                    log::info!("[script {}] {}", caller.data().script_id, String::from_utf8_lossy(&buf));
+                }
+            })
+            .map_err(script_error)?;
+
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .map_err(|e| Error::validation(format!("Failed to instantiate script: {}", e)))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&mut store, "alloc")
+            .map_err(|e| Error::validation(format!("Script must export alloc: {}", e)))?;
+        let on_event = instance
+            .get_typed_func::<(i32, i32), i64>(&mut store, "on_event")
+            .map_err(|e| Error::validation(format!("Script must export on_event: {}", e)))?;
+        if instance.get_memory(&mut store, "memory").is_none() {
+            return Err(Error::validation("Script must export memory"));
+        }
+
+        Ok(Self {
+            info,
+            subscriptions,
+            store,
+            instance,
+            alloc,
+            on_event,
+        })
+    }
+
+    fn is_subscribed(&self, kind: ScriptEventKind) -> bool {
+        self.subscriptions.is_empty() || self.subscriptions.contains(&kind)
+    }
+
+    /// Run the script for one event and return the mutations it requested
+    fn handle(&mut self, event: &ScriptEvent, limits: &ScriptLimits) -> Result<Vec<EntityMutation>> {
+        let input = serde_json::to_vec(event)?;
+        self.store.set_fuel(limits.fuel_per_event).map_err(script_error)?;
+
+        let memory = self
+            .instance
+            .get_memory(&mut self.store, "memory")
+            .ok_or_else(|| Error::validation("Script does not export memory"))?;
+
+        let ptr = self.alloc.call(&mut self.store, input.len() as i32).map_err(script_error)?;
+        memory.write(&mut self.store, ptr as u32 as usize, &input).map_err(script_error)?;
+
+        let packed = self
+            .on_event
+            .call(&mut self.store, (ptr, input.len() as i32))
+            .map_err(script_error)? as u64;
+        if packed == 0 {
+            return Ok(Vec::new());
+        }
+
+        let out_ptr = (packed >> 32) as usize;
+        let out_len = (packed & 0xffff_ffff) as usize;
+        if out_len > limits.max_output_bytes {
+            return Err(Error::validation(format!(
+                "Script output of {} bytes exceeds the {} byte limit",
+                out_len, limits.max_output_bytes
+            )));
+        }
+
+        let mut output = vec![0u8; out_len];
+        memory.read(&self.store, out_ptr, &mut output).map_err(script_error)?;
+
+        let commands: Vec<EntityMutation> = serde_json::from_slice(&output)?;
+        if commands.len() > limits.max_commands_per_event {
+            return Err(Error::validation(format!(
+                "Script issued {} commands, limit is {}",
+                commands.len(),
+                limits.max_commands_per_event
+            )));
+        }
+        Ok(commands)
+    }
+}
+
+type SharedScript = Arc<Mutex<RoomScript>>;
+
+fn script_error(e: impl std::fmt::Display) -> Error {
+    Error::internal(format!("Script error: {}", e))
+}
+
+/// Loads, runs and unloads room scripts
+#[derive(Clone)]
+pub struct ScriptManager {
+    engine: Engine,
+    scripts: Arc<RwLock<HashMap<String, Vec<SharedScript>>>>, // room_id -> scripts
+    limits: ScriptLimits,
+}
+
+impl ScriptManager {
+    pub fn new() -> Result<Self> {
+        Self::with_limits(ScriptLimits::default())
+    }
+
+    /// Create ScriptManager with custom resource limits
+    pub fn with_limits(limits: ScriptLimits) -> Result<Self> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config).map_err(script_error)?;
+
+        Ok(Self {
+            engine,
+            scripts: Arc::new(RwLock::new(HashMap::new())),
+            limits,
+        })
+    }
+
+    /// Compile and instantiate a script for a room, replacing any script with the same ID
+    pub async fn load(
+        &self,
+        room_id: &str,
+        script_id: &str,
+        wasm: &[u8],
+        subscriptions: Vec<ScriptEventKind>,
+    ) -> Result<ScriptInfo> {
+        let info = ScriptInfo {
+            script_id: script_id.to_string(),
+            room_id: room_id.to_string(),
+            subscriptions: subscriptions.clone(),
+            faults: 0,
+            loaded_at: chrono::Utc::now(),
+        };
+
+        let engine = self.engine.clone();
+        let limits = self.limits.clone();
+        let script_info = info.clone();
+        let wasm = wasm.to_vec();
+        let script = tokio::task::spawn_blocking(move || {
+            RoomScript::instantiate(&engine, &limits, script_info, subscriptions.into_iter().collect(), &wasm)
+        })
+        .await
+        .map_err(script_error)??;
+
+        let mut scripts = self.scripts.write().await;
+        let room_scripts = scripts.entry(room_id.to_string()).or_default();
+        let mut replaced = Vec::with_capacity(room_scripts.len());
+        for existing in room_scripts.drain(..) {
+            if existing.lock().await.info.script_id != script_id {
+                replaced.push(existing);
+            }
+        }
+        replaced.push(Arc::new(Mutex::new(script)));
+        *room_scripts = replaced;
+
+        log::info!("Loaded script {} for room {}", script_id, room_id);
+        Ok(info)
+    }
+
+    /// Unload a script; returns whether it was loaded
+    pub async fn unload(&self, room_id: &str, script_id: &str) -> bool {
+        let mut scripts = self.scripts.write().await;
+        let Some(room_scripts) = scripts.get_mut(room_id) else {
+            return false;
+        };
+
+        let before = room_scripts.len();
+        let mut kept = Vec::with_capacity(before);
+        for script in room_scripts.drain(..) {
+            if script.lock().await.info.script_id != script_id {
+                kept.push(script);
+            }
+        }
+        let removed = kept.len() < before;
+        if kept.is_empty() {
+            scripts.remove(room_id);
+        } else {
+            *room_scripts = kept;
+        }
+        removed
+    }
+
+    /// List scripts loaded for a room
+    pub async fn list(&self, room_id: &str) -> Vec<ScriptInfo> {
+        let scripts = self.room_scripts(room_id).await;
+        let mut infos = Vec::with_capacity(scripts.len());
+        for script in scripts {
+            infos.push(script.lock().await.info.clone());
+        }
+        infos
+    }
+
+    /// Deliver an event to the room's subscribed scripts and apply their mutations.
+    ///
+    /// Mutations are recorded in the room history under `script:{script_id}` and do
+    /// not trigger further script events, so scripts cannot loop on each other.
+    pub async fn dispatch(&self, room_manager: &RoomManager, room_id: &str, event: ScriptEvent) -> Vec<Operation> {
+        let mut operations = Vec::new();
+        let mut faulted = Vec::new();
+
+        for script in self.room_scripts(room_id).await {
+            let (script_id, result) = {
+                let mut script = script.lock_owned().await;
+                if !script.is_subscribed(event.kind()) {
+                    continue;
+                }
+
+                // Guest code runs until its fuel is spent, keep it off the executor
+                let limits = self.limits.clone();
+                let input = event.clone();
+                let (mut script, result) = match tokio::task::spawn_blocking(move || {
+                    let result = script.handle(&input, &limits);
+                    (script, result)
+                })
+                .await
+                {
+                    Ok(done) => done,
+                    Err(e) => {
+                        log::error!("Script task in room {} did not complete: {}", room_id, e);
+                        continue;
+                    }
+                };
+                match &result {
+                    Ok(_) => script.info.faults = 0,
+                    Err(_) => script.info.faults += 1,
+                }
+                if script.info.faults >= MAX_CONSECUTIVE_FAULTS {
+                    faulted.push(script.info.script_id.clone());
+                }
+                (script.info.script_id.clone(), result)
+            };
+
+            let commands = match result {
+                Ok(commands) => commands,
+                Err(e) => {
+                    log::warn!("Script {} in room {} failed: {}", script_id, room_id, e);
+                    continue;
+                }
+            };
+
+            let author = format!("script:{}", script_id);
+            for command in commands {
+                // Scripts may only touch their own room
+                let command = match command {
+                    EntityMutation::Upsert { mut entity } => {
+                        entity.room_id = room_id.to_string();
+                        EntityMutation::Upsert { entity }
+                    }
+                    remove => remove,
+                };
+                match room_manager.apply_mutation(room_id, Some(&author), command).await {
+                    Ok(Some(operation)) => operations.push(operation),
+                    Ok(None) => {}
+                    Err(e) => log::warn!("Failed to apply mutation from script {}: {}", script_id, e),
+                }
+            }
+        }
+
+        for script_id in faulted {
+            log::warn!("Unloading script {} in room {} after repeated faults", script_id, room_id);
+            self.unload(room_id, &script_id).await;
+        }
+
+        operations
+    }
+
+    async fn room_scripts(&self, room_id: &str) -> Vec<SharedScript> {
+        let scripts = self.scripts.read().await;
+        scripts.get(room_id).cloned().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{manager_with, room};
+    use reticulum_core::entity_sync::EntityChange;
+
+    // Always spawns a "marker" entity owned by the script
+    const SPAWN_MARKER: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (data (i32.const 1024) "[{\"type\":\"upsert\",\"entity\":{\"entity_id\":\"marker\",\"room_id\":\"elsewhere\",\"template_id\":\"flag\",\"owner_id\":\"script\",\"position\":{\"x\":0,\"y\":0,\"z\":0},\"rotation\":{\"x\":0,\"y\":0,\"z\":0,\"w\":1},\"components\":{}}}]")
+          (func (export "alloc") (param i32) (result i32) (i32.const 0))
+          (func (export "on_event") (param i32 i32) (result i64)
+            (i64.or (i64.shl (i64.const 1024) (i64.const 32)) (i64.const 198))))
+    "#;
+
+    const INFINITE_LOOP: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (func (export "alloc") (param i32) (result i32) (i32.const 0))
+          (func (export "on_event") (param i32 i32) (result i64)
+            (loop $spin (br $spin))
+            (i64.const 0)))
+    "#;
+
+    #[tokio::test]
+    async fn test_script_mutates_room_state() {
+        let rooms = manager_with([room("game", "author", &[])]).await;
+        let scripts = ScriptManager::new().unwrap();
+        scripts
+            .load("game", "marker", SPAWN_MARKER.as_bytes(), vec![ScriptEventKind::PlayerJoined])
+            .await
+            .unwrap();
+
+        // Unsubscribed events are not delivered
+        let ops = scripts
+            .dispatch(&rooms, "game", ScriptEvent::PlayerLeft { user_id: "alice".to_string() })
+            .await;
+        assert!(ops.is_empty());
+
+        let mut changes = rooms.subscribe_changes();
+        let ops = scripts
+            .dispatch(&rooms, "game", ScriptEvent::PlayerJoined { user_id: "alice".to_string() })
+            .await;
+        assert_eq!(ops.len(), 1);
+        assert_eq!(ops[0].author_id, "script:marker");
+
+        let entities = rooms.get_entities("game").await.unwrap();
+        assert_eq!(entities.len(), 1);
+        assert_eq!(entities[0].room_id, "game");
+
+        // Script changes reach clients like any other
+        let event = changes.try_recv().unwrap();
+        assert_eq!(event.room_id, "game");
+        assert_eq!(event.author_id, "script:marker");
+        assert!(matches!(event.change, EntityChange::Spawn { entity } if entity.entity_id == "marker"));
+    }
+
+    #[tokio::test]
+    async fn test_runaway_script_is_stopped_and_unloaded() {
+        let rooms = manager_with([room("game", "author", &[])]).await;
+        let scripts = ScriptManager::with_limits(ScriptLimits {
+            fuel_per_event: 10_000,
+            ..ScriptLimits::default()
+        })
+        .unwrap();
+        scripts.load("game", "spin", INFINITE_LOOP.as_bytes(), Vec::new()).await.unwrap();
+
+        for _ in 0..MAX_CONSECUTIVE_FAULTS {
+            let ops = scripts
+                .dispatch(&rooms, "game", ScriptEvent::PlayerJoined { user_id: "alice".to_string() })
+                .await;
+            assert!(ops.is_empty());
+        }
+        assert!(scripts.list("game").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_load_rejects_oversized_memory_and_missing_exports() {
+        let scripts = ScriptManager::with_limits(ScriptLimits {
+            max_memory_bytes: 64 * 1024,
+            ..ScriptLimits::default()
+        })
+        .unwrap();
+
+        let greedy = r#"(module (memory (export "memory") 4)
+            (func (export "alloc") (param i32) (result i32) (i32.const 0))
+            (func (export "on_event") (param i32 i32) (result i64) (i64.const 0)))"#;
+        assert!(scripts.load("game", "greedy", greedy.as_bytes(), Vec::new()).await.is_err());
+
+        let missing = r#"(module (memory (export "memory") 1))"#;
+        assert!(scripts.load("game", "missing", missing.as_bytes(), Vec::new()).await.is_err());
+    }
+}
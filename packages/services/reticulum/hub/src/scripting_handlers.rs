@@ -0,0 +1,218 @@
+//! HTTP handlers for managing room scripts
+//!
+//! Loading and unloading scripts is reserved to the room owner and admins,
+//! since scripts act on the room with authority over every entity.
+
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use serde::Deserialize;
+use serde_json::json;
+use validator::Validate;
+
+use reticulum_core::{db, models as core_models, Config, DatabaseConnection, Error, Result};
+
+use crate::auth;
+use crate::room::RoomManager;
+use crate::scripting::{ScriptEventKind, ScriptManager};
+
+/// Request body for loading a script from the storage service
+#[derive(Debug, Deserialize, Validate)]
+pub struct LoadScriptRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub script_id: String,
+    #[validate(length(min = 1))]
+    pub asset_id: String,
+    /// Events the script receives; empty subscribes to all
+    #[serde(default)]
+    pub subscriptions: Vec<ScriptEventKind>,
+}
+
+/// Check that the caller owns the room or instance `room_id`, or is an admin
+async fn authorize_script_change(
+    config: &Config,
+    db: &DatabaseConnection,
+    room_manager: &RoomManager,
+    http: &HttpRequest,
+    room_id: &str,
+) -> Result<()> {
+    let caller = auth::authenticate(config, http)?;
+    let room = room_manager
+        .get_room(room_id, db)
+        .await?
+        .ok_or_else(|| Error::not_found(format!("Room {} not found", room_id)))?;
+    auth::authorize_room_owner(db, &caller, &room).await
+}
+
+/// Load a WASM script uploaded to the storage service into a room
+pub async fn load_script(
+    config: web::Data<Config>,
+    room_manager: web::Data<RoomManager>,
+    script_manager: web::Data<ScriptManager>,
+    room_id: web::Path<String>,
+    http: HttpRequest,
+    req: web::Json<LoadScriptRequest>,
+) -> HttpResponse {
+    if let Err(errors) = req.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "error": "validation_error",
+            "message": errors.to_string()
+        }));
+    }
+
+    // Connect to database
+    let db = match db::connect(&config).await {
+        Ok(db) => db,
+        Err(e) => {
+            log::error!("Database connection failed: {}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "error": "database_error",
+                "message": "Failed to connect to database"
+            }));
+        }
+    };
+
+    let room_id = room_id.into_inner();
+    if let Err(e) = authorize_script_change(&config, &db, &room_manager, &http, &room_id).await {
+        return e.error_response();
+    }
+
+    let asset = match core_models::AssetModel::find_by_asset_id(&db, &req.asset_id).await {
+        Ok(Some(asset)) => asset,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(json!({
+                "error": "not_found",
+                "message": format!("Asset {} not found", req.asset_id)
+            }));
+        }
+        Err(e) => {
+            log::error!("Failed to find asset: {}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "error": "internal_error",
+                "message": "Failed to retrieve asset"
+            }));
+        }
+    };
+
+    if asset.mime_type != "application/wasm" && !asset.file_name.ends_with(".wasm") {
+        return HttpResponse::BadRequest().json(json!({
+            "error": "validation_error",
+            "message": "Asset is not a WASM module"
+        }));
+    }
+
+    // Assets live on the storage volume shared with the storage service
+    let wasm = match tokio::fs::read(&asset.file_path).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            log::error!("Failed to read script asset {}: {}", asset.asset_id, e);
+            return HttpResponse::InternalServerError().json(json!({
+                "error": "internal_error",
+                "message": "Failed to read script asset"
+            }));
+        }
+    };
+
+    match script_manager
+        .load(&room_id, &req.script_id, &wasm, req.subscriptions.clone())
+        .await
+    {
+        Ok(info) => HttpResponse::Created().json(json!({
+            "room_id": room_id,
+            "script": info
+        })),
+        Err(Error::Validation(message)) => HttpResponse::BadRequest().json(json!({
+            "error": "invalid_script",
+            "message": message
+        })),
+        Err(e) => {
+            log::error!("Failed to load script: {}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "error": "internal_error",
+                "message": "Failed to load script"
+            }))
+        }
+    }
+}
+
+/// List scripts loaded in a room
+pub async fn list_scripts(
+    script_manager: web::Data<ScriptManager>,
+    room_id: web::Path<String>,
+) -> HttpResponse {
+    let room_id = room_id.into_inner();
+    let scripts = script_manager.list(&room_id).await;
+
+    HttpResponse::Ok().json(json!({
+        "room_id": room_id,
+        "scripts": scripts
+    }))
+}
+
+/// Unload a script from a room
+pub async fn unload_script(
+    config: web::Data<Config>,
+    room_manager: web::Data<RoomManager>,
+    script_manager: web::Data<ScriptManager>,
+    path: web::Path<(String, String)>,
+    http: HttpRequest,
+) -> HttpResponse {
+    let (room_id, script_id) = path.into_inner();
+
+    // Connect to database
+    let db = match db::connect(&config).await {
+        Ok(db) => db,
+        Err(e) => {
+            log::error!("Database connection failed: {}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "error": "database_error",
+                "message": "Failed to connect to database"
+            }));
+        }
+    };
+
+    if let Err(e) = authorize_script_change(&config, &db, &room_manager, &http, &room_id).await {
+        return e.error_response();
+    }
+
+    if script_manager.unload(&room_id, &script_id).await {
+        HttpResponse::Ok().json(json!({
+            "message": format!("Script {} unloaded", script_id)
+        }))
+    } else {
+        HttpResponse::NotFound().json(json!({
+            "error": "not_found",
+            "message": format!("Script {} not found", script_id)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{manager_with, request_from, room};
+    use actix_web::test::TestRequest;
+
+    #[tokio::test]
+    async fn test_room_creator_may_load_scripts() {
+        let config = Config::load_or_default().unwrap();
+        // Neither caller has a numeric id, so no role is looked up
+        let db = DatabaseConnection::Disconnected;
+        let room_manager = manager_with([room("game", "alice", &[])]).await;
+
+        let creator = request_from(&config, "alice");
+        assert!(authorize_script_change(&config, &db, &room_manager, &creator, "game")
+            .await
+            .is_ok());
+
+        let other = request_from(&config, "bob");
+        assert!(matches!(
+            authorize_script_change(&config, &db, &room_manager, &other, "game").await,
+            Err(Error::Authorization(_))
+        ));
+
+        let anonymous = TestRequest::default().to_http_request();
+        assert!(matches!(
+            authorize_script_change(&config, &db, &room_manager, &anonymous, "game").await,
+            Err(Error::Auth(_))
+        ));
+    }
+}
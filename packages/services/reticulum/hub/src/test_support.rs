@@ -0,0 +1,57 @@
+//! Fixtures shared by the hub's unit tests
+
+use actix_web::test::TestRequest;
+use actix_web::HttpRequest;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use reticulum_core::Config;
+use std::collections::HashMap;
+
+use crate::room::{RoomManager, RoomState};
+
+/// Access token for `user_id`, shaped like the auth service's
+pub fn token(config: &Config, user_id: &str) -> String {
+    let claims = serde_json::json!({
+        "sub": user_id,
+        "email": format!("{}@example.com", user_id),
+        "exp": chrono::Utc::now().timestamp() + 60,
+        "iat": chrono::Utc::now().timestamp(),
+    });
+    let key = EncodingKey::from_secret(config.auth.jwt_secret.as_bytes());
+    encode(&Header::default(), &claims, &key).unwrap()
+}
+
+/// Request sent by `user_id` with a bearer token
+pub fn request_from(config: &Config, user_id: &str) -> HttpRequest {
+    TestRequest::default()
+        .insert_header(("Authorization", format!("Bearer {}", token(config, user_id))))
+        .to_http_request()
+}
+
+/// Primary instance of a room created by `created_by`, with `players` seated
+/// in join order so the first one hosts
+pub fn room(room_id: &str, created_by: &str, players: &[&str]) -> RoomState {
+    RoomState {
+        room_id: room_id.to_string(),
+        name: format!("Room {}", room_id),
+        description: None,
+        max_players: 10,
+        current_players: players.len() as i32,
+        created_by: created_by.to_string(),
+        entities: HashMap::new(),
+        host_client_id: players.first().map(|player| player.to_string()),
+        instance_id: room_id.to_string(),
+        players: players.iter().map(|player| player.to_string()).collect(),
+    }
+}
+
+/// Room manager with `rooms` loaded
+pub async fn manager_with(rooms: impl IntoIterator<Item = RoomState>) -> RoomManager {
+    let manager = RoomManager::new();
+    {
+        let mut loaded = manager.rooms.write().await;
+        for room in rooms {
+            loaded.insert(room.instance_id.clone(), room);
+        }
+    }
+    manager
+}
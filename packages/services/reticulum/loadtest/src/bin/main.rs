@@ -0,0 +1,24 @@
+//! Reticulum Load Test Binary
+//!
+//! Simulated clients for benchmarking the presence service, configured
+//! through `LOADTEST_*` environment variables
+
+use reticulum_loadtest::config::LoadTestConfig;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let config = LoadTestConfig::from_env()?;
+    log::info!(
+        "Running {} clients in {} rooms against {} for {}s",
+        config.clients,
+        config.rooms,
+        config.presence_url,
+        config.duration.as_secs()
+    );
+
+    let report = reticulum_loadtest::run(config).await;
+    println!("{}", report);
+    Ok(())
+}
@@ -0,0 +1,277 @@
+//! One simulated client
+//!
+//! A client connects to its room over WebSocket, says hello and waits for the
+//! server hello, then sends position updates along its path, chat messages and
+//! entity spawns until its time is up. Messages carry the time they were sent
+//! since the run started, so the peers that receive them can time them:
+//! chat text and entity components hold it in microseconds, position
+//! sequence numbers in milliseconds.
+//!
+//! A client counts as a peer of its room from the moment it joins until it
+//! starts leaving, and peers only count messages sent in that time. The chat
+//! history replayed on join thus does not show up as extra deliveries. Before
+//! leaving, a client stops sending and keeps reading for a moment to collect
+//! messages still on their way.
+
+use futures_util::{SinkExt, StreamExt};
+use graphwiz_protocol::generated::graphwiz::core::message::Payload;
+use graphwiz_protocol::{ChatMessageType, Message, MessageBuilder, MessageParser, PositionUpdate};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::time::{interval_at, sleep_until, timeout, MissedTickBehavior};
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+use crate::config::{client_id, room_id, LoadTestConfig};
+use crate::movement::Path;
+use crate::stats::{Kind, Stats};
+
+/// Time a client waits for the server hello
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Time a client keeps reading after it stopped sending
+const DRAIN_PERIOD: Duration = Duration::from_secs(2);
+
+/// Prefix of load test chat messages, followed by the send time
+const CHAT_PREFIX: &str = "loadtest";
+
+/// Entity component holding the send time
+const SENT_AT_COMPONENT: &str = "loadtest_sent_at_us";
+
+/// State shared by all clients of a run
+pub struct Swarm {
+    pub config: LoadTestConfig,
+    pub stats: Stats,
+    started: Instant,
+    /// Clients of each room done with their handshake and not yet leaving
+    occupancy: Vec<Mutex<usize>>,
+}
+
+impl Swarm {
+    pub fn new(config: LoadTestConfig) -> Self {
+        let occupancy = (0..config.rooms).map(|_| Mutex::new(0)).collect();
+        Self {
+            config,
+            stats: Stats::new(),
+            started: Instant::now(),
+            occupancy,
+        }
+    }
+
+    /// Clients in the rooms, all told
+    pub fn connected(&self) -> usize {
+        self.occupancy.iter().map(|room| *room.lock().unwrap()).sum()
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Time since a send time taken from `elapsed`
+    fn since(&self, sent_at: Duration) -> Option<Duration> {
+        self.elapsed().checked_sub(sent_at)
+    }
+
+    /// Send time of a message to `room`, and the number of peers who should
+    /// receive it
+    fn stamp(&self, room: usize) -> (Duration, u64) {
+        let occupancy = self.occupancy[room].lock().unwrap();
+        (self.elapsed(), occupancy.saturating_sub(1) as u64)
+    }
+
+    /// Enter `room`; messages sent from now on are expected to reach us
+    fn join(&self, room: usize) -> Duration {
+        let mut occupancy = self.occupancy[room].lock().unwrap();
+        *occupancy += 1;
+        self.elapsed()
+    }
+
+    /// Start leaving `room`; messages sent from now on are not expected to
+    /// reach us
+    fn leave(&self, room: usize) -> Duration {
+        let mut occupancy = self.occupancy[room].lock().unwrap();
+        *occupancy -= 1;
+        self.elapsed()
+    }
+}
+
+/// Simulate the client with index `index` from connect to leave
+pub async fn run_client(swarm: Arc<Swarm>, index: usize) {
+    if let Err(e) = simulate(&swarm, index).await {
+        log::debug!("Client {} stopped: {}", index, e);
+    }
+}
+
+async fn simulate(swarm: &Arc<Swarm>, index: usize) -> anyhow::Result<()> {
+    let config = &swarm.config;
+    let room = config.room_of(index);
+    let me = client_id(index);
+    let path = config.movement.path(index);
+
+    let connecting = Instant::now();
+    let socket = match tokio_tungstenite::connect_async(config.room_url(index)).await {
+        Ok((socket, _)) => socket,
+        Err(e) => {
+            swarm.stats.connect_failed();
+            return Err(e.into());
+        }
+    };
+    let (mut sink, mut stream) = socket.split();
+
+    swarm.stats.sent(Kind::Handshake, 1);
+    let display_name = format!("Load Test {}", index);
+    let hello = MessageBuilder::client_hello(me.clone(), display_name, String::new(), room_id(room));
+    sink.send(WsMessage::Binary(MessageParser::serialize(&hello)?)).await?;
+
+    let greeted = timeout(HANDSHAKE_TIMEOUT, async {
+        while let Some(frame) = stream.next().await {
+            if let WsMessage::Binary(bytes) = frame? {
+                if matches!(MessageParser::parse(&bytes)?.payload, Some(Payload::ServerHello(_))) {
+                    return anyhow::Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    })
+    .await;
+    if !matches!(greeted, Ok(Ok(true))) {
+        anyhow::bail!("no server hello");
+    }
+    swarm.stats.received(Kind::Handshake, [connecting.elapsed()]);
+
+    let joined_at = swarm.join(room);
+    let left_at = Arc::new(AtomicU64::new(u64::MAX));
+
+    let mut reader = {
+        let swarm = swarm.clone();
+        let me = me.clone();
+        let left_at = left_at.clone();
+        tokio::spawn(async move {
+            while let Some(Ok(frame)) = stream.next().await {
+                if let WsMessage::Binary(bytes) = frame {
+                    if let Ok(message) = MessageParser::parse(&bytes) {
+                        let member = joined_at..Duration::from_micros(left_at.load(Ordering::Relaxed));
+                        record(&swarm, &me, member, message);
+                    }
+                }
+            }
+        })
+    };
+
+    let deadline = tokio::time::Instant::now() + config.duration;
+    let mut positions = ticker(match config.position_rate {
+        0 => None,
+        rate => Some(Duration::from_secs(1) / rate),
+    });
+    let mut chats = ticker(config.chat_interval);
+    let mut spawns = ticker(config.spawn_interval);
+    let mut spawned = 0;
+
+    let left_early = loop {
+        let message = tokio::select! {
+            _ = sleep_until(deadline) => break false,
+            _ = &mut reader => break true,
+            _ = tick(&mut positions) => {
+                let (sent_at, peers) = swarm.stamp(room);
+                swarm.stats.sent(Kind::Position, peers);
+                position(sent_at, &me, &path)
+            }
+            _ = tick(&mut chats) => {
+                let (sent_at, peers) = swarm.stamp(room);
+                swarm.stats.sent(Kind::Chat, peers);
+                let text = format!("{} {}", CHAT_PREFIX, sent_at.as_micros());
+                MessageBuilder::chat_message(me.clone(), text, ChatMessageType::Normal)
+            }
+            _ = tick(&mut spawns) => {
+                let (sent_at, peers) = swarm.stamp(room);
+                swarm.stats.sent(Kind::Entity, peers);
+                spawned += 1;
+                let sent_at = sent_at.as_micros().to_string();
+                let components = HashMap::from([(SENT_AT_COMPONENT.to_string(), sent_at)]);
+                let entity_id = format!("{}-entity-{}", me, spawned);
+                MessageBuilder::entity_spawn(entity_id, "loadtest-cube".to_string(), me.clone(), components)
+            }
+        };
+        if sink.send(WsMessage::Binary(MessageParser::serialize(&message)?)).await.is_err() {
+            break true;
+        }
+    };
+
+    // Peers stop expecting our deliveries before we stop reading theirs
+    left_at.store(swarm.leave(room).as_micros() as u64, Ordering::Relaxed);
+    if left_early {
+        swarm.stats.disconnected();
+    } else {
+        let _ = timeout(DRAIN_PERIOD, &mut reader).await;
+        let _ = sink.close().await;
+    }
+    reader.abort();
+    Ok(())
+}
+
+/// A position update stamped with its send time in milliseconds
+fn position(sent_at: Duration, me: &str, path: &Path) -> Message {
+    let t = sent_at.as_secs_f32();
+    let mut message = MessageBuilder::position_update(me.to_string(), path.position(t), path.rotation(t));
+    if let Some(Payload::PositionUpdate(update)) = message.payload.as_mut() {
+        update.sequence_number = sent_at.as_millis() as u32;
+    }
+    message
+}
+
+/// Time and count a message from the server, received while we were a
+/// `member` of the room
+fn record(swarm: &Swarm, me: &str, member: std::ops::Range<Duration>, message: Message) {
+    // Anything sent before we joined is history, not a delivery to us
+    let delivery = |sent_at: Duration| member.contains(&sent_at).then(|| swarm.since(sent_at)).flatten();
+
+    match message.payload {
+        Some(Payload::PositionBatch(batch)) => {
+            let latencies = batch
+                .updates
+                .iter()
+                .filter(|update| update.entity_id != me)
+                .filter_map(|update: &PositionUpdate| {
+                    delivery(Duration::from_millis(update.sequence_number as u64))
+                });
+            swarm.stats.received(Kind::Position, latencies.collect::<Vec<_>>());
+        }
+        Some(Payload::ChatMessage(chat)) if chat.from_client_id != me => {
+            let sent_at = chat
+                .message
+                .strip_prefix(CHAT_PREFIX)
+                .and_then(|micros| micros.trim().parse().ok())
+                .map(Duration::from_micros);
+            swarm.stats.received(Kind::Chat, sent_at.and_then(delivery));
+        }
+        Some(Payload::EntitySpawn(spawn)) if spawn.owner_id != me => {
+            let sent_at = spawn
+                .components
+                .get(SENT_AT_COMPONENT)
+                .and_then(|micros| micros.parse().ok())
+                .map(Duration::from_micros);
+            swarm.stats.received(Kind::Entity, sent_at.and_then(delivery));
+        }
+        Some(Payload::ErrorFrame(error)) => swarm.stats.error_frame(&error.code),
+        _ => {}
+    }
+}
+
+/// Interval firing every `period` from one period on, or never
+fn ticker(period: Option<Duration>) -> Option<tokio::time::Interval> {
+    period.map(|period| {
+        let mut ticker = interval_at(tokio::time::Instant::now() + period, period);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        ticker
+    })
+}
+
+async fn tick(ticker: &mut Option<tokio::time::Interval>) {
+    match ticker {
+        Some(ticker) => {
+            ticker.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
@@ -0,0 +1,113 @@
+//! Load test settings
+
+use std::time::Duration;
+
+use crate::movement::Movement;
+
+/// Presence instance the clients connect to
+pub const DEFAULT_PRESENCE_URL: &str = "ws://localhost:8003";
+
+#[derive(Debug, Clone)]
+pub struct LoadTestConfig {
+    /// Base WebSocket URL of presence, without the `/ws/{room_id}` path
+    pub presence_url: String,
+    pub clients: usize,
+    /// Clients are spread evenly over this many rooms
+    pub rooms: usize,
+    /// Clients connect one after another over this period
+    pub ramp_up: Duration,
+    /// How long every client stays connected once it is in
+    pub duration: Duration,
+    /// Position updates per second from each client; 0 sends none
+    pub position_rate: u32,
+    /// Time between chat messages from each client
+    pub chat_interval: Option<Duration>,
+    /// Time between entity spawns from each client
+    pub spawn_interval: Option<Duration>,
+    pub movement: Movement,
+}
+
+impl Default for LoadTestConfig {
+    fn default() -> Self {
+        Self {
+            presence_url: DEFAULT_PRESENCE_URL.to_string(),
+            clients: 100,
+            rooms: 10,
+            ramp_up: Duration::from_secs(10),
+            duration: Duration::from_secs(60),
+            position_rate: 10,
+            chat_interval: Some(Duration::from_secs(10)),
+            spawn_interval: Some(Duration::from_secs(30)),
+            movement: Movement::Mixed,
+        }
+    }
+}
+
+impl LoadTestConfig {
+    /// Defaults overridden by `LOADTEST_*` variables.
+    ///
+    /// Intervals of 0 seconds turn chat or entity spawns off.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let number = |name: &str| -> anyhow::Result<Option<u64>> {
+            var(name)
+                .map(|value| {
+                    value.parse().map_err(|_| anyhow::anyhow!("{} must be a number, got {:?}", name, value))
+                })
+                .transpose()
+        };
+        let interval = |name: &str, default: Option<Duration>| -> anyhow::Result<Option<Duration>> {
+            Ok(match number(name)? {
+                Some(0) => None,
+                Some(secs) => Some(Duration::from_secs(secs)),
+                None => default,
+            })
+        };
+
+        let defaults = Self::default();
+        let config = Self {
+            presence_url: var("LOADTEST_PRESENCE_URL").unwrap_or(defaults.presence_url),
+            clients: number("LOADTEST_CLIENTS")?.map_or(defaults.clients, |n| n as usize),
+            rooms: number("LOADTEST_ROOMS")?.map_or(defaults.rooms, |n| n as usize),
+            ramp_up: number("LOADTEST_RAMP_UP_SECS")?.map_or(defaults.ramp_up, Duration::from_secs),
+            duration: number("LOADTEST_DURATION_SECS")?.map_or(defaults.duration, Duration::from_secs),
+            position_rate: number("LOADTEST_POSITION_HZ")?.map_or(defaults.position_rate, |n| n as u32),
+            chat_interval: interval("LOADTEST_CHAT_INTERVAL_SECS", defaults.chat_interval)?,
+            spawn_interval: interval("LOADTEST_SPAWN_INTERVAL_SECS", defaults.spawn_interval)?,
+            movement: match var("LOADTEST_MOVEMENT") {
+                Some(value) => value.parse()?,
+                None => defaults.movement,
+            },
+        };
+
+        if config.clients == 0 || config.rooms == 0 {
+            anyhow::bail!("LOADTEST_CLIENTS and LOADTEST_ROOMS must be at least 1");
+        }
+        Ok(config)
+    }
+
+    /// Room of the client with index `client`
+    pub fn room_of(&self, client: usize) -> usize {
+        client % self.rooms
+    }
+
+    /// WebSocket URL of a room for the client with index `client`
+    pub fn room_url(&self, client: usize) -> String {
+        format!(
+            "{}/ws/{}?client_id={}",
+            self.presence_url.trim_end_matches('/'),
+            room_id(self.room_of(client)),
+            client_id(client)
+        )
+    }
+}
+
+/// Id of the load test room with index `room`
+pub fn room_id(room: usize) -> String {
+    format!("loadtest-room-{}", room)
+}
+
+/// Client id of the simulated client with index `client`
+pub fn client_id(client: usize) -> String {
+    format!("loadtest-client-{}", client)
+}
@@ -0,0 +1,51 @@
+//! Load test client for the presence service
+//!
+//! Spawns simulated clients that speak the protobuf protocol over WebSocket,
+//! and reports latency percentiles and dropped messages. Entity spawns go
+//! through the hub when presence is connected to it over Redis.
+
+pub mod client;
+pub mod config;
+pub mod movement;
+pub mod stats;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use client::{run_client, Swarm};
+use config::LoadTestConfig;
+use stats::Report;
+
+/// Time between progress lines
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Connect every client over the ramp-up period and report once all have left
+pub async fn run(config: LoadTestConfig) -> Report {
+    let swarm = Arc::new(Swarm::new(config));
+    let config = &swarm.config;
+
+    let progress = {
+        let swarm = swarm.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(PROGRESS_INTERVAL);
+            loop {
+                ticker.tick().await;
+                log::info!("{} clients connected", swarm.connected());
+            }
+        })
+    };
+
+    let gap = config.ramp_up / config.clients as u32;
+    let mut ramp = tokio::time::interval(gap.max(Duration::from_micros(1)));
+    let mut clients = Vec::with_capacity(config.clients);
+    for index in 0..config.clients {
+        ramp.tick().await;
+        clients.push(tokio::spawn(run_client(swarm.clone(), index)));
+    }
+    for client in clients {
+        let _ = client.await;
+    }
+
+    progress.abort();
+    swarm.stats.report()
+}
@@ -0,0 +1,157 @@
+//! Movement paths of simulated clients
+//!
+//! Paths are functions of time, so a client needs no state to know where it
+//! is and every run moves the same way.
+
+use graphwiz_protocol::{Quaternion, Vector3};
+use std::f32::consts::TAU;
+use std::str::FromStr;
+
+/// Spacing of the grid the clients of a room start on, in meters
+const GRID_SPACING: f32 = 4.0;
+const GRID_WIDTH: usize = 10;
+
+/// Walking speed, in meters per second
+const WALK_SPEED: f32 = 1.4;
+
+/// How clients move around their room
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Movement {
+    /// Stand still
+    Idle,
+    /// Walk in circles around the starting point
+    Circle,
+    /// Walk back and forth along a line
+    Line,
+    /// Stroll along a smooth, irregular path
+    Wander,
+    /// Each client picks one of the above
+    Mixed,
+}
+
+impl FromStr for Movement {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> anyhow::Result<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "idle" => Ok(Self::Idle),
+            "circle" => Ok(Self::Circle),
+            "line" => Ok(Self::Line),
+            "wander" => Ok(Self::Wander),
+            "mixed" => Ok(Self::Mixed),
+            _ => anyhow::bail!("Unknown movement {:?}, expected idle, circle, line, wander or mixed", value),
+        }
+    }
+}
+
+impl Movement {
+    /// Path of the client with index `client`
+    pub fn path(self, client: usize) -> Path {
+        let movement = match self {
+            Self::Mixed => [Self::Idle, Self::Circle, Self::Line, Self::Wander][client % 4],
+            movement => movement,
+        };
+        let slot = client / 4;
+        Path {
+            movement,
+            origin: [
+                (slot % GRID_WIDTH) as f32 * GRID_SPACING,
+                0.0,
+                (slot / GRID_WIDTH % GRID_WIDTH) as f32 * GRID_SPACING,
+            ],
+            // Spread clients out along their paths
+            phase: client as f32 * 0.618_034 % 1.0 * TAU,
+        }
+    }
+}
+
+/// Where one client is at any time
+#[derive(Debug, Clone, Copy)]
+pub struct Path {
+    movement: Movement,
+    origin: [f32; 3],
+    phase: f32,
+}
+
+impl Path {
+    /// Position `t` seconds into the run
+    pub fn position(&self, t: f32) -> Vector3 {
+        let [x, y, z] = self.origin;
+        let (dx, dz) = match self.movement {
+            Movement::Idle | Movement::Mixed => (0.0, 0.0),
+            Movement::Circle => {
+                let radius = GRID_SPACING / 2.0;
+                let angle = self.phase + t * WALK_SPEED / radius;
+                (radius * angle.cos(), radius * angle.sin())
+            }
+            Movement::Line => {
+                let length = GRID_SPACING * 2.0;
+                let s = (self.phase / TAU * 2.0 * length + t * WALK_SPEED) % (2.0 * length);
+                (if s < length { s } else { 2.0 * length - s }, 0.0)
+            }
+            Movement::Wander => {
+                // Slowed down so the sum of the waves stays near walking speed
+                let radius = GRID_SPACING / 2.0;
+                let t = t * WALK_SPEED / (radius * 3.0);
+                (
+                    radius * ((t + self.phase).sin() + 0.5 * (2.3 * t + 2.0 * self.phase).sin()),
+                    radius * ((1.3 * t + self.phase).cos() + 0.5 * (2.9 * t).sin()),
+                )
+            }
+        };
+        Vector3 { x: x + dx, y, z: z + dz }
+    }
+
+    /// Heading `t` seconds into the run, facing the direction of travel
+    pub fn rotation(&self, t: f32) -> Quaternion {
+        let from = self.position(t);
+        let to = self.position(t + 0.1);
+        let (dx, dz) = (to.x - from.x, to.z - from.z);
+        if dx.abs() < f32::EPSILON && dz.abs() < f32::EPSILON {
+            return Quaternion { x: 0.0, y: 0.0, z: 0.0, w: 1.0 };
+        }
+        let yaw = dx.atan2(dz);
+        Quaternion { x: 0.0, y: (yaw / 2.0).sin(), z: 0.0, w: (yaw / 2.0).cos() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distance(a: &Vector3, b: &Vector3) -> f32 {
+        ((a.x - b.x).powi(2) + (a.y - b.y).powi(2) + (a.z - b.z).powi(2)).sqrt()
+    }
+
+    #[test]
+    fn test_parse_movement() {
+        assert_eq!("Circle".parse::<Movement>().unwrap(), Movement::Circle);
+        assert!("teleport".parse::<Movement>().is_err());
+    }
+
+    #[test]
+    fn test_paths_move_at_walking_speed() {
+        for movement in [Movement::Circle, Movement::Line, Movement::Wander] {
+            let path = movement.path(7);
+            for step in 0..100 {
+                let t = step as f32 * 0.1;
+                let moved = distance(&path.position(t), &path.position(t + 0.1));
+                assert!(moved < WALK_SPEED * 0.1 * 3.0, "{:?} jumped {} m", movement, moved);
+            }
+        }
+    }
+
+    #[test]
+    fn test_idle_clients_stand_still() {
+        let path = Movement::Idle.path(3);
+        assert_eq!(distance(&path.position(0.0), &path.position(30.0)), 0.0);
+        assert_eq!(path.rotation(5.0).w, 1.0);
+    }
+
+    #[test]
+    fn test_mixed_clients_take_different_paths() {
+        let paths: Vec<Path> = (0..4).map(|client| Movement::Mixed.path(client)).collect();
+        let movements: Vec<Movement> = paths.iter().map(|path| path.movement).collect();
+        assert_eq!(movements, [Movement::Idle, Movement::Circle, Movement::Line, Movement::Wander]);
+    }
+}
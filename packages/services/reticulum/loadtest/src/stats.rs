@@ -0,0 +1,251 @@
+//! Latency and delivery figures of a run
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// What was measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Kind {
+    /// Connection opened until the server hello arrived
+    Handshake,
+    /// Position update sent until a peer got it in a position batch
+    Position,
+    /// Chat message sent until a peer got it
+    Chat,
+    /// Entity spawn sent until a peer got it
+    Entity,
+}
+
+impl Kind {
+    pub const ALL: [Kind; 4] = [Kind::Handshake, Kind::Position, Kind::Chat, Kind::Entity];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Kind::Handshake => "handshake",
+            Kind::Position => "position",
+            Kind::Chat => "chat",
+            Kind::Entity => "entity",
+        }
+    }
+
+    /// Whether every expected delivery should arrive. Position updates are
+    /// coalesced per tick, so only the latest of a tick reaches peers.
+    pub fn reliable(self) -> bool {
+        self != Kind::Position
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    sent: u64,
+    expected: u64,
+    received: u64,
+    latencies: Vec<Duration>,
+}
+
+#[derive(Debug, Default)]
+struct Totals {
+    counters: BTreeMap<Kind, Counters>,
+    connect_failures: u64,
+    disconnects: u64,
+    error_frames: BTreeMap<String, u64>,
+}
+
+/// Figures collected by all clients of a run
+#[derive(Debug, Default)]
+pub struct Stats {
+    totals: Mutex<Totals>,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A message was sent that `deliveries` clients should receive
+    pub fn sent(&self, kind: Kind, deliveries: u64) {
+        let mut totals = self.totals.lock().unwrap();
+        let counters = totals.counters.entry(kind).or_default();
+        counters.sent += 1;
+        counters.expected += deliveries;
+    }
+
+    /// Messages of `kind` arrived after the given latencies
+    pub fn received(&self, kind: Kind, latencies: impl IntoIterator<Item = Duration>) {
+        let mut totals = self.totals.lock().unwrap();
+        let counters = totals.counters.entry(kind).or_default();
+        for latency in latencies {
+            counters.received += 1;
+            counters.latencies.push(latency);
+        }
+    }
+
+    pub fn connect_failed(&self) {
+        self.totals.lock().unwrap().connect_failures += 1;
+    }
+
+    /// The server closed a connection before the client was done
+    pub fn disconnected(&self) {
+        self.totals.lock().unwrap().disconnects += 1;
+    }
+
+    pub fn error_frame(&self, code: &str) {
+        *self.totals.lock().unwrap().error_frames.entry(code.to_string()).or_default() += 1;
+    }
+
+    /// Summary of everything recorded so far
+    pub fn report(&self) -> Report {
+        let mut totals = self.totals.lock().unwrap();
+        let kinds = Kind::ALL
+            .iter()
+            .map(|&kind| {
+                let counters = totals.counters.entry(kind).or_default();
+                counters.latencies.sort_unstable();
+                let latencies = &counters.latencies;
+                KindReport {
+                    kind,
+                    sent: counters.sent,
+                    expected: counters.expected,
+                    received: counters.received,
+                    dropped: kind.reliable().then(|| counters.expected.saturating_sub(counters.received)),
+                    p50: percentile(latencies, 50.0),
+                    p90: percentile(latencies, 90.0),
+                    p99: percentile(latencies, 99.0),
+                    max: latencies.last().copied(),
+                }
+            })
+            .collect();
+
+        Report {
+            kinds,
+            connect_failures: totals.connect_failures,
+            disconnects: totals.disconnects,
+            error_frames: totals.error_frames.clone(),
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted samples
+pub fn percentile(sorted: &[Duration], p: f64) -> Option<Duration> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+#[derive(Debug, Clone)]
+pub struct KindReport {
+    pub kind: Kind,
+    pub sent: u64,
+    /// Deliveries to peers the sent messages should have made
+    pub expected: u64,
+    pub received: u64,
+    /// Expected deliveries that never arrived; `None` for lossy kinds
+    pub dropped: Option<u64>,
+    pub p50: Option<Duration>,
+    pub p90: Option<Duration>,
+    pub p99: Option<Duration>,
+    pub max: Option<Duration>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub kinds: Vec<KindReport>,
+    pub connect_failures: u64,
+    pub disconnects: u64,
+    /// Error frames received, by code
+    pub error_frames: BTreeMap<String, u64>,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |latency: Option<Duration>| match latency {
+            Some(latency) => format!("{:.1}", latency.as_secs_f64() * 1000.0),
+            None => "-".to_string(),
+        };
+
+        writeln!(
+            f,
+            "{:<10} {:>10} {:>12} {:>12} {:>10} {:>9} {:>9} {:>9} {:>9}",
+            "", "sent", "expected", "received", "dropped", "p50 ms", "p90 ms", "p99 ms", "max ms"
+        )?;
+        for report in &self.kinds {
+            writeln!(
+                f,
+                "{:<10} {:>10} {:>12} {:>12} {:>10} {:>9} {:>9} {:>9} {:>9}",
+                report.kind.name(),
+                report.sent,
+                report.expected,
+                report.received,
+                report.dropped.map_or("-".to_string(), |dropped| dropped.to_string()),
+                ms(report.p50),
+                ms(report.p90),
+                ms(report.p99),
+                ms(report.max),
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "connect failures: {}", self.connect_failures)?;
+        writeln!(f, "disconnects: {}", self.disconnects)?;
+        for (code, count) in &self.error_frames {
+            writeln!(f, "error frames {}: {}", code, count)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_percentile() {
+        let samples: Vec<Duration> = (1..=100).map(ms).collect();
+        assert_eq!(percentile(&samples, 50.0), Some(ms(50)));
+        assert_eq!(percentile(&samples, 99.0), Some(ms(99)));
+        assert_eq!(percentile(&samples, 100.0), Some(ms(100)));
+        assert_eq!(percentile(&[ms(7)], 0.0), Some(ms(7)));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn test_drops_are_counted_for_reliable_kinds_only() {
+        let stats = Stats::new();
+        stats.sent(Kind::Chat, 3);
+        stats.sent(Kind::Chat, 3);
+        stats.received(Kind::Chat, [ms(30), ms(10), ms(20), ms(40)]);
+        stats.sent(Kind::Position, 3);
+        stats.received(Kind::Position, [ms(5)]);
+
+        let report = stats.report();
+        let chat = &report.kinds[Kind::Chat as usize];
+        assert_eq!((chat.sent, chat.expected, chat.received), (2, 6, 4));
+        assert_eq!(chat.dropped, Some(2));
+        assert_eq!(chat.p50, Some(ms(20)));
+        assert_eq!(chat.max, Some(ms(40)));
+        assert_eq!(report.kinds[Kind::Position as usize].dropped, None);
+        assert_eq!(report.kinds[Kind::Entity as usize].p50, None);
+    }
+
+    #[test]
+    fn test_report_lists_error_frames() {
+        let stats = Stats::new();
+        stats.error_frame("RATE_LIMITED");
+        stats.error_frame("RATE_LIMITED");
+        stats.connect_failed();
+
+        let report = stats.report();
+        assert_eq!(report.error_frames["RATE_LIMITED"], 2);
+        let text = report.to_string();
+        assert!(text.contains("error frames RATE_LIMITED: 2"));
+        assert!(text.contains("connect failures: 1"));
+    }
+}
@@ -0,0 +1,198 @@
+//! Admin endpoints for operating a presence instance
+//!
+//! Every endpoint takes a bearer token with the admin role, like the
+//! moderation audit log. Session inspection covers the connections held by
+//! this instance; rooms spread over a cluster are inspected per instance.
+
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use reticulum_core::Error;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::chat::ChatService;
+use crate::drain::{DrainController, DrainRequest};
+use crate::moderation::{disconnect_connection, ModerationManager};
+use crate::webtransport::WebTransportManager;
+use crate::websocket::{SessionDetails, Transport, WebSocketManager};
+
+/// Force a session off the instance
+#[derive(Debug, Default, Deserialize)]
+pub struct DisconnectRequest {
+    /// Told to the client in the error frame sent before closing
+    pub reason: Option<String>,
+}
+
+/// System message for a room
+#[derive(Debug, Deserialize)]
+pub struct SystemMessageRequest {
+    pub message: String,
+}
+
+/// Start draining this instance: new connections are refused, clients are
+/// redirected to other instances and the instance shuts down once they left
+pub async fn start_drain(
+    req: HttpRequest,
+    moderation: web::Data<ModerationManager>,
+    ws_manager: web::Data<WebSocketManager>,
+    drain: web::Data<DrainController>,
+    body: Option<web::Json<DrainRequest>>,
+) -> HttpResponse {
+    let admin = match moderation.require_admin(&req).await {
+        Ok(admin) => admin,
+        Err(e) => return e.error_response(),
+    };
+
+    let request = body.map(web::Json::into_inner).unwrap_or_default();
+    match drain.drain(&ws_manager, request).await {
+        Ok(status) => {
+            log::info!("Drain of instance {} started by {}", status.instance_id, admin.user_id);
+            HttpResponse::Accepted().json(status)
+        }
+        Err(e) => e.error_response(),
+    }
+}
+
+/// Progress of this instance's drain
+pub async fn get_drain_status(
+    req: HttpRequest,
+    moderation: web::Data<ModerationManager>,
+    ws_manager: web::Data<WebSocketManager>,
+    drain: web::Data<DrainController>,
+) -> HttpResponse {
+    if let Err(e) = moderation.require_admin(&req).await {
+        return e.error_response();
+    }
+
+    HttpResponse::Ok().json(drain.status(&ws_manager).await)
+}
+
+/// Live state of a room's sessions on this instance
+pub async fn get_room_sessions(
+    req: HttpRequest,
+    moderation: web::Data<ModerationManager>,
+    ws_manager: web::Data<WebSocketManager>,
+    webtransport: web::Data<WebTransportManager>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    if let Err(e) = moderation.require_admin(&req).await {
+        return e.error_response();
+    }
+
+    let room_id = path.into_inner();
+    let sessions = with_transport_rtt(&webtransport, ws_manager.room_sessions(&room_id).await).await;
+    HttpResponse::Ok().json(json!({
+        "room_id": room_id,
+        "total": sessions.len(),
+        "sessions": sessions
+    }))
+}
+
+/// Live state of a user's sessions on this instance, in any room
+pub async fn get_user_sessions(
+    req: HttpRequest,
+    moderation: web::Data<ModerationManager>,
+    ws_manager: web::Data<WebSocketManager>,
+    webtransport: web::Data<WebTransportManager>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    if let Err(e) = moderation.require_admin(&req).await {
+        return e.error_response();
+    }
+
+    let user_id = path.into_inner();
+    let sessions = with_transport_rtt(&webtransport, ws_manager.user_sessions(&user_id).await).await;
+    HttpResponse::Ok().json(json!({
+        "user_id": user_id,
+        "total": sessions.len(),
+        "sessions": sessions
+    }))
+}
+
+/// Disconnect a session. The room sees it leave; the client may rejoin
+/// unless it is also banned.
+pub async fn disconnect_session(
+    req: HttpRequest,
+    moderation: web::Data<ModerationManager>,
+    ws_manager: web::Data<WebSocketManager>,
+    path: web::Path<String>,
+    body: Option<web::Json<DisconnectRequest>>,
+) -> HttpResponse {
+    let admin = match moderation.require_admin(&req).await {
+        Ok(admin) => admin,
+        Err(e) => return e.error_response(),
+    };
+
+    let conn_id = path.into_inner();
+    let reason = body
+        .map(web::Json::into_inner)
+        .unwrap_or_default()
+        .reason
+        .unwrap_or_else(|| "Disconnected by an administrator".to_string());
+    if !disconnect_connection(&ws_manager, &conn_id, "DISCONNECTED", reason).await {
+        return Error::not_found(format!("Session {} not found on this instance", conn_id)).error_response();
+    }
+
+    log::info!("Session {} disconnected by {}", conn_id, admin.user_id);
+    HttpResponse::NoContent().finish()
+}
+
+/// Post a system message to a room
+pub async fn post_room_message(
+    req: HttpRequest,
+    moderation: web::Data<ModerationManager>,
+    ws_manager: web::Data<WebSocketManager>,
+    chat: web::Data<ChatService>,
+    path: web::Path<String>,
+    body: web::Json<SystemMessageRequest>,
+) -> HttpResponse {
+    let admin = match moderation.require_admin(&req).await {
+        Ok(admin) => admin,
+        Err(e) => return e.error_response(),
+    };
+
+    let room_id = path.into_inner();
+    match chat.announce(&ws_manager, &room_id, &body.message, &admin.user_id).await {
+        Ok(record) => HttpResponse::Created().json(record),
+        Err(e) => e.error_response(),
+    }
+}
+
+/// Live presence of a room on this instance: its host, settings and every
+/// session with its last reported position
+pub async fn get_room_snapshot(
+    req: HttpRequest,
+    moderation: web::Data<ModerationManager>,
+    ws_manager: web::Data<WebSocketManager>,
+    webtransport: web::Data<WebTransportManager>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    if let Err(e) = moderation.require_admin(&req).await {
+        return e.error_response();
+    }
+
+    let room_id = path.into_inner();
+    let sessions = with_transport_rtt(&webtransport, ws_manager.room_sessions(&room_id).await).await;
+    if sessions.is_empty() {
+        return Error::not_found(format!("Room {} has no sessions on this instance", room_id)).error_response();
+    }
+
+    HttpResponse::Ok().json(json!({
+        "room_id": room_id,
+        "taken_at": chrono::Utc::now().to_rfc3339(),
+        "host": ws_manager.get_room_host(&room_id).await,
+        "tick_rate_hz": ws_manager.room_tick_rate(&room_id).await,
+        "spatial_audio": ws_manager.spatial_config(&room_id).await,
+        "total": sessions.len(),
+        "sessions": sessions
+    }))
+}
+
+/// WebTransport sessions are not pinged; their round trip comes from QUIC
+async fn with_transport_rtt(webtransport: &WebTransportManager, mut sessions: Vec<SessionDetails>) -> Vec<SessionDetails> {
+    for session in sessions.iter_mut().filter(|session| session.transport == Transport::WebTransport) {
+        if let Some(rtt) = webtransport.rtt(&session.conn_id).await {
+            session.rtt_ms = Some(rtt.as_millis() as u64);
+        }
+    }
+    sessions
+}
@@ -47,6 +47,6 @@ async fn main() -> std::io::Result<()> {
     log::info!("  Workers: {}", config.server.workers.unwrap_or(1));
 
     // Create and run the presence service
-    let service = PresenceService::new(config);
+    let service = PresenceService::new(config).await;
     service.run().await
 }
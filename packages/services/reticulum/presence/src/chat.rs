@@ -0,0 +1,762 @@
+//! Room chat
+//!
+//! Chat messages from clients pass through the content filters, get an id and
+//! are stored in the room history before delivery. Normal and shout messages
+//! go to the whole room, whispers only to their recipient and team messages
+//! only to connections on the sender's team. A joining connection is sent the
+//! recent history it may see, and moderators can delete messages, which the
+//! room is told about so clients can hide them.
+//!
+//! Client ids are picked by the clients, so private chat never relies on them.
+//! Whispers go between token-verified users, and teams are assigned to
+//! verified users by the room's moderators.
+//!
+//! History lives in the database when one is configured and in memory
+//! otherwise. Whispers and team messages reach connections on this instance
+//! only; public messages fan out to the cluster like any room broadcast.
+
+use crate::protobuf::{Flow, MessageContext, MessageHandler, Route};
+use crate::websocket::{WebSocketManager, WsMessage};
+use async_trait::async_trait;
+use graphwiz_protocol::generated::graphwiz::core::message::Payload;
+use graphwiz_protocol::generated::graphwiz::core::{ChatMessage, ChatMessageType};
+use graphwiz_protocol::{Message, MessageBuilder, MessageParser, MessageType};
+use reticulum_core::models::{ChatKind, ChatRecord, ChatRecordModel};
+use reticulum_core::{DatabaseConnection, Error, Result};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Messages sent to a connection when it joins
+pub const DEFAULT_REPLAY_LEN: u64 = 50;
+
+/// Largest page of history returned at once
+pub const MAX_HISTORY_PAGE: u64 = 100;
+
+/// Messages kept per room when there is no database
+const MEMORY_HISTORY_LEN: usize = 500;
+
+/// Sender client id of messages posted by the service itself
+pub const SYSTEM_CLIENT_ID: &str = "system";
+
+/// Outcome of checking a chat message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterVerdict {
+    Allow,
+    /// Deliver this text instead
+    Replace(String),
+    /// Drop the message, telling the sender why
+    Reject(String),
+}
+
+/// Check on the text of chat messages before they are stored or delivered
+pub trait ContentFilter: Send + Sync {
+    fn check(&self, text: &str) -> FilterVerdict;
+}
+
+/// Masks blocked words with asterisks
+pub struct WordListFilter {
+    words: Vec<String>, // lowercase
+}
+
+impl WordListFilter {
+    pub fn new<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            words: words
+                .into_iter()
+                .map(|word| word.as_ref().trim().to_lowercase())
+                .filter(|word| !word.is_empty())
+                .collect(),
+        }
+    }
+}
+
+impl ContentFilter for WordListFilter {
+    fn check(&self, text: &str) -> FilterVerdict {
+        let mut masked = String::with_capacity(text.len());
+        let mut changed = false;
+        let mut word = String::new();
+
+        // Words are runs of alphanumerics; everything between them is kept
+        for c in text.chars().chain(std::iter::once('\0')) {
+            if c.is_alphanumeric() {
+                word.push(c);
+                continue;
+            }
+            if self.words.contains(&word.to_lowercase()) {
+                masked.push_str(&"*".repeat(word.chars().count()));
+                changed = true;
+            } else {
+                masked.push_str(&word);
+            }
+            word.clear();
+            if c != '\0' {
+                masked.push(c);
+            }
+        }
+
+        if changed {
+            FilterVerdict::Replace(masked)
+        } else {
+            FilterVerdict::Allow
+        }
+    }
+}
+
+/// Rejects messages containing links
+pub struct LinkFilter;
+
+impl ContentFilter for LinkFilter {
+    fn check(&self, text: &str) -> FilterVerdict {
+        let has_link = text.split_whitespace().any(|token| {
+            let token = token.to_lowercase();
+            token.contains("://") || token.starts_with("www.")
+        });
+        if has_link {
+            FilterVerdict::Reject("Links are not allowed in chat".to_string())
+        } else {
+            FilterVerdict::Allow
+        }
+    }
+}
+
+/// Filters, stores and routes room chat
+#[derive(Clone)]
+pub struct ChatService {
+    db: Option<DatabaseConnection>,
+    filters: Vec<Arc<dyn ContentFilter>>,
+    memory: Arc<RwLock<HashMap<String, VecDeque<ChatRecord>>>>, // room_id -> history without a database
+    next_id: Arc<AtomicI32>, // row ids for in-memory history
+    teams: Arc<RwLock<HashMap<String, HashMap<String, String>>>>, // room_id -> user_id -> team
+    replay_len: u64,
+}
+
+impl ChatService {
+    /// Chat without content filters; history is kept in memory when `db` is
+    /// `None`
+    pub fn new(db: Option<DatabaseConnection>) -> Self {
+        Self {
+            db,
+            filters: Vec::new(),
+            memory: Arc::new(RwLock::new(HashMap::new())),
+            next_id: Arc::new(AtomicI32::new(1)),
+            teams: Arc::new(RwLock::new(HashMap::new())),
+            replay_len: DEFAULT_REPLAY_LEN,
+        }
+    }
+
+    /// Chat with the filters configured by `CHAT_BLOCKED_WORDS`, a comma
+    /// separated word list, and `CHAT_BLOCK_LINKS`
+    pub fn from_env(db: Option<DatabaseConnection>) -> Self {
+        let mut chat = Self::new(db);
+
+        if let Ok(words) = std::env::var("CHAT_BLOCKED_WORDS") {
+            let filter = WordListFilter::new(words.split(','));
+            if !filter.words.is_empty() {
+                chat = chat.with_filter(Arc::new(filter));
+            }
+        }
+        let block_links = std::env::var("CHAT_BLOCK_LINKS")
+            .map(|value| matches!(value.to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        if block_links {
+            chat = chat.with_filter(Arc::new(LinkFilter));
+        }
+
+        chat
+    }
+
+    /// Run `filter` on every message, after the filters already added
+    pub fn with_filter(mut self, filter: Arc<dyn ContentFilter>) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Handle `ws_manager`'s chat messages and replay history on join
+    pub async fn start(&self, ws_manager: &WebSocketManager) {
+        ws_manager
+            .register_handler(MessageType::ChatMessage, Arc::new(self.clone()))
+            .await;
+        ws_manager.attach_chat(self.clone()).await;
+    }
+
+    /// Text to deliver after every filter, or why the message was rejected
+    pub fn filter(&self, text: &str) -> std::result::Result<String, String> {
+        let mut text = text.to_string();
+        for filter in &self.filters {
+            match filter.check(&text) {
+                FilterVerdict::Allow => {}
+                FilterVerdict::Replace(replaced) => text = replaced,
+                FilterVerdict::Reject(reason) => return Err(reason),
+            }
+        }
+        Ok(text)
+    }
+
+    /// Page of a room's history visible to a reader, oldest first. `user_id`
+    /// must be token-verified. `before` is the row id to page back from; `None`
+    /// reader fields see public messages only.
+    pub async fn history(
+        &self,
+        room_id: &str,
+        user_id: Option<&str>,
+        team: Option<&str>,
+        before: Option<i32>,
+        limit: u64,
+    ) -> Result<Vec<ChatRecord>> {
+        let limit = limit.min(MAX_HISTORY_PAGE);
+        if let Some(db) = &self.db {
+            return ChatRecordModel::find_visible(db, room_id, user_id, team, before, limit).await;
+        }
+
+        let memory = self.memory.read().await;
+        let mut page: Vec<ChatRecord> = memory
+            .get(room_id)
+            .into_iter()
+            .flatten()
+            .rev()
+            .filter(|record| before.is_none_or(|before| record.id < before))
+            .filter(|record| record.is_visible_to(user_id, team))
+            .take(limit as usize)
+            .cloned()
+            .collect();
+        page.reverse();
+        Ok(page)
+    }
+
+    /// Put a verified user on a team in a room, or take them off it with
+    /// `None`. Their connections on this instance switch teams at once, and
+    /// later connections join the team as they arrive.
+    pub async fn assign_team(
+        &self,
+        ws_manager: &WebSocketManager,
+        room_id: &str,
+        user_id: &str,
+        team: Option<String>,
+    ) -> Result<()> {
+        let team = team.map(|team| team.trim().to_string());
+        if team.as_deref() == Some("") {
+            return Err(Error::validation("Team names cannot be empty"));
+        }
+
+        {
+            let mut teams = self.teams.write().await;
+            let room_teams = teams.entry(room_id.to_string()).or_default();
+            match &team {
+                Some(team) => {
+                    room_teams.insert(user_id.to_string(), team.clone());
+                }
+                None => {
+                    room_teams.remove(user_id);
+                }
+            }
+        }
+
+        for conn_id in ws_manager.get_room_connections(room_id).await {
+            if let Some(conn) = ws_manager.get_connection_info(&conn_id).await {
+                if conn.authenticated && conn.user_id.as_deref() == Some(user_id) {
+                    ws_manager.set_team(&conn_id, team.clone()).await;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Team a verified user was assigned in a room
+    pub async fn team_of(&self, room_id: &str, user_id: &str) -> Option<String> {
+        self.teams.read().await.get(room_id)?.get(user_id).cloned()
+    }
+
+    /// Put a joining connection on its user's team and send it the recent
+    /// history it may see
+    pub async fn replay(&self, ws_manager: &WebSocketManager, conn_id: &str) {
+        let Some(conn) = ws_manager.get_connection_info(conn_id).await else {
+            return;
+        };
+        let Some(room_id) = conn.room_id.as_deref() else {
+            return;
+        };
+
+        let user_id = conn.user_id.as_deref().filter(|_| conn.authenticated);
+        let mut team = conn.team.clone();
+        if let Some(user_id) = user_id {
+            if let Some(assigned) = self.team_of(room_id, user_id).await {
+                ws_manager.set_team(conn_id, Some(assigned.clone())).await;
+                team = Some(assigned);
+            }
+        }
+
+        let records = match self
+            .history(room_id, user_id, team.as_deref(), None, self.replay_len)
+            .await
+        {
+            Ok(records) => records,
+            Err(e) => {
+                log::warn!("Failed to load chat history of room {}: {}", room_id, e);
+                return;
+            }
+        };
+
+        for record in &records {
+            if let Ok(bytes) = MessageParser::serialize(&message_from_record(record)) {
+                let _ = ws_manager.send_to_connection(conn_id, WsMessage::Binary(bytes)).await;
+            }
+        }
+    }
+
+    /// Remove a message from the room's history and tell the room
+    pub async fn delete(
+        &self,
+        ws_manager: &WebSocketManager,
+        room_id: &str,
+        chat_id: &str,
+        deleted_by: &str,
+    ) -> Result<()> {
+        let deleted = match &self.db {
+            Some(db) => ChatRecordModel::mark_deleted(db, room_id, chat_id, deleted_by)
+                .await?
+                .is_some(),
+            None => {
+                let mut memory = self.memory.write().await;
+                let history = memory.entry(room_id.to_string()).or_default();
+                let before = history.len();
+                history.retain(|record| record.chat_id != chat_id);
+                history.len() != before
+            }
+        };
+        if !deleted {
+            return Err(Error::not_found(format!("Chat message {} not found in room {}", chat_id, room_id)));
+        }
+
+        log::info!("Chat message {} in room {} deleted by {}", chat_id, room_id, deleted_by);
+        let bytes = MessageParser::serialize(&MessageBuilder::chat_deleted(chat_id.to_string()))
+            .map_err(|e| Error::internal(format!("Failed to encode message: {}", e)))?;
+        ws_manager.broadcast_to_room(room_id, &bytes, None).await;
+        Ok(())
+    }
+
+    /// Post a system message to a room as a shout, unfiltered. It is kept in
+    /// the history like any public message, so late joiners see it too.
+    pub async fn announce(
+        &self,
+        ws_manager: &WebSocketManager,
+        room_id: &str,
+        body: &str,
+        sent_by: &str,
+    ) -> Result<ChatRecord> {
+        let body = body.trim();
+        if body.is_empty() {
+            return Err(Error::validation("System messages need text"));
+        }
+
+        let record = self
+            .store(ChatRecord {
+                id: 0,
+                chat_id: Uuid::new_v4().to_string(),
+                room_id: room_id.to_string(),
+                from_client_id: SYSTEM_CLIENT_ID.to_string(),
+                from_user_id: Some(sent_by.to_string()),
+                kind: ChatKind::Shout,
+                to_user_id: None,
+                channel: None,
+                body: body.to_string(),
+                created_at: chrono::Utc::now().naive_utc(),
+            })
+            .await?;
+
+        log::info!("System message {} posted to room {} by {}", record.chat_id, room_id, sent_by);
+        let bytes = MessageParser::serialize(&message_from_record(&record))
+            .map_err(|e| Error::internal(format!("Failed to encode message: {}", e)))?;
+        ws_manager.broadcast_to_room(room_id, &bytes, None).await;
+        Ok(record)
+    }
+
+    async fn store(&self, record: ChatRecord) -> Result<ChatRecord> {
+        if let Some(db) = &self.db {
+            return ChatRecordModel::record(db, &record).await;
+        }
+
+        let record = ChatRecord {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            ..record
+        };
+        let mut memory = self.memory.write().await;
+        let history = memory.entry(record.room_id.clone()).or_default();
+        history.push_back(record.clone());
+        if history.len() > MEMORY_HISTORY_LEN {
+            history.pop_front();
+        }
+        Ok(record)
+    }
+}
+
+#[async_trait]
+impl MessageHandler for ChatService {
+    async fn handle(&self, message: &mut Message, ctx: &mut MessageContext<'_>) -> Result<Flow> {
+        let Some(Payload::ChatMessage(chat)) = message.payload.as_mut() else {
+            return Ok(Flow::Continue);
+        };
+
+        let body = match self.filter(&chat.message) {
+            Ok(body) => body,
+            Err(reason) => {
+                ctx.send(Route::Sender, MessageBuilder::error_frame("CHAT_REJECTED", reason, 0));
+                return Ok(Flow::Drop);
+            }
+        };
+
+        // Senders speak for their own connection only
+        let from_client_id = ctx.client_id().unwrap_or(ctx.conn_id).to_string();
+        let team = ctx.connection.as_ref().and_then(|conn| conn.team.clone());
+        let kind = match ChatMessageType::try_from(chat.r#type).unwrap_or(ChatMessageType::Normal) {
+            ChatMessageType::Normal => ChatKind::Normal,
+            ChatMessageType::Whisper => ChatKind::Whisper,
+            ChatMessageType::Shout => ChatKind::Shout,
+            ChatMessageType::Team => ChatKind::Team,
+        };
+
+        let route = match kind {
+            ChatKind::Normal | ChatKind::Shout => Route::Room,
+            ChatKind::Whisper => {
+                let reason = if ctx.user_id().is_none() {
+                    Some("Sign in to whisper")
+                } else if chat.to_user_id.is_empty() {
+                    Some("Whispers need a recipient")
+                } else {
+                    None
+                };
+                if let Some(reason) = reason {
+                    ctx.send(
+                        Route::Sender,
+                        MessageBuilder::error_frame("CHAT_REJECTED", reason.to_string(), 0),
+                    );
+                    return Ok(Flow::Drop);
+                }
+                // Only a connection whose token proved the recipient's id hears it
+                let to_user_id = chat.to_user_id.clone();
+                Route::Connections(
+                    room_connections_where(ctx, |conn| {
+                        conn.authenticated && conn.user_id.as_deref() == Some(to_user_id.as_str())
+                    })
+                    .await,
+                )
+            }
+            ChatKind::Team => {
+                let Some(team) = team.clone() else {
+                    ctx.send(
+                        Route::Sender,
+                        MessageBuilder::error_frame("CHAT_REJECTED", "You are not on a team".to_string(), 0),
+                    );
+                    return Ok(Flow::Drop);
+                };
+                Route::Connections(
+                    room_connections_where(ctx, |conn| conn.team.as_deref() == Some(team.as_str())).await,
+                )
+            }
+        };
+
+        let record = self
+            .store(ChatRecord {
+                id: 0,
+                chat_id: Uuid::new_v4().to_string(),
+                room_id: ctx.room_id.to_string(),
+                from_client_id,
+                from_user_id: ctx.user_id().map(str::to_string),
+                kind,
+                to_user_id: (kind == ChatKind::Whisper).then(|| chat.to_user_id.clone()),
+                channel: if kind == ChatKind::Team { team } else { None },
+                body,
+                created_at: chrono::Utc::now().naive_utc(),
+            })
+            .await?;
+
+        *chat = chat_from_record(&record);
+        ctx.route = Some(route);
+        Ok(Flow::Continue)
+    }
+}
+
+/// Connections in the sender's room, other than the sender, matching `keep`
+async fn room_connections_where<F>(ctx: &MessageContext<'_>, keep: F) -> Vec<String>
+where
+    F: Fn(&crate::websocket::WebSocketConnection) -> bool,
+{
+    let mut targets = Vec::new();
+    for conn_id in ctx.ws_manager.get_room_connections(ctx.room_id).await {
+        if conn_id == ctx.conn_id {
+            continue;
+        }
+        if let Some(conn) = ctx.ws_manager.get_connection_info(&conn_id).await {
+            if keep(&conn) {
+                targets.push(conn_id);
+            }
+        }
+    }
+    targets
+}
+
+fn chat_from_record(record: &ChatRecord) -> ChatMessage {
+    ChatMessage {
+        from_client_id: record.from_client_id.clone(),
+        message: record.body.clone(),
+        timestamp: record.created_at.and_utc().timestamp_millis(),
+        r#type: match record.kind {
+            ChatKind::Normal => ChatMessageType::Normal,
+            ChatKind::Whisper => ChatMessageType::Whisper,
+            ChatKind::Shout => ChatMessageType::Shout,
+            ChatKind::Team => ChatMessageType::Team,
+        } as i32,
+        to_user_id: record.to_user_id.clone().unwrap_or_default(),
+        channel: record.channel.clone().unwrap_or_default(),
+        chat_id: record.chat_id.clone(),
+        deleted: false,
+    }
+}
+
+/// Client message carrying a stored chat message
+pub fn message_from_record(record: &ChatRecord) -> Message {
+    let mut message = MessageBuilder::chat_message(String::new(), String::new(), ChatMessageType::Normal);
+    message.payload = Some(Payload::ChatMessage(chat_from_record(record)));
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protobuf::route_message;
+    use crate::queue::OutboundReceiver;
+    use std::time::Duration;
+    use tokio::time::timeout;
+
+    async fn next_chat(rx: &mut OutboundReceiver<WsMessage>) -> Option<ChatMessage> {
+        loop {
+            match timeout(Duration::from_millis(100), rx.queue.recv()).await {
+                Ok(Some(WsMessage::Binary(bytes))) => match MessageParser::parse(&bytes).ok()?.payload {
+                    Some(Payload::ChatMessage(chat)) => return Some(chat),
+                    // Skip hellos and presence events
+                    _ => continue,
+                },
+                _ => return None,
+            }
+        }
+    }
+
+    async fn send_chat(ws_manager: &WebSocketManager, conn_id: &str, message: Message) {
+        let handlers = ws_manager.handlers().await;
+        route_message(&handlers, ws_manager, "room-1", conn_id, &MessageParser::serialize(&message).unwrap())
+            .await
+            .unwrap();
+    }
+
+    fn chat(text: &str, kind: ChatMessageType, to_user_id: &str) -> Message {
+        let mut message = MessageBuilder::chat_message("spoofed".to_string(), text.to_string(), kind);
+        if let Some(Payload::ChatMessage(chat)) = message.payload.as_mut() {
+            chat.to_user_id = to_user_id.to_string();
+        }
+        message
+    }
+
+    async fn join(ws_manager: &WebSocketManager, conn_id: &str, user_id: &str) -> OutboundReceiver<WsMessage> {
+        ws_manager
+            .add_authenticated_connection(
+                conn_id.to_string(),
+                Some("room-1".to_string()),
+                user_id.to_string(),
+                Some(user_id.to_string()),
+            )
+            .await
+    }
+
+    #[test]
+    fn test_filters() {
+        let chat = ChatService::new(None)
+            .with_filter(Arc::new(WordListFilter::new(["darn"])))
+            .with_filter(Arc::new(LinkFilter));
+
+        assert_eq!(chat.filter("hello there").unwrap(), "hello there");
+        assert_eq!(chat.filter("Darn, darned darn!").unwrap(), "****, darned ****!");
+        assert!(chat.filter("see https://example.com").is_err());
+        assert!(chat.filter("see www.example.com").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_whispers_and_team_messages_reach_only_their_audience() {
+        let ws_manager = WebSocketManager::new();
+        let chat_service = ChatService::new(None);
+        chat_service.start(&ws_manager).await;
+
+        let mut alice = join(&ws_manager, "c1", "alice").await;
+        let mut bob = join(&ws_manager, "c2", "bob").await;
+        let mut carol = join(&ws_manager, "c3", "carol").await;
+        chat_service
+            .assign_team(&ws_manager, "room-1", "alice", Some("red".to_string()))
+            .await
+            .unwrap();
+        chat_service
+            .assign_team(&ws_manager, "room-1", "carol", Some("red".to_string()))
+            .await
+            .unwrap();
+
+        send_chat(&ws_manager, "c1", chat("psst", ChatMessageType::Whisper, "bob")).await;
+        let whisper = next_chat(&mut bob).await.unwrap();
+        assert_eq!(whisper.message, "psst");
+        assert_eq!(whisper.from_client_id, "alice");
+        assert!(!whisper.chat_id.is_empty());
+        assert!(next_chat(&mut carol).await.is_none());
+
+        send_chat(&ws_manager, "c1", chat("go left", ChatMessageType::Team, "")).await;
+        let team = next_chat(&mut carol).await.unwrap();
+        assert_eq!(team.channel, "red");
+        assert!(next_chat(&mut bob).await.is_none());
+        assert!(next_chat(&mut alice).await.is_none());
+
+        chat_service.assign_team(&ws_manager, "room-1", "carol", None).await.unwrap();
+        send_chat(&ws_manager, "c1", chat("go right", ChatMessageType::Team, "")).await;
+        assert!(next_chat(&mut carol).await.is_none());
+        assert!(chat_service
+            .assign_team(&ws_manager, "room-1", "carol", Some(" ".to_string()))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_whispers_reach_only_verified_users() {
+        let ws_manager = WebSocketManager::new();
+        let chat_service = ChatService::new(None);
+        chat_service.start(&ws_manager).await;
+
+        let _alice = join(&ws_manager, "c1", "alice").await;
+        let mut bob = join(&ws_manager, "c2", "bob").await;
+        // Claims bob's user and client id without a token
+        let mut impostor = ws_manager
+            .add_connection(
+                "c3".to_string(),
+                Some("room-1".to_string()),
+                Some("bob".to_string()),
+                Some("bob".to_string()),
+            )
+            .await;
+
+        send_chat(&ws_manager, "c1", chat("psst", ChatMessageType::Whisper, "bob")).await;
+        assert_eq!(next_chat(&mut bob).await.unwrap().message, "psst");
+        assert!(next_chat(&mut impostor).await.is_none());
+
+        // Unverified connections can neither whisper nor read whispers back
+        send_chat(&ws_manager, "c3", chat("it's me", ChatMessageType::Whisper, "alice")).await;
+        assert!(chat_service
+            .history("room-1", Some("alice"), None, None, 10)
+            .await
+            .unwrap()
+            .iter()
+            .all(|record| record.body == "psst"));
+
+        chat_service.replay(&ws_manager, "c3").await;
+        assert!(next_chat(&mut impostor).await.is_none());
+        chat_service.replay(&ws_manager, "c2").await;
+        assert_eq!(next_chat(&mut bob).await.unwrap().message, "psst");
+    }
+
+    #[tokio::test]
+    async fn test_joining_connections_take_their_users_team() {
+        let ws_manager = WebSocketManager::new();
+        let chat_service = ChatService::new(None);
+        chat_service.start(&ws_manager).await;
+
+        let _alice = join(&ws_manager, "c1", "alice").await;
+        chat_service
+            .assign_team(&ws_manager, "room-1", "alice", Some("red".to_string()))
+            .await
+            .unwrap();
+        chat_service
+            .assign_team(&ws_manager, "room-1", "bob", Some("red".to_string()))
+            .await
+            .unwrap();
+        send_chat(&ws_manager, "c1", chat("regroup", ChatMessageType::Team, "")).await;
+
+        let mut bob = join(&ws_manager, "c2", "bob").await;
+        chat_service.replay(&ws_manager, "c2").await;
+        assert_eq!(next_chat(&mut bob).await.unwrap().message, "regroup");
+        let conn = ws_manager.get_connection_info("c2").await.unwrap();
+        assert_eq!(conn.team.as_deref(), Some("red"));
+
+        // Only verified connections take a user's team
+        let mut impostor = ws_manager
+            .add_connection("c3".to_string(), Some("room-1".to_string()), Some("bob".to_string()), None)
+            .await;
+        chat_service.replay(&ws_manager, "c3").await;
+        assert!(next_chat(&mut impostor).await.is_none());
+        assert!(ws_manager.get_connection_info("c3").await.unwrap().team.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_history_is_replayed_on_join_and_deletions_announced() {
+        let ws_manager = WebSocketManager::new();
+        let chat_service = ChatService::new(None);
+        chat_service.start(&ws_manager).await;
+
+        let _alice = ws_manager
+            .add_connection("c1".to_string(), Some("room-1".to_string()), None, Some("alice".to_string()))
+            .await;
+        send_chat(&ws_manager, "c1", chat("first", ChatMessageType::Normal, "")).await;
+        send_chat(&ws_manager, "c1", chat("second", ChatMessageType::Normal, "")).await;
+        send_chat(&ws_manager, "c1", chat("for carol", ChatMessageType::Whisper, "carol")).await;
+
+        let mut bob = ws_manager
+            .add_connection("c2".to_string(), Some("room-1".to_string()), None, Some("bob".to_string()))
+            .await;
+        chat_service.replay(&ws_manager, "c2").await;
+        let first = next_chat(&mut bob).await.unwrap();
+        assert_eq!(first.message, "first");
+        assert_eq!(next_chat(&mut bob).await.unwrap().message, "second");
+        assert!(next_chat(&mut bob).await.is_none());
+
+        chat_service
+            .delete(&ws_manager, "room-1", &first.chat_id, "moderator")
+            .await
+            .unwrap();
+        let notice = next_chat(&mut bob).await.unwrap();
+        assert!(notice.deleted);
+        assert_eq!(notice.chat_id, first.chat_id);
+
+        let history = chat_service.history("room-1", None, None, None, 10).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].body, "second");
+        assert!(chat_service
+            .delete(&ws_manager, "room-1", &first.chat_id, "moderator")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_system_messages_reach_the_room_and_its_history() {
+        let ws_manager = WebSocketManager::new();
+        let chat_service = ChatService::new(None).with_filter(Arc::new(LinkFilter));
+        chat_service.start(&ws_manager).await;
+
+        let mut alice = ws_manager
+            .add_connection("c1".to_string(), Some("room-1".to_string()), None, Some("alice".to_string()))
+            .await;
+        assert!(chat_service.announce(&ws_manager, "room-1", "  ", "admin").await.is_err());
+
+        let record = chat_service
+            .announce(&ws_manager, "room-1", "Restarting at https://status.example.com", "admin")
+            .await
+            .unwrap();
+        assert_eq!(record.from_user_id.as_deref(), Some("admin"));
+
+        let notice = next_chat(&mut alice).await.unwrap();
+        assert_eq!(notice.from_client_id, SYSTEM_CLIENT_ID);
+        assert_eq!(notice.message, "Restarting at https://status.example.com");
+        assert_eq!(notice.r#type, ChatMessageType::Shout as i32);
+
+        let history = chat_service.history("room-1", None, None, None, 10).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].chat_id, notice.chat_id);
+    }
+}
@@ -0,0 +1,124 @@
+//! Chat handlers for presence service
+//!
+//! History is public: only normal and shout messages are returned, since
+//! whispers and team messages reach their audience through the join replay.
+//! Deleting a message and assigning teams take the same bearer token as
+//! moderation actions and any moderating authority in the room, room hosts
+//! included.
+
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use reticulum_core::Error;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::chat::{ChatService, DEFAULT_REPLAY_LEN};
+use crate::moderation::{Authority, ModerationManager, Moderator};
+use crate::websocket::WebSocketManager;
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    /// Id of the oldest message already seen; omitted for the newest page
+    pub before: Option<i32>,
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AssignTeamRequest {
+    /// Team to put the user on; `None` takes them off their team
+    pub team: Option<String>,
+}
+
+/// Page of a room's chat history, oldest first
+pub async fn get_history(
+    chat: web::Data<ChatService>,
+    path: web::Path<String>,
+    query: web::Query<HistoryQuery>,
+) -> HttpResponse {
+    let room_id = path.into_inner();
+    let limit = query.limit.unwrap_or(DEFAULT_REPLAY_LEN);
+
+    match chat.history(&room_id, None, None, query.before, limit).await {
+        Ok(messages) => HttpResponse::Ok().json(json!({
+            "room_id": room_id,
+            "total": messages.len(),
+            // Page back from here for older messages
+            "next_before": messages.first().map(|message| message.id),
+            "messages": messages
+        })),
+        Err(e) => {
+            log::error!("Failed to fetch chat history of room {}: {}", room_id, e);
+            e.error_response()
+        }
+    }
+}
+
+/// Delete a chat message (room moderators)
+pub async fn delete_message(
+    req: HttpRequest,
+    chat: web::Data<ChatService>,
+    moderation: web::Data<ModerationManager>,
+    ws_manager: web::Data<WebSocketManager>,
+    path: web::Path<(String, String)>,
+) -> HttpResponse {
+    let (room_id, chat_id) = path.into_inner();
+
+    let moderator = match authorize_moderator(&req, &moderation, &ws_manager, &room_id).await {
+        Ok(moderator) => moderator,
+        Err(e) => return e.error_response(),
+    };
+
+    match chat.delete(&ws_manager, &room_id, &chat_id, &moderator.user_id).await {
+        Ok(()) => HttpResponse::Ok().json(json!({
+            "success": true,
+            "message": format!("Chat message {} deleted from room {}", chat_id, room_id)
+        })),
+        Err(e) => {
+            log::warn!("Chat deletion by {} refused: {}", moderator.user_id, e);
+            e.error_response()
+        }
+    }
+}
+
+/// Put a user on a chat team in a room (room moderators)
+pub async fn assign_team(
+    req: HttpRequest,
+    chat: web::Data<ChatService>,
+    moderation: web::Data<ModerationManager>,
+    ws_manager: web::Data<WebSocketManager>,
+    path: web::Path<(String, String)>,
+    body: web::Json<AssignTeamRequest>,
+) -> HttpResponse {
+    let (room_id, user_id) = path.into_inner();
+
+    let moderator = match authorize_moderator(&req, &moderation, &ws_manager, &room_id).await {
+        Ok(moderator) => moderator,
+        Err(e) => return e.error_response(),
+    };
+
+    let team = body.into_inner().team;
+    match chat.assign_team(&ws_manager, &room_id, &user_id, team.clone()).await {
+        Ok(()) => {
+            log::info!("User {} put on team {:?} in room {} by {}", user_id, team, room_id, moderator.user_id);
+            HttpResponse::Ok().json(json!({
+                "room_id": room_id,
+                "user_id": user_id,
+                "team": team
+            }))
+        }
+        Err(e) => e.error_response(),
+    }
+}
+
+/// The caller, if they may moderate chat in `room_id`
+async fn authorize_moderator(
+    req: &HttpRequest,
+    moderation: &ModerationManager,
+    ws_manager: &WebSocketManager,
+    room_id: &str,
+) -> Result<Moderator, Error> {
+    let moderator = moderation.authenticate(req)?;
+    match moderation.authority(ws_manager, &moderator, room_id).await? {
+        Authority::None => Err(Error::authorization("Not allowed to moderate chat in this room")),
+        _ => Ok(moderator),
+    }
+}
@@ -0,0 +1,493 @@
+//! Graceful drain for rolling deploys
+//!
+//! A draining instance refuses new connections and reports itself unhealthy,
+//! so the load balancer stops sending clients to it. Each connected client is
+//! sent a `REDIRECT` with the address of the least loaded peer and its resume
+//! token. The session is offered to the other instances on
+//! [`MIGRATION_CHANNEL`], and the instance the client reconnects to takes it
+//! over. The client keeps its connection ID, and the room sees neither a leave
+//! nor a join. Messages sent to the room while the client reconnects are not
+//! replayed.
+//!
+//! Sessions that have not moved by the deadline are closed as usual, and the
+//! instance is drained. WebTransport sessions cannot resume, so they are
+//! redirected without a token and rejoin the room.
+
+use crate::redis::{ClusterBroadcaster, PubSubTransport};
+use crate::websocket::{close_connection, hand_off_connection, WebSocketManager, WsMessage};
+use chrono::{DateTime, Utc};
+use graphwiz_protocol::{MessageBuilder, MessageParser};
+use reticulum_core::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{watch, RwLock};
+
+/// Channel on which instances offer and claim the sessions of draining instances
+pub const MIGRATION_CHANNEL: &str = "graphwiz:migrations";
+
+/// How long a drain waits for sessions to move before closing them
+pub const DEFAULT_DRAIN_TIMEOUT_SECS: u64 = 30;
+
+/// Longest drain an admin may ask for
+const MAX_DRAIN_TIMEOUT_SECS: u64 = 3600;
+
+/// How often a drain checks whether every session has moved
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Session a draining instance offers to the rest of the cluster
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MigrationTicket {
+    pub resume_token: String,
+    pub conn_id: String,
+    pub room_id: String,
+    pub user_id: Option<String>,
+    pub client_id: Option<String>,
+    pub team: Option<String>,
+    /// Unix time in milliseconds after which the offer lapses
+    pub expires_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum MigrationEvent {
+    /// A draining instance offers a session
+    Offered { ticket: MigrationTicket },
+    /// A client reconnected to an instance with the token of an offered session
+    Claimed { resume_token: String, conn_id: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MigrationRelay {
+    instance_id: String,
+    #[serde(flatten)]
+    event: MigrationEvent,
+}
+
+/// Where an instance is in its drain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DrainPhase {
+    Serving,
+    Draining,
+    Drained,
+}
+
+/// Body of a drain request
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DrainRequest {
+    /// Seconds to wait for sessions to move; defaults to 30
+    pub timeout_secs: Option<u64>,
+    /// Address to send clients to; defaults to the least loaded peer
+    pub target_url: Option<String>,
+}
+
+/// Progress of a drain for the admin endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct DrainStatus {
+    pub instance_id: String,
+    pub phase: DrainPhase,
+    pub started_at: Option<DateTime<Utc>>,
+    pub deadline: Option<DateTime<Utc>>,
+    /// Empty when clients reconnect to the usual service address
+    pub target_url: Option<String>,
+    pub redirected: usize,
+    pub migrated: usize,
+    /// Sessions still held by this instance, including parked ones
+    pub remaining: usize,
+}
+
+#[derive(Default)]
+struct DrainState {
+    started_at: Option<DateTime<Utc>>,
+    deadline: Option<DateTime<Utc>>,
+    target_url: Option<String>,
+    redirected: HashMap<String, String>, // conn_id -> resume token offered for it
+    migrated: usize,
+}
+
+/// Drains this instance and takes over the sessions of draining peers
+#[derive(Clone)]
+pub struct DrainController {
+    cluster: ClusterBroadcaster,
+    transport: Arc<dyn PubSubTransport>,
+    phase: Arc<watch::Sender<DrainPhase>>,
+    state: Arc<RwLock<DrainState>>,
+    tickets: Arc<RwLock<HashMap<String, MigrationTicket>>>, // resume token -> session offered by a peer
+}
+
+impl DrainController {
+    /// Controller moving sessions between the instances of `cluster`
+    pub fn new(cluster: ClusterBroadcaster) -> Self {
+        let (phase, _) = watch::channel(DrainPhase::Serving);
+        Self {
+            transport: cluster.transport(),
+            cluster,
+            phase: Arc::new(phase),
+            state: Arc::new(RwLock::new(DrainState::default())),
+            tickets: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Accept sessions offered by draining peers and let `ws_manager` refuse
+    /// new connections while this instance drains
+    pub async fn start(&self, ws_manager: &WebSocketManager) -> Result<()> {
+        ws_manager.attach_drain(self.clone()).await;
+        let mut relays = self.transport.psubscribe(MIGRATION_CHANNEL).await?;
+
+        let controller = self.clone();
+        let manager = ws_manager.clone();
+        tokio::spawn(async move {
+            while let Some((_, payload)) = relays.recv().await {
+                match serde_json::from_slice::<MigrationRelay>(&payload) {
+                    Ok(relay) if relay.instance_id != controller.cluster.instance_id() => {
+                        controller.handle_event(&manager, relay.event).await;
+                    }
+                    Ok(_) => {}
+                    Err(e) => log::warn!("Invalid migration relay: {}", e),
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    async fn handle_event(&self, ws_manager: &WebSocketManager, event: MigrationEvent) {
+        match event {
+            MigrationEvent::Offered { ticket } => {
+                let now = Utc::now().timestamp_millis();
+                let mut tickets = self.tickets.write().await;
+                tickets.retain(|_, ticket| ticket.expires_at > now);
+                tickets.insert(ticket.resume_token.clone(), ticket);
+            }
+            MigrationEvent::Claimed { resume_token, conn_id } => {
+                self.tickets.write().await.remove(&resume_token);
+
+                let moved = {
+                    let mut state = self.state.write().await;
+                    let moved = state.redirected.get(&conn_id) == Some(&resume_token);
+                    if moved {
+                        state.redirected.remove(&conn_id);
+                        state.migrated += 1;
+                    }
+                    moved
+                };
+                if moved {
+                    log::info!("Connection {} moved to another instance", conn_id);
+                    hand_off_connection(ws_manager, &conn_id).await;
+                }
+            }
+        }
+    }
+
+    /// Current phase
+    pub fn phase(&self) -> DrainPhase {
+        *self.phase.borrow()
+    }
+
+    /// Whether new connections are refused
+    pub fn is_draining(&self) -> bool {
+        self.phase() != DrainPhase::Serving
+    }
+
+    /// Resolves once the drain is over and the instance can shut down
+    pub async fn drained(&self) {
+        let mut phase = self.phase.subscribe();
+        let _ = phase.wait_for(|phase| *phase == DrainPhase::Drained).await;
+    }
+
+    /// Progress of the drain
+    pub async fn status(&self, ws_manager: &WebSocketManager) -> DrainStatus {
+        let state = self.state.read().await;
+        DrainStatus {
+            instance_id: self.cluster.instance_id().to_string(),
+            phase: self.phase(),
+            started_at: state.started_at,
+            deadline: state.deadline,
+            target_url: state.target_url.clone(),
+            redirected: state.redirected.len() + state.migrated,
+            migrated: state.migrated,
+            remaining: ws_manager.connection_ids().await.len(),
+        }
+    }
+
+    /// Stop taking connections, redirect every client and shut down once
+    /// their sessions have moved or the timeout has passed
+    pub async fn drain(&self, ws_manager: &WebSocketManager, request: DrainRequest) -> Result<DrainStatus> {
+        let timeout_secs = request.timeout_secs.unwrap_or(DEFAULT_DRAIN_TIMEOUT_SECS);
+        if timeout_secs == 0 || timeout_secs > MAX_DRAIN_TIMEOUT_SECS {
+            return Err(Error::validation(format!(
+                "timeout_secs must be between 1 and {}",
+                MAX_DRAIN_TIMEOUT_SECS
+            )));
+        }
+        if !self.phase.send_if_modified(|phase| {
+            let serving = *phase == DrainPhase::Serving;
+            if serving {
+                *phase = DrainPhase::Draining;
+            }
+            serving
+        }) {
+            return Err(Error::validation("Instance is already draining"));
+        }
+
+        self.cluster.set_draining(true, ws_manager.connection_count().await).await;
+
+        let target_url = match request.target_url {
+            Some(url) => Some(url),
+            None => self.cluster.redirect_target().await,
+        };
+        let started_at = Utc::now();
+        let deadline = started_at + chrono::Duration::seconds(timeout_secs as i64);
+        {
+            let mut state = self.state.write().await;
+            state.started_at = Some(started_at);
+            state.deadline = Some(deadline);
+            state.target_url = target_url.clone();
+        }
+        log::info!(
+            "Draining presence instance {} within {}s, redirecting clients to {}",
+            self.cluster.instance_id(),
+            timeout_secs,
+            target_url.as_deref().unwrap_or("the service address")
+        );
+
+        for conn_id in ws_manager.connection_ids().await {
+            self.redirect(ws_manager, &conn_id, target_url.as_deref().unwrap_or_default(), deadline)
+                .await;
+        }
+
+        let controller = self.clone();
+        let manager = ws_manager.clone();
+        tokio::spawn(async move {
+            controller.finish(&manager, deadline).await;
+        });
+
+        Ok(self.status(ws_manager).await)
+    }
+
+    /// Offer a connection's session to the other instances and tell its client
+    /// where to reconnect
+    async fn redirect(&self, ws_manager: &WebSocketManager, conn_id: &str, target_url: &str, deadline: DateTime<Utc>) {
+        let Some(conn) = ws_manager.get_connection_info(conn_id).await else {
+            return;
+        };
+
+        let resume_token = match (ws_manager.resume_token(conn_id).await, conn.room_id) {
+            (Some(resume_token), Some(room_id)) => {
+                let ticket = MigrationTicket {
+                    resume_token: resume_token.clone(),
+                    conn_id: conn_id.to_string(),
+                    room_id,
+                    user_id: conn.user_id,
+                    client_id: conn.client_id,
+                    team: conn.team,
+                    expires_at: deadline.timestamp_millis(),
+                };
+                if let Err(e) = self.publish(MigrationEvent::Offered { ticket }).await {
+                    log::warn!("Failed to offer the session of {}: {}", conn_id, e);
+                }
+                self.state
+                    .write()
+                    .await
+                    .redirected
+                    .insert(conn_id.to_string(), resume_token.clone());
+                resume_token
+            }
+            _ => String::new(),
+        };
+
+        let reconnect_within_ms = (deadline - Utc::now()).num_milliseconds().clamp(0, u32::MAX as i64) as u32;
+        let message = MessageBuilder::redirect(target_url.to_string(), resume_token, reconnect_within_ms);
+        match MessageParser::serialize(&message) {
+            Ok(bytes) => {
+                let _ = ws_manager.send_to_connection(conn_id, WsMessage::Binary(bytes)).await;
+            }
+            Err(e) => log::error!("Failed to encode redirect for {}: {}", conn_id, e),
+        }
+    }
+
+    /// Wait for the sessions to move, close those left at `deadline` and
+    /// mark the instance drained
+    async fn finish(&self, ws_manager: &WebSocketManager, deadline: DateTime<Utc>) {
+        while Utc::now() < deadline && !ws_manager.connection_ids().await.is_empty() {
+            tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+        }
+
+        let remaining = ws_manager.connection_ids().await;
+        if !remaining.is_empty() {
+            log::info!("Closing {} connections that did not move before the deadline", remaining.len());
+        }
+        for conn_id in remaining {
+            close_connection(ws_manager, &conn_id).await;
+        }
+
+        log::info!("Presence instance {} drained", self.cluster.instance_id());
+        self.phase.send_replace(DrainPhase::Drained);
+    }
+
+    /// Take over a session a draining peer offered under `resume_token`, if
+    /// it was in `room_id` and the offer has not lapsed
+    pub async fn claim(&self, resume_token: &str, room_id: &str) -> Option<MigrationTicket> {
+        let ticket = {
+            let mut tickets = self.tickets.write().await;
+            match tickets.get(resume_token) {
+                Some(ticket) if ticket.room_id == room_id => tickets.remove(resume_token)?,
+                _ => return None,
+            }
+        };
+        if ticket.expires_at <= Utc::now().timestamp_millis() {
+            return None;
+        }
+
+        let claimed = MigrationEvent::Claimed {
+            resume_token: ticket.resume_token.clone(),
+            conn_id: ticket.conn_id.clone(),
+        };
+        if let Err(e) = self.publish(claimed).await {
+            log::warn!("Failed to claim the session of {}: {}", ticket.conn_id, e);
+        }
+        Some(ticket)
+    }
+
+    async fn publish(&self, event: MigrationEvent) -> Result<()> {
+        let relay = MigrationRelay {
+            instance_id: self.cluster.instance_id().to_string(),
+            event,
+        };
+        self.transport
+            .publish(MIGRATION_CHANNEL, serde_json::to_vec(&relay)?)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queue::OutboundReceiver;
+    use crate::redis::{MemoryTransport, RedisConfig};
+    use graphwiz_protocol::generated::graphwiz::core::message::Payload;
+    use graphwiz_protocol::generated::graphwiz::core::Redirect;
+    use tokio::time::timeout;
+
+    async fn instance(transport: &Arc<MemoryTransport>) -> (WebSocketManager, DrainController) {
+        let cluster = ClusterBroadcaster::new(RedisConfig::default(), transport.clone());
+        let ws_manager = WebSocketManager::new();
+        let drain = DrainController::new(cluster);
+        drain.start(&ws_manager).await.unwrap();
+        (ws_manager, drain)
+    }
+
+    async fn next_redirect(rx: &mut OutboundReceiver<WsMessage>) -> Option<Redirect> {
+        loop {
+            match timeout(Duration::from_millis(500), rx.queue.recv()).await {
+                Ok(Some(WsMessage::Binary(bytes))) => match MessageParser::parse(&bytes).ok()?.payload {
+                    Some(Payload::Redirect(redirect)) => return Some(redirect),
+                    _ => continue,
+                },
+                _ => return None,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_moves_to_the_instance_the_client_reconnects_to() {
+        let transport = Arc::new(MemoryTransport::new());
+        let (old_manager, old) = instance(&transport).await;
+        let (_new_manager, new) = instance(&transport).await;
+
+        let mut rx = old_manager
+            .add_connection("conn-1".to_string(), Some("room-1".to_string()), Some("user-1".to_string()), Some("alice".to_string()))
+            .await;
+        let token = old_manager.enable_resume("conn-1").await;
+
+        let request = DrainRequest {
+            timeout_secs: Some(5),
+            target_url: Some("wss://presence-2.example".to_string()),
+        };
+        let status = old.drain(&old_manager, request).await.unwrap();
+        assert_eq!(status.phase, DrainPhase::Draining);
+        assert_eq!(status.redirected, 1);
+        assert!(old.drain(&old_manager, DrainRequest::default()).await.is_err());
+
+        let redirect = next_redirect(&mut rx).await.unwrap();
+        assert_eq!(redirect.url, "wss://presence-2.example");
+        assert_eq!(redirect.resume_token, token);
+        assert!(redirect.reconnect_within_ms > 0);
+
+        // The offer reaches the other instance over the transport
+        let mut ticket = None;
+        for _ in 0..50 {
+            ticket = new.claim(&token, "room-1").await;
+            if ticket.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let ticket = ticket.unwrap();
+        assert_eq!(ticket.conn_id, "conn-1");
+        assert_eq!(ticket.client_id.as_deref(), Some("alice"));
+        assert_eq!(new.claim(&token, "room-1").await, None);
+
+        // The claim releases the session here and ends the drain early
+        timeout(Duration::from_secs(2), old.drained()).await.unwrap();
+        assert!(old_manager.get_connection_info("conn-1").await.is_none());
+        let status = old.status(&old_manager).await;
+        assert_eq!(status.phase, DrainPhase::Drained);
+        assert_eq!(status.migrated, 1);
+        assert_eq!(status.remaining, 0);
+    }
+
+    #[tokio::test]
+    async fn test_offers_are_only_claimed_in_their_room() {
+        let transport = Arc::new(MemoryTransport::new());
+        let (_ws_manager, drain) = instance(&transport).await;
+        let ticket = MigrationTicket {
+            resume_token: "token".to_string(),
+            conn_id: "conn-1".to_string(),
+            room_id: "room-1".to_string(),
+            user_id: None,
+            client_id: None,
+            team: None,
+            expires_at: Utc::now().timestamp_millis() + 60_000,
+        };
+        drain
+            .tickets
+            .write()
+            .await
+            .insert(ticket.resume_token.clone(), ticket.clone());
+
+        assert_eq!(drain.claim("token", "room-2").await, None);
+        assert_eq!(drain.claim("other", "room-1").await, None);
+        assert_eq!(drain.claim("token", "room-1").await, Some(ticket));
+    }
+
+    #[tokio::test]
+    async fn test_drain_closes_sessions_left_at_the_deadline() {
+        let transport = Arc::new(MemoryTransport::new());
+        let (ws_manager, drain) = instance(&transport).await;
+        let _rx = ws_manager
+            .add_connection("conn-1".to_string(), Some("room-1".to_string()), None, Some("bob".to_string()))
+            .await;
+
+        let invalid = DrainRequest {
+            timeout_secs: Some(0),
+            target_url: None,
+        };
+        assert!(drain.drain(&ws_manager, invalid).await.is_err());
+        assert!(!drain.is_draining());
+
+        let request = DrainRequest {
+            timeout_secs: Some(1),
+            target_url: None,
+        };
+        let status = drain.drain(&ws_manager, request).await.unwrap();
+        assert_eq!(status.target_url, None);
+        assert!(drain.is_draining());
+
+        timeout(Duration::from_secs(3), drain.drained()).await.unwrap();
+        assert!(ws_manager.get_connection_info("conn-1").await.is_none());
+        assert_eq!(drain.status(&ws_manager).await.migrated, 0);
+    }
+}
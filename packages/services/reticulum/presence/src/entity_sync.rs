@@ -0,0 +1,440 @@
+//! Entity sync with the hub
+//!
+//! Entity messages from clients are not relayed to the room. They are sent to
+//! the hub, which applies them to the authoritative room state and publishes
+//! every applied change, including changes made over its HTTP API. Each
+//! presence instance delivers those changes to its connections in the room,
+//! so every client, the sender included, sees a change once and as applied.
+//!
+//! Undo and redo messages go to the hub as well, which steps through the
+//! sender's own changes and publishes what it applied like any other change.
+
+use crate::protobuf::{Flow, MessageContext, MessageHandler, Route};
+use crate::redis::PubSubTransport;
+use crate::websocket::WebSocketManager;
+use async_trait::async_trait;
+use graphwiz_protocol::generated::graphwiz::core::message::Payload;
+use graphwiz_protocol::generated::graphwiz::core::{Quaternion as QuaternionProto, Vector3 as Vector3Proto};
+use graphwiz_protocol::{Message, MessageBuilder, MessageParser, MessageType};
+use reticulum_core::entity_sync::{
+    EntityChange, EntityHistoryRequest, EntitySyncEvent, EntitySyncRequest, HistoryStep, ENTITY_EVENTS_CHANNEL,
+    ENTITY_HISTORY_CHANNEL, ENTITY_REQUESTS_CHANNEL,
+};
+use reticulum_core::models::{EntityData, Quaternion, Vector3};
+use reticulum_core::Result;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Forwards client entity changes to the hub and delivers the hub's changes
+#[derive(Clone)]
+pub struct EntitySync {
+    transport: Arc<dyn PubSubTransport>,
+}
+
+impl EntitySync {
+    /// Sync over `transport`, which the hub must share
+    pub fn new(transport: Arc<dyn PubSubTransport>) -> Self {
+        Self { transport }
+    }
+
+    /// Route `ws_manager`'s entity messages through the hub and deliver the
+    /// hub's changes to its rooms
+    pub async fn start(&self, ws_manager: &WebSocketManager) -> Result<()> {
+        let mut events = self.transport.psubscribe(ENTITY_EVENTS_CHANNEL).await?;
+
+        for message_type in [
+            MessageType::EntitySpawn,
+            MessageType::EntityUpdate,
+            MessageType::EntityDespawn,
+            MessageType::EntityHistory,
+        ] {
+            ws_manager
+                .register_handler(message_type, Arc::new(self.clone()))
+                .await;
+        }
+
+        let ws_manager = ws_manager.clone();
+        tokio::spawn(async move {
+            while let Some((_, payload)) = events.recv().await {
+                match serde_json::from_slice::<EntitySyncEvent>(&payload) {
+                    Ok(event) => deliver_event(&ws_manager, &event).await,
+                    Err(e) => log::warn!("Invalid entity event: {}", e),
+                }
+            }
+        });
+
+        log::info!("Entity changes are synced with the hub over {}", self.transport.name());
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MessageHandler for EntitySync {
+    async fn handle(&self, message: &mut Message, ctx: &mut MessageContext<'_>) -> Result<Flow> {
+        if let Some(Payload::EntityHistory(history)) = &message.payload {
+            let step = if history.redo { HistoryStep::Redo } else { HistoryStep::Undo };
+            self.step_history(step, ctx).await;
+            return Ok(Flow::Drop);
+        }
+
+        let change = match message.payload.as_ref().and_then(|payload| change_from_payload(ctx.room_id, payload)) {
+            Some(change) => change,
+            None => return Ok(Flow::Continue),
+        };
+
+        // Only token-verified users are credited, so nobody can record
+        // changes in another user's history
+        let request = EntitySyncRequest {
+            room_id: ctx.room_id.to_string(),
+            author_id: ctx.user_id().map(str::to_string),
+            change,
+        };
+        match self
+            .transport
+            .publish(ENTITY_REQUESTS_CHANNEL, serde_json::to_vec(&request)?)
+            .await
+        {
+            // The room gets the change once the hub has applied it
+            Ok(()) => Ok(Flow::Drop),
+            Err(e) => {
+                log::warn!("Hub unreachable, relaying entity change directly: {}", e);
+                Ok(Flow::Continue)
+            }
+        }
+    }
+}
+
+impl EntitySync {
+    /// Ask the hub to undo or redo a change of the sender. Only a user id
+    /// proven by an access token has a history, so nobody can step through
+    /// another user's changes by claiming their id.
+    async fn step_history(&self, step: HistoryStep, ctx: &mut MessageContext<'_>) {
+        let Some(user_id) = ctx.user_id().map(str::to_string) else {
+            ctx.send(
+                Route::Sender,
+                MessageBuilder::error_frame("HISTORY_REJECTED", "Sign in to undo".to_string(), 0),
+            );
+            return;
+        };
+
+        let request = EntityHistoryRequest {
+            room_id: ctx.room_id.to_string(),
+            user_id,
+            step,
+        };
+        let published = match serde_json::to_vec(&request) {
+            Ok(payload) => self.transport.publish(ENTITY_HISTORY_CHANNEL, payload).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = published {
+            log::warn!("Hub unreachable, dropping {:?} in room {}: {}", step, request.room_id, e);
+            ctx.send(
+                Route::Sender,
+                MessageBuilder::error_frame("HISTORY_UNAVAILABLE", "Undo and redo are unavailable".to_string(), 0),
+            );
+        }
+    }
+}
+
+async fn deliver_event(ws_manager: &WebSocketManager, event: &EntitySyncEvent) {
+    let mut messages = vec![message_from_change(&event.change)];
+    // Spawn messages carry no transform
+    if let EntityChange::Spawn { entity } = &event.change {
+        messages.push(MessageBuilder::position_update(
+            entity.entity_id.clone(),
+            Vector3Proto {
+                x: entity.position.x,
+                y: entity.position.y,
+                z: entity.position.z,
+            },
+            QuaternionProto {
+                x: entity.rotation.x,
+                y: entity.rotation.y,
+                z: entity.rotation.z,
+                w: entity.rotation.w,
+            },
+        ));
+    }
+
+    for message in messages {
+        match MessageParser::serialize(&message) {
+            Ok(bytes) => ws_manager.broadcast_local(&event.room_id, &bytes, None).await,
+            Err(e) => log::error!("Failed to encode entity change for room {}: {}", event.room_id, e),
+        }
+    }
+}
+
+/// Entity change described by a client message, or `None` for other payloads
+pub fn change_from_payload(room_id: &str, payload: &Payload) -> Option<EntityChange> {
+    match payload {
+        Payload::EntitySpawn(spawn) => Some(EntityChange::Spawn {
+            // Spawns carry no transform; position updates move the entity
+            entity: EntityData {
+                entity_id: spawn.entity_id.clone(),
+                room_id: room_id.to_string(),
+                template_id: spawn.template_id.clone(),
+                owner_id: spawn.owner_id.clone(),
+                position: Vector3 { x: 0.0, y: 0.0, z: 0.0 },
+                rotation: Quaternion { x: 0.0, y: 0.0, z: 0.0, w: 1.0 },
+                components: Value::Object(
+                    spawn
+                        .components
+                        .iter()
+                        .map(|(name, value)| (name.clone(), component_from_str(value)))
+                        .collect(),
+                ),
+            },
+        }),
+        Payload::EntityUpdate(update) => Some(EntityChange::Update {
+            entity_id: update.entity_id.clone(),
+            components: update
+                .components
+                .iter()
+                .map(|(name, value)| (name.clone(), component_from_str(&String::from_utf8_lossy(value))))
+                .collect(),
+        }),
+        Payload::EntityDespawn(despawn) => Some(EntityChange::Despawn {
+            entity_id: despawn.entity_id.clone(),
+        }),
+        _ => None,
+    }
+}
+
+/// Client message announcing an applied entity change
+pub fn message_from_change(change: &EntityChange) -> Message {
+    match change {
+        EntityChange::Spawn { entity } => {
+            let components = entity
+                .components
+                .as_object()
+                .map(|components| {
+                    components
+                        .iter()
+                        .map(|(name, value)| (name.clone(), component_to_string(value)))
+                        .collect()
+                })
+                .unwrap_or_default();
+            MessageBuilder::entity_spawn(
+                entity.entity_id.clone(),
+                entity.template_id.clone(),
+                entity.owner_id.clone(),
+                components,
+            )
+        }
+        EntityChange::Update { entity_id, components } => MessageBuilder::entity_update(
+            entity_id.clone(),
+            components
+                .iter()
+                .map(|(name, value)| (name.clone(), component_to_string(value).into_bytes()))
+                .collect::<HashMap<_, _>>(),
+        ),
+        EntityChange::Despawn { entity_id } => MessageBuilder::entity_despawn(entity_id.clone()),
+    }
+}
+
+/// Components hold JSON where they parse as JSON and plain strings otherwise
+fn component_from_str(value: &str) -> Value {
+    serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()))
+}
+
+fn component_to_string(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protobuf::route_message;
+    use crate::queue::OutboundReceiver;
+    use crate::redis::MemoryTransport;
+    use crate::websocket::WsMessage;
+    use std::time::Duration;
+    use tokio::time::timeout;
+
+    async fn next_message(rx: &mut OutboundReceiver<WsMessage>) -> Option<Message> {
+        match timeout(Duration::from_millis(100), rx.queue.recv()).await {
+            Ok(Some(WsMessage::Binary(bytes))) => MessageParser::parse(&bytes).ok(),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_update_components_round_trip() {
+        let mut components = HashMap::new();
+        components.insert("color".to_string(), b"red".to_vec());
+        components.insert("health".to_string(), b"42".to_vec());
+        let message = MessageBuilder::entity_update("entity-1".to_string(), components.clone());
+
+        let change = change_from_payload("room-1", message.payload.as_ref().unwrap()).unwrap();
+        match &change {
+            EntityChange::Update { components, .. } => {
+                assert_eq!(components["color"], Value::String("red".to_string()));
+                assert_eq!(components["health"], serde_json::json!(42));
+            }
+            other => panic!("expected update, got {:?}", other),
+        }
+
+        match message_from_change(&change).payload {
+            Some(Payload::EntityUpdate(update)) => assert_eq!(update.components, components),
+            other => panic!("expected entity update, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_entity_changes_go_through_hub() {
+        let transport = MemoryTransport::new();
+        let mut hub = transport.psubscribe(ENTITY_REQUESTS_CHANNEL).await.unwrap();
+
+        let ws_manager = WebSocketManager::new();
+        let mut sender = ws_manager
+            .add_authenticated_connection("sender".to_string(), Some("room-1".to_string()), "user-alice".to_string(), None)
+            .await;
+        let mut peer = ws_manager
+            .add_connection("peer".to_string(), Some("room-1".to_string()), None, None)
+            .await;
+        EntitySync::new(Arc::new(transport.clone()))
+            .start(&ws_manager)
+            .await
+            .unwrap();
+
+        // The client's spawn goes to the hub, not the room
+        let spawn = MessageBuilder::entity_spawn("box".to_string(), "cube".to_string(), "alice".to_string(), HashMap::new());
+        let handlers = ws_manager.handlers().await;
+        route_message(&handlers, &ws_manager, "room-1", "sender", &MessageParser::serialize(&spawn).unwrap())
+            .await
+            .unwrap();
+        assert!(next_message(&mut peer).await.is_none());
+
+        let (_, payload) = timeout(Duration::from_secs(1), hub.recv()).await.unwrap().unwrap();
+        let request: EntitySyncRequest = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(request.room_id, "room-1");
+        assert_eq!(request.author_id.as_deref(), Some("user-alice"));
+
+        // Once applied, everyone in the room sees it, the sender included
+        let event = EntitySyncEvent {
+            room_id: request.room_id,
+            seq: 1,
+            author_id: "user-alice".to_string(),
+            change: request.change,
+            timestamp: 0,
+        };
+        transport
+            .publish(ENTITY_EVENTS_CHANNEL, serde_json::to_vec(&event).unwrap())
+            .await
+            .unwrap();
+        for rx in [&mut sender, &mut peer] {
+            let message = timeout(Duration::from_secs(1), async {
+                loop {
+                    if let Some(message) = next_message(rx).await {
+                        return message;
+                    }
+                }
+            })
+            .await
+            .unwrap();
+            assert!(matches!(message.payload, Some(Payload::EntitySpawn(spawn)) if spawn.entity_id == "box"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_disguised_entity_changes_are_not_relayed() {
+        let transport = MemoryTransport::new();
+        let mut hub = transport.psubscribe(ENTITY_REQUESTS_CHANNEL).await.unwrap();
+
+        let ws_manager = WebSocketManager::new();
+        let _sender = ws_manager
+            .add_authenticated_connection("sender".to_string(), Some("room-1".to_string()), "user-alice".to_string(), None)
+            .await;
+        let mut peer = ws_manager
+            .add_connection("peer".to_string(), Some("room-1".to_string()), None, None)
+            .await;
+        EntitySync::new(Arc::new(transport.clone()))
+            .start(&ws_manager)
+            .await
+            .unwrap();
+
+        // A spawn sent as voice would bypass the hub and reach the room as if applied
+        let mut spawn = MessageBuilder::entity_spawn("box".to_string(), "cube".to_string(), "alice".to_string(), HashMap::new());
+        spawn.r#type = MessageType::VoiceData as i32;
+        let handlers = ws_manager.handlers().await;
+        route_message(&handlers, &ws_manager, "room-1", "sender", &MessageParser::serialize(&spawn).unwrap())
+            .await
+            .unwrap();
+
+        assert!(next_message(&mut peer).await.is_none());
+        assert!(timeout(Duration::from_millis(100), hub.recv()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_claimed_user_ids_author_nothing() {
+        let transport = MemoryTransport::new();
+        let mut hub = transport.psubscribe(ENTITY_REQUESTS_CHANNEL).await.unwrap();
+
+        let ws_manager = WebSocketManager::new();
+        let _claimant = ws_manager
+            .add_connection("claimant".to_string(), Some("room-1".to_string()), Some("user-alice".to_string()), Some("bob".to_string()))
+            .await;
+        EntitySync::new(Arc::new(transport.clone()))
+            .start(&ws_manager)
+            .await
+            .unwrap();
+
+        let despawn = MessageParser::serialize(&MessageBuilder::entity_despawn("box".to_string())).unwrap();
+        let handlers = ws_manager.handlers().await;
+        route_message(&handlers, &ws_manager, "room-1", "claimant", &despawn)
+            .await
+            .unwrap();
+
+        let (_, payload) = timeout(Duration::from_secs(1), hub.recv()).await.unwrap().unwrap();
+        let request: EntitySyncRequest = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(request.author_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_undo_requests_go_to_the_hub() {
+        let transport = MemoryTransport::new();
+        let mut hub = transport.psubscribe(ENTITY_HISTORY_CHANNEL).await.unwrap();
+
+        let ws_manager = WebSocketManager::new();
+        let _sender = ws_manager
+            .add_authenticated_connection("sender".to_string(), Some("room-1".to_string()), "user-alice".to_string(), None)
+            .await;
+        let mut anonymous = ws_manager
+            .add_connection("anonymous".to_string(), Some("room-1".to_string()), None, Some("bob".to_string()))
+            .await;
+        let mut claimant = ws_manager
+            .add_connection("claimant".to_string(), Some("room-1".to_string()), Some("user-alice".to_string()), None)
+            .await;
+        EntitySync::new(Arc::new(transport.clone()))
+            .start(&ws_manager)
+            .await
+            .unwrap();
+        let handlers = ws_manager.handlers().await;
+
+        let undo = MessageParser::serialize(&MessageBuilder::entity_history(false)).unwrap();
+        route_message(&handlers, &ws_manager, "room-1", "sender", &undo)
+            .await
+            .unwrap();
+        let (_, payload) = timeout(Duration::from_secs(1), hub.recv()).await.unwrap().unwrap();
+        let request: EntityHistoryRequest = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(request.room_id, "room-1");
+        assert_eq!(request.user_id, "user-alice");
+        assert_eq!(request.step, HistoryStep::Undo);
+
+        // Neither a client id nor a claimed user id reaches anyone's history
+        for (conn_id, rx) in [("anonymous", &mut anonymous), ("claimant", &mut claimant)] {
+            route_message(&handlers, &ws_manager, "room-1", conn_id, &undo)
+                .await
+                .unwrap();
+            assert!(matches!(
+                next_message(rx).await.and_then(|m| m.payload),
+                Some(Payload::ErrorFrame(error)) if error.code == "HISTORY_REJECTED"
+            ));
+        }
+        assert!(timeout(Duration::from_millis(100), hub.recv()).await.is_err());
+    }
+}
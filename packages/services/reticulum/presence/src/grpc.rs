@@ -0,0 +1,102 @@
+//! gRPC presence service
+//!
+//! Serves `PresenceService.WatchPresence` from the presence feed, so observers
+//! can follow a room without a client connection. Like the server-sent event
+//! stream, it is public and needs no token. The server only runs when
+//! `PRESENCE_GRPC_PORT` is set.
+
+use futures::Stream;
+use graphwiz_protocol::generated::graphwiz::networking::presence_service_server::{
+    PresenceService, PresenceServiceServer,
+};
+use graphwiz_protocol::generated::graphwiz::networking::PresenceRequest;
+use graphwiz_protocol::PresenceEvent;
+use reticulum_core::{Error, Result};
+use std::net::SocketAddr;
+use std::pin::Pin;
+use tonic::{Request, Response, Status};
+
+use crate::presence_feed::PresenceFeed;
+
+type EventStream<T> = Pin<Box<dyn Stream<Item = std::result::Result<T, Status>> + Send>>;
+
+/// Presence service backed by this instance's presence feed
+#[derive(Clone)]
+pub struct PresenceGrpcService {
+    feed: PresenceFeed,
+}
+
+impl PresenceGrpcService {
+    pub fn new(feed: PresenceFeed) -> Self {
+        Self { feed }
+    }
+
+    /// Serve the presence service on `port` until the server fails
+    pub async fn serve(self, port: u16) -> Result<()> {
+        let addr = SocketAddr::from(([0, 0, 0, 0], port));
+        log::info!("Starting gRPC presence service on {}", addr);
+        tonic::transport::Server::builder()
+            .add_service(PresenceServiceServer::new(self))
+            .serve(addr)
+            .await
+            .map_err(|e| Error::internal(format!("gRPC server failed: {}", e)))
+    }
+}
+
+#[tonic::async_trait]
+impl PresenceService for PresenceGrpcService {
+    type WatchPresenceStream = EventStream<PresenceEvent>;
+
+    async fn watch_presence(
+        &self,
+        request: Request<PresenceRequest>,
+    ) -> std::result::Result<Response<Self::WatchPresenceStream>, Status> {
+        let room_id = request.into_inner().room_id;
+        if room_id.is_empty() {
+            return Err(Status::invalid_argument("room_id is required"));
+        }
+
+        let events = self.feed.subscribe(&room_id);
+        let stream = futures::stream::unfold(events, |mut events| async move {
+            let event = events.next().await?;
+            Some((Ok(event), events))
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use graphwiz_protocol::{MessageBuilder, MessageParser, PresenceEventType};
+
+    #[tokio::test]
+    async fn test_watch_presence_requires_room() {
+        let service = PresenceGrpcService::new(PresenceFeed::new());
+        let status = service
+            .watch_presence(Request::new(PresenceRequest { room_id: String::new() }))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_watch_presence_streams_room_events() {
+        let feed = PresenceFeed::new();
+        let service = PresenceGrpcService::new(feed.clone());
+        let mut stream = service
+            .watch_presence(Request::new(PresenceRequest { room_id: "lobby".to_string() }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let leave = MessageBuilder::presence_event("c1".to_string(), PresenceEventType::Leave, None);
+        feed.publish("lobby", &MessageParser::serialize(&leave).unwrap());
+
+        let event = stream.next().await.unwrap().unwrap();
+        assert_eq!(event.client_id, "c1");
+        assert_eq!(event.event_type, PresenceEventType::Leave as i32);
+    }
+}
@@ -1,13 +1,15 @@
 //! HTTP handlers for presence service
 
-use actix_web::{web, HttpRequest, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use reticulum_core::models::ModerationActionType;
 use serde::Deserialize;
 
+use crate::drain::DrainController;
+use crate::moderation::ModerationManager;
+use crate::redis::ClusterBroadcaster;
 use crate::session::SessionManager;
-use crate::signaling::SignalingMessage;
-use crate::webtransport::{SessionInfo, WebTransportManager};
-use reticulum_core::Config;
-use std::sync::Arc;
+use crate::websocket::WebSocketManager;
+use crate::webtransport::WebTransportManager;
 
 #[derive(Debug, Deserialize)]
 pub struct ConnectRequest {
@@ -20,18 +22,8 @@ pub struct ConnectRequest {
 pub async fn connect_webtransport(
     req: HttpRequest,
     webtransport_manager: web::Data<WebTransportManager>,
+    body: web::Json<ConnectRequest>,
 ) -> HttpResponse {
-    // Parse request body for connection details
-    let body = match req.extract::<web::Json<ConnectRequest>>().await {
-        Ok(json) => json.0,
-        Err(e) => {
-            log::error!("Failed to parse connect request: {}", e);
-            return HttpResponse::BadRequest().json(serde_json::json!({
-                "status": "error",
-                "message": "Invalid request body"
-            }));
-        }
-    };
 
     log::info!(
         "WebTransport connection requested: room={}, client_id={}",
@@ -39,49 +31,48 @@ pub async fn connect_webtransport(
         body.client_id
     );
 
-    // WebTransport connections use HTTP/3 directly on port 4443
-    // This endpoint confirms WebTransport is available
+    // WebTransport connections use HTTP/3 directly; this endpoint tells the
+    // client where to connect and which certificate to pin
+    let Some(addr) = webtransport_manager.local_addr().await else {
+        return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+            "status": "webtransport_unavailable",
+            "message": "WebTransport server is not running, use the WebSocket endpoint"
+        }));
+    };
+
+    let host = req
+        .connection_info()
+        .host()
+        .split(':')
+        .next()
+        .unwrap_or("localhost")
+        .to_string();
+
     HttpResponse::Ok().json(serde_json::json!({
         "status": "webtransport_available",
         "message": "WebTransport endpoint ready",
-        "hint": "Client should initiate WebTransport connection via HTTP/3 on port 4443"
+        "url": format!(
+            "https://{}:{}/presence/{}?client_id={}&user_id={}",
+            host,
+            addr.port(),
+            body.room_id,
+            body.client_id,
+            body.user_id
+        ),
+        "certificate_hash": webtransport_manager.certificate_hash().await
     }))
 }
 
 /// Handle regular HTTP connection request (WebSocket fallback)
-pub async fn connect(
-    config: web::Data<Config>,
-    session_manager: web::Data<SessionManager>,
-    req: HttpRequest,
-) -> HttpResponse {
-    // Check if WebTransport should be used
-    let use_webtransport = config
-        .get("USE_WEBTRANSPORT")
-        .and_then(|v| if v == "true" { Some(true) } else { None })
-        .unwrap_or(false);
-
-    if use_webtransport {
-        log::warn!("WebTransport requested but not yet supported via HTTP - use WS endpoint");
-        // WebTransport connections use HTTP/3 directly, not HTTP
-        // For now, fall back to WebSocket
-    }
-
-    // Parse request body for WebSocket connection details
-    let body: serde_json::Value = match actix_web::web::body::to_bytes(req).await {
-        Ok(bytes) => match serde_json::from_slice(&bytes) {
-            Ok(v) => v,
-            Err(e) => {
-                log::error!("Failed to parse request body: {}", e);
-                return HttpResponse::BadRequest().json(serde_json::json!({
-                    "error": "Invalid request body",
-                    "details": e.to_string()
-                }));
-            }
-        },
+pub async fn connect(session_manager: web::Data<SessionManager>, bytes: web::Bytes) -> HttpResponse {
+    // Parse request body for WebSocket connection details; WebTransport
+    // clients use `/webtransport/connect` instead
+    let body: serde_json::Value = match serde_json::from_slice(&bytes) {
+        Ok(v) => v,
         Err(e) => {
-            log::error!("Failed to read request body: {}", e);
+            log::error!("Failed to parse request body: {}", e);
             return HttpResponse::BadRequest().json(serde_json::json!({
-                "error": "Failed to read request",
+                "error": "Invalid request body",
                 "details": e.to_string()
             }));
         }
@@ -96,14 +87,14 @@ pub async fn connect(
     let client_id = body
         .get("client_id")
         .and_then(|v| v.as_str())
-        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
-        .to_string();
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
 
     let user_id = body
         .get("user_id")
         .and_then(|v| v.as_str())
-        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
-        .to_string();
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
 
     // Add WebSocket session
     let ws_session_id = uuid::Uuid::new_v4().to_string();
@@ -136,17 +127,84 @@ pub async fn connect(
     }))
 }
 
-/// Health check handler
-pub async fn health() -> HttpResponse {
+/// Keep a session from `connect` alive; sessions without a heartbeat or a
+/// live connection are reaped
+pub async fn session_heartbeat(
+    session_manager: web::Data<SessionManager>,
+    session_id: web::Path<String>,
+) -> HttpResponse {
+    match session_manager.update_heartbeat(&session_id).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => actix_web::ResponseError::error_response(&e),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MuteRequest {
+    pub muted: bool,
+}
+
+/// Mute or unmute the voice of a session from `connect`; the voice relay
+/// drops a muted client's frames. Only the session's user, or someone who
+/// may mute in its room, may do so.
+pub async fn set_session_muted(
+    req: HttpRequest,
+    session_manager: web::Data<SessionManager>,
+    moderation: web::Data<ModerationManager>,
+    ws_manager: web::Data<WebSocketManager>,
+    session_id: web::Path<String>,
+    body: web::Json<MuteRequest>,
+) -> HttpResponse {
+    let caller = match moderation.authenticate(&req) {
+        Ok(caller) => caller,
+        Err(e) => return e.error_response(),
+    };
+    let Some(session) = session_manager.get_session(&session_id).await else {
+        return reticulum_core::Error::not_found(format!("Session {} not found", session_id)).error_response();
+    };
+
+    if session.user_id != caller.user_id {
+        let room_id = session.room_id.as_deref().unwrap_or_default();
+        let action = if body.muted { ModerationActionType::Mute } else { ModerationActionType::Unmute };
+        match moderation.authority(&ws_manager, &caller, room_id).await {
+            Ok(authority) if authority.permits(action) => {}
+            Ok(_) => {
+                return reticulum_core::Error::authorization(format!("Not allowed to mute session {}", session_id))
+                    .error_response();
+            }
+            Err(e) => return e.error_response(),
+        }
+    }
+
+    match session_manager.set_muted(&session_id, body.muted).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => e.error_response(),
+    }
+}
+
+/// Health check handler; unhealthy while draining, so load balancers stop
+/// sending clients here
+pub async fn health(drain: web::Data<DrainController>) -> HttpResponse {
+    if drain.is_draining() {
+        return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+            "status": "draining"
+        }));
+    }
+
     HttpResponse::Ok().json(serde_json::json!({
         "status": "healthy"
     }))
 }
 
+/// Cluster membership and pub/sub health of this instance
+pub async fn get_cluster_status(cluster: web::Data<ClusterBroadcaster>) -> HttpResponse {
+    HttpResponse::Ok().json(cluster.status().await)
+}
+
 /// Get all room clients (legacy handler for compatibility)
 pub async fn get_room_clients(room_id: web::Path<String>) -> HttpResponse {
     HttpResponse::Ok().json(serde_json::json!({
         "room_id": room_id.into_inner(),
-        "clients": vec![]
+        "clients": Vec::<String>::new()
     }))
 }
@@ -0,0 +1,142 @@
+//! Room host election
+//!
+//! The first client to join a room hosts it. When the host leaves, the
+//! remaining client whose role ranks highest takes over, ties going to the
+//! earliest joiner. The room is told who the new host is and the hub hands
+//! them the previous host's entities.
+//!
+//! Roles and entities only go to users proven by an access token. Clients
+//! that merely claim a user id rank by join order alone.
+
+use reticulum_core::models::UserRole;
+use serde::Serialize;
+
+/// Host reassignment after the previous host left a room
+#[derive(Debug, Clone, Serialize)]
+pub struct HostMigration {
+    pub room_id: String,
+    pub previous_host: String,
+    pub new_host: Option<String>, // None when the room is now empty
+}
+
+/// Host election priority for a role; higher values are preferred
+pub fn host_priority_for_role(role: &UserRole) -> i32 {
+    match role {
+        UserRole::Admin => 2,
+        UserRole::Moderator => 1,
+        UserRole::User => 0,
+    }
+}
+
+/// Elect a host from candidates listed in join order.
+///
+/// The highest priority wins; ties go to the earliest joiner, so the result is
+/// deterministic for a given room membership.
+pub fn elect_host<'a>(candidates: impl IntoIterator<Item = (&'a str, i32)>) -> Option<String> {
+    let mut best: Option<(&str, i32)> = None;
+    for (client_id, priority) in candidates {
+        if best.is_none_or(|(_, best_priority)| priority > best_priority) {
+            best = Some((client_id, priority));
+        }
+    }
+    best.map(|(client_id, _)| client_id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redis::{MemoryTransport, PubSubTransport};
+    use crate::websocket::{close_connection, WebSocketManager};
+    use reticulum_core::occupancy::{HostHandoff, HOST_CHANNEL};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::time::timeout;
+
+    async fn join(ws_manager: &WebSocketManager, client_id: &str) {
+        let _ = ws_manager
+            .add_authenticated_connection(
+                format!("conn-{}", client_id),
+                Some("room-1".to_string()),
+                format!("user-{}", client_id),
+                Some(client_id.to_string()),
+            )
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_host_migrates_by_join_order() {
+        let ws_manager = WebSocketManager::new();
+        for client_id in ["a", "b", "c"] {
+            join(&ws_manager, client_id).await;
+        }
+        assert_eq!(ws_manager.get_room_host("room-1").await.as_deref(), Some("a"));
+
+        // Non-host departures leave the host alone
+        close_connection(&ws_manager, "conn-b").await;
+        assert_eq!(ws_manager.get_room_host("room-1").await.as_deref(), Some("a"));
+
+        close_connection(&ws_manager, "conn-a").await;
+        assert_eq!(ws_manager.get_room_host("room-1").await.as_deref(), Some("c"));
+
+        // Last one out clears the host
+        close_connection(&ws_manager, "conn-c").await;
+        assert!(ws_manager.get_room_host("room-1").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_host_migration_prefers_role_priority() {
+        let ws_manager = WebSocketManager::new();
+        for client_id in ["host", "user", "moderator"] {
+            join(&ws_manager, client_id).await;
+        }
+        ws_manager
+            .set_host_priority("conn-moderator", host_priority_for_role(&UserRole::Moderator))
+            .await;
+
+        close_connection(&ws_manager, "conn-host").await;
+        assert_eq!(ws_manager.get_room_host("room-1").await.as_deref(), Some("moderator"));
+    }
+
+    #[tokio::test]
+    async fn test_new_host_is_handed_to_the_hub() {
+        let ws_manager = WebSocketManager::new();
+        let transport = Arc::new(MemoryTransport::new());
+        let mut handoffs = transport.psubscribe(HOST_CHANNEL).await.unwrap();
+        ws_manager.attach_hub(transport).await;
+        for client_id in ["a", "b"] {
+            join(&ws_manager, client_id).await;
+        }
+
+        close_connection(&ws_manager, "conn-a").await;
+
+        let (_, payload) = timeout(Duration::from_secs(1), handoffs.recv()).await.unwrap().unwrap();
+        let handoff: HostHandoff = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(handoff.instance_id, "room-1");
+        assert_eq!(handoff.new_host, "user-b");
+    }
+
+    #[tokio::test]
+    async fn test_claimed_user_ids_take_no_entities() {
+        let ws_manager = WebSocketManager::new();
+        let transport = Arc::new(MemoryTransport::new());
+        let mut handoffs = transport.psubscribe(HOST_CHANNEL).await.unwrap();
+        ws_manager.attach_hub(transport).await;
+        join(&ws_manager, "a").await;
+        let _ = ws_manager
+            .add_connection("conn-b".to_string(), Some("room-1".to_string()), Some("user-admin".to_string()), Some("b".to_string()))
+            .await;
+        assert!(!ws_manager.get_connection_info("conn-b").await.unwrap().authenticated);
+
+        // The claimant hosts by join order but the hub is not told
+        close_connection(&ws_manager, "conn-a").await;
+        assert_eq!(ws_manager.get_room_host("room-1").await.as_deref(), Some("b"));
+        assert!(timeout(Duration::from_millis(100), handoffs.recv()).await.is_err());
+    }
+
+    #[test]
+    fn test_elect_host_ties_go_to_earliest_joiner() {
+        assert_eq!(elect_host([("a", 0), ("b", 0)]).as_deref(), Some("a"));
+        assert_eq!(elect_host([("a", 0), ("b", 1), ("c", 1)]).as_deref(), Some("b"));
+        assert_eq!(elect_host(std::iter::empty()), None);
+    }
+}
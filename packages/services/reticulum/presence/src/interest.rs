@@ -0,0 +1,226 @@
+//! Area-of-interest filtering for position updates
+//!
+//! Each connection's position is taken from the last `PositionUpdate` it sent.
+//! A receiver gets every update from senders that are close or speaking, a
+//! reduced rate from senders inside its view radius, and a trickle (or
+//! nothing) from senders beyond it. Rates are limited per receiver and entity,
+//! so a sender moving several entities does not starve any of them.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Tuning for interest management
+#[derive(Debug, Clone)]
+pub struct InterestConfig {
+    /// View radius for connections that did not choose one
+    pub default_view_radius: f32,
+    /// Senders within this distance are always delivered at full rate
+    pub full_rate_radius: f32,
+    /// Minimum interval between updates from senders inside the view radius
+    pub reduced_interval: Duration,
+    /// Minimum interval between updates from senders beyond the view radius;
+    /// `None` drops them entirely
+    pub distant_interval: Option<Duration>,
+    /// How long a sender counts as speaking after its last voice packet
+    pub speaking_window: Duration,
+}
+
+impl Default for InterestConfig {
+    fn default() -> Self {
+        Self {
+            default_view_radius: 30.0,
+            full_rate_radius: 8.0,
+            reduced_interval: Duration::from_millis(200),
+            distant_interval: Some(Duration::from_secs(1)),
+            speaking_window: Duration::from_millis(750),
+        }
+    }
+}
+
+/// Priority tier of a sender as seen by one receiver
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterestTier {
+    Full,
+    Reduced,
+    Distant,
+}
+
+/// Positions, view radii and delivery history for the connections of a service
+pub struct InterestState {
+    config: InterestConfig,
+    positions: HashMap<String, [f32; 3]>, // conn_id -> last reported position
+    view_radii: HashMap<String, f32>, // conn_id -> view radius
+    speaking_until: HashMap<String, Instant>, // conn_id -> end of speaking window
+    last_sent: HashMap<(String, String), Instant>, // (receiver, entity_id) -> last delivery
+}
+
+impl InterestState {
+    pub fn new(config: InterestConfig) -> Self {
+        Self {
+            config,
+            positions: HashMap::new(),
+            view_radii: HashMap::new(),
+            speaking_until: HashMap::new(),
+            last_sent: HashMap::new(),
+        }
+    }
+
+    pub fn update_position(&mut self, conn_id: &str, position: [f32; 3]) {
+        self.positions.insert(conn_id.to_string(), position);
+    }
+
+    /// Last position `conn_id` reported
+    pub fn position(&self, conn_id: &str) -> Option<[f32; 3]> {
+        self.positions.get(conn_id).copied()
+    }
+
+    pub fn set_view_radius(&mut self, conn_id: &str, radius: f32) {
+        self.view_radii.insert(conn_id.to_string(), radius.max(0.0));
+    }
+
+    /// Record voice activity so the sender is delivered at full rate
+    pub fn mark_speaking(&mut self, conn_id: &str, now: Instant) {
+        self.speaking_until
+            .insert(conn_id.to_string(), now + self.config.speaking_window);
+    }
+
+    /// Tier of `sender` for `receiver`. Unknown positions get full rate so
+    /// new arrivals are never starved.
+    pub fn tier(&self, receiver: &str, sender: &str, now: Instant) -> InterestTier {
+        if self.speaking_until.get(sender).is_some_and(|until| *until > now) {
+            return InterestTier::Full;
+        }
+
+        let (Some(from), Some(to)) = (self.positions.get(receiver), self.positions.get(sender)) else {
+            return InterestTier::Full;
+        };
+
+        let distance = from
+            .iter()
+            .zip(to.iter())
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum::<f32>()
+            .sqrt();
+        let view_radius = self
+            .view_radii
+            .get(receiver)
+            .copied()
+            .unwrap_or(self.config.default_view_radius);
+
+        if distance <= self.config.full_rate_radius.min(view_radius) {
+            InterestTier::Full
+        } else if distance <= view_radius {
+            InterestTier::Reduced
+        } else {
+            InterestTier::Distant
+        }
+    }
+
+    /// Whether an update of `entity_id` from `sender` should go to `receiver`
+    /// now; records the delivery when it should
+    pub fn should_deliver(&mut self, receiver: &str, sender: &str, entity_id: &str, now: Instant) -> bool {
+        let interval = match self.tier(receiver, sender, now) {
+            InterestTier::Full => return true,
+            InterestTier::Reduced => self.config.reduced_interval,
+            InterestTier::Distant => match self.config.distant_interval {
+                Some(interval) => interval,
+                None => return false,
+            },
+        };
+
+        let key = (receiver.to_string(), entity_id.to_string());
+        if self
+            .last_sent
+            .get(&key)
+            .is_some_and(|sent| now.duration_since(*sent) < interval)
+        {
+            return false;
+        }
+
+        self.last_sent.insert(key, now);
+        true
+    }
+
+    /// Forget a connection that left
+    pub fn remove(&mut self, conn_id: &str) {
+        self.positions.remove(conn_id);
+        self.view_radii.remove(conn_id);
+        self.speaking_until.remove(conn_id);
+        self.last_sent.retain(|(receiver, _), _| receiver != conn_id);
+    }
+}
+
+impl Default for InterestState {
+    fn default() -> Self {
+        Self::new(InterestConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_with(positions: &[(&str, [f32; 3])]) -> InterestState {
+        let mut state = InterestState::default();
+        for (conn_id, position) in positions {
+            state.update_position(conn_id, *position);
+        }
+        state
+    }
+
+    #[test]
+    fn test_tiers_by_distance() {
+        let state = state_with(&[
+            ("me", [0.0, 0.0, 0.0]),
+            ("near", [3.0, 0.0, 4.0]),
+            ("mid", [20.0, 0.0, 0.0]),
+            ("far", [100.0, 0.0, 0.0]),
+        ]);
+        let now = Instant::now();
+
+        assert_eq!(state.tier("me", "near", now), InterestTier::Full);
+        assert_eq!(state.tier("me", "mid", now), InterestTier::Reduced);
+        assert_eq!(state.tier("me", "far", now), InterestTier::Distant);
+        assert_eq!(state.tier("me", "unknown", now), InterestTier::Full);
+    }
+
+    #[test]
+    fn test_speaking_and_view_radius() {
+        let mut state = state_with(&[("me", [0.0, 0.0, 0.0]), ("far", [100.0, 0.0, 0.0])]);
+        let now = Instant::now();
+
+        state.mark_speaking("far", now);
+        assert_eq!(state.tier("me", "far", now), InterestTier::Full);
+        assert_eq!(state.tier("me", "far", now + Duration::from_secs(1)), InterestTier::Distant);
+
+        state.set_view_radius("me", 150.0);
+        assert_eq!(state.tier("me", "far", now + Duration::from_secs(1)), InterestTier::Reduced);
+    }
+
+    #[test]
+    fn test_reduced_rate_per_entity() {
+        let mut state = state_with(&[("me", [0.0, 0.0, 0.0]), ("mid", [20.0, 0.0, 0.0])]);
+        let now = Instant::now();
+
+        assert!(state.should_deliver("me", "mid", "avatar", now));
+        assert!(!state.should_deliver("me", "mid", "avatar", now + Duration::from_millis(50)));
+        // Another entity from the same sender has its own budget
+        assert!(state.should_deliver("me", "mid", "hand", now + Duration::from_millis(50)));
+        assert!(state.should_deliver("me", "mid", "avatar", now + Duration::from_millis(200)));
+    }
+
+    #[test]
+    fn test_distant_updates_can_be_culled() {
+        let mut state = InterestState::new(InterestConfig {
+            distant_interval: None,
+            ..Default::default()
+        });
+        state.update_position("me", [0.0, 0.0, 0.0]);
+        state.update_position("far", [100.0, 0.0, 0.0]);
+
+        assert!(!state.should_deliver("me", "far", "avatar", Instant::now()));
+
+        state.remove("far");
+        assert!(state.should_deliver("me", "far", "avatar", Instant::now()));
+    }
+}
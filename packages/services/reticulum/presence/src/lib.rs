@@ -2,30 +2,63 @@
 //!
 //! Handles WebTransport/WebRTC signaling, presence tracking, and real-time messaging
 
+pub mod admin_handlers;
+pub mod chat;
+pub mod chat_handlers;
 pub mod session;
 pub mod signaling;
+pub mod spatial;
+pub mod tick;
 pub mod websocket;
+pub mod drain;
+pub mod entity_sync;
+pub mod grpc;
 pub mod handlers;
+pub mod host;
+pub mod interest;
+pub mod liveness;
+pub mod metrics;
+pub mod moderation;
 pub mod moderation_handlers;
+pub mod presence_feed;
+pub mod presence_feed_handlers;
+pub mod protobuf;
+pub mod queue;
+pub mod rate_limit;
+pub mod recording;
+pub mod recording_handlers;
 pub mod routes;
 pub mod redis;
+pub mod resume;
+pub mod user_presence;
+pub mod user_presence_handlers;
+pub mod voice;
 pub mod webtransport;
 
 use actix_web::{web, App, HttpServer};
-use reticulum_core::Config;
+use reticulum_core::{db, Config};
 
+use chat::ChatService;
+use drain::{DrainController, DrainRequest};
+use grpc::PresenceGrpcService;
+use moderation::ModerationManager;
+use recording::SessionRecorder;
 use routes::configure_routes;
 use session::SessionManager;
+use signaling::SignalingServer;
+use user_presence::PresenceDirectory;
+use voice::VoiceRelay;
+use websocket::WebSocketManager;
 
 pub struct PresenceService {
     config: Config,
     ws_manager: WebSocketManager,
-    pubsub: redis::RedisPubSub,
+    cluster: redis::ClusterBroadcaster,
     webtransport_manager: webtransport::WebTransportManager,
 }
 
 impl PresenceService {
-    pub fn new(config: Config) -> Self {
+    pub async fn new(config: Config) -> Self {
         let ws_manager = WebSocketManager::new();
 
         let redis_config = redis::RedisConfig {
@@ -34,31 +67,21 @@ impl PresenceService {
             channel_prefix: "graphwiz".to_string(),
         };
 
-        let (message_tx, _) = tokio::sync::mpsc::channel::<redis::PubSubMessage>(1000);
+        // Falls back to an in-process transport when Redis is unavailable
+        let mut cluster = redis::ClusterBroadcaster::connect(redis_config).await;
 
-        let pubsub = match redis::RedisPubSub::new(redis_config, message_tx).await {
-            Ok(pubsub) => pubsub,
-            Err(e) => {
-                log::warn!("Failed to initialize Redis pub/sub: {}. Using fallback mode.", e);
-                // Create a disabled pub/sub instance
-                redis::RedisPubSub {
-                    config: redis_config,
-                    client: Arc::new(redis::Client::open(&redis_config.url).unwrap_or_else(|_| {
-                        redis::Client::open("redis://127.0.0.1:6379").unwrap()
-                    })),
-                    _subscriber_task: None,
-                }
-            }
-        };
+        // Where clients of draining instances can reconnect to this one
+        if let Ok(url) = std::env::var("PRESENCE_PUBLIC_URL") {
+            cluster = cluster.with_public_url(url);
+        }
 
-        // Create WebTransport manager
-        let webtransport_config = webtransport::ServerConfig::default();
-        let webtransport_manager = webtransport::WebTransportManager::new(webtransport_config);
+        // WebTransport sessions join the same rooms as WebSocket clients
+        let webtransport_manager = webtransport::WebTransportManager::new(ws_manager.clone());
 
         Self {
             config,
             ws_manager,
-            pubsub,
+            cluster,
             webtransport_manager,
         }
     }
@@ -72,84 +95,129 @@ impl PresenceService {
 
         // Create and start session manager
         let session_manager = SessionManager::new();
-        tokio::spawn(async move {
-            session_manager.start_background_flush_task().await;
-        });
+        session_manager.start_background_flush_task();
 
-        HttpServer::new(move || {
-            App::new()
-                .app_data(web::Data::new(self.config.clone()))
-                .app_data(web::Data::new(session_manager.clone()))
-                .app_data(web::Data::new(self.pubsub.clone()))
-                .app_data(web::Data::new(self.webtransport_manager.clone()))
-                .wrap(actix_cors::Cors::permissive())
-                .wrap(reticulum_core::middleware::LoggingMiddleware)
-                .configure(configure_routes)
-        })
-        .bind((host.as_str(), port))?
-        .workers(workers)
-        .run()
-        .await
-    }
-}
+        // Close WebSocket sessions that were not resumed within the grace period
+        self.ws_manager.start_resume_expiry_task();
 
-impl PresenceService {
-    pub fn new(config: Config) -> Self {
-        let ws_manager = WebSocketManager::new();
+        // Close connections and sessions that stopped showing signs of life
+        liveness::Reaper::new(self.ws_manager.clone(), session_manager.clone()).start();
 
-        let redis_config = redis::RedisConfig {
-            url: std::env::var("REDIS_URL")
-                .unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string()),
-            channel_prefix: "graphwiz".to_string(),
-        };
+        // Closed connections free their hub seats, and hosts elected on host
+        // departures take over the previous host's entities in the hub
+        self.ws_manager.attach_hub(self.cluster.transport()).await;
+
+        // Share room broadcasts with the other presence instances
+        if let Err(e) = self.cluster.start(&self.ws_manager).await {
+            log::warn!("Cluster fan-out unavailable, broadcasts stay on this instance: {}", e);
+        }
+
+        // Move sessions to the other instances before shutting down, and take
+        // over those of draining peers
+        let drain = DrainController::new(self.cluster.clone());
+        if let Err(e) = drain.start(&self.ws_manager).await {
+            log::warn!("Session migration unavailable, draining will close connections: {}", e);
+        }
 
-        let (message_tx, _) = tokio::sync::mpsc::channel::<redis::PubSubMessage>(1000);
+        // Entity changes go through the hub, which is only reachable over Redis
+        if self.cluster.transport().name() == "redis" {
+            let entity_sync = entity_sync::EntitySync::new(self.cluster.transport());
+            if let Err(e) = entity_sync.start(&self.ws_manager).await {
+                log::warn!("Entity sync with the hub unavailable, entity changes stay in the room: {}", e);
+            }
+        }
 
-        let pubsub = match redis::RedisPubSub::new(redis_config, message_tx).await {
-            Ok(pubsub) => pubsub,
+        // Moderation and chat keep their state in memory without a database
+        let db = match db::connect(&self.config).await {
+            Ok(db) => Some(db),
             Err(e) => {
-                log::warn!("Failed to initialize Redis pub/sub: {}. Using fallback mode.", e);
-                // Create a disabled pub/sub instance
-                redis::RedisPubSub {
-                    config: redis_config,
-                    client: Arc::new(redis::Client::open(&redis_config.url).unwrap_or_else(|_| {
-                        redis::Client::open("redis://127.0.0.1:6379").unwrap()
-                    })),
-                    _subscriber_task: None,
-                }
+                log::warn!("Database unavailable, moderation actions and chat history will not be persisted: {}", e);
+                None
             }
         };
 
-        // Create WebTransport manager
-        let webtransport_config = webtransport::ServerConfig::default();
-        let webtransport_manager = webtransport::WebTransportManager::new(webtransport_config);
+        // Record rooms on request and keep replay spectators from taking part;
+        // started before other handlers so spectators' messages go no further
+        let recorder = SessionRecorder::from_env();
+        recorder.start(&self.ws_manager).await;
 
-        Self {
-            config,
-            ws_manager,
-            pubsub,
-            webtransport_manager,
+        // Enforce mutes, bans and room locks recorded in the moderation log
+        let moderation = ModerationManager::new(db.clone(), self.config.clone())
+            .with_transport(self.cluster.instance_id(), self.cluster.transport());
+        moderation.start(&self.ws_manager).await;
+
+        // Filter, store and route chat, replaying history to joining clients
+        let chat = ChatService::from_env(db);
+        chat.start(&self.ws_manager).await;
+
+        // Share signed-in users' status with subscribers in any room
+        let mut user_presence = PresenceDirectory::new(self.cluster.instance_id(), self.cluster.transport());
+        if let Some(secs) = std::env::var("PRESENCE_IDLE_TIMEOUT_SECS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+        {
+            user_presence = user_presence.with_idle_timeout(std::time::Duration::from_secs(secs));
+        }
+        if let Err(e) = user_presence.start(&self.ws_manager).await {
+            log::warn!("User presence unavailable: {}", e);
         }
-    }
 
-    pub async fn run(self) -> std::io::Result<()> {
-        let host = self.config.server.host.clone();
-        let port = self.config.server.port;
-        let workers = self.config.server.workers.unwrap_or(1);
+        // Relay WebRTC signals between peers on any instance
+        let signaling = SignalingServer::new(self.cluster.instance_id(), self.cluster.transport());
+        if let Err(e) = signaling.start(&self.ws_manager).await {
+            log::warn!("WebRTC signaling unavailable: {}", e);
+        }
 
-        log::info!("Starting presence service on {}:{}", host, port);
+        // Relay voice for clients that cannot use WebRTC, mixing the loudest
+        // speakers for listeners on weak connections
+        let mut voice = VoiceRelay::new(session_manager.clone());
+        if let Some(speakers) = std::env::var("VOICE_MIX_SPEAKERS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+        {
+            voice = voice.with_mixed_speakers(speakers);
+        }
+        voice.start(&self.ws_manager).await;
 
-        // Create and start session manager
-        let session_manager = SessionManager::new();
-        tokio::spawn(async move {
-            session_manager.start_background_flush_task().await;
-        });
+        let webtransport_port = std::env::var("WEBTRANSPORT_PORT")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(webtransport::DEFAULT_WEBTRANSPORT_PORT);
+        if let Err(e) = self.webtransport_manager.start_server(webtransport_port).await {
+            log::warn!("WebTransport unavailable, serving WebSocket only: {}", e);
+        }
+
+        // Stream room presence to observers over gRPC, when a port is configured
+        if let Some(grpc_port) = std::env::var("PRESENCE_GRPC_PORT")
+            .ok()
+            .and_then(|value| value.parse().ok())
+        {
+            let grpc_service = PresenceGrpcService::new(self.ws_manager.presence_feed().clone());
+            tokio::spawn(async move {
+                if let Err(e) = grpc_service.serve(grpc_port).await {
+                    log::warn!("gRPC presence service unavailable: {}", e);
+                }
+            });
+        }
+
+        let shutdown = Shutdown {
+            drain: drain.clone(),
+            ws_manager: self.ws_manager.clone(),
+            cluster: self.cluster.clone(),
+            webtransport_manager: self.webtransport_manager.clone(),
+        };
 
-        HttpServer::new(move || {
+        let server = HttpServer::new(move || {
             App::new()
                 .app_data(web::Data::new(self.config.clone()))
                 .app_data(web::Data::new(session_manager.clone()))
-                .app_data(web::Data::new(self.pubsub.clone()))
+                .app_data(web::Data::new(self.ws_manager.clone()))
+                .app_data(web::Data::new(moderation.clone()))
+                .app_data(web::Data::new(chat.clone()))
+                .app_data(web::Data::new(user_presence.clone()))
+                .app_data(web::Data::new(recorder.clone()))
+                .app_data(web::Data::new(drain.clone()))
+                .app_data(web::Data::new(self.cluster.clone()))
                 .app_data(web::Data::new(self.webtransport_manager.clone()))
                 .wrap(actix_cors::Cors::permissive())
                 .wrap(reticulum_core::middleware::LoggingMiddleware)
@@ -157,7 +225,61 @@ impl PresenceService {
         })
         .bind((host.as_str(), port))?
         .workers(workers)
-        .run()
-        .await
+        // SIGTERM drains the instance instead of dropping every connection
+        .disable_signals()
+        .run();
+
+        let handle = server.handle();
+        tokio::spawn(async move {
+            shutdown.wait().await;
+            handle.stop(true).await;
+        });
+
+        server.await
+    }
+}
+
+/// Drains the instance on SIGTERM or Ctrl-C and leaves the cluster once it
+/// is drained, whoever started the drain
+struct Shutdown {
+    drain: DrainController,
+    ws_manager: WebSocketManager,
+    cluster: redis::ClusterBroadcaster,
+    webtransport_manager: webtransport::WebTransportManager,
+}
+
+impl Shutdown {
+    async fn wait(self) {
+        tokio::select! {
+            _ = shutdown_signal() => {
+                log::info!("Shutdown requested, draining");
+                if let Err(e) = self.drain.drain(&self.ws_manager, DrainRequest::default()).await {
+                    log::info!("Not starting a drain: {}", e);
+                }
+                self.drain.drained().await;
+            }
+            _ = self.drain.drained() => {}
+        }
+
+        self.cluster.stop();
+        if let Err(e) = self.webtransport_manager.stop_server().await {
+            log::warn!("Failed to stop WebTransport: {}", e);
+        }
+    }
+}
+
+/// Resolves on SIGTERM, as sent by orchestrators, or Ctrl-C
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        if let Ok(mut terminate) = signal(SignalKind::terminate()) {
+            tokio::select! {
+                _ = terminate.recv() => {}
+                _ = tokio::signal::ctrl_c() => {}
+            }
+            return;
+        }
     }
+    let _ = tokio::signal::ctrl_c().await;
 }
@@ -0,0 +1,353 @@
+//! Connection liveness and zombie reaping
+//!
+//! WebSocket connections are pinged every ping interval. Browsers answer
+//! pings by themselves, so clients need no changes, and the round trip of
+//! every answered ping goes to the latency tracker. Any frame from the client
+//! counts as a sign of life. A connection silent for longer than the liveness
+//! timeout is a dead TCP connection the OS has not noticed yet: the reaper
+//! closes it and its room is told it left. Closing any connection for good
+//! tells the hub to free the user's seat.
+//!
+//! WebTransport sessions are not pinged; QUIC keep-alives and the QUIC idle
+//! timeout already close dead sessions.
+//!
+//! Sessions registered over `/connect` stay alive through heartbeats or a live
+//! connection of the same client in their room, and are reaped otherwise.
+//! Clients that open a WebSocket or WebTransport session without calling
+//! `/connect` have no such session; only their connection is tracked, by the
+//! pings and QUIC timeouts above.
+
+use crate::session::{ClientSession, SessionManager};
+use crate::websocket::{close_connection, WebSocketManager, WsMessage};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+
+/// Time between pings to a connection
+pub const DEFAULT_PING_INTERVAL_SECS: u64 = 10;
+
+/// Silence after which a connection is considered dead
+pub const DEFAULT_LIVENESS_TIMEOUT_SECS: u64 = 30;
+
+#[derive(Debug, Clone, Copy)]
+pub struct LivenessConfig {
+    pub ping_interval: Duration,
+    pub timeout: Duration,
+}
+
+impl Default for LivenessConfig {
+    fn default() -> Self {
+        Self {
+            ping_interval: Duration::from_secs(DEFAULT_PING_INTERVAL_SECS),
+            timeout: Duration::from_secs(DEFAULT_LIVENESS_TIMEOUT_SECS),
+        }
+    }
+}
+
+impl LivenessConfig {
+    /// Defaults overridden by `PRESENCE_PING_INTERVAL_SECS` and
+    /// `PRESENCE_LIVENESS_TIMEOUT_SECS`
+    pub fn from_env() -> Self {
+        let secs = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|value| value.parse::<u64>().ok())
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs)
+        };
+
+        let defaults = Self::default();
+        Self {
+            ping_interval: secs("PRESENCE_PING_INTERVAL_SECS").unwrap_or(defaults.ping_interval),
+            timeout: secs("PRESENCE_LIVENESS_TIMEOUT_SECS").unwrap_or(defaults.timeout),
+        }
+    }
+}
+
+struct ConnectionLiveness {
+    last_seen: Instant,
+    pending_ping: Option<(u64, Instant)>, // nonce and send time of the unanswered ping
+    rtt: Option<Duration>,
+}
+
+/// Last sign of life and round trip time of each pinged connection
+#[derive(Clone)]
+pub struct LivenessTracker {
+    config: LivenessConfig,
+    connections: Arc<RwLock<HashMap<String, ConnectionLiveness>>>,
+    next_nonce: Arc<AtomicU64>,
+}
+
+impl LivenessTracker {
+    pub fn new(config: LivenessConfig) -> Self {
+        Self {
+            config,
+            connections: Arc::new(RwLock::new(HashMap::new())),
+            next_nonce: Arc::new(AtomicU64::new(1)),
+        }
+    }
+
+    pub fn config(&self) -> LivenessConfig {
+        self.config
+    }
+
+    /// Start watching a connection with a live socket
+    pub async fn register(&self, conn_id: &str) {
+        self.connections.write().await.insert(
+            conn_id.to_string(),
+            ConnectionLiveness {
+                last_seen: Instant::now(),
+                pending_ping: None,
+                rtt: None,
+            },
+        );
+    }
+
+    /// Stop watching a connection that lost its socket
+    pub async fn unregister(&self, conn_id: &str) {
+        self.connections.write().await.remove(conn_id);
+    }
+
+    /// Record a frame from the client
+    pub async fn seen(&self, conn_id: &str) {
+        if let Some(liveness) = self.connections.write().await.get_mut(conn_id) {
+            liveness.last_seen = Instant::now();
+        }
+    }
+
+    /// Payload of the next ping to a connection; an unanswered ping is
+    /// forgotten, since its pong would no longer tell the current round trip
+    pub async fn ping(&self, conn_id: &str) -> Vec<u8> {
+        let nonce = self.next_nonce.fetch_add(1, Ordering::Relaxed);
+        if let Some(liveness) = self.connections.write().await.get_mut(conn_id) {
+            liveness.pending_ping = Some((nonce, Instant::now()));
+        }
+        nonce.to_be_bytes().to_vec()
+    }
+
+    /// Record a pong; returns the round trip if it answers the last ping
+    pub async fn pong(&self, conn_id: &str, payload: &[u8]) -> Option<Duration> {
+        let nonce = u64::from_be_bytes(payload.try_into().ok()?);
+        let mut connections = self.connections.write().await;
+        let liveness = connections.get_mut(conn_id)?;
+        liveness.last_seen = Instant::now();
+
+        match liveness.pending_ping {
+            Some((pending, sent_at)) if pending == nonce => {
+                let rtt = sent_at.elapsed();
+                liveness.pending_ping = None;
+                liveness.rtt = Some(rtt);
+                Some(rtt)
+            }
+            _ => None,
+        }
+    }
+
+    /// Round trip of the last answered ping
+    pub async fn rtt(&self, conn_id: &str) -> Option<Duration> {
+        self.connections.read().await.get(conn_id).and_then(|liveness| liveness.rtt)
+    }
+
+    /// Connections silent for longer than the timeout
+    pub async fn stale(&self) -> Vec<String> {
+        self.connections
+            .read()
+            .await
+            .iter()
+            .filter(|(_, liveness)| liveness.last_seen.elapsed() > self.config.timeout)
+            .map(|(conn_id, _)| conn_id.clone())
+            .collect()
+    }
+}
+
+impl Default for LivenessTracker {
+    fn default() -> Self {
+        Self::new(LivenessConfig::default())
+    }
+}
+
+/// Closes WebSocket connections and `/connect` sessions that stopped showing
+/// signs of life
+#[derive(Clone)]
+pub struct Reaper {
+    ws_manager: WebSocketManager,
+    session_manager: SessionManager,
+}
+
+impl Reaper {
+    /// Reaper for `ws_manager`'s connections and `session_manager`'s
+    /// sessions; closing a connection frees its hub seat
+    pub fn new(ws_manager: WebSocketManager, session_manager: SessionManager) -> Self {
+        Self {
+            ws_manager,
+            session_manager,
+        }
+    }
+
+    /// Reap every ping interval
+    pub fn start(&self) -> JoinHandle<()> {
+        let reaper = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(reaper.ws_manager.liveness().config().ping_interval);
+            loop {
+                interval.tick().await;
+                reaper.reap().await;
+            }
+        })
+    }
+
+    /// Close stale connections and unregister stale sessions; returns how
+    /// many of both were reaped
+    pub async fn reap(&self) -> usize {
+        let mut reaped = 0;
+        for conn_id in self.ws_manager.liveness().stale().await {
+            self.reap_connection(&conn_id).await;
+            reaped += 1;
+        }
+
+        let timeout = self.ws_manager.liveness().config().timeout;
+        let timeout = chrono::Duration::from_std(timeout).unwrap_or_else(|_| chrono::Duration::seconds(30));
+        for session in self.session_manager.stale_sessions(timeout).await {
+            // A live socket is heartbeat enough
+            if self.has_live_connection(&session).await {
+                let _ = self.session_manager.update_heartbeat(&session.session_id).await;
+                continue;
+            }
+            log::info!("Reaping session {} of client {}: no heartbeat", session.session_id, session.client_id);
+            if let Err(e) = self.session_manager.unregister_session(&session.session_id).await {
+                log::warn!("Failed to reap session {}: {}", session.session_id, e);
+                continue;
+            }
+            reaped += 1;
+        }
+
+        reaped
+    }
+
+    async fn reap_connection(&self, conn_id: &str) {
+        let Some(conn) = self.ws_manager.get_connection_info(conn_id).await else {
+            self.ws_manager.liveness().unregister(conn_id).await;
+            return;
+        };
+        log::info!(
+            "Reaping connection {} in room {:?}: silent for over {}s",
+            conn_id,
+            conn.room_id,
+            self.ws_manager.liveness().config().timeout.as_secs()
+        );
+
+        let _ = self.ws_manager.send_to_connection(conn_id, WsMessage::Close).await;
+        close_connection(&self.ws_manager, conn_id).await;
+    }
+
+    async fn has_live_connection(&self, session: &ClientSession) -> bool {
+        let Some(room_id) = &session.room_id else {
+            return false;
+        };
+        for conn_id in self.ws_manager.get_room_connections(room_id).await {
+            if let Some(conn) = self.ws_manager.get_connection_info(&conn_id).await {
+                if conn.client_id.as_deref() == Some(session.client_id.as_str()) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redis::{MemoryTransport, PubSubTransport};
+    use reticulum_core::occupancy::{SeatRelease, OCCUPANCY_CHANNEL};
+
+    fn config(timeout_ms: u64) -> LivenessConfig {
+        LivenessConfig {
+            ping_interval: Duration::from_millis(10),
+            timeout: Duration::from_millis(timeout_ms),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pongs_measure_round_trip_and_keep_connections_alive() {
+        let tracker = LivenessTracker::new(config(50));
+        tracker.register("c1").await;
+        tracker.register("c2").await;
+
+        let stale_ping = tracker.ping("c1").await;
+        let ping = tracker.ping("c1").await;
+        assert!(tracker.pong("c1", &stale_ping).await.is_none());
+        assert!(tracker.pong("c1", &ping).await.is_some());
+        assert!(tracker.rtt("c1").await.is_some());
+        assert!(tracker.pong("c1", b"garbage").await.is_none());
+
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        tracker.seen("c1").await;
+        assert_eq!(tracker.stale().await, vec!["c2".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_reaper_closes_silent_connections_and_frees_their_seats() {
+        let ws_manager = WebSocketManager::new().with_liveness(config(20));
+        let transport = Arc::new(MemoryTransport::new());
+        let mut releases = transport.psubscribe(OCCUPANCY_CHANNEL).await.unwrap();
+        ws_manager.attach_hub(transport).await;
+        let session_manager = SessionManager::new();
+        let reaper = Reaper::new(ws_manager.clone(), session_manager.clone());
+
+        let mut zombie = ws_manager
+            .add_authenticated_connection("c1".to_string(), Some("room-1".to_string()), "alice".to_string(), Some("a".to_string()))
+            .await;
+        let _alive = ws_manager
+            .add_authenticated_connection("c2".to_string(), Some("room-1".to_string()), "bob".to_string(), Some("b".to_string()))
+            .await;
+        ws_manager.liveness().register("c1").await;
+        ws_manager.liveness().register("c2").await;
+
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        ws_manager.liveness().seen("c2").await;
+        assert_eq!(reaper.reap().await, 1);
+
+        assert!(matches!(zombie.queue.recv().await, Some(WsMessage::Close)));
+        assert!(ws_manager.get_connection_info("c1").await.is_none());
+        assert!(ws_manager.get_connection_info("c2").await.is_some());
+
+        let (_, payload) = releases.recv().await.unwrap();
+        let release: SeatRelease = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(release.instance_id, "room-1");
+        assert_eq!(release.user_id, "alice");
+    }
+
+    #[tokio::test]
+    async fn test_reaper_keeps_sessions_with_a_live_connection() {
+        let ws_manager = WebSocketManager::new().with_liveness(config(20));
+        let session_manager = SessionManager::new();
+        let reaper = Reaper::new(ws_manager.clone(), session_manager.clone());
+
+        let stale = chrono::Utc::now() - chrono::Duration::seconds(60);
+        for (session_id, client_id) in [("s1", "a"), ("s2", "b")] {
+            session_manager
+                .register_session(ClientSession {
+                    session_id: session_id.to_string(),
+                    client_id: client_id.to_string(),
+                    user_id: client_id.to_string(),
+                    room_id: Some("room-1".to_string()),
+                    connected_at: stale,
+                    last_heartbeat: stale,
+                    is_muted: false,
+                })
+                .await
+                .unwrap();
+        }
+        let _a = ws_manager
+            .add_connection("c1".to_string(), Some("room-1".to_string()), None, Some("a".to_string()))
+            .await;
+
+        assert_eq!(reaper.reap().await, 1);
+        assert!(session_manager.get_session("s1").await.is_some());
+        assert!(session_manager.get_session("s2").await.is_none());
+    }
+}
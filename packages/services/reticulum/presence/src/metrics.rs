@@ -1,12 +1,11 @@
 //! Metrics and monitoring for WebSocket connections
 
-use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
 /// WebSocket connection metrics
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct ConnectionMetrics {
     pub total_connections: u64,
     pub active_connections: u64,
@@ -19,22 +18,6 @@ pub struct ConnectionMetrics {
     pub message_errors: u64,
 }
 
-impl Default for ConnectionMetrics {
-    fn default() -> Self {
-        Self {
-            total_connections: 0,
-            active_connections: 0,
-            total_disconnections: 0,
-            total_messages_sent: 0,
-            total_messages_received: 0,
-            total_bytes_sent: 0,
-            total_bytes_received: 0,
-            connection_errors: 0,
-            message_errors: 0,
-        }
-    }
-}
-
 /// Metrics collector for WebSocket operations
 #[derive(Clone)]
 pub struct MetricsCollector {
@@ -382,7 +365,7 @@ impl Default for PerformanceMonitor {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tokio::time::{sleep, Duration};
+    use tokio::time::Duration;
 
     #[tokio::test]
     async fn test_metrics_collector() {
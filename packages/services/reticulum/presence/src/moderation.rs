@@ -0,0 +1,926 @@
+//! Room moderation
+//!
+//! Moderation actions are appended to the `moderation_actions` log, which is
+//! both the audit trail and the source of each room's mutes, bans and lock
+//! state. Every instance folds a room's log into a cached `RoomModeration`
+//! and reloads it every `REFRESH_INTERVAL`, so actions taken through another
+//! instance apply here within that interval and survive restarts. Instances
+//! also share each action on [`MODERATION_CHANNEL`] as it is taken, so kicked
+//! and banned users are removed from every instance at once.
+//!
+//! Without a database, actions only last for the life of this instance.
+
+use crate::protobuf::{Flow, MessageContext, MessageHandler, Route};
+use crate::redis::PubSubTransport;
+use crate::websocket::{close_connection, WebSocketManager, WsMessage};
+use actix_web::HttpRequest;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use graphwiz_protocol::generated::graphwiz::core::message::Payload;
+use graphwiz_protocol::{Message, MessageBuilder, MessageParser};
+use reticulum_core::jwt::validate_token;
+use reticulum_core::models::{
+    ModerationAction, ModerationActionModel, ModerationActionType, RoleModel, RoomModel, UserModel, UserRole,
+};
+use reticulum_core::{Config, DatabaseConnection, Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// How long a room's cached moderation state is used before it is reloaded
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Channel on which instances share the actions taken through them
+pub const MODERATION_CHANNEL: &str = "graphwiz:moderation";
+
+/// Longest timed mute or ban; longer sanctions are left open-ended
+pub const MAX_SANCTION_SECS: u64 = 10 * 365 * 24 * 60 * 60;
+
+/// Length of a mute or ban of `secs` seconds, as requested by a moderator
+pub fn sanction_duration(secs: u64) -> Result<chrono::Duration> {
+    if secs > MAX_SANCTION_SECS {
+        return Err(Error::validation(format!(
+            "Sanctions can last at most {} seconds; omit the duration for one that lasts until lifted",
+            MAX_SANCTION_SECS
+        )));
+    }
+    chrono::TimeDelta::try_seconds(secs as i64).ok_or_else(|| Error::validation("Invalid sanction duration"))
+}
+
+/// Authenticated caller of a moderation endpoint
+#[derive(Debug, Clone)]
+pub struct Moderator {
+    pub user_id: String,
+    pub email: String,
+}
+
+/// Why a user may moderate a room, strongest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Authority {
+    /// `ADMIN` role
+    Admin,
+    /// `MODERATOR` role
+    Moderator,
+    /// Created the room
+    RoomOwner,
+    /// Holds the room's host role
+    RoomHost,
+    None,
+}
+
+impl Authority {
+    /// Whether this authority may take `action`; room hosts may only kick and
+    /// mute
+    pub fn permits(&self, action: ModerationActionType) -> bool {
+        match self {
+            Authority::Admin | Authority::Moderator | Authority::RoomOwner => true,
+            Authority::RoomHost => matches!(
+                action,
+                ModerationActionType::Kick | ModerationActionType::Mute | ModerationActionType::Unmute
+            ),
+            Authority::None => false,
+        }
+    }
+}
+
+/// Mute or ban of one user
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sanction {
+    /// `None` lasts until lifted
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+impl Sanction {
+    pub fn is_active(&self, now: NaiveDateTime) -> bool {
+        !matches!(self.expires_at, Some(expires_at) if expires_at <= now)
+    }
+}
+
+/// Moderation state of a room, the result of replaying its actions
+#[derive(Debug, Clone, Default)]
+pub struct RoomModeration {
+    pub locked: bool,
+    mutes: HashMap<String, Sanction>, // user_id -> mute
+    bans: HashMap<String, Sanction>,  // user_id -> ban
+}
+
+impl RoomModeration {
+    /// Replay `actions`, oldest first
+    pub fn from_actions<'a>(actions: impl IntoIterator<Item = &'a ModerationAction>) -> Self {
+        let mut state = Self::default();
+        for action in actions {
+            state.apply(action);
+        }
+        state
+    }
+
+    pub fn apply(&mut self, action: &ModerationAction) {
+        let target = action.target_user_id.clone();
+        let sanction = Sanction {
+            expires_at: action.expires_at,
+        };
+        match (action.action, target) {
+            (ModerationActionType::Mute, Some(user_id)) => {
+                self.mutes.insert(user_id, sanction);
+            }
+            (ModerationActionType::Unmute, Some(user_id)) => {
+                self.mutes.remove(&user_id);
+            }
+            (ModerationActionType::Ban, Some(user_id)) => {
+                self.bans.insert(user_id, sanction);
+            }
+            (ModerationActionType::Unban, Some(user_id)) => {
+                self.bans.remove(&user_id);
+            }
+            (ModerationActionType::Lock, _) => self.locked = true,
+            (ModerationActionType::Unlock, _) => self.locked = false,
+            // Kicks end a connection but leave no lasting state
+            _ => {}
+        }
+    }
+
+    /// The user's mute, if it is in effect at `now`
+    pub fn mute(&self, user_id: &str, now: NaiveDateTime) -> Option<&Sanction> {
+        self.mutes.get(user_id).filter(|mute| mute.is_active(now))
+    }
+
+    /// The user's ban, if it is in effect at `now`
+    pub fn ban(&self, user_id: &str, now: NaiveDateTime) -> Option<&Sanction> {
+        self.bans.get(user_id).filter(|ban| ban.is_active(now))
+    }
+}
+
+struct CachedRoom {
+    state: RoomModeration,
+    loaded_at: Instant,
+}
+
+/// An action shared with the other instances, as published on
+/// [`MODERATION_CHANNEL`]
+#[derive(Debug, Serialize, Deserialize)]
+struct ActionRelay {
+    instance_id: String,
+    action: ModerationAction,
+}
+
+/// Moderation action requested by a moderator
+#[derive(Debug, Clone)]
+pub struct ActionRequest {
+    pub room_id: String,
+    pub action: ModerationActionType,
+    pub target_user_id: Option<String>,
+    pub reason: Option<String>,
+    /// Length of a mute or ban; `None` lasts until lifted
+    pub duration: Option<chrono::Duration>,
+}
+
+/// Authorizes, records and enforces moderation actions
+#[derive(Clone)]
+pub struct ModerationManager {
+    db: Option<DatabaseConnection>,
+    config: Arc<Config>,
+    rooms: Arc<RwLock<HashMap<String, CachedRoom>>>, // room_id -> cached moderation state
+    cluster: Option<(String, Arc<dyn PubSubTransport>)>, // this instance's id and the transport shared with its peers
+}
+
+impl ModerationManager {
+    /// Moderation backed by `db`, or kept in memory when it is `None`
+    pub fn new(db: Option<DatabaseConnection>, config: Config) -> Self {
+        Self {
+            db,
+            config: Arc::new(config),
+            rooms: Arc::new(RwLock::new(HashMap::new())),
+            cluster: None,
+        }
+    }
+
+    /// Share actions with the instances on `transport`, so kicks and bans
+    /// remove the user wherever they are connected
+    pub fn with_transport(mut self, instance_id: &str, transport: Arc<dyn PubSubTransport>) -> Self {
+        self.cluster = Some((instance_id.to_string(), transport));
+        self
+    }
+
+    /// Enforce mutes on `ws_manager`'s messages and bans and locks on its
+    /// joins, along with the actions other instances share
+    pub async fn start(&self, ws_manager: &WebSocketManager) {
+        ws_manager.register_global_handler(Arc::new(self.clone())).await;
+        ws_manager.attach_moderation(self.clone()).await;
+
+        let Some((instance_id, transport)) = &self.cluster else {
+            return;
+        };
+        let mut relays = match transport.psubscribe(MODERATION_CHANNEL).await {
+            Ok(relays) => relays,
+            Err(e) => {
+                log::warn!("Moderation actions stay on this instance until the next refresh: {}", e);
+                return;
+            }
+        };
+        let moderation = self.clone();
+        let manager = ws_manager.clone();
+        let instance_id = instance_id.clone();
+        tokio::spawn(async move {
+            while let Some((_, payload)) = relays.recv().await {
+                match serde_json::from_slice::<ActionRelay>(&payload) {
+                    Ok(relay) if relay.instance_id != instance_id => {
+                        moderation.apply_relayed(&manager, &relay.action).await;
+                    }
+                    Ok(_) => {}
+                    Err(e) => log::warn!("Invalid moderation relay: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Caller identified by the request's `Authorization: Bearer` token
+    pub fn authenticate(&self, req: &HttpRequest) -> Result<Moderator> {
+        let header = req
+            .headers()
+            .get("Authorization")
+            .and_then(|h| h.to_str().ok())
+            .ok_or_else(|| Error::auth("Missing authorization header"))?;
+        let token = header
+            .strip_prefix("Bearer ")
+            .ok_or_else(|| Error::auth("Invalid authorization header format"))?;
+        self.verify_token(token)
+    }
+
+    /// User identified by an access token
+    pub fn verify_token(&self, token: &str) -> Result<Moderator> {
+        let claims = validate_token(&self.config, token)?;
+        Ok(Moderator {
+            user_id: claims.sub,
+            email: claims.email,
+        })
+    }
+
+    /// Role of a user; users without an assignment, or unknown to the
+    /// database, are plain users
+    pub async fn user_role(&self, user_id: &str) -> Result<UserRole> {
+        let (Some(db), Ok(id)) = (&self.db, user_id.parse::<i32>()) else {
+            return Ok(UserRole::User);
+        };
+
+        if let Some(assignment) = RoleModel::get_user_role(db, id).await? {
+            return UserRole::from_str(&assignment.role).map_err(Error::internal);
+        }
+        let role = UserModel::find_by_id(db, id).await?.and_then(|user| user.role);
+        Ok(role
+            .and_then(|role| UserRole::from_str(&role).ok())
+            .unwrap_or(UserRole::User))
+    }
+
+    /// Strongest authority `moderator` holds over `room_id`
+    pub async fn authority(&self, ws_manager: &WebSocketManager, moderator: &Moderator, room_id: &str) -> Result<Authority> {
+        match self.user_role(&moderator.user_id).await? {
+            UserRole::Admin => return Ok(Authority::Admin),
+            UserRole::Moderator => return Ok(Authority::Moderator),
+            UserRole::User => {}
+        }
+
+        let owner = match &self.db {
+            Some(db) => RoomModel::find_by_room_id(db, room_id).await?.map(|room| room.created_by),
+            None => None,
+        };
+        Ok(room_authority(ws_manager, moderator, room_id, owner.as_deref()).await)
+    }
+
+    /// Check that the caller is an admin, for reading the audit log
+    pub async fn require_admin(&self, req: &HttpRequest) -> Result<Moderator> {
+        let moderator = self.authenticate(req)?;
+        match self.user_role(&moderator.user_id).await? {
+            UserRole::Admin => Ok(moderator),
+            _ => Err(Error::authorization("Admin role required")),
+        }
+    }
+
+    /// Authorize, record and enforce an action taken by `moderator`
+    pub async fn take_action(
+        &self,
+        ws_manager: &WebSocketManager,
+        moderator: &Moderator,
+        request: ActionRequest,
+    ) -> Result<ModerationAction> {
+        let room_wide = matches!(request.action, ModerationActionType::Lock | ModerationActionType::Unlock);
+        if !room_wide && request.target_user_id.is_none() {
+            return Err(Error::validation(format!("{} requires a target user", request.action.as_str())));
+        }
+
+        let authority = self.authority(ws_manager, moderator, &request.room_id).await?;
+        if !authority.permits(request.action) {
+            return Err(Error::authorization(format!(
+                "Not allowed to {} in room {}",
+                request.action.as_str(),
+                request.room_id
+            )));
+        }
+
+        let expires_at = match (request.action, request.duration) {
+            (ModerationActionType::Mute | ModerationActionType::Ban, Some(duration)) => Some(
+                chrono::Utc::now()
+                    .naive_utc()
+                    .checked_add_signed(duration)
+                    .ok_or_else(|| Error::validation("Sanction duration is too long"))?,
+            ),
+            _ => None,
+        };
+        let action = self
+            .record(
+                &request.room_id,
+                request.action,
+                request.target_user_id.as_deref(),
+                &moderator.user_id,
+                request.reason.as_deref(),
+                expires_at,
+            )
+            .await?;
+
+        self.enforce(ws_manager, &action).await;
+        self.share(&action).await;
+        Ok(action)
+    }
+
+    /// Append an action to the log and apply it to the room's state
+    pub async fn record(
+        &self,
+        room_id: &str,
+        action: ModerationActionType,
+        target_user_id: Option<&str>,
+        actor_user_id: &str,
+        reason: Option<&str>,
+        expires_at: Option<NaiveDateTime>,
+    ) -> Result<ModerationAction> {
+        let action = match &self.db {
+            Some(db) => {
+                ModerationActionModel::record(db, room_id, action, target_user_id, actor_user_id, reason, expires_at)
+                    .await?
+            }
+            None => ModerationAction {
+                id: 0,
+                room_id: room_id.to_string(),
+                action,
+                target_user_id: target_user_id.map(str::to_string),
+                actor_user_id: actor_user_id.to_string(),
+                reason: reason.map(str::to_string),
+                expires_at,
+                created_at: chrono::Utc::now().naive_utc(),
+            },
+        };
+
+        self.load_room(room_id).await?;
+        if let Some(cached) = self.rooms.write().await.get_mut(room_id) {
+            cached.state.apply(&action);
+        }
+        Ok(action)
+    }
+
+    /// Disconnect kicked and banned users and tell the room
+    async fn enforce(&self, ws_manager: &WebSocketManager, action: &ModerationAction) {
+        let notification_type = match action.action {
+            ModerationActionType::Kick => "player_kicked",
+            ModerationActionType::Mute | ModerationActionType::Unmute => "player_muted",
+            ModerationActionType::Ban | ModerationActionType::Unban => "player_banned",
+            ModerationActionType::Lock | ModerationActionType::Unlock => "room_locked",
+        };
+
+        remove_target(ws_manager, action).await;
+
+        // Room broadcasts reach the other instances already
+        let notification = serde_json::json!({
+            "type": notification_type,
+            "data": action,
+        });
+        ws_manager
+            .broadcast_to_room(&action.room_id, notification.to_string().as_bytes(), None)
+            .await;
+    }
+
+    /// Publish an action for the other instances to enforce
+    async fn share(&self, action: &ModerationAction) {
+        let Some((instance_id, transport)) = &self.cluster else {
+            return;
+        };
+        let relay = ActionRelay {
+            instance_id: instance_id.clone(),
+            action: action.clone(),
+        };
+        let published = match serde_json::to_vec(&relay) {
+            Ok(payload) => transport.publish(MODERATION_CHANNEL, payload).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = published {
+            log::warn!("Failed to share moderation action in room {}: {}", action.room_id, e);
+        }
+    }
+
+    /// Apply an action taken through another instance: update the room's
+    /// cached state and remove kicked and banned users from this instance
+    async fn apply_relayed(&self, ws_manager: &WebSocketManager, action: &ModerationAction) {
+        if let Some(cached) = self.rooms.write().await.get_mut(&action.room_id) {
+            cached.state.apply(action);
+        }
+
+        remove_target(ws_manager, action).await;
+    }
+
+    /// Whether the user `user_id`, verified by the caller, may join `room_id`.
+    ///
+    /// Bans keep out the user they name. Locked rooms admit only admins and
+    /// moderators, so anonymous joins are refused. While the room's log cannot
+    /// be read, no one is admitted.
+    pub async fn admit(&self, room_id: &str, user_id: Option<&str>) -> Result<()> {
+        if let Err(e) = self.load_room(room_id).await {
+            log::warn!("Failed to load moderation state of room {}: {}", room_id, e);
+            return Err(e);
+        }
+
+        let now = chrono::Utc::now().naive_utc();
+        let locked = {
+            let rooms = self.rooms.read().await;
+            let Some(cached) = rooms.get(room_id) else {
+                return Ok(());
+            };
+            if user_id.is_none() && cached.state.locked {
+                return Err(Error::auth(format!("Sign in to join room {}", room_id)));
+            }
+            if let Some(ban) = user_id.and_then(|user_id| cached.state.ban(user_id, now)) {
+                return Err(Error::authorization(match ban.expires_at {
+                    Some(expires_at) => format!("Banned from room {} until {}", room_id, expires_at),
+                    None => format!("Banned from room {}", room_id),
+                }));
+            }
+            cached.state.locked
+        };
+
+        if let (true, Some(user_id)) = (locked, user_id) {
+            if !matches!(self.user_role(user_id).await?, UserRole::Admin | UserRole::Moderator) {
+                return Err(Error::authorization(format!("Room {} is locked", room_id)));
+            }
+        }
+        Ok(())
+    }
+
+    /// The user's mute in the room, if in effect
+    pub async fn mute_of(&self, room_id: &str, user_id: &str) -> Option<Sanction> {
+        if let Err(e) = self.load_room(room_id).await {
+            log::warn!("Failed to load moderation state of room {}: {}", room_id, e);
+        }
+        let rooms = self.rooms.read().await;
+        rooms
+            .get(room_id)
+            .and_then(|cached| cached.state.mute(user_id, chrono::Utc::now().naive_utc()))
+            .cloned()
+    }
+
+    /// Actions taken in a room, oldest first
+    pub async fn room_actions(&self, room_id: &str) -> Result<Vec<ModerationAction>> {
+        ModerationActionModel::find_by_room(self.database()?, room_id).await
+    }
+
+    /// Actions taken against or by a user, oldest first
+    pub async fn user_actions(&self, user_id: &str) -> Result<Vec<ModerationAction>> {
+        ModerationActionModel::find_by_user(self.database()?, user_id).await
+    }
+
+    fn database(&self) -> Result<&DatabaseConnection> {
+        self.db
+            .as_ref()
+            .ok_or_else(|| Error::internal("Moderation log requires a database"))
+    }
+
+    /// Cache the room's state unless a fresh copy is cached already
+    async fn load_room(&self, room_id: &str) -> Result<()> {
+        if let Some(cached) = self.rooms.read().await.get(room_id) {
+            if self.db.is_none() || cached.loaded_at.elapsed() < REFRESH_INTERVAL {
+                return Ok(());
+            }
+        }
+
+        let state = match &self.db {
+            Some(db) => RoomModeration::from_actions(&ModerationActionModel::find_by_room(db, room_id).await?),
+            None => RoomModeration::default(),
+        };
+        self.rooms.write().await.insert(
+            room_id.to_string(),
+            CachedRoom {
+                state,
+                loaded_at: Instant::now(),
+            },
+        );
+        Ok(())
+    }
+}
+
+/// Drops chat and voice from muted users
+#[async_trait]
+impl MessageHandler for ModerationManager {
+    async fn handle(&self, message: &mut Message, ctx: &mut MessageContext<'_>) -> Result<Flow> {
+        let is_chat = matches!(message.payload, Some(Payload::ChatMessage(_)));
+        if !is_chat && !matches!(message.payload, Some(Payload::VoiceData(_))) {
+            return Ok(Flow::Continue);
+        }
+        let Some(user_id) = ctx.user_id().map(str::to_string) else {
+            return Ok(Flow::Continue);
+        };
+        let Some(mute) = self.mute_of(ctx.room_id, &user_id).await else {
+            return Ok(Flow::Continue);
+        };
+
+        // Voice arrives many times a second, so only chat is answered
+        if is_chat {
+            let retry_after_ms = mute
+                .expires_at
+                .map(|expires_at| (expires_at - chrono::Utc::now().naive_utc()).num_milliseconds().max(0))
+                .unwrap_or(0);
+            let error = MessageBuilder::error_frame("MUTED", "You are muted in this room".to_string(), retry_after_ms);
+            ctx.send(Route::Sender, error);
+        }
+        Ok(Flow::Drop)
+    }
+}
+
+/// Authority `moderator` holds over `room_id` as its owner, the user id
+/// `owner` that created it, or as its host
+async fn room_authority(
+    ws_manager: &WebSocketManager,
+    moderator: &Moderator,
+    room_id: &str,
+    owner: Option<&str>,
+) -> Authority {
+    if owner == Some(moderator.user_id.as_str()) {
+        return Authority::RoomOwner;
+    }
+
+    if let Some(host_client_id) = ws_manager.get_room_host(room_id).await {
+        for conn_id in ws_manager.get_room_connections(room_id).await {
+            let Some(conn) = ws_manager.get_connection_info(&conn_id).await else {
+                continue;
+            };
+            // A host connection only speaks for the user its token proved
+            if conn.authenticated
+                && conn.user_id.as_deref() == Some(moderator.user_id.as_str())
+                && conn.client_id.as_deref() == Some(host_client_id.as_str())
+            {
+                return Authority::RoomHost;
+            }
+        }
+    }
+
+    Authority::None
+}
+
+/// Close this instance's connections of a kicked or banned user
+async fn remove_target(ws_manager: &WebSocketManager, action: &ModerationAction) {
+    let (ModerationActionType::Kick | ModerationActionType::Ban, Some(user_id)) =
+        (action.action, &action.target_user_id)
+    else {
+        return;
+    };
+    let code = if action.action == ModerationActionType::Ban { "BANNED" } else { "KICKED" };
+    let message = action
+        .reason
+        .clone()
+        .unwrap_or_else(|| "Removed by a moderator".to_string());
+    let removed = disconnect_user(ws_manager, &action.room_id, user_id, code, message).await;
+    log::info!("Removed {} connections of {} from room {}", removed, user_id, action.room_id);
+}
+
+/// Close the user's connections to the room on this instance, telling each
+/// why with an error frame. Returns how many were closed.
+pub async fn disconnect_user(
+    ws_manager: &WebSocketManager,
+    room_id: &str,
+    user_id: &str,
+    code: &str,
+    message: String,
+) -> usize {
+    let mut removed = 0;
+    for conn_id in ws_manager.get_room_connections(room_id).await {
+        let Some(conn) = ws_manager.get_connection_info(&conn_id).await else {
+            continue;
+        };
+        if conn.user_id.as_deref() != Some(user_id) {
+            continue;
+        }
+
+        disconnect_connection(ws_manager, &conn_id, code, message.clone()).await;
+        removed += 1;
+    }
+    removed
+}
+
+/// Close one connection on this instance, telling it why with an error
+/// frame. Returns false when the connection is not here.
+pub async fn disconnect_connection(ws_manager: &WebSocketManager, conn_id: &str, code: &str, message: String) -> bool {
+    if ws_manager.get_connection_info(conn_id).await.is_none() {
+        return false;
+    }
+
+    if let Ok(bytes) = MessageParser::serialize(&MessageBuilder::error_frame(code, message, 0)) {
+        let _ = ws_manager.send_to_connection(conn_id, WsMessage::Binary(bytes)).await;
+    }
+    let _ = ws_manager.send_to_connection(conn_id, WsMessage::Close).await;
+    close_connection(ws_manager, conn_id).await;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protobuf::route_message;
+    use crate::queue::OutboundReceiver;
+    use crate::redis::MemoryTransport;
+    use graphwiz_protocol::ChatMessageType;
+
+    fn action(kind: ModerationActionType, target: Option<&str>, expires_at: Option<NaiveDateTime>) -> ModerationAction {
+        ModerationAction {
+            id: 0,
+            room_id: "room-1".to_string(),
+            action: kind,
+            target_user_id: target.map(str::to_string),
+            actor_user_id: "mod".to_string(),
+            reason: None,
+            expires_at,
+            created_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+
+    async fn next_message(rx: &mut OutboundReceiver<WsMessage>) -> Option<WsMessage> {
+        tokio::time::timeout(Duration::from_millis(100), rx.queue.recv())
+            .await
+            .ok()
+            .flatten()
+    }
+
+    #[test]
+    fn test_actions_fold_into_room_state() {
+        let now = chrono::Utc::now().naive_utc();
+        let actions = [
+            action(ModerationActionType::Lock, None, None),
+            action(ModerationActionType::Mute, Some("alice"), Some(now - chrono::Duration::minutes(1))),
+            action(ModerationActionType::Mute, Some("bob"), Some(now + chrono::Duration::minutes(5))),
+            action(ModerationActionType::Ban, Some("carol"), None),
+            action(ModerationActionType::Ban, Some("dave"), None),
+            action(ModerationActionType::Unban, Some("dave"), None),
+            action(ModerationActionType::Kick, Some("erin"), None),
+        ];
+        let state = RoomModeration::from_actions(&actions);
+
+        assert!(state.locked);
+        // Expired mutes no longer apply
+        assert!(state.mute("alice", now).is_none());
+        assert!(state.mute("bob", now).is_some());
+        assert!(state.ban("carol", now).is_some());
+        assert!(state.ban("dave", now).is_none());
+        assert!(state.ban("erin", now).is_none());
+
+        let unlocked = RoomModeration::from_actions(actions.iter().chain([&action(ModerationActionType::Unlock, None, None)]));
+        assert!(!unlocked.locked);
+    }
+
+    #[test]
+    fn test_room_hosts_may_only_kick_and_mute() {
+        assert!(Authority::RoomHost.permits(ModerationActionType::Kick));
+        assert!(Authority::RoomHost.permits(ModerationActionType::Mute));
+        assert!(!Authority::RoomHost.permits(ModerationActionType::Ban));
+        assert!(!Authority::RoomHost.permits(ModerationActionType::Lock));
+        assert!(Authority::RoomOwner.permits(ModerationActionType::Ban));
+        assert!(!Authority::None.permits(ModerationActionType::Kick));
+    }
+
+    #[tokio::test]
+    async fn test_room_creators_own_the_room() {
+        let ws_manager = WebSocketManager::new();
+        let alice = Moderator {
+            user_id: "1".to_string(),
+            email: "alice@example.com".to_string(),
+        };
+        let bob = Moderator {
+            user_id: "2".to_string(),
+            email: "bob@example.com".to_string(),
+        };
+
+        assert_eq!(room_authority(&ws_manager, &alice, "room-1", Some("1")).await, Authority::RoomOwner);
+        assert_eq!(room_authority(&ws_manager, &bob, "room-1", Some("1")).await, Authority::None);
+        // Rooms are created by user id, never by email
+        assert_eq!(
+            room_authority(&ws_manager, &alice, "room-1", Some("alice@example.com")).await,
+            Authority::None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_only_verified_hosts_hold_host_authority() {
+        let ws_manager = WebSocketManager::new();
+        let alice = Moderator {
+            user_id: "1".to_string(),
+            email: "alice@example.com".to_string(),
+        };
+
+        // The first connection hosts the room, under a user id it only claims
+        let _claimed = ws_manager
+            .add_connection("c1".to_string(), Some("room-1".to_string()), Some("1".to_string()), Some("alice".to_string()))
+            .await;
+        assert_eq!(ws_manager.get_room_host("room-1").await.as_deref(), Some("alice"));
+        assert_eq!(room_authority(&ws_manager, &alice, "room-1", None).await, Authority::None);
+
+        let ws_manager = WebSocketManager::new();
+        let _verified = ws_manager
+            .add_authenticated_connection("c1".to_string(), Some("room-1".to_string()), "1".to_string(), Some("alice".to_string()))
+            .await;
+        assert_eq!(room_authority(&ws_manager, &alice, "room-1", None).await, Authority::RoomHost);
+    }
+
+    #[tokio::test]
+    async fn test_host_can_mute_but_others_cannot() {
+        let ws_manager = WebSocketManager::new();
+        let moderation = ModerationManager::new(None, Config::load_or_default().unwrap());
+        moderation.start(&ws_manager).await;
+        let _host = ws_manager
+            .add_authenticated_connection("c1".to_string(), Some("room-1".to_string()), "1".to_string(), Some("alice".to_string()))
+            .await;
+        let mut muted = ws_manager
+            .add_authenticated_connection("c2".to_string(), Some("room-1".to_string()), "2".to_string(), Some("bob".to_string()))
+            .await;
+
+        let bob = Moderator {
+            user_id: "2".to_string(),
+            email: "bob@example.com".to_string(),
+        };
+        let mute_alice = ActionRequest {
+            room_id: "room-1".to_string(),
+            action: ModerationActionType::Mute,
+            target_user_id: Some("1".to_string()),
+            reason: None,
+            duration: None,
+        };
+        assert!(matches!(
+            moderation.take_action(&ws_manager, &bob, mute_alice).await,
+            Err(Error::Authorization(_))
+        ));
+
+        let alice = Moderator {
+            user_id: "1".to_string(),
+            email: "alice@example.com".to_string(),
+        };
+        let mute_bob = ActionRequest {
+            room_id: "room-1".to_string(),
+            action: ModerationActionType::Mute,
+            target_user_id: Some("2".to_string()),
+            reason: Some("spam".to_string()),
+            duration: Some(chrono::Duration::minutes(10)),
+        };
+        let action = moderation.take_action(&ws_manager, &alice, mute_bob).await.unwrap();
+        assert!(action.expires_at.is_some());
+        assert!(matches!(next_message(&mut muted).await, Some(WsMessage::Binary(_))));
+
+        // Bob's chat is dropped and he is told why
+        let chat = MessageBuilder::chat_message("bob".to_string(), "hi".to_string(), ChatMessageType::Normal);
+        let handlers = ws_manager.handlers().await;
+        route_message(&handlers, &ws_manager, "room-1", "c2", &MessageParser::serialize(&chat).unwrap())
+            .await
+            .unwrap();
+        match next_message(&mut muted).await {
+            Some(WsMessage::Binary(bytes)) => match MessageParser::parse(&bytes).unwrap().payload {
+                Some(Payload::ErrorFrame(error)) => {
+                    assert_eq!(error.code, "MUTED");
+                    assert!(error.retry_after_ms > 0);
+                }
+                other => panic!("expected error frame, got {:?}", other),
+            },
+            other => panic!("expected error frame, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_overlong_sanctions_are_refused() {
+        assert_eq!(sanction_duration(600).unwrap(), chrono::Duration::minutes(10));
+        assert!(sanction_duration(MAX_SANCTION_SECS).is_ok());
+        // Would wrap negative as an i64
+        assert!(matches!(sanction_duration(u64::MAX), Err(Error::Validation(_))));
+        assert!(matches!(sanction_duration(MAX_SANCTION_SECS + 1), Err(Error::Validation(_))));
+
+        let ws_manager = WebSocketManager::new();
+        let moderation = ModerationManager::new(None, Config::load_or_default().unwrap());
+        let _host = ws_manager
+            .add_authenticated_connection("c1".to_string(), Some("room-1".to_string()), "1".to_string(), Some("alice".to_string()))
+            .await;
+        let alice = Moderator {
+            user_id: "1".to_string(),
+            email: "alice@example.com".to_string(),
+        };
+        let mute_forever = ActionRequest {
+            room_id: "room-1".to_string(),
+            action: ModerationActionType::Mute,
+            target_user_id: Some("2".to_string()),
+            reason: None,
+            duration: Some(chrono::TimeDelta::MAX),
+        };
+        assert!(matches!(
+            moderation.take_action(&ws_manager, &alice, mute_forever).await,
+            Err(Error::Validation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_joining_users_are_identified_by_their_token() {
+        let ws_manager = WebSocketManager::new();
+        let config = Config::load_or_default().unwrap();
+        ModerationManager::new(None, config.clone()).start(&ws_manager).await;
+
+        let claims = serde_json::json!({
+            "sub": "2",
+            "email": "bob@example.com",
+            "exp": chrono::Utc::now().timestamp() + 60,
+            "iat": chrono::Utc::now().timestamp(),
+        });
+        let token = jsonwebtoken::encode(
+            &jsonwebtoken::Header::default(),
+            &claims,
+            &jsonwebtoken::EncodingKey::from_secret(config.auth.jwt_secret.as_bytes()),
+        )
+        .unwrap();
+        assert_eq!(ws_manager.verify_user(&token).await.unwrap(), "2");
+        assert!(matches!(ws_manager.verify_user("not-a-token").await, Err(Error::Auth(_))));
+    }
+
+    #[tokio::test]
+    async fn test_banned_users_are_removed_and_kept_out() {
+        let ws_manager = WebSocketManager::new();
+        let moderation = ModerationManager::new(None, Config::load_or_default().unwrap());
+        moderation.start(&ws_manager).await;
+        let mut banned = ws_manager
+            .add_connection("c1".to_string(), Some("room-1".to_string()), Some("2".to_string()), Some("bob".to_string()))
+            .await;
+
+        moderation
+            .record("room-1", ModerationActionType::Ban, Some("2"), "1", None, None)
+            .await
+            .unwrap();
+        let removed = disconnect_user(&ws_manager, "room-1", "2", "BANNED", "bye".to_string()).await;
+        assert_eq!(removed, 1);
+        assert!(matches!(next_message(&mut banned).await, Some(WsMessage::Binary(_))));
+        assert!(matches!(next_message(&mut banned).await, Some(WsMessage::Close)));
+        assert_eq!(ws_manager.room_connection_count("room-1").await, 0);
+
+        assert!(ws_manager.admit("room-1", Some("2")).await.is_err());
+        assert!(ws_manager.admit("room-1", Some("3")).await.is_ok());
+        // A ban only names its user, so guests still get in
+        assert!(ws_manager.admit("room-1", None).await.is_ok());
+
+        moderation
+            .record("room-1", ModerationActionType::Lock, None, "1", None, None)
+            .await
+            .unwrap();
+        assert!(ws_manager.admit("room-1", Some("3")).await.is_err());
+        assert!(matches!(ws_manager.admit("room-1", None).await, Err(Error::Auth(_))));
+    }
+
+    #[tokio::test]
+    async fn test_kicks_and_bans_reach_every_instance() {
+        let transport: Arc<dyn PubSubTransport> = Arc::new(MemoryTransport::new());
+        let config = Config::load_or_default().unwrap();
+        let first_manager = WebSocketManager::new();
+        let first = ModerationManager::new(None, config.clone()).with_transport("instance-1", transport.clone());
+        first.start(&first_manager).await;
+        let second_manager = WebSocketManager::new();
+        let second = ModerationManager::new(None, config).with_transport("instance-2", transport);
+        second.start(&second_manager).await;
+
+        let _host = first_manager
+            .add_authenticated_connection("c1".to_string(), Some("room-1".to_string()), "1".to_string(), Some("alice".to_string()))
+            .await;
+        let mut kicked = second_manager
+            .add_authenticated_connection("c2".to_string(), Some("room-1".to_string()), "2".to_string(), Some("bob".to_string()))
+            .await;
+        assert!(second_manager.admit("room-1", Some("3")).await.is_ok());
+
+        let alice = Moderator {
+            user_id: "1".to_string(),
+            email: "alice@example.com".to_string(),
+        };
+        let kick_bob = ActionRequest {
+            room_id: "room-1".to_string(),
+            action: ModerationActionType::Kick,
+            target_user_id: Some("2".to_string()),
+            reason: None,
+            duration: None,
+        };
+        first.take_action(&first_manager, &alice, kick_bob).await.unwrap();
+        assert!(matches!(next_message(&mut kicked).await, Some(WsMessage::Binary(_))));
+        assert!(matches!(next_message(&mut kicked).await, Some(WsMessage::Close)));
+        assert_eq!(second_manager.room_connection_count("room-1").await, 0);
+
+        // Bans apply on the other instance before its next refresh
+        let ban = first
+            .record("room-1", ModerationActionType::Ban, Some("3"), "1", None, None)
+            .await
+            .unwrap();
+        first.share(&ban).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(second_manager.admit("room-1", Some("3")).await.is_err());
+    }
+}
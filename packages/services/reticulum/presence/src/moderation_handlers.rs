@@ -1,17 +1,22 @@
 //! Moderation handlers for presence service
+//!
+//! Every action requires a bearer token. Admins, moderators and the room's
+//! creator may take any action in a room; its host may kick and mute. The
+//! audit log is readable by admins only.
 
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use reticulum_core::models::{ModerationAction, ModerationActionType};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
-use crate::session::SessionManager;
+use crate::moderation::{sanction_duration, ActionRequest, ModerationManager};
+use crate::websocket::WebSocketManager;
 
 /// Kick player from room
 #[derive(Debug, Deserialize)]
 pub struct KickPlayerRequest {
     pub room_id: String,
-    pub target_client_id: String,
-    pub kicked_by_client_id: String,
+    pub target_user_id: String,
     pub reason: Option<String>,
 }
 
@@ -19,8 +24,21 @@ pub struct KickPlayerRequest {
 #[derive(Debug, Deserialize)]
 pub struct MutePlayerRequest {
     pub room_id: String,
-    pub target_client_id: String,
+    pub target_user_id: String,
     pub muted: bool,
+    /// Mute length; omitted for a mute that lasts until lifted
+    pub duration_secs: Option<u64>,
+    pub reason: Option<String>,
+}
+
+/// Ban/unban player
+#[derive(Debug, Deserialize)]
+pub struct BanPlayerRequest {
+    pub room_id: String,
+    pub target_user_id: String,
+    pub banned: bool,
+    /// Ban length; omitted for a ban that lasts until lifted
+    pub duration_secs: Option<u64>,
     pub reason: Option<String>,
 }
 
@@ -29,7 +47,6 @@ pub struct MutePlayerRequest {
 pub struct LockRoomRequest {
     pub room_id: String,
     pub locked: bool,
-    pub locked_by_client_id: String,
     pub reason: Option<String>,
 }
 
@@ -37,191 +54,174 @@ pub struct LockRoomRequest {
 pub struct ModerationActionResponse {
     pub success: bool,
     pub message: String,
+    pub action: ModerationAction,
 }
 
 /// Kick player from room
 pub async fn kick_player(
-    session_manager: web::Data<SessionManager>,
+    req: HttpRequest,
+    moderation: web::Data<ModerationManager>,
+    ws_manager: web::Data<WebSocketManager>,
     body: web::Json<KickPlayerRequest>,
 ) -> HttpResponse {
-    let req = body.into_inner();
-
-    log::info!(
-        "Kick player request: room={}, target={}, by={}, reason={:?}",
-        req.room_id,
-        req.target_client_id,
-        req.kicked_by_client_id,
-        req.reason
-    );
-
-    // Get session to be kicked
-    match session_manager.get_session(&req.target_client_id).await {
-        Some(session) => {
-            // Verify player is in the room
-            if session.room_id.as_ref() != Some(&req.room_id) {
-                return HttpResponse::BadRequest().json(json!({
-                    "success": false,
-                    "message": format!("Player {} is not in room {}", req.target_client_id, req.room_id)
-                }));
-            }
-
-            // Disconnect the session
-            match session_manager.unregister_session(&req.target_client_id).await {
-                Ok(_) => {
-                    log::info!("Player {} kicked from room {}", req.target_client_id, req.room_id);
-
-                    // Broadcast kick notification to room
-                    let kick_notification = json!({
-                        "type": "player_kicked",
-                        "data": {
-                            "kicked_client_id": req.target_client_id,
-                            "kicked_by_client_id": req.kicked_by_client_id,
-                            "reason": req.reason.unwrap_or_else(|| "Kicked by moderator".to_string())
-                        }
-                    });
-
-                    // Broadcast to all room members
-                    match session_manager.broadcast_to_room(&req.room_id, kick_notification).await {
-                        Ok(count) => {
-                            log::info!("Kick notification broadcasted to {} room members", count);
-                        }
-                        Err(e) => {
-                            log::error!("Failed to broadcast kick notification: {}", e);
-                        }
-                    }
-
-                    HttpResponse::Ok().json(json!({
-                        "success": true,
-                        "message": format!("Player {} kicked from room", req.target_client_id)
-                    }))
-                }
-                Err(e) => {
-                    log::error!("Failed to kick player: {}", e);
-                    HttpResponse::InternalServerError().json(json!({
-                        "success": false,
-                        "message": "Failed to kick player"
-                    }))
-                }
-            }
-        }
-        None => {
-            HttpResponse::NotFound().json(json!({
-                "success": false,
-                "message": format!("Player {} not found", req.target_client_id)
-            }))
-        }
-    }
+    let body = body.into_inner();
+    let request = ActionRequest {
+        room_id: body.room_id,
+        action: ModerationActionType::Kick,
+        target_user_id: Some(body.target_user_id),
+        reason: body.reason,
+        duration: None,
+    };
+    moderate(&req, &moderation, &ws_manager, request).await
 }
 
 /// Mute/unmute player
 pub async fn mute_player(
-    session_manager: web::Data<SessionManager>,
+    req: HttpRequest,
+    moderation: web::Data<ModerationManager>,
+    ws_manager: web::Data<WebSocketManager>,
     body: web::Json<MutePlayerRequest>,
 ) -> HttpResponse {
-    let req = body.into_inner();
+    let body = body.into_inner();
+    let duration = match body.duration_secs.map(sanction_duration).transpose() {
+        Ok(duration) => duration,
+        Err(e) => return e.error_response(),
+    };
+    let request = ActionRequest {
+        room_id: body.room_id,
+        action: if body.muted { ModerationActionType::Mute } else { ModerationActionType::Unmute },
+        target_user_id: Some(body.target_user_id),
+        reason: body.reason,
+        duration,
+    };
+    moderate(&req, &moderation, &ws_manager, request).await
+}
 
-    log::info!(
-        "Mute player request: room={}, target={}, muted={}, reason={:?}",
-        req.room_id,
-        req.target_client_id,
-        req.muted,
-        req.reason
-    );
+/// Ban/unban player
+pub async fn ban_player(
+    req: HttpRequest,
+    moderation: web::Data<ModerationManager>,
+    ws_manager: web::Data<WebSocketManager>,
+    body: web::Json<BanPlayerRequest>,
+) -> HttpResponse {
+    let body = body.into_inner();
+    let duration = match body.duration_secs.map(sanction_duration).transpose() {
+        Ok(duration) => duration,
+        Err(e) => return e.error_response(),
+    };
+    let request = ActionRequest {
+        room_id: body.room_id,
+        action: if body.banned { ModerationActionType::Ban } else { ModerationActionType::Unban },
+        target_user_id: Some(body.target_user_id),
+        reason: body.reason,
+        duration,
+    };
+    moderate(&req, &moderation, &ws_manager, request).await
+}
+
+/// Lock/unlock room
+pub async fn lock_room(
+    req: HttpRequest,
+    moderation: web::Data<ModerationManager>,
+    ws_manager: web::Data<WebSocketManager>,
+    body: web::Json<LockRoomRequest>,
+) -> HttpResponse {
+    let body = body.into_inner();
+    let request = ActionRequest {
+        room_id: body.room_id,
+        action: if body.locked { ModerationActionType::Lock } else { ModerationActionType::Unlock },
+        target_user_id: None,
+        reason: body.reason,
+        duration: None,
+    };
+    moderate(&req, &moderation, &ws_manager, request).await
+}
+
+/// Moderation log of a room (admin only)
+pub async fn get_room_actions(
+    req: HttpRequest,
+    moderation: web::Data<ModerationManager>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let room_id = path.into_inner();
+
+    if let Err(e) = moderation.require_admin(&req).await {
+        return e.error_response();
+    }
 
-    match session_manager.mute_player(&req.target_client_id, req.muted).await {
-        Ok(_) => {
-            log::info!("Player {} {} in room {}",
-                req.target_client_id,
-                if req.muted { "muted" } else { "unmuted" },
-                req.room_id
-            );
-
-            // Broadcast mute notification to room
-            let mute_notification = json!({
-                "type": "player_muted",
-                "data": {
-                    "muted_client_id": req.target_client_id,
-                    "muted": req.muted,
-                    "reason": req.reason.unwrap_or_else(|| "Muted by moderator".to_string())
-                }
-            });
-
-            // Broadcast to all room members
-            match session_manager.broadcast_to_room(&req.room_id, mute_notification).await {
-                Ok(count) => {
-                    log::info!("Mute notification broadcasted to {} room members", count);
-                }
-                Err(e) => {
-                    log::error!("Failed to broadcast mute notification: {}", e);
-                }
-            }
-
-            HttpResponse::Ok().json(json!({
-                "success": true,
-                "message": format!("Player {} {}", req.target_client_id, if req.muted { "muted" } else { "unmuted" })
-            }))
+    match moderation.room_actions(&room_id).await {
+        Ok(actions) => HttpResponse::Ok().json(json!({
+            "room_id": room_id,
+            "total": actions.len(),
+            "actions": actions
+        })),
+        Err(e) => {
+            log::error!("Failed to fetch moderation log of room {}: {}", room_id, e);
+            e.error_response()
         }
+    }
+}
+
+/// Moderation actions taken against or by a user (admin only)
+pub async fn get_user_actions(
+    req: HttpRequest,
+    moderation: web::Data<ModerationManager>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let user_id = path.into_inner();
+
+    if let Err(e) = moderation.require_admin(&req).await {
+        return e.error_response();
+    }
+
+    match moderation.user_actions(&user_id).await {
+        Ok(actions) => HttpResponse::Ok().json(json!({
+            "user_id": user_id,
+            "total": actions.len(),
+            "actions": actions
+        })),
         Err(e) => {
-            log::error!("Failed to mute player: {}", e);
-            HttpResponse::InternalServerError().json(json!({
-                "success": false,
-                "message": "Failed to mute player"
-            }))
+            log::error!("Failed to fetch moderation log of user {}: {}", user_id, e);
+            e.error_response()
         }
     }
 }
 
-/// Lock/unlock room
-pub async fn lock_room(
-    session_manager: web::Data<SessionManager>,
-    body: web::Json<LockRoomRequest>,
+async fn moderate(
+    req: &HttpRequest,
+    moderation: &ModerationManager,
+    ws_manager: &WebSocketManager,
+    request: ActionRequest,
 ) -> HttpResponse {
-    let req = body.into_inner();
+    let moderator = match moderation.authenticate(req) {
+        Ok(moderator) => moderator,
+        Err(e) => return e.error_response(),
+    };
 
     log::info!(
-        "Lock room request: room={}, locked={}, by={}, reason={:?}",
-        req.room_id,
-        req.locked,
-        req.locked_by_client_id,
-        req.reason
+        "Moderation request: room={}, action={}, target={:?}, by={}, reason={:?}",
+        request.room_id,
+        request.action.as_str(),
+        request.target_user_id,
+        moderator.user_id,
+        request.reason
     );
 
-    match session_manager.set_room_locked(&req.room_id, req.locked).await {
-        Ok(_) => {
-            log::info!("Room {} {}", req.room_id, if req.locked { "locked" } else { "unlocked" });
-
-            // Broadcast lock notification to room
-            let lock_notification = json!({
-                "type": "room_locked",
-                "data": {
-                    "room_id": req.room_id,
-                    "locked": req.locked,
-                    "locked_by_client_id": req.locked_by_client_id,
-                    "reason": req.reason.unwrap_or_else(|| "Room locked".to_string())
-                }
-            });
-
-            // Broadcast to all room members
-            match session_manager.broadcast_to_room(&req.room_id, lock_notification).await {
-                Ok(count) => {
-                    log::info!("Lock notification broadcasted to {} room members", count);
-                }
-                Err(e) => {
-                    log::error!("Failed to broadcast lock notification: {}", e);
-                }
-            }
-
-            HttpResponse::Ok().json(json!({
-                "success": true,
-                "message": format!("Room {} {}", req.room_id, if req.locked { "locked" } else { "unlocked" })
-            }))
+    match moderation.take_action(ws_manager, &moderator, request).await {
+        Ok(action) => {
+            let message = match &action.target_user_id {
+                Some(target) => format!("{} applied to {} in room {}", action.action.as_str(), target, action.room_id),
+                None => format!("{} applied to room {}", action.action.as_str(), action.room_id),
+            };
+            HttpResponse::Ok().json(ModerationActionResponse {
+                success: true,
+                message,
+                action,
+            })
         }
         Err(e) => {
-            log::error!("Failed to lock room: {}", e);
-            HttpResponse::InternalServerError().json(json!({
-                "success": false,
-                "message": "Failed to lock room"
-            }))
+            log::warn!("Moderation request by {} refused: {}", moderator.user_id, e);
+            e.error_response()
         }
     }
 }
@@ -0,0 +1,205 @@
+//! Room presence events for observers outside the room
+//!
+//! Dashboards, bots and the hub watch who joins and leaves a room without
+//! opening a client connection. Every room broadcast delivered on this
+//! instance, including those relayed from other instances, passes through
+//! [`PresenceFeed::publish`], so a subscriber sees each event of a room once
+//! whichever instance its clients are connected to. Subscribers are served
+//! over server-sent events and the gRPC `WatchPresence` stream.
+
+use graphwiz_protocol::generated::graphwiz::core::message::Payload;
+use graphwiz_protocol::{MessageParser, PresenceEvent, PresenceEventType};
+use serde_json::{json, Value};
+use tokio::sync::broadcast;
+
+/// Events buffered for a subscriber before it starts skipping ahead
+pub const FEED_CAPACITY: usize = 1024;
+
+/// A presence event and the room it happened in
+#[derive(Debug, Clone)]
+pub struct RoomPresenceEvent {
+    pub room_id: String,
+    pub event: PresenceEvent,
+}
+
+/// Fan-out of room presence events to observers
+#[derive(Clone)]
+pub struct PresenceFeed {
+    tx: broadcast::Sender<RoomPresenceEvent>,
+}
+
+impl PresenceFeed {
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(FEED_CAPACITY);
+        Self { tx }
+    }
+
+    /// Pass on a room broadcast if it is a presence event.
+    ///
+    /// Messages are only parsed while someone is watching. Status events
+    /// describe users rather than the room and are left out.
+    pub fn publish(&self, room_id: &str, message: &[u8]) {
+        if self.tx.receiver_count() == 0 {
+            return;
+        }
+        let Ok(parsed) = MessageParser::parse(message) else {
+            return;
+        };
+        if let Some(Payload::PresenceEvent(event)) = parsed.payload {
+            if event.event_type == PresenceEventType::Status as i32 {
+                return;
+            }
+            // Nobody may have been watching after all
+            let _ = self.tx.send(RoomPresenceEvent { room_id: room_id.to_string(), event });
+        }
+    }
+
+    /// Watch the presence events of a room from now on
+    pub fn subscribe(&self, room_id: &str) -> RoomEvents {
+        RoomEvents {
+            room_id: room_id.to_string(),
+            rx: self.tx.subscribe(),
+        }
+    }
+
+    /// Number of observers watching any room
+    pub fn observer_count(&self) -> usize {
+        self.tx.receiver_count()
+    }
+}
+
+impl Default for PresenceFeed {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Presence events of one room
+pub struct RoomEvents {
+    room_id: String,
+    rx: broadcast::Receiver<RoomPresenceEvent>,
+}
+
+impl RoomEvents {
+    /// Next event in the room, or `None` once the feed is gone.
+    ///
+    /// An observer too slow to keep up misses the events it fell behind on
+    /// rather than holding up the rooms it watches.
+    pub async fn next(&mut self) -> Option<PresenceEvent> {
+        loop {
+            match self.rx.recv().await {
+                Ok(RoomPresenceEvent { room_id, event }) if room_id == self.room_id => return Some(event),
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    log::warn!("Presence observer of room {} skipped {} events", self.room_id, missed);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+/// SSE event name of a presence event type
+pub fn event_name(event_type: i32) -> &'static str {
+    match PresenceEventType::try_from(event_type) {
+        Ok(PresenceEventType::Join) => "join",
+        Ok(PresenceEventType::Leave) => "leave",
+        Ok(PresenceEventType::Update) => "update",
+        Ok(PresenceEventType::HostChanged) => "host_changed",
+        Ok(PresenceEventType::Status) => "status",
+        Err(_) => "unknown",
+    }
+}
+
+/// JSON body of a presence event
+pub fn event_json(room_id: &str, event: &PresenceEvent) -> Value {
+    let data = event.data.clone().unwrap_or_default();
+    json!({
+        "room_id": room_id,
+        "client_id": event.client_id,
+        "event_type": event_name(event.event_type),
+        "display_name": data.display_name,
+        "avatar_url": data.avatar_url,
+        "host_client_id": data.host_client_id,
+        "position": data.position.map(|p| json!({ "x": p.x, "y": p.y, "z": p.z })),
+        "timestamp": chrono::Utc::now().timestamp_millis(),
+    })
+}
+
+/// A presence event framed as a server-sent event
+pub fn sse_frame(room_id: &str, event: &PresenceEvent) -> String {
+    format!("event: {}\ndata: {}\n\n", event_name(event.event_type), event_json(room_id, event))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graphwiz_protocol::{ChatMessageType, MessageBuilder, PresenceData};
+
+    fn join(client_id: &str) -> Vec<u8> {
+        let data = PresenceData { display_name: "Ada".to_string(), ..Default::default() };
+        let message =
+            MessageBuilder::presence_event(client_id.to_string(), PresenceEventType::Join, Some(data));
+        MessageParser::serialize(&message).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_observers_only_see_their_room() {
+        let feed = PresenceFeed::new();
+        let mut lobby = feed.subscribe("lobby");
+
+        feed.publish("other", &join("c1"));
+        feed.publish("lobby", &join("c2"));
+
+        let event = lobby.next().await.unwrap();
+        assert_eq!(event.client_id, "c2");
+        assert_eq!(event.event_type, PresenceEventType::Join as i32);
+    }
+
+    #[tokio::test]
+    async fn test_other_payloads_and_status_are_ignored() {
+        let feed = PresenceFeed::new();
+        let mut lobby = feed.subscribe("lobby");
+
+        let chat =
+            MessageBuilder::chat_message("c1".to_string(), "hi".to_string(), ChatMessageType::Normal);
+        feed.publish("lobby", &MessageParser::serialize(&chat).unwrap());
+        let status = MessageBuilder::presence_event("c1".to_string(), PresenceEventType::Status, None);
+        feed.publish("lobby", &MessageParser::serialize(&status).unwrap());
+        feed.publish("lobby", b"not protobuf");
+        feed.publish("lobby", &join("c3"));
+
+        assert_eq!(lobby.next().await.unwrap().client_id, "c3");
+    }
+
+    #[tokio::test]
+    async fn test_lagging_observer_skips_ahead() {
+        let feed = PresenceFeed::new();
+        let mut lobby = feed.subscribe("lobby");
+
+        for i in 0..FEED_CAPACITY + 10 {
+            feed.publish("lobby", &join(&format!("c{}", i)));
+        }
+
+        let event = lobby.next().await.unwrap();
+        assert_eq!(event.client_id, "c10");
+    }
+
+    #[test]
+    fn test_sse_frame() {
+        let event = PresenceEvent {
+            client_id: "c1".to_string(),
+            event_type: PresenceEventType::HostChanged as i32,
+            data: Some(PresenceData { host_client_id: "c1".to_string(), ..Default::default() }),
+        };
+
+        let frame = sse_frame("lobby", &event);
+        assert!(frame.starts_with("event: host_changed\ndata: {"));
+        assert!(frame.ends_with("}\n\n"));
+        let data = frame.lines().nth(1).unwrap().trim_start_matches("data: ");
+        let body: Value = serde_json::from_str(data).unwrap();
+        assert_eq!(body["room_id"], "lobby");
+        assert_eq!(body["host_client_id"], "c1");
+        assert!(body["position"].is_null());
+    }
+}
@@ -0,0 +1,39 @@
+//! Server-sent event stream of room presence
+//!
+//! Like the client list of a room, the stream is public: it carries what any
+//! member of the room already sees about who is there.
+
+use actix_web::{web, HttpResponse};
+use std::time::Duration;
+
+use crate::presence_feed::sse_frame;
+use crate::websocket::WebSocketManager;
+
+/// Comment sent while a room is quiet so proxies keep the stream open
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Stream join, leave, update and host change events of a room
+pub async fn stream_room_events(
+    ws_manager: web::Data<WebSocketManager>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let room_id = path.into_inner();
+    let events = ws_manager.presence_feed().subscribe(&room_id);
+    let keep_alive = tokio::time::interval(KEEP_ALIVE_INTERVAL);
+
+    let stream = futures::stream::unfold(
+        (room_id, events, keep_alive),
+        |(room_id, mut events, mut keep_alive)| async move {
+            let frame = tokio::select! {
+                event = events.next() => sse_frame(&room_id, &event?),
+                _ = keep_alive.tick() => ": keep-alive\n\n".to_string(),
+            };
+            Some((Ok::<_, actix_web::Error>(web::Bytes::from(frame)), (room_id, events, keep_alive)))
+        },
+    );
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(stream)
+}
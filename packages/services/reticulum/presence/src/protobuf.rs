@@ -1,176 +1,490 @@
-//! Protobuf message handling for WebSocket communication
+//! Protobuf message routing for presence connections
+//!
+//! Every client message passes through a chain of `MessageHandler`s before it
+//! is delivered. Handlers registered for all types run first, then those
+//! registered for the message's `MessageType`, each in registration order. A
+//! handler can rewrite the message, drop it, change where it goes, or send
+//! further messages. Without handlers a message follows the default route for
+//! its payload (see `default_route`). Messages whose type does not match their
+//! payload are dropped, so no payload can skip the handlers meant for it.
 
-use crate::reticulum_core::Result;
-use prost::Message;
-
-// Re-export generated protobuf types
-pub mod graphwiz {
-    pub mod core {
-        include!(concat!(env!("OUT_DIR"), "/graphwiz.core.rs"));
-    }
-}
+use crate::websocket::{WebSocketConnection, WebSocketManager, WsMessage};
+use async_trait::async_trait;
+use graphwiz_protocol::generated::graphwiz::core::message::Payload;
+use graphwiz_protocol::{Message, MessageParser, MessageType};
+use reticulum_core::Result;
+use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Parse protobuf message from bytes
-pub fn parse_message(bytes: &[u8]) -> Result<graphwiz::core::Message> {
-    graphwiz::core::Message::decode(bytes)
-        .map_err(|e| reticulum_core::Error::InternalError(format!("Failed to decode message: {}", e)))
+pub fn parse_message(bytes: &[u8]) -> Result<Message> {
+    MessageParser::parse(bytes)
+        .map_err(|e| reticulum_core::Error::internal(format!("Failed to decode message: {}", e)))
 }
 
 /// Encode protobuf message to bytes
-pub fn encode_message(message: &graphwiz::core::Message) -> Result<Vec<u8>> {
-    let mut buf = Vec::new();
-    message
-        .encode(&mut buf)
-        .map_err(|e| reticulum_core::Error::InternalError(format!("Failed to encode message: {}", e)))?;
-    Ok(buf)
+pub fn encode_message(message: &Message) -> Result<Vec<u8>> {
+    MessageParser::serialize(message)
+        .map_err(|e| reticulum_core::Error::internal(format!("Failed to encode message: {}", e)))
 }
 
-/// Message handler for different message types
-pub trait MessageHandler: Send + Sync {
-    fn handle_client_hello(&self, room_id: &str, conn_id: &str, msg: &graphwiz::core::ClientHello) -> Result<Option<Vec<u8>>>;
-    fn handle_position_update(&self, room_id: &str, conn_id: &str, msg: &graphwiz::core::PositionUpdate) -> Result<Option<Vec<u8>>>;
-    fn handle_chat_message(&self, room_id: &str, conn_id: &str, msg: &graphwiz::core::ChatMessage) -> Result<Option<Vec<u8>>>;
-    fn handle_entity_spawn(&self, room_id: &str, conn_id: &str, msg: &graphwiz::core::EntitySpawn) -> Result<Option<Vec<u8>>>;
-    fn handle_presence_event(&self, room_id: &str, conn_id: &str, msg: &graphwiz::core::PresenceEvent) -> Result<Option<Vec<u8>>>;
+/// Where a message is delivered
+#[derive(Debug, Clone, PartialEq)]
+pub enum Route {
+    /// Every connection in the room except the sender
+    Room,
+    /// The room's next position tick; only for position updates
+    Tick,
+    /// The listed connections
+    Connections(Vec<String>),
+    /// Back to the sender
+    Sender,
+}
+
+/// Where a payload goes when no handler redirects it, or `None` when clients
+/// may not send it
+pub fn default_route(payload: &Payload) -> Option<Route> {
+    match payload {
+        Payload::PositionUpdate(_) => Some(Route::Tick),
+        Payload::VoiceData(_)
+        | Payload::EntitySpawn(_)
+        | Payload::EntityUpdate(_)
+        | Payload::EntityDespawn(_)
+        | Payload::ChatMessage(_) => Some(Route::Room),
+        // Hellos are exchanged when the connection opens
+        Payload::ClientHello(_) => None,
+        // Handled by the user presence directory
+        Payload::PresenceSubscription(_) => None,
+        // Relayed to one peer by the signaling server
+        Payload::RtcSignal(_) => None,
+        // Sent to the hub by entity sync
+        Payload::EntityHistory(_) => None,
+        // Only the server sends these; status updates are handled by the user
+        // presence directory
+        Payload::ServerHello(_)
+        | Payload::PresenceEvent(_)
+        | Payload::Redirect(_)
+        | Payload::PositionBatch(_)
+        | Payload::SpatialAudio(_)
+        | Payload::ErrorFrame(_) => None,
+    }
 }
 
-/// Default message handler
-pub struct DefaultMessageHandler;
+/// Whether `message_type` is a type `payload` is sent under
+pub fn type_matches(payload: &Payload, message_type: i32) -> bool {
+    let Ok(message_type) = MessageType::try_from(message_type) else {
+        return false;
+    };
+    let types: &[MessageType] = match payload {
+        Payload::ClientHello(_) => &[MessageType::ClientHello],
+        Payload::ServerHello(_) => &[MessageType::ServerHello],
+        Payload::Redirect(_) => &[MessageType::Redirect],
+        Payload::PositionUpdate(_) => &[MessageType::PositionUpdate],
+        Payload::VoiceData(_) => &[MessageType::VoiceData],
+        Payload::PositionBatch(_) => &[MessageType::PositionBatch],
+        Payload::SpatialAudio(_) => &[MessageType::SpatialAudio],
+        Payload::EntitySpawn(_) => &[MessageType::EntitySpawn],
+        Payload::EntityUpdate(_) => &[MessageType::EntityUpdate],
+        Payload::EntityDespawn(_) => &[MessageType::EntityDespawn],
+        Payload::EntityHistory(_) => &[MessageType::EntityHistory],
+        Payload::ChatMessage(_) => &[MessageType::ChatMessage],
+        Payload::PresenceEvent(_) => &[
+            MessageType::PresenceJoin,
+            MessageType::PresenceLeave,
+            MessageType::PresenceUpdate,
+            MessageType::PresenceHostChanged,
+            MessageType::PresenceStatus,
+        ],
+        Payload::PresenceSubscription(_) => &[MessageType::PresenceSubscribe],
+        Payload::ErrorFrame(_) => &[MessageType::Error],
+        Payload::RtcSignal(_) => &[MessageType::RtcSignal],
+    };
+    types.contains(&message_type)
+}
 
-impl MessageHandler for DefaultMessageHandler {
-    fn handle_client_hello(&self, room_id: &str, conn_id: &str, msg: &graphwiz::core::ClientHello) -> Result<Option<Vec<u8>>> {
-        log::info!(
-            "Client hello from {} in room {}: display_name={}, auth_token={}",
-            conn_id,
+/// Whether the chain goes on after a handler
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    Continue,
+    /// Skip the remaining handlers and do not deliver the message; messages
+    /// already sent through the context are still delivered
+    Drop,
+}
+
+/// Room and session a message arrived on, and what happens to it next
+pub struct MessageContext<'a> {
+    pub ws_manager: &'a WebSocketManager,
+    pub room_id: &'a str,
+    pub conn_id: &'a str,
+    /// The sender's connection; `None` if it left while the message was queued
+    pub connection: Option<WebSocketConnection>,
+    /// Delivery of the message once the chain completes; `None` drops it
+    pub route: Option<Route>,
+    outbox: Vec<(Route, Message)>,
+}
+
+impl<'a> MessageContext<'a> {
+    pub async fn new(ws_manager: &'a WebSocketManager, room_id: &'a str, conn_id: &'a str) -> MessageContext<'a> {
+        MessageContext {
+            connection: ws_manager.get_connection_info(conn_id).await,
+            ws_manager,
             room_id,
-            msg.display_name,
-            if msg.auth_token.is_empty() { "none" } else { "***" }
-        );
+            conn_id,
+            route: None,
+            outbox: Vec::new(),
+        }
+    }
 
-        // Create server hello response
-        let response = graphwiz::core::Message {
-            message_id: uuid::Uuid::new_v4().to_string(),
-            timestamp: chrono::Utc::now().timestamp_micros(),
-            r#type: graphwiz::core::MessageType::ServerHello as i32,
-            server_hello: Some(graphwiz::core::ServerHello {
-                server_version: env!("CARGO_PKG_VERSION").to_string(),
-                assigned_client_id: conn_id.to_string(),
-                room_id: room_id.to_string(),
-                initial_state: None,
-            }),
-        };
+    /// Deliver another message once the chain completes
+    pub fn send(&mut self, route: Route, message: Message) {
+        self.outbox.push((route, message));
+    }
 
-        encode_message(&response).map(Some)
+    /// Client id of the sender, if it gave one
+    pub fn client_id(&self) -> Option<&str> {
+        self.connection.as_ref().and_then(|conn| conn.client_id.as_deref())
     }
 
-    fn handle_position_update(&self, room_id: &str, conn_id: &str, msg: &graphwiz::core::PositionUpdate) -> Result<Option<Vec<u8>>> {
-        log::debug!(
-            "Position update from {} in room {}: entity_id={}, seq={}",
-            conn_id,
-            room_id,
-            msg.entity_id,
-            msg.sequence_number
-        );
+    /// User id of the sender, if an access token proved it
+    pub fn user_id(&self) -> Option<&str> {
+        self.connection
+            .as_ref()
+            .filter(|conn| conn.authenticated)
+            .and_then(|conn| conn.user_id.as_deref())
+    }
+}
 
-        // Broadcast to other clients in room
-        Ok(None) // Let the WebSocket manager handle broadcasting
+/// Step in the message handler chain
+#[async_trait]
+pub trait MessageHandler: Send + Sync {
+    /// Handle `message`, which may be modified in place
+    async fn handle(&self, message: &mut Message, ctx: &mut MessageContext<'_>) -> Result<Flow>;
+}
+
+/// Handlers to run per message type
+#[derive(Clone, Default)]
+pub struct HandlerChain {
+    global: Vec<Arc<dyn MessageHandler>>,
+    by_type: HashMap<i32, Vec<Arc<dyn MessageHandler>>>, // MessageType -> handlers
+}
+
+impl HandlerChain {
+    /// Empty chain; every message follows its default route
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    fn handle_chat_message(&self, room_id: &str, conn_id: &str, msg: &graphwiz::core::ChatMessage) -> Result<Option<Vec<u8>>> {
-        log::info!(
-            "Chat message from {} in room {}: {}",
-            conn_id,
-            room_id,
-            msg.message
-        );
+    /// Chain with the handlers the presence service relies on
+    pub fn with_defaults() -> Self {
+        let mut chain = Self::new();
+        chain.register(MessageType::VoiceData, Arc::new(SpeakingHandler));
+        chain
+    }
 
-        // Broadcast to other clients
-        Ok(None)
+    /// Run `handler` for messages of `message_type`
+    pub fn register(&mut self, message_type: MessageType, handler: Arc<dyn MessageHandler>) -> &mut Self {
+        self.by_type
+            .entry(message_type as i32)
+            .or_default()
+            .push(handler);
+        self
     }
 
-    fn handle_entity_spawn(&self, room_id: &str, conn_id: &str, msg: &graphwiz::core::EntitySpawn) -> Result<Option<Vec<u8>>> {
-        log::info!(
-            "Entity spawn from {} in room {}: entity_id={}, template={}",
-            conn_id,
-            room_id,
-            msg.entity_id,
-            msg.template_id
-        );
+    /// Run `handler` for every message, before the per-type handlers
+    pub fn register_all(&mut self, handler: Arc<dyn MessageHandler>) -> &mut Self {
+        self.global.push(handler);
+        self
+    }
 
-        Ok(None)
+    /// Run the handlers for `message`, stopping at the first `Flow::Drop`
+    pub async fn run(&self, message: &mut Message, ctx: &mut MessageContext<'_>) -> Result<Flow> {
+        let typed = self.by_type.get(&message.r#type).into_iter().flatten();
+        for handler in self.global.iter().chain(typed) {
+            if handler.handle(message, ctx).await? == Flow::Drop {
+                ctx.route = None;
+                return Ok(Flow::Drop);
+            }
+        }
+        Ok(Flow::Continue)
     }
+}
 
-    fn handle_presence_event(&self, room_id: &str, conn_id: &str, msg: &graphwiz::core::PresenceEvent) -> Result<Option<Vec<u8>>> {
-        log::info!(
-            "Presence event from {} in room {}: client_id={:?}, event_type={:?}",
-            conn_id,
-            room_id,
-            msg.client_id,
-            msg.r#type
-        );
+/// Delivers voice at full rate by marking the sender as speaking
+struct SpeakingHandler;
 
-        Ok(None)
+#[async_trait]
+impl MessageHandler for SpeakingHandler {
+    async fn handle(&self, _message: &mut Message, ctx: &mut MessageContext<'_>) -> Result<Flow> {
+        ctx.ws_manager.mark_speaking(ctx.conn_id).await;
+        Ok(Flow::Continue)
     }
 }
 
-/// Route a protobuf message to the appropriate handler
+/// Route a client message through the handler chain and deliver the result.
+///
+/// Payloads that are not protobuf messages are dropped.
 pub async fn route_message(
-    message: &graphwiz::core::Message,
+    chain: &HandlerChain,
+    ws_manager: &WebSocketManager,
     room_id: &str,
     conn_id: &str,
-    handler: &dyn MessageHandler,
-) -> Result<Option<Vec<u8>>> {
-    // Check for client hello
-    if let Some(hello) = &message.client_hello {
-        return handler.handle_client_hello(room_id, conn_id, hello);
-    }
+    bytes: &[u8],
+) -> Result<()> {
+    let mut message = match parse_message(bytes) {
+        Ok(message) => message,
+        Err(e) => {
+            log::debug!("Dropping undecodable message from {}: {}", conn_id, e);
+            return Ok(());
+        }
+    };
 
-    // Check for position update
-    if let Some(pos_update) = &message.position_update {
-        return handler.handle_position_update(room_id, conn_id, pos_update);
+    // Handlers are chosen by type and routes by payload, so the two must agree
+    if !message.payload.as_ref().is_some_and(|payload| type_matches(payload, message.r#type)) {
+        log::debug!(
+            "Dropping message of type {} from {}: type does not match its payload",
+            message.r#type,
+            conn_id
+        );
+        return Ok(());
     }
 
-    // Check for chat message
-    if let Some(chat) = &message.chat_message {
-        return handler.handle_chat_message(room_id, conn_id, chat);
+    let mut ctx = MessageContext::new(ws_manager, room_id, conn_id).await;
+    ctx.route = message.payload.as_ref().and_then(default_route);
+    if ctx.route.is_none() {
+        log::debug!(
+            "Dropping message of type {} from {}: not relayed from clients",
+            message.r#type,
+            conn_id
+        );
     }
 
-    // Check for entity spawn
-    if let Some(spawn) = &message.entity_spawn {
-        return handler.handle_entity_spawn(room_id, conn_id, spawn);
+    let flow = chain.run(&mut message, &mut ctx).await?;
+
+    let MessageContext { route, outbox, .. } = ctx;
+    if let (Flow::Continue, Some(route)) = (flow, route) {
+        deliver(ws_manager, room_id, conn_id, &route, message).await?;
+    }
+    for (route, message) in outbox {
+        deliver(ws_manager, room_id, conn_id, &route, message).await?;
     }
 
-    // Check for presence event
-    if let Some(presence) = &message.presence_event {
-        return handler.handle_presence_event(room_id, conn_id, presence);
+    Ok(())
+}
+
+async fn deliver(
+    ws_manager: &WebSocketManager,
+    room_id: &str,
+    conn_id: &str,
+    route: &Route,
+    message: Message,
+) -> Result<()> {
+    let message = match (route, message) {
+        (Route::Tick, Message { payload: Some(Payload::PositionUpdate(update)), .. }) => {
+            ws_manager.queue_position_update(room_id, conn_id, update).await;
+            return Ok(());
+        }
+        // Anything else sent to the tick goes to the room as it is
+        (_, message) => message,
+    };
+
+    let bytes = encode_message(&message)?;
+    match route {
+        Route::Room | Route::Tick => ws_manager.broadcast_to_room(room_id, &bytes, Some(conn_id)).await,
+        Route::Connections(conn_ids) => {
+            for target in conn_ids {
+                ws_manager
+                    .send_to_connection(target, WsMessage::Binary(bytes.clone()))
+                    .await?;
+            }
+        }
+        Route::Sender => {
+            ws_manager
+                .send_to_connection(conn_id, WsMessage::Binary(bytes))
+                .await?
+        }
     }
 
-    Ok(None)
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::queue::OutboundReceiver;
+    use graphwiz_protocol::generated::graphwiz::core::PresenceEventType;
+    use graphwiz_protocol::{ChatMessageType, MessageBuilder};
+    use std::time::Duration;
+
+    async fn next_message(rx: &mut OutboundReceiver<WsMessage>) -> Option<Message> {
+        match tokio::time::timeout(Duration::from_millis(100), rx.queue.recv()).await {
+            Ok(Some(WsMessage::Binary(bytes))) => MessageParser::parse(&bytes).ok(),
+            _ => None,
+        }
+    }
+
+    async fn next_message_bytes(rx: &mut OutboundReceiver<WsMessage>) -> Option<WsMessage> {
+        tokio::time::timeout(Duration::from_millis(100), rx.queue.recv())
+            .await
+            .ok()
+            .flatten()
+    }
+
+    fn chat(text: &str) -> Vec<u8> {
+        let message = MessageBuilder::chat_message("alice".to_string(), text.to_string(), ChatMessageType::Normal);
+        MessageParser::serialize(&message).unwrap()
+    }
+
+    fn chat_text(message: &Message) -> Option<&str> {
+        match &message.payload {
+            Some(Payload::ChatMessage(chat)) => Some(chat.message.as_str()),
+            _ => None,
+        }
+    }
+
+    async fn room() -> (WebSocketManager, OutboundReceiver<WsMessage>, OutboundReceiver<WsMessage>) {
+        let ws_manager = WebSocketManager::new();
+        let sender = ws_manager
+            .add_connection("sender".to_string(), Some("room-1".to_string()), None, Some("alice".to_string()))
+            .await;
+        let peer = ws_manager
+            .add_connection("peer".to_string(), Some("room-1".to_string()), None, Some("bob".to_string()))
+            .await;
+        (ws_manager, sender, peer)
+    }
+
+    /// Uppercases chat and tells the sender it was heard
+    struct Shout;
+
+    #[async_trait]
+    impl MessageHandler for Shout {
+        async fn handle(&self, message: &mut Message, ctx: &mut MessageContext<'_>) -> Result<Flow> {
+            if let Some(Payload::ChatMessage(chat)) = &mut message.payload {
+                chat.message = chat.message.to_uppercase();
+            }
+            let ack = MessageBuilder::chat_message("server".to_string(), "heard".to_string(), ChatMessageType::Normal);
+            ctx.send(Route::Sender, ack);
+            Ok(Flow::Continue)
+        }
+    }
+
+    /// Drops every message from clients named in the list
+    struct Block(Vec<&'static str>);
+
+    #[async_trait]
+    impl MessageHandler for Block {
+        async fn handle(&self, _message: &mut Message, ctx: &mut MessageContext<'_>) -> Result<Flow> {
+            if ctx.client_id().is_some_and(|client_id| self.0.contains(&client_id)) {
+                return Ok(Flow::Drop);
+            }
+            Ok(Flow::Continue)
+        }
+    }
 
     #[test]
     fn test_parse_message() {
-        // Create a test message
-        let msg = graphwiz::core::Message {
-            message_id: "test-123".to_string(),
-            timestamp: 12345,
-            r#type: graphwiz::core::MessageType::ClientHello as i32,
-            client_hello: Some(graphwiz::core::ClientHello {
-                client_id: "client-1".to_string(),
-                display_name: "Test User".to_string(),
-                auth_token: "token-123".to_string(),
-                requested_room: "room-1".to_string(),
-            }),
-            ..Default::default()
-        };
-
-        let encoded = encode_message(&msg).unwrap();
+        let mut message = MessageBuilder::client_hello(
+            "client-1".to_string(),
+            "Test User".to_string(),
+            "token-123".to_string(),
+            "room-1".to_string(),
+        );
+        message.message_id = "test-123".to_string();
+
+        let encoded = encode_message(&message).unwrap();
         let decoded = parse_message(&encoded).unwrap();
 
         assert_eq!(decoded.message_id, "test-123");
+        assert!(parse_message(b"\xff\xff not protobuf").is_err());
+    }
+
+    #[test]
+    fn test_client_payloads_have_default_routes() {
+        let relayed = [
+            MessageBuilder::entity_despawn("entity-1".to_string()),
+            MessageBuilder::chat_message("alice".to_string(), "hi".to_string(), ChatMessageType::Normal),
+        ];
+        for message in relayed {
+            assert_eq!(default_route(message.payload.as_ref().unwrap()), Some(Route::Room));
+        }
+
+        let dropped = [
+            MessageBuilder::position_batch(Vec::new(), 1),
+            MessageBuilder::presence_event("alice".to_string(), PresenceEventType::Join, None),
+        ];
+        for message in dropped {
+            assert_eq!(default_route(message.payload.as_ref().unwrap()), None);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_undecodable_messages_are_dropped() {
+        let (ws_manager, mut sender, mut peer) = room().await;
+        let chain = HandlerChain::with_defaults();
+
+        route_message(&chain, &ws_manager, "room-1", "sender", b"\xff\xff not protobuf")
+            .await
+            .unwrap();
+
+        assert!(next_message_bytes(&mut peer).await.is_none());
+        assert!(next_message_bytes(&mut sender).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_messages_must_match_their_payload() {
+        let (ws_manager, mut sender, mut peer) = room().await;
+        let mut chain = HandlerChain::with_defaults();
+        chain.register(MessageType::ChatMessage, Arc::new(Shout));
+        chain.register(MessageType::PositionUpdate, Arc::new(Shout));
+
+        // Chat posing as a position update would skip the chat handlers
+        let mut disguised = MessageBuilder::chat_message("bob".to_string(), "hello".to_string(), ChatMessageType::Normal);
+        disguised.r#type = MessageType::PositionUpdate as i32;
+        let mut empty = MessageBuilder::chat_message("bob".to_string(), "hello".to_string(), ChatMessageType::Normal);
+        empty.payload = None;
+        for message in [disguised, empty] {
+            route_message(&chain, &ws_manager, "room-1", "sender", &MessageParser::serialize(&message).unwrap())
+                .await
+                .unwrap();
+        }
+
+        assert!(next_message(&mut peer).await.is_none());
+        assert!(next_message(&mut sender).await.is_none());
+        assert!(type_matches(
+            &Payload::PresenceEvent(Default::default()),
+            MessageType::PresenceStatus as i32
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_handlers_transform_and_fan_out() {
+        let (ws_manager, mut sender, mut peer) = room().await;
+        let mut chain = HandlerChain::with_defaults();
+        chain.register(MessageType::ChatMessage, Arc::new(Shout));
+
+        route_message(&chain, &ws_manager, "room-1", "sender", &chat("hello"))
+            .await
+            .unwrap();
+
+        let relayed = next_message(&mut peer).await.unwrap();
+        assert_eq!(chat_text(&relayed), Some("HELLO"));
+        let ack = next_message(&mut sender).await.unwrap();
+        assert_eq!(chat_text(&ack), Some("heard"));
+        assert!(next_message(&mut sender).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_drop_stops_chain() {
+        let (ws_manager, mut sender, mut peer) = room().await;
+        let mut chain = HandlerChain::with_defaults();
+        chain.register_all(Arc::new(Block(vec!["alice"])));
+        chain.register(MessageType::ChatMessage, Arc::new(Shout));
+
+        route_message(&chain, &ws_manager, "room-1", "sender", &chat("hello"))
+            .await
+            .unwrap();
+
+        assert!(next_message(&mut peer).await.is_none());
+        // Shout never ran, so there is no acknowledgement either
+        assert!(next_message(&mut sender).await.is_none());
     }
 }
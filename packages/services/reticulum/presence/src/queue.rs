@@ -2,9 +2,97 @@
 
 use reticulum_core::Result;
 use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
-use tokio::time::{Duration, Instant};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{mpsc, Notify, RwLock};
+use std::time::{Duration, Instant};
+
+/// Outcome of offering a message to an outbound queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Enqueued {
+    Sent,
+    /// Dropped to leave room for reliable messages
+    Shed,
+    /// The receiver cannot keep up and should be disconnected
+    SlowConsumer,
+    Closed,
+}
+
+/// Sending half of a connection's bounded outbound queue.
+///
+/// Unreliable messages are shed once the queue is half full, keeping the rest
+/// for reliable ones. A receiver that has no room for a reliable message, or
+/// that keeps shedding for longer than `slow_timeout`, is a slow consumer.
+#[derive(Clone)]
+pub struct OutboundQueue<T> {
+    tx: mpsc::Sender<T>,
+    capacity: usize,
+    slow_timeout: Duration,
+    shedding_since: Arc<Mutex<Option<Instant>>>,
+    evicted: Arc<Notify>,
+}
+
+/// Receiving half of an outbound queue
+pub struct OutboundReceiver<T> {
+    pub queue: mpsc::Receiver<T>,
+    /// Notified when the sender gives up on a slow consumer
+    pub evicted: Arc<Notify>,
+}
+
+/// Create a bounded outbound queue
+pub fn outbound_queue<T>(capacity: usize, slow_timeout: Duration) -> (OutboundQueue<T>, OutboundReceiver<T>) {
+    let (tx, rx) = mpsc::channel(capacity.max(2));
+    let evicted = Arc::new(Notify::new());
+
+    let queue = OutboundQueue {
+        tx,
+        capacity: capacity.max(2),
+        slow_timeout,
+        shedding_since: Arc::new(Mutex::new(None)),
+        evicted: evicted.clone(),
+    };
+    (queue, OutboundReceiver { queue: rx, evicted })
+}
+
+impl<T> OutboundQueue<T> {
+    /// Queue `message` without waiting for room
+    pub fn offer(&self, message: T, reliable: bool, now: Instant) -> Enqueued {
+        if !reliable && self.tx.capacity() <= self.capacity / 2 {
+            let mut shedding_since = self.shedding_since.lock().unwrap();
+            let since = *shedding_since.get_or_insert(now);
+            return if now.duration_since(since) > self.slow_timeout {
+                Enqueued::SlowConsumer
+            } else {
+                Enqueued::Shed
+            };
+        }
+
+        match self.tx.try_send(message) {
+            Ok(()) => {
+                if !reliable {
+                    *self.shedding_since.lock().unwrap() = None;
+                }
+                Enqueued::Sent
+            }
+            Err(TrySendError::Full(_)) => Enqueued::SlowConsumer,
+            Err(TrySendError::Closed(_)) => Enqueued::Closed,
+        }
+    }
+
+    /// Tell the receiver to disconnect
+    pub fn evict(&self) {
+        self.evicted.notify_one();
+    }
+
+    /// Messages waiting to be sent
+    pub fn len(&self) -> usize {
+        self.capacity - self.tx.capacity()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
 
 /// Message with acknowledgment tracking
 #[derive(Clone, Debug)]
@@ -66,10 +154,10 @@ impl MessageQueue {
 
             tx.send(msg)
                 .await
-                .map_err(|_| reticulum_core::Error::InternalError("Queue closed".to_string()))?;
+                .map_err(|_| reticulum_core::Error::internal("Queue closed"))?;
             Ok(())
         } else {
-            Err(reticulum_core::Error::InternalError(format!(
+            Err(reticulum_core::Error::internal(format!(
                 "Queue not found for connection: {}",
                 conn_id
             )))
@@ -81,7 +169,7 @@ impl MessageQueue {
         let queues = self.queues.read().await;
         queues
             .get(conn_id)
-            .map(|tx| tx.capacity() - tx.len())
+            .map(|tx| self.queue_size - tx.capacity())
             .unwrap_or(0)
     }
 
@@ -90,6 +178,11 @@ impl MessageQueue {
         let queues = self.queues.read().await;
         queues.len()
     }
+
+    /// Interval between retry attempts
+    pub fn retry_interval(&self) -> Duration {
+        self.retry_interval
+    }
 }
 
 impl Default for MessageQueue {
@@ -207,7 +300,7 @@ impl ReliableDelivery {
         let message_id = uuid::Uuid::new_v4().to_string();
 
         // Register for acknowledgment tracking
-        self.ack_tracker.register(message_id.clone(), data.clone());
+        self.ack_tracker.register(message_id.clone(), data.clone()).await;
 
         // Queue for delivery
         self.queue
@@ -282,8 +375,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_reliable_delivery() {
-        let delivery = ReliableDelivery::default();
+        let queue = MessageQueue::default();
+        let delivery = ReliableDelivery::new(queue.clone(), AckTracker::default());
         let conn_id = "test-conn";
+        let _rx = queue.create_queue(conn_id.to_string()).await;
 
         // Send reliable message
         let data = vec![9, 10, 11, 12];
@@ -315,4 +410,36 @@ mod tests {
         assert_eq!(expired.len(), 1);
         assert_eq!(expired[0].0, message_id);
     }
+
+    #[tokio::test]
+    async fn test_outbound_queue_sheds_before_reliable() {
+        let (queue, mut rx) = outbound_queue::<u8>(4, Duration::from_secs(5));
+        let now = Instant::now();
+
+        assert_eq!(queue.offer(1, false, now), Enqueued::Sent);
+        assert_eq!(queue.offer(2, false, now), Enqueued::Sent);
+        // Half full: unreliable messages are shed, reliable ones still fit
+        assert_eq!(queue.offer(3, false, now), Enqueued::Shed);
+        assert_eq!(queue.offer(4, true, now), Enqueued::Sent);
+        assert_eq!(queue.offer(5, true, now), Enqueued::Sent);
+        assert_eq!(queue.len(), 4);
+
+        // No room for a reliable message
+        assert_eq!(queue.offer(6, true, now), Enqueued::SlowConsumer);
+
+        assert_eq!(rx.queue.recv().await, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_outbound_queue_evicts_persistent_shedding() {
+        let (queue, rx) = outbound_queue::<u8>(2, Duration::from_secs(5));
+        let now = Instant::now();
+
+        assert_eq!(queue.offer(1, false, now), Enqueued::Sent);
+        assert_eq!(queue.offer(2, false, now), Enqueued::Shed);
+        assert_eq!(queue.offer(3, false, now + Duration::from_secs(6)), Enqueued::SlowConsumer);
+
+        queue.evict();
+        rx.evicted.notified().await;
+    }
 }
@@ -62,7 +62,7 @@ impl RateLimiter {
         // Check if limit exceeded
         if window.timestamps.len() >= self.max_requests {
             let wait_time = self.window_duration.saturating_sub(now.duration_since(window.window_start));
-            return Err(reticulum_core::Error::RateLimitExceeded(format!(
+            return Err(reticulum_core::Error::rate_limited(format!(
                 "Rate limit exceeded. Try again in {} seconds.",
                 wait_time.as_secs()
             )));
@@ -106,6 +106,103 @@ struct SlidingWindow {
     window_start: Instant,
 }
 
+/// Token bucket allowing bursts of up to `capacity` requests at a sustained
+/// `refill_per_sec`
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    tokens: f64,
+    capacity: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Create a full bucket
+    pub fn new(capacity: usize, refill_per_sec: f64, now: Instant) -> Self {
+        Self {
+            tokens: capacity as f64,
+            capacity: capacity as f64,
+            refill_per_sec,
+            last_refill: now,
+        }
+    }
+
+    /// Take a token, or return how long until one is available
+    pub fn try_take(&mut self, now: Instant) -> std::result::Result<(), Duration> {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / self.refill_per_sec))
+        }
+    }
+
+    /// Tokens taken and not yet refilled
+    pub fn used(&self) -> usize {
+        (self.capacity - self.tokens.floor()) as usize
+    }
+}
+
+/// Per-key token bucket rate limiter
+#[derive(Clone)]
+pub struct TokenBucketLimiter {
+    buckets: Arc<RwLock<HashMap<String, TokenBucket>>>,
+    capacity: usize,
+    refill_per_sec: f64,
+}
+
+impl TokenBucketLimiter {
+    /// Create a new token bucket limiter
+    ///
+    /// # Arguments
+    /// * `capacity` - Maximum burst size per key
+    /// * `refill_per_sec` - Sustained requests per second per key
+    pub fn new(capacity: usize, refill_per_sec: f64) -> Self {
+        Self {
+            buckets: Arc::new(RwLock::new(HashMap::new())),
+            capacity: capacity.max(1),
+            refill_per_sec: refill_per_sec.max(f64::MIN_POSITIVE),
+        }
+    }
+
+    /// Take a token for `key`, or return how long to wait for the next one
+    pub async fn acquire(&self, key: &str) -> std::result::Result<(), Duration> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.write().await;
+        buckets
+            .entry(key.to_string())
+            .or_insert_with(|| TokenBucket::new(self.capacity, self.refill_per_sec, now))
+            .try_take(now)
+    }
+
+    /// Check if a request should be allowed
+    pub async fn check_rate_limit(&self, key: &str) -> Result<()> {
+        self.acquire(key).await.map_err(|retry_after| {
+            reticulum_core::Error::rate_limited(format!(
+                "Rate limit exceeded. Try again in {} ms.",
+                retry_after.as_millis()
+            ))
+        })
+    }
+
+    /// Remove a key from the rate limiter (cleanup on disconnect)
+    pub async fn remove(&self, key: &str) {
+        let mut buckets = self.buckets.write().await;
+        buckets.remove(key);
+    }
+
+    /// Get current usage for a key
+    pub async fn get_usage(&self, key: &str) -> (usize, usize) {
+        let buckets = self.buckets.read().await;
+        let used = buckets.get(key).map(TokenBucket::used).unwrap_or(0);
+        (used, self.capacity)
+    }
+}
+
 /// Rate limiter metrics
 #[derive(Clone, Debug)]
 pub struct RateLimiterMetrics {
@@ -114,17 +211,21 @@ pub struct RateLimiterMetrics {
     pub current_connections: usize,
 }
 
-/// Rate limiter with metrics tracking
+/// Rate limiter with metrics tracking.
+///
+/// Backed by token buckets, so a key may burst up to `max_requests` at once
+/// while its sustained rate stays at `max_requests` per window.
 #[derive(Clone)]
 pub struct MetricRateLimiter {
-    inner: RateLimiter,
+    inner: TokenBucketLimiter,
     metrics: Arc<RwLock<RateLimiterMetrics>>,
 }
 
 impl MetricRateLimiter {
     pub fn new(max_requests: usize, window_duration_secs: u64) -> Self {
+        let refill_per_sec = max_requests as f64 / window_duration_secs.max(1) as f64;
         Self {
-            inner: RateLimiter::new(max_requests, window_duration_secs),
+            inner: TokenBucketLimiter::new(max_requests, refill_per_sec),
             metrics: Arc::new(RwLock::new(RateLimiterMetrics {
                 total_requests: 0,
                 blocked_requests: 0,
@@ -133,18 +234,28 @@ impl MetricRateLimiter {
         }
     }
 
-    /// Check rate limit and track metrics
-    pub async fn check_rate_limit(&self, key: &str) -> Result<()> {
+    /// Take a token for `key` and track metrics, returning how long to wait
+    /// when the limit is exceeded
+    pub async fn acquire(&self, key: &str) -> std::result::Result<(), Duration> {
+        let result = self.inner.acquire(key).await;
+
         let mut metrics = self.metrics.write().await;
         metrics.total_requests += 1;
-
-        match self.inner.check_rate_limit(key).await {
-            Ok(()) => Ok(()),
-            Err(e) => {
-                metrics.blocked_requests += 1;
-                Err(e)
-            }
+        if result.is_err() {
+            metrics.blocked_requests += 1;
         }
+
+        result
+    }
+
+    /// Check rate limit and track metrics
+    pub async fn check_rate_limit(&self, key: &str) -> Result<()> {
+        self.acquire(key).await.map_err(|retry_after| {
+            reticulum_core::Error::rate_limited(format!(
+                "Rate limit exceeded. Try again in {} ms.",
+                retry_after.as_millis()
+            ))
+        })
     }
 
     /// Remove a key from the rate limiter
@@ -246,4 +357,37 @@ mod tests {
         // Should work again
         assert!(limiter.check_rate_limit("test-key").await.is_ok());
     }
+
+    #[test]
+    fn test_token_bucket_refills() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(2, 10.0, start); // burst of 2, 10 per second
+
+        assert!(bucket.try_take(start).is_ok());
+        assert!(bucket.try_take(start).is_ok());
+        assert_eq!(bucket.used(), 2);
+
+        // Empty: the next token arrives after 100ms
+        let retry_after = bucket.try_take(start).unwrap_err();
+        assert_eq!(retry_after.as_millis(), 100);
+
+        assert!(bucket.try_take(start + Duration::from_millis(100)).is_ok());
+        // Refills never exceed the burst size
+        assert!(bucket.try_take(start + Duration::from_secs(10)).is_ok());
+        assert!(bucket.try_take(start + Duration::from_secs(10)).is_ok());
+        assert!(bucket.try_take(start + Duration::from_secs(10)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_metric_rate_limiter_counts_blocked() {
+        let limiter = MetricRateLimiter::new(1, 60);
+
+        assert!(limiter.acquire("conn").await.is_ok());
+        assert!(limiter.acquire("conn").await.is_err());
+        assert!(limiter.check_rate_limit("other").await.is_ok());
+
+        let metrics = limiter.get_metrics().await;
+        assert_eq!(metrics.total_requests, 3);
+        assert_eq!(metrics.blocked_requests, 1);
+    }
 }
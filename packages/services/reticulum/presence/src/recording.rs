@@ -0,0 +1,743 @@
+//! Session recording and replay
+//!
+//! A recording is a log of a room's traffic: every message its clients send
+//! to presence and every message presence broadcasts to the room, each
+//! stamped with when it happened. Logs are appended to the storage service
+//! while the session runs and can be streamed back into a fresh,
+//! spectator-only room at 1x or faster, for event recaps, training material
+//! or chasing desync bugs.
+//!
+//! The log format is compact and append-only, with all integers as varints:
+//!
+//! - header: `GWRC`, format version, start time in Unix milliseconds, room id
+//! - entries: milliseconds since the previous entry, direction (0 inbound,
+//!   1 outbound), sender's client id (empty for outbound), message bytes
+//!
+//! Strings and messages are prefixed by their length. Position updates are
+//! recorded as the unthinned batch of each room tick, before area of interest
+//! filters it per client.
+//!
+//! A room is recorded by the instance it was started on. That instance sees
+//! every broadcast in the room, including those relayed from other
+//! instances, but only the inbound messages of its own clients.
+
+use async_trait::async_trait;
+use graphwiz_protocol::generated::graphwiz::core::message::Payload;
+use graphwiz_protocol::{Message, MessageBuilder};
+use prost::encoding::{decode_varint, encode_varint};
+use reticulum_core::{Error, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock};
+use uuid::Uuid;
+
+use crate::protobuf::{Flow, MessageContext, MessageHandler, Route};
+use crate::websocket::WebSocketManager;
+
+/// First bytes of every session log
+const LOG_MAGIC: &[u8; 4] = b"GWRC";
+const LOG_VERSION: u64 = 1;
+
+/// Time between appends of a recording to storage
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Pending log size at which a recording is appended without waiting for the
+/// next flush
+const FLUSH_SIZE: usize = 256 * 1024;
+
+/// Pending log size at which a recording whose appends keep failing is
+/// given up
+const MAX_PENDING: usize = 4 * 1024 * 1024;
+
+pub const MAX_REPLAY_SPEED: f64 = 16.0;
+
+/// Default time between starting a replay and its first message, for
+/// spectators to join the replay room
+const DEFAULT_REPLAY_LEAD_IN: Duration = Duration::from_secs(5);
+
+/// Rooms starting with this are replays, where clients only watch
+pub const REPLAY_ROOM_PREFIX: &str = "replay-";
+
+/// Whether clients in `room_id` are spectators of a replay
+pub fn is_replay_room(room_id: &str) -> bool {
+    room_id.starts_with(REPLAY_ROOM_PREFIX)
+}
+
+/// Which way a recorded message went
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    /// From a client to presence
+    Inbound,
+    /// From presence to the room
+    Outbound,
+}
+
+/// One message of a session log
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    /// Milliseconds since the recording started
+    pub offset_ms: u64,
+    pub direction: Direction,
+    /// Client that sent an inbound message
+    pub client_id: Option<String>,
+    pub message: Vec<u8>,
+}
+
+/// A decoded session log
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionLog {
+    pub room_id: String,
+    pub started_at_ms: u64,
+    pub entries: Vec<LogEntry>,
+}
+
+impl SessionLog {
+    /// Header of a new log
+    pub fn encode_header(room_id: &str, started_at_ms: u64) -> Vec<u8> {
+        let mut buf = LOG_MAGIC.to_vec();
+        encode_varint(LOG_VERSION, &mut buf);
+        encode_varint(started_at_ms, &mut buf);
+        encode_bytes(room_id.as_bytes(), &mut buf);
+        buf
+    }
+
+    /// Append an entry `delta_ms` after the previous one
+    pub fn encode_entry(buf: &mut Vec<u8>, delta_ms: u64, direction: Direction, client_id: &str, message: &[u8]) {
+        encode_varint(delta_ms, buf);
+        buf.push(match direction {
+            Direction::Inbound => 0,
+            Direction::Outbound => 1,
+        });
+        encode_bytes(client_id.as_bytes(), buf);
+        encode_bytes(message, buf);
+    }
+
+    /// Decode a log. An entry cut short at the end, as left by a recording
+    /// whose last append did not complete, is ignored.
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let invalid = |what: &str| Error::validation(format!("Invalid session log: {}", what));
+
+        let mut buf = bytes
+            .strip_prefix(LOG_MAGIC.as_slice())
+            .ok_or_else(|| invalid("not a session log"))?;
+        let version = decode_varint(&mut buf).map_err(|_| invalid("truncated header"))?;
+        if version != LOG_VERSION {
+            return Err(invalid(&format!("unsupported version {}", version)));
+        }
+        let started_at_ms = decode_varint(&mut buf).map_err(|_| invalid("truncated header"))?;
+        let room_id = decode_bytes(&mut buf)
+            .and_then(|room_id| String::from_utf8(room_id.to_vec()).ok())
+            .ok_or_else(|| invalid("bad room id"))?;
+
+        let mut entries = Vec::new();
+        let mut offset_ms = 0;
+        while !buf.is_empty() {
+            let Some(entry) = decode_entry(&mut buf, &mut offset_ms) else {
+                log::warn!("Session log of room {} ends in a partial entry", room_id);
+                break;
+            };
+            entries.push(entry);
+        }
+
+        Ok(Self { room_id, started_at_ms, entries })
+    }
+
+    /// Milliseconds from the start of the recording to its last message
+    pub fn duration_ms(&self) -> u64 {
+        self.entries.last().map_or(0, |entry| entry.offset_ms)
+    }
+}
+
+fn encode_bytes(bytes: &[u8], buf: &mut Vec<u8>) {
+    encode_varint(bytes.len() as u64, buf);
+    buf.extend_from_slice(bytes);
+}
+
+fn decode_bytes<'a>(buf: &mut &'a [u8]) -> Option<&'a [u8]> {
+    let len = decode_varint(buf).ok()? as usize;
+    if buf.len() < len {
+        return None;
+    }
+    let (bytes, rest) = buf.split_at(len);
+    *buf = rest;
+    Some(bytes)
+}
+
+fn decode_entry(buf: &mut &[u8], offset_ms: &mut u64) -> Option<LogEntry> {
+    *offset_ms += decode_varint(buf).ok()?;
+    let (&direction, rest) = buf.split_first()?;
+    *buf = rest;
+    let direction = match direction {
+        0 => Direction::Inbound,
+        1 => Direction::Outbound,
+        _ => return None,
+    };
+    let client_id = String::from_utf8(decode_bytes(buf)?.to_vec()).ok()?;
+    let message = decode_bytes(buf)?.to_vec();
+    Some(LogEntry {
+        offset_ms: *offset_ms,
+        direction,
+        client_id: (!client_id.is_empty()).then_some(client_id),
+        message,
+    })
+}
+
+/// Where session logs are kept
+#[async_trait]
+pub trait RecordingStore: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Append `chunk` to a log, creating it if needed
+    async fn append(&self, recording_id: &str, chunk: &[u8]) -> Result<()>;
+
+    /// The whole log, or `None` if there is no such recording
+    async fn fetch(&self, recording_id: &str) -> Result<Option<Vec<u8>>>;
+}
+
+/// Logs kept by the storage service
+pub struct StorageServiceStore {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl StorageServiceStore {
+    pub fn new(base_url: &str) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| Error::internal(format!("Failed to create storage client: {}", e)))?;
+        Ok(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl RecordingStore for StorageServiceStore {
+    fn name(&self) -> &'static str {
+        "storage"
+    }
+
+    async fn append(&self, recording_id: &str, chunk: &[u8]) -> Result<()> {
+        self.client
+            .post(format!("{}/recordings/{}/chunks", self.base_url, recording_id))
+            .header("Content-Type", "application/octet-stream")
+            .body(chunk.to_vec())
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| Error::internal(format!("Failed to append to recording {}: {}", recording_id, e)))?;
+        Ok(())
+    }
+
+    async fn fetch(&self, recording_id: &str) -> Result<Option<Vec<u8>>> {
+        let failed = |e: reqwest::Error| Error::internal(format!("Failed to fetch recording {}: {}", recording_id, e));
+
+        let response = self
+            .client
+            .get(format!("{}/recordings/{}", self.base_url, recording_id))
+            .send()
+            .await
+            .map_err(failed)?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let bytes = response.error_for_status().map_err(failed)?.bytes().await.map_err(failed)?;
+        Ok(Some(bytes.to_vec()))
+    }
+}
+
+/// Logs kept in memory, for a single instance without a storage service
+#[derive(Default)]
+pub struct MemoryRecordingStore {
+    logs: RwLock<HashMap<String, Vec<u8>>>,
+}
+
+impl MemoryRecordingStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RecordingStore for MemoryRecordingStore {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn append(&self, recording_id: &str, chunk: &[u8]) -> Result<()> {
+        let mut logs = self.logs.write().await;
+        logs.entry(recording_id.to_string()).or_default().extend_from_slice(chunk);
+        Ok(())
+    }
+
+    async fn fetch(&self, recording_id: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self.logs.read().await.get(recording_id).cloned())
+    }
+}
+
+/// A recording in progress, as reported to the API
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingInfo {
+    pub recording_id: String,
+    pub room_id: String,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    /// Messages recorded so far
+    pub messages: u64,
+}
+
+/// A replay once started
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayInfo {
+    pub recording_id: String,
+    /// Room the recording was made in
+    pub source_room_id: String,
+    /// Room spectators join to watch
+    pub room_id: String,
+    pub speed: f64,
+    /// When the first message is played
+    pub starts_at: chrono::DateTime<chrono::Utc>,
+    /// Playing time at `speed`, in milliseconds
+    pub duration_ms: u64,
+    /// Messages that will be played
+    pub messages: usize,
+}
+
+struct Recording {
+    id: String,
+    room_id: String,
+    started_at: chrono::DateTime<chrono::Utc>,
+    started: Instant,
+    log: Mutex<PendingLog>,
+    /// Held while appending, so chunks reach the store in order
+    flushing: Mutex<()>,
+}
+
+/// Part of a log not yet appended to the store
+#[derive(Default)]
+struct PendingLog {
+    bytes: Vec<u8>,
+    last_offset_ms: u64,
+    messages: u64,
+}
+
+impl Recording {
+    async fn info(&self) -> RecordingInfo {
+        RecordingInfo {
+            recording_id: self.id.clone(),
+            room_id: self.room_id.clone(),
+            started_at: self.started_at,
+            messages: self.log.lock().await.messages,
+        }
+    }
+}
+
+/// Records rooms on request and replays their logs into spectator rooms
+#[derive(Clone)]
+pub struct SessionRecorder {
+    store: Arc<dyn RecordingStore>,
+    recordings: Arc<RwLock<HashMap<String, Arc<Recording>>>>, // room_id -> recording in progress
+    lead_in: Duration,
+}
+
+impl SessionRecorder {
+    pub fn new(store: Arc<dyn RecordingStore>) -> Self {
+        Self {
+            store,
+            recordings: Arc::new(RwLock::new(HashMap::new())),
+            lead_in: DEFAULT_REPLAY_LEAD_IN,
+        }
+    }
+
+    /// Wait `lead_in` between starting a replay and playing its first message
+    pub fn with_lead_in(mut self, lead_in: Duration) -> Self {
+        self.lead_in = lead_in;
+        self
+    }
+
+    /// Keep logs in the storage service at `STORAGE_SERVICE_URL`, or in
+    /// memory when it is not set
+    pub fn from_env() -> Self {
+        let store: Arc<dyn RecordingStore> = match std::env::var("STORAGE_SERVICE_URL") {
+            Ok(url) if !url.is_empty() => match StorageServiceStore::new(&url) {
+                Ok(store) => Arc::new(store),
+                Err(e) => {
+                    log::warn!("{}, session recordings are kept in memory", e);
+                    Arc::new(MemoryRecordingStore::new())
+                }
+            },
+            _ => {
+                log::warn!("STORAGE_SERVICE_URL not set, session recordings are kept in memory");
+                Arc::new(MemoryRecordingStore::new())
+            }
+        };
+        Self::new(store)
+    }
+
+    /// Record rooms on `ws_manager`, keep spectators of replays from taking
+    /// part, and append recordings to the store periodically
+    pub async fn start(&self, ws_manager: &WebSocketManager) {
+        ws_manager.register_global_handler(Arc::new(SpectatorGuard)).await;
+        ws_manager.attach_recorder(self.clone()).await;
+
+        let recorder = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(FLUSH_INTERVAL);
+            loop {
+                ticker.tick().await;
+                recorder.flush_all().await;
+            }
+        });
+        log::info!("Session recordings kept in {}", self.store.name());
+    }
+
+    /// Start recording a room
+    pub async fn start_recording(&self, room_id: &str) -> Result<RecordingInfo> {
+        if is_replay_room(room_id) {
+            return Err(Error::validation("Replays cannot be recorded"));
+        }
+
+        let started_at = chrono::Utc::now();
+        let recording = Arc::new(Recording {
+            id: Uuid::new_v4().to_string(),
+            room_id: room_id.to_string(),
+            started_at,
+            started: Instant::now(),
+            log: Mutex::new(PendingLog {
+                bytes: SessionLog::encode_header(room_id, started_at.timestamp_millis() as u64),
+                ..PendingLog::default()
+            }),
+            flushing: Mutex::new(()),
+        });
+
+        {
+            let mut recordings = self.recordings.write().await;
+            if recordings.contains_key(room_id) {
+                return Err(Error::validation(format!("Room {} is already being recorded", room_id)));
+            }
+            recordings.insert(room_id.to_string(), recording.clone());
+        }
+
+        // Write the header right away, so an unreachable store shows up now
+        if let Err(e) = self.flush(&recording).await {
+            self.recordings.write().await.remove(room_id);
+            return Err(e);
+        }
+
+        log::info!("Recording room {} as {}", room_id, recording.id);
+        Ok(recording.info().await)
+    }
+
+    /// Stop recording a room, appending the rest of its log to the store
+    pub async fn stop_recording(&self, room_id: &str) -> Result<RecordingInfo> {
+        let recording = self
+            .recordings
+            .write()
+            .await
+            .remove(room_id)
+            .ok_or_else(|| Error::not_found(format!("Room {} is not being recorded", room_id)))?;
+
+        self.flush(&recording).await?;
+        log::info!("Stopped recording room {} as {}", room_id, recording.id);
+        Ok(recording.info().await)
+    }
+
+    /// The room's recording in progress
+    pub async fn recording(&self, room_id: &str) -> Option<RecordingInfo> {
+        let recording = self.recordings.read().await.get(room_id).cloned()?;
+        Some(recording.info().await)
+    }
+
+    pub async fn is_recording(&self, room_id: &str) -> bool {
+        self.recordings.read().await.contains_key(room_id)
+    }
+
+    /// Add a message to the room's log if it is being recorded
+    pub async fn record(&self, room_id: &str, direction: Direction, client_id: &str, message: &[u8]) {
+        let Some(recording) = self.recordings.read().await.get(room_id).cloned() else {
+            return;
+        };
+
+        let full = {
+            let mut log = recording.log.lock().await;
+            let offset_ms = recording.started.elapsed().as_millis() as u64;
+            let delta_ms = offset_ms.saturating_sub(log.last_offset_ms);
+            log.last_offset_ms = log.last_offset_ms.max(offset_ms);
+            log.messages += 1;
+            SessionLog::encode_entry(&mut log.bytes, delta_ms, direction, client_id, message);
+            log.bytes.len() >= FLUSH_SIZE
+        };
+
+        if full {
+            let recorder = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = recorder.flush(&recording).await {
+                    log::warn!("{}", e);
+                }
+            });
+        }
+    }
+
+    /// Append a recording's pending log to the store. On failure the log
+    /// stays pending for the next attempt.
+    async fn flush(&self, recording: &Recording) -> Result<()> {
+        let _flushing = recording.flushing.lock().await;
+        let chunk = std::mem::take(&mut recording.log.lock().await.bytes);
+        if chunk.is_empty() {
+            return Ok(());
+        }
+
+        if let Err(e) = self.store.append(&recording.id, &chunk).await {
+            let mut log = recording.log.lock().await;
+            let newer = std::mem::replace(&mut log.bytes, chunk);
+            log.bytes.extend_from_slice(&newer);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Append every recording, giving up on those the store keeps refusing
+    async fn flush_all(&self) {
+        let recordings: Vec<Arc<Recording>> = self.recordings.read().await.values().cloned().collect();
+        for recording in recordings {
+            let Err(e) = self.flush(&recording).await else {
+                continue;
+            };
+            if recording.log.lock().await.bytes.len() < MAX_PENDING {
+                log::warn!("{}, retrying", e);
+                continue;
+            }
+
+            log::error!("{}, stopped recording room {}", e, recording.room_id);
+            let mut recordings = self.recordings.write().await;
+            if recordings.get(&recording.room_id).is_some_and(|r| Arc::ptr_eq(r, &recording)) {
+                recordings.remove(&recording.room_id);
+            }
+        }
+    }
+
+    /// Fetch and decode a recording
+    pub async fn load(&self, recording_id: &str) -> Result<SessionLog> {
+        let not_found = || Error::not_found(format!("Recording {} not found", recording_id));
+        if Uuid::parse_str(recording_id).is_err() {
+            return Err(not_found());
+        }
+        let bytes = self.store.fetch(recording_id).await?.ok_or_else(not_found)?;
+        SessionLog::decode(&bytes)
+    }
+
+    /// Play the broadcasts of a recording into a new spectator room at
+    /// `speed` times real time, starting shortly after this returns
+    pub async fn replay(
+        &self,
+        ws_manager: &WebSocketManager,
+        recording_id: &str,
+        log: SessionLog,
+        speed: f64,
+    ) -> Result<ReplayInfo> {
+        if !(1.0..=MAX_REPLAY_SPEED).contains(&speed) {
+            return Err(Error::validation(format!(
+                "Replay speed must be between 1 and {}",
+                MAX_REPLAY_SPEED
+            )));
+        }
+
+        let room_id = format!("{}{}", REPLAY_ROOM_PREFIX, Uuid::new_v4());
+        let entries: Vec<LogEntry> = log
+            .entries
+            .into_iter()
+            .filter(|entry| entry.direction == Direction::Outbound)
+            .collect();
+        let info = ReplayInfo {
+            recording_id: recording_id.to_string(),
+            source_room_id: log.room_id,
+            room_id: room_id.clone(),
+            speed,
+            starts_at: chrono::Utc::now() + chrono::Duration::from_std(self.lead_in).unwrap_or_default(),
+            duration_ms: entries.last().map_or(0, |entry| (entry.offset_ms as f64 / speed) as u64),
+            messages: entries.len(),
+        };
+
+        let ws_manager = ws_manager.clone();
+        let recording_id = recording_id.to_string();
+        let start = tokio::time::Instant::now() + self.lead_in;
+        tokio::spawn(async move {
+            for entry in entries {
+                let at = Duration::from_millis(entry.offset_ms).div_f64(speed);
+                tokio::time::sleep_until(start + at).await;
+                ws_manager.broadcast_to_room(&room_id, &entry.message, None).await;
+            }
+            log::info!("Finished replaying recording {} in room {}", recording_id, room_id);
+        });
+
+        log::info!(
+            "Replaying recording {} of room {} in room {} at {}x",
+            info.recording_id,
+            info.source_room_id,
+            info.room_id,
+            speed
+        );
+        Ok(info)
+    }
+}
+
+/// Drops what spectators of a replay send to the room
+struct SpectatorGuard;
+
+#[async_trait]
+impl MessageHandler for SpectatorGuard {
+    async fn handle(&self, message: &mut Message, ctx: &mut MessageContext<'_>) -> Result<Flow> {
+        if !is_replay_room(ctx.room_id) {
+            return Ok(Flow::Continue);
+        }
+        let relayed = ctx.route.is_some() || matches!(message.payload, Some(Payload::RtcSignal(_)));
+        if !relayed {
+            return Ok(Flow::Continue);
+        }
+
+        // Positions and voice arrive many times a second, so they are not answered
+        if !matches!(message.payload, Some(Payload::PositionUpdate(_) | Payload::VoiceData(_))) {
+            let error = MessageBuilder::error_frame(
+                "SPECTATOR_ONLY",
+                "Replays can only be watched".to_string(),
+                0,
+            );
+            ctx.send(Route::Sender, error);
+        }
+        Ok(Flow::Drop)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protobuf::route_message;
+    use crate::queue::OutboundReceiver;
+    use crate::websocket::WsMessage;
+    use graphwiz_protocol::{ChatMessageType, MessageParser};
+
+    fn chat(text: &str) -> Vec<u8> {
+        let message = MessageBuilder::chat_message("alice".to_string(), text.to_string(), ChatMessageType::Normal);
+        MessageParser::serialize(&message).unwrap()
+    }
+
+    async fn next_message(rx: &mut OutboundReceiver<WsMessage>, wait: Duration) -> Option<Message> {
+        match tokio::time::timeout(wait, rx.queue.recv()).await {
+            Ok(Some(WsMessage::Binary(bytes))) => MessageParser::parse(&bytes).ok(),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_log_round_trip() {
+        let mut bytes = SessionLog::encode_header("lobby", 1_700_000_000_000);
+        SessionLog::encode_entry(&mut bytes, 0, Direction::Inbound, "alice", b"hello");
+        SessionLog::encode_entry(&mut bytes, 250, Direction::Outbound, "", b"world");
+
+        let log = SessionLog::decode(&bytes).unwrap();
+        assert_eq!(log.room_id, "lobby");
+        assert_eq!(log.started_at_ms, 1_700_000_000_000);
+        assert_eq!(
+            log.entries,
+            vec![
+                LogEntry {
+                    offset_ms: 0,
+                    direction: Direction::Inbound,
+                    client_id: Some("alice".to_string()),
+                    message: b"hello".to_vec(),
+                },
+                LogEntry {
+                    offset_ms: 250,
+                    direction: Direction::Outbound,
+                    client_id: None,
+                    message: b"world".to_vec(),
+                },
+            ]
+        );
+        assert_eq!(log.duration_ms(), 250);
+    }
+
+    #[test]
+    fn test_partial_entry_is_ignored() {
+        let mut bytes = SessionLog::encode_header("lobby", 0);
+        SessionLog::encode_entry(&mut bytes, 10, Direction::Outbound, "", b"kept");
+        let complete = bytes.len();
+        SessionLog::encode_entry(&mut bytes, 10, Direction::Outbound, "", b"cut short");
+        bytes.truncate(complete + 4);
+
+        let log = SessionLog::decode(&bytes).unwrap();
+        assert_eq!(log.entries.len(), 1);
+        assert!(SessionLog::decode(b"not a log").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_recording_reaches_store() {
+        let store = Arc::new(MemoryRecordingStore::new());
+        let recorder = SessionRecorder::new(store.clone());
+
+        let info = recorder.start_recording("lobby").await.unwrap();
+        assert!(recorder.start_recording("lobby").await.is_err());
+        recorder.record("lobby", Direction::Inbound, "alice", b"hi").await;
+        recorder.record("lobby", Direction::Outbound, "", b"hi").await;
+        recorder.record("elsewhere", Direction::Outbound, "", b"not recorded").await;
+        let stopped = recorder.stop_recording("lobby").await.unwrap();
+        assert_eq!(stopped.messages, 2);
+        assert!(!recorder.is_recording("lobby").await);
+
+        let log = recorder.load(&info.recording_id).await.unwrap();
+        assert_eq!(log.room_id, "lobby");
+        assert_eq!(log.entries.len(), 2);
+        assert_eq!(log.entries[0].client_id.as_deref(), Some("alice"));
+        assert!(recorder.start_recording("replay-1234").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_replay_plays_broadcasts_to_spectators() {
+        let ws_manager = WebSocketManager::new();
+        let recorder =
+            SessionRecorder::new(Arc::new(MemoryRecordingStore::new())).with_lead_in(Duration::from_millis(200));
+
+        let mut bytes = SessionLog::encode_header("lobby", 0);
+        SessionLog::encode_entry(&mut bytes, 0, Direction::Inbound, "alice", &chat("first"));
+        SessionLog::encode_entry(&mut bytes, 0, Direction::Outbound, "", &chat("first"));
+        SessionLog::encode_entry(&mut bytes, 2000, Direction::Outbound, "", &chat("second"));
+        let log = SessionLog::decode(&bytes).unwrap();
+
+        assert!(recorder.replay(&ws_manager, "rec", log.clone(), 100.0).await.is_err());
+        let info = recorder.replay(&ws_manager, "rec", log, 8.0).await.unwrap();
+        assert_eq!(info.messages, 2);
+        assert_eq!(info.duration_ms, 250);
+
+        let mut rx = ws_manager
+            .add_connection("spectator".to_string(), Some(info.room_id.clone()), None, None)
+            .await;
+        assert!(next_message(&mut rx, Duration::from_millis(300)).await.is_some());
+        assert!(next_message(&mut rx, Duration::from_millis(150)).await.is_none());
+        assert!(next_message(&mut rx, Duration::from_millis(300)).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_spectators_cannot_send() {
+        let ws_manager = WebSocketManager::new();
+        ws_manager.register_global_handler(Arc::new(SpectatorGuard)).await;
+        let mut spectator = ws_manager
+            .add_connection("spectator".to_string(), Some("replay-1".to_string()), None, Some("bob".to_string()))
+            .await;
+        let mut other = ws_manager
+            .add_connection("other".to_string(), Some("replay-1".to_string()), None, None)
+            .await;
+
+        let handlers = ws_manager.handlers().await;
+        route_message(&handlers, &ws_manager, "replay-1", "spectator", &chat("hello"))
+            .await
+            .unwrap();
+
+        let reply = next_message(&mut spectator, Duration::from_millis(100)).await.unwrap();
+        assert!(matches!(reply.payload, Some(Payload::ErrorFrame(error)) if error.code == "SPECTATOR_ONLY"));
+        assert!(next_message(&mut other, Duration::from_millis(100)).await.is_none());
+    }
+}
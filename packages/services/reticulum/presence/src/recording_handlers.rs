@@ -0,0 +1,120 @@
+//! Session recording handlers for presence service
+//!
+//! Recording a room and replaying its recordings take the same bearer token
+//! as moderation actions. Admins, moderators and the room's creator may
+//! record it and replay what was recorded in it; room hosts may not.
+
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use reticulum_core::{Error, Result};
+use serde::Deserialize;
+
+use crate::moderation::{Authority, ModerationManager};
+use crate::recording::SessionRecorder;
+use crate::websocket::WebSocketManager;
+
+#[derive(Debug, Deserialize)]
+pub struct ReplayRequest {
+    /// Playback speed, 1 for real time; defaults to 1
+    pub speed: Option<f64>,
+}
+
+/// Check that the caller may record `room_id` or replay its recordings
+async fn authorize(
+    req: &HttpRequest,
+    moderation: &ModerationManager,
+    ws_manager: &WebSocketManager,
+    room_id: &str,
+) -> Result<String> {
+    let moderator = moderation.authenticate(req)?;
+    match moderation.authority(ws_manager, &moderator, room_id).await? {
+        Authority::Admin | Authority::Moderator | Authority::RoomOwner => Ok(moderator.user_id),
+        Authority::RoomHost | Authority::None => Err(Error::authorization("Not allowed to record this room")),
+    }
+}
+
+/// Start recording a room
+pub async fn start_recording(
+    req: HttpRequest,
+    moderation: web::Data<ModerationManager>,
+    ws_manager: web::Data<WebSocketManager>,
+    recorder: web::Data<SessionRecorder>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let room_id = path.into_inner();
+    let user_id = match authorize(&req, &moderation, &ws_manager, &room_id).await {
+        Ok(user_id) => user_id,
+        Err(e) => return e.error_response(),
+    };
+
+    match recorder.start_recording(&room_id).await {
+        Ok(recording) => {
+            log::info!("{} started recording room {}", user_id, room_id);
+            HttpResponse::Created().json(recording)
+        }
+        Err(e) => {
+            log::warn!("Recording room {} failed: {}", room_id, e);
+            e.error_response()
+        }
+    }
+}
+
+/// Stop recording a room
+pub async fn stop_recording(
+    req: HttpRequest,
+    moderation: web::Data<ModerationManager>,
+    ws_manager: web::Data<WebSocketManager>,
+    recorder: web::Data<SessionRecorder>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let room_id = path.into_inner();
+    if let Err(e) = authorize(&req, &moderation, &ws_manager, &room_id).await {
+        return e.error_response();
+    }
+
+    match recorder.stop_recording(&room_id).await {
+        Ok(recording) => HttpResponse::Ok().json(recording),
+        Err(e) => e.error_response(),
+    }
+}
+
+/// The room's recording in progress
+pub async fn get_recording(
+    recorder: web::Data<SessionRecorder>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let room_id = path.into_inner();
+    match recorder.recording(&room_id).await {
+        Some(recording) => HttpResponse::Ok().json(recording),
+        None => Error::not_found(format!("Room {} is not being recorded", room_id)).error_response(),
+    }
+}
+
+/// Replay a recording into a new spectator room
+pub async fn replay_recording(
+    req: HttpRequest,
+    moderation: web::Data<ModerationManager>,
+    ws_manager: web::Data<WebSocketManager>,
+    recorder: web::Data<SessionRecorder>,
+    path: web::Path<String>,
+    body: Option<web::Json<ReplayRequest>>,
+) -> HttpResponse {
+    let recording_id = path.into_inner();
+    let speed = body.and_then(|body| body.speed).unwrap_or(1.0);
+
+    // Authenticate before reaching out to storage
+    if let Err(e) = moderation.authenticate(&req) {
+        return e.error_response();
+    }
+    let log = match recorder.load(&recording_id).await {
+        Ok(log) => log,
+        Err(e) => return e.error_response(),
+    };
+    if let Err(e) = authorize(&req, &moderation, &ws_manager, &log.room_id).await {
+        return e.error_response();
+    }
+
+    match recorder.replay(&ws_manager, &recording_id, log, speed).await {
+        Ok(replay) => HttpResponse::Created().json(replay),
+        Err(e) => e.error_response(),
+    }
+}
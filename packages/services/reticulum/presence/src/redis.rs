@@ -1,12 +1,38 @@
 //! Redis pub/sub for scaling WebSocket server across multiple instances
-
-use reticulum_core::Result;
-use std::sync::Arc;
-use tokio::sync::mpsc;
+//!
+//! Room broadcasts are delivered to local connections right away and published
+//! to `{prefix}:room:{room_id}`. Every instance subscribes to `{prefix}:*`,
+//! skips its own messages and delivers the rest to its local connections in
+//! the room. Instances also announce themselves on `{prefix}:instances` so
+//! each one knows which peers are alive, where clients can reach them and
+//! whether they are draining.
+
+use crate::drain::MIGRATION_CHANNEL;
+use crate::moderation::MODERATION_CHANNEL;
+use crate::signaling::SIGNALING_CHANNEL;
+use crate::user_presence::USER_PRESENCE_CHANNEL;
+use crate::websocket::WebSocketManager;
+use async_trait::async_trait;
+use futures::StreamExt;
+use reticulum_core::entity_sync::{ENTITY_EVENTS_CHANNEL, ENTITY_HISTORY_CHANNEL, ENTITY_REQUESTS_CHANNEL};
+use reticulum_core::occupancy::{HOST_CHANNEL, OCCUPANCY_CHANNEL, SEAT_CLAIM_CHANNEL};
+use reticulum_core::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, RwLock};
 use tokio::task::JoinHandle;
-use redis::AsyncCommands;
-use redis::aio::Connection;
-use serde_json;
+
+/// Interval between instance heartbeats
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Peers missing this many heartbeats are considered gone
+const MISSED_HEARTBEATS: u32 = 3;
+
+/// Messages buffered between a subscription and its consumer
+const SUBSCRIPTION_BUFFER: usize = 1024;
 
 /// Redis configuration
 #[derive(Clone, Debug)]
@@ -25,9 +51,13 @@ impl Default for RedisConfig {
 }
 
 /// Message to broadcast via Redis pub/sub
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct PubSubMessage {
+    /// Instance that published the message; set on publish
+    #[serde(default)]
+    pub origin: String,
     pub room_id: String,
+    #[serde(rename = "type")]
     pub message_type: PubSubMessageType,
     pub data: Vec<u8>,
     pub exclude_connection: Option<String>,
@@ -35,359 +65,193 @@ pub struct PubSubMessage {
 }
 
 impl PubSubMessage {
+    /// Room broadcast of a protobuf message
+    pub fn broadcast(room_id: &str, data: Vec<u8>, exclude_connection: Option<String>) -> Self {
+        Self {
+            origin: String::new(),
+            room_id: room_id.to_string(),
+            message_type: PubSubMessageType::Broadcast,
+            data,
+            exclude_connection,
+            timestamp: chrono::Utc::now().timestamp_micros(),
+        }
+    }
+
     /// Convert to JSON for Redis pub/sub
-    pub fn to_json(&self) -> Result<String, serde_json::Error> {
-        let json = json!({
-            "room_id": self.room_id,
-            "type": format!("{:?}", self.message_type),
-            "data": self.data,
-            "exclude_connection": self.exclude_connection,
-            "timestamp": self.timestamp,
-        });
-        serde_json::to_string(&json)
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    pub fn from_json(payload: &[u8]) -> serde_json::Result<Self> {
+        serde_json::from_slice(payload)
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum PubSubMessageType {
     Broadcast,
     PresenceEvent,
     SystemNotification,
 }
 
-/// Redis pub/sub manager for scaling
-pub struct RedisPubSub {
-    config: RedisConfig,
-    client: Arc<redis::Client>,
-    _subscriber_task: Option<JoinHandle<()>>,
+/// Liveness announcement published by every instance
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct InstanceHeartbeat {
+    instance_id: String,
+    connections: usize,
+    timestamp: i64,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    draining: bool,
 }
 
-impl RedisPubSub {
-    /// Create a new Redis pub/sub manager
-    ///
-    /// # Arguments
-    /// * `config` - Redis connection configuration
-    /// * `message_receiver` - Channel to receive published messages
-    pub async fn new(
-        config: RedisConfig,
-        mut message_receiver: mpsc::Receiver<PubSubMessage>,
-    ) -> Result<Self> {
-        log::info!("Initializing Redis pub/sub: {}", config.url);
+/// Channel transport shared by presence instances
+#[async_trait]
+pub trait PubSubTransport: Send + Sync {
+    /// Publish `payload` on `channel`
+    async fn publish(&self, channel: &str, payload: Vec<u8>) -> Result<()>;
 
-        // Connect to Redis with connection pooling
-        let client = redis::Client::open(config.url.clone())
-            .map_err(|e| reticulum_core::error::Error::RedisError(format!("Failed to connect to Redis: {}", e)))?;
+    /// Receive `(channel, payload)` for every channel matching `pattern`, a
+    /// Redis glob ending in `*`
+    async fn psubscribe(&self, pattern: &str) -> Result<mpsc::Receiver<(String, Vec<u8>)>>;
 
-        let client = Arc::new(client);
+    /// Short name for status output
+    fn name(&self) -> &'static str;
+}
 
-        // Test connection
-        let mut conn = client
-            .get_async_connection()
+/// Pub/sub over a Redis server
+pub struct RedisTransport {
+    client: redis::Client,
+    publisher: redis::aio::ConnectionManager,
+}
+
+impl RedisTransport {
+    /// Connect to Redis and check it answers
+    pub async fn connect(url: &str) -> Result<Self> {
+        let client = redis::Client::open(url)
+            .map_err(|e| Error::internal(format!("Failed to connect to Redis: {}", e)))?;
+
+        let mut publisher = client
+            .get_connection_manager()
             .await
-            .map_err(|e| reticulum_core::error::Error::RedisError(format!("Failed to get async connection: {}", e)))?;
+            .map_err(|e| Error::internal(format!("Failed to get async connection: {}", e)))?;
 
         redis::cmd("PING")
-            .query_async::<_, String>(&mut conn)
+            .query_async::<_, String>(&mut publisher)
             .await
-            .map_err(|e| reticulum_core::error::Error::RedisError(format!("Redis ping failed: {}", e)))?;
-
-        log::info!("Successfully connected to Redis at {}", config.url);
+            .map_err(|e| Error::internal(format!("Redis ping failed: {}", e)))?;
 
-        // Spawn a task to handle publishing messages
-        let client_clone = client.clone();
-        let config_clone = config.clone();
-        let _subscriber_task = Some(tokio::spawn(async move {
-            while let Some(msg) = message_receiver.recv().await {
-                if let Err(e) = Self::publish_message(&client_clone, &config_clone, &msg).await {
-                    log::error!("Failed to publish message: {}", e);
-                }
-            }
-        }));
-
-        Ok(Self {
-            config,
-            client,
-            _subscriber_task,
-        })
+        Ok(Self { client, publisher })
     }
+}
 
-    /// Publish a message to Redis
-    async fn publish_message(
-        client: &redis::Client,
-        config: &RedisConfig,
-        msg: &PubSubMessage,
-    ) -> Result<()> {
-        // Serialize message to JSON
-        let json_str = msg.to_json()
-            .map_err(|e| reticulum_core::error::Error::SerializationError(format!("Failed to serialize message: {}", e)))?;
-
-        // Build channel name: {prefix}:room:{room_id}
-        let channel = format!(
-            "{}:room:{}",
-            config.channel_prefix,
-            msg.room_id.replace('/', ":")
-        );
-
-        // Get async connection
-        let mut conn = client
-            .get_async_connection()
-            .await
-            .map_err(|e| reticulum_core::Error::RedisError(format!("Failed to get async connection: {}", e)))?;
-
-        // Publish to Redis channel
-        let _: () = redis::cmd("PUBLISH")
-            .arg(&channel)
-            .arg(&json_str)
-            .query_async(&mut conn)
+#[async_trait]
+impl PubSubTransport for RedisTransport {
+    async fn publish(&self, channel: &str, payload: Vec<u8>) -> Result<()> {
+        let mut conn = self.publisher.clone();
+        redis::cmd("PUBLISH")
+            .arg(channel)
+            .arg(payload)
+            .query_async::<_, ()>(&mut conn)
             .await
-            .map_err(|e| reticulum_core::Error::RedisError(format!("Failed to publish to Redis: {}", e)))?;
-
-        log::debug!(
-            "Published to Redis channel {}: {} bytes",
-            channel,
-            msg.data.len()
-        );
-
-        Ok(())
+            .map_err(|e| Error::internal(format!("Failed to publish to Redis: {}", e)))
     }
 
-    /// Subscribe to a Redis channel for room messages
-    pub async fn subscribe_room(
-        &self,
-        room_id: &str,
-        callback: impl Fn(serde_json::Value) + Send + Sync + 'static,
-    ) -> Result<JoinHandle<()>> {
-        let channel = format!(
-            "{}:room:{}",
-            self.config.channel_prefix,
-            room_id.replace('/', ":")
-        );
-
-        let client_clone = self.client.clone();
-        let channel_clone = channel.clone();
+    async fn psubscribe(&self, pattern: &str) -> Result<mpsc::Receiver<(String, Vec<u8>)>> {
+        let (tx, rx) = mpsc::channel(SUBSCRIPTION_BUFFER);
+        let client = self.client.clone();
+        let pattern = pattern.to_string();
+
+        // Pub/sub connections are not managed, so resubscribe whenever the
+        // connection drops until the receiver goes away
+        tokio::spawn(async move {
+            while !tx.is_closed() {
+                let mut pubsub = match client.get_async_connection().await {
+                    Ok(conn) => conn.into_pubsub(),
+                    Err(e) => {
+                        log::error!("Failed to get Redis connection for subscription: {}", e);
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                        continue;
+                    }
+                };
 
-        Ok(tokio::spawn(async move {
-            let pubsub = client_clone.as_pubsub();
-            let mut conn = match client_clone.get_async_connection().await {
-                Ok(c) => c,
-                Err(e) => {
-                    log::error!("Failed to get Redis connection for subscription: {}", e);
-                    return;
+                if let Err(e) = pubsub.psubscribe(&pattern).await {
+                    log::error!("Failed to subscribe to Redis pattern {}: {}", pattern, e);
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    continue;
                 }
-            };
-
-            if let Err(e) = pubsub.subscribe(&channel_clone).async_query(&mut conn).await {
-                log::error!("Failed to subscribe to Redis channel {}: {}", channel_clone, e);
-                return;
-            }
-
-            log::info!("Subscribed to Redis channel: {}", channel_clone);
+                log::info!("Subscribed to Redis pattern: {}", pattern);
 
-            // Process messages
-            let msg_stream = pubsub.on_message();
-            while let Some(msg) = msg_stream.next().await {
-                match msg {
-                    redis::AsyncMessage::Data(data) => {
-                        if let Ok(value) = serde_json::from_slice(&data.get_payload_bytes()) {
-                            callback(value);
-                        }
-                    }
-                    redis::AsyncMessage::Subscribe(channel, count) => {
-                        log::debug!("Subscribed to {} (count: {})", channel, count);
-                    }
-                    redis::AsyncMessage::Unsubscribe(channel) => {
-                        log::debug!("Unsubscribed from {}", channel);
-                        break;
+                let mut messages = pubsub.on_message();
+                while let Some(msg) = messages.next().await {
+                    let message = (msg.get_channel_name().to_string(), msg.get_payload_bytes().to_vec());
+                    if tx.send(message).await.is_err() {
+                        return;
                     }
-                    _ => {}
                 }
-            }
-        }))
-    }
 
-    /// Publish presence update for a user in a room
-    pub async fn publish_presence_update(
-        &self,
-        room_id: &str,
-        user_id: &str,
-        event: &str,
-    ) -> Result<()> {
-        let message = PubSubMessage {
-            room_id: room_id.to_string(),
-            message_type: PubSubMessageType::PresenceEvent,
-            data: serde_json::to_vec(&json!({
-                "user_id": user_id,
-                "event": event,
-            }))
-            .unwrap(),
-            exclude_connection: None,
-            timestamp: chrono::Utc::now().timestamp(),
-        };
+                log::warn!("Redis subscription to {} lost, reconnecting", pattern);
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        });
 
-        Self::publish_message(&self.client, &self.config, &message).await
+        Ok(rx)
     }
-}
 
-impl Default for RedisConfig {
-    fn default() -> Self {
-        Self {
-            url: "redis://127.0.0.1:6379".to_string(),
-            channel_prefix: "graphwiz".to_string(),
-        }
+    fn name(&self) -> &'static str {
+        "redis"
     }
 }
 
-/// Message to broadcast via Redis pub/sub
-#[derive(Clone, Debug)]
-pub struct PubSubMessage {
-    pub room_id: String,
-    pub message_type: PubSubMessageType,
-    pub data: Vec<u8>,
-    pub exclude_connection: Option<String>,
-    pub timestamp: i64,
-}
-
-#[derive(Clone, Debug)]
-pub enum PubSubMessageType {
-    Broadcast,
-    PresenceEvent,
-    SystemNotification,
-}
+/// A pattern subscription and the channel feeding it
+type Subscriber = (String, mpsc::Sender<(String, Vec<u8>)>);
 
-/// Redis pub/sub manager for scaling
-pub struct RedisPubSub {
-    config: RedisConfig,
-    _subscriber_task: Option<JoinHandle<()>>,
+/// In-process pub/sub. Instances sharing one `MemoryTransport` see each
+/// other's messages; a single instance uses it when Redis is unavailable.
+#[derive(Clone, Default)]
+pub struct MemoryTransport {
+    subscribers: Arc<Mutex<Vec<Subscriber>>>,
 }
 
-impl RedisPubSub {
-    /// Create a new Redis pub/sub manager
-    ///
-    /// # Arguments
-    /// * `config` - Redis connection configuration
-    /// * `message_receiver` - Channel to receive published messages
-    pub async fn new(
-        config: RedisConfig,
-        mut message_receiver: mpsc::Receiver<PubSubMessage>,
-    ) -> Result<Self> {
-        log::info!("Initializing Redis pub/sub: {}", config.url);
-
-        // Note: This is a placeholder implementation
-        // In production, you would use a Redis client library like:
-        // - `redis-rs` with async/tokio support
-        // - Connection pooling
-        // - Automatic reconnection
-        // - Proper error handling
-
-        // Spawn a task to handle publishing messages
-        let _subscriber_task = Some(tokio::spawn(async move {
-            while let Some(msg) = message_receiver.recv().await {
-                // Publish to Redis
-                if let Err(e) = Self::publish_message(&config, &msg).await {
-                    log::error!("Failed to publish message: {}", e);
-                }
-            }
-        }));
-
-        Ok(Self {
-            config,
-            _subscriber_task,
-        })
+impl MemoryTransport {
+    pub fn new() -> Self {
+        Self::default()
     }
+}
 
-    /// Publish a message to Redis
-    async fn publish_message(config: &RedisConfig, msg: &PubSubMessage) -> Result<()> {
-        // Placeholder implementation
-        // In production, you would:
-        // 1. Connect to Redis
-        // 2. Serialize the message (JSON/bincode/protobuf)
-        // 3. Publish to the appropriate channel: {prefix}:room:{room_id}
-        // 4. Handle connection errors and reconnection
-
-        let channel = format!(
-            "{}:room:{}",
-            config.channel_prefix,
-            msg.room_id.replace('/', ":")
-        );
-
-        log::debug!(
-            "Publishing to Redis channel {}: {} bytes",
-            channel,
-            msg.data.len()
-        );
-
-        // Simulate Redis publish
-        // let client = redis::Client::open(config.url.as_str())?;
-        // let mut con = client.get_async_connection().await?;
-        // con.publish(channel, serialized_data).await?;
-
-        Ok(())
+fn pattern_matches(pattern: &str, channel: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => channel.starts_with(prefix),
+        None => pattern == channel,
     }
+}
 
-    /// Subscribe to a room's channel
-    pub async fn subscribe_room(
-        &self,
-        room_id: &str,
-        mut message_sender: mpsc::Sender<PubSubMessage>,
-    ) -> Result<JoinHandle<()>> {
-        let config = self.config.clone();
-        let room_id = room_id.to_string();
-
-        let handle = tokio::spawn(async move {
-            // Placeholder implementation
-            // In production, you would:
-            // 1. Connect to Redis
-            // 2. Subscribe to the channel: {prefix}:room:{room_id}
-            // 3. Listen for messages
-            // 4. Deserialize and send to message_sender
-            // 5. Handle reconnection on failure
-
-            let channel = format!(
-                "{}:room:{}",
-                config.channel_prefix,
-                room_id.replace('/', ":")
-            );
-
-            log::info!("Subscribed to Redis channel: {}", channel);
-
-            // Simulate subscription - in production this would be a long-running task
-            // let client = redis::Client::open(config.url.as_str())?;
-            // let mut con = client.get_async_connection().await?;
-            // let mut pubsub = con.into_pubsub();
-            // pubsub.subscribe(&channel).await?;
-
-            // loop {
-            //     let msg = pubsub.on_message().await?;
-            //     let payload: PubSubMessage = deserialize(msg.get_payload_bytes()?)?;
-            //     message_sender.send(payload).await?;
-            // }
-
-            // Keep the task alive
-            loop {
-                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+#[async_trait]
+impl PubSubTransport for MemoryTransport {
+    async fn publish(&self, channel: &str, payload: Vec<u8>) -> Result<()> {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        subscribers.retain(|(_, tx)| !tx.is_closed());
+        for (pattern, tx) in subscribers.iter() {
+            // Like Redis, a subscriber that falls behind loses messages
+            if pattern_matches(pattern, channel) {
+                let _ = tx.try_send((channel.to_string(), payload.clone()));
             }
-        });
-
-        Ok(handle)
-    }
-
-    /// Unsubscribe from a room's channel
-    pub async fn unsubscribe_room(&self, _room_id: &str) -> Result<()> {
-        // Placeholder implementation
-        // In production, you would cancel the subscription task
+        }
         Ok(())
     }
 
-    /// Publish a message to a room
-    pub async fn publish(&self, msg: PubSubMessage) -> Result<()> {
-        Self::publish_message(&self.config, &msg).await
+    async fn psubscribe(&self, pattern: &str) -> Result<mpsc::Receiver<(String, Vec<u8>)>> {
+        let (tx, rx) = mpsc::channel(SUBSCRIPTION_BUFFER);
+        self.subscribers
+            .lock()
+            .unwrap()
+            .push((pattern.to_string(), tx));
+        Ok(rx)
     }
 
-    /// Check if Redis is available
-    pub async fn health_check(&self) -> Result<bool> {
-        // Placeholder implementation
-        // In production, you would ping Redis
-        Ok(true)
+    fn name(&self) -> &'static str {
+        "memory"
     }
 }
 
@@ -416,7 +280,7 @@ impl RedisConnectionState {
         // TTL: 300 seconds (auto-refreshed)
 
         let key = format!("{}:connection:{}", self.config.channel_prefix, conn_id);
-        log::debug!("Registered connection in Redis: {}", key);
+        log::debug!("Registered connection in Redis: {} (room {}, user {})", key, room_id, user_id);
 
         Ok(())
     }
@@ -441,7 +305,7 @@ impl RedisConnectionState {
         // Return connection IDs
 
         let pattern = format!("{}:connection:*", self.config.channel_prefix);
-        log::debug!("Scanning Redis for pattern: {}", pattern);
+        log::debug!("Scanning Redis for pattern: {} (room {})", pattern, room_id);
 
         Ok(Vec::new())
     }
@@ -458,11 +322,44 @@ impl RedisConnectionState {
     }
 }
 
+/// Health of a presence instance as seen from this one
+#[derive(Clone, Debug, Serialize)]
+pub struct InstanceHealth {
+    pub instance_id: String,
+    pub connections: usize,
+    /// Unix time of the last heartbeat in milliseconds
+    pub last_seen: i64,
+    /// Address clients can reconnect to, if the instance advertises one
+    pub url: Option<String>,
+    /// Whether the instance is moving its clients elsewhere
+    pub draining: bool,
+    #[serde(skip)]
+    last_seen_at: Option<Instant>,
+}
+
+/// Cluster membership and transport health for the status endpoint
+#[derive(Clone, Debug, Serialize)]
+pub struct ClusterStatus {
+    pub instance_id: String,
+    pub transport: &'static str,
+    pub healthy: bool,
+    pub url: Option<String>,
+    pub draining: bool,
+    pub instances: Vec<InstanceHealth>,
+}
+
 /// Cluster-aware message broadcaster using Redis
+#[derive(Clone)]
 pub struct ClusterBroadcaster {
-    pubsub: RedisPubSub,
-    connection_state: RedisConnectionState,
+    config: RedisConfig,
+    transport: Arc<dyn PubSubTransport>,
+    connection_state: Arc<RedisConnectionState>,
     instance_id: String,
+    peers: Arc<RwLock<HashMap<String, InstanceHealth>>>, // instance_id -> last heartbeat
+    healthy: Arc<AtomicBool>,
+    public_url: Option<String>,
+    draining: Arc<AtomicBool>,
+    tasks: Arc<Mutex<Vec<JoinHandle<()>>>>,
 }
 
 impl ClusterBroadcaster {
@@ -470,20 +367,65 @@ impl ClusterBroadcaster {
     ///
     /// # Arguments
     /// * `config` - Redis configuration
-    /// * `message_receiver` - Channel for outgoing messages
-    pub async fn new(
-        config: RedisConfig,
-        message_receiver: mpsc::Receiver<PubSubMessage>,
-    ) -> Result<Self> {
-        let pubsub = RedisPubSub::new(config.clone(), message_receiver).await?;
-        let connection_state = RedisConnectionState::new(config);
-        let instance_id = uuid::Uuid::new_v4().to_string();
-
-        Ok(Self {
-            pubsub,
-            connection_state,
-            instance_id,
-        })
+    /// * `transport` - Pub/sub transport shared with the other instances
+    pub fn new(config: RedisConfig, transport: Arc<dyn PubSubTransport>) -> Self {
+        Self {
+            connection_state: Arc::new(RedisConnectionState::new(config.clone())),
+            config,
+            transport,
+            instance_id: uuid::Uuid::new_v4().to_string(),
+            peers: Arc::new(RwLock::new(HashMap::new())),
+            healthy: Arc::new(AtomicBool::new(true)),
+            public_url: None,
+            draining: Arc::new(AtomicBool::new(false)),
+            tasks: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Advertise `url` to the other instances as the address clients can
+    /// reconnect to when they drain
+    pub fn with_public_url(mut self, url: impl Into<String>) -> Self {
+        self.public_url = Some(url.into());
+        self
+    }
+
+    /// Connect to Redis, falling back to an in-process transport so a single
+    /// instance keeps working without Redis
+    pub async fn connect(config: RedisConfig) -> Self {
+        log::info!("Initializing Redis pub/sub: {}", config.url);
+
+        let transport: Arc<dyn PubSubTransport> = match RedisTransport::connect(&config.url).await {
+            Ok(transport) => {
+                log::info!("Successfully connected to Redis at {}", config.url);
+                Arc::new(transport)
+            }
+            Err(e) => {
+                log::warn!("Failed to initialize Redis pub/sub: {}. Broadcasts stay on this instance.", e);
+                Arc::new(MemoryTransport::new())
+            }
+        };
+
+        Self::new(config, transport)
+    }
+
+    fn room_channel(&self, room_id: &str) -> String {
+        format!("{}:room:{}", self.config.channel_prefix, room_id.replace('/', ":"))
+    }
+
+    fn instances_channel(&self) -> String {
+        format!("{}:instances", self.config.channel_prefix)
+    }
+
+    /// Publish a message for the other instances
+    pub async fn publish(&self, mut msg: PubSubMessage) -> Result<()> {
+        msg.origin = self.instance_id.clone();
+        let payload = msg.to_json()?.into_bytes();
+        let result = self
+            .transport
+            .publish(&self.room_channel(&msg.room_id), payload)
+            .await;
+        self.record_publish(&result);
+        result
     }
 
     /// Broadcast a message to all instances
@@ -493,15 +435,227 @@ impl ClusterBroadcaster {
         data: Vec<u8>,
         exclude_connection: Option<String>,
     ) -> Result<()> {
-        let msg = PubSubMessage {
-            room_id: room_id.to_string(),
-            message_type: PubSubMessageType::Broadcast,
-            data,
-            exclude_connection,
-            timestamp: chrono::Utc::now().timestamp_micros(),
+        self.publish(PubSubMessage::broadcast(room_id, data, exclude_connection))
+            .await
+    }
+
+    fn record_publish(&self, result: &Result<()>) {
+        let was_healthy = self.healthy.swap(result.is_ok(), Ordering::Relaxed);
+        match result {
+            Err(e) if was_healthy => log::error!("Cluster pub/sub unavailable: {}", e),
+            Ok(()) if !was_healthy => log::info!("Cluster pub/sub recovered"),
+            _ => {}
+        }
+    }
+
+    /// Join the cluster with `ws_manager`: its room broadcasts are published,
+    /// other instances' broadcasts reach its connections and heartbeats are
+    /// exchanged
+    pub async fn start(&self, ws_manager: &WebSocketManager) -> Result<()> {
+        let mut incoming = self
+            .transport
+            .psubscribe(&format!("{}:*", self.config.channel_prefix))
+            .await?;
+
+        let (publish_tx, mut publish_rx) = mpsc::channel::<PubSubMessage>(SUBSCRIPTION_BUFFER);
+        ws_manager.attach_cluster(publish_tx).await;
+
+        let publisher = {
+            let cluster = self.clone();
+            tokio::spawn(async move {
+                while let Some(msg) = publish_rx.recv().await {
+                    if let Err(e) = cluster.publish(msg).await {
+                        log::debug!("Failed to publish message: {}", e);
+                    }
+                }
+            })
+        };
+
+        let subscriber = {
+            let cluster = self.clone();
+            let ws_manager = ws_manager.clone();
+            tokio::spawn(async move {
+                while let Some((channel, payload)) = incoming.recv().await {
+                    cluster.handle_message(&ws_manager, &channel, &payload).await;
+                }
+            })
+        };
+
+        let heartbeat = {
+            let cluster = self.clone();
+            let ws_manager = ws_manager.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    cluster.send_heartbeat(ws_manager.connection_count().await).await;
+                    cluster.prune_instances(Instant::now()).await;
+                }
+            })
+        };
+
+        self.tasks
+            .lock()
+            .unwrap()
+            .extend([publisher, subscriber, heartbeat]);
+
+        log::info!(
+            "Presence instance {} joined the cluster over {}",
+            self.instance_id,
+            self.transport.name()
+        );
+        Ok(())
+    }
+
+    /// Stop publishing, receiving and heartbeating
+    pub fn stop(&self) {
+        for task in self.tasks.lock().unwrap().drain(..) {
+            task.abort();
+        }
+    }
+
+    async fn handle_message(&self, ws_manager: &WebSocketManager, channel: &str, payload: &[u8]) {
+        // Entity sync, user presence, signaling, moderation and the hub's seat
+        // claims, releases and host handoffs have their own subscribers
+        if channel == ENTITY_EVENTS_CHANNEL
+            || channel == ENTITY_REQUESTS_CHANNEL
+            || channel == ENTITY_HISTORY_CHANNEL
+            || channel == USER_PRESENCE_CHANNEL
+            || channel == SIGNALING_CHANNEL
+            || channel == MODERATION_CHANNEL
+            || channel == SEAT_CLAIM_CHANNEL
+            || channel == OCCUPANCY_CHANNEL
+            || channel == HOST_CHANNEL
+            || channel == MIGRATION_CHANNEL
+        {
+            return;
+        }
+
+        if channel == self.instances_channel() {
+            match serde_json::from_slice::<InstanceHeartbeat>(payload) {
+                Ok(heartbeat) => self.record_heartbeat(heartbeat, Instant::now()).await,
+                Err(e) => log::warn!("Invalid heartbeat on {}: {}", channel, e),
+            }
+            return;
+        }
+
+        let msg = match PubSubMessage::from_json(payload) {
+            Ok(msg) => msg,
+            Err(e) => {
+                log::warn!("Invalid pub/sub message on {}: {}", channel, e);
+                return;
+            }
+        };
+
+        // Local connections already got this instance's own broadcasts
+        if msg.origin == self.instance_id {
+            return;
+        }
+
+        match msg.message_type {
+            PubSubMessageType::Broadcast => {
+                ws_manager
+                    .broadcast_local(&msg.room_id, &msg.data, msg.exclude_connection.as_deref())
+                    .await;
+            }
+            PubSubMessageType::PresenceEvent | PubSubMessageType::SystemNotification => {
+                log::debug!("Ignoring {:?} from {} on {}", msg.message_type, msg.origin, channel);
+            }
+        }
+    }
+
+    async fn send_heartbeat(&self, connections: usize) {
+        let heartbeat = InstanceHeartbeat {
+            instance_id: self.instance_id.clone(),
+            connections,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            url: self.public_url.clone(),
+            draining: self.is_draining(),
+        };
+        let result = match serde_json::to_vec(&heartbeat) {
+            Ok(payload) => self.transport.publish(&self.instances_channel(), payload).await,
+            Err(e) => Err(e.into()),
         };
+        self.record_publish(&result);
+    }
+
+    async fn record_heartbeat(&self, heartbeat: InstanceHeartbeat, now: Instant) {
+        if heartbeat.instance_id == self.instance_id {
+            return;
+        }
+
+        let mut peers = self.peers.write().await;
+        if !peers.contains_key(&heartbeat.instance_id) {
+            log::info!("Presence instance {} joined the cluster", heartbeat.instance_id);
+        }
+        peers.insert(
+            heartbeat.instance_id.clone(),
+            InstanceHealth {
+                instance_id: heartbeat.instance_id,
+                connections: heartbeat.connections,
+                last_seen: heartbeat.timestamp,
+                url: heartbeat.url,
+                draining: heartbeat.draining,
+                last_seen_at: Some(now),
+            },
+        );
+    }
+
+    /// Forget peers that missed too many heartbeats
+    async fn prune_instances(&self, now: Instant) {
+        let timeout = HEARTBEAT_INTERVAL * MISSED_HEARTBEATS;
+        let mut peers = self.peers.write().await;
+        peers.retain(|instance_id, peer| {
+            let alive = peer
+                .last_seen_at
+                .is_some_and(|seen| now.saturating_duration_since(seen) < timeout);
+            if !alive {
+                log::warn!("Presence instance {} stopped sending heartbeats", instance_id);
+            }
+            alive
+        });
+    }
+
+    /// Peers that sent a heartbeat recently
+    pub async fn instances(&self) -> Vec<InstanceHealth> {
+        self.peers.read().await.values().cloned().collect()
+    }
+
+    /// Mark this instance as draining, or serving again, and tell the other
+    /// instances right away so they stop redirecting clients to it
+    pub async fn set_draining(&self, draining: bool, connections: usize) {
+        self.draining.store(draining, Ordering::Relaxed);
+        self.send_heartbeat(connections).await;
+    }
+
+    /// Whether this instance is draining
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::Relaxed)
+    }
+
+    /// Address of the least loaded peer that is not draining, for clients of
+    /// this instance to reconnect to
+    pub async fn redirect_target(&self) -> Option<String> {
+        self.peers
+            .read()
+            .await
+            .values()
+            .filter(|peer| !peer.draining)
+            .filter_map(|peer| peer.url.as_ref().map(|url| (peer.connections, url)))
+            .min()
+            .map(|(_, url)| url.clone())
+    }
 
-        self.pubsub.publish(msg).await
+    /// Cluster membership and transport health
+    pub async fn status(&self) -> ClusterStatus {
+        ClusterStatus {
+            instance_id: self.instance_id.clone(),
+            transport: self.transport.name(),
+            healthy: self.health_check().await,
+            url: self.public_url.clone(),
+            draining: self.is_draining(),
+            instances: self.instances().await,
+        }
     }
 
     /// Register this instance's connection
@@ -523,25 +677,32 @@ impl ClusterBroadcaster {
             .await
     }
 
+    /// Transport shared with the other instances
+    pub fn transport(&self) -> Arc<dyn PubSubTransport> {
+        self.transport.clone()
+    }
+
     /// Get instance ID
     pub fn instance_id(&self) -> &str {
         &self.instance_id
     }
 
-    /// Health check
-    pub async fn health_check(&self) -> Result<bool> {
-        self.pubsub.health_check().await
+    /// Whether the last publish reached the transport
+    pub async fn health_check(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tokio::time::{sleep, Duration};
+    use crate::websocket::WsMessage;
+    use tokio::time::timeout;
 
     #[tokio::test]
     async fn test_pubsub_message_creation() {
         let msg = PubSubMessage {
+            origin: "instance-1".to_string(),
             room_id: "test-room".to_string(),
             message_type: PubSubMessageType::Broadcast,
             data: vec![1, 2, 3, 4],
@@ -552,6 +713,9 @@ mod tests {
         assert_eq!(msg.room_id, "test-room");
         assert_eq!(msg.data.len(), 4);
         assert!(msg.exclude_connection.is_some());
+
+        let json = msg.to_json().unwrap();
+        assert_eq!(PubSubMessage::from_json(json.as_bytes()).unwrap(), msg);
     }
 
     #[tokio::test]
@@ -576,4 +740,119 @@ mod tests {
         let connections = state.get_room_connections("room-1").await.unwrap();
         assert!(connections.is_empty()); // Placeholder returns empty
     }
+
+    async fn recv_binary(rx: &mut crate::queue::OutboundReceiver<WsMessage>) -> Option<Vec<u8>> {
+        match timeout(Duration::from_millis(200), rx.queue.recv()).await {
+            Ok(Some(WsMessage::Binary(bytes))) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_reaches_other_instances() {
+        let transport = Arc::new(MemoryTransport::new());
+        let instance_a = WebSocketManager::new();
+        let instance_b = WebSocketManager::new();
+        ClusterBroadcaster::new(RedisConfig::default(), transport.clone())
+            .start(&instance_a)
+            .await
+            .unwrap();
+        ClusterBroadcaster::new(RedisConfig::default(), transport)
+            .start(&instance_b)
+            .await
+            .unwrap();
+
+        let mut local = instance_a
+            .add_connection("conn-a".to_string(), Some("room-1".to_string()), None, None)
+            .await;
+        let mut remote = instance_b
+            .add_connection("conn-b".to_string(), Some("room-1".to_string()), None, None)
+            .await;
+        let mut excluded = instance_b
+            .add_connection("conn-c".to_string(), Some("room-1".to_string()), None, None)
+            .await;
+
+        instance_a
+            .broadcast_to_room("room-1", b"hello", Some("conn-c"))
+            .await;
+
+        assert_eq!(recv_binary(&mut local).await.as_deref(), Some(&b"hello"[..]));
+        assert_eq!(recv_binary(&mut remote).await.as_deref(), Some(&b"hello"[..]));
+        assert_eq!(recv_binary(&mut excluded).await, None);
+
+        // The origin instance does not deliver its own broadcast twice
+        assert_eq!(recv_binary(&mut local).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_instance_health_tracking() {
+        let cluster = ClusterBroadcaster::new(RedisConfig::default(), Arc::new(MemoryTransport::new()));
+        let now = Instant::now();
+
+        cluster
+            .record_heartbeat(
+                InstanceHeartbeat {
+                    instance_id: "peer-1".to_string(),
+                    connections: 3,
+                    timestamp: 0,
+                    url: None,
+                    draining: false,
+                },
+                now,
+            )
+            .await;
+        cluster
+            .record_heartbeat(
+                InstanceHeartbeat {
+                    instance_id: cluster.instance_id().to_string(),
+                    connections: 0,
+                    timestamp: 0,
+                    url: None,
+                    draining: false,
+                },
+                now,
+            )
+            .await;
+
+        let instances = cluster.instances().await;
+        assert_eq!(instances.len(), 1);
+        assert_eq!(instances[0].connections, 3);
+
+        cluster.prune_instances(now + HEARTBEAT_INTERVAL).await;
+        assert_eq!(cluster.instances().await.len(), 1);
+
+        cluster
+            .prune_instances(now + HEARTBEAT_INTERVAL * MISSED_HEARTBEATS)
+            .await;
+        assert!(cluster.instances().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_redirect_target_is_least_loaded_serving_peer() {
+        let cluster = ClusterBroadcaster::new(RedisConfig::default(), Arc::new(MemoryTransport::new()));
+        let now = Instant::now();
+        assert_eq!(cluster.redirect_target().await, None);
+
+        for (instance_id, connections, url, draining) in [
+            ("busy", 40, Some("wss://busy.example"), false),
+            ("quiet", 2, Some("wss://quiet.example"), true),
+            ("hidden", 0, None, false),
+            ("spare", 10, Some("wss://spare.example"), false),
+        ] {
+            cluster
+                .record_heartbeat(
+                    InstanceHeartbeat {
+                        instance_id: instance_id.to_string(),
+                        connections,
+                        timestamp: 0,
+                        url: url.map(str::to_string),
+                        draining,
+                    },
+                    now,
+                )
+                .await;
+        }
+
+        assert_eq!(cluster.redirect_target().await.as_deref(), Some("wss://spare.example"));
+    }
 }
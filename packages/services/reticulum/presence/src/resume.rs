@@ -0,0 +1,173 @@
+//! Session resumption for WebSocket clients that briefly lose their connection
+//!
+//! Every connection is issued a resume token in `ServerHello`. Reliable messages
+//! sent to it are stamped with a per-connection sequence number and kept in a
+//! bounded buffer. When the socket drops without a close frame the session is
+//! parked for a grace period instead of being torn down, so a client that
+//! reconnects with its token and the last sequence it received keeps its place
+//! in the room and has the missed messages replayed.
+
+use chrono::{DateTime, Duration, Utc};
+use std::collections::VecDeque;
+use uuid::Uuid;
+
+/// How long a dropped connection stays resumable
+pub const DEFAULT_RESUME_GRACE_SECS: i64 = 30;
+
+/// Reliable messages kept per connection for replay
+pub const DEFAULT_RESUME_BUFFER_LEN: usize = 256;
+
+/// Field number of `Message.sequence` in core.proto
+const SEQUENCE_FIELD_TAG: u32 = 4;
+
+/// Generate an unguessable resume token
+pub fn generate_resume_token() -> String {
+    Uuid::new_v4().simple().to_string()
+}
+
+/// Set `Message.sequence` on an encoded message.
+///
+/// `sequence` is a singular field, and protobuf decoders keep the last value
+/// when one appears more than once. Appending the field therefore overrides
+/// any sequence already present without re-encoding the rest of the relayed
+/// message.
+pub fn stamp_sequence(message: &[u8], sequence: u32) -> Vec<u8> {
+    let mut stamped = Vec::with_capacity(message.len() + 6);
+    stamped.extend_from_slice(message);
+    prost::encoding::uint32::encode(SEQUENCE_FIELD_TAG, &sequence, &mut stamped);
+    stamped
+}
+
+/// Reliable messages sent to one connection, kept for replay after a reconnect
+pub struct OutboundBuffer {
+    entries: VecDeque<Vec<u8>>,
+    last_sequence: u32,
+    capacity: usize,
+}
+
+impl OutboundBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            last_sequence: 0,
+            capacity,
+        }
+    }
+
+    /// Assign the next sequence number to `message`, buffer it and return the
+    /// stamped bytes to send
+    pub fn push(&mut self, message: &[u8]) -> Vec<u8> {
+        self.last_sequence += 1;
+        let stamped = stamp_sequence(message, self.last_sequence);
+
+        if self.capacity > 0 {
+            if self.entries.len() == self.capacity {
+                self.entries.pop_front();
+            }
+            self.entries.push_back(stamped.clone());
+        }
+
+        stamped
+    }
+
+    /// Sequence number of the most recent message, 0 before the first
+    pub fn last_sequence(&self) -> u32 {
+        self.last_sequence
+    }
+
+    /// Messages sent after `last_received`, oldest first.
+    ///
+    /// Returns `None` when some of them have already been evicted, or when the
+    /// client claims a sequence that was never sent; the session cannot be
+    /// resumed consistently in either case.
+    pub fn replay_after(&self, last_received: u32) -> Option<Vec<Vec<u8>>> {
+        let missed = self.last_sequence.checked_sub(last_received)? as usize;
+        if missed > self.entries.len() {
+            return None;
+        }
+
+        Some(self.entries.iter().skip(self.entries.len() - missed).cloned().collect())
+    }
+}
+
+/// Resumption state kept for a connection
+pub struct ResumableSession {
+    pub token: String,
+    /// Incremented each time a new socket takes the session over, so the task
+    /// serving a replaced socket can tell it no longer owns the connection
+    pub attachment: u64,
+    pub buffer: OutboundBuffer,
+    pub parked_at: Option<DateTime<Utc>>,
+}
+
+impl ResumableSession {
+    pub fn new(buffer_len: usize) -> Self {
+        Self {
+            token: generate_resume_token(),
+            attachment: 0,
+            buffer: OutboundBuffer::new(buffer_len),
+            parked_at: None,
+        }
+    }
+
+    /// Whether the session was parked longer than `grace` before `now`
+    pub fn is_expired(&self, grace: Duration, now: DateTime<Utc>) -> bool {
+        self.parked_at.is_some_and(|parked_at| now - parked_at > grace)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graphwiz_protocol::{MessageBuilder, MessageParser};
+
+    #[test]
+    fn test_stamp_sequence_sets_message_field() {
+        let message = MessageBuilder::entity_despawn("entity-1".to_string());
+        let encoded = MessageParser::serialize(&message).unwrap();
+
+        let stamped = stamp_sequence(&encoded, 7);
+        let restamped = stamp_sequence(&stamped, 8);
+
+        assert_eq!(MessageParser::parse(&stamped).unwrap().sequence, 7);
+        assert_eq!(MessageParser::parse(&restamped).unwrap().sequence, 8);
+    }
+
+    #[test]
+    fn test_replay_after_returns_missed_messages() {
+        let mut buffer = OutboundBuffer::new(8);
+        let sent: Vec<Vec<u8>> = (0u8..5).map(|i| buffer.push(&[i])).collect();
+
+        assert_eq!(buffer.last_sequence(), 5);
+        assert_eq!(buffer.replay_after(2).unwrap(), sent[2..].to_vec());
+        assert!(buffer.replay_after(5).unwrap().is_empty());
+        // A client cannot be ahead of the server
+        assert!(buffer.replay_after(6).is_none());
+    }
+
+    #[test]
+    fn test_replay_fails_once_messages_are_evicted() {
+        let mut buffer = OutboundBuffer::new(3);
+        for i in 0u8..5 {
+            buffer.push(&[i]);
+        }
+
+        assert_eq!(buffer.replay_after(2).unwrap().len(), 3);
+        assert!(buffer.replay_after(1).is_none());
+    }
+
+    #[test]
+    fn test_parked_session_expiry() {
+        let mut session = ResumableSession::new(4);
+        let now = Utc::now();
+        let grace = Duration::seconds(DEFAULT_RESUME_GRACE_SECS);
+
+        assert!(!session.is_expired(grace, now));
+
+        session.parked_at = Some(now - Duration::seconds(5));
+        assert!(!session.is_expired(grace, now));
+
+        session.parked_at = Some(now - grace - Duration::seconds(1));
+        assert!(session.is_expired(grace, now));
+    }
+}
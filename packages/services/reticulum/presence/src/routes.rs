@@ -1,33 +1,59 @@
 //! Route configuration for presence service
 
 use actix_web::web;
-use crate::{handlers, moderation_handlers, websocket};
+use crate::{
+    admin_handlers, chat_handlers, handlers, moderation_handlers, presence_feed_handlers,
+    recording_handlers, user_presence_handlers, websocket,
+};
 
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
     cfg
         // Health check
         .route("/health", web::get().to(handlers::health))
+        .route("/cluster", web::get().to(handlers::get_cluster_status))
         // Connection routes
         .route("/connect", web::post().to(handlers::connect))
+        .route("/sessions/{session_id}/heartbeat", web::post().to(handlers::session_heartbeat))
+        .route("/sessions/{session_id}/mute", web::put().to(handlers::set_session_muted))
         .route("/webtransport/connect", web::post().to(handlers::connect_webtransport))
         // Room presence
         .route("/rooms/{room_id}/clients", web::get().to(handlers::get_room_clients))
+        .route("/rooms/{room_id}/events", web::get().to(presence_feed_handlers::stream_room_events))
+        // Session recording
+        .route("/rooms/{room_id}/recording", web::post().to(recording_handlers::start_recording))
+        .route("/rooms/{room_id}/recording", web::get().to(recording_handlers::get_recording))
+        .route("/rooms/{room_id}/recording", web::delete().to(recording_handlers::stop_recording))
+        .route("/recordings/{recording_id}/replay", web::post().to(recording_handlers::replay_recording))
         // WebSocket routes
         .route("/ws/{room_id}", web::get().to(websocket::websocket_handler))
         .route("/ws/{room_id}/stats", web::get().to(websocket::get_stats))
+        .route("/ws/{room_id}/tick-rate", web::put().to(websocket::set_tick_rate))
+        .route("/ws/{room_id}/spatial-audio", web::get().to(websocket::get_spatial_audio))
+        .route("/ws/{room_id}/spatial-audio", web::put().to(websocket::set_spatial_audio))
+        .route("/ws/{room_id}/spatial-audio", web::delete().to(websocket::remove_spatial_audio))
         .route("/ws/stats", web::get().to(websocket::get_all_stats))
         // Performance metrics
         .route("/metrics", web::get().to(websocket::get_metrics))
-        // WebRTC signaling
-        .route("/signaling", web::post().to(handlers::signaling))
-        // Message queue management (NEW)
-        .route("/queue/batch-config", web::post().to(handlers::set_batch_config))
-        .route("/queue/depth-limit", web::post().to(handlers::set_queue_depth))
-        .route("/queue/stats/{session_id}", web::get().to(handlers::get_queue_stats))
-        .route("/queue/stats", web::get().to(handlers::get_all_queue_stats))
-        .route("/queue/rate-limit/{session_id}", web::post().to(handlers::set_rate_limit))
         // Moderation routes
         .route("/moderation/kick", web::post().to(moderation_handlers::kick_player))
         .route("/moderation/mute", web::post().to(moderation_handlers::mute_player))
-        .route("/moderation/lock", web::post().to(moderation_handlers::lock_room));
+        .route("/moderation/ban", web::post().to(moderation_handlers::ban_player))
+        .route("/moderation/lock", web::post().to(moderation_handlers::lock_room))
+        .route("/moderation/rooms/{room_id}/actions", web::get().to(moderation_handlers::get_room_actions))
+        .route("/moderation/users/{user_id}/actions", web::get().to(moderation_handlers::get_user_actions))
+        // Chat routes
+        .route("/chat/rooms/{room_id}/messages", web::get().to(chat_handlers::get_history))
+        .route("/chat/rooms/{room_id}/messages/{chat_id}", web::delete().to(chat_handlers::delete_message))
+        .route("/chat/rooms/{room_id}/teams/{user_id}", web::put().to(chat_handlers::assign_team))
+        // User presence routes
+        .route("/presence/users", web::get().to(user_presence_handlers::get_users_presence))
+        .route("/presence/users/{user_id}", web::get().to(user_presence_handlers::get_user_presence))
+        // Admin routes
+        .route("/admin/drain", web::post().to(admin_handlers::start_drain))
+        .route("/admin/drain", web::get().to(admin_handlers::get_drain_status))
+        .route("/admin/rooms/{room_id}/sessions", web::get().to(admin_handlers::get_room_sessions))
+        .route("/admin/rooms/{room_id}/snapshot", web::get().to(admin_handlers::get_room_snapshot))
+        .route("/admin/rooms/{room_id}/messages", web::post().to(admin_handlers::post_room_message))
+        .route("/admin/users/{user_id}/sessions", web::get().to(admin_handlers::get_user_sessions))
+        .route("/admin/sessions/{conn_id}", web::delete().to(admin_handlers::disconnect_session));
 }
@@ -5,7 +5,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use chrono::{DateTime, Utc, Duration};
-use serde::{Serialize, Deserialize};
+use serde::Serialize;
 
 #[derive(Clone)]
 pub struct ClientSession {
@@ -18,18 +18,20 @@ pub struct ClientSession {
     pub is_muted: bool,
 }
 
+/// Sessions registered over `/connect`. Clones share the same sessions.
+#[derive(Clone)]
 pub struct SessionManager {
     sessions: Arc<RwLock<HashMap<String, ClientSession>>>,
     room_sessions: Arc<RwLock<HashMap<String, Vec<String>>>>, // room_id -> session_ids
-    room_hosts: Arc<RwLock<HashMap<String, Option<String>>>>, // room_id -> host_client_id
     pending_messages: Arc<RwLock<HashMap<String, Vec<serde_json::Value>>>>, // session_id -> pending messages
-    locked_rooms: Arc<RwLock<HashMap<String, bool>>>, // room_id -> is_locked
     flush_tracker: Arc<RwLock<FlushTracker>>, // Track flush times per session
     batch_size: usize,
     max_batch_size: usize,           // Maximum allowed batch size
     batch_timeout: Duration,
     max_queue_depth: usize,          // Maximum queue depth per session
     rate_limits: Arc<RwLock<HashMap<String, RateLimiter>>>, // session_id -> rate limiter
+    rate_limit_messages: usize,      // Messages allowed per window for new sessions
+    rate_limit_window: Duration,
 }
 
 pub struct RateLimiter {
@@ -107,16 +109,15 @@ impl SessionManager {
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             room_sessions: Arc::new(RwLock::new(HashMap::new())),
-            room_hosts: Arc::new(RwLock::new(HashMap::new())),
-
             pending_messages: Arc::new(RwLock::new(HashMap::new())),
-            locked_rooms: Arc::new(RwLock::new(HashMap::new())),
             flush_tracker: Arc::new(RwLock::new(FlushTracker::new())),
             batch_size: 50, // Default batch size
             max_batch_size:100, // Maximum allowed batch size (configurable)
-            batch_timeout: Duration::from_millis(50), // Flush every 50ms
+            batch_timeout: Duration::milliseconds(50), // Flush every 50ms
             max_queue_depth: 1000, // Maximum queue depth per session
             rate_limits: Arc::new(RwLock::new(HashMap::new())),
+            rate_limit_messages: 100, // 100 messages per second
+            rate_limit_window: Duration::seconds(1),
         }
     }
 
@@ -132,16 +133,15 @@ impl SessionManager {
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             room_sessions: Arc::new(RwLock::new(HashMap::new())),
-            room_hosts: Arc::new(RwLock::new(HashMap::new())),
-
             pending_messages: Arc::new(RwLock::new(HashMap::new())),
-            locked_rooms: Arc::new(RwLock::new(HashMap::new())),
             flush_tracker: Arc::new(RwLock::new(FlushTracker::new())),
             batch_size,
             max_batch_size,
             batch_timeout,
             max_queue_depth,
             rate_limits: Arc::new(RwLock::new(HashMap::new())),
+            rate_limit_messages,
+            rate_limit_window,
         }
     }
 
@@ -159,7 +159,6 @@ impl SessionManager {
             room_sessions.entry(room_id.clone())
                 .or_insert_with(Vec::new)
                 .push(session_id.clone());
-            self.assign_host_if_needed(&room_id, &session.client_id).await;
         }
 
         // Initialize pending messages
@@ -171,7 +170,7 @@ impl SessionManager {
         let mut rate_limits = self.rate_limits.write().await;
         rate_limits.insert(
             session_id.clone(),
-            RateLimiter::new(100, Duration::from_secs(1)) // 100 messages per second
+            RateLimiter::new(self.rate_limit_messages, self.rate_limit_window)
         );
 
         log::info!("Session {} registered with rate limiting", session_id);
@@ -179,6 +178,68 @@ impl SessionManager {
     }
 
 
+    /// Get a session by ID
+    pub async fn get_session(&self, session_id: &str) -> Option<ClientSession> {
+        let sessions = self.sessions.read().await;
+        sessions.get(session_id).cloned()
+    }
+
+    /// Record a heartbeat from a session
+    pub async fn update_heartbeat(&self, session_id: &str) -> Result<()> {
+        let mut sessions = self.sessions.write().await;
+        match sessions.get_mut(session_id) {
+            Some(session) => {
+                session.last_heartbeat = Utc::now();
+                Ok(())
+            }
+            None => Err(reticulum_core::Error::not_found(format!("Session {} not found", session_id))),
+        }
+    }
+
+    /// Mute or unmute a session's voice
+    pub async fn set_muted(&self, session_id: &str, muted: bool) -> Result<()> {
+        let mut sessions = self.sessions.write().await;
+        match sessions.get_mut(session_id) {
+            Some(session) => {
+                session.is_muted = muted;
+                Ok(())
+            }
+            None => Err(reticulum_core::Error::not_found(format!("Session {} not found", session_id))),
+        }
+    }
+
+    /// Whether a client has a muted session in the room
+    pub async fn is_client_muted(&self, room_id: &str, client_id: &str) -> bool {
+        let sessions = self.sessions.read().await;
+        sessions.values().any(|session| {
+            session.is_muted && session.client_id == client_id && session.room_id.as_deref() == Some(room_id)
+        })
+    }
+
+    /// Sessions without a heartbeat for longer than `timeout`
+    pub async fn stale_sessions(&self, timeout: Duration) -> Vec<ClientSession> {
+        let cutoff = Utc::now() - timeout;
+        let sessions = self.sessions.read().await;
+        sessions
+            .values()
+            .filter(|session| session.last_heartbeat < cutoff)
+            .cloned()
+            .collect()
+    }
+
+    /// Sessions in a room, in join order
+    pub async fn get_room_sessions(&self, room_id: &str) -> Vec<ClientSession> {
+        let sessions = self.sessions.read().await;
+        let room_sessions = self.room_sessions.read().await;
+        room_sessions
+            .get(room_id)
+            .into_iter()
+            .flatten()
+            .filter_map(|id| sessions.get(id))
+            .cloned()
+            .collect()
+    }
+
     /// Unregister a session
     pub async fn unregister_session(&self, session_id: &str) -> Result<Option<ClientSession>> {
         let mut sessions = self.sessions.write().await;
@@ -224,7 +285,7 @@ impl SessionManager {
             let mut rate_limits = self.rate_limits.write().await;
             let rate_limiter = rate_limits
                 .entry(session_id.to_string())
-                .or_insert_with(|| RateLimiter::new(100, Duration::from_secs(1))); // 100 messages per second default
+                .or_insert_with(|| RateLimiter::new(self.rate_limit_messages, self.rate_limit_window));
             
             if !rate_limiter.check() {
                 log::warn!("Rate limit exceeded for session {}", session_id);
@@ -273,6 +334,23 @@ impl SessionManager {
         }
     }
 
+    /// Queue a message for every session in a room whose queue has room for
+    /// it; returns how many sessions queued it
+    pub async fn broadcast_to_room(&self, room_id: &str, message: serde_json::Value) -> Result<usize> {
+        let session_ids = self.room_sessions.read().await.get(room_id).cloned().unwrap_or_default();
+        let mut pending = self.pending_messages.write().await;
+
+        let mut queued = 0;
+        for session_id in session_ids {
+            let messages = pending.entry(session_id).or_default();
+            if messages.len() < self.max_queue_depth {
+                messages.push(message.clone());
+                queued += 1;
+            }
+        }
+        Ok(queued)
+    }
+
     /// Flush all pending messages for a session
     pub async fn flush_session(&self, session_id: &str) -> Result<usize> {
         let mut pending = self.pending_messages.write().await;
@@ -287,11 +365,12 @@ impl SessionManager {
 
                 // In a real implementation, send to clients here
                 // For now, we just log them
+                let count = messages.len();
                 for msg in messages {
                     log::info!("Session {} message: {:?}", session_id, msg);
                 }
 
-                Ok(messages.len())
+                Ok(count)
             } else {
                 Ok(0)
             }
@@ -358,7 +437,7 @@ impl SessionManager {
         // Check if session exists
         let sessions = self.sessions.read().await;
         if !sessions.contains_key(session_id) {
-            return Err(eyre::eyre!("Session not found: {}", session_id));
+            return Err(reticulum_core::Error::not_found(format!("Session not found: {}", session_id)));
         }
 
         rate_limits.insert(
@@ -371,44 +450,26 @@ impl SessionManager {
         Ok(())
     }
 
-    /// Assign host to room if not already assigned (first joiner becomes host)
-    async fn assign_host_if_needed(&self, room_id: &str, client_id: &str) {
-        let mut room_hosts = self.room_hosts.write().await;
-        let existing = room_hosts.entry(room_id.to_string()).or_insert(None);
-        if existing.is_none() {
-            *existing = Some(client_id.to_string());
-            log::info!("Client {} is now the host of room {}", client_id, room_id);
-        }
-    }
-
-    /// Get the host client ID for a room
-    pub async fn get_room_host(&self, room_id: &str) -> Option<String> {
-        let room_hosts = self.room_hosts.read().await;
-        room_hosts.get(room_id).cloned().flatten()
-    }
-
-    /// Flush all pending messages (called periodically)
     /// Flush all pending messages (called periodically)
     pub async fn flush_all(&self) {
-        let pending = self.pending_messages.read().await;
-        let session_ids: Vec<_> = pending.keys().cloned().collect();
+        let session_ids: Vec<_> = self.pending_messages.read().await.keys().cloned().collect();
 
         for session_id in session_ids {
-            self.flush_session(&session_id).await;
+            let _ = self.flush_session(&session_id).await;
         }
     }
 
     /// Start background task to flush messages periodically
     pub fn start_background_flush_task(&self) -> tokio::task::JoinHandle<()> {
-        let pending_messages = self.pending_messages.clone();
-        let batch_timeout = self.batch_timeout;
+        let session_manager = self.clone();
+        let batch_timeout = self.batch_timeout.to_std().unwrap_or(std::time::Duration::from_millis(50));
 
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(batch_timeout);
 
             loop {
                 interval.tick().await;
-                pending_messages.flush_all().await;
+                session_manager.flush_all().await;
             }
         })
     }
@@ -514,12 +575,12 @@ mod tests {
         // Add messages below batch size
         for i in 0..49 {
             let result = manager.add_to_batch(&session_id, json!({"msg": i})).await.unwrap();
-            assert_eq!(result, false, "Should not flush at message {}", i);
+            assert!(!result, "Should not flush at message {}", i);
         }
 
         // Add message at batch size (50)
         let result = manager.add_to_batch(&session_id, json!({"msg": 49})).await.unwrap();
-        assert_eq!(result, true, "Should flush at message 49");
+        assert!(result, "Should flush at message 49");
 
         // Get batched messages
         let batched = manager.get_batched_messages(&session_id).await;
@@ -544,7 +605,7 @@ mod tests {
 
         // Add messages up to max_queue_depth (1000)
         for i in 0..1000 {
-            let result = manager.add_to_batch(&session_id, json!({"msg": i})).await.unwrap();
+            let result = manager.add_to_batch(&session_id, json!({"msg": i})).await;
             assert!(result.is_ok(), "Should succeed at message {}", i);
         }
 
@@ -615,10 +676,10 @@ mod tests {
         let manager = SessionManager::with_config(
             25,                      // batch_size
             75,                      // max_batch_size
-            Duration::from_millis(100),  // batch_timeout
+            Duration::milliseconds(100),  // batch_timeout
             500,                      // max_queue_depth
             200,                      // rate_limit_messages
-            Duration::from_millis(500)  // rate_limit_window
+            Duration::milliseconds(500)  // rate_limit_window
         );
 
         let session = ClientSession {
@@ -637,12 +698,12 @@ mod tests {
         // Add messages up to configured batch size
         for i in 0..24 {
             let result = manager.add_to_batch(&session_id, json!({"msg": i})).await.unwrap();
-            assert_eq!(result, false, "Should not flush at message {}", i);
+            assert!(!result, "Should not flush at message {}", i);
         }
 
         // Add message at batch size (25)
         let result = manager.add_to_batch(&session_id, json!({"msg": 24})).await.unwrap();
-        assert_eq!(result, true, "Should flush at message 24 with configured batch size");
+        assert!(result, "Should flush at message 24 with configured batch size");
     }
 
     #[tokio::test]
@@ -733,22 +794,21 @@ mod tests {
         let room_id = "room1".to_string();
 
         // Create 3 sessions in same room
-        let session_ids: Vec<String> = (0..3)
-            .map(|i| {
-                let session_id = Uuid::new_v4().to_string();
-                let session = ClientSession {
-                    session_id: session_id.clone(),
-                    client_id: Uuid::new_v4().to_string(),
-                    user_id: format!("user{}", i),
-                    room_id: Some(room_id.clone()),
-                    connected_at: chrono::Utc::now(),
-                    last_heartbeat: chrono::Utc::now(),
-                    is_muted: false,
-                };
-                manager.register_session(session).await.unwrap();
-                session_id
-            })
-            .collect();
+        let mut session_ids = Vec::new();
+        for i in 0..3 {
+            let session_id = Uuid::new_v4().to_string();
+            let session = ClientSession {
+                session_id: session_id.clone(),
+                client_id: Uuid::new_v4().to_string(),
+                user_id: format!("user{}", i),
+                room_id: Some(room_id.clone()),
+                connected_at: chrono::Utc::now(),
+                last_heartbeat: chrono::Utc::now(),
+                is_muted: false,
+            };
+            manager.register_session(session).await.unwrap();
+            session_ids.push(session_id);
+        }
 
         // Broadcast message
         let message = json!({"type": "test", "data": "hello"});
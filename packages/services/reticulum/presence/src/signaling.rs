@@ -1,17 +1,44 @@
-//! WebRTC/WebTransport signaling
+//! WebRTC signaling relay
+//!
+//! Small rooms can set up peer-to-peer media without the SFU. Clients exchange
+//! offers, answers and ICE candidates as `RTC_SIGNAL` messages addressed to one
+//! peer by client id. The server stamps the sender and delivers the signal to
+//! the peer's connection on this instance, or relays it to the other instances
+//! on [`SIGNALING_CHANNEL`]. Session descriptions are passed on untouched.
+//!
+//! When two peers offer to each other at once (glare), the offer from the
+//! lower client id wins. The losing offer is dropped where it would have been
+//! delivered and its sender gets a `SIGNALING_GLARE` error frame, after which
+//! it rolls back and answers the winning offer. A hangup ends the call between
+//! two peers, or every call of the sender when it names no peer. Clients that
+//! signaled are hung up when they disconnect.
 
-use reticulum_core::Result;
+use crate::protobuf::{Flow, MessageContext, MessageHandler, Route};
+use crate::redis::PubSubTransport;
+use crate::websocket::{WebSocketManager, WsMessage};
+use async_trait::async_trait;
+use graphwiz_protocol::generated::graphwiz::core::message::Payload;
+use graphwiz_protocol::generated::graphwiz::core::{RtcSignal, RtcSignalType};
+use graphwiz_protocol::{Message, MessageBuilder, MessageParser, MessageType};
+use reticulum_core::{Error, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::RwLock;
 
-#[derive(Clone, Serialize, Deserialize)]
+/// Channel on which instances relay signals for peers they do not serve
+pub const SIGNALING_CHANNEL: &str = "graphwiz:signaling";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignalingMessage {
     pub message_type: SignalingMessageType,
     pub from_client_id: String,
+    /// Peer the signal is for; `None` only for a hangup of every call
     pub to_client_id: Option<String>,
     pub payload: SignalingPayload,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SignalingMessageType {
     Offer,
     Answer,
@@ -27,64 +54,432 @@ pub enum SignalingPayload {
     Hangup,
 }
 
+impl SignalingMessage {
+    /// Signal sent by `from_client_id`; the sender the client claims is ignored
+    pub fn from_proto(signal: &RtcSignal, from_client_id: &str) -> Result<Self> {
+        let signal_type = RtcSignalType::try_from(signal.signal_type)
+            .map_err(|_| Error::validation(format!("Unknown signal type {}", signal.signal_type)))?;
+        let (message_type, payload) = match signal_type {
+            RtcSignalType::Offer => (
+                SignalingMessageType::Offer,
+                SignalingPayload::Offer { sdp: signal.sdp.clone() },
+            ),
+            RtcSignalType::Answer => (
+                SignalingMessageType::Answer,
+                SignalingPayload::Answer { sdp: signal.sdp.clone() },
+            ),
+            RtcSignalType::IceCandidate => (
+                SignalingMessageType::IceCandidate,
+                SignalingPayload::IceCandidate {
+                    candidate: signal.candidate.clone(),
+                    sdp_mid: signal.sdp_mid.clone(),
+                    sdp_mline_index: u16::try_from(signal.sdp_mline_index)
+                        .map_err(|_| Error::validation("sdp_mline_index is out of range"))?,
+                },
+            ),
+            RtcSignalType::Hangup => (SignalingMessageType::Hangup, SignalingPayload::Hangup),
+        };
+
+        let to_client_id = Some(signal.to_client_id.clone()).filter(|to| !to.is_empty());
+        if to_client_id.is_none() && message_type != SignalingMessageType::Hangup {
+            return Err(Error::validation("Signals other than hangups need a to_client_id"));
+        }
+
+        Ok(Self {
+            message_type,
+            from_client_id: from_client_id.to_string(),
+            to_client_id,
+            payload,
+        })
+    }
+
+    pub fn to_proto(&self) -> RtcSignal {
+        let mut signal = RtcSignal {
+            from_client_id: self.from_client_id.clone(),
+            to_client_id: self.to_client_id.clone().unwrap_or_default(),
+            ..Default::default()
+        };
+        match &self.payload {
+            SignalingPayload::Offer { sdp } => {
+                signal.signal_type = RtcSignalType::Offer as i32;
+                signal.sdp = sdp.clone();
+            }
+            SignalingPayload::Answer { sdp } => {
+                signal.signal_type = RtcSignalType::Answer as i32;
+                signal.sdp = sdp.clone();
+            }
+            SignalingPayload::IceCandidate { candidate, sdp_mid, sdp_mline_index } => {
+                signal.signal_type = RtcSignalType::IceCandidate as i32;
+                signal.candidate = candidate.clone();
+                signal.sdp_mid = sdp_mid.clone();
+                signal.sdp_mline_index = u32::from(*sdp_mline_index);
+            }
+            SignalingPayload::Hangup => signal.signal_type = RtcSignalType::Hangup as i32,
+        }
+        signal
+    }
+}
+
+/// A signal relayed to the other instances, as published on [`SIGNALING_CHANNEL`]
+#[derive(Debug, Serialize, Deserialize)]
+struct SignalRelay {
+    instance_id: String,
+    room_id: String,
+    signal: SignalingMessage,
+}
+
+/// Routes signals between peers of a room across the cluster
 #[derive(Clone)]
 pub struct SignalingServer {
-    // In a real implementation, this would manage WebRTC peer connections
+    instance_id: String,
+    transport: Arc<dyn PubSubTransport>,
+    offers: Arc<RwLock<HashSet<(String, String, String)>>>, // (room_id, from, to) of offers sent from here and not yet answered
+    signaled: Arc<RwLock<HashSet<(String, String)>>>, // (room_id, client_id) of local clients hung up on disconnect
 }
 
 impl SignalingServer {
-    pub fn new() -> Self {
-        Self {}
+    /// Server relaying to the instances on `transport`
+    pub fn new(instance_id: &str, transport: Arc<dyn PubSubTransport>) -> Self {
+        Self {
+            instance_id: instance_id.to_string(),
+            transport,
+            offers: Arc::new(RwLock::new(HashSet::new())),
+            signaled: Arc::new(RwLock::new(HashSet::new())),
+        }
     }
 
-    /// Handle a WebRTC offer from a client
-    pub async fn handle_offer(&self, room_id: &str, from_client: &str, _offer: &str) -> Result<()> {
-        // Broadcast offer to other clients in the room
-        log::info!("Received WebRTC offer from {} in room {}", from_client, room_id);
-        Ok(())
-    }
+    /// Relay `ws_manager`'s signals and deliver those relayed by other instances
+    pub async fn start(&self, ws_manager: &WebSocketManager) -> Result<()> {
+        let mut relays = self.transport.psubscribe(SIGNALING_CHANNEL).await?;
+
+        ws_manager
+            .register_handler(MessageType::RtcSignal, Arc::new(self.clone()))
+            .await;
+        ws_manager.attach_signaling(self.clone()).await;
 
-    /// Handle a WebRTC answer from a client
-    pub async fn handle_answer(&self, room_id: &str, from_client: &str, _answer: &str) -> Result<()> {
-        // Send answer to the target client
-        log::info!("Received WebRTC answer from {} in room {}", from_client, room_id);
+        let server = self.clone();
+        let manager = ws_manager.clone();
+        tokio::spawn(async move {
+            while let Some((_, payload)) = relays.recv().await {
+                match serde_json::from_slice::<SignalRelay>(&payload) {
+                    Ok(relay) if relay.instance_id != server.instance_id => {
+                        server.deliver(&manager, &relay.room_id, &relay.signal).await;
+                    }
+                    Ok(_) => {}
+                    Err(e) => log::warn!("Invalid signal relay: {}", e),
+                }
+            }
+        });
+
+        log::info!("WebRTC signals are relayed over {}", self.transport.name());
         Ok(())
     }
 
-    /// Handle an ICE candidate from a client
-    pub async fn handle_ice_candidate(
+    /// Route a signal from a client of this instance to its peer
+    pub async fn relay(
         &self,
+        ws_manager: &WebSocketManager,
         room_id: &str,
-        from_client: &str,
-        _candidate: &str,
-        _sdp_mid: &str,
-        _sdp_mline_index: u16,
+        message: SignalingMessage,
     ) -> Result<()> {
-        log::info!("Received ICE candidate from {} in room {}", from_client, room_id);
-        Ok(())
+        log::debug!(
+            "{:?} from {} to {} in room {}",
+            message.message_type,
+            message.from_client_id,
+            message.to_client_id.as_deref().unwrap_or("everyone"),
+            room_id
+        );
+        match message.message_type {
+            SignalingMessageType::Offer => self.handle_offer(room_id, &message).await,
+            SignalingMessageType::Hangup => self.handle_hangup(room_id, &message).await,
+            SignalingMessageType::Answer | SignalingMessageType::IceCandidate => {}
+        }
+
+        // A peer connected here needs nothing from the other instances
+        let delivered = self.deliver(ws_manager, room_id, &message).await;
+        if delivered && message.to_client_id.is_some() {
+            return Ok(());
+        }
+
+        let relay = SignalRelay {
+            instance_id: self.instance_id.clone(),
+            room_id: room_id.to_string(),
+            signal: message,
+        };
+        self.transport
+            .publish(SIGNALING_CHANNEL, serde_json::to_vec(&relay)?)
+            .await
     }
 
-    /// Handle client disconnect
-    pub async fn handle_hangup(&self, room_id: &str, from_client: &str) -> Result<()> {
-        log::info!("Client {} disconnected from room {}", from_client, room_id);
-        Ok(())
+    /// Remember an offer until it is answered, to detect glare
+    async fn handle_offer(&self, room_id: &str, message: &SignalingMessage) {
+        if let Some(to) = &message.to_client_id {
+            self.offers
+                .write()
+                .await
+                .insert((room_id.to_string(), message.from_client_id.clone(), to.clone()));
+        }
+    }
+
+    /// Forget the offers of the calls a hangup ends
+    async fn handle_hangup(&self, room_id: &str, message: &SignalingMessage) {
+        let from = &message.from_client_id;
+        let ended = |offerer: &String, answerer: &String| match &message.to_client_id {
+            Some(to) => (offerer == from && answerer == to) || (offerer == to && answerer == from),
+            None => offerer == from || answerer == from,
+        };
+        self.offers
+            .write()
+            .await
+            .retain(|(room, offerer, answerer)| room != room_id || !ended(offerer, answerer));
+    }
+
+    /// Hang up every call of a client that disconnected, if it signaled
+    pub async fn disconnected(&self, ws_manager: &WebSocketManager, room_id: &str, client_id: &str) {
+        if !self
+            .signaled
+            .write()
+            .await
+            .remove(&(room_id.to_string(), client_id.to_string()))
+        {
+            return;
+        }
+
+        let hangup = SignalingMessage {
+            message_type: SignalingMessageType::Hangup,
+            from_client_id: client_id.to_string(),
+            to_client_id: None,
+            payload: SignalingPayload::Hangup,
+        };
+        if let Err(e) = self.relay(ws_manager, room_id, hangup).await {
+            log::warn!("Failed to hang up the calls of {} in room {}: {}", client_id, room_id, e);
+        }
+    }
+
+    /// Send a signal to its recipients among this instance's connections,
+    /// returning whether any is connected here
+    async fn deliver(&self, ws_manager: &WebSocketManager, room_id: &str, message: &SignalingMessage) -> bool {
+        let from = &message.from_client_id;
+        let mut recipients = Vec::new();
+        for conn_id in ws_manager.get_room_connections(room_id).await {
+            let Some(client_id) = ws_manager
+                .get_connection_info(&conn_id)
+                .await
+                .and_then(|conn| conn.client_id)
+            else {
+                continue;
+            };
+            let addressed = match &message.to_client_id {
+                Some(to) => &client_id == to,
+                None => &client_id != from,
+            };
+            if addressed {
+                recipients.push((conn_id, client_id));
+            }
+        }
+        if recipients.is_empty() {
+            return false;
+        }
+
+        let mut glare = Vec::new();
+        {
+            let mut offers = self.offers.write().await;
+            for (conn_id, client_id) in &recipients {
+                let theirs = (room_id.to_string(), client_id.clone(), from.clone());
+                match message.message_type {
+                    SignalingMessageType::Offer if offers.contains(&theirs) => {
+                        // The lower client id wins; the recipient's own offer
+                        // was dropped on the way to the sender
+                        if from > client_id {
+                            log::debug!("Dropping offer from {} to {}: glare", from, client_id);
+                            return true;
+                        }
+                        offers.remove(&theirs);
+                        glare.push(conn_id.clone());
+                    }
+                    SignalingMessageType::Answer | SignalingMessageType::Hangup => {
+                        offers.remove(&theirs);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        for conn_id in glare {
+            let notice = MessageBuilder::error_frame(
+                "SIGNALING_GLARE",
+                format!("{} offered at the same time; roll back and answer their offer", from),
+                0,
+            );
+            send(ws_manager, &conn_id, &notice).await;
+        }
+        let signal = MessageBuilder::rtc_signal(message.to_proto());
+        for (conn_id, _) in &recipients {
+            send(ws_manager, conn_id, &signal).await;
+        }
+        true
     }
 }
 
-impl Default for SignalingServer {
-    fn default() -> Self {
-        Self::new()
+async fn send(ws_manager: &WebSocketManager, conn_id: &str, message: &Message) {
+    match MessageParser::serialize(message) {
+        Ok(bytes) => {
+            let _ = ws_manager.send_to_connection(conn_id, WsMessage::Binary(bytes)).await;
+        }
+        Err(e) => log::error!("Failed to encode signal for {}: {}", conn_id, e),
+    }
+}
+
+#[async_trait]
+impl MessageHandler for SignalingServer {
+    async fn handle(&self, message: &mut Message, ctx: &mut MessageContext<'_>) -> Result<Flow> {
+        let Some(Payload::RtcSignal(signal)) = message.payload.as_ref() else {
+            return Ok(Flow::Continue);
+        };
+        let Some(client_id) = ctx.client_id().map(str::to_string) else {
+            ctx.send(
+                Route::Sender,
+                MessageBuilder::error_frame("SIGNALING_REJECTED", "Connect with a client id to signal".to_string(), 0),
+            );
+            return Ok(Flow::Drop);
+        };
+
+        self.signaled
+            .write()
+            .await
+            .insert((ctx.room_id.to_string(), client_id.clone()));
+        let relayed = match SignalingMessage::from_proto(signal, &client_id) {
+            Ok(signal) => self.relay(ctx.ws_manager, ctx.room_id, signal).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = relayed {
+            ctx.send(
+                Route::Sender,
+                MessageBuilder::error_frame("SIGNALING_REJECTED", e.to_string(), 0),
+            );
+        }
+        Ok(Flow::Drop)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::protobuf::route_message;
+    use crate::queue::OutboundReceiver;
+    use crate::redis::MemoryTransport;
+    use std::time::Duration;
+    use tokio::time::timeout;
+
+    /// Next signal or error frame a connection receives, skipping the rest
+    async fn next(rx: &mut OutboundReceiver<WsMessage>) -> Option<Payload> {
+        loop {
+            match timeout(Duration::from_millis(100), rx.queue.recv()).await {
+                Ok(Some(WsMessage::Binary(bytes))) => match MessageParser::parse(&bytes).ok()?.payload {
+                    Some(payload @ (Payload::RtcSignal(_) | Payload::ErrorFrame(_))) => return Some(payload),
+                    _ => continue,
+                },
+                _ => return None,
+            }
+        }
+    }
+
+    async fn join(ws_manager: &WebSocketManager, client_id: &str) -> OutboundReceiver<WsMessage> {
+        ws_manager
+            .add_connection(client_id.to_string(), Some("room-1".to_string()), None, Some(client_id.to_string()))
+            .await
+    }
+
+    async fn signal(ws_manager: &WebSocketManager, from: &str, signal_type: RtcSignalType, to: &str) {
+        let message = MessageBuilder::rtc_signal(RtcSignal {
+            signal_type: signal_type as i32,
+            // Clients cannot pose as someone else
+            from_client_id: "mallory".to_string(),
+            to_client_id: to.to_string(),
+            sdp: format!("sdp from {}", from),
+            ..Default::default()
+        });
+        let handlers = ws_manager.handlers().await;
+        route_message(&handlers, ws_manager, "room-1", from, &MessageParser::serialize(&message).unwrap())
+            .await
+            .unwrap();
+    }
+
+    fn signal_of(payload: Option<Payload>) -> RtcSignal {
+        match payload {
+            Some(Payload::RtcSignal(signal)) => signal,
+            other => panic!("expected a signal, got {:?}", other),
+        }
+    }
 
     #[tokio::test]
-    async fn test_signaling_server() {
-        let server = SignalingServer::new();
-        let result = server.handle_offer("room1", "client1", "test_sdp").await;
-        assert!(result.is_ok());
+    async fn test_signals_reach_only_the_addressed_peer() {
+        let ws_manager = WebSocketManager::new();
+        let server = SignalingServer::new("instance-1", Arc::new(MemoryTransport::new()));
+        server.start(&ws_manager).await.unwrap();
+        let mut alice = join(&ws_manager, "alice").await;
+        let mut bob = join(&ws_manager, "bob").await;
+        let mut carol = join(&ws_manager, "carol").await;
+
+        signal(&ws_manager, "alice", RtcSignalType::Offer, "bob").await;
+        let offer = signal_of(next(&mut bob).await);
+        assert_eq!(offer.signal_type, RtcSignalType::Offer as i32);
+        assert_eq!(offer.from_client_id, "alice");
+        assert_eq!(offer.sdp, "sdp from alice");
+        assert!(next(&mut carol).await.is_none());
+
+        signal(&ws_manager, "bob", RtcSignalType::Answer, "alice").await;
+        assert_eq!(signal_of(next(&mut alice).await).signal_type, RtcSignalType::Answer as i32);
+
+        // An offer needs a peer
+        signal(&ws_manager, "alice", RtcSignalType::Offer, "").await;
+        assert!(matches!(next(&mut alice).await, Some(Payload::ErrorFrame(error)) if error.code == "SIGNALING_REJECTED"));
+    }
+
+    #[tokio::test]
+    async fn test_glare_keeps_the_lower_client_ids_offer() {
+        let ws_manager = WebSocketManager::new();
+        let server = SignalingServer::new("instance-1", Arc::new(MemoryTransport::new()));
+        server.start(&ws_manager).await.unwrap();
+        let mut alice = join(&ws_manager, "alice").await;
+        let mut bob = join(&ws_manager, "bob").await;
+
+        signal(&ws_manager, "bob", RtcSignalType::Offer, "alice").await;
+        assert_eq!(signal_of(next(&mut alice).await).from_client_id, "bob");
+
+        // Alice offered before seeing Bob's offer; hers wins
+        signal(&ws_manager, "alice", RtcSignalType::Offer, "bob").await;
+        assert!(matches!(next(&mut bob).await, Some(Payload::ErrorFrame(error)) if error.code == "SIGNALING_GLARE"));
+        assert_eq!(signal_of(next(&mut bob).await).from_client_id, "alice");
+        assert!(next(&mut alice).await.is_none());
+
+        // Bob tries again without rolling back; his offer loses
+        signal(&ws_manager, "bob", RtcSignalType::Offer, "alice").await;
+        assert!(next(&mut alice).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_signals_cross_instances_and_disconnects_hang_up() {
+        let transport: Arc<dyn PubSubTransport> = Arc::new(MemoryTransport::new());
+        let first_manager = WebSocketManager::new();
+        SignalingServer::new("instance-1", transport.clone())
+            .start(&first_manager)
+            .await
+            .unwrap();
+        let second_manager = WebSocketManager::new();
+        SignalingServer::new("instance-2", transport)
+            .start(&second_manager)
+            .await
+            .unwrap();
+        let _alice = join(&first_manager, "alice").await;
+        let mut bob = join(&second_manager, "bob").await;
+
+        signal(&first_manager, "alice", RtcSignalType::Offer, "bob").await;
+        assert_eq!(signal_of(next(&mut bob).await).from_client_id, "alice");
+
+        crate::websocket::close_connection(&first_manager, "alice").await;
+        let hangup = signal_of(next(&mut bob).await);
+        assert_eq!(hangup.signal_type, RtcSignalType::Hangup as i32);
+        assert_eq!(hangup.from_client_id, "alice");
     }
 }
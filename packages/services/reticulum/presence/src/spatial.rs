@@ -0,0 +1,352 @@
+//! Spatial audio metadata
+//!
+//! Presence already sees every avatar's position, so it works out how each
+//! listener should hear each speaker instead of leaving every client to do
+//! it for everyone else in the room. In rooms with spatial audio turned on,
+//! each listener is sent a `SPATIAL_AUDIO` message after a tick that moved
+//! someone. It holds a gain and a pan for every speaker whose values changed
+//! noticeably. Clients apply them to the speaker's `VOICE_DATA` frames or SFU
+//! track, and the voice relay applies the gains to mixed voice.
+//!
+//! Gain falls off with distance along the room's curve. Speakers standing in
+//! one of the room's megaphone zones are heard across the room. Pan follows
+//! the direction of the speaker from the way the listener faces.
+
+use graphwiz_protocol::SpeakerSpatial;
+use reticulum_core::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Smallest gain change sent to a listener
+const GAIN_STEP: f32 = 0.02;
+
+/// Smallest pan change sent to a listener
+const PAN_STEP: f32 = 0.05;
+
+/// Speakers closer than this are heard from the middle
+const MIN_PAN_DISTANCE: f32 = 0.1;
+
+/// How gain falls off with distance, after the distance models of Web Audio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Falloff {
+    /// Full gain up to `max_distance`
+    None,
+    /// Falls in a straight line from `ref_distance` to `max_distance`
+    Linear,
+    /// Halves as the distance beyond `ref_distance` doubles, at `rolloff` 1
+    #[default]
+    Inverse,
+    /// Falls as a power of the distance
+    Exponential,
+}
+
+/// Area whose speakers are heard across the room
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MegaphoneZone {
+    pub center: [f32; 3],
+    pub radius: f32,
+    /// Gain of the speaker anywhere in the room
+    #[serde(default = "full_gain")]
+    pub gain: f32,
+}
+
+fn full_gain() -> f32 {
+    1.0
+}
+
+/// Spatial audio settings of a room
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpatialConfig {
+    pub falloff: Falloff,
+    /// Distance up to which speakers are heard at full gain
+    pub ref_distance: f32,
+    /// Distance beyond which speakers cannot be heard
+    pub max_distance: f32,
+    /// Steepness of the falloff
+    pub rolloff: f32,
+    pub megaphones: Vec<MegaphoneZone>,
+}
+
+impl Default for SpatialConfig {
+    fn default() -> Self {
+        Self {
+            falloff: Falloff::default(),
+            ref_distance: 1.0,
+            max_distance: 30.0,
+            rolloff: 1.0,
+            megaphones: Vec::new(),
+        }
+    }
+}
+
+impl SpatialConfig {
+    /// Reject settings no curve can be drawn from
+    pub fn validate(&self) -> Result<()> {
+        if !(self.ref_distance > 0.0 && self.ref_distance.is_finite()) {
+            return Err(Error::validation("ref_distance must be positive"));
+        }
+        if !(self.max_distance > self.ref_distance && self.max_distance.is_finite()) {
+            return Err(Error::validation("max_distance must be greater than ref_distance"));
+        }
+        if !(self.rolloff >= 0.0 && self.rolloff.is_finite()) {
+            return Err(Error::validation("rolloff must not be negative"));
+        }
+        for zone in &self.megaphones {
+            if !(zone.radius > 0.0 && zone.center.iter().all(|c| c.is_finite())) {
+                return Err(Error::validation("Megaphone zones need a center and a positive radius"));
+            }
+            if !(0.0..=1.0).contains(&zone.gain) {
+                return Err(Error::validation("Megaphone gain must be between 0 and 1"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Gain of a speaker `distance` away
+    pub fn falloff_gain(&self, distance: f32) -> f32 {
+        if distance > self.max_distance {
+            return 0.0;
+        }
+        let distance = distance.max(self.ref_distance);
+        let gain = match self.falloff {
+            Falloff::None => 1.0,
+            Falloff::Linear => {
+                1.0 - self.rolloff * (distance - self.ref_distance) / (self.max_distance - self.ref_distance)
+            }
+            Falloff::Inverse => {
+                self.ref_distance / (self.ref_distance + self.rolloff * (distance - self.ref_distance))
+            }
+            Falloff::Exponential => (distance / self.ref_distance).powf(-self.rolloff),
+        };
+        gain.clamp(0.0, 1.0)
+    }
+
+    /// Gain of a speaker at `speaker` heard from `listener`
+    pub fn gain(&self, listener: [f32; 3], speaker: [f32; 3]) -> f32 {
+        let megaphone = self
+            .megaphones
+            .iter()
+            .filter(|zone| distance(zone.center, speaker) <= zone.radius)
+            .map(|zone| zone.gain)
+            .fold(0.0, f32::max);
+        self.falloff_gain(distance(listener, speaker)).max(megaphone)
+    }
+}
+
+/// Where a connection's avatar is and which way it faces
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pose {
+    pub position: [f32; 3],
+    /// Rotation quaternion `[x, y, z, w]`; avatars face -Z when it is unknown
+    pub rotation: Option<[f32; 4]>,
+}
+
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a.iter().zip(b.iter()).map(|(a, b)| (a - b) * (a - b)).sum::<f32>().sqrt()
+}
+
+/// Rotate `v` by the inverse of the unit quaternion `q`
+fn unrotate(v: [f32; 3], q: [f32; 4]) -> [f32; 3] {
+    let (x, y, z, w) = (-q[0], -q[1], -q[2], q[3]);
+    let cross = |a: [f32; 3], b: [f32; 3]| {
+        [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+    };
+    let t = cross([x, y, z], v).map(|c| 2.0 * c);
+    let u = cross([x, y, z], t);
+    [v[0] + w * t[0] + u[0], v[1] + w * t[1] + u[1], v[2] + w * t[2] + u[2]]
+}
+
+/// Pan of a speaker at `speaker` for `listener`: -1 fully left, 1 fully right
+pub fn pan(listener: &Pose, speaker: [f32; 3]) -> f32 {
+    let offset = [
+        speaker[0] - listener.position[0],
+        speaker[1] - listener.position[1],
+        speaker[2] - listener.position[2],
+    ];
+    let local = match listener.rotation {
+        Some(rotation) => unrotate(offset, rotation),
+        None => offset,
+    };
+    // Right is +X and ahead is -Z, so only the horizontal plane counts
+    let horizontal = (local[0] * local[0] + local[2] * local[2]).sqrt();
+    if horizontal < MIN_PAN_DISTANCE {
+        return 0.0;
+    }
+    (local[0] / horizontal).clamp(-1.0, 1.0)
+}
+
+/// Room settings, avatar poses and what each listener was last sent
+#[derive(Default)]
+pub struct SpatialState {
+    configs: HashMap<String, SpatialConfig>, // room_id -> settings; rooms without are not spatial
+    poses: HashMap<String, Pose>, // conn_id -> last reported pose
+    sent: HashMap<String, HashMap<String, (f32, f32)>>, // listener conn_id -> speaker client_id -> (gain, pan)
+}
+
+impl SpatialState {
+    pub fn config(&self, room_id: &str) -> Option<&SpatialConfig> {
+        self.configs.get(room_id)
+    }
+
+    /// Turn spatial audio on for a room, or change its settings
+    pub fn set_config(&mut self, room_id: &str, config: SpatialConfig) {
+        self.configs.insert(room_id.to_string(), config);
+    }
+
+    /// Turn spatial audio off for a room
+    pub fn remove_config(&mut self, room_id: &str) -> Option<SpatialConfig> {
+        self.configs.remove(room_id)
+    }
+
+    pub fn update_pose(&mut self, conn_id: &str, position: [f32; 3], rotation: Option<[f32; 4]>) {
+        self.poses.insert(conn_id.to_string(), Pose { position, rotation });
+    }
+
+    /// Send a listener every speaker again with the next update
+    pub fn reset(&mut self, conn_id: &str) {
+        self.sent.remove(conn_id);
+    }
+
+    /// Forget a connection that left
+    pub fn remove(&mut self, conn_id: &str) {
+        self.poses.remove(conn_id);
+        self.sent.remove(conn_id);
+    }
+
+    /// Gains last sent to a listener, by speaker client id
+    pub fn gains(&self, conn_id: &str) -> HashMap<String, f32> {
+        self.sent
+            .get(conn_id)
+            .map(|sent| sent.iter().map(|(client_id, (gain, _))| (client_id.clone(), *gain)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Speakers whose gain or pan changed for `listener` since it was last
+    /// told, out of `speakers`: the connections of the room with their client
+    /// ids. Empty when the room is not spatial or the listener has not
+    /// said where it is.
+    pub fn changes(&mut self, room_id: &str, listener: &str, speakers: &[(String, String)]) -> Vec<SpeakerSpatial> {
+        let (Some(config), Some(pose)) = (self.configs.get(room_id), self.poses.get(listener)) else {
+            return Vec::new();
+        };
+
+        let sent = self.sent.entry(listener.to_string()).or_default();
+        sent.retain(|client_id, _| speakers.iter().any(|(_, speaker)| speaker == client_id));
+
+        let mut changes = Vec::new();
+        for (conn_id, client_id) in speakers {
+            if conn_id == listener {
+                continue;
+            }
+            let Some(speaker) = self.poses.get(conn_id) else {
+                continue;
+            };
+            let gain = config.gain(pose.position, speaker.position);
+            let pan = pan(pose, speaker.position);
+            let changed = match sent.get(client_id) {
+                Some((sent_gain, sent_pan)) => {
+                    (gain - sent_gain).abs() >= GAIN_STEP
+                        || (pan - sent_pan).abs() >= PAN_STEP
+                        // Silence and full gain are always passed on exactly
+                        || (gain != *sent_gain && (gain == 0.0 || gain == 1.0))
+                }
+                None => true,
+            };
+            if changed {
+                sent.insert(client_id.clone(), (gain, pan));
+                changes.push(SpeakerSpatial {
+                    client_id: client_id.clone(),
+                    gain,
+                    pan,
+                });
+            }
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn speakers(ids: &[&str]) -> Vec<(String, String)> {
+        ids.iter().map(|id| (format!("conn-{}", id), id.to_string())).collect()
+    }
+
+    #[test]
+    fn test_falloff_curves() {
+        let mut config = SpatialConfig::default();
+        assert_eq!(config.falloff_gain(0.5), 1.0);
+        assert!((config.falloff_gain(2.0) - 0.5).abs() < 1e-6);
+        assert_eq!(config.falloff_gain(31.0), 0.0);
+
+        config.falloff = Falloff::Linear;
+        config.ref_distance = 10.0;
+        assert!((config.falloff_gain(20.0) - 0.5).abs() < 1e-6);
+
+        config.falloff = Falloff::None;
+        assert_eq!(config.falloff_gain(29.0), 1.0);
+
+        config.max_distance = config.ref_distance;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_megaphone_zone_carries_across_the_room() {
+        let config = SpatialConfig {
+            megaphones: vec![MegaphoneZone {
+                center: [0.0, 0.0, 0.0],
+                radius: 2.0,
+                gain: 0.8,
+            }],
+            ..Default::default()
+        };
+        assert_eq!(config.gain([100.0, 0.0, 0.0], [1.0, 0.0, 0.0]), 0.8);
+        assert_eq!(config.gain([100.0, 0.0, 0.0], [5.0, 0.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn test_pan_follows_facing() {
+        let facing_forward = Pose {
+            position: [0.0, 0.0, 0.0],
+            rotation: None,
+        };
+        assert!((pan(&facing_forward, [3.0, 0.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!((pan(&facing_forward, [0.0, 0.0, -3.0])).abs() < 1e-6);
+
+        // Turned 90 degrees left, a speaker straight ahead of the room is on the right
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        let turned_left = Pose {
+            position: [0.0, 0.0, 0.0],
+            rotation: Some([0.0, half, 0.0, half]),
+        };
+        assert!((pan(&turned_left, [0.0, 0.0, -3.0]) - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_only_changes_are_sent() {
+        let mut state = SpatialState::default();
+        state.set_config("room", SpatialConfig::default());
+        state.update_pose("conn-alice", [0.0, 0.0, 0.0], None);
+        state.update_pose("conn-bob", [2.0, 0.0, 0.0], None);
+        let others = speakers(&["alice", "bob"]);
+
+        let changes = state.changes("room", "conn-alice", &others);
+        assert_eq!(changes.len(), 1);
+        assert!((changes[0].gain - 0.5).abs() < 1e-6);
+        assert!((changes[0].pan - 1.0).abs() < 1e-6);
+
+        // A step too small to hear is not sent
+        state.update_pose("conn-bob", [2.01, 0.0, 0.0], None);
+        assert!(state.changes("room", "conn-alice", &others).is_empty());
+
+        state.update_pose("conn-bob", [40.0, 0.0, 0.0], None);
+        let changes = state.changes("room", "conn-alice", &others);
+        assert_eq!(changes[0].gain, 0.0);
+        assert_eq!(state.gains("conn-alice").get("bob"), Some(&0.0));
+
+        assert!(state.changes("other-room", "conn-alice", &others).is_empty());
+    }
+}
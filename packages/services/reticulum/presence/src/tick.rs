@@ -0,0 +1,115 @@
+//! Fixed-rate tick aggregation of position updates
+//!
+//! Position updates are not relayed as they arrive. Each room keeps the latest
+//! transform per entity until its next tick, when every client gets a single
+//! `POSITION_BATCH` frame holding the updates it is interested in. Updates
+//! superseded within a tick are dropped, so bandwidth follows the tick rate
+//! rather than the rate at which clients happen to send.
+
+use graphwiz_protocol::PositionUpdate;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Tick rate for rooms without their own setting
+pub const DEFAULT_TICK_RATE_HZ: u32 = 20;
+
+/// Upper bound for configurable tick rates
+pub const MAX_TICK_RATE_HZ: u32 = 60;
+
+/// Clamp a requested tick rate to the supported range
+pub fn clamp_tick_rate(rate_hz: u32) -> u32 {
+    rate_hz.clamp(1, MAX_TICK_RATE_HZ)
+}
+
+/// Interval between ticks at `rate_hz`
+pub fn tick_interval(rate_hz: u32) -> Duration {
+    Duration::from_secs_f64(1.0 / clamp_tick_rate(rate_hz) as f64)
+}
+
+/// Service-wide tick rate from `PRESENCE_TICK_RATE_HZ`, or the default
+pub fn default_tick_rate() -> u32 {
+    std::env::var("PRESENCE_TICK_RATE_HZ")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .map(clamp_tick_rate)
+        .unwrap_or(DEFAULT_TICK_RATE_HZ)
+}
+
+/// Position updates collected for a room since its last tick
+#[derive(Default)]
+pub struct RoomFrame {
+    updates: HashMap<String, (String, PositionUpdate)>, // entity_id -> (sender conn_id, latest update)
+    tick: i64,
+    superseded: u64,
+}
+
+impl RoomFrame {
+    /// Keep `update` as the latest transform for its entity
+    pub fn push(&mut self, sender_id: &str, update: PositionUpdate) {
+        let entity_id = update.entity_id.clone();
+        if self
+            .updates
+            .insert(entity_id, (sender_id.to_string(), update))
+            .is_some()
+        {
+            self.superseded += 1;
+        }
+    }
+
+    /// Advance to the next tick, returning its number and the collected
+    /// updates with their senders
+    pub fn advance(&mut self) -> (i64, Vec<(String, PositionUpdate)>) {
+        self.tick += 1;
+        (self.tick, self.updates.drain().map(|(_, entry)| entry).collect())
+    }
+
+    /// Updates dropped because a newer one for the same entity arrived first
+    pub fn superseded(&self) -> u64 {
+        self.superseded
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.updates.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(entity_id: &str, x: f32) -> PositionUpdate {
+        PositionUpdate {
+            entity_id: entity_id.to_string(),
+            position: Some(graphwiz_protocol::Vector3 { x, y: 0.0, z: 0.0 }),
+            rotation: None,
+            sequence_number: 0,
+        }
+    }
+
+    #[test]
+    fn test_frame_keeps_latest_update_per_entity() {
+        let mut frame = RoomFrame::default();
+        frame.push("conn-a", update("avatar-a", 1.0));
+        frame.push("conn-a", update("avatar-a", 2.0));
+        frame.push("conn-b", update("avatar-b", 5.0));
+
+        assert_eq!(frame.superseded(), 1);
+
+        let (tick, mut updates) = frame.advance();
+        updates.sort_by(|a, b| a.1.entity_id.cmp(&b.1.entity_id));
+
+        assert_eq!(tick, 1);
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[0].0, "conn-a");
+        assert_eq!(updates[0].1.position.as_ref().unwrap().x, 2.0);
+        assert!(frame.is_empty());
+        assert_eq!(frame.advance().0, 2);
+    }
+
+    #[test]
+    fn test_tick_rate_bounds() {
+        assert_eq!(tick_interval(20), Duration::from_millis(50));
+        assert_eq!(tick_interval(0), Duration::from_secs(1));
+        assert_eq!(clamp_tick_rate(1_000), MAX_TICK_RATE_HZ);
+    }
+}
@@ -0,0 +1,714 @@
+//! User presence across rooms
+//!
+//! Tracks whether signed-in users are online, away or busy and which room they
+//! are in, independent of the room they are watched from. A user is online
+//! while any of their connections is active and goes away once every
+//! connection has been idle for the idle timeout; any message from the client
+//! counts as activity. Clients may set themselves away or busy, which holds
+//! until they set themselves online again or disconnect.
+//!
+//! Signed-in connections subscribe to a list of users and are sent each user's
+//! status right away and again whenever it changes. A user's room is only
+//! shown to themselves and to watchers in the same room. Every instance
+//! announces the users connected to it on the cluster transport, so
+//! subscribers see users connected anywhere. A user connected to several
+//! instances shows the most available status among them.
+
+use crate::protobuf::{Flow, MessageContext, MessageHandler, Route};
+use crate::redis::PubSubTransport;
+use crate::session::ClientSession;
+use crate::websocket::{WebSocketManager, WsMessage};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use graphwiz_protocol::generated::graphwiz::core::message::Payload;
+use graphwiz_protocol::generated::graphwiz::core::{PresenceEventType, PresenceStatus};
+use graphwiz_protocol::{Message, MessageBuilder, MessageParser};
+use reticulum_core::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+/// Channel on which instances announce the presence of their users
+pub const USER_PRESENCE_CHANNEL: &str = "graphwiz:presence:users";
+
+/// Inactivity after which a user is shown as away
+pub const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 300;
+
+/// Users a single connection may watch
+pub const MAX_SUBSCRIPTIONS: usize = 500;
+
+/// How often idle users are looked for
+const SWEEP_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How often every local user is announced again; other instances forget a
+/// user after three missed announcements, so a crashed instance's users go
+/// offline
+const ANNOUNCE_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UserStatus {
+    Offline,
+    Online,
+    Away,
+    Busy,
+}
+
+impl UserStatus {
+    /// Availability order used to combine connections; busy is an explicit
+    /// choice and wins over everything
+    fn rank(&self) -> u8 {
+        match self {
+            UserStatus::Offline => 0,
+            UserStatus::Away => 1,
+            UserStatus::Online => 2,
+            UserStatus::Busy => 3,
+        }
+    }
+
+    pub fn to_proto(self) -> PresenceStatus {
+        match self {
+            UserStatus::Offline => PresenceStatus::Offline,
+            UserStatus::Online => PresenceStatus::Online,
+            UserStatus::Away => PresenceStatus::Away,
+            UserStatus::Busy => PresenceStatus::Busy,
+        }
+    }
+
+    pub fn from_proto(status: PresenceStatus) -> Self {
+        match status {
+            PresenceStatus::Offline => UserStatus::Offline,
+            PresenceStatus::Online => UserStatus::Online,
+            PresenceStatus::Away => UserStatus::Away,
+            PresenceStatus::Busy => UserStatus::Busy,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct UserPresence {
+    pub user_id: String,
+    pub status: UserStatus,
+    /// Room of the user's most recently active connection
+    pub room_id: Option<String>,
+    /// Last activity in milliseconds since the epoch; `None` when offline
+    pub last_active: Option<i64>,
+}
+
+impl UserPresence {
+    pub fn offline(user_id: &str) -> Self {
+        Self {
+            user_id: user_id.to_string(),
+            status: UserStatus::Offline,
+            room_id: None,
+            last_active: None,
+        }
+    }
+
+    /// What the user `viewer` in room `viewer_room_id` may see: the room is
+    /// left out unless the viewer is the user or is in the same room
+    pub fn seen_by(&self, viewer: &str, viewer_room_id: Option<&str>) -> UserPresence {
+        let same_room = self.room_id.is_some() && self.room_id.as_deref() == viewer_room_id;
+        if viewer == self.user_id || same_room {
+            return self.clone();
+        }
+        UserPresence {
+            room_id: None,
+            ..self.clone()
+        }
+    }
+
+    /// Whether subscribers would see a difference; activity alone is not one
+    fn same_state(&self, other: &UserPresence) -> bool {
+        self.status == other.status && self.room_id == other.room_id
+    }
+
+    /// Status event sent to subscribers
+    pub fn to_message(&self) -> Message {
+        MessageBuilder::presence_status(
+            self.user_id.clone(),
+            self.status.to_proto(),
+            self.room_id.clone().unwrap_or_default(),
+        )
+    }
+}
+
+/// A user's presence on one instance, as published on [`USER_PRESENCE_CHANNEL`]
+#[derive(Debug, Serialize, Deserialize)]
+struct PresenceAnnouncement {
+    instance_id: String,
+    presence: UserPresence,
+}
+
+struct RemotePresence {
+    presence: UserPresence,
+    received_at: DateTime<Utc>,
+}
+
+/// Presence of signed-in users and the connections watching them
+#[derive(Clone)]
+pub struct PresenceDirectory {
+    instance_id: String,
+    transport: Arc<dyn PubSubTransport>,
+    sessions: Arc<RwLock<HashMap<String, ClientSession>>>, // conn_id -> session of a signed-in connection
+    manual: Arc<RwLock<HashMap<String, UserStatus>>>, // user_id -> status the user chose
+    remote: Arc<RwLock<HashMap<String, HashMap<String, RemotePresence>>>>, // user_id -> instance_id -> presence
+    subscriptions: Arc<RwLock<HashMap<String, HashSet<String>>>>, // conn_id -> watched user_ids
+    announced: Arc<RwLock<HashMap<String, UserPresence>>>, // user_id -> presence last published
+    notified: Arc<RwLock<HashMap<String, UserPresence>>>, // user_id -> presence last sent to subscribers
+    idle_timeout: chrono::Duration,
+}
+
+impl PresenceDirectory {
+    /// Directory sharing presence with the instances on `transport`
+    pub fn new(instance_id: &str, transport: Arc<dyn PubSubTransport>) -> Self {
+        Self {
+            instance_id: instance_id.to_string(),
+            transport,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            manual: Arc::new(RwLock::new(HashMap::new())),
+            remote: Arc::new(RwLock::new(HashMap::new())),
+            subscriptions: Arc::new(RwLock::new(HashMap::new())),
+            announced: Arc::new(RwLock::new(HashMap::new())),
+            notified: Arc::new(RwLock::new(HashMap::new())),
+            idle_timeout: chrono::Duration::seconds(DEFAULT_IDLE_TIMEOUT_SECS as i64),
+        }
+    }
+
+    /// Show users as away after `timeout` without activity
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = chrono::Duration::from_std(timeout).unwrap_or(self.idle_timeout);
+        self
+    }
+
+    /// Track `ws_manager`'s connections, handle status and subscription
+    /// messages and follow the other instances' announcements
+    pub async fn start(&self, ws_manager: &WebSocketManager) -> Result<()> {
+        let mut announcements = self.transport.psubscribe(USER_PRESENCE_CHANNEL).await?;
+
+        ws_manager.register_global_handler(Arc::new(self.clone())).await;
+        ws_manager.attach_user_presence(self.clone()).await;
+
+        let directory = self.clone();
+        let manager = ws_manager.clone();
+        tokio::spawn(async move {
+            while let Some((_, payload)) = announcements.recv().await {
+                match serde_json::from_slice::<PresenceAnnouncement>(&payload) {
+                    Ok(announcement) => directory.receive(&manager, announcement).await,
+                    Err(e) => log::warn!("Invalid presence announcement: {}", e),
+                }
+            }
+        });
+
+        let directory = self.clone();
+        let manager = ws_manager.clone();
+        tokio::spawn(async move {
+            let sweeps_per_announce = (ANNOUNCE_INTERVAL.as_secs() / SWEEP_INTERVAL.as_secs()).max(1);
+            let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+            let mut sweeps: u64 = 0;
+            loop {
+                interval.tick().await;
+                sweeps += 1;
+                directory.sweep(&manager, sweeps.is_multiple_of(sweeps_per_announce)).await;
+            }
+        });
+
+        log::info!("User presence is shared over {}", self.transport.name());
+        Ok(())
+    }
+
+    /// Start tracking a connection; connections without a user are ignored
+    pub async fn connected(&self, ws_manager: &WebSocketManager, conn_id: &str) {
+        let Some(conn) = ws_manager.get_connection_info(conn_id).await else {
+            return;
+        };
+        let Some(user_id) = conn.user_id.clone() else {
+            return;
+        };
+
+        self.sessions.write().await.insert(
+            conn_id.to_string(),
+            ClientSession {
+                session_id: conn_id.to_string(),
+                client_id: conn.client_id.clone().unwrap_or_else(|| conn_id.to_string()),
+                user_id: user_id.clone(),
+                room_id: conn.room_id.clone(),
+                connected_at: conn.connected_at,
+                last_heartbeat: Utc::now(),
+                is_muted: false,
+            },
+        );
+        self.update(ws_manager, &user_id).await;
+    }
+
+    /// Stop tracking a closed connection and drop its subscriptions
+    pub async fn disconnected(&self, ws_manager: &WebSocketManager, conn_id: &str) {
+        self.subscriptions.write().await.remove(conn_id);
+        let Some(session) = self.sessions.write().await.remove(conn_id) else {
+            return;
+        };
+
+        // A chosen status lasts as long as the user stays connected
+        if !self.has_sessions(&session.user_id).await {
+            self.manual.write().await.remove(&session.user_id);
+        }
+        self.update(ws_manager, &session.user_id).await;
+    }
+
+    /// Record activity on a connection, bringing its user back from away
+    pub async fn touch(&self, ws_manager: &WebSocketManager, conn_id: &str) {
+        let now = Utc::now();
+        let user_id = {
+            let mut sessions = self.sessions.write().await;
+            let Some(session) = sessions.get_mut(conn_id) else {
+                return;
+            };
+            let was_idle = now - session.last_heartbeat >= self.idle_timeout;
+            session.last_heartbeat = now;
+            if !was_idle {
+                return;
+            }
+            session.user_id.clone()
+        };
+        self.update(ws_manager, &user_id).await;
+    }
+
+    /// Set the status a user chose; `Online` and `Offline` go back to the
+    /// status their activity gives
+    pub async fn set_status(&self, ws_manager: &WebSocketManager, user_id: &str, status: UserStatus) {
+        {
+            let mut manual = self.manual.write().await;
+            match status {
+                UserStatus::Away | UserStatus::Busy => manual.insert(user_id.to_string(), status),
+                UserStatus::Online | UserStatus::Offline => manual.remove(user_id),
+            };
+        }
+        self.update(ws_manager, user_id).await;
+    }
+
+    /// Watch `user_ids` from a signed-in connection, replacing what it watched
+    /// before, and send it their current status
+    pub async fn subscribe(&self, ws_manager: &WebSocketManager, conn_id: &str, user_ids: Vec<String>) {
+        let Some((viewer, viewer_room_id)) = viewer_of(ws_manager, conn_id).await else {
+            return;
+        };
+        let watched: HashSet<String> = user_ids
+            .into_iter()
+            .filter(|user_id| !user_id.is_empty())
+            .take(MAX_SUBSCRIPTIONS)
+            .collect();
+
+        let mut snapshot = Vec::with_capacity(watched.len());
+        for user_id in &watched {
+            snapshot.push(self.presence(user_id).await.seen_by(&viewer, viewer_room_id.as_deref()));
+        }
+        self.subscriptions.write().await.insert(conn_id.to_string(), watched);
+
+        for presence in snapshot {
+            if let Ok(bytes) = MessageParser::serialize(&presence.to_message()) {
+                let _ = ws_manager.send_to_connection(conn_id, WsMessage::Binary(bytes)).await;
+            }
+        }
+    }
+
+    /// Current presence of a user across every instance
+    pub async fn presence(&self, user_id: &str) -> UserPresence {
+        let mut best = self.local_presence(user_id).await;
+
+        if let Some(instances) = self.remote.read().await.get(user_id) {
+            for remote in instances.values() {
+                let better = match &best {
+                    Some(best) => remote.presence.status.rank() > best.status.rank(),
+                    None => true,
+                };
+                if better {
+                    best = Some(remote.presence.clone());
+                }
+            }
+        }
+
+        best.unwrap_or_else(|| UserPresence::offline(user_id))
+    }
+
+    /// Presence of a user on this instance; `None` when not connected here
+    async fn local_presence(&self, user_id: &str) -> Option<UserPresence> {
+        let latest = {
+            let sessions = self.sessions.read().await;
+            sessions
+                .values()
+                .filter(|session| session.user_id == user_id)
+                .max_by_key(|session| session.last_heartbeat)
+                .map(|session| (session.last_heartbeat, session.room_id.clone()))
+        };
+        let (last_active, room_id) = latest?;
+
+        let status = match self.manual.read().await.get(user_id) {
+            Some(status) => *status,
+            None if Utc::now() - last_active >= self.idle_timeout => UserStatus::Away,
+            None => UserStatus::Online,
+        };
+
+        Some(UserPresence {
+            user_id: user_id.to_string(),
+            status,
+            room_id,
+            last_active: Some(last_active.timestamp_millis()),
+        })
+    }
+
+    async fn has_sessions(&self, user_id: &str) -> bool {
+        self.sessions
+            .read()
+            .await
+            .values()
+            .any(|session| session.user_id == user_id)
+    }
+
+    /// Publish a change to this instance's view of a user and tell subscribers
+    async fn update(&self, ws_manager: &WebSocketManager, user_id: &str) {
+        let local = self.local_presence(user_id).await;
+        let changed = {
+            let mut announced = self.announced.write().await;
+            match &local {
+                Some(presence) => announced
+                    .insert(user_id.to_string(), presence.clone())
+                    .is_none_or(|previous| !previous.same_state(presence)),
+                None => announced.remove(user_id).is_some(),
+            }
+        };
+        if changed {
+            self.announce(local.unwrap_or_else(|| UserPresence::offline(user_id))).await;
+        }
+
+        self.notify(ws_manager, user_id).await;
+    }
+
+    async fn announce(&self, presence: UserPresence) {
+        let announcement = PresenceAnnouncement {
+            instance_id: self.instance_id.clone(),
+            presence,
+        };
+        let payload = match serde_json::to_vec(&announcement) {
+            Ok(payload) => payload,
+            Err(e) => {
+                log::error!("Failed to encode presence of {}: {}", announcement.presence.user_id, e);
+                return;
+            }
+        };
+        if let Err(e) = self.transport.publish(USER_PRESENCE_CHANNEL, payload).await {
+            log::warn!("Failed to announce presence of {}: {}", announcement.presence.user_id, e);
+        }
+    }
+
+    /// Send a user's presence to the connections watching it if it changed
+    async fn notify(&self, ws_manager: &WebSocketManager, user_id: &str) {
+        let presence = self.presence(user_id).await;
+        {
+            let mut notified = self.notified.write().await;
+            let previous = if presence.status == UserStatus::Offline {
+                notified.remove(user_id)
+            } else {
+                notified.insert(user_id.to_string(), presence.clone())
+            };
+            let unchanged = match previous {
+                Some(previous) => previous.same_state(&presence),
+                None => presence.status == UserStatus::Offline,
+            };
+            if unchanged {
+                return;
+            }
+        }
+
+        let watchers: Vec<String> = self
+            .subscriptions
+            .read()
+            .await
+            .iter()
+            .filter(|(_, watched)| watched.contains(user_id))
+            .map(|(conn_id, _)| conn_id.clone())
+            .collect();
+        if watchers.is_empty() {
+            return;
+        }
+
+        for conn_id in watchers {
+            let Some((viewer, viewer_room_id)) = viewer_of(ws_manager, &conn_id).await else {
+                continue;
+            };
+            let seen = presence.seen_by(&viewer, viewer_room_id.as_deref());
+            match MessageParser::serialize(&seen.to_message()) {
+                Ok(bytes) => {
+                    let _ = ws_manager.send_to_connection(&conn_id, WsMessage::Binary(bytes)).await;
+                }
+                Err(e) => log::error!("Failed to encode presence of {}: {}", user_id, e),
+            }
+        }
+    }
+
+    /// Apply another instance's announcement
+    async fn receive(&self, ws_manager: &WebSocketManager, announcement: PresenceAnnouncement) {
+        if announcement.instance_id == self.instance_id {
+            return;
+        }
+
+        let user_id = announcement.presence.user_id.clone();
+        {
+            let mut remote = self.remote.write().await;
+            if announcement.presence.status == UserStatus::Offline {
+                if let Some(instances) = remote.get_mut(&user_id) {
+                    instances.remove(&announcement.instance_id);
+                    if instances.is_empty() {
+                        remote.remove(&user_id);
+                    }
+                }
+            } else {
+                remote.entry(user_id.clone()).or_default().insert(
+                    announcement.instance_id,
+                    RemotePresence {
+                        presence: announcement.presence,
+                        received_at: Utc::now(),
+                    },
+                );
+            }
+        }
+
+        self.notify(ws_manager, &user_id).await;
+    }
+
+    /// Move idle users to away, forget instances that stopped announcing and,
+    /// with `announce_all`, announce every local user again
+    pub async fn sweep(&self, ws_manager: &WebSocketManager, announce_all: bool) {
+        let cutoff = Utc::now() - chrono::Duration::from_std(ANNOUNCE_INTERVAL * 3).unwrap_or_default();
+        let mut users: HashSet<String> = HashSet::new();
+        {
+            let mut remote = self.remote.write().await;
+            for (user_id, instances) in remote.iter_mut() {
+                let before = instances.len();
+                instances.retain(|_, presence| presence.received_at >= cutoff);
+                if instances.len() != before {
+                    users.insert(user_id.clone());
+                }
+            }
+            remote.retain(|_, instances| !instances.is_empty());
+        }
+        users.extend(self.sessions.read().await.values().map(|session| session.user_id.clone()));
+
+        for user_id in &users {
+            self.update(ws_manager, user_id).await;
+        }
+
+        if announce_all {
+            let announced: Vec<UserPresence> = self.announced.read().await.values().cloned().collect();
+            for presence in announced {
+                self.announce(presence).await;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl MessageHandler for PresenceDirectory {
+    async fn handle(&self, message: &mut Message, ctx: &mut MessageContext<'_>) -> Result<Flow> {
+        // Every client message is activity
+        self.touch(ctx.ws_manager, ctx.conn_id).await;
+
+        match message.payload.as_ref() {
+            Some(Payload::PresenceSubscription(subscription)) => {
+                if ctx.user_id().is_none() {
+                    ctx.send(
+                        Route::Sender,
+                        MessageBuilder::error_frame("PRESENCE_REJECTED", "Sign in to watch users".to_string(), 0),
+                    );
+                    return Ok(Flow::Drop);
+                }
+                self.subscribe(ctx.ws_manager, ctx.conn_id, subscription.user_ids.clone())
+                    .await;
+                Ok(Flow::Drop)
+            }
+            Some(Payload::PresenceEvent(event)) if event.event_type == PresenceEventType::Status as i32 => {
+                let Some(user_id) = ctx.user_id().map(str::to_string) else {
+                    ctx.send(
+                        Route::Sender,
+                        MessageBuilder::error_frame("PRESENCE_REJECTED", "Sign in to set a status".to_string(), 0),
+                    );
+                    return Ok(Flow::Drop);
+                };
+                let status = event
+                    .data
+                    .as_ref()
+                    .and_then(|data| PresenceStatus::try_from(data.status).ok())
+                    .unwrap_or(PresenceStatus::Online);
+                self.set_status(ctx.ws_manager, &user_id, UserStatus::from_proto(status))
+                    .await;
+                Ok(Flow::Drop)
+            }
+            _ => Ok(Flow::Continue),
+        }
+    }
+}
+
+/// Verified user and room of a watching connection; `None` for connections
+/// without a verified user, which may not watch anyone
+async fn viewer_of(ws_manager: &WebSocketManager, conn_id: &str) -> Option<(String, Option<String>)> {
+    let conn = ws_manager.get_connection_info(conn_id).await?;
+    if !conn.authenticated {
+        return None;
+    }
+    Some((conn.user_id?, conn.room_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protobuf::route_message;
+    use crate::queue::OutboundReceiver;
+    use crate::redis::MemoryTransport;
+    use tokio::time::timeout;
+
+    async fn next_status(rx: &mut OutboundReceiver<WsMessage>) -> Option<(String, PresenceStatus, String)> {
+        loop {
+            match timeout(Duration::from_millis(100), rx.queue.recv()).await {
+                Ok(Some(WsMessage::Binary(bytes))) => match MessageParser::parse(&bytes).ok()?.payload {
+                    Some(Payload::PresenceEvent(event)) if event.event_type == PresenceEventType::Status as i32 => {
+                        let data = event.data?;
+                        let status = PresenceStatus::try_from(data.status).ok()?;
+                        return Some((data.user_id, status, data.room_id));
+                    }
+                    // Skip hellos and room presence
+                    _ => continue,
+                },
+                _ => return None,
+            }
+        }
+    }
+
+    async fn send(ws_manager: &WebSocketManager, conn_id: &str, message: Message) {
+        let handlers = ws_manager.handlers().await;
+        route_message(&handlers, ws_manager, "lobby", conn_id, &MessageParser::serialize(&message).unwrap())
+            .await
+            .unwrap();
+    }
+
+    async fn join(
+        ws_manager: &WebSocketManager,
+        directory: &PresenceDirectory,
+        conn_id: &str,
+        room_id: &str,
+        user_id: Option<&str>,
+    ) -> OutboundReceiver<WsMessage> {
+        let room_id = Some(room_id.to_string());
+        let client_id = Some(conn_id.to_string());
+        let rx = match user_id {
+            Some(user_id) => {
+                ws_manager
+                    .add_authenticated_connection(conn_id.to_string(), room_id, user_id.to_string(), client_id)
+                    .await
+            }
+            None => ws_manager.add_connection(conn_id.to_string(), room_id, None, client_id).await,
+        };
+        directory.connected(ws_manager, conn_id).await;
+        rx
+    }
+
+    fn set_status(status: PresenceStatus) -> Message {
+        MessageBuilder::presence_status(String::new(), status, String::new())
+    }
+
+    #[tokio::test]
+    async fn test_subscribers_follow_status_changes() {
+        let ws_manager = WebSocketManager::new();
+        let directory = PresenceDirectory::new("instance-1", Arc::new(MemoryTransport::new()));
+        directory.start(&ws_manager).await.unwrap();
+
+        let mut watcher = join(&ws_manager, &directory, "c1", "arena", Some("bob")).await;
+        send(&ws_manager, "c1", MessageBuilder::presence_subscribe(vec!["alice".to_string()])).await;
+        assert_eq!(
+            next_status(&mut watcher).await.unwrap(),
+            ("alice".to_string(), PresenceStatus::Offline, String::new())
+        );
+
+        let _alice = join(&ws_manager, &directory, "c2", "arena", Some("alice")).await;
+        assert_eq!(
+            next_status(&mut watcher).await.unwrap(),
+            ("alice".to_string(), PresenceStatus::Online, "arena".to_string())
+        );
+
+        send(&ws_manager, "c2", set_status(PresenceStatus::Busy)).await;
+        assert_eq!(next_status(&mut watcher).await.unwrap().1, PresenceStatus::Busy);
+
+        directory.disconnected(&ws_manager, "c2").await;
+        assert_eq!(next_status(&mut watcher).await.unwrap().1, PresenceStatus::Offline);
+    }
+
+    #[tokio::test]
+    async fn test_idle_users_turn_away_until_active() {
+        let ws_manager = WebSocketManager::new();
+        let directory = PresenceDirectory::new("instance-1", Arc::new(MemoryTransport::new()))
+            .with_idle_timeout(Duration::from_millis(50));
+        directory.start(&ws_manager).await.unwrap();
+
+        let mut watcher = join(&ws_manager, &directory, "c1", "lobby", Some("bob")).await;
+        let _alice = join(&ws_manager, &directory, "c2", "lobby", Some("alice")).await;
+        directory.subscribe(&ws_manager, "c1", vec!["alice".to_string()]).await;
+        assert_eq!(next_status(&mut watcher).await.unwrap().1, PresenceStatus::Online);
+
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        directory.sweep(&ws_manager, false).await;
+        assert_eq!(next_status(&mut watcher).await.unwrap().1, PresenceStatus::Away);
+
+        send(&ws_manager, "c2", MessageBuilder::presence_subscribe(Vec::new())).await;
+        assert_eq!(next_status(&mut watcher).await.unwrap().1, PresenceStatus::Online);
+    }
+
+    #[tokio::test]
+    async fn test_presence_is_shared_between_instances() {
+        let transport: Arc<dyn PubSubTransport> = Arc::new(MemoryTransport::new());
+        let first_manager = WebSocketManager::new();
+        let first = PresenceDirectory::new("instance-1", transport.clone());
+        first.start(&first_manager).await.unwrap();
+        let second_manager = WebSocketManager::new();
+        let second = PresenceDirectory::new("instance-2", transport);
+        second.start(&second_manager).await.unwrap();
+
+        let mut watcher = join(&second_manager, &second, "c1", "arena", Some("bob")).await;
+        second.subscribe(&second_manager, "c1", vec!["alice".to_string()]).await;
+        assert_eq!(next_status(&mut watcher).await.unwrap().1, PresenceStatus::Offline);
+
+        let _alice = join(&first_manager, &first, "c2", "arena", Some("alice")).await;
+        assert_eq!(
+            next_status(&mut watcher).await.unwrap(),
+            ("alice".to_string(), PresenceStatus::Online, "arena".to_string())
+        );
+        assert_eq!(second.presence("alice").await.status, UserStatus::Online);
+
+        first.disconnected(&first_manager, "c2").await;
+        assert_eq!(next_status(&mut watcher).await.unwrap().1, PresenceStatus::Offline);
+    }
+
+    #[tokio::test]
+    async fn test_only_signed_in_users_watch_and_rooms_stay_private() {
+        let ws_manager = WebSocketManager::new();
+        let directory = PresenceDirectory::new("instance-1", Arc::new(MemoryTransport::new()));
+        directory.start(&ws_manager).await.unwrap();
+        let _alice = join(&ws_manager, &directory, "c1", "arena", Some("alice")).await;
+
+        let mut anonymous = join(&ws_manager, &directory, "c2", "lobby", None).await;
+        send(&ws_manager, "c2", MessageBuilder::presence_subscribe(vec!["alice".to_string()])).await;
+        assert!(next_status(&mut anonymous).await.is_none());
+
+        // Watchers elsewhere see the status but not the room
+        let mut stranger = join(&ws_manager, &directory, "c3", "lobby", Some("bob")).await;
+        send(&ws_manager, "c3", MessageBuilder::presence_subscribe(vec!["alice".to_string()])).await;
+        assert_eq!(
+            next_status(&mut stranger).await.unwrap(),
+            ("alice".to_string(), PresenceStatus::Online, String::new())
+        );
+
+        let presence = directory.presence("alice").await;
+        assert_eq!(presence.seen_by("alice", None).room_id.as_deref(), Some("arena"));
+        assert_eq!(presence.seen_by("bob", Some("arena")).room_id.as_deref(), Some("arena"));
+        assert!(presence.seen_by("bob", None).room_id.is_none());
+    }
+}
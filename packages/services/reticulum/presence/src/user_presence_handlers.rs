@@ -0,0 +1,70 @@
+//! User presence handlers for presence service
+//!
+//! Snapshots of the status connected clients get over their subscriptions, for
+//! services and pages without a connection. Lookups take the same bearer token
+//! as moderation actions. Callers see other users' status but not their room,
+//! which only a subscribed connection in the same room is shown.
+
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use reticulum_core::Error;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::moderation::ModerationManager;
+use crate::user_presence::{PresenceDirectory, MAX_SUBSCRIPTIONS};
+
+#[derive(Debug, Deserialize)]
+pub struct UsersQuery {
+    /// Comma separated user ids
+    pub ids: String,
+}
+
+/// Current presence of a list of users
+pub async fn get_users_presence(
+    req: HttpRequest,
+    directory: web::Data<PresenceDirectory>,
+    moderation: web::Data<ModerationManager>,
+    query: web::Query<UsersQuery>,
+) -> HttpResponse {
+    let caller = match moderation.authenticate(&req) {
+        Ok(caller) => caller,
+        Err(e) => return e.error_response(),
+    };
+
+    let user_ids: Vec<&str> = query
+        .ids
+        .split(',')
+        .map(str::trim)
+        .filter(|user_id| !user_id.is_empty())
+        .collect();
+    if user_ids.len() > MAX_SUBSCRIPTIONS {
+        return Error::validation(format!("At most {} users may be looked up at once", MAX_SUBSCRIPTIONS))
+            .error_response();
+    }
+
+    let mut users = Vec::with_capacity(user_ids.len());
+    for user_id in user_ids {
+        users.push(directory.presence(user_id).await.seen_by(&caller.user_id, None));
+    }
+
+    HttpResponse::Ok().json(json!({
+        "total": users.len(),
+        "users": users
+    }))
+}
+
+/// Current presence of one user
+pub async fn get_user_presence(
+    req: HttpRequest,
+    directory: web::Data<PresenceDirectory>,
+    moderation: web::Data<ModerationManager>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let caller = match moderation.authenticate(&req) {
+        Ok(caller) => caller,
+        Err(e) => return e.error_response(),
+    };
+
+    let presence = directory.presence(&path.into_inner()).await;
+    HttpResponse::Ok().json(presence.seen_by(&caller.user_id, None))
+}
@@ -0,0 +1,659 @@
+//! Voice relay for rooms without the SFU
+//!
+//! Clients that cannot reach the SFU, for instance because WebRTC is
+//! blocked, send their microphone as `VOICE_DATA` frames over their room
+//! connection. The relay checks each frame, stamps it with the sender's
+//! client id and hands it to the room like any other broadcast, so listeners
+//! on every instance receive it as it is.
+//!
+//! Listeners on weak connections can ask for a mix instead by connecting
+//! with `voice=mixed`. Every 20 ms their instance decodes the frames of the
+//! loudest few speakers, sums them and sends each mixed listener a single
+//! frame, leaving out the listener's own voice. In rooms with spatial audio
+//! (see `spatial`) each voice is weighed by the gain the listener hears it at,
+//! and the loudest are picked after weighing.
+//!
+//! Frames of speakers muted by a moderator (see `moderation`) or muted in
+//! their session are dropped.
+//!
+//! G.711 (`PCMU`, `PCMA`) is mixed out of the box. Opus, which browsers
+//! send, needs libopus: build with the `opus` feature. Frames the mixer
+//! cannot decode reach mixed listeners as they are. Mixed frames are Opus
+//! when the feature is on and `PCMU` otherwise.
+
+use async_trait::async_trait;
+use graphwiz_protocol::generated::graphwiz::core::message::Payload;
+use graphwiz_protocol::{Message, MessageBuilder, MessageParser, VoiceCodec, VoiceData};
+use reticulum_core::Result;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use crate::protobuf::{Flow, MessageContext, MessageHandler};
+use crate::session::SessionManager;
+use crate::websocket::{WebSocketManager, WsMessage};
+
+/// Sample rate the mixer works at
+const MIX_SAMPLE_RATE: usize = 48_000;
+
+/// Length of a mixed frame
+const MIX_FRAME: Duration = Duration::from_millis(20);
+const MIX_FRAME_SAMPLES: usize = MIX_SAMPLE_RATE / 50;
+
+/// Audio kept per speaker to even out network jitter; older audio is dropped
+const MAX_BUFFERED_SAMPLES: usize = MIX_FRAME_SAMPLES * 5;
+
+/// Speakers whose voice goes into a mix
+pub const DEFAULT_MIXED_SPEAKERS: usize = 3;
+
+/// Share of a speaker's level kept from one mixed frame to the next, so a
+/// speaker pausing between words keeps their place among the loudest
+const LEVEL_DECAY: f32 = 0.9;
+
+/// Time after which a silent speaker's decoder is released
+const SPEAKER_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Largest frame relayed; Opus packets are at most 1275 bytes and 60 ms of
+/// G.711 is 480
+const MAX_FRAME_BYTES: usize = 1500;
+
+/// Sender of mixed frames
+pub const MIX_CLIENT_ID: &str = "mix";
+
+/// Codec of mixed frames
+#[cfg(feature = "opus")]
+const MIX_CODEC: VoiceCodec = VoiceCodec::Opus;
+#[cfg(not(feature = "opus"))]
+const MIX_CODEC: VoiceCodec = VoiceCodec::Pcmu;
+
+/// Turns frames of one codec into 48 kHz mono samples and back
+trait FrameCodec: Send {
+    fn decode(&mut self, frame: &[u8]) -> Option<Vec<i16>>;
+    fn encode(&mut self, samples: &[i16]) -> Option<Vec<u8>>;
+}
+
+/// Coder for `codec`, or `None` if this build cannot decode it
+fn frame_codec(codec: VoiceCodec) -> Option<Box<dyn FrameCodec>> {
+    match codec {
+        VoiceCodec::Pcmu => Some(Box::new(G711::MuLaw)),
+        VoiceCodec::Pcma => Some(Box::new(G711::ALaw)),
+        #[cfg(feature = "opus")]
+        VoiceCodec::Opus => opus::OpusCodec::new().map(|codec| Box::new(codec) as Box<dyn FrameCodec>),
+        #[cfg(not(feature = "opus"))]
+        VoiceCodec::Opus => None,
+    }
+}
+
+/// G.711 at 8 kHz
+#[derive(Debug, Clone, Copy)]
+enum G711 {
+    MuLaw,
+    ALaw,
+}
+
+/// 48 kHz samples per 8 kHz G.711 sample
+const G711_UPSAMPLING: usize = MIX_SAMPLE_RATE / 8_000;
+
+impl FrameCodec for G711 {
+    fn decode(&mut self, frame: &[u8]) -> Option<Vec<i16>> {
+        let samples: Vec<i16> = match self {
+            Self::MuLaw => frame.iter().map(|&byte| mulaw_decode(byte)).collect(),
+            Self::ALaw => frame.iter().map(|&byte| alaw_decode(byte)).collect(),
+        };
+        Some(upsample(&samples, G711_UPSAMPLING))
+    }
+
+    fn encode(&mut self, samples: &[i16]) -> Option<Vec<u8>> {
+        let samples = downsample(samples, G711_UPSAMPLING);
+        Some(match self {
+            Self::MuLaw => samples.into_iter().map(mulaw_encode).collect(),
+            Self::ALaw => samples.into_iter().map(alaw_encode).collect(),
+        })
+    }
+}
+
+const MULAW_BIAS: i32 = 0x84;
+const MULAW_CLIP: i32 = 32635;
+
+fn mulaw_encode(sample: i16) -> u8 {
+    let sample = sample as i32;
+    let sign = if sample < 0 { 0x80 } else { 0 };
+    let magnitude = sample.abs().min(MULAW_CLIP) + MULAW_BIAS;
+    let exponent = segment(magnitude);
+    let mantissa = (magnitude >> (exponent + 3)) & 0x0F;
+    !(sign | (exponent << 4) | mantissa) as u8
+}
+
+fn mulaw_decode(byte: u8) -> i16 {
+    let byte = !byte as i32;
+    let exponent = (byte >> 4) & 0x07;
+    let mantissa = byte & 0x0F;
+    let magnitude = (((mantissa << 3) + MULAW_BIAS) << exponent) - MULAW_BIAS;
+    (if byte & 0x80 != 0 { -magnitude } else { magnitude }) as i16
+}
+
+fn alaw_encode(sample: i16) -> u8 {
+    let sample = sample as i32;
+    let (sign, magnitude) = if sample >= 0 { (0x80, sample) } else { (0, -sample - 1) };
+    let compressed = if magnitude >= 256 {
+        let exponent = segment(magnitude);
+        (exponent << 4) | ((magnitude >> (exponent + 3)) & 0x0F)
+    } else {
+        magnitude >> 4
+    };
+    ((sign | compressed) ^ 0x55) as u8
+}
+
+fn alaw_decode(byte: u8) -> i16 {
+    let byte = (byte ^ 0x55) as i32;
+    let exponent = (byte >> 4) & 0x07;
+    let mantissa = byte & 0x0F;
+    let magnitude = match exponent {
+        0 => (mantissa << 4) + 8,
+        _ => ((mantissa << 4) + 0x108) << (exponent - 1),
+    };
+    (if byte & 0x80 != 0 { magnitude } else { -magnitude }) as i16
+}
+
+/// G.711 segment of a magnitude: the position of its highest bit above bit 7
+fn segment(magnitude: i32) -> i32 {
+    (0..8).rev().find(|exponent| magnitude & (0x80 << exponent) != 0).unwrap_or(0)
+}
+
+/// Raise the sample rate `factor` times, interpolating linearly
+fn upsample(samples: &[i16], factor: usize) -> Vec<i16> {
+    let mut out = Vec::with_capacity(samples.len() * factor);
+    for (i, &sample) in samples.iter().enumerate() {
+        let next = samples.get(i + 1).copied().unwrap_or(sample) as i32;
+        for step in 0..factor {
+            let sample = sample as i32;
+            out.push((sample + (next - sample) * step as i32 / factor as i32) as i16);
+        }
+    }
+    out
+}
+
+/// Lower the sample rate `factor` times, averaging
+fn downsample(samples: &[i16], factor: usize) -> Vec<i16> {
+    samples
+        .chunks(factor)
+        .map(|chunk| (chunk.iter().map(|&sample| sample as i32).sum::<i32>() / chunk.len() as i32) as i16)
+        .collect()
+}
+
+#[cfg(feature = "opus")]
+mod opus {
+    use super::{FrameCodec, MIX_SAMPLE_RATE};
+    use audiopus::coder::{Decoder, Encoder};
+    use audiopus::packet::Packet;
+    use audiopus::{Application, Channels, MutSignals, SampleRate};
+
+    /// Longest Opus frame, 120 ms
+    const MAX_FRAME_SAMPLES: usize = MIX_SAMPLE_RATE * 120 / 1000;
+
+    /// Largest Opus packet
+    const MAX_PACKET_BYTES: usize = 1275;
+
+    pub struct OpusCodec {
+        decoder: Decoder,
+        encoder: Encoder,
+    }
+
+    impl OpusCodec {
+        pub fn new() -> Option<Self> {
+            let decoder = Decoder::new(SampleRate::Hz48000, Channels::Mono).ok()?;
+            let encoder = Encoder::new(SampleRate::Hz48000, Channels::Mono, Application::Voip).ok()?;
+            Some(Self { decoder, encoder })
+        }
+    }
+
+    impl FrameCodec for OpusCodec {
+        fn decode(&mut self, frame: &[u8]) -> Option<Vec<i16>> {
+            let packet = Packet::try_from(frame).ok()?;
+            let mut samples = vec![0; MAX_FRAME_SAMPLES];
+            let output = MutSignals::try_from(&mut samples).ok()?;
+            let len = self.decoder.decode(Some(packet), output, false).ok()?;
+            samples.truncate(len);
+            Some(samples)
+        }
+
+        fn encode(&mut self, samples: &[i16]) -> Option<Vec<u8>> {
+            let mut packet = vec![0; MAX_PACKET_BYTES];
+            let len = self.encoder.encode(samples, &mut packet).ok()?;
+            packet.truncate(len);
+            Some(packet)
+        }
+    }
+}
+
+/// Sum voices, each weighed by its gain
+fn mix(voices: &[(&[i16], f32)]) -> Vec<i16> {
+    let mut sum = vec![0f32; MIX_FRAME_SAMPLES];
+    for (samples, gain) in voices {
+        for (total, &sample) in sum.iter_mut().zip(samples.iter()) {
+            *total += sample as f32 * gain;
+        }
+    }
+    sum.into_iter()
+        .map(|total| total.clamp(i16::MIN as f32, i16::MAX as f32) as i16)
+        .collect()
+}
+
+/// Root mean square of a frame
+fn level(samples: &[i16]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let power: f64 = samples.iter().map(|&sample| (sample as f64).powi(2)).sum();
+    (power / samples.len() as f64).sqrt() as f32
+}
+
+struct Speaker {
+    decoder: Box<dyn FrameCodec>,
+    codec: VoiceCodec,
+    buffered: VecDeque<i16>,
+    /// Loudness, decaying while the speaker is quiet
+    level: f32,
+    last_heard: Instant,
+}
+
+struct Listener {
+    client_id: Option<String>,
+    encoder: Box<dyn FrameCodec>,
+}
+
+/// Voices and mixed listeners of one room on this instance
+#[derive(Default)]
+struct RoomMix {
+    speakers: HashMap<String, Speaker>, // client_id -> voice awaiting the next mixed frame
+    listeners: HashMap<String, Listener>, // conn_id ->
+    sequence: i32,
+}
+
+impl RoomMix {
+    /// Buffer a speaker's frame for mixing. Returns false if it cannot be
+    /// decoded.
+    fn push(&mut self, voice: &VoiceData) -> bool {
+        let Ok(codec) = VoiceCodec::try_from(voice.codec) else {
+            return false;
+        };
+        let speaker = match self.speakers.get_mut(&voice.from_client_id) {
+            // A speaker switching codecs needs a new decoder
+            Some(speaker) if speaker.codec == codec => speaker,
+            _ => {
+                let Some(decoder) = frame_codec(codec) else {
+                    return false;
+                };
+                let speaker = Speaker {
+                    decoder,
+                    codec,
+                    buffered: VecDeque::new(),
+                    level: 0.0,
+                    last_heard: Instant::now(),
+                };
+                self.speakers.insert(voice.from_client_id.clone(), speaker);
+                self.speakers.get_mut(&voice.from_client_id).expect("speaker was just added")
+            }
+        };
+
+        let Some(samples) = speaker.decoder.decode(&voice.audio_data) else {
+            log::debug!("Undecodable voice frame from {}", voice.from_client_id);
+            return true;
+        };
+        speaker.buffered.extend(samples);
+        let excess = speaker.buffered.len().saturating_sub(MAX_BUFFERED_SAMPLES);
+        speaker.buffered.drain(..excess);
+        speaker.last_heard = Instant::now();
+        true
+    }
+
+    /// Mix the next frame of the loudest speakers for each listener. `gains`
+    /// holds the spatial gain of speakers by listener; speakers missing from
+    /// it are heard at full gain.
+    fn next_frames(
+        &mut self,
+        mixed_speakers: usize,
+        gains: &HashMap<String, HashMap<String, f32>>,
+    ) -> Vec<(String, Vec<u8>)> {
+        let now = Instant::now();
+        self.speakers
+            .retain(|_, speaker| now.duration_since(speaker.last_heard) < SPEAKER_TIMEOUT);
+
+        let mut speaking: Vec<(&str, Vec<i16>, f32)> = Vec::new();
+        for (client_id, speaker) in self.speakers.iter_mut() {
+            if speaker.buffered.len() < MIX_FRAME_SAMPLES {
+                speaker.level *= LEVEL_DECAY;
+                continue;
+            }
+            let samples: Vec<i16> = speaker.buffered.drain(..MIX_FRAME_SAMPLES).collect();
+            speaker.level = level(&samples).max(speaker.level * LEVEL_DECAY);
+            speaking.push((client_id.as_str(), samples, speaker.level));
+        }
+        if speaking.is_empty() {
+            return Vec::new();
+        }
+
+        self.sequence = self.sequence.wrapping_add(1);
+        let mut frames = Vec::new();
+        for (conn_id, listener) in self.listeners.iter_mut() {
+            let gains = gains.get(conn_id);
+            // (samples, gain, loudness as heard), leaving out the listener's own voice
+            let mut heard: Vec<(&[i16], f32, f32)> = speaking
+                .iter()
+                .filter(|(client_id, _, _)| Some(*client_id) != listener.client_id.as_deref())
+                .map(|(client_id, samples, level)| {
+                    let gain = gains.and_then(|gains| gains.get(*client_id)).copied().unwrap_or(1.0);
+                    (samples.as_slice(), gain, level * gain)
+                })
+                .filter(|(_, gain, _)| *gain > 0.0)
+                .collect();
+            if heard.is_empty() {
+                continue;
+            }
+            heard.sort_by(|a, b| b.2.total_cmp(&a.2));
+            heard.truncate(mixed_speakers);
+
+            let voices: Vec<(&[i16], f32)> = heard.iter().map(|(samples, gain, _)| (*samples, *gain)).collect();
+            let samples = mix(&voices);
+            let Some(audio_data) = listener.encoder.encode(&samples) else {
+                continue;
+            };
+            let message = MessageBuilder::voice_data(MIX_CLIENT_ID.to_string(), audio_data, self.sequence, MIX_CODEC);
+            if let Ok(bytes) = MessageParser::serialize(&message) {
+                frames.push((conn_id.clone(), bytes));
+            }
+        }
+        frames
+    }
+}
+
+/// Checks and relays voice frames, and mixes them for listeners who asked
+#[derive(Clone)]
+pub struct VoiceRelay {
+    session_manager: SessionManager,
+    rooms: Arc<Mutex<HashMap<String, RoomMix>>>, // room_id -> mix on this instance
+    mixed_speakers: usize,
+}
+
+impl VoiceRelay {
+    pub fn new(session_manager: SessionManager) -> Self {
+        Self {
+            session_manager,
+            rooms: Arc::new(Mutex::new(HashMap::new())),
+            mixed_speakers: DEFAULT_MIXED_SPEAKERS,
+        }
+    }
+
+    /// Mix the `speakers` loudest speakers for mixed listeners
+    pub fn with_mixed_speakers(mut self, speakers: usize) -> Self {
+        self.mixed_speakers = speakers.max(1);
+        self
+    }
+
+    /// Check voice frames sent on `ws_manager` and mix them for listeners
+    /// who connected with `voice=mixed`
+    pub async fn start(&self, ws_manager: &WebSocketManager) {
+        ws_manager
+            .register_handler(graphwiz_protocol::MessageType::VoiceData, Arc::new(self.clone()))
+            .await;
+        ws_manager.attach_voice_relay(self.clone()).await;
+    }
+
+    /// Send a connection mixed voice instead of every speaker's frames
+    pub async fn add_mixed_listener(
+        &self,
+        ws_manager: &WebSocketManager,
+        room_id: &str,
+        conn_id: &str,
+        client_id: Option<String>,
+    ) {
+        let Some(encoder) = frame_codec(MIX_CODEC) else {
+            log::warn!("Cannot encode mixed voice, {} receives every speaker", conn_id);
+            return;
+        };
+
+        let mut rooms = self.rooms.lock().await;
+        let idle = !rooms.contains_key(room_id);
+        let room = rooms.entry(room_id.to_string()).or_default();
+        room.listeners.insert(conn_id.to_string(), Listener { client_id, encoder });
+        drop(rooms);
+
+        if idle {
+            self.start_mixing(ws_manager.clone(), room_id.to_string());
+        }
+    }
+
+    /// Stop mixing for a connection that left
+    pub async fn remove_mixed_listener(&self, room_id: &str, conn_id: &str) {
+        let mut rooms = self.rooms.lock().await;
+        if let Some(room) = rooms.get_mut(room_id) {
+            room.listeners.remove(conn_id);
+        }
+    }
+
+    /// Whether anyone in the room on this instance listens to a mix
+    pub async fn has_mixed_listeners(&self, room_id: &str) -> bool {
+        let rooms = self.rooms.lock().await;
+        rooms.get(room_id).is_some_and(|room| !room.listeners.is_empty())
+    }
+
+    /// Take a frame broadcast to the room into its mix. Returns the mixed
+    /// listeners it must no longer be delivered to, which is none if the
+    /// frame cannot be decoded.
+    pub async fn mix_broadcast(&self, room_id: &str, voice: &VoiceData) -> HashSet<String> {
+        let mut rooms = self.rooms.lock().await;
+        let Some(room) = rooms.get_mut(room_id) else {
+            return HashSet::new();
+        };
+        if !room.push(voice) {
+            return HashSet::new();
+        }
+        room.listeners.keys().cloned().collect()
+    }
+
+    /// Send mixed frames to the room's mixed listeners until none are left
+    fn start_mixing(&self, ws_manager: WebSocketManager, room_id: String) {
+        let relay = self.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(MIX_FRAME);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+            loop {
+                interval.tick().await;
+                let listeners: Vec<String> = {
+                    let rooms = relay.rooms.lock().await;
+                    let Some(room) = rooms.get(&room_id) else {
+                        break;
+                    };
+                    room.listeners.keys().cloned().collect()
+                };
+                let gains = ws_manager.spatial_gains(&listeners).await;
+
+                let frames = {
+                    let mut rooms = relay.rooms.lock().await;
+                    let Some(room) = rooms.get_mut(&room_id) else {
+                        break;
+                    };
+                    if room.listeners.is_empty() {
+                        rooms.remove(&room_id);
+                        break;
+                    }
+                    room.next_frames(relay.mixed_speakers, &gains)
+                };
+                for (conn_id, bytes) in frames {
+                    let _ = ws_manager.send_to_connection(&conn_id, WsMessage::Binary(bytes)).await;
+                }
+            }
+
+            log::debug!("Voice mix for room {} stopped", room_id);
+        });
+    }
+}
+
+#[async_trait]
+impl MessageHandler for VoiceRelay {
+    async fn handle(&self, message: &mut Message, ctx: &mut MessageContext<'_>) -> Result<Flow> {
+        let Some(Payload::VoiceData(voice)) = message.payload.as_mut() else {
+            return Ok(Flow::Continue);
+        };
+        let client_id = ctx.client_id().unwrap_or(ctx.conn_id).to_string();
+
+        if VoiceCodec::try_from(voice.codec).is_err() || voice.audio_data.len() > MAX_FRAME_BYTES {
+            log::debug!("Dropping malformed voice frame from {}", client_id);
+            return Ok(Flow::Drop);
+        }
+        let muted_by_moderator = match ctx.user_id() {
+            Some(user_id) => ctx.ws_manager.is_muted(ctx.room_id, user_id).await,
+            None => false,
+        };
+        if muted_by_moderator || self.session_manager.is_client_muted(ctx.room_id, &client_id).await {
+            return Ok(Flow::Drop);
+        }
+
+        // Listeners tell speakers apart by the id the server vouches for
+        voice.from_client_id = client_id;
+        Ok(Flow::Continue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moderation::ModerationManager;
+    use crate::protobuf::{route_message, HandlerChain};
+    use graphwiz_protocol::MessageType;
+    use reticulum_core::models::ModerationActionType;
+    use reticulum_core::Config;
+
+    fn tone(amplitude: f32, samples: usize) -> Vec<i16> {
+        (0..samples)
+            .map(|i| (amplitude * (i as f32 * 0.05).sin()) as i16)
+            .collect()
+    }
+
+    fn pcmu_frame(from: &str, samples: &[i16]) -> VoiceData {
+        let audio_data = samples.iter().map(|&sample| mulaw_encode(sample)).collect();
+        VoiceData {
+            from_client_id: from.to_string(),
+            audio_data,
+            sequence_number: 0,
+            codec: VoiceCodec::Pcmu as i32,
+        }
+    }
+
+    #[test]
+    fn test_g711_round_trip() {
+        for sample in [-32768i16, -1000, -1, 0, 1, 100, 5000, 32767] {
+            let mulaw = mulaw_decode(mulaw_encode(sample)) as i32;
+            let alaw = alaw_decode(alaw_encode(sample)) as i32;
+            let tolerance = (sample as i32).abs() / 16 + 16;
+            assert!((mulaw - sample as i32).abs() <= tolerance, "mu-law {} -> {}", sample, mulaw);
+            assert!((alaw - sample as i32).abs() <= tolerance, "A-law {} -> {}", sample, alaw);
+        }
+        // 20 ms of 8 kHz G.711 is one mixed frame
+        assert_eq!(G711::ALaw.decode(&[0xD5; 160]).unwrap().len(), MIX_FRAME_SAMPLES);
+    }
+
+    #[test]
+    fn test_mix_weighs_and_clamps() {
+        let alice = vec![1000; MIX_FRAME_SAMPLES];
+        let bob = vec![32000; MIX_FRAME_SAMPLES];
+
+        assert_eq!(mix(&[(alice.as_slice(), 1.0), (bob.as_slice(), 0.5)])[0], 17000);
+        assert_eq!(mix(&[(alice.as_slice(), 1.0), (bob.as_slice(), 1.0)])[0], i16::MAX);
+    }
+
+    #[test]
+    fn test_loudest_speakers_are_mixed() {
+        let mut room = RoomMix::default();
+        room.listeners.insert(
+            "conn-carol".to_string(),
+            Listener {
+                client_id: Some("carol".to_string()),
+                encoder: frame_codec(VoiceCodec::Pcmu).unwrap(),
+            },
+        );
+        room.listeners.insert(
+            "conn-alice".to_string(),
+            Listener {
+                client_id: Some("alice".to_string()),
+                encoder: frame_codec(VoiceCodec::Pcmu).unwrap(),
+            },
+        );
+        let samples = MIX_FRAME_SAMPLES / G711_UPSAMPLING;
+        let mixed_level = |frames: &[(String, Vec<u8>)], conn_id: &str| {
+            let (_, bytes) = frames.iter().find(|(id, _)| id == conn_id).unwrap();
+            let voice = match MessageParser::parse(bytes).unwrap().payload {
+                Some(Payload::VoiceData(voice)) => voice,
+                other => panic!("expected voice, got {:?}", other),
+            };
+            assert_eq!(voice.from_client_id, MIX_CLIENT_ID);
+            assert_eq!(voice.audio_data.len(), samples);
+            level(&G711::MuLaw.decode(&voice.audio_data).unwrap())
+        };
+
+        // Carol hears alice, the loudest; alice hears bob rather than herself
+        assert!(room.push(&pcmu_frame("alice", &tone(8000.0, samples))));
+        assert!(room.push(&pcmu_frame("bob", &tone(100.0, samples))));
+        let frames = room.next_frames(1, &HashMap::new());
+        assert_eq!(frames.len(), 2);
+        assert!(mixed_level(&frames, "conn-carol") > 1000.0);
+        assert!(mixed_level(&frames, "conn-alice") < 1000.0);
+
+        // Alice is out of carol's earshot
+        let gains = HashMap::from([(
+            "conn-carol".to_string(),
+            HashMap::from([("alice".to_string(), 0.0)]),
+        )]);
+        assert!(room.push(&pcmu_frame("alice", &tone(8000.0, samples))));
+        assert!(room.push(&pcmu_frame("bob", &tone(100.0, samples))));
+        let frames = room.next_frames(1, &gains);
+        assert!(mixed_level(&frames, "conn-carol") < 1000.0);
+
+        // Nothing buffered, nothing to mix
+        assert!(room.next_frames(1, &HashMap::new()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_moderator_mutes_drop_voice() {
+        let ws_manager = WebSocketManager::new();
+        let moderation = ModerationManager::new(None, Config::load_or_default().unwrap());
+        ws_manager.attach_moderation(moderation.clone()).await;
+        let mut chain = HandlerChain::with_defaults();
+        chain.register(MessageType::VoiceData, Arc::new(VoiceRelay::new(SessionManager::new())));
+        let _speaker = ws_manager
+            .add_authenticated_connection("c1".to_string(), Some("room-1".to_string()), "1".to_string(), Some("alice".to_string()))
+            .await;
+        let mut listener = ws_manager
+            .add_connection("c2".to_string(), Some("room-1".to_string()), None, Some("bob".to_string()))
+            .await;
+        let frame = MessageBuilder::voice_data("alice".to_string(), vec![0xFF; 160], 0, VoiceCodec::Pcmu);
+        let bytes = MessageParser::serialize(&frame).unwrap();
+        let heard = |rx: &mut crate::queue::OutboundReceiver<WsMessage>| rx.queue.try_recv().is_ok();
+
+        route_message(&chain, &ws_manager, "room-1", "c1", &bytes).await.unwrap();
+        assert!(heard(&mut listener));
+
+        moderation
+            .record("room-1", ModerationActionType::Mute, Some("1"), "mod", None, None)
+            .await
+            .unwrap();
+        route_message(&chain, &ws_manager, "room-1", "c1", &bytes).await.unwrap();
+        assert!(!heard(&mut listener));
+    }
+
+    #[cfg(not(feature = "opus"))]
+    #[test]
+    fn test_opus_needs_the_feature() {
+        let mut room = RoomMix::default();
+        let voice = VoiceData {
+            from_client_id: "alice".to_string(),
+            audio_data: vec![0xFC, 0xFF, 0xFE],
+            sequence_number: 0,
+            codec: VoiceCodec::Opus as i32,
+        };
+        assert!(!room.push(&voice));
+    }
+}
@@ -3,18 +3,50 @@
 use actix_web::{web, HttpRequest, HttpResponse};
 use actix_ws::Message;
 use futures::StreamExt;
+use reticulum_core::models::UserRole;
+use reticulum_core::occupancy::{HostHandoff, SeatClaim, SeatRelease, HOST_CHANNEL, OCCUPANCY_CHANNEL, SEAT_CLAIM_CHANNEL};
 use reticulum_core::Result;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
 // Imports for protobuf
-use graphwiz_protocol::core::presence_event::EventType;
-use graphwiz_protocol::core::presence_data;
-use graphwiz_protocol::core::{Message, PresenceEvent, PresenceData, ServerHello};
-use graphwiz_protocol::MessageType;
+use graphwiz_protocol::{MessageBuilder, MessageParser, MessageType, PositionUpdate, PresenceData, PresenceEventType};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, RwLock};
 use uuid::Uuid;
 
+use crate::host::{elect_host, host_priority_for_role, HostMigration};
+use crate::interest::InterestState;
+use crate::liveness::{LivenessConfig, LivenessTracker};
+use crate::metrics::PerformanceMonitor;
+use crate::presence_feed::PresenceFeed;
+use crate::chat::ChatService;
+use crate::drain::{DrainController, MigrationTicket};
+use crate::moderation::{Authority, ModerationManager};
+use crate::user_presence::PresenceDirectory;
+use crate::signaling::SignalingServer;
+use crate::protobuf::{route_message, HandlerChain, MessageHandler};
+use crate::queue::{outbound_queue, Enqueued, OutboundQueue, OutboundReceiver};
+use crate::recording::{Direction, SessionRecorder};
+use crate::rate_limit::MetricRateLimiter;
+use crate::redis::{PubSubMessage, PubSubTransport};
+use crate::resume::{ResumableSession, DEFAULT_RESUME_BUFFER_LEN, DEFAULT_RESUME_GRACE_SECS};
+use crate::spatial::{SpatialConfig, SpatialState};
+use crate::tick::{clamp_tick_rate, default_tick_rate, tick_interval, RoomFrame};
+use crate::voice::VoiceRelay;
+
+/// Messages a client may send in a burst; the sustained limit is this many
+/// per `RATE_LIMIT_WINDOW_SECS`
+const RATE_LIMIT_BURST: usize = 60;
+const RATE_LIMIT_WINDOW_SECS: u64 = 2;
+
+/// Outbound messages queued per connection
+const SEND_QUEUE_LEN: usize = 512;
+
+/// How long a connection may keep shedding updates before it is disconnected
+const SLOW_CONSUMER_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// WebSocket message types
 #[derive(Clone, Debug)]
 pub enum WsMessage {
@@ -23,6 +55,34 @@ pub enum WsMessage {
     Close,
 }
 
+/// Connection taken over by a reconnecting client
+pub struct ResumedConnection {
+    pub conn_id: String,
+    pub attachment: u64,
+    pub rx: OutboundReceiver<WsMessage>,
+    pub replayed: usize,
+}
+
+/// Result of a resume attempt
+pub enum ResumeOutcome {
+    Resumed(ResumedConnection),
+    /// The session exists but the missed messages are no longer buffered; it
+    /// should be closed and the client joined afresh
+    Expired(String),
+    Unknown,
+}
+
+/// What happened to a connection when its socket went away
+#[derive(Debug, PartialEq)]
+pub enum Detached {
+    /// Kept for resumption; the room sees no departure yet
+    Parked,
+    /// A resumed socket owns the connection now
+    Superseded,
+    /// Nothing keeps the connection; it should be closed
+    Released,
+}
+
 /// WebSocket connection info
 #[derive(Clone)]
 pub struct WebSocketConnection {
@@ -31,14 +91,89 @@ pub struct WebSocketConnection {
     pub connected_at: chrono::DateTime<chrono::Utc>,
     pub user_id: Option<String>,
     pub client_id: Option<String>,
+    /// Team the connection chats with, if a room moderator put its user on one
+    pub team: Option<String>,
+    pub transport: Transport,
+    /// Whether `user_id` was proven by an access token rather than claimed
+    pub authenticated: bool,
+    /// Host election priority of the user's role
+    pub host_priority: i32,
+}
+
+/// How a connection reaches this instance
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Transport {
+    #[default]
+    WebSocket,
+    WebTransport,
+}
+
+/// Rate limit state of a connection
+#[derive(Debug, Clone, Serialize)]
+pub struct RateLimitState {
+    /// Whether the connection was told it is rate limited and has not sent
+    /// an accepted message since
+    pub limited: bool,
+    /// Tokens in use out of `burst`
+    pub used: usize,
+    pub burst: usize,
+}
+
+/// Live state of a connection, for admins inspecting a room
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionDetails {
+    pub conn_id: String,
+    pub room_id: Option<String>,
+    pub user_id: Option<String>,
+    pub client_id: Option<String>,
+    pub team: Option<String>,
+    pub transport: Transport,
+    pub connected_at: chrono::DateTime<chrono::Utc>,
+    /// Whether the client hosts its room
+    pub host: bool,
+    /// Round trip of the last answered ping
+    pub rtt_ms: Option<u64>,
+    /// Messages waiting to be written to the client
+    pub queue_depth: usize,
+    /// Whether the socket is gone and the session waits to be resumed
+    pub parked: bool,
+    pub rate_limit: RateLimitState,
+    /// Last position the client reported
+    pub position: Option<[f32; 3]>,
 }
 
 /// WebSocket connection manager
 #[derive(Clone)]
 pub struct WebSocketManager {
-    connections: Arc<RwLock<HashMap<String, mpsc::UnboundedSender<WsMessage>>>>,
+    connections: Arc<RwLock<HashMap<String, OutboundQueue<WsMessage>>>>,
     connection_info: Arc<RwLock<HashMap<String, WebSocketConnection>>>,
     room_connections: Arc<RwLock<HashMap<String, Vec<String>>>>,
+    room_hosts: Arc<RwLock<HashMap<String, String>>>, // room_id -> host client_id
+    resumable: Arc<RwLock<HashMap<String, ResumableSession>>>, // conn_id -> resumption state
+    resume_tokens: Arc<RwLock<HashMap<String, String>>>, // resume token -> conn_id
+    resume_grace: chrono::Duration,
+    resume_buffer_len: usize,
+    interest: Arc<RwLock<InterestState>>, // area-of-interest state for position updates
+    room_frames: Arc<RwLock<HashMap<String, RoomFrame>>>, // room_id -> updates awaiting the next tick
+    room_tick_rates: Arc<RwLock<HashMap<String, u32>>>, // room_id -> tick rate override
+    default_tick_rate: u32,
+    rate_limiter: Arc<MetricRateLimiter>,
+    rate_limited: Arc<RwLock<HashSet<String>>>, // conn_ids already told they are rate limited
+    metrics: Arc<PerformanceMonitor>,
+    cluster: Arc<RwLock<Option<mpsc::Sender<PubSubMessage>>>>, // room broadcasts for other instances
+    hub: Arc<RwLock<Option<Arc<dyn PubSubTransport>>>>, // seat claims, releases and host handoffs for the hub
+    handlers: Arc<RwLock<Arc<HandlerChain>>>, // copied on write so messages never wait on registration
+    moderation: Arc<RwLock<Option<ModerationManager>>>, // bans and locks checked on join
+    chat: Arc<RwLock<Option<ChatService>>>, // history replayed on join
+    user_presence: Arc<RwLock<Option<PresenceDirectory>>>, // signed-in users tracked across rooms
+    signaling: Arc<RwLock<Option<SignalingServer>>>, // calls hung up on disconnect
+    liveness: LivenessTracker, // pings and last signs of life of WebSocket connections
+    presence_feed: PresenceFeed, // room presence for observers outside the room
+    recorder: Arc<RwLock<Option<SessionRecorder>>>, // rooms whose traffic is recorded
+    voice: Arc<RwLock<Option<VoiceRelay>>>, // voice mixed for listeners on weak connections
+    spatial: Arc<RwLock<SpatialState>>, // spatial audio settings and what each listener was sent
+    drain: Arc<RwLock<Option<DrainController>>>, // moves sessions between instances on shutdown
 }
 
 impl WebSocketManager {
@@ -47,44 +182,334 @@ impl WebSocketManager {
             connections: Arc::new(RwLock::new(HashMap::new())),
             connection_info: Arc::new(RwLock::new(HashMap::new())),
             room_connections: Arc::new(RwLock::new(HashMap::new())),
+            room_hosts: Arc::new(RwLock::new(HashMap::new())),
+            resumable: Arc::new(RwLock::new(HashMap::new())),
+            resume_tokens: Arc::new(RwLock::new(HashMap::new())),
+            resume_grace: chrono::Duration::seconds(DEFAULT_RESUME_GRACE_SECS),
+            resume_buffer_len: DEFAULT_RESUME_BUFFER_LEN,
+            interest: Arc::new(RwLock::new(InterestState::default())),
+            room_frames: Arc::new(RwLock::new(HashMap::new())),
+            room_tick_rates: Arc::new(RwLock::new(HashMap::new())),
+            default_tick_rate: default_tick_rate(),
+            rate_limiter: Arc::new(MetricRateLimiter::new(RATE_LIMIT_BURST, RATE_LIMIT_WINDOW_SECS)),
+            rate_limited: Arc::new(RwLock::new(HashSet::new())),
+            metrics: Arc::new(PerformanceMonitor::new()),
+            cluster: Arc::new(RwLock::new(None)),
+            hub: Arc::new(RwLock::new(None)),
+            handlers: Arc::new(RwLock::new(Arc::new(HandlerChain::with_defaults()))),
+            moderation: Arc::new(RwLock::new(None)),
+            chat: Arc::new(RwLock::new(None)),
+            user_presence: Arc::new(RwLock::new(None)),
+            signaling: Arc::new(RwLock::new(None)),
+            liveness: LivenessTracker::new(LivenessConfig::from_env()),
+            presence_feed: PresenceFeed::new(),
+            recorder: Arc::new(RwLock::new(None)),
+            voice: Arc::new(RwLock::new(None)),
+            spatial: Arc::new(RwLock::new(SpatialState::default())),
+            drain: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Ping connections and time them out as `config` says
+    pub fn with_liveness(mut self, config: LivenessConfig) -> Self {
+        self.liveness = LivenessTracker::new(config);
+        self
+    }
+
+    /// Run `handler` on client messages of `message_type` before delivery
+    pub async fn register_handler(&self, message_type: MessageType, handler: Arc<dyn MessageHandler>) {
+        let mut handlers = self.handlers.write().await;
+        Arc::make_mut(&mut handlers).register(message_type, handler);
+    }
+
+    /// Run `handler` on every client message, before per-type handlers
+    pub async fn register_global_handler(&self, handler: Arc<dyn MessageHandler>) {
+        let mut handlers = self.handlers.write().await;
+        Arc::make_mut(&mut handlers).register_all(handler);
+    }
+
+    /// Current handler chain
+    pub async fn handlers(&self) -> Arc<HandlerChain> {
+        self.handlers.read().await.clone()
+    }
+
+    /// Publish room broadcasts to other presence instances through `tx`
+    pub async fn attach_cluster(&self, tx: mpsc::Sender<PubSubMessage>) {
+        *self.cluster.write().await = Some(tx);
+    }
+
+    /// Tell the hub over `transport` who joined or left a room and who hosts
+    /// it after a migration
+    pub async fn attach_hub(&self, transport: Arc<dyn PubSubTransport>) {
+        *self.hub.write().await = Some(transport);
+    }
+
+    /// Check joins against `moderation`'s bans and room locks
+    pub async fn attach_moderation(&self, moderation: ModerationManager) {
+        *self.moderation.write().await = Some(moderation);
+    }
+
+    /// User id proven by an access token; tokens can only be checked with
+    /// moderation attached
+    pub async fn verify_user(&self, token: &str) -> Result<String> {
+        let moderation = self.moderation.read().await.clone();
+        match moderation {
+            Some(moderation) => Ok(moderation.verify_token(token)?.user_id),
+            None => Err(reticulum_core::Error::auth("Access tokens cannot be verified here")),
+        }
+    }
+
+    /// Whether the verified user `user_id` may join `room_id`; everyone may
+    /// without moderation
+    pub async fn admit(&self, room_id: &str, user_id: Option<&str>) -> Result<()> {
+        let moderation = self.moderation.read().await.clone();
+        match moderation {
+            Some(moderation) => moderation.admit(room_id, user_id).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Whether a moderator muted `user_id` in `room_id`; no one is muted
+    /// without moderation
+    pub async fn is_muted(&self, room_id: &str, user_id: &str) -> bool {
+        let moderation = self.moderation.read().await.clone();
+        match moderation {
+            Some(moderation) => moderation.mute_of(room_id, user_id).await.is_some(),
+            None => false,
         }
     }
 
-    /// Add a new connection
+    /// Replay `chat`'s room history to joining connections
+    pub async fn attach_chat(&self, chat: ChatService) {
+        *self.chat.write().await = Some(chat);
+    }
+
+    /// Send a joining connection its room's recent chat
+    pub async fn replay_chat(&self, conn_id: &str) {
+        let chat = self.chat.read().await.clone();
+        if let Some(chat) = chat {
+            chat.replay(self, conn_id).await;
+        }
+    }
+
+    /// Report joining and leaving connections to `directory`
+    pub async fn attach_user_presence(&self, directory: PresenceDirectory) {
+        *self.user_presence.write().await = Some(directory);
+    }
+
+    /// Show a joining connection's user as online
+    pub async fn track_user_presence(&self, conn_id: &str) {
+        let directory = self.user_presence.read().await.clone();
+        if let Some(directory) = directory {
+            directory.connected(self, conn_id).await;
+        }
+    }
+
+    /// Forget a closed connection in the user presence directory
+    pub async fn untrack_user_presence(&self, conn_id: &str) {
+        let directory = self.user_presence.read().await.clone();
+        if let Some(directory) = directory {
+            directory.disconnected(self, conn_id).await;
+        }
+    }
+
+    /// Hang up the calls of closed connections through `signaling`
+    pub async fn attach_signaling(&self, signaling: SignalingServer) {
+        *self.signaling.write().await = Some(signaling);
+    }
+
+    /// Hang up the WebRTC calls of a client that left a room
+    pub async fn hang_up_calls(&self, room_id: &str, client_id: &str) {
+        let signaling = self.signaling.read().await.clone();
+        if let Some(signaling) = signaling {
+            signaling.disconnected(self, room_id, client_id).await;
+        }
+    }
+
+    /// Record the traffic of rooms through `recorder` while it records them
+    pub async fn attach_recorder(&self, recorder: SessionRecorder) {
+        *self.recorder.write().await = Some(recorder);
+    }
+
+    /// The recorder, if it is recording the room
+    async fn recording(&self, room_id: &str) -> Option<SessionRecorder> {
+        let recorder = self.recorder.read().await.clone()?;
+        recorder.is_recording(room_id).await.then_some(recorder)
+    }
+
+    /// Add a client's message to the room's recording, if any
+    pub async fn record_inbound(&self, room_id: &str, conn_id: &str, message: &[u8]) {
+        let Some(recorder) = self.recording(room_id).await else {
+            return;
+        };
+        let client_id = self
+            .get_connection_info(conn_id)
+            .await
+            .and_then(|conn| conn.client_id)
+            .unwrap_or_else(|| conn_id.to_string());
+        recorder.record(room_id, Direction::Inbound, &client_id, message).await;
+    }
+
+    /// Mix voice through `relay` for connections that ask for it
+    pub async fn attach_voice_relay(&self, relay: VoiceRelay) {
+        *self.voice.write().await = Some(relay);
+    }
+
+    /// Send a connection one mixed voice stream instead of every speaker's
+    pub async fn join_voice_mix(&self, room_id: &str, conn_id: &str, client_id: Option<String>) {
+        let relay = self.voice.read().await.clone();
+        if let Some(relay) = relay {
+            relay.add_mixed_listener(self, room_id, conn_id, client_id).await;
+        }
+    }
+
+    /// Stop mixing voice for a closed connection
+    pub async fn leave_voice_mix(&self, room_id: &str, conn_id: &str) {
+        let relay = self.voice.read().await.clone();
+        if let Some(relay) = relay {
+            relay.remove_mixed_listener(room_id, conn_id).await;
+        }
+    }
+
+    /// Refuse connections while `drain` drains this instance and take over
+    /// the sessions draining peers offer
+    pub async fn attach_drain(&self, drain: DrainController) {
+        *self.drain.write().await = Some(drain);
+    }
+
+    /// Whether this instance is sending its clients elsewhere
+    pub async fn is_draining(&self) -> bool {
+        let drain = self.drain.read().await.clone();
+        drain.is_some_and(|drain| drain.is_draining())
+    }
+
+    /// Take over a session a draining instance offered under `resume_token`
+    pub async fn claim_migration(&self, resume_token: &str, room_id: &str) -> Option<MigrationTicket> {
+        let drain = self.drain.read().await.clone();
+        drain?.claim(resume_token, room_id).await
+    }
+
+    /// Hand a voice frame broadcast to the room to its mix. Returns the
+    /// connections that receive it mixed rather than as it is.
+    async fn mix_voice(&self, room_id: &str, message: &[u8]) -> HashSet<String> {
+        let relay = self.voice.read().await.clone();
+        let Some(relay) = relay else {
+            return HashSet::new();
+        };
+        if !relay.has_mixed_listeners(room_id).await {
+            return HashSet::new();
+        }
+        match MessageParser::parse(message).map(|parsed| parsed.payload) {
+            Ok(Some(graphwiz_protocol::generated::graphwiz::core::message::Payload::VoiceData(voice))) => {
+                relay.mix_broadcast(room_id, &voice).await
+            }
+            _ => HashSet::new(),
+        }
+    }
+
+    /// Get the liveness tracker
+    pub fn liveness(&self) -> &LivenessTracker {
+        &self.liveness
+    }
+
+    /// Get the feed of room presence events
+    pub fn presence_feed(&self) -> &PresenceFeed {
+        &self.presence_feed
+    }
+
+    /// Get rate limiter
+    pub fn rate_limiter(&self) -> &Arc<MetricRateLimiter> {
+        &self.rate_limiter
+    }
+
+    /// Get metrics collector
+    pub fn metrics(&self) -> &Arc<PerformanceMonitor> {
+        &self.metrics
+    }
+
+    /// Add a new connection whose user id, if any, is only a label
     pub async fn add_connection(
         &self,
         conn_id: String,
         room_id: Option<String>,
         user_id: Option<String>,
         client_id: Option<String>,
-    ) -> mpsc::UnboundedReceiver<WsMessage> {
-        let (tx, rx) = mpsc::unbounded_channel();
+    ) -> OutboundReceiver<WsMessage> {
+        self.register_connection(conn_id, room_id, user_id, client_id, false).await
+    }
+
+    /// Add a new connection whose user id was proven by an access token
+    pub async fn add_authenticated_connection(
+        &self,
+        conn_id: String,
+        room_id: Option<String>,
+        user_id: String,
+        client_id: Option<String>,
+    ) -> OutboundReceiver<WsMessage> {
+        self.register_connection(conn_id, room_id, Some(user_id), client_id, true).await
+    }
+
+    async fn register_connection(
+        &self,
+        conn_id: String,
+        room_id: Option<String>,
+        user_id: Option<String>,
+        client_id: Option<String>,
+        authenticated: bool,
+    ) -> OutboundReceiver<WsMessage> {
+        // Anyone can claim a user id, so only proven ones rank by role
+        let host_priority = match authenticated {
+            true => self.host_priority(user_id.as_deref()).await,
+            false => host_priority_for_role(&UserRole::User),
+        };
+        self.metrics().metrics().record_connection().await;
+        self.rate_limiter().increment_connections().await;
+        if let Some(ref room) = room_id {
+            self.metrics().room_metrics().init_room(room.clone()).await;
+            self.metrics().room_metrics().record_connection(room).await;
+        }
+
+        let (tx, rx) = outbound_queue(SEND_QUEUE_LEN, SLOW_CONSUMER_TIMEOUT);
 
         // Store sender
         let mut connections = self.connections.write().await;
         connections.insert(conn_id.clone(), tx);
 
         // Store connection info
+        let client_id_for_host = client_id.clone();
+        let conn = WebSocketConnection {
+            id: conn_id.clone(),
+            room_id: room_id.clone(),
+            connected_at: chrono::Utc::now(),
+            user_id,
+            client_id,
+            team: None,
+            transport: Transport::WebSocket,
+            authenticated,
+            host_priority,
+        };
         let mut connection_info = self.connection_info.write().await;
-        connection_info.insert(
-            conn_id.clone(),
-            WebSocketConnection {
-                id: conn_id.clone(),
-                room_id: room_id.clone(),
-                connected_at: chrono::Utc::now(),
-                user_id,
-                client_id,
-            },
-        );
+        connection_info.insert(conn_id.clone(), conn.clone());
 
-        // Add to room if applicable
+         // Add to room if applicable
         if let Some(room_id) = room_id {
             let mut room_connections = self.room_connections.write().await;
             room_connections
                 .entry(room_id.clone())
                 .or_insert_with(Vec::new)
                 .push(conn_id.clone());
+
+            // First client to join becomes host
+            if let Some(client_id) = client_id_for_host {
+                let mut room_hosts = self.room_hosts.write().await;
+                room_hosts.entry(room_id).or_insert(client_id);
+            }
         }
+        drop(connection_info);
+        drop(connections);
+
+        // Clients may connect without joining their hub instance first
+        self.claim_seat(&conn).await;
 
         rx
     }
@@ -94,200 +519,807 @@ impl WebSocketManager {
         let mut connections = self.connections.write().await;
         connections.remove(conn_id);
 
-        // Remove from room
+        self.rate_limiter().remove(conn_id).await;
+        self.rate_limited.write().await.remove(conn_id);
+
         let mut connection_info = self.connection_info.write().await;
         if let Some(conn) = connection_info.remove(conn_id) {
+            self.metrics().metrics().record_disconnection().await;
+            self.rate_limiter().decrement_connections().await;
+
             // Remove from room
             if let Some(room_id) = &conn.room_id {
+                self.metrics().room_metrics().record_disconnection(room_id).await;
+
                 let mut room_connections = self.room_connections.write().await;
                 if let Some(conns) = room_connections.get_mut(room_id) {
                     conns.retain(|id| id != conn_id);
                     if conns.is_empty() {
                         room_connections.remove(room_id);
+                        self.room_tick_rates.write().await.remove(room_id);
+                        self.spatial.write().await.remove_config(room_id);
+                        self.metrics().room_metrics().remove_room(room_id).await;
                     }
                 }
             }
-        }
-        Some(conn)
-        else {
+
+            let mut resumable = self.resumable.write().await;
+            if let Some(session) = resumable.remove(conn_id) {
+                let mut resume_tokens = self.resume_tokens.write().await;
+                resume_tokens.remove(&session.token);
+            }
+
+            let mut interest = self.interest.write().await;
+            interest.remove(conn_id);
+            self.spatial.write().await.remove(conn_id);
+            Some(conn)
+        } else {
             None
         }
     }
 
-/// WebSocket connection info
-#[derive(Clone)]
-pub struct WebSocketConnection {
-    pub id: String,
-    pub room_id: Option<String>,
-    pub connected_at: chrono::DateTime<chrono::Utc>,
-    pub user_id: Option<String>,
-    pub client_id: Option<String>,
-}
+    /// Issue a resume token for a connection and start buffering its reliable messages
+    pub async fn enable_resume(&self, conn_id: &str) -> String {
+        let session = ResumableSession::new(self.resume_buffer_len);
+        let token = session.token.clone();
 
-/// WebSocket connection manager
-#[derive(Clone)]
-pub struct WebSocketManager {
-    connections: Arc<RwLock<HashMap<String, mpsc::UnboundedSender<WsMessage>>>>,
-    connection_info: Arc<RwLock<HashMap<String, WebSocketConnection>>>,
-    room_connections: Arc<RwLock<HashMap<String, Vec<String>>>>,
-    // Production-ready features - temporarily disabled
-    // rate_limiter: Arc<MetricRateLimiter>,
-    // metrics: Arc<PerformanceMonitor>,
-    // message_queue: Arc<MessageQueue>,
-}
+        let mut resumable = self.resumable.write().await;
+        resumable.insert(conn_id.to_string(), session);
 
-impl WebSocketManager {
-    pub fn new() -> Self {
-        Self {
-            connections: Arc::new(RwLock::new(HashMap::new())),
-            connection_info: Arc::new(RwLock::new(HashMap::new())),
-            room_connections: Arc::new(RwLock::new(HashMap::new())),
+        let mut resume_tokens = self.resume_tokens.write().await;
+        resume_tokens.insert(token.clone(), conn_id.to_string());
+
+        token
+    }
+
+    /// Resume token of a connection, if it can resume
+    pub async fn resume_token(&self, conn_id: &str) -> Option<String> {
+        let resumable = self.resumable.read().await;
+        resumable.get(conn_id).map(|session| session.token.clone())
+    }
+
+    /// Hand a session over to a reconnecting client.
+    ///
+    /// A `ServerHello` and the reliable messages sent after `last_sequence` are
+    /// queued on a fresh channel before it is swapped in. Dropping the previous
+    /// sender ends the task serving the old socket if it is still alive.
+    pub async fn resume_connection(&self, token: &str, room_id: &str, last_sequence: u32) -> ResumeOutcome {
+        let conn_id = match self.resume_tokens.read().await.get(token) {
+            Some(conn_id) => conn_id.clone(),
+            None => return ResumeOutcome::Unknown,
+        };
+
+        let in_room = self
+            .get_connection_info(&conn_id)
+            .await
+            .is_some_and(|conn| conn.room_id.as_deref() == Some(room_id));
+        if !in_room {
+            return ResumeOutcome::Unknown;
+        }
+
+        let mut connections = self.connections.write().await;
+        let mut resumable = self.resumable.write().await;
+        let Some(session) = resumable.get_mut(&conn_id) else {
+            return ResumeOutcome::Unknown;
+        };
+
+        let Some(replay) = session.buffer.replay_after(last_sequence) else {
+            log::info!(
+                "Cannot resume {}: client is at sequence {}, server at {}",
+                conn_id,
+                last_sequence,
+                session.buffer.last_sequence()
+            );
+            return ResumeOutcome::Expired(conn_id);
+        };
+
+        let (tx, rx) = outbound_queue(SEND_QUEUE_LEN, SLOW_CONSUMER_TIMEOUT);
+        let now = Instant::now();
+        if let Ok(hello_bytes) = create_server_hello_msg(room_id, &conn_id, &session.token, true) {
+            tx.offer(WsMessage::Binary(hello_bytes), true, now);
+        }
+        let replayed = replay.len();
+        for message in replay {
+            tx.offer(WsMessage::Binary(message), true, now);
         }
+
+        connections.insert(conn_id.clone(), tx);
+        session.parked_at = None;
+        session.attachment += 1;
+
+        ResumeOutcome::Resumed(ResumedConnection {
+            conn_id,
+            attachment: session.attachment,
+            rx,
+            replayed,
+        })
     }
 
-    // Production methods - temporarily disabled
-    /*
-    /// Get rate limiter
-    pub fn rate_limiter(&self) -> &Arc<MetricRateLimiter> {
-        &self.rate_limiter
+    /// Detach a socket that went away.
+    ///
+    /// With `park` set, a resumable connection keeps its room membership and
+    /// keeps buffering reliable messages until it is resumed or expires.
+    pub async fn detach_connection(&self, conn_id: &str, attachment: u64, park: bool) -> Detached {
+        let mut connections = self.connections.write().await;
+        let mut resumable = self.resumable.write().await;
+
+        match resumable.get_mut(conn_id) {
+            Some(session) if session.attachment != attachment => Detached::Superseded,
+            Some(session) if park => {
+                connections.remove(conn_id);
+                session.parked_at = Some(chrono::Utc::now());
+                Detached::Parked
+            }
+            _ => Detached::Released,
+        }
     }
 
-    /// Get metrics collector
-    pub fn metrics(&self) -> &Arc<PerformanceMonitor> {
-        &self.metrics
+    /// Stop resumption for connections parked longer than the grace period.
+    ///
+    /// Returns their IDs; they still need to be closed.
+    pub async fn expire_parked_connections(&self) -> Vec<String> {
+        let now = chrono::Utc::now();
+        let mut resumable = self.resumable.write().await;
+        let expired: Vec<String> = resumable
+            .iter()
+            .filter(|(_, session)| session.is_expired(self.resume_grace, now))
+            .map(|(conn_id, _)| conn_id.clone())
+            .collect();
+
+        let mut resume_tokens = self.resume_tokens.write().await;
+        for conn_id in &expired {
+            if let Some(session) = resumable.remove(conn_id) {
+                resume_tokens.remove(&session.token);
+            }
+        }
+
+        expired
+    }
+
+    /// Periodically close parked connections that were not resumed in time
+    pub fn start_resume_expiry_task(&self) -> tokio::task::JoinHandle<()> {
+        let ws_manager = self.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(5));
+
+            loop {
+                interval.tick().await;
+                for conn_id in ws_manager.expire_parked_connections().await {
+                    log::info!("Resume grace period for {} expired", conn_id);
+                    close_connection(&ws_manager, &conn_id).await;
+                }
+            }
+        })
     }
 
-    /// Get message queue
-    pub fn message_queue(&self) -> &Arc<MessageQueue> {
-        &self.message_queue
+    /// Get the host client ID for a room
+    pub async fn get_room_host(&self, room_id: &str) -> Option<String> {
+        let room_hosts = self.room_hosts.read().await;
+        room_hosts.get(room_id).cloned()
     }
-    */
 
-    /// Add a new connection
-    pub async fn add_connection(
+    /// Host election priority of `user_id`'s role; anonymous users, and
+    /// everyone without moderation, rank as plain users
+    async fn host_priority(&self, user_id: Option<&str>) -> i32 {
+        let moderation = self.moderation.read().await.clone();
+        let role = match (moderation, user_id) {
+            (Some(moderation), Some(user_id)) => moderation.user_role(user_id).await.unwrap_or_else(|e| {
+                log::warn!("Failed to look up the role of {}, electing them as a user: {}", user_id, e);
+                UserRole::User
+            }),
+            _ => UserRole::User,
+        };
+        host_priority_for_role(&role)
+    }
+
+    /// Override the host election priority of a connection
+    pub async fn set_host_priority(&self, conn_id: &str, priority: i32) {
+        if let Some(conn) = self.connection_info.write().await.get_mut(conn_id) {
+            conn.host_priority = priority;
+        }
+    }
+
+    /// Elect a new host by role and join order if `departed_client_id` was the
+    /// room's host, and tell the hub to hand them the previous host's entities.
+    ///
+    /// Call after the departing connection has been removed.
+    pub async fn migrate_room_host(&self, room_id: &str, departed_client_id: &str) -> Option<HostMigration> {
+        // Collect remaining clients in join order before taking the host lock
+        let conn_ids = self.get_room_connections(room_id).await;
+        let remaining: Vec<(String, i32, Option<String>)> = {
+            let connection_info = self.connection_info.read().await;
+            conn_ids
+                .iter()
+                .filter_map(|id| connection_info.get(id))
+                .filter_map(|c| {
+                    // The hub only hands entities to proven users
+                    let user_id = c.user_id.clone().filter(|_| c.authenticated);
+                    Some((c.client_id.clone()?, c.host_priority, user_id))
+                })
+                .collect()
+        };
+
+        let new_host = {
+            let mut room_hosts = self.room_hosts.write().await;
+            if room_hosts.get(room_id).map(String::as_str) != Some(departed_client_id) {
+                return None;
+            }
+
+            let new_host = elect_host(remaining.iter().map(|(client_id, priority, _)| (client_id.as_str(), *priority)));
+            match &new_host {
+                Some(client_id) => room_hosts.insert(room_id.to_string(), client_id.clone()),
+                None => room_hosts.remove(room_id),
+            };
+            new_host
+        };
+        log::info!("Host of room {} migrated from {} to {:?}", room_id, departed_client_id, new_host);
+
+        // The hub knows players by user id, so anonymous and unverified hosts
+        // keep no entities
+        let new_host_user = remaining
+            .iter()
+            .find(|(client_id, _, _)| Some(client_id) == new_host.as_ref())
+            .and_then(|(_, _, user_id)| user_id.clone());
+        if let Some(user_id) = new_host_user {
+            self.hand_off_host(room_id, user_id).await;
+        }
+
+        Some(HostMigration {
+            room_id: room_id.to_string(),
+            previous_host: departed_client_id.to_string(),
+            new_host,
+        })
+    }
+
+    /// Claim a hub seat for a new connection's user, unless they were already
+    /// in the room over another connection. Only token-verified users are
+    /// seated, so nobody can take a seat under another user's id.
+    async fn claim_seat(&self, conn: &WebSocketConnection) {
+        let (Some(room_id), Some(user_id), true) = (&conn.room_id, &conn.user_id, conn.authenticated) else {
+            return;
+        };
+        let Some(hub) = self.hub.read().await.clone() else {
+            return;
+        };
+        if self.user_connections_in_room(room_id, user_id).await > 1 {
+            return;
+        }
+
+        let claim = SeatClaim {
+            instance_id: room_id.clone(),
+            user_id: user_id.clone(),
+        };
+        let published = match serde_json::to_vec(&claim) {
+            Ok(payload) => hub.publish(SEAT_CLAIM_CHANNEL, payload).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = published {
+            log::warn!("Failed to claim a hub seat for {} in {}: {}", user_id, room_id, e);
+        }
+    }
+
+    /// Free the hub seat of a closed connection's user, unless they are still
+    /// in the room over another connection. Only token-verified users are
+    /// released, so nobody can free another user's seat by claiming their id.
+    async fn release_seat(&self, conn: &WebSocketConnection) {
+        let (Some(room_id), Some(user_id), true) = (&conn.room_id, &conn.user_id, conn.authenticated) else {
+            return;
+        };
+        let Some(hub) = self.hub.read().await.clone() else {
+            return;
+        };
+        if self.user_connections_in_room(room_id, user_id).await > 0 {
+            return;
+        }
+
+        let release = SeatRelease {
+            instance_id: room_id.clone(),
+            user_id: user_id.clone(),
+            reason: "disconnected".to_string(),
+        };
+        let published = match serde_json::to_vec(&release) {
+            Ok(payload) => hub.publish(OCCUPANCY_CHANNEL, payload).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = published {
+            log::warn!("Failed to release the hub seat of {} in {}: {}", user_id, room_id, e);
+        }
+    }
+
+    /// Connections in the room proven to belong to `user_id`
+    async fn user_connections_in_room(&self, room_id: &str, user_id: &str) -> usize {
+        let mut count = 0;
+        for conn_id in self.get_room_connections(room_id).await {
+            let matches = self
+                .get_connection_info(&conn_id)
+                .await
+                .is_some_and(|conn| conn.authenticated && conn.user_id.as_deref() == Some(user_id));
+            if matches {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    async fn hand_off_host(&self, room_id: &str, new_host: String) {
+        let Some(hub) = self.hub.read().await.clone() else {
+            return;
+        };
+        let handoff = HostHandoff {
+            instance_id: room_id.to_string(),
+            new_host,
+        };
+        let published = match serde_json::to_vec(&handoff) {
+            Ok(payload) => hub.publish(HOST_CHANNEL, payload).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = published {
+            log::warn!("Failed to hand the host of {} to {} in the hub: {}", room_id, handoff.new_host, e);
+        }
+    }
+
+    /// Get all connections in a room
+    pub async fn get_room_connections(&self, room_id: &str) -> Vec<String> {
+        let room_connections = self.room_connections.read().await;
+        room_connections.get(room_id).cloned().unwrap_or_default()
+    }
+
+    /// Get connection info by ID
+    pub async fn get_connection_info(&self, conn_id: &str) -> Option<WebSocketConnection> {
+        let connection_info = self.connection_info.read().await;
+        connection_info.get(conn_id).cloned()
+    }
+
+    /// IDs of every connection this instance holds, parked ones included
+    pub async fn connection_ids(&self) -> Vec<String> {
+        let connection_info = self.connection_info.read().await;
+        connection_info.keys().cloned().collect()
+    }
+
+    /// Live state of a connection on this instance
+    pub async fn session_details(&self, conn_id: &str) -> Option<SessionDetails> {
+        let conn = self.get_connection_info(conn_id).await?;
+        // Parked connections have no outbound queue until they resume
+        let queue_depth = self.connections.read().await.get(conn_id).map(|tx| tx.len());
+        let host = match (&conn.room_id, &conn.client_id) {
+            (Some(room_id), Some(client_id)) => self.get_room_host(room_id).await.as_ref() == Some(client_id),
+            _ => false,
+        };
+        let (used, burst) = self.rate_limiter().get_usage(conn_id).await;
+        let limited = self.rate_limited.read().await.contains(conn_id);
+        let rtt = self.liveness.rtt(conn_id).await;
+        let position = self.interest.read().await.position(conn_id);
+
+        Some(SessionDetails {
+            conn_id: conn.id,
+            room_id: conn.room_id,
+            user_id: conn.user_id,
+            client_id: conn.client_id,
+            team: conn.team,
+            transport: conn.transport,
+            connected_at: conn.connected_at,
+            host,
+            rtt_ms: rtt.map(|rtt| rtt.as_millis() as u64),
+            queue_depth: queue_depth.unwrap_or(0),
+            parked: queue_depth.is_none(),
+            rate_limit: RateLimitState { limited, used, burst },
+            position,
+        })
+    }
+
+    /// Live state of a room's connections on this instance
+    pub async fn room_sessions(&self, room_id: &str) -> Vec<SessionDetails> {
+        let mut sessions = Vec::new();
+        for conn_id in self.get_room_connections(room_id).await {
+            if let Some(details) = self.session_details(&conn_id).await {
+                sessions.push(details);
+            }
+        }
+        sessions
+    }
+
+    /// Live state of a user's connections on this instance, in any room
+    pub async fn user_sessions(&self, user_id: &str) -> Vec<SessionDetails> {
+        let conn_ids: Vec<String> = {
+            let connection_info = self.connection_info.read().await;
+            connection_info
+                .values()
+                .filter(|conn| conn.user_id.as_deref() == Some(user_id))
+                .map(|conn| conn.id.clone())
+                .collect()
+        };
+        let mut sessions = Vec::new();
+        for conn_id in conn_ids {
+            if let Some(details) = self.session_details(&conn_id).await {
+                sessions.push(details);
+            }
+        }
+        sessions
+    }
+
+    /// Broadcast message to all connections in a room except sender, on this
+    /// instance and on every other instance in the cluster
+    pub async fn broadcast_to_room(&self, room_id: &str, message: &[u8], exclude: Option<&str>) {
+        self.broadcast_local(room_id, message, exclude).await;
+
+        if let Some(tx) = self.cluster.read().await.as_ref() {
+            let msg = PubSubMessage::broadcast(room_id, message.to_vec(), exclude.map(str::to_string));
+            if tx.try_send(msg).is_err() {
+                log::warn!("Cluster publish queue full, broadcast in room {} stays local", room_id);
+            }
+        }
+    }
+
+    /// Broadcast message to the connections of a room on this instance only
+    pub async fn broadcast_local(&self, room_id: &str, message: &[u8], exclude: Option<&str>) {
+        self.presence_feed.publish(room_id, message);
+        if let Some(recorder) = self.recording(room_id).await {
+            recorder.record(room_id, Direction::Outbound, "", message).await;
+        }
+        let reliable = is_reliable(message);
+        let mixed = self.mix_voice(room_id, message).await;
+        let conn_ids = self.get_room_connections(room_id).await;
+        let connections = self.connections.read().await;
+        let mut resumable = if reliable {
+            Some(self.resumable.write().await)
+        } else {
+            None
+        };
+
+        let mut sent_count = 0;
+        for conn_id in conn_ids {
+            // Skip excluded connection
+            if let Some(exclude_id) = exclude {
+                if conn_id == exclude_id {
+                    continue;
+                }
+            }
+            if mixed.contains(&conn_id) {
+                continue;
+            }
+
+            // Reliable messages are sequenced and buffered even while the
+            // connection is parked, so they can be replayed on resume
+            let bytes = match resumable.as_mut().and_then(|r| r.get_mut(&conn_id)) {
+                Some(session) => session.buffer.push(message),
+                None => message.to_vec(),
+            };
+
+            if let Some(tx) = connections.get(&conn_id) {
+                if self.deliver(&conn_id, tx, WsMessage::Binary(bytes), reliable).await == Enqueued::Sent {
+                    sent_count += 1;
+                }
+            }
+        }
+
+        log::info!("Broadcast to {} clients in room {}", sent_count, room_id);
+    }
+
+    /// Queue a message for a connection, evicting the connection when it
+    /// cannot keep up
+    async fn deliver(
         &self,
-        conn_id: String,
-        room_id: Option<String>,
-        user_id: Option<String>,
-        client_id: Option<String>,
-    ) -> mpsc::UnboundedReceiver<WsMessage> {
-        // Production metrics - temporarily disabled
-        // self.metrics().metrics().record_connection().await;
-        // if let Some(ref room) = room_id {
-        //     self.metrics().room_metrics().init_room(room.clone()).await;
-        //     self.metrics().room_metrics().record_connection(room).await;
-        // }
+        conn_id: &str,
+        tx: &OutboundQueue<WsMessage>,
+        message: WsMessage,
+        reliable: bool,
+    ) -> Enqueued {
+        let len = match &message {
+            WsMessage::Binary(bytes) => bytes.len(),
+            WsMessage::Text(text) => text.len(),
+            WsMessage::Close => 0,
+        };
+
+        let outcome = tx.offer(message, reliable, Instant::now());
+        match outcome {
+            Enqueued::Sent => self.metrics().metrics().record_message_sent(len).await,
+            Enqueued::SlowConsumer => {
+                log::warn!("Disconnecting slow consumer {} ({} messages queued)", conn_id, tx.len());
+                self.metrics().metrics().record_connection_error().await;
+                tx.evict();
+            }
+            Enqueued::Shed | Enqueued::Closed => {}
+        }
+        outcome
+    }
 
-        let (tx, rx) = mpsc::unbounded_channel();
+    /// Check a client's message against its rate limit. The first rejected
+    /// message after an accepted one is answered with a `RATE_LIMITED` error
+    /// frame, so a flooding client is not flooded back.
+    pub async fn check_rate_limit(&self, conn_id: &str) -> bool {
+        match self.rate_limiter().acquire(conn_id).await {
+            Ok(()) => {
+                if self.rate_limited.read().await.contains(conn_id) {
+                    self.rate_limited.write().await.remove(conn_id);
+                }
+                true
+            }
+            Err(retry_after) => {
+                if self.rate_limited.write().await.insert(conn_id.to_string()) {
+                    log::warn!("Rate limit exceeded for {}", conn_id);
+                    if let Ok(error_bytes) = create_rate_limited_message(retry_after) {
+                        let _ = self.send_to_connection(conn_id, WsMessage::Binary(error_bytes)).await;
+                    }
+                }
+                false
+            }
+        }
+    }
 
-        // Store sender
-        let mut connections = self.connections.write().await;
-        connections.insert(conn_id.clone(), tx);
+    /// Set the radius within which a connection receives position updates at
+    /// more than the distant rate
+    pub async fn set_view_radius(&self, conn_id: &str, radius: f32) {
+        let mut interest = self.interest.write().await;
+        interest.set_view_radius(conn_id, radius);
+    }
 
-        // Store connection info
+    /// Set the team a connection chats with. Teams are assigned by the server,
+    /// never chosen by the client.
+    pub async fn set_team(&self, conn_id: &str, team: Option<String>) {
         let mut connection_info = self.connection_info.write().await;
-        connection_info.insert(
-            conn_id.clone(),
-            WebSocketConnection {
-                id: conn_id.clone(),
-                room_id: room_id.clone(),
-                connected_at: chrono::Utc::now(),
-                user_id,
-                client_id,
-            },
-        );
+        if let Some(conn) = connection_info.get_mut(conn_id) {
+            conn.team = team;
+        }
+    }
 
-         // Add to room if applicable
-        if let Some(room_id) = room_id {
-            let mut room_connections = self.room_connections.write().await;
-            room_connections
-                .entry(room_id.clone())
-                .or_insert_with(Vec::new)
-                .push(conn_id.clone());
+    /// Record the transport a connection arrived over
+    pub async fn set_transport(&self, conn_id: &str, transport: Transport) {
+        let mut connection_info = self.connection_info.write().await;
+        if let Some(conn) = connection_info.get_mut(conn_id) {
+            conn.transport = transport;
+        }
+    }
+
+    /// Deliver a connection's position updates at full rate while it speaks
+    pub async fn mark_speaking(&self, conn_id: &str) {
+        let mut interest = self.interest.write().await;
+        interest.mark_speaking(conn_id, Instant::now());
+    }
+
+    /// Queue a position update for the room's next tick, starting the room's
+    /// tick loop if it is idle
+    pub async fn queue_position_update(&self, room_id: &str, sender_id: &str, update: PositionUpdate) {
+        if let Some(position) = &update.position {
+            let position = [position.x, position.y, position.z];
+            let mut interest = self.interest.write().await;
+            interest.update_position(sender_id, position);
+            drop(interest);
+
+            let rotation = update.rotation.as_ref().map(|r| [r.x, r.y, r.z, r.w]);
+            let mut spatial = self.spatial.write().await;
+            spatial.update_pose(sender_id, position, rotation);
+        }
+
+        let mut room_frames = self.room_frames.write().await;
+        let idle = !room_frames.contains_key(room_id);
+        room_frames
+            .entry(room_id.to_string())
+            .or_default()
+            .push(sender_id, update);
+        drop(room_frames);
+
+        if idle {
+            self.start_room_tick(room_id.to_string());
+        }
+    }
+
+    /// Get the tick rate of a room
+    pub async fn room_tick_rate(&self, room_id: &str) -> u32 {
+        let room_tick_rates = self.room_tick_rates.read().await;
+        room_tick_rates.get(room_id).copied().unwrap_or(self.default_tick_rate)
+    }
+
+    /// Set the tick rate of a room, returning the rate applied after clamping
+    pub async fn set_room_tick_rate(&self, room_id: &str, rate_hz: u32) -> u32 {
+        let rate_hz = clamp_tick_rate(rate_hz);
+        let mut room_tick_rates = self.room_tick_rates.write().await;
+        room_tick_rates.insert(room_id.to_string(), rate_hz);
+        rate_hz
+    }
+
+    /// Spatial audio settings of a room; `None` when it has none
+    pub async fn spatial_config(&self, room_id: &str) -> Option<SpatialConfig> {
+        let spatial = self.spatial.read().await;
+        spatial.config(room_id).cloned()
+    }
+
+    /// Turn spatial audio on for a room, or change its settings, and send its
+    /// listeners every speaker again
+    pub async fn set_spatial_config(&self, room_id: &str, config: SpatialConfig) -> Result<()> {
+        config.validate()?;
+        let conn_ids = self.get_room_connections(room_id).await;
+        {
+            let mut spatial = self.spatial.write().await;
+            spatial.set_config(room_id, config);
+            for conn_id in &conn_ids {
+                spatial.reset(conn_id);
+            }
         }
+        self.send_spatial_audio(room_id).await;
+        Ok(())
+    }
 
-        rx
+    /// Turn spatial audio off for a room
+    pub async fn remove_spatial_config(&self, room_id: &str) -> Option<SpatialConfig> {
+        let conn_ids = self.get_room_connections(room_id).await;
+        let mut spatial = self.spatial.write().await;
+        for conn_id in &conn_ids {
+            spatial.reset(conn_id);
         }
+        spatial.remove_config(room_id)
+    }
 
-        rx
+    /// Spatial gains of speakers by client id, for each of `conn_ids`
+    pub async fn spatial_gains(&self, conn_ids: &[String]) -> HashMap<String, HashMap<String, f32>> {
+        let spatial = self.spatial.read().await;
+        conn_ids
+            .iter()
+            .map(|conn_id| (conn_id.clone(), spatial.gains(conn_id)))
+            .collect()
     }
 
-    /// Remove a connection
-    pub async fn remove_connection(&self, conn_id: &str) -> Option<WebSocketConnection> {
-        let mut connections = self.connections.write().await;
-        connections.remove(conn_id);
+    /// Send each listener in a spatial room the speakers it now hears
+    /// differently
+    async fn send_spatial_audio(&self, room_id: &str) {
+        let conn_ids = self.get_room_connections(room_id).await;
+        let speakers: Vec<(String, String)> = {
+            let connection_info = self.connection_info.read().await;
+            conn_ids
+                .iter()
+                .map(|conn_id| {
+                    let client_id = connection_info
+                        .get(conn_id)
+                        .and_then(|conn| conn.client_id.clone())
+                        .unwrap_or_else(|| conn_id.clone());
+                    (conn_id.clone(), client_id)
+                })
+                .collect()
+        };
 
-        // Production cleanup
-        // self.rate_limiter().remove(conn_id).await;
-        // self.message_queue().remove_queue(conn_id).await;
+        let frames: Vec<(String, Vec<u8>)> = {
+            let mut spatial = self.spatial.write().await;
+            if spatial.config(room_id).is_none() {
+                return;
+            }
+            conn_ids
+                .iter()
+                .filter_map(|conn_id| {
+                    let changes = spatial.changes(room_id, conn_id, &speakers);
+                    if changes.is_empty() {
+                        return None;
+                    }
+                    MessageParser::serialize(&MessageBuilder::spatial_audio(changes))
+                        .ok()
+                        .map(|bytes| (conn_id.clone(), bytes))
+                })
+                .collect()
+        };
 
-        let mut connection_info = self.connection_info.write().await;
-        if let Some(conn) = connection_info.remove(conn_id) {
-            // Record metrics - temporarily disabled
-            // self.metrics().metrics().record_disconnection().await;
+        // Changes are not repeated, so they are sent reliably
+        for (conn_id, bytes) in frames {
+            let _ = self.send_to_connection(&conn_id, WsMessage::Binary(bytes)).await;
+        }
+    }
 
-            // Remove from room
-            if let Some(room_id) = &conn.room_id {
-                // self.metrics().room_metrics().record_disconnection(room_id).await;
+    /// Run a room's tick loop until a tick finds no updates
+    fn start_room_tick(&self, room_id: String) {
+        let ws_manager = self.clone();
 
-                let mut room_connections = self.room_connections.write().await;
-                if let Some(conns) = room_connections.get_mut(room_id) {
-                    conns.retain(|id| id != conn_id);
-                    if conns.is_empty() {
-                        room_connections.remove(room_id);
-                        // self.metrics().room_metrics().remove_room(room_id).await;
-                    }
+        tokio::spawn(async move {
+            let mut rate_hz = ws_manager.room_tick_rate(&room_id).await;
+            let mut interval = tokio::time::interval(tick_interval(rate_hz));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+            loop {
+                interval.tick().await;
+                if !ws_manager.flush_room_tick(&room_id).await {
+                    break;
+                }
+
+                // Pick up rate changes made while the loop is running
+                let current = ws_manager.room_tick_rate(&room_id).await;
+                if current != rate_hz {
+                    rate_hz = current;
+                    interval = tokio::time::interval(tick_interval(rate_hz));
+                    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
                 }
             }
-            Some(conn)
-        } else {
-            None
-        }
-    }
 
-    /// Get all connections in a room
-    pub async fn get_room_connections(&self, room_id: &str) -> Vec<String> {
-        let room_connections = self.room_connections.read().await;
-        room_connections.get(room_id).cloned().unwrap_or_default()
+            log::debug!("Tick loop for room {} is idle", room_id);
+        });
     }
 
-    /// Get connection info by ID
-    pub async fn get_connection_info(&self, conn_id: &str) -> Option<WebSocketConnection> {
-        let connection_info = self.connection_info.read().await;
-        connection_info.get(conn_id).cloned()
-    }
+    /// Send each client one frame with the updates collected since the previous
+    /// tick, thinned by area of interest. Returns false when there was nothing
+    /// to send, which lets the loop stop until the next update arrives.
+    async fn flush_room_tick(&self, room_id: &str) -> bool {
+        let (tick, updates) = {
+            let mut room_frames = self.room_frames.write().await;
+            let Some(frame) = room_frames.get_mut(room_id) else {
+                return false;
+            };
+            if frame.is_empty() {
+                room_frames.remove(room_id);
+                return false;
+            }
+            frame.advance()
+        };
+
+        // Recordings keep the whole frame, before area of interest thins it
+        if let Some(recorder) = self.recording(room_id).await {
+            let batch = updates.iter().map(|(_, update)| update.clone()).collect();
+            if let Ok(bytes) = MessageParser::serialize(&MessageBuilder::position_batch(batch, tick)) {
+                recorder.record(room_id, Direction::Outbound, "", &bytes).await;
+            }
+        }
 
-    /// Broadcast message to all connections in a room except sender
-    pub async fn broadcast_to_room(&self, room_id: &str, message: &[u8], exclude: Option<&str>) {
         let conn_ids = self.get_room_connections(room_id).await;
-        let connections = self.connections.read().await;
+        let now = Instant::now();
+
+        let frames: Vec<(String, Vec<u8>)> = {
+            let mut interest = self.interest.write().await;
+            conn_ids
+                .iter()
+                .filter_map(|conn_id| {
+                    let batch: Vec<PositionUpdate> = updates
+                        .iter()
+                        .filter(|(sender_id, _)| sender_id != conn_id)
+                        .filter(|(sender_id, update)| {
+                            interest.should_deliver(conn_id, sender_id, &update.entity_id, now)
+                        })
+                        .map(|(_, update)| update.clone())
+                        .collect();
+                    if batch.is_empty() {
+                        return None;
+                    }
 
-        let mut sent_count = 0;
-        for conn_id in conn_ids {
-            // Skip excluded connection
-            if let Some(exclude_id) = exclude {
-                if conn_id == exclude_id {
-                    continue;
-                }
-            }
+                    let message = MessageBuilder::position_batch(batch, tick);
+                    MessageParser::serialize(&message)
+                        .ok()
+                        .map(|bytes| (conn_id.clone(), bytes))
+                })
+                .collect()
+        };
 
+        let connections = self.connections.read().await;
+        for (conn_id, bytes) in frames {
             if let Some(tx) = connections.get(&conn_id) {
-                match tx.send(WsMessage::Binary(message.to_vec())) {
-                    Ok(_) => sent_count += 1,
-                    Err(e) => log::warn!("Failed to send to {}: {}", conn_id, e),
-                }
+                self.deliver(&conn_id, tx, WsMessage::Binary(bytes), false).await;
             }
         }
+        drop(connections);
 
-        log::info!("Broadcast to {} clients in room {}", sent_count, room_id);
+        // Someone moved, so listeners may hear speakers differently
+        self.send_spatial_audio(room_id).await;
+
+        true
     }
 
     /// Send message to specific connection
     pub async fn send_to_connection(&self, conn_id: &str, message: WsMessage) -> Result<()> {
         let connections = self.connections.read().await;
         if let Some(tx) = connections.get(conn_id) {
-            tx.send(message)
-                .map_err(|e| reticulum_core::Error::internal(format!("Failed to send message: {}", e)))?;
+            let (message, reliable) = match message {
+                WsMessage::Binary(bytes) if is_reliable(&bytes) => {
+                    let mut resumable = self.resumable.write().await;
+                    match resumable.get_mut(conn_id) {
+                        Some(session) => (WsMessage::Binary(session.buffer.push(&bytes)), true),
+                        None => (WsMessage::Binary(bytes), true),
+                    }
+                }
+                WsMessage::Binary(bytes) => (WsMessage::Binary(bytes), false),
+                other => (other, true),
+            };
+            match self.deliver(conn_id, tx, message, reliable).await {
+                Enqueued::Sent | Enqueued::Shed => {}
+                Enqueued::SlowConsumer | Enqueued::Closed => {
+                    return Err(reticulum_core::Error::internal(format!(
+                        "Failed to send message to {}",
+                        conn_id
+                    )));
+                }
+            }
         }
         Ok(())
     }
@@ -323,7 +1355,12 @@ pub async fn websocket_handler(
     // Parse query parameters from query string
     let query_string = req.query_string();
     let mut user_id_param = None;
+    let mut token_param = None;
     let mut client_id_param = None;
+    let mut resume_token_param = None;
+    let mut last_seq_param = None;
+    let mut view_radius_param = None;
+    let mut voice_param = None;
 
     for pair in query_string.split('&') {
         let mut parts = pair.splitn(2, '=');
@@ -331,7 +1368,12 @@ pub async fn websocket_handler(
             if let Some(value) = parts.next() {
                 match key {
                     "user_id" => user_id_param = Some(value.to_string()),
+                    "token" => token_param = Some(value.to_string()),
                     "client_id" => client_id_param = Some(value.to_string()),
+                    "resume_token" => resume_token_param = Some(value.to_string()),
+                    "last_seq" => last_seq_param = value.parse::<u32>().ok(),
+                    "view_radius" => view_radius_param = value.parse::<f32>().ok(),
+                    "voice" => voice_param = Some(value.to_string()),
                     _ => {}
                 }
             }
@@ -346,44 +1388,150 @@ pub async fn websocket_handler(
         client_id_param
     );
 
-    // Determine user_id and client_id (from auth or query params)
-    let (user_id, client_id) = if let Some(uid) = user_id_param {
-        (Some(uid), client_id_param)
-    } else {
-        (user_id_param, client_id_param)
+    // Only an access token proves who the user is; browsers cannot set
+    // headers on a WebSocket, so it may come as a query parameter
+    let token = token_param.or_else(|| {
+        req.headers()
+            .get("Authorization")
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.strip_prefix("Bearer "))
+            .map(str::to_string)
+    });
+    let verified_user_id = match token {
+        Some(token) => match ws_manager.verify_user(&token).await {
+            Ok(user_id) => Some(user_id),
+            Err(e) => {
+                log::info!("Refusing connection {} to room {}: {}", conn_id, room_id, e);
+                return Ok(actix_web::ResponseError::error_response(&e));
+            }
+        },
+        None => None,
     };
 
+    // A bare `user_id` is only a label and cannot claim a verified identity
+    let user_id = verified_user_id.clone().or(user_id_param);
+    let client_id = client_id_param;
+
+    // A draining instance sends its clients elsewhere and takes no new ones
+    if ws_manager.is_draining().await {
+        log::info!("Refusing connection {} to room {}: instance is draining", conn_id, room_id);
+        return Ok(HttpResponse::ServiceUnavailable()
+            .insert_header(("Retry-After", "1"))
+            .json(serde_json::json!({
+                "error": "draining",
+                "message": "This instance is shutting down"
+            })));
+    }
+
+    // Banned users and joins to locked rooms are turned away before the upgrade
+    if let Err(e) = ws_manager.admit(&room_id, verified_user_id.as_deref()).await {
+        log::info!("Refusing connection {} to room {}: {}", conn_id, room_id, e);
+        return Ok(actix_web::ResponseError::error_response(&e));
+    }
+
     // Start WebSocket using actix-ws handle API
     let (response, mut session, mut msg_stream) = actix_ws::handle(&req, stream)?;
 
-    // Register connection and get channel for sending
-    let mut rx = ws_manager.add_connection(conn_id.clone(), Some(room_id.clone()), user_id, client_id.clone()).await;
+    // Take over a dropped session rather than joining as a new participant
+    let resumed = match resume_token_param.as_deref() {
+        Some(token) => match ws_manager
+            .resume_connection(token, &room_id, last_seq_param.unwrap_or(0))
+            .await
+        {
+            ResumeOutcome::Resumed(resumed) => Some(resumed),
+            ResumeOutcome::Expired(stale_conn_id) => {
+                close_connection(&ws_manager, &stale_conn_id).await;
+                None
+            }
+            ResumeOutcome::Unknown => None,
+        },
+        None => None,
+    };
+
+    // Or take over a session a draining instance sent here
+    let migrated = match (&resumed, resume_token_param.as_deref()) {
+        (None, Some(token)) => ws_manager.claim_migration(token, &room_id).await,
+        _ => None,
+    };
+
+    let (conn_id, attachment, mut rx) = match resumed {
+        Some(resumed) => {
+            log::info!(
+                "Resumed connection {} in room {} ({} messages replayed)",
+                resumed.conn_id,
+                room_id,
+                resumed.replayed
+            );
+            (resumed.conn_id, resumed.attachment, resumed.rx)
+        }
+        None => {
+            // A moved session keeps the identity it had on the draining instance
+            let (conn_id, user_id, client_id, team) = match &migrated {
+                Some(ticket) => {
+                    log::info!("Connection {} moved here from a draining instance", ticket.conn_id);
+                    (
+                        ticket.conn_id.clone(),
+                        ticket.user_id.clone(),
+                        ticket.client_id.clone(),
+                        ticket.team.clone(),
+                    )
+                }
+                None => (conn_id, user_id, client_id, None),
+            };
+
+            // Register connection and get channel for sending
+            let rx = register_client(
+                &ws_manager,
+                conn_id.clone(),
+                &room_id,
+                user_id,
+                verified_user_id.as_deref(),
+                client_id.clone(),
+            )
+            .await;
+            let resume_token = ws_manager.enable_resume(&conn_id).await;
+            if let Some(radius) = view_radius_param {
+                ws_manager.set_view_radius(&conn_id, radius).await;
+            }
+            if team.is_some() {
+                ws_manager.set_team(&conn_id, team).await;
+            }
+            if voice_param.as_deref() == Some("mixed") {
+                ws_manager.join_voice_mix(&room_id, &conn_id, client_id.clone()).await;
+            }
+            if migrated.is_some() {
+                // The room already knows the client, so it is only greeted
+                if let Ok(hello_bytes) = create_server_hello_msg(&room_id, &conn_id, &resume_token, true) {
+                    let _ = ws_manager.send_to_connection(&conn_id, WsMessage::Binary(hello_bytes)).await;
+                }
+                ws_manager.track_user_presence(&conn_id).await;
+            } else {
+                announce_join(&ws_manager, &conn_id, &room_id, client_id.as_deref(), &resume_token).await;
+            }
+
+            (conn_id, 0, rx)
+        }
+    };
 
     let conn_id_clone = conn_id.clone();
     let room_id_clone = room_id.clone();
     let ws_manager_clone = ws_manager.clone();
-    let client_id_for_broadcast = client_id.clone();
+    let liveness = ws_manager.liveness().clone();
+    liveness.register(&conn_id).await;
 
-    // Broadcast PRESENCE_JOIN event to room with host information
-    if let Some(existing_client_id) = &client_id_for_broadcast {
-        if let Ok(presence_bytes) = create_presence_join_message(
-            existing_client_id,
-            &room_id_clone,
-            &host_client_id,
-        ) {
-            // Send to new client
-            let _ = ws_manager.send_to_connection(&conn_id, WsMessage::Binary(presence_bytes.clone())).await;
-            // Broadcast to existing clients
-            ws_manager.broadcast_to_room(&room_id_clone, &presence_bytes, None).await;
-        }
-    }
     actix_web::rt::spawn(async move {
+        let mut clean_close = false;
+        let mut close_reason = None;
+        let ping_interval = liveness.config().ping_interval;
+        let mut ping_timer = tokio::time::interval_at(tokio::time::Instant::now() + ping_interval, ping_interval);
+
         loop {
             tokio::select! {
                 // Receive messages from client
                 result = msg_stream.next() => {
                     match result {
                         Some(Ok(msg)) => {
+                            liveness.seen(&conn_id_clone).await;
                             match msg {
                                 Message::Binary(bytes) => {
                                     log::info!("Received {} bytes from {} in room {}", bytes.len(), conn_id_clone, room_id_clone);
@@ -413,10 +1561,16 @@ pub async fn websocket_handler(
                                 }
                                 Message::Close(reason) => {
                                     log::info!("Client {} disconnected: {:?}", conn_id_clone, reason);
+                                    clean_close = true;
                                     break;
                                 }
-                                Message::Nop | Message::Continuation(_) | Message::Pong(_) => {
-                                    // Ignore nop, continuation and pong frames
+                                Message::Pong(bytes) => {
+                                    if let Some(rtt) = liveness.pong(&conn_id_clone, &bytes).await {
+                                        ws_manager_clone.metrics().latency().record(rtt).await;
+                                    }
+                                }
+                                Message::Nop | Message::Continuation(_) => {
+                                    // Ignore nop and continuation frames
                                 }
                             }
                         }
@@ -430,8 +1584,25 @@ pub async fn websocket_handler(
                         }
                     }
                 }
+                // Browsers answer pings by themselves; the reaper closes
+                // connections that stop answering
+                _ = ping_timer.tick() => {
+                    let payload = liveness.ping(&conn_id_clone).await;
+                    if session.ping(&payload).await.is_err() {
+                        break;
+                    }
+                }
+                // Disconnect when the client cannot keep up; the session is
+                // parked, so the client can resume once it catches up
+                _ = rx.evicted.notified() => {
+                    close_reason = Some(actix_ws::CloseReason {
+                        code: actix_ws::CloseCode::Again,
+                        description: Some("slow consumer".to_string()),
+                    });
+                    break;
+                }
                 // Send messages to client
-                msg = rx.recv() => {
+                msg = rx.queue.recv() => {
                     match msg {
                         Some(WsMessage::Text(text)) => {
                             if session.text(text).await.is_err() {
@@ -448,80 +1619,202 @@ pub async fn websocket_handler(
                             return; // Exit immediately after close
                         }
                         None => {
-                            // Channel closed
-                            break;
+                            // Sender dropped: a resumed socket took the
+                            // connection over, or it was already removed
+                            let _ = session.close(None).await;
+                            return;
                         }
                     }
                 }
             }
         }
 
-        // Cleanup on disconnect
-        log::info!("Cleaning up connection {}", conn_id_clone);
-        ws_manager_clone.remove_connection(&conn_id_clone).await;
-        let _ = session.close(None).await;
+        // Keep the session for a reconnect unless the client said goodbye,
+        // or while draining until another instance claims it
+        let park = !clean_close || ws_manager_clone.is_draining().await;
+        match ws_manager_clone
+            .detach_connection(&conn_id_clone, attachment, park)
+            .await
+        {
+            Detached::Parked => {
+                log::info!("Connection {} parked for resumption", conn_id_clone);
+                liveness.unregister(&conn_id_clone).await;
+            }
+            Detached::Superseded => {}
+            Detached::Released => {
+                log::info!("Cleaning up connection {}", conn_id_clone);
+                close_connection(&ws_manager_clone, &conn_id_clone).await;
+            }
+        }
+        let _ = session.close(close_reason).await;
     });
 
-    // Send initial server hello (protobuf binary)
-    let hello_bytes = create_server_hello_msg(&room_id, &conn_id);
-    let _ = ws_manager
-        .send_to_connection(&conn_id, WsMessage::Binary(hello_bytes))
-        .await;
-
     log::info!("WebSocket connection established: {} in room {}", conn_id, room_id);
 
     Ok(response)
 }
 
+/// Register a new connection of `user_id` to `room_id`.
+///
+/// Shared by every transport. The connection speaks for `user_id` only when an
+/// access token proved it, as `verified_user_id`; otherwise the id is a label
+/// that history, moderation and host handoffs ignore.
+pub(crate) async fn register_client(
+    ws_manager: &WebSocketManager,
+    conn_id: String,
+    room_id: &str,
+    user_id: Option<String>,
+    verified_user_id: Option<&str>,
+    client_id: Option<String>,
+) -> OutboundReceiver<WsMessage> {
+    match user_id {
+        Some(user_id) if verified_user_id == Some(user_id.as_str()) => {
+            ws_manager
+                .add_authenticated_connection(conn_id, Some(room_id.to_string()), user_id, client_id)
+                .await
+        }
+        user_id => ws_manager.add_connection(conn_id, Some(room_id.to_string()), user_id, client_id).await,
+    }
+}
+
+/// Greet a newly registered connection and announce it to its room.
+///
+/// Shared by every transport, so WebTransport sessions show up in the room the
+/// same way WebSocket clients do. `resume_token` is empty for transports that
+/// cannot resume.
+pub(crate) async fn announce_join(
+    ws_manager: &WebSocketManager,
+    conn_id: &str,
+    room_id: &str,
+    client_id: Option<&str>,
+    resume_token: &str,
+) {
+    // Send initial server hello (protobuf binary)
+    if let Ok(hello_bytes) = create_server_hello_msg(room_id, conn_id, resume_token, false) {
+        let _ = ws_manager
+            .send_to_connection(conn_id, WsMessage::Binary(hello_bytes))
+            .await;
+    }
+
+    let host_client_id = ws_manager.get_room_host(room_id).await.unwrap_or_default();
+    // Peers address whispers to the joining user, so only a verified id is shown
+    let user_id = ws_manager
+        .get_connection_info(conn_id)
+        .await
+        .filter(|conn| conn.authenticated)
+        .and_then(|conn| conn.user_id)
+        .unwrap_or_default();
+
+    // Broadcast PRESENCE_JOIN event to room with host information
+    if let Some(existing_client_id) = client_id {
+        if let Ok(presence_bytes) = create_presence_join_message(
+            existing_client_id,
+            room_id,
+            &host_client_id,
+            &user_id,
+        ) {
+            // Send to new client
+            let _ = ws_manager.send_to_connection(conn_id, WsMessage::Binary(presence_bytes.clone())).await;
+            // Broadcast to existing clients
+            ws_manager.broadcast_to_room(room_id, &presence_bytes, None).await;
+        }
+    }
+
+    // Catch the newcomer up on the conversation
+    ws_manager.replay_chat(conn_id).await;
+
+    ws_manager.track_user_presence(conn_id).await;
+}
+
+/// Remove a connection and what this instance tracks for it
+async fn release_connection(ws_manager: &WebSocketManager, conn_id: &str) -> Option<WebSocketConnection> {
+    let conn = ws_manager.remove_connection(conn_id).await?;
+    ws_manager.liveness().unregister(conn_id).await;
+    ws_manager.untrack_user_presence(conn_id).await;
+    if let Some(room_id) = &conn.room_id {
+        ws_manager.leave_voice_mix(room_id, conn_id).await;
+    }
+    Some(conn)
+}
+
+/// Remove a connection another instance took over. The client is still in
+/// the room, so no leave is announced and its calls stay up.
+pub(crate) async fn hand_off_connection(ws_manager: &WebSocketManager, conn_id: &str) {
+    release_connection(ws_manager, conn_id).await;
+}
+
+/// Remove a connection for good, free its hub seat and hand its host role on
+pub(crate) async fn close_connection(ws_manager: &WebSocketManager, conn_id: &str) {
+    let Some(conn) = release_connection(ws_manager, conn_id).await else {
+        return;
+    };
+
+    if let (Some(room_id), Some(client_id)) = (&conn.room_id, &conn.client_id) {
+        if let Ok(leave_bytes) = create_presence_leave_message(client_id) {
+            ws_manager.broadcast_to_room(room_id, &leave_bytes, None).await;
+        }
+        ws_manager.hang_up_calls(room_id, client_id).await;
+    }
+
+    // The client never leaves its hub instance itself when the connection dies
+    ws_manager.release_seat(&conn).await;
+
+    // Hand the host role on so host-driven scenes keep running
+    if let (Some(room_id), Some(client_id)) = (conn.room_id, conn.client_id) {
+        if let Some(migration) = ws_manager.migrate_room_host(&room_id, &client_id).await {
+            if let Some(new_host) = &migration.new_host {
+                if let Ok(host_bytes) = create_host_changed_message(&migration.previous_host, new_host) {
+                    ws_manager.broadcast_to_room(&room_id, &host_bytes, None).await;
+                }
+            }
+        }
+    }
+}
+
+/// Whether a message must survive a reconnect.
+///
+/// Position and voice updates are superseded by the next one and hellos belong
+/// to a single socket. Payloads that are not protobuf messages are sent
+/// unsequenced.
+fn is_reliable(message: &[u8]) -> bool {
+    match MessageParser::parse(message) {
+        Ok(parsed) => !matches!(
+            MessageType::try_from(parsed.r#type),
+            Ok(MessageType::PositionUpdate
+                | MessageType::PositionBatch
+                | MessageType::VoiceData
+                | MessageType::ClientHello
+                | MessageType::ServerHello
+                | MessageType::Error)
+        ),
+        Err(_) => false,
+    }
+}
+
 /// Handle incoming client message
-async fn handle_client_message(
+pub(crate) async fn handle_client_message(
     ws_manager: &WebSocketManager,
     room_id: &str,
     sender_id: &str,
     message: &[u8],
 ) -> Result<()> {
-    // Production metrics - temporarily disabled
-    // ws_manager.metrics().metrics().record_message_received(message.len()).await;
-    // ws_manager.metrics().room_metrics().record_message_received(room_id).await;
-
-    // Per-connection rate limit check - temporarily disabled
-    // if let Err(e) = ws_manager.rate_limiter().check_rate_limit(sender_id).await {
-    //     log::warn!("Per-connection rate limit exceeded for {}: {:?}", sender_id, e);
-    //     return Err(e);
-    // }
-
-    // Broadcast message to room (excluding sender)
-    // Note: Protobuf parsing disabled for now, treats all messages as binary
-    let room_connections = ws_manager.get_room_connections(room_id).await;
-    log::info!(
-        "Broadcasting {} bytes from {} in room {} to {} other clients",
-        message.len(),
-        sender_id,
-        room_id,
-        room_connections.len().saturating_sub(1)
-    );
+    ws_manager.metrics().metrics().record_message_received(message.len()).await;
+    ws_manager.metrics().room_metrics().record_message_received(room_id).await;
 
-    ws_manager
-        .broadcast_to_room(room_id, message, Some(sender_id))
-        .await;
+    // Per-connection rate limit check; the client is told with an error frame
+    if !ws_manager.check_rate_limit(sender_id).await {
+        return Ok(());
+    }
+    ws_manager.record_inbound(room_id, sender_id, message).await;
 
-    // Production metrics - temporarily disabled
-    // ws_manager.metrics().room_metrics().record_message_sent(room_id).await;
-    // ws_manager.metrics().metrics().record_message_sent(message.len()).await;
+    // The handler chain decides where the message goes; by default position
+    // updates join the room's next tick and everything else goes to the room
+    let handlers = ws_manager.handlers().await;
+    route_message(&handlers, ws_manager, room_id, sender_id, message).await?;
 
-    Ok(())
-}
+    ws_manager.metrics().room_metrics().record_message_sent(room_id).await;
 
-/// Create server hello message
-fn create_server_hello(room_id: &str, client_id: &str) -> String {
-    // Create a JSON message for now
-    // In production, this would be a proper protobuf message
-    format!(
-        r#"{{"type":"SERVER_HELLO","room_id":"{}","client_id":"{}","timestamp":{}}}"#,
-        room_id,
-        client_id,
-        chrono::Utc::now().timestamp()
-    )
+    Ok(())
 }
 
 /// Get WebSocket connection stats
@@ -548,10 +1841,102 @@ pub async fn get_stats(
     HttpResponse::Ok().json(serde_json::json!({
         "room_id": room_id,
         "connection_count": connection_info.len(),
+        "tick_rate_hz": ws_manager.room_tick_rate(&room_id).await,
         "connections": connection_info
     }))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct TickRateRequest {
+    pub rate_hz: u32,
+}
+
+/// Check that the caller may change `room_id`'s settings: an admin, a
+/// moderator or the room's creator
+async fn authorize_room_settings(
+    req: &HttpRequest,
+    moderation: &ModerationManager,
+    ws_manager: &WebSocketManager,
+    room_id: &str,
+) -> Result<String> {
+    let moderator = moderation.authenticate(req)?;
+    match moderation.authority(ws_manager, &moderator, room_id).await? {
+        Authority::Admin | Authority::Moderator | Authority::RoomOwner => Ok(moderator.user_id),
+        Authority::RoomHost | Authority::None => Err(reticulum_core::Error::authorization(format!(
+            "Not allowed to change the settings of room {}",
+            room_id
+        ))),
+    }
+}
+
+/// Set the position update tick rate for a room
+pub async fn set_tick_rate(
+    http_req: HttpRequest,
+    moderation: web::Data<ModerationManager>,
+    ws_manager: web::Data<WebSocketManager>,
+    room_id: web::Path<String>,
+    req: web::Json<TickRateRequest>,
+) -> HttpResponse {
+    let room_id = room_id.into_inner();
+    if let Err(e) = authorize_room_settings(&http_req, &moderation, &ws_manager, &room_id).await {
+        return actix_web::ResponseError::error_response(&e);
+    }
+    let rate_hz = ws_manager.set_room_tick_rate(&room_id, req.rate_hz).await;
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "room_id": room_id,
+        "tick_rate_hz": rate_hz
+    }))
+}
+
+/// Get the spatial audio settings of a room
+pub async fn get_spatial_audio(
+    ws_manager: web::Data<WebSocketManager>,
+    room_id: web::Path<String>,
+) -> HttpResponse {
+    let room_id = room_id.into_inner();
+    match ws_manager.spatial_config(&room_id).await {
+        Some(config) => HttpResponse::Ok().json(config),
+        None => actix_web::ResponseError::error_response(&reticulum_core::Error::not_found(format!(
+            "Room {} has no spatial audio",
+            room_id
+        ))),
+    }
+}
+
+/// Turn spatial audio on for a room, or change its settings
+pub async fn set_spatial_audio(
+    http_req: HttpRequest,
+    moderation: web::Data<ModerationManager>,
+    ws_manager: web::Data<WebSocketManager>,
+    room_id: web::Path<String>,
+    req: web::Json<SpatialConfig>,
+) -> HttpResponse {
+    let room_id = room_id.into_inner();
+    if let Err(e) = authorize_room_settings(&http_req, &moderation, &ws_manager, &room_id).await {
+        return actix_web::ResponseError::error_response(&e);
+    }
+    let config = req.into_inner();
+    match ws_manager.set_spatial_config(&room_id, config.clone()).await {
+        Ok(()) => HttpResponse::Ok().json(config),
+        Err(e) => actix_web::ResponseError::error_response(&e),
+    }
+}
+
+/// Turn spatial audio off for a room
+pub async fn remove_spatial_audio(
+    req: HttpRequest,
+    moderation: web::Data<ModerationManager>,
+    ws_manager: web::Data<WebSocketManager>,
+    room_id: web::Path<String>,
+) -> HttpResponse {
+    if let Err(e) = authorize_room_settings(&req, &moderation, &ws_manager, &room_id).await {
+        return actix_web::ResponseError::error_response(&e);
+    }
+    ws_manager.remove_spatial_config(&room_id).await;
+    HttpResponse::NoContent().finish()
+}
+
 /// Get all WebSocket connections
 pub async fn get_all_stats(ws_manager: web::Data<WebSocketManager>) -> HttpResponse {
     let total_count = ws_manager.connection_count().await;
@@ -564,57 +1949,34 @@ pub async fn get_all_stats(ws_manager: web::Data<WebSocketManager>) -> HttpRespo
 
 /// Get performance metrics
 pub async fn get_metrics(ws_manager: web::Data<WebSocketManager>) -> HttpResponse {
-    // Collect metrics from WebSocket manager
-    let sessions = ws_manager.get_all_sessions();
-    
-    // Calculate basic metrics
-    let total_connections = sessions.len();
-    let active_connections = sessions.iter().filter(|s| s.is_active()).count();
-    let total_messages_sent: u64 = sessions.iter()
-        .map(|s| s.messages_sent())
-        .sum();
-    
-    // Get latency metrics (average heartbeat round-trip time)
-    let latency_samples: Vec<u64> = sessions.iter()
-        .flat_map(|s| {
-            let sessions: Vec<_> = s.get_sessions().iter().collect();
-            sessions.iter().filter_map(|sess| {
-                sess.last_heartbeat.map(|lh| {
-                    lh.saturating_sub(lh, lh).unwrap_or(0)
-                })
-            })
-        })
-        .collect();
-    
-    let avg_latency = if latency_samples.is_empty() {
-        0
-    } else {
-        latency_samples.iter().sum::<u64>() / latency_samples.len() as u64
-    };
-
-    let current_time = chrono::Utc::now();
+    let monitor = ws_manager.metrics();
+    let global = monitor.metrics().get_metrics().await;
+    let rooms = monitor.room_metrics().get_all_metrics().await;
+    let rate_limits = ws_manager.rate_limiter().get_metrics().await;
 
     HttpResponse::Ok().json(serde_json::json!({
         "service": "reticulum-presence",
-        "timestamp": current_time.to_rfc3339(),
+        "timestamp": chrono::Utc::now().to_rfc3339(),
         "metrics": {
             "connections": {
-                "total": total_connections,
-                "active": active_connections,
+                "total": global.total_connections,
+                "active": global.active_connections,
+                "disconnections": global.total_disconnections,
+                "errors": global.connection_errors,
             },
-            "performance": {
-                "total_messages_sent": total_messages_sent,
-                "avg_latency_ms": avg_latency,
+            "messages": {
+                "sent": global.total_messages_sent,
+                "received": global.total_messages_received,
+                "bytes_sent": global.total_bytes_sent,
+                "bytes_received": global.total_bytes_received,
+                "errors": global.message_errors,
             },
-            "uptime_seconds": sessions.iter()
-                .filter(|s| s.is_active())
-                .map(|s| {
-                    s.connected_at.as_ref()
-                        .map(|ca| {
-                            ca.saturating_sub(current_time, ca).unwrap_or(0).num_seconds().unwrap_or(0)
-                        })
-                        .sum::<u64>()
-                })
+            "rate_limiting": {
+                "total_requests": rate_limits.total_requests,
+                "blocked_requests": rate_limits.blocked_requests,
+            },
+            "active_rooms": rooms.len(),
+            "uptime_seconds": monitor.metrics().get_uptime_secs(),
         }
     }))
 }
@@ -624,73 +1986,165 @@ fn create_presence_join_message(
     client_id: &str,
     room_id: &str,
     host_client_id: &str,
+    user_id: &str,
 ) -> Result<Vec<u8>> {
-    let presence_event = PresenceEvent {
-        event_type: Some(EventType::Join as i32),
-        client_id: client_id.to_string(),
-        data: Some(PresenceData {
-            host_client_id: Some(host_client_id.to_string()),
-            room_id: Some(room_id.to_string()),
-            ..Default::default()
-        }),
-    };
-    
-    let message = Message {
-        message_id: uuid::Uuid::new_v4().to_string(),
-        timestamp: chrono::Utc::now().timestamp(),
-        r#type: MessageType::PresenceJoin as i32,
-        presence_event: Some(presence_event),
+    let data = PresenceData {
+        host_client_id: host_client_id.to_string(),
+        room_id: room_id.to_string(),
+        user_id: user_id.to_string(),
         ..Default::default()
     };
-    
-    message.encode_to_vec().map_err(|e| {
+    let mut message = MessageBuilder::presence_event(client_id.to_string(), PresenceEventType::Join, Some(data));
+    message.r#type = MessageType::PresenceJoin as i32;
+    MessageParser::serialize(&message).map_err(|e| {
         reticulum_core::Error::internal(format!("Failed to encode presence join message: {}", e))
     })
 }
 
-/// Create server hello message (protobuf)
-fn create_server_hello_msg(room_id: &str, conn_id: &str) -> Vec<u8> {
-    let server_hello = ServerHello {
-        room_id: room_id.to_string(),
-        client_id: conn_id.to_string(),
-        timestamp: chrono::Utc::now().timestamp(),
-    };
-    
-    let message = Message {
-        message_id: uuid::Uuid::new_v4().to_string(),
-        timestamp: chrono::Utc::now().timestamp(),
-        r#type: MessageType::ServerHello as i32,
-        server_hello: Some(server_hello),
-        ..Default::default()
-    };
-    
-    message.encode_to_vec()
+/// Create PRESENCE_LEAVE event for a departed client
+fn create_presence_leave_message(client_id: &str) -> Result<Vec<u8>> {
+    let mut message = MessageBuilder::presence_event(client_id.to_string(), PresenceEventType::Leave, None);
+    message.r#type = MessageType::PresenceLeave as i32;
+    MessageParser::serialize(&message).map_err(|e| {
+        reticulum_core::Error::internal(format!("Failed to encode presence leave message: {}", e))
+    })
 }
 
-    let current_time = chrono::Utc::now();
+/// Create PRESENCE_HOST_CHANGED event announcing the new room host
+fn create_host_changed_message(previous_host: &str, new_host: &str) -> Result<Vec<u8>> {
+    let message = MessageBuilder::host_changed(previous_host.to_string(), new_host.to_string());
+    MessageParser::serialize(&message).map_err(|e| {
+        reticulum_core::Error::internal(format!("Failed to encode host changed message: {}", e))
+    })
+}
 
-    HttpResponse::Ok().json(serde_json::json!({
-        "service": "reticulum-presence",
-        "timestamp": current_time.to_rfc3339(),
-        "metrics": {
-            "connections": {
-                "total": total_connections,
-                "active": active_connections,
-            },
-            "performance": {
-                "total_messages_sent": total_messages_sent,
-                "avg_latency_ms": avg_latency,
-            },
-            "uptime_seconds": sessions.iter()
-                .filter(|s| s.is_active())
-                .map(|s| {
-                    s.connected_at.as_ref()
-                        .map(|ca| {
-                            ca.saturating_sub(current_time, ca).unwrap_or(0).num_seconds().unwrap_or(0)
-                        })
-                        .sum::<u64>()
-                })
-        }
-    }))
+/// Create RATE_LIMITED error frame telling the client when to retry
+fn create_rate_limited_message(retry_after: Duration) -> Result<Vec<u8>> {
+    let message = MessageBuilder::error_frame(
+        "RATE_LIMITED",
+        "Too many messages".to_string(),
+        retry_after.as_millis() as i64,
+    );
+    MessageParser::serialize(&message).map_err(|e| {
+        reticulum_core::Error::internal(format!("Failed to encode error frame: {}", e))
+    })
+}
+
+/// Create server hello message (protobuf) carrying the session's resume token
+fn create_server_hello_msg(room_id: &str, conn_id: &str, resume_token: &str, resumed: bool) -> Result<Vec<u8>> {
+    let message = MessageBuilder::server_hello(
+        conn_id.to_string(),
+        room_id.to_string(),
+        resume_token.to_string(),
+        resumed,
+    );
+    MessageParser::serialize(&message).map_err(|e| {
+        reticulum_core::Error::internal(format!("Failed to encode server hello: {}", e))
+    })
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity_sync::EntitySync;
+    use crate::moderation::{ActionRequest, Moderator};
+    use crate::redis::MemoryTransport;
+    use reticulum_core::entity_sync::{EntityHistoryRequest, ENTITY_HISTORY_CHANNEL};
+    use reticulum_core::models::ModerationActionType;
+    use reticulum_core::Config;
+    use tokio::time::timeout;
+
+    #[tokio::test]
+    async fn test_first_connection_to_a_room_claims_a_hub_seat() {
+        let ws_manager = WebSocketManager::new();
+        let transport = Arc::new(MemoryTransport::new());
+        let mut claims = transport.psubscribe(SEAT_CLAIM_CHANNEL).await.unwrap();
+        ws_manager.attach_hub(transport).await;
+
+        for conn_id in ["c1", "c2"] {
+            let _ = ws_manager
+                .add_authenticated_connection(conn_id.to_string(), Some("room-1".to_string()), "alice".to_string(), None)
+                .await;
+        }
+        // Claiming someone's id over an unverified connection takes no seat
+        let _ = ws_manager
+            .add_connection("c3".to_string(), Some("room-1".to_string()), Some("bob".to_string()), None)
+            .await;
+
+        let (_, payload) = timeout(Duration::from_secs(1), claims.recv()).await.unwrap().unwrap();
+        let claim: SeatClaim = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(claim.instance_id, "room-1");
+        assert_eq!(claim.user_id, "alice");
+
+        // A second tab of the same user already holds the seat
+        assert!(timeout(Duration::from_millis(100), claims.recv()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_only_token_verified_user_ids_undo_moderate_and_take_entities() {
+        for verified in [false, true] {
+            let ws_manager = WebSocketManager::new();
+            let transport = Arc::new(MemoryTransport::new());
+            let mut history = transport.psubscribe(ENTITY_HISTORY_CHANNEL).await.unwrap();
+            let mut handoffs = transport.psubscribe(HOST_CHANNEL).await.unwrap();
+            ws_manager.attach_hub(transport.clone()).await;
+            EntitySync::new(transport).start(&ws_manager).await.unwrap();
+            let moderation = ModerationManager::new(None, Config::load_or_default().unwrap());
+            moderation.start(&ws_manager).await;
+
+            // Alice hosts the room and bob joins, with tokens or only `user_id` labels
+            let mut receivers = Vec::new();
+            for (conn_id, user_id, client_id) in [("c1", "1", "alice"), ("c2", "2", "bob")] {
+                let rx = register_client(
+                    &ws_manager,
+                    conn_id.to_string(),
+                    "room-1",
+                    Some(user_id.to_string()),
+                    verified.then_some(user_id),
+                    Some(client_id.to_string()),
+                )
+                .await;
+                receivers.push(rx);
+            }
+
+            let undo = MessageParser::serialize(&MessageBuilder::entity_history(false)).unwrap();
+            let handlers = ws_manager.handlers().await;
+            route_message(&handlers, &ws_manager, "room-1", "c1", &undo)
+                .await
+                .unwrap();
+            match timeout(Duration::from_millis(100), history.recv()).await {
+                Ok(Some((_, payload))) => {
+                    let request: EntityHistoryRequest = serde_json::from_slice(&payload).unwrap();
+                    assert!(verified);
+                    assert_eq!(request.user_id, "1");
+                }
+                _ => assert!(!verified),
+            }
+
+            let alice = Moderator {
+                user_id: "1".to_string(),
+                email: "alice@example.com".to_string(),
+            };
+            let mute_bob = ActionRequest {
+                room_id: "room-1".to_string(),
+                action: ModerationActionType::Mute,
+                target_user_id: Some("2".to_string()),
+                reason: None,
+                duration: None,
+            };
+            assert_eq!(moderation.take_action(&ws_manager, &alice, mute_bob).await.is_ok(), verified);
+
+            // Bob hosts once alice leaves, but only takes her entities as a proven user
+            close_connection(&ws_manager, "c1").await;
+            assert_eq!(ws_manager.get_room_host("room-1").await.as_deref(), Some("bob"));
+            match timeout(Duration::from_millis(100), handoffs.recv()).await {
+                Ok(Some((_, payload))) => {
+                    let handoff: HostHandoff = serde_json::from_slice(&payload).unwrap();
+                    assert!(verified);
+                    assert_eq!(handoff.new_host, "2");
+                }
+                _ => assert!(!verified),
+            }
+        }
+    }
+}
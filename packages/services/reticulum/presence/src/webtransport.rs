@@ -1,139 +1,121 @@
 //! WebTransport HTTP/3 server implementation using wtransport 0.6
 //!
-//! Provides bidirectional streams over HTTP/3 with WebTransport API
-//! Based on wtransport 0.6 API (Endpoint/Connection model)
-
-use reticulum_core::Result;
+//! WebTransport sessions join rooms through the `WebSocketManager`, so they
+//! share membership, host election, tick aggregation and rate limits with
+//! WebSocket clients and receive the same broadcasts.
+//!
+//! Clients connect to `https://host:4443/presence/{room_id}?client_id=..&token=..`
+//! and open one bidirectional stream by writing an empty frame on it. That
+//! stream carries reliable messages as frames with a 4-byte big-endian length
+//! prefix. Position and voice updates, which are superseded by the next one,
+//! travel as datagrams in both directions.
+
+use crate::queue::OutboundReceiver;
+use crate::websocket::{
+    announce_join, close_connection, handle_client_message, register_client, Transport,
+    WebSocketManager, WsMessage,
+};
+use graphwiz_protocol::{MessageParser, MessageType};
+use reticulum_core::{Error, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
 use uuid::Uuid;
-use wtransport::{Connection, Endpoint, Identity, ServerConfig};
+use wtransport::endpoint::endpoint_side::Server;
+use wtransport::endpoint::IncomingSession;
+use wtransport::{Connection, Endpoint, Identity, RecvStream, SendStream, ServerConfig, VarInt};
 
-/// Wrapper for bidirectional stream
-pub struct BidirectionalStream {
-    pub stream_id: u64,
-    pub send: wtransport::stream::SendStream,
-    pub recv: wtransport::stream::RecvStream,
-}
+/// Default UDP port for the HTTP/3 server
+pub const DEFAULT_WEBTRANSPORT_PORT: u16 = 4443;
 
-/// Session info with active WebTransport connection
-pub struct SessionInfo {
-    pub session_id: String,
-    pub connection: Connection,
-    pub webtransport_connection: WebTransportConnection,
-    streams: Arc<RwLock<HashMap<u64, BidirectionalStream>>>,
-    stream_tx: mpsc::Sender<(u64, Vec<u8>)>,
-    _accept_handle: Option<JoinHandle<()>>,
-}
+/// Largest reliable frame accepted from a client
+pub const MAX_FRAME_LEN: usize = 1024 * 1024;
 
-impl SessionInfo {
-    pub fn new(connection: Connection, webtransport_connection: WebTransportConnection) -> Self {
-        let (stream_tx, _) = mpsc::channel(100);
-        let session_id = Uuid::new_v4().to_string();
+/// Time a new session gets to open its reliable stream
+const STREAM_OPEN_TIMEOUT: Duration = Duration::from_secs(10);
 
-        let info = Self {
-            session_id: session_id.clone(),
-            connection,
-            webtransport_connection,
-            streams: Arc::new(RwLock::new(HashMap::new())),
-            stream_tx,
-            _accept_handle: None,
-        };
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(3);
 
-        // Start accepting streams in background
-        let accept_handle = tokio::spawn(Self::accept_streams_loop(
-            session_id.clone(),
-            info.connection.clone(),
-            info.streams.clone(),
-            info.stream_tx.clone(),
-        ));
+/// Close code for evicted slow consumers, matching the WebSocket "try again" code
+const CLOSE_SLOW_CONSUMER: u32 = 1013;
 
-        let mut this = info;
-        this._accept_handle = Some(accept_handle);
-        this
-    }
+/// Write one length-prefixed frame
+pub async fn write_frame<W: AsyncWrite + Unpin>(writer: &mut W, payload: &[u8]) -> std::io::Result<()> {
+    writer.write_all(&(payload.len() as u32).to_be_bytes()).await?;
+    writer.write_all(payload).await
+}
 
-    async fn accept_streams_loop(
-        session_id: String,
-        connection: Connection,
-        streams: Arc<RwLock<HashMap<u64, BidirectionalStream>>>,
-        stream_tx: mpsc::Sender<(u64, Vec<u8>)>,
-    ) {
-        let mut buffer = vec![0; 65536].into_boxed_slice();
+/// Read one length-prefixed frame, or `None` once the peer finished the stream
+pub async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R) -> std::io::Result<Option<Vec<u8>>> {
+    let mut len = [0u8; 4];
+    match reader.read_exact(&mut len).await {
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
 
-        loop {
-            tokio::select! {
-                stream = connection.accept_bi() => {
-                    match stream {
-                        Ok((send_stream, recv_stream)) => {
-                            let stream_id: u64 = send_stream.id().into();
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("frame of {} bytes exceeds the {} byte limit", len, MAX_FRAME_LEN),
+        ));
+    }
 
-                            log::info!("Accepted BI stream {} for session {}", stream_id, session_id);
+    let mut payload = vec![0u8; len];
+    reader.read_exact(&mut payload).await?;
+    Ok(Some(payload))
+}
 
-                            let mut streams_write = streams.write().await;
-                            streams_write.insert(stream_id, BidirectionalStream {
-                                stream_id,
-                                send: send_stream,
-                                recv: recv_stream,
-                            });
+/// Room and identity requested in a session path
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionParams {
+    pub room_id: String,
+    pub client_id: Option<String>,
+    pub user_id: Option<String>,
+    /// Access token proving the user's identity
+    pub token: Option<String>,
+}
 
-                            let _ = stream_tx.send((stream_id, vec![])).await;
-                        }
-                        Err(e) => {
-                            log::error!("Failed to accept BI stream for session {}: {}", session_id, e);
-                            break;
-                        }
-                    }
-                }
-                _ = connection.closed() => {
-                    log::info!("Connection closed for session {}", session_id);
-                    break;
-                }
-            }
+impl SessionParams {
+    /// Parse `/presence/{room_id}?client_id=..&user_id=..&token=..`
+    pub fn parse(path: &str) -> Option<Self> {
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        let room_id = path.strip_prefix("/presence/")?;
+        if room_id.is_empty() || room_id.contains('/') {
+            return None;
         }
-    }
 
-    pub async fn add_stream(
-        &self,
-        stream_id: u64,
-        send: wtransport::stream::SendStream,
-        recv: wtransport::stream::RecvStream,
-    ) {
-        let mut streams = self.streams.write().await;
-        streams.insert(
-            stream_id,
-            BidirectionalStream {
-                stream_id,
-                send,
-                recv,
-            },
-        );
-    }
-
-    pub async fn get_stream(&self, stream_id: u64) -> Option<BidirectionalStream> {
-        let streams = self.streams.read().await;
-        streams.get(&stream_id).cloned()
-    }
-
-    pub async fn send_on_stream(&self, stream_id: u64, data: Vec<u8>) -> Result<()> {
-        let send = {
-            let streams = self.streams.read().await;
-            streams.get(&stream_id).map(|s| s.send.clone())
+        let mut params = Self {
+            room_id: room_id.to_string(),
+            client_id: None,
+            user_id: None,
+            token: None,
         };
-
-        if let Some(mut send) = send {
-            send.write_all(&data).await?;
-            send.finish()?;
-            Ok(())
-        } else {
-            Err(eyre::eyre!("Stream {} not found", stream_id))
+        for pair in query.split('&') {
+            match pair.split_once('=') {
+                Some(("client_id", value)) => params.client_id = Some(value.to_string()),
+                Some(("user_id", value)) => params.user_id = Some(value.to_string()),
+                Some(("token", value)) => params.token = Some(value.to_string()),
+                _ => {}
+            }
         }
+        Some(params)
     }
 }
 
+/// Session info with active WebTransport connection
+pub struct SessionInfo {
+    pub session_id: String,
+    pub connection: Connection,
+    pub webtransport_connection: WebTransportConnection,
+}
+
 /// WebTransport connection info (user-level metadata)
 #[derive(Clone)]
 pub struct WebTransportConnection {
@@ -164,127 +146,102 @@ impl WebTransportConnection {
     }
 
     pub fn age(&self) -> chrono::Duration {
-        self.connected_at.signed_duration_since(chrono::Utc::now())
+        chrono::Utc::now().signed_duration_since(self.connected_at)
     }
 }
 
-/// WebTransport stream types
-pub enum WebTransportStreamType {
-    Unidirectional,
-    Bidirectional,
-}
-
 /// WebTransport session manager with full HTTP/3 server support
+#[derive(Clone)]
 pub struct WebTransportManager {
+    ws_manager: WebSocketManager,
     sessions: Arc<RwLock<HashMap<String, Arc<SessionInfo>>>>,
-    config: ServerConfig,
-    server: Arc<RwLock<Option<Endpoint>>>,
+    server: Arc<RwLock<Option<Arc<Endpoint<Server>>>>>,
+    local_addr: Arc<RwLock<Option<SocketAddr>>>,
+    certificate_hash: Arc<RwLock<Option<String>>>,
     accept_task: Arc<RwLock<Option<JoinHandle<()>>>>,
 }
 
 impl WebTransportManager {
-    pub fn new(config: Option<ServerConfig>) -> Self {
-        let config = config.unwrap_or_else(|| {
-            ServerConfig::builder()
-                .with_bind_default(4443)
-                .with_identity(Identity::self_signed(["localhost"]).expect("self-signed cert"))
-                .build()
-        });
-
+    pub fn new(ws_manager: WebSocketManager) -> Self {
         Self {
+            ws_manager,
             sessions: Arc::new(RwLock::new(HashMap::new())),
-            config,
             server: Arc::new(RwLock::new(None)),
+            local_addr: Arc::new(RwLock::new(None)),
+            certificate_hash: Arc::new(RwLock::new(None)),
             accept_task: Arc::new(RwLock::new(None)),
         }
     }
 
-    pub fn config(&self) -> &ServerConfig {
-        &self.config
+    /// Start the HTTP/3 server with the certificate in `WEBTRANSPORT_CERT` and
+    /// `WEBTRANSPORT_KEY`, or a self-signed one for local development
+    pub async fn start_server(&self, port: u16) -> Result<SocketAddr> {
+        let identity = match (
+            std::env::var("WEBTRANSPORT_CERT"),
+            std::env::var("WEBTRANSPORT_KEY"),
+        ) {
+            (Ok(cert), Ok(key)) => Identity::load_pemfiles(cert, key)
+                .await
+                .map_err(|e| Error::internal(format!("Failed to load WebTransport certificate: {}", e)))?,
+            _ => {
+                log::warn!("WEBTRANSPORT_CERT/WEBTRANSPORT_KEY not set, using a self-signed certificate");
+                Identity::self_signed(["localhost", "127.0.0.1", "::1"])
+                    .map_err(|e| Error::internal(format!("Failed to create self-signed certificate: {}", e)))?
+            }
+        };
+
+        self.start_server_with_identity(port, identity).await
     }
 
-    pub async fn start_server(&self, port: u16) -> Result<()> {
-        log::info!("Starting WebTransport HTTP/3 server on port {}", port);
+    /// Start the HTTP/3 server on `port` (0 picks a free one) and return the
+    /// bound address
+    pub async fn start_server_with_identity(&self, port: u16, identity: Identity) -> Result<SocketAddr> {
+        // Browsers accept self-signed certificates pinned by their SHA-256 hash
+        let certificate_hash = identity.certificate_chain().as_slice().first().map(|cert| {
+            cert.hash()
+                .as_ref()
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<String>()
+        });
 
         let server_config = ServerConfig::builder()
             .with_bind_default(port)
-            .with_identity(Identity::self_signed(["localhost"]).expect("self-signed cert"))
+            .with_identity(identity)
+            .keep_alive_interval(Some(KEEP_ALIVE_INTERVAL))
             .build();
 
-        let server = Endpoint::server(server_config)?;
+        let server = Endpoint::server(server_config)
+            .map_err(|e| Error::internal(format!("Failed to bind WebTransport server: {}", e)))?;
+        let local_addr = server
+            .local_addr()
+            .map_err(|e| Error::internal(format!("Failed to read WebTransport address: {}", e)))?;
+        let server = Arc::new(server);
 
-        let server_clone = server.clone();
-        let session_manager = self.sessions.clone();
+        log::info!("WebTransport server accepting connections on {}", local_addr);
 
+        let manager = self.clone();
+        let accept_server = server.clone();
         let accept_handle = tokio::spawn(async move {
-            log::info!("WebTransport server accepting connections on port {}", port);
-
-            let mut id_counter = 0u64;
-
             loop {
-                let incoming = server_clone.accept().await;
-
-                let connection = match incoming.await {
-                    Ok(session_request) => {
-                        let authority = session_request.authority();
-                        let path = session_request.path();
-                        log::info!(
-                            "New WebTransport session: authority='{}', path='{}'",
-                            authority,
-                            path
-                        );
-
-                        match session_request.accept().await {
-                            Ok(conn) => conn,
-                            Err(e) => {
-                                log::error!("Failed to accept session: {}", e);
-                                continue;
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        log::error!("Failed to accept connection: {}", e);
-                        continue;
-                    }
-                };
-
-                let session_manager = session_manager.clone();
-                tokio::spawn(async move {
-                    let session_id = Uuid::new_v4().to_string();
-                    let remote_addr = connection.remote_address().to_string();
-
-                    log::info!("Session {} connected from {}", session_id, remote_addr);
-
-                    let webtransport_conn = WebTransportConnection::new(
-                        session_id.clone(),
-                        "client".to_string(),
-                        "user".to_string(),
-                        "default".to_string(),
-                        remote_addr,
-                    );
-
-                    let session_info = Arc::new(SessionInfo::new(connection, webtransport_conn));
-
-                    let mut sessions = session_manager.write().await;
-                    sessions.insert(session_id.clone(), session_info);
-
-                    connection.closed().await;
-
-                    log::info!("Session {} disconnected", session_id);
-                    sessions.remove(&session_id);
-                });
+                let incoming = accept_server.accept().await;
+                tokio::spawn(manager.clone().run_session(incoming));
             }
         });
 
         *self.server.write().await = Some(server);
-        *self.accept_task.write().await = Some(accept_handle);
+        *self.local_addr.write().await = Some(local_addr);
+        *self.certificate_hash.write().await = certificate_hash;
+        if let Some(previous) = self.accept_task.write().await.replace(accept_handle) {
+            previous.abort();
+        }
 
-        Ok(())
+        Ok(local_addr)
     }
 
     pub async fn stop_server(&self) -> Result<()> {
-        if let Some(ref server) = *self.server.read().await {
-            server.close(0, b"Server shutting down");
+        if let Some(server) = self.server.write().await.take() {
+            server.close(VarInt::from_u32(0), b"Server shutting down");
         }
 
         if let Some(handle) = self.accept_task.write().await.take() {
@@ -292,40 +249,222 @@ impl WebTransportManager {
             log::info!("WebTransport accept task stopped");
         }
 
+        *self.local_addr.write().await = None;
         Ok(())
     }
 
-    pub async fn accept_connection(
-        &self,
-        connection: Connection,
-        client_id: String,
-        user_id: String,
-        room_id: String,
-    ) -> Result<String> {
+    /// Address the server is bound to, if running
+    pub async fn local_addr(&self) -> Option<SocketAddr> {
+        *self.local_addr.read().await
+    }
+
+    /// Hex SHA-256 hash of the server certificate, for `serverCertificateHashes`
+    pub async fn certificate_hash(&self) -> Option<String> {
+        self.certificate_hash.read().await.clone()
+    }
+
+    /// Accept a session, join it to its room and relay messages until it closes
+    async fn run_session(self, incoming: IncomingSession) {
+        let request = match incoming.await {
+            Ok(request) => request,
+            Err(e) => {
+                log::error!("Failed to accept connection: {}", e);
+                return;
+            }
+        };
+
+        let Some(mut params) = SessionParams::parse(request.path()) else {
+            log::warn!("Rejecting WebTransport session for path '{}'", request.path());
+            request.not_found().await;
+            return;
+        };
+
+        if self.ws_manager.is_draining().await {
+            log::info!("Refusing WebTransport session to room {}: instance is draining", params.room_id);
+            request.forbidden().await;
+            return;
+        }
+
+        // Only an access token proves who the user is; a bare `user_id` is a label
+        let verified_user_id = match &params.token {
+            Some(token) => match self.ws_manager.verify_user(token).await {
+                Ok(user_id) => Some(user_id),
+                Err(e) => {
+                    log::info!("Refusing WebTransport session to room {}: {}", params.room_id, e);
+                    request.forbidden().await;
+                    return;
+                }
+            },
+            None => None,
+        };
+        if verified_user_id.is_some() {
+            params.user_id = verified_user_id.clone();
+        }
+
+        if let Err(e) = self.ws_manager.admit(&params.room_id, verified_user_id.as_deref()).await {
+            log::info!("Refusing WebTransport session to room {}: {}", params.room_id, e);
+            request.forbidden().await;
+            return;
+        }
+
+        let connection = match request.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                log::error!("Failed to accept session: {}", e);
+                return;
+            }
+        };
+
+        // QUIC only announces a stream once data is written, so the client
+        // opens the reliable stream with an empty frame
+        let (send, recv) = match tokio::time::timeout(STREAM_OPEN_TIMEOUT, connection.accept_bi()).await {
+            Ok(Ok(streams)) => streams,
+            Ok(Err(e)) => {
+                log::warn!("WebTransport session closed before opening its stream: {}", e);
+                return;
+            }
+            Err(_) => {
+                connection.close(VarInt::from_u32(0), b"reliable stream not opened");
+                return;
+            }
+        };
+
         let session_id = Uuid::new_v4().to_string();
         let remote_addr = connection.remote_address().to_string();
+        let session_info = Arc::new(SessionInfo {
+            session_id: session_id.clone(),
+            connection,
+            webtransport_connection: WebTransportConnection::new(
+                session_id.clone(),
+                params.client_id.clone().unwrap_or_default(),
+                params.user_id.clone().unwrap_or_default(),
+                params.room_id.clone(),
+                remote_addr.clone(),
+            ),
+        });
+        self.sessions
+            .write()
+            .await
+            .insert(session_id.clone(), session_info.clone());
 
-        let webtransport_conn = WebTransportConnection::new(
-            session_id.clone(),
-            client_id.clone(),
-            user_id.clone(),
-            room_id.clone(),
+        log::info!(
+            "WebTransport session {} from {} joined room {}",
+            session_id,
             remote_addr,
+            params.room_id
         );
 
-        let session_info = Arc::new(SessionInfo::new(connection, webtransport_conn));
-
-        let mut sessions = self.sessions.write().await;
-        sessions.insert(session_id.clone(), session_info);
+        // Sessions cannot be resumed, so they announce without a resume token
+        let rx = register_client(
+            &self.ws_manager,
+            session_id.clone(),
+            &params.room_id,
+            params.user_id.clone(),
+            verified_user_id.as_deref(),
+            params.client_id.clone(),
+        )
+        .await;
+        self.ws_manager.set_transport(&session_id, Transport::WebTransport).await;
+        announce_join(
+            &self.ws_manager,
+            &session_id,
+            &params.room_id,
+            params.client_id.as_deref(),
+            "",
+        )
+        .await;
+
+        self.relay(&session_info, send, recv, rx).await;
 
         log::info!(
-            "WebTransport connection accepted: {} from {} to room {}",
+            "WebTransport session {} disconnected after {}s",
             session_id,
-            client_id,
-            room_id
+            session_info.webtransport_connection.age().num_seconds()
         );
+        self.sessions.write().await.remove(&session_id);
+        close_connection(&self.ws_manager, &session_id).await;
+    }
 
-        Ok(session_id)
+    /// Feed datagrams and stream frames into the room and drain the outbound queue
+    async fn relay(
+        &self,
+        session_info: &SessionInfo,
+        mut send: SendStream,
+        mut recv: RecvStream,
+        mut rx: OutboundReceiver<WsMessage>,
+    ) {
+        let connection = &session_info.connection;
+        let session_id = session_info.session_id.clone();
+        let room_id = session_info.webtransport_connection.room_id.clone();
+
+        // Stream reads are not cancel-safe, so frames are read in their own task
+        let reader = {
+            let ws_manager = self.ws_manager.clone();
+            let session_id = session_id.clone();
+            let room_id = room_id.clone();
+            tokio::spawn(async move {
+                loop {
+                    match read_frame(&mut recv).await {
+                        Ok(Some(frame)) if frame.is_empty() => {}
+                        Ok(Some(frame)) => {
+                            if let Err(e) =
+                                handle_client_message(&ws_manager, &room_id, &session_id, &frame).await
+                            {
+                                log::error!("Error handling message from {}: {:?}", session_id, e);
+                            }
+                        }
+                        Ok(None) => break,
+                        Err(e) => {
+                            log::warn!("WebTransport stream error from {}: {}", session_id, e);
+                            break;
+                        }
+                    }
+                }
+            })
+        };
+
+        loop {
+            tokio::select! {
+                datagram = connection.receive_datagram() => {
+                    match datagram {
+                        Ok(datagram) => {
+                            if let Err(e) =
+                                handle_client_message(&self.ws_manager, &room_id, &session_id, &datagram.payload())
+                                    .await
+                            {
+                                log::error!("Error handling datagram from {}: {:?}", session_id, e);
+                            }
+                        }
+                        Err(e) => {
+                            log::info!("WebTransport session {} closed: {}", session_id, e);
+                            break;
+                        }
+                    }
+                }
+                // Unlike WebSocket sessions these cannot be resumed, so an
+                // evicted slow consumer simply has to reconnect
+                _ = rx.evicted.notified() => {
+                    connection.close(VarInt::from_u32(CLOSE_SLOW_CONSUMER), b"slow consumer");
+                    break;
+                }
+                msg = rx.queue.recv() => {
+                    let sent = match msg {
+                        Some(WsMessage::Binary(bytes)) => send_message(connection, &mut send, &bytes).await,
+                        Some(WsMessage::Text(text)) => write_frame(&mut send, text.as_bytes()).await,
+                        Some(WsMessage::Close) | None => {
+                            connection.close(VarInt::from_u32(0), b"");
+                            break;
+                        }
+                    };
+                    if let Err(e) = sent {
+                        log::warn!("Failed to send to WebTransport session {}: {}", session_id, e);
+                        break;
+                    }
+                }
+            }
+        }
+
+        reader.abort();
     }
 
     pub async fn get_connection(&self, session_id: &str) -> Option<Arc<SessionInfo>> {
@@ -333,11 +472,6 @@ impl WebTransportManager {
         sessions.get(session_id).cloned()
     }
 
-    pub async fn remove_connection(&self, session_id: &str) -> Option<Arc<SessionInfo>> {
-        let mut sessions = self.sessions.write().await;
-        sessions.remove(session_id)
-    }
-
     pub async fn get_room_connections(&self, room_id: &str) -> Vec<Arc<SessionInfo>> {
         let sessions = self.sessions.read().await;
         sessions
@@ -352,65 +486,45 @@ impl WebTransportManager {
         sessions.values().cloned().collect()
     }
 
+    /// Round trip the QUIC connection of a session currently estimates
+    pub async fn rtt(&self, session_id: &str) -> Option<Duration> {
+        let sessions = self.sessions.read().await;
+        sessions.get(session_id).map(|info| info.connection.rtt())
+    }
+
     pub async fn session_count(&self) -> usize {
         let sessions = self.sessions.read().await;
         sessions.len()
     }
 
-    pub async fn send_to_session(
-        &self,
-        session_id: &str,
-        data: &[u8],
-        stream_id: Option<u64>,
-    ) -> Result<()> {
-        let sessions = self.sessions.read().await;
-        if let Some(conn) = sessions.get(session_id) {
-            if let Some(sid) = stream_id {
-                conn.send_on_stream(sid, data.to_vec()).await?;
-            }
-            Ok(())
-        } else {
-            Err(eyre::eyre!("Session not found: {}", session_id))
+    /// Queue a message for a session; it goes out as a datagram or on the
+    /// reliable stream depending on its type
+    pub async fn send_to_session(&self, session_id: &str, data: &[u8]) -> Result<()> {
+        if !self.sessions.read().await.contains_key(session_id) {
+            return Err(Error::not_found(format!("Session not found: {}", session_id)));
         }
+        self.ws_manager
+            .send_to_connection(session_id, WsMessage::Binary(data.to_vec()))
+            .await
     }
 
-    pub async fn create_bidirectional_stream(&self, session_id: &str) -> Result<u64> {
-        let sessions = self.sessions.read().await;
-        let session_info = sessions
-            .get(session_id)
-            .ok_or_else(|| eyre::eyre!("Session not found: {}", session_id))?;
-
-        drop(sessions);
-
-        let (send, recv) = session_info.connection.open_bi().await?;
-        let stream_id: u64 = send.id().into();
-
-        session_info.add_stream(stream_id, send, recv).await;
+    /// Close a session; it leaves its room once the relay loop notices
+    pub async fn close_session(&self, session_id: &str, reason: &str) -> Result<()> {
+        let session_info = self
+            .get_connection(session_id)
+            .await
+            .ok_or_else(|| Error::not_found(format!("Session not found: {}", session_id)))?;
 
         log::info!(
-            "Created bidirectional stream {} for WebTransport session {}",
-            stream_id,
-            session_id
+            "Closing WebTransport session {} ({}) - age: {:?}",
+            session_id,
+            reason,
+            session_info.webtransport_connection.age()
         );
-
-        Ok(stream_id)
-    }
-
-    pub async fn close_session(&self, session_id: &str, reason: &str) -> Result<()> {
-        let session_info = self.remove_connection(session_id).await;
-
-        if let Some(info) = session_info {
-            log::info!(
-                "Closing WebTransport session {} ({}) - age: {:?}",
-                session_id,
-                reason,
-                info.webtransport_connection.age()
-            );
-            info.connection.close(0, reason.as_bytes());
-            Ok(())
-        } else {
-            Err(eyre::eyre!("Session not found: {}", session_id))
-        }
+        session_info
+            .connection
+            .close(VarInt::from_u32(0), reason.as_bytes());
+        Ok(())
     }
 
     pub async fn get_stats(&self) -> WebTransportStats {
@@ -431,19 +545,34 @@ impl WebTransportManager {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct WebTransportStats {
-    pub total_connections: usize,
-    pub connections_by_room: HashMap<String, usize>,
+/// Whether a message may be lost, because the next one supersedes it
+fn is_datagram(message: &[u8]) -> bool {
+    match MessageParser::parse(message) {
+        Ok(parsed) => matches!(
+            MessageType::try_from(parsed.r#type),
+            Ok(MessageType::PositionUpdate | MessageType::PositionBatch | MessageType::VoiceData)
+        ),
+        Err(_) => false,
+    }
 }
 
-impl Default for WebTransportStats {
-    fn default() -> Self {
-        Self {
-            total_connections: 0,
-            connections_by_room: HashMap::new(),
-        }
+/// Send position and voice updates as datagrams when they fit; everything
+/// else, and datagrams the path cannot carry, goes on the reliable stream
+async fn send_message(connection: &Connection, send: &mut SendStream, message: &[u8]) -> std::io::Result<()> {
+    let fits_datagram = connection
+        .max_datagram_size()
+        .is_some_and(|max| message.len() <= max);
+
+    if fits_datagram && is_datagram(message) && connection.send_datagram(message).is_ok() {
+        return Ok(());
     }
+    write_frame(send, message).await
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct WebTransportStats {
+    pub total_connections: usize,
+    pub connections_by_room: HashMap<String, usize>,
 }
 
 #[cfg(test)]
@@ -452,21 +581,45 @@ mod tests {
 
     #[tokio::test]
     async fn test_webtransport_manager_creation() {
-        let config = ServerConfig::builder()
-            .with_bind_default(4443)
-            .with_identity(Identity::self_signed(["localhost"]).unwrap())
-            .build();
-        let manager = WebTransportManager::new(Some(config));
+        let manager = WebTransportManager::new(WebSocketManager::new());
         assert_eq!(manager.session_count().await, 0);
+        assert!(manager.local_addr().await.is_none());
+    }
+
+    #[test]
+    fn test_session_params_parse() {
+        let params = SessionParams::parse("/presence/room-1?client_id=alice&user_id=u1&token=t").unwrap();
+        assert_eq!(params.room_id, "room-1");
+        assert_eq!(params.client_id.as_deref(), Some("alice"));
+        assert_eq!(params.user_id.as_deref(), Some("u1"));
+        assert_eq!(params.token.as_deref(), Some("t"));
+
+        assert_eq!(SessionParams::parse("/presence/room-2").unwrap().client_id, None);
+        assert!(SessionParams::parse("/presence/").is_none());
+        assert!(SessionParams::parse("/other/room-1").is_none());
     }
 
     #[tokio::test]
-    async fn test_webtransport_manager_session_count() {
-        let config = ServerConfig::builder()
-            .with_bind_default(4443)
-            .with_identity(Identity::self_signed(["localhost"]).unwrap())
-            .build();
-        let manager = WebTransportManager::new(Some(config));
-        assert_eq!(manager.session_count().await, 0);
+    async fn test_frame_round_trip() {
+        let (mut client, mut server) = tokio::io::duplex(64);
+        write_frame(&mut client, b"hello").await.unwrap();
+        write_frame(&mut client, b"").await.unwrap();
+        drop(client);
+
+        assert_eq!(read_frame(&mut server).await.unwrap(), Some(b"hello".to_vec()));
+        assert_eq!(read_frame(&mut server).await.unwrap(), Some(Vec::new()));
+        assert_eq!(read_frame(&mut server).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_frame_rejected() {
+        let (mut client, mut server) = tokio::io::duplex(64);
+        client
+            .write_all(&((MAX_FRAME_LEN + 1) as u32).to_be_bytes())
+            .await
+            .unwrap();
+
+        let err = read_frame(&mut server).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
     }
 }
@@ -0,0 +1,150 @@
+//! Integration tests for the WebTransport transport
+
+use graphwiz_protocol::generated::graphwiz::core::message::Payload;
+use graphwiz_protocol::{
+    ChatMessageType, Message, MessageBuilder, MessageParser, MessageType, Quaternion, Vector3,
+};
+use reticulum_presence::queue::OutboundReceiver;
+use reticulum_presence::websocket::{WebSocketManager, WsMessage};
+use reticulum_presence::webtransport::{read_frame, write_frame, WebTransportManager};
+use std::time::Duration;
+use tokio::time::timeout;
+use wtransport::{ClientConfig, Endpoint, Identity, RecvStream, VarInt};
+
+const WAIT: Duration = Duration::from_secs(5);
+
+fn message_type(message: &Message) -> Option<MessageType> {
+    MessageType::try_from(message.r#type).ok()
+}
+
+/// Read stream frames until one of `wanted` type arrives
+async fn next_frame_of(recv: &mut RecvStream, wanted: MessageType) -> Message {
+    timeout(WAIT, async {
+        loop {
+            let frame = read_frame(recv).await.unwrap().expect("stream finished");
+            let message = MessageParser::parse(&frame).unwrap();
+            if message_type(&message) == Some(wanted) {
+                return message;
+            }
+        }
+    })
+    .await
+    .expect("timed out waiting for frame")
+}
+
+/// Read a WebSocket peer's queue until a message of `wanted` type arrives
+async fn next_queued_of(rx: &mut OutboundReceiver<WsMessage>, wanted: MessageType) -> Message {
+    timeout(WAIT, async {
+        loop {
+            if let Some(WsMessage::Binary(bytes)) = rx.queue.recv().await {
+                let message = MessageParser::parse(&bytes).unwrap();
+                if message_type(&message) == Some(wanted) {
+                    return message;
+                }
+            }
+        }
+    })
+    .await
+    .expect("timed out waiting for queued message")
+}
+
+#[tokio::test]
+async fn test_webtransport_session_shares_room_with_websocket() {
+    let ws_manager = WebSocketManager::new();
+    let mut peer_rx = ws_manager
+        .add_connection("ws-peer".to_string(), Some("room-1".to_string()), None, Some("bob".to_string()))
+        .await;
+
+    let manager = WebTransportManager::new(ws_manager.clone());
+    let identity = Identity::self_signed(["localhost"]).unwrap();
+    let cert_hash = identity.certificate_chain().as_slice()[0].hash();
+    let addr = manager.start_server_with_identity(0, identity).await.unwrap();
+
+    let client_config = ClientConfig::builder()
+        .with_bind_default()
+        .with_server_certificate_hashes([cert_hash])
+        .build();
+    let client = Endpoint::client(client_config).unwrap();
+    let connection = client
+        .connect(format!(
+            "https://localhost:{}/presence/room-1?client_id=alice&user_id=user-alice",
+            addr.port()
+        ))
+        .await
+        .unwrap();
+
+    // Open the reliable stream with an empty frame
+    let (mut send, mut recv) = connection.open_bi().await.unwrap().await.unwrap();
+    write_frame(&mut send, b"").await.unwrap();
+
+    next_frame_of(&mut recv, MessageType::ServerHello).await;
+    assert_eq!(ws_manager.room_connection_count("room-1").await, 2);
+    assert_eq!(manager.session_count().await, 1);
+
+    // Room broadcasts reach the session on the reliable stream
+    let chat = MessageBuilder::chat_message("bob".to_string(), "hi alice".to_string(), ChatMessageType::Normal);
+    ws_manager
+        .broadcast_to_room("room-1", &MessageParser::serialize(&chat).unwrap(), Some("ws-peer"))
+        .await;
+    next_frame_of(&mut recv, MessageType::ChatMessage).await;
+
+    // Reliable frames from the session are relayed to the room
+    let reply = MessageBuilder::chat_message("alice".to_string(), "hi bob".to_string(), ChatMessageType::Normal);
+    write_frame(&mut send, &MessageParser::serialize(&reply).unwrap())
+        .await
+        .unwrap();
+    next_queued_of(&mut peer_rx, MessageType::ChatMessage).await;
+
+    // Position datagrams go through tick aggregation like WebSocket updates
+    let position = MessageBuilder::position_update(
+        "avatar-alice".to_string(),
+        Vector3 { x: 1.0, y: 0.0, z: 0.0 },
+        Quaternion { x: 0.0, y: 0.0, z: 0.0, w: 1.0 },
+    );
+    connection
+        .send_datagram(MessageParser::serialize(&position).unwrap())
+        .unwrap();
+    let batch = next_queued_of(&mut peer_rx, MessageType::PositionBatch).await;
+    match batch.payload {
+        Some(Payload::PositionBatch(batch)) => {
+            assert_eq!(batch.updates.len(), 1);
+            assert_eq!(batch.updates[0].entity_id, "avatar-alice");
+        }
+        other => panic!("expected position batch, got {:?}", other),
+    }
+
+    // Closing the session removes it from the shared room
+    connection.close(VarInt::from_u32(0), b"done");
+    timeout(WAIT, async {
+        while ws_manager.room_connection_count("room-1").await > 1 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("session was not removed from the room");
+    assert_eq!(manager.session_count().await, 0);
+
+    manager.stop_server().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_webtransport_rejects_unknown_path() {
+    let manager = WebTransportManager::new(WebSocketManager::new());
+    let identity = Identity::self_signed(["localhost"]).unwrap();
+    let cert_hash = identity.certificate_chain().as_slice()[0].hash();
+    let addr = manager.start_server_with_identity(0, identity).await.unwrap();
+
+    let client = Endpoint::client(
+        ClientConfig::builder()
+            .with_bind_default()
+            .with_server_certificate_hashes([cert_hash])
+            .build(),
+    )
+    .unwrap();
+
+    let result = client
+        .connect(format!("https://localhost:{}/elsewhere", addr.port()))
+        .await;
+    assert!(result.is_err());
+    assert_eq!(manager.session_count().await, 0);
+}
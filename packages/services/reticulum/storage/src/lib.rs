@@ -5,6 +5,7 @@
 pub mod handlers;
 pub mod jwt_auth;
 pub mod rate_limiter;
+pub mod recordings;
 pub mod routes;
 pub mod storage_backend;
 pub mod virus_scanner;
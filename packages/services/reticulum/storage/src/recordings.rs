@@ -0,0 +1,91 @@
+//! HTTP handlers for session recordings
+//!
+//! Presence records rooms into compact logs and appends them here chunk by
+//! chunk while the session runs. Logs are opaque to storage: it only keeps
+//! the bytes in order and hands them back for replay.
+
+use actix_web::{web, HttpResponse};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::storage_backend::{StorageBackend, StorageError};
+
+/// Largest chunk presence may append in one request
+pub const MAX_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Largest log kept for one recording
+pub const MAX_RECORDING_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// Recording ids are UUIDs, which also keeps them safe to use as file names
+fn parse_recording_id(recording_id: &str) -> Option<String> {
+    Uuid::parse_str(recording_id).ok().map(|id| id.to_string())
+}
+
+fn invalid_recording_id() -> HttpResponse {
+    HttpResponse::BadRequest().json(serde_json::json!({
+        "error": "invalid_recording_id",
+        "message": "Recording id must be a UUID"
+    }))
+}
+
+/// Append a chunk to a recording's log
+pub async fn append_recording_chunk(
+    storage_backend: web::Data<Arc<dyn StorageBackend>>,
+    recording_id: web::Path<String>,
+    body: web::Bytes,
+) -> HttpResponse {
+    let Some(recording_id) = parse_recording_id(&recording_id) else {
+        return invalid_recording_id();
+    };
+
+    match storage_backend
+        .append_recording(&recording_id, &body, MAX_RECORDING_SIZE)
+        .await
+    {
+        Ok(size) => {
+            log::debug!("Appended {} bytes to recording {} ({} bytes)", body.len(), recording_id, size);
+            HttpResponse::NoContent().finish()
+        }
+        Err(StorageError::FileTooLarge { size, max }) => {
+            log::warn!("Recording {} would grow to {} bytes, over the {} byte limit", recording_id, size, max);
+            HttpResponse::PayloadTooLarge().json(serde_json::json!({
+                "error": "recording_too_large",
+                "message": format!("Recording would exceed {} bytes", max)
+            }))
+        }
+        Err(e) => {
+            log::error!("Failed to append to recording {}: {}", recording_id, e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "storage_error",
+                "message": "Failed to store recording"
+            }))
+        }
+    }
+}
+
+/// Download a recording's log
+pub async fn get_recording(
+    storage_backend: web::Data<Arc<dyn StorageBackend>>,
+    recording_id: web::Path<String>,
+) -> HttpResponse {
+    let Some(recording_id) = parse_recording_id(&recording_id) else {
+        return invalid_recording_id();
+    };
+
+    match storage_backend.get_recording(&recording_id).await {
+        Ok(data) => HttpResponse::Ok()
+            .content_type("application/octet-stream")
+            .body(data),
+        Err(StorageError::FileNotFound(_)) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "not_found",
+            "message": "Recording not found"
+        })),
+        Err(e) => {
+            log::error!("Failed to read recording {}: {}", recording_id, e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "storage_error",
+                "message": "Failed to read recording"
+            }))
+        }
+    }
+}
@@ -1,6 +1,6 @@
 //! Route configuration for storage service
 
-use crate::handlers;
+use crate::{handlers, recordings};
 use actix_web::web;
 
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
@@ -19,5 +19,12 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
         .route("/assets", web::get().to(handlers::list_assets))
         .route("/assets/{asset_id}", web::get().to(handlers::get_asset))
         .route("/assets/{asset_id}", web::delete().to(handlers::delete_asset))
-        .route("/assets/{asset_id}/download", web::get().to(handlers::download_asset));
+        .route("/assets/{asset_id}/download", web::get().to(handlers::download_asset))
+        // Session recordings, written by presence
+        .service(
+            web::resource("/recordings/{recording_id}/chunks")
+                .app_data(web::PayloadConfig::new(recordings::MAX_CHUNK_SIZE))
+                .route(web::post().to(recordings::append_recording_chunk)),
+        )
+        .route("/recordings/{recording_id}", web::get().to(recordings::get_recording));
 }
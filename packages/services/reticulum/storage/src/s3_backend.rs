@@ -351,4 +351,19 @@ impl StorageBackend for S3StorageBackend {
     async fn cleanup_chunks(&self, _owner_id: &str, _session_id: &str) -> Result<(), StorageError> {
         Err(StorageError::Storage("Chunked uploads not supported for S3 storage".to_string()))
     }
+
+    /// Append to a session recording (not applicable to S3, whose objects cannot be appended to)
+    async fn append_recording(
+        &self,
+        _recording_id: &str,
+        _data: &[u8],
+        _max_size: u64,
+    ) -> Result<u64, StorageError> {
+        Err(StorageError::Storage("Session recordings not supported for S3 storage".to_string()))
+    }
+
+    /// Retrieve a session recording (not applicable to S3)
+    async fn get_recording(&self, _recording_id: &str) -> Result<Vec<u8>, StorageError> {
+        Err(StorageError::Storage("Session recordings not supported for S3 storage".to_string()))
+    }
 }
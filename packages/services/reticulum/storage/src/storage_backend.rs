@@ -92,6 +92,13 @@ pub trait StorageBackend: Send + Sync {
 
     /// Clean up all chunks for a session
     async fn cleanup_chunks(&self, owner_id: &str, session_id: &str) -> StorageResult<()>;
+
+    /// Append to the log of a recorded session, creating it if needed.
+    /// Returns the size of the log afterwards.
+    async fn append_recording(&self, recording_id: &str, data: &[u8], max_size: u64) -> StorageResult<u64>;
+
+    /// Retrieve the log of a recorded session
+    async fn get_recording(&self, recording_id: &str) -> StorageResult<Vec<u8>>;
 }
 
 #[derive(Debug, Clone, Default)]
@@ -403,4 +410,41 @@ impl StorageBackend for LocalStorageBackend {
 
         Ok(())
     }
+
+    async fn append_recording(&self, recording_id: &str, data: &[u8], max_size: u64) -> StorageResult<u64> {
+        use tokio::io::AsyncWriteExt;
+
+        let recordings_dir = Path::new(&self.base_path).join("recordings");
+        tokio::fs::create_dir_all(&recordings_dir).await?;
+
+        let path = recordings_dir.join(format!("{}.gwrec", recording_id));
+        let size = match tokio::fs::metadata(&path).await {
+            Ok(metadata) => metadata.len(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(StorageError::Io(e)),
+        };
+        let new_size = size + data.len() as u64;
+        if new_size > max_size {
+            return Err(StorageError::FileTooLarge { size: new_size, max: max_size });
+        }
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await?;
+        file.write_all(data).await?;
+        file.flush().await?;
+
+        Ok(new_size)
+    }
+
+    async fn get_recording(&self, recording_id: &str) -> StorageResult<Vec<u8>> {
+        let path = Path::new(&self.base_path)
+            .join("recordings")
+            .join(format!("{}.gwrec", recording_id));
+        tokio::fs::read(&path)
+            .await
+            .map_err(|_e| StorageError::FileNotFound(recording_id.to_string()))
+    }
 }
@@ -25,13 +25,13 @@ fn main() -> Result<()> {
     // Get OUT_DIR for generated files
     let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
 
-    // Generate Rust code from protobuf definitions
-    prost_build::Config::new()
+    // Generate Rust code from protobuf definitions, with gRPC clients and servers
+    tonic_build::configure()
         .out_dir(&out_dir)
-        .compile_well_known_types()
+        .compile_well_known_types(true)
         .extern_path(".google.protobuf", "::prost_types")
         .file_descriptor_set_path(out_dir.join("file_descriptor_set.bin"))
-        .compile_protos(
+        .compile(
             &[
                 proto_dir.join("core.proto"),
                 proto_dir.join("networking.proto"),
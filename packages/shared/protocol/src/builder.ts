@@ -68,6 +68,14 @@ export class MessageBuilder {
     return this.create(MessageType.ENTITY_DESPAWN, { entityId });
   }
 
+  /**
+   * Create a message undoing the sender's last entity change, or redoing
+   * their last undone one
+   */
+  static createEntityHistory(redo: boolean): Message {
+    return this.create(MessageType.ENTITY_HISTORY, { redo });
+  }
+
   /**
    * Create a chat message
    */
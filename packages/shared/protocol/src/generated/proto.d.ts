@@ -18,18 +18,30 @@ export namespace graphwiz {
             /** Message type */
             type?: (graphwiz.core.MessageType|null);
 
+            /** Message sequence */
+            sequence?: (number|null);
+
             /** Message clientHello */
             clientHello?: (graphwiz.core.IClientHello|null);
 
             /** Message serverHello */
             serverHello?: (graphwiz.core.IServerHello|null);
 
+            /** Message redirect */
+            redirect?: (graphwiz.core.IRedirect|null);
+
             /** Message positionUpdate */
             positionUpdate?: (graphwiz.core.IPositionUpdate|null);
 
             /** Message voiceData */
             voiceData?: (graphwiz.core.IVoiceData|null);
 
+            /** Message positionBatch */
+            positionBatch?: (graphwiz.core.IPositionBatch|null);
+
+            /** Message spatialAudio */
+            spatialAudio?: (graphwiz.core.ISpatialAudio|null);
+
             /** Message entitySpawn */
             entitySpawn?: (graphwiz.core.IEntitySpawn|null);
 
@@ -39,11 +51,23 @@ export namespace graphwiz {
             /** Message entityDespawn */
             entityDespawn?: (graphwiz.core.IEntityDespawn|null);
 
+            /** Message entityHistory */
+            entityHistory?: (graphwiz.core.IEntityHistory|null);
+
             /** Message chatMessage */
             chatMessage?: (graphwiz.core.IChatMessage|null);
 
             /** Message presenceEvent */
             presenceEvent?: (graphwiz.core.IPresenceEvent|null);
+
+            /** Message presenceSubscription */
+            presenceSubscription?: (graphwiz.core.IPresenceSubscription|null);
+
+            /** Message errorFrame */
+            errorFrame?: (graphwiz.core.IErrorFrame|null);
+
+            /** Message rtcSignal */
+            rtcSignal?: (graphwiz.core.IRtcSignal|null);
         }
 
         /** Represents a Message. */
@@ -64,18 +88,30 @@ export namespace graphwiz {
             /** Message type. */
             public type: graphwiz.core.MessageType;
 
+            /** Message sequence. */
+            public sequence: number;
+
             /** Message clientHello. */
             public clientHello?: (graphwiz.core.IClientHello|null);
 
             /** Message serverHello. */
             public serverHello?: (graphwiz.core.IServerHello|null);
 
+            /** Message redirect. */
+            public redirect?: (graphwiz.core.IRedirect|null);
+
             /** Message positionUpdate. */
             public positionUpdate?: (graphwiz.core.IPositionUpdate|null);
 
             /** Message voiceData. */
             public voiceData?: (graphwiz.core.IVoiceData|null);
 
+            /** Message positionBatch. */
+            public positionBatch?: (graphwiz.core.IPositionBatch|null);
+
+            /** Message spatialAudio. */
+            public spatialAudio?: (graphwiz.core.ISpatialAudio|null);
+
             /** Message entitySpawn. */
             public entitySpawn?: (graphwiz.core.IEntitySpawn|null);
 
@@ -85,14 +121,26 @@ export namespace graphwiz {
             /** Message entityDespawn. */
             public entityDespawn?: (graphwiz.core.IEntityDespawn|null);
 
+            /** Message entityHistory. */
+            public entityHistory?: (graphwiz.core.IEntityHistory|null);
+
             /** Message chatMessage. */
             public chatMessage?: (graphwiz.core.IChatMessage|null);
 
             /** Message presenceEvent. */
             public presenceEvent?: (graphwiz.core.IPresenceEvent|null);
 
+            /** Message presenceSubscription. */
+            public presenceSubscription?: (graphwiz.core.IPresenceSubscription|null);
+
+            /** Message errorFrame. */
+            public errorFrame?: (graphwiz.core.IErrorFrame|null);
+
+            /** Message rtcSignal. */
+            public rtcSignal?: (graphwiz.core.IRtcSignal|null);
+
             /** Message payload. */
-            public payload?: ("clientHello"|"serverHello"|"positionUpdate"|"voiceData"|"entitySpawn"|"entityUpdate"|"entityDespawn"|"chatMessage"|"presenceEvent");
+            public payload?: ("clientHello"|"serverHello"|"redirect"|"positionUpdate"|"voiceData"|"positionBatch"|"spatialAudio"|"entitySpawn"|"entityUpdate"|"entityDespawn"|"entityHistory"|"chatMessage"|"presenceEvent"|"presenceSubscription"|"errorFrame"|"rtcSignal");
 
             /**
              * Creates a new Message instance using the specified properties.
@@ -177,15 +225,24 @@ export namespace graphwiz {
             UNKNOWN = 0,
             CLIENT_HELLO = 1,
             SERVER_HELLO = 2,
+            ERROR = 3,
+            REDIRECT = 4,
             POSITION_UPDATE = 10,
             VOICE_DATA = 11,
+            POSITION_BATCH = 12,
+            SPATIAL_AUDIO = 13,
             ENTITY_SPAWN = 20,
             ENTITY_UPDATE = 21,
             ENTITY_DESPAWN = 22,
+            ENTITY_HISTORY = 23,
             CHAT_MESSAGE = 30,
             PRESENCE_JOIN = 40,
             PRESENCE_LEAVE = 41,
-            PRESENCE_UPDATE = 42
+            PRESENCE_UPDATE = 42,
+            PRESENCE_HOST_CHANGED = 43,
+            PRESENCE_STATUS = 44,
+            PRESENCE_SUBSCRIBE = 45,
+            RTC_SIGNAL = 50
         }
 
         /** Properties of a Vector3. */
@@ -527,6 +584,315 @@ export namespace graphwiz {
             public static getTypeUrl(typeUrlPrefix?: string): string;
         }
 
+        /** Properties of a PositionBatch. */
+        interface IPositionBatch {
+
+            /** PositionBatch updates */
+            updates?: (graphwiz.core.IPositionUpdate[]|null);
+
+            /** PositionBatch tick */
+            tick?: (number|Long|null);
+        }
+
+        /** Represents a PositionBatch. */
+        class PositionBatch implements IPositionBatch {
+
+            /**
+             * Constructs a new PositionBatch.
+             * @param [properties] Properties to set
+             */
+            constructor(properties?: graphwiz.core.IPositionBatch);
+
+            /** PositionBatch updates. */
+            public updates: graphwiz.core.IPositionUpdate[];
+
+            /** PositionBatch tick. */
+            public tick: (number|Long);
+
+            /**
+             * Creates a new PositionBatch instance using the specified properties.
+             * @param [properties] Properties to set
+             * @returns PositionBatch instance
+             */
+            public static create(properties?: graphwiz.core.IPositionBatch): graphwiz.core.PositionBatch;
+
+            /**
+             * Encodes the specified PositionBatch message. Does not implicitly {@link graphwiz.core.PositionBatch.verify|verify} messages.
+             * @param message PositionBatch message or plain object to encode
+             * @param [writer] Writer to encode to
+             * @returns Writer
+             */
+            public static encode(message: graphwiz.core.IPositionBatch, writer?: $protobuf.Writer): $protobuf.Writer;
+
+            /**
+             * Encodes the specified PositionBatch message, length delimited. Does not implicitly {@link graphwiz.core.PositionBatch.verify|verify} messages.
+             * @param message PositionBatch message or plain object to encode
+             * @param [writer] Writer to encode to
+             * @returns Writer
+             */
+            public static encodeDelimited(message: graphwiz.core.IPositionBatch, writer?: $protobuf.Writer): $protobuf.Writer;
+
+            /**
+             * Decodes a PositionBatch message from the specified reader or buffer.
+             * @param reader Reader or buffer to decode from
+             * @param [length] Message length if known beforehand
+             * @returns PositionBatch
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            public static decode(reader: ($protobuf.Reader|Uint8Array), length?: number): graphwiz.core.PositionBatch;
+
+            /**
+             * Decodes a PositionBatch message from the specified reader or buffer, length delimited.
+             * @param reader Reader or buffer to decode from
+             * @returns PositionBatch
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            public static decodeDelimited(reader: ($protobuf.Reader|Uint8Array)): graphwiz.core.PositionBatch;
+
+            /**
+             * Verifies a PositionBatch message.
+             * @param message Plain object to verify
+             * @returns `null` if valid, otherwise the reason why it is not
+             */
+            public static verify(message: { [k: string]: any }): (string|null);
+
+            /**
+             * Creates a PositionBatch message from a plain object. Also converts values to their respective internal types.
+             * @param object Plain object
+             * @returns PositionBatch
+             */
+            public static fromObject(object: { [k: string]: any }): graphwiz.core.PositionBatch;
+
+            /**
+             * Creates a plain object from a PositionBatch message. Also converts values to other types if specified.
+             * @param message PositionBatch
+             * @param [options] Conversion options
+             * @returns Plain object
+             */
+            public static toObject(message: graphwiz.core.PositionBatch, options?: $protobuf.IConversionOptions): { [k: string]: any };
+
+            /**
+             * Converts this PositionBatch to JSON.
+             * @returns JSON object
+             */
+            public toJSON(): { [k: string]: any };
+
+            /**
+             * Gets the default type url for PositionBatch
+             * @param [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
+             * @returns The default type url
+             */
+            public static getTypeUrl(typeUrlPrefix?: string): string;
+        }
+
+        /** Properties of a SpatialAudio. */
+        interface ISpatialAudio {
+
+            /** SpatialAudio speakers */
+            speakers?: (graphwiz.core.ISpeakerSpatial[]|null);
+        }
+
+        /** Represents a SpatialAudio. */
+        class SpatialAudio implements ISpatialAudio {
+
+            /**
+             * Constructs a new SpatialAudio.
+             * @param [properties] Properties to set
+             */
+            constructor(properties?: graphwiz.core.ISpatialAudio);
+
+            /** SpatialAudio speakers. */
+            public speakers: graphwiz.core.ISpeakerSpatial[];
+
+            /**
+             * Creates a new SpatialAudio instance using the specified properties.
+             * @param [properties] Properties to set
+             * @returns SpatialAudio instance
+             */
+            public static create(properties?: graphwiz.core.ISpatialAudio): graphwiz.core.SpatialAudio;
+
+            /**
+             * Encodes the specified SpatialAudio message. Does not implicitly {@link graphwiz.core.SpatialAudio.verify|verify} messages.
+             * @param message SpatialAudio message or plain object to encode
+             * @param [writer] Writer to encode to
+             * @returns Writer
+             */
+            public static encode(message: graphwiz.core.ISpatialAudio, writer?: $protobuf.Writer): $protobuf.Writer;
+
+            /**
+             * Encodes the specified SpatialAudio message, length delimited. Does not implicitly {@link graphwiz.core.SpatialAudio.verify|verify} messages.
+             * @param message SpatialAudio message or plain object to encode
+             * @param [writer] Writer to encode to
+             * @returns Writer
+             */
+            public static encodeDelimited(message: graphwiz.core.ISpatialAudio, writer?: $protobuf.Writer): $protobuf.Writer;
+
+            /**
+             * Decodes a SpatialAudio message from the specified reader or buffer.
+             * @param reader Reader or buffer to decode from
+             * @param [length] Message length if known beforehand
+             * @returns SpatialAudio
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            public static decode(reader: ($protobuf.Reader|Uint8Array), length?: number): graphwiz.core.SpatialAudio;
+
+            /**
+             * Decodes a SpatialAudio message from the specified reader or buffer, length delimited.
+             * @param reader Reader or buffer to decode from
+             * @returns SpatialAudio
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            public static decodeDelimited(reader: ($protobuf.Reader|Uint8Array)): graphwiz.core.SpatialAudio;
+
+            /**
+             * Verifies a SpatialAudio message.
+             * @param message Plain object to verify
+             * @returns `null` if valid, otherwise the reason why it is not
+             */
+            public static verify(message: { [k: string]: any }): (string|null);
+
+            /**
+             * Creates a SpatialAudio message from a plain object. Also converts values to their respective internal types.
+             * @param object Plain object
+             * @returns SpatialAudio
+             */
+            public static fromObject(object: { [k: string]: any }): graphwiz.core.SpatialAudio;
+
+            /**
+             * Creates a plain object from a SpatialAudio message. Also converts values to other types if specified.
+             * @param message SpatialAudio
+             * @param [options] Conversion options
+             * @returns Plain object
+             */
+            public static toObject(message: graphwiz.core.SpatialAudio, options?: $protobuf.IConversionOptions): { [k: string]: any };
+
+            /**
+             * Converts this SpatialAudio to JSON.
+             * @returns JSON object
+             */
+            public toJSON(): { [k: string]: any };
+
+            /**
+             * Gets the default type url for SpatialAudio
+             * @param [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
+             * @returns The default type url
+             */
+            public static getTypeUrl(typeUrlPrefix?: string): string;
+        }
+
+        /** Properties of a SpeakerSpatial. */
+        interface ISpeakerSpatial {
+
+            /** SpeakerSpatial clientId */
+            clientId?: (string|null);
+
+            /** SpeakerSpatial gain */
+            gain?: (number|null);
+
+            /** SpeakerSpatial pan */
+            pan?: (number|null);
+        }
+
+        /** Represents a SpeakerSpatial. */
+        class SpeakerSpatial implements ISpeakerSpatial {
+
+            /**
+             * Constructs a new SpeakerSpatial.
+             * @param [properties] Properties to set
+             */
+            constructor(properties?: graphwiz.core.ISpeakerSpatial);
+
+            /** SpeakerSpatial clientId. */
+            public clientId: string;
+
+            /** SpeakerSpatial gain. */
+            public gain: number;
+
+            /** SpeakerSpatial pan. */
+            public pan: number;
+
+            /**
+             * Creates a new SpeakerSpatial instance using the specified properties.
+             * @param [properties] Properties to set
+             * @returns SpeakerSpatial instance
+             */
+            public static create(properties?: graphwiz.core.ISpeakerSpatial): graphwiz.core.SpeakerSpatial;
+
+            /**
+             * Encodes the specified SpeakerSpatial message. Does not implicitly {@link graphwiz.core.SpeakerSpatial.verify|verify} messages.
+             * @param message SpeakerSpatial message or plain object to encode
+             * @param [writer] Writer to encode to
+             * @returns Writer
+             */
+            public static encode(message: graphwiz.core.ISpeakerSpatial, writer?: $protobuf.Writer): $protobuf.Writer;
+
+            /**
+             * Encodes the specified SpeakerSpatial message, length delimited. Does not implicitly {@link graphwiz.core.SpeakerSpatial.verify|verify} messages.
+             * @param message SpeakerSpatial message or plain object to encode
+             * @param [writer] Writer to encode to
+             * @returns Writer
+             */
+            public static encodeDelimited(message: graphwiz.core.ISpeakerSpatial, writer?: $protobuf.Writer): $protobuf.Writer;
+
+            /**
+             * Decodes a SpeakerSpatial message from the specified reader or buffer.
+             * @param reader Reader or buffer to decode from
+             * @param [length] Message length if known beforehand
+             * @returns SpeakerSpatial
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            public static decode(reader: ($protobuf.Reader|Uint8Array), length?: number): graphwiz.core.SpeakerSpatial;
+
+            /**
+             * Decodes a SpeakerSpatial message from the specified reader or buffer, length delimited.
+             * @param reader Reader or buffer to decode from
+             * @returns SpeakerSpatial
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            public static decodeDelimited(reader: ($protobuf.Reader|Uint8Array)): graphwiz.core.SpeakerSpatial;
+
+            /**
+             * Verifies a SpeakerSpatial message.
+             * @param message Plain object to verify
+             * @returns `null` if valid, otherwise the reason why it is not
+             */
+            public static verify(message: { [k: string]: any }): (string|null);
+
+            /**
+             * Creates a SpeakerSpatial message from a plain object. Also converts values to their respective internal types.
+             * @param object Plain object
+             * @returns SpeakerSpatial
+             */
+            public static fromObject(object: { [k: string]: any }): graphwiz.core.SpeakerSpatial;
+
+            /**
+             * Creates a plain object from a SpeakerSpatial message. Also converts values to other types if specified.
+             * @param message SpeakerSpatial
+             * @param [options] Conversion options
+             * @returns Plain object
+             */
+            public static toObject(message: graphwiz.core.SpeakerSpatial, options?: $protobuf.IConversionOptions): { [k: string]: any };
+
+            /**
+             * Converts this SpeakerSpatial to JSON.
+             * @returns JSON object
+             */
+            public toJSON(): { [k: string]: any };
+
+            /**
+             * Gets the default type url for SpeakerSpatial
+             * @param [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
+             * @returns The default type url
+             */
+            public static getTypeUrl(typeUrlPrefix?: string): string;
+        }
+
         /** Properties of a VoiceData. */
         interface IVoiceData {
 
@@ -964,84 +1330,205 @@ export namespace graphwiz {
             public static getTypeUrl(typeUrlPrefix?: string): string;
         }
 
-        /** Properties of a ChatMessage. */
-        interface IChatMessage {
-
-            /** ChatMessage fromClientId */
-            fromClientId?: (string|null);
-
-            /** ChatMessage message */
-            message?: (string|null);
-
-            /** ChatMessage timestamp */
-            timestamp?: (number|Long|null);
+        /** Properties of an EntityHistory. */
+        interface IEntityHistory {
 
-            /** ChatMessage type */
-            type?: (graphwiz.core.ChatMessageType|null);
+            /** EntityHistory redo */
+            redo?: (boolean|null);
         }
 
-        /** Represents a ChatMessage. */
-        class ChatMessage implements IChatMessage {
+        /** Represents an EntityHistory. */
+        class EntityHistory implements IEntityHistory {
 
             /**
-             * Constructs a new ChatMessage.
+             * Constructs a new EntityHistory.
              * @param [properties] Properties to set
              */
-            constructor(properties?: graphwiz.core.IChatMessage);
-
-            /** ChatMessage fromClientId. */
-            public fromClientId: string;
-
-            /** ChatMessage message. */
-            public message: string;
+            constructor(properties?: graphwiz.core.IEntityHistory);
 
-            /** ChatMessage timestamp. */
-            public timestamp: (number|Long);
-
-            /** ChatMessage type. */
-            public type: graphwiz.core.ChatMessageType;
+            /** EntityHistory redo. */
+            public redo: boolean;
 
             /**
-             * Creates a new ChatMessage instance using the specified properties.
+             * Creates a new EntityHistory instance using the specified properties.
              * @param [properties] Properties to set
-             * @returns ChatMessage instance
+             * @returns EntityHistory instance
              */
-            public static create(properties?: graphwiz.core.IChatMessage): graphwiz.core.ChatMessage;
+            public static create(properties?: graphwiz.core.IEntityHistory): graphwiz.core.EntityHistory;
 
             /**
-             * Encodes the specified ChatMessage message. Does not implicitly {@link graphwiz.core.ChatMessage.verify|verify} messages.
-             * @param message ChatMessage message or plain object to encode
+             * Encodes the specified EntityHistory message. Does not implicitly {@link graphwiz.core.EntityHistory.verify|verify} messages.
+             * @param message EntityHistory message or plain object to encode
              * @param [writer] Writer to encode to
              * @returns Writer
              */
-            public static encode(message: graphwiz.core.IChatMessage, writer?: $protobuf.Writer): $protobuf.Writer;
+            public static encode(message: graphwiz.core.IEntityHistory, writer?: $protobuf.Writer): $protobuf.Writer;
 
             /**
-             * Encodes the specified ChatMessage message, length delimited. Does not implicitly {@link graphwiz.core.ChatMessage.verify|verify} messages.
-             * @param message ChatMessage message or plain object to encode
+             * Encodes the specified EntityHistory message, length delimited. Does not implicitly {@link graphwiz.core.EntityHistory.verify|verify} messages.
+             * @param message EntityHistory message or plain object to encode
              * @param [writer] Writer to encode to
              * @returns Writer
              */
-            public static encodeDelimited(message: graphwiz.core.IChatMessage, writer?: $protobuf.Writer): $protobuf.Writer;
+            public static encodeDelimited(message: graphwiz.core.IEntityHistory, writer?: $protobuf.Writer): $protobuf.Writer;
 
             /**
-             * Decodes a ChatMessage message from the specified reader or buffer.
+             * Decodes an EntityHistory message from the specified reader or buffer.
              * @param reader Reader or buffer to decode from
              * @param [length] Message length if known beforehand
-             * @returns ChatMessage
+             * @returns EntityHistory
              * @throws {Error} If the payload is not a reader or valid buffer
              * @throws {$protobuf.util.ProtocolError} If required fields are missing
              */
-            public static decode(reader: ($protobuf.Reader|Uint8Array), length?: number): graphwiz.core.ChatMessage;
+            public static decode(reader: ($protobuf.Reader|Uint8Array), length?: number): graphwiz.core.EntityHistory;
 
             /**
-             * Decodes a ChatMessage message from the specified reader or buffer, length delimited.
+             * Decodes an EntityHistory message from the specified reader or buffer, length delimited.
              * @param reader Reader or buffer to decode from
-             * @returns ChatMessage
+             * @returns EntityHistory
              * @throws {Error} If the payload is not a reader or valid buffer
              * @throws {$protobuf.util.ProtocolError} If required fields are missing
              */
-            public static decodeDelimited(reader: ($protobuf.Reader|Uint8Array)): graphwiz.core.ChatMessage;
+            public static decodeDelimited(reader: ($protobuf.Reader|Uint8Array)): graphwiz.core.EntityHistory;
+
+            /**
+             * Verifies an EntityHistory message.
+             * @param message Plain object to verify
+             * @returns `null` if valid, otherwise the reason why it is not
+             */
+            public static verify(message: { [k: string]: any }): (string|null);
+
+            /**
+             * Creates an EntityHistory message from a plain object. Also converts values to their respective internal types.
+             * @param object Plain object
+             * @returns EntityHistory
+             */
+            public static fromObject(object: { [k: string]: any }): graphwiz.core.EntityHistory;
+
+            /**
+             * Creates a plain object from an EntityHistory message. Also converts values to other types if specified.
+             * @param message EntityHistory
+             * @param [options] Conversion options
+             * @returns Plain object
+             */
+            public static toObject(message: graphwiz.core.EntityHistory, options?: $protobuf.IConversionOptions): { [k: string]: any };
+
+            /**
+             * Converts this EntityHistory to JSON.
+             * @returns JSON object
+             */
+            public toJSON(): { [k: string]: any };
+
+            /**
+             * Gets the default type url for EntityHistory
+             * @param [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
+             * @returns The default type url
+             */
+            public static getTypeUrl(typeUrlPrefix?: string): string;
+        }
+
+        /** Properties of a ChatMessage. */
+        interface IChatMessage {
+
+            /** ChatMessage fromClientId */
+            fromClientId?: (string|null);
+
+            /** ChatMessage message */
+            message?: (string|null);
+
+            /** ChatMessage timestamp */
+            timestamp?: (number|Long|null);
+
+            /** ChatMessage type */
+            type?: (graphwiz.core.ChatMessageType|null);
+
+            /** ChatMessage toUserId */
+            toUserId?: (string|null);
+
+            /** ChatMessage channel */
+            channel?: (string|null);
+
+            /** ChatMessage chatId */
+            chatId?: (string|null);
+
+            /** ChatMessage deleted */
+            deleted?: (boolean|null);
+        }
+
+        /** Represents a ChatMessage. */
+        class ChatMessage implements IChatMessage {
+
+            /**
+             * Constructs a new ChatMessage.
+             * @param [properties] Properties to set
+             */
+            constructor(properties?: graphwiz.core.IChatMessage);
+
+            /** ChatMessage fromClientId. */
+            public fromClientId: string;
+
+            /** ChatMessage message. */
+            public message: string;
+
+            /** ChatMessage timestamp. */
+            public timestamp: (number|Long);
+
+            /** ChatMessage type. */
+            public type: graphwiz.core.ChatMessageType;
+
+            /** ChatMessage toUserId. */
+            public toUserId: string;
+
+            /** ChatMessage channel. */
+            public channel: string;
+
+            /** ChatMessage chatId. */
+            public chatId: string;
+
+            /** ChatMessage deleted. */
+            public deleted: boolean;
+
+            /**
+             * Creates a new ChatMessage instance using the specified properties.
+             * @param [properties] Properties to set
+             * @returns ChatMessage instance
+             */
+            public static create(properties?: graphwiz.core.IChatMessage): graphwiz.core.ChatMessage;
+
+            /**
+             * Encodes the specified ChatMessage message. Does not implicitly {@link graphwiz.core.ChatMessage.verify|verify} messages.
+             * @param message ChatMessage message or plain object to encode
+             * @param [writer] Writer to encode to
+             * @returns Writer
+             */
+            public static encode(message: graphwiz.core.IChatMessage, writer?: $protobuf.Writer): $protobuf.Writer;
+
+            /**
+             * Encodes the specified ChatMessage message, length delimited. Does not implicitly {@link graphwiz.core.ChatMessage.verify|verify} messages.
+             * @param message ChatMessage message or plain object to encode
+             * @param [writer] Writer to encode to
+             * @returns Writer
+             */
+            public static encodeDelimited(message: graphwiz.core.IChatMessage, writer?: $protobuf.Writer): $protobuf.Writer;
+
+            /**
+             * Decodes a ChatMessage message from the specified reader or buffer.
+             * @param reader Reader or buffer to decode from
+             * @param [length] Message length if known beforehand
+             * @returns ChatMessage
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            public static decode(reader: ($protobuf.Reader|Uint8Array), length?: number): graphwiz.core.ChatMessage;
+
+            /**
+             * Decodes a ChatMessage message from the specified reader or buffer, length delimited.
+             * @param reader Reader or buffer to decode from
+             * @returns ChatMessage
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            public static decodeDelimited(reader: ($protobuf.Reader|Uint8Array)): graphwiz.core.ChatMessage;
 
             /**
              * Verifies a ChatMessage message.
@@ -1053,282 +1540,658 @@ export namespace graphwiz {
             /**
              * Creates a ChatMessage message from a plain object. Also converts values to their respective internal types.
              * @param object Plain object
-             * @returns ChatMessage
+             * @returns ChatMessage
+             */
+            public static fromObject(object: { [k: string]: any }): graphwiz.core.ChatMessage;
+
+            /**
+             * Creates a plain object from a ChatMessage message. Also converts values to other types if specified.
+             * @param message ChatMessage
+             * @param [options] Conversion options
+             * @returns Plain object
+             */
+            public static toObject(message: graphwiz.core.ChatMessage, options?: $protobuf.IConversionOptions): { [k: string]: any };
+
+            /**
+             * Converts this ChatMessage to JSON.
+             * @returns JSON object
+             */
+            public toJSON(): { [k: string]: any };
+
+            /**
+             * Gets the default type url for ChatMessage
+             * @param [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
+             * @returns The default type url
+             */
+            public static getTypeUrl(typeUrlPrefix?: string): string;
+        }
+
+        /** ChatMessageType enum. */
+        enum ChatMessageType {
+            NORMAL = 0,
+            WHISPER = 1,
+            SHOUT = 2,
+            TEAM = 3
+        }
+
+        /** Properties of an ErrorFrame. */
+        interface IErrorFrame {
+
+            /** ErrorFrame code */
+            code?: (string|null);
+
+            /** ErrorFrame message */
+            message?: (string|null);
+
+            /** ErrorFrame retryAfterMs */
+            retryAfterMs?: (number|Long|null);
+        }
+
+        /** Represents an ErrorFrame. */
+        class ErrorFrame implements IErrorFrame {
+
+            /**
+             * Constructs a new ErrorFrame.
+             * @param [properties] Properties to set
+             */
+            constructor(properties?: graphwiz.core.IErrorFrame);
+
+            /** ErrorFrame code. */
+            public code: string;
+
+            /** ErrorFrame message. */
+            public message: string;
+
+            /** ErrorFrame retryAfterMs. */
+            public retryAfterMs: (number|Long);
+
+            /**
+             * Creates a new ErrorFrame instance using the specified properties.
+             * @param [properties] Properties to set
+             * @returns ErrorFrame instance
+             */
+            public static create(properties?: graphwiz.core.IErrorFrame): graphwiz.core.ErrorFrame;
+
+            /**
+             * Encodes the specified ErrorFrame message. Does not implicitly {@link graphwiz.core.ErrorFrame.verify|verify} messages.
+             * @param message ErrorFrame message or plain object to encode
+             * @param [writer] Writer to encode to
+             * @returns Writer
+             */
+            public static encode(message: graphwiz.core.IErrorFrame, writer?: $protobuf.Writer): $protobuf.Writer;
+
+            /**
+             * Encodes the specified ErrorFrame message, length delimited. Does not implicitly {@link graphwiz.core.ErrorFrame.verify|verify} messages.
+             * @param message ErrorFrame message or plain object to encode
+             * @param [writer] Writer to encode to
+             * @returns Writer
+             */
+            public static encodeDelimited(message: graphwiz.core.IErrorFrame, writer?: $protobuf.Writer): $protobuf.Writer;
+
+            /**
+             * Decodes an ErrorFrame message from the specified reader or buffer.
+             * @param reader Reader or buffer to decode from
+             * @param [length] Message length if known beforehand
+             * @returns ErrorFrame
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            public static decode(reader: ($protobuf.Reader|Uint8Array), length?: number): graphwiz.core.ErrorFrame;
+
+            /**
+             * Decodes an ErrorFrame message from the specified reader or buffer, length delimited.
+             * @param reader Reader or buffer to decode from
+             * @returns ErrorFrame
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            public static decodeDelimited(reader: ($protobuf.Reader|Uint8Array)): graphwiz.core.ErrorFrame;
+
+            /**
+             * Verifies an ErrorFrame message.
+             * @param message Plain object to verify
+             * @returns `null` if valid, otherwise the reason why it is not
+             */
+            public static verify(message: { [k: string]: any }): (string|null);
+
+            /**
+             * Creates an ErrorFrame message from a plain object. Also converts values to their respective internal types.
+             * @param object Plain object
+             * @returns ErrorFrame
+             */
+            public static fromObject(object: { [k: string]: any }): graphwiz.core.ErrorFrame;
+
+            /**
+             * Creates a plain object from an ErrorFrame message. Also converts values to other types if specified.
+             * @param message ErrorFrame
+             * @param [options] Conversion options
+             * @returns Plain object
+             */
+            public static toObject(message: graphwiz.core.ErrorFrame, options?: $protobuf.IConversionOptions): { [k: string]: any };
+
+            /**
+             * Converts this ErrorFrame to JSON.
+             * @returns JSON object
+             */
+            public toJSON(): { [k: string]: any };
+
+            /**
+             * Gets the default type url for ErrorFrame
+             * @param [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
+             * @returns The default type url
+             */
+            public static getTypeUrl(typeUrlPrefix?: string): string;
+        }
+
+        /** Properties of a PresenceEvent. */
+        interface IPresenceEvent {
+
+            /** PresenceEvent clientId */
+            clientId?: (string|null);
+
+            /** PresenceEvent eventType */
+            eventType?: (graphwiz.core.PresenceEventType|null);
+
+            /** PresenceEvent data */
+            data?: (graphwiz.core.IPresenceData|null);
+        }
+
+        /** Represents a PresenceEvent. */
+        class PresenceEvent implements IPresenceEvent {
+
+            /**
+             * Constructs a new PresenceEvent.
+             * @param [properties] Properties to set
+             */
+            constructor(properties?: graphwiz.core.IPresenceEvent);
+
+            /** PresenceEvent clientId. */
+            public clientId: string;
+
+            /** PresenceEvent eventType. */
+            public eventType: graphwiz.core.PresenceEventType;
+
+            /** PresenceEvent data. */
+            public data?: (graphwiz.core.IPresenceData|null);
+
+            /**
+             * Creates a new PresenceEvent instance using the specified properties.
+             * @param [properties] Properties to set
+             * @returns PresenceEvent instance
+             */
+            public static create(properties?: graphwiz.core.IPresenceEvent): graphwiz.core.PresenceEvent;
+
+            /**
+             * Encodes the specified PresenceEvent message. Does not implicitly {@link graphwiz.core.PresenceEvent.verify|verify} messages.
+             * @param message PresenceEvent message or plain object to encode
+             * @param [writer] Writer to encode to
+             * @returns Writer
+             */
+            public static encode(message: graphwiz.core.IPresenceEvent, writer?: $protobuf.Writer): $protobuf.Writer;
+
+            /**
+             * Encodes the specified PresenceEvent message, length delimited. Does not implicitly {@link graphwiz.core.PresenceEvent.verify|verify} messages.
+             * @param message PresenceEvent message or plain object to encode
+             * @param [writer] Writer to encode to
+             * @returns Writer
+             */
+            public static encodeDelimited(message: graphwiz.core.IPresenceEvent, writer?: $protobuf.Writer): $protobuf.Writer;
+
+            /**
+             * Decodes a PresenceEvent message from the specified reader or buffer.
+             * @param reader Reader or buffer to decode from
+             * @param [length] Message length if known beforehand
+             * @returns PresenceEvent
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            public static decode(reader: ($protobuf.Reader|Uint8Array), length?: number): graphwiz.core.PresenceEvent;
+
+            /**
+             * Decodes a PresenceEvent message from the specified reader or buffer, length delimited.
+             * @param reader Reader or buffer to decode from
+             * @returns PresenceEvent
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            public static decodeDelimited(reader: ($protobuf.Reader|Uint8Array)): graphwiz.core.PresenceEvent;
+
+            /**
+             * Verifies a PresenceEvent message.
+             * @param message Plain object to verify
+             * @returns `null` if valid, otherwise the reason why it is not
+             */
+            public static verify(message: { [k: string]: any }): (string|null);
+
+            /**
+             * Creates a PresenceEvent message from a plain object. Also converts values to their respective internal types.
+             * @param object Plain object
+             * @returns PresenceEvent
+             */
+            public static fromObject(object: { [k: string]: any }): graphwiz.core.PresenceEvent;
+
+            /**
+             * Creates a plain object from a PresenceEvent message. Also converts values to other types if specified.
+             * @param message PresenceEvent
+             * @param [options] Conversion options
+             * @returns Plain object
+             */
+            public static toObject(message: graphwiz.core.PresenceEvent, options?: $protobuf.IConversionOptions): { [k: string]: any };
+
+            /**
+             * Converts this PresenceEvent to JSON.
+             * @returns JSON object
+             */
+            public toJSON(): { [k: string]: any };
+
+            /**
+             * Gets the default type url for PresenceEvent
+             * @param [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
+             * @returns The default type url
+             */
+            public static getTypeUrl(typeUrlPrefix?: string): string;
+        }
+
+        /** PresenceEventType enum. */
+        enum PresenceEventType {
+            JOIN = 0,
+            LEAVE = 1,
+            UPDATE = 2,
+            HOST_CHANGED = 3,
+            STATUS = 4
+        }
+
+        /** Properties of a PresenceData. */
+        interface IPresenceData {
+
+            /** PresenceData displayName */
+            displayName?: (string|null);
+
+            /** PresenceData avatarUrl */
+            avatarUrl?: (string|null);
+
+            /** PresenceData position */
+            position?: (graphwiz.core.IVector3|null);
+
+            /** PresenceData rotation */
+            rotation?: (graphwiz.core.IQuaternion|null);
+
+            /** PresenceData avatarConfig */
+            avatarConfig?: (graphwiz.core.IAvatarConfig|null);
+
+            /** PresenceData hostClientId */
+            hostClientId?: (string|null);
+
+            /** PresenceData userId */
+            userId?: (string|null);
+
+            /** PresenceData status */
+            status?: (graphwiz.core.PresenceStatus|null);
+
+            /** PresenceData roomId */
+            roomId?: (string|null);
+        }
+
+        /** Represents a PresenceData. */
+        class PresenceData implements IPresenceData {
+
+            /**
+             * Constructs a new PresenceData.
+             * @param [properties] Properties to set
+             */
+            constructor(properties?: graphwiz.core.IPresenceData);
+
+            /** PresenceData displayName. */
+            public displayName: string;
+
+            /** PresenceData avatarUrl. */
+            public avatarUrl: string;
+
+            /** PresenceData position. */
+            public position?: (graphwiz.core.IVector3|null);
+
+            /** PresenceData rotation. */
+            public rotation?: (graphwiz.core.IQuaternion|null);
+
+            /** PresenceData avatarConfig. */
+            public avatarConfig?: (graphwiz.core.IAvatarConfig|null);
+
+            /** PresenceData hostClientId. */
+            public hostClientId: string;
+
+            /** PresenceData userId. */
+            public userId: string;
+
+            /** PresenceData status. */
+            public status: graphwiz.core.PresenceStatus;
+
+            /** PresenceData roomId. */
+            public roomId: string;
+
+            /**
+             * Creates a new PresenceData instance using the specified properties.
+             * @param [properties] Properties to set
+             * @returns PresenceData instance
+             */
+            public static create(properties?: graphwiz.core.IPresenceData): graphwiz.core.PresenceData;
+
+            /**
+             * Encodes the specified PresenceData message. Does not implicitly {@link graphwiz.core.PresenceData.verify|verify} messages.
+             * @param message PresenceData message or plain object to encode
+             * @param [writer] Writer to encode to
+             * @returns Writer
+             */
+            public static encode(message: graphwiz.core.IPresenceData, writer?: $protobuf.Writer): $protobuf.Writer;
+
+            /**
+             * Encodes the specified PresenceData message, length delimited. Does not implicitly {@link graphwiz.core.PresenceData.verify|verify} messages.
+             * @param message PresenceData message or plain object to encode
+             * @param [writer] Writer to encode to
+             * @returns Writer
+             */
+            public static encodeDelimited(message: graphwiz.core.IPresenceData, writer?: $protobuf.Writer): $protobuf.Writer;
+
+            /**
+             * Decodes a PresenceData message from the specified reader or buffer.
+             * @param reader Reader or buffer to decode from
+             * @param [length] Message length if known beforehand
+             * @returns PresenceData
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            public static decode(reader: ($protobuf.Reader|Uint8Array), length?: number): graphwiz.core.PresenceData;
+
+            /**
+             * Decodes a PresenceData message from the specified reader or buffer, length delimited.
+             * @param reader Reader or buffer to decode from
+             * @returns PresenceData
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            public static decodeDelimited(reader: ($protobuf.Reader|Uint8Array)): graphwiz.core.PresenceData;
+
+            /**
+             * Verifies a PresenceData message.
+             * @param message Plain object to verify
+             * @returns `null` if valid, otherwise the reason why it is not
+             */
+            public static verify(message: { [k: string]: any }): (string|null);
+
+            /**
+             * Creates a PresenceData message from a plain object. Also converts values to their respective internal types.
+             * @param object Plain object
+             * @returns PresenceData
              */
-            public static fromObject(object: { [k: string]: any }): graphwiz.core.ChatMessage;
+            public static fromObject(object: { [k: string]: any }): graphwiz.core.PresenceData;
 
             /**
-             * Creates a plain object from a ChatMessage message. Also converts values to other types if specified.
-             * @param message ChatMessage
+             * Creates a plain object from a PresenceData message. Also converts values to other types if specified.
+             * @param message PresenceData
              * @param [options] Conversion options
              * @returns Plain object
              */
-            public static toObject(message: graphwiz.core.ChatMessage, options?: $protobuf.IConversionOptions): { [k: string]: any };
+            public static toObject(message: graphwiz.core.PresenceData, options?: $protobuf.IConversionOptions): { [k: string]: any };
 
             /**
-             * Converts this ChatMessage to JSON.
+             * Converts this PresenceData to JSON.
              * @returns JSON object
              */
             public toJSON(): { [k: string]: any };
 
             /**
-             * Gets the default type url for ChatMessage
+             * Gets the default type url for PresenceData
              * @param [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
              * @returns The default type url
              */
             public static getTypeUrl(typeUrlPrefix?: string): string;
         }
 
-        /** ChatMessageType enum. */
-        enum ChatMessageType {
-            NORMAL = 0,
-            WHISPER = 1,
-            SHOUT = 2
+        /** PresenceStatus enum. */
+        enum PresenceStatus {
+            OFFLINE = 0,
+            ONLINE = 1,
+            AWAY = 2,
+            BUSY = 3
         }
 
-        /** Properties of a PresenceEvent. */
-        interface IPresenceEvent {
-
-            /** PresenceEvent clientId */
-            clientId?: (string|null);
-
-            /** PresenceEvent eventType */
-            eventType?: (graphwiz.core.PresenceEventType|null);
+        /** Properties of a PresenceSubscription. */
+        interface IPresenceSubscription {
 
-            /** PresenceEvent data */
-            data?: (graphwiz.core.IPresenceData|null);
+            /** PresenceSubscription userIds */
+            userIds?: (string[]|null);
         }
 
-        /** Represents a PresenceEvent. */
-        class PresenceEvent implements IPresenceEvent {
+        /** Represents a PresenceSubscription. */
+        class PresenceSubscription implements IPresenceSubscription {
 
             /**
-             * Constructs a new PresenceEvent.
+             * Constructs a new PresenceSubscription.
              * @param [properties] Properties to set
              */
-            constructor(properties?: graphwiz.core.IPresenceEvent);
-
-            /** PresenceEvent clientId. */
-            public clientId: string;
+            constructor(properties?: graphwiz.core.IPresenceSubscription);
 
-            /** PresenceEvent eventType. */
-            public eventType: graphwiz.core.PresenceEventType;
-
-            /** PresenceEvent data. */
-            public data?: (graphwiz.core.IPresenceData|null);
+            /** PresenceSubscription userIds. */
+            public userIds: string[];
 
             /**
-             * Creates a new PresenceEvent instance using the specified properties.
+             * Creates a new PresenceSubscription instance using the specified properties.
              * @param [properties] Properties to set
-             * @returns PresenceEvent instance
+             * @returns PresenceSubscription instance
              */
-            public static create(properties?: graphwiz.core.IPresenceEvent): graphwiz.core.PresenceEvent;
+            public static create(properties?: graphwiz.core.IPresenceSubscription): graphwiz.core.PresenceSubscription;
 
             /**
-             * Encodes the specified PresenceEvent message. Does not implicitly {@link graphwiz.core.PresenceEvent.verify|verify} messages.
-             * @param message PresenceEvent message or plain object to encode
+             * Encodes the specified PresenceSubscription message. Does not implicitly {@link graphwiz.core.PresenceSubscription.verify|verify} messages.
+             * @param message PresenceSubscription message or plain object to encode
              * @param [writer] Writer to encode to
              * @returns Writer
              */
-            public static encode(message: graphwiz.core.IPresenceEvent, writer?: $protobuf.Writer): $protobuf.Writer;
+            public static encode(message: graphwiz.core.IPresenceSubscription, writer?: $protobuf.Writer): $protobuf.Writer;
 
             /**
-             * Encodes the specified PresenceEvent message, length delimited. Does not implicitly {@link graphwiz.core.PresenceEvent.verify|verify} messages.
-             * @param message PresenceEvent message or plain object to encode
+             * Encodes the specified PresenceSubscription message, length delimited. Does not implicitly {@link graphwiz.core.PresenceSubscription.verify|verify} messages.
+             * @param message PresenceSubscription message or plain object to encode
              * @param [writer] Writer to encode to
              * @returns Writer
              */
-            public static encodeDelimited(message: graphwiz.core.IPresenceEvent, writer?: $protobuf.Writer): $protobuf.Writer;
+            public static encodeDelimited(message: graphwiz.core.IPresenceSubscription, writer?: $protobuf.Writer): $protobuf.Writer;
 
             /**
-             * Decodes a PresenceEvent message from the specified reader or buffer.
+             * Decodes a PresenceSubscription message from the specified reader or buffer.
              * @param reader Reader or buffer to decode from
              * @param [length] Message length if known beforehand
-             * @returns PresenceEvent
+             * @returns PresenceSubscription
              * @throws {Error} If the payload is not a reader or valid buffer
              * @throws {$protobuf.util.ProtocolError} If required fields are missing
              */
-            public static decode(reader: ($protobuf.Reader|Uint8Array), length?: number): graphwiz.core.PresenceEvent;
+            public static decode(reader: ($protobuf.Reader|Uint8Array), length?: number): graphwiz.core.PresenceSubscription;
 
             /**
-             * Decodes a PresenceEvent message from the specified reader or buffer, length delimited.
+             * Decodes a PresenceSubscription message from the specified reader or buffer, length delimited.
              * @param reader Reader or buffer to decode from
-             * @returns PresenceEvent
+             * @returns PresenceSubscription
              * @throws {Error} If the payload is not a reader or valid buffer
              * @throws {$protobuf.util.ProtocolError} If required fields are missing
              */
-            public static decodeDelimited(reader: ($protobuf.Reader|Uint8Array)): graphwiz.core.PresenceEvent;
+            public static decodeDelimited(reader: ($protobuf.Reader|Uint8Array)): graphwiz.core.PresenceSubscription;
 
             /**
-             * Verifies a PresenceEvent message.
+             * Verifies a PresenceSubscription message.
              * @param message Plain object to verify
              * @returns `null` if valid, otherwise the reason why it is not
              */
             public static verify(message: { [k: string]: any }): (string|null);
 
             /**
-             * Creates a PresenceEvent message from a plain object. Also converts values to their respective internal types.
+             * Creates a PresenceSubscription message from a plain object. Also converts values to their respective internal types.
              * @param object Plain object
-             * @returns PresenceEvent
+             * @returns PresenceSubscription
              */
-            public static fromObject(object: { [k: string]: any }): graphwiz.core.PresenceEvent;
+            public static fromObject(object: { [k: string]: any }): graphwiz.core.PresenceSubscription;
 
             /**
-             * Creates a plain object from a PresenceEvent message. Also converts values to other types if specified.
-             * @param message PresenceEvent
+             * Creates a plain object from a PresenceSubscription message. Also converts values to other types if specified.
+             * @param message PresenceSubscription
              * @param [options] Conversion options
              * @returns Plain object
              */
-            public static toObject(message: graphwiz.core.PresenceEvent, options?: $protobuf.IConversionOptions): { [k: string]: any };
+            public static toObject(message: graphwiz.core.PresenceSubscription, options?: $protobuf.IConversionOptions): { [k: string]: any };
 
             /**
-             * Converts this PresenceEvent to JSON.
+             * Converts this PresenceSubscription to JSON.
              * @returns JSON object
              */
             public toJSON(): { [k: string]: any };
 
             /**
-             * Gets the default type url for PresenceEvent
+             * Gets the default type url for PresenceSubscription
              * @param [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
              * @returns The default type url
              */
             public static getTypeUrl(typeUrlPrefix?: string): string;
         }
 
-        /** PresenceEventType enum. */
-        enum PresenceEventType {
-            JOIN = 0,
-            LEAVE = 1,
-            UPDATE = 2
-        }
+        /** Properties of a RtcSignal. */
+        interface IRtcSignal {
 
-        /** Properties of a PresenceData. */
-        interface IPresenceData {
+            /** RtcSignal signalType */
+            signalType?: (graphwiz.core.RtcSignalType|null);
 
-            /** PresenceData displayName */
-            displayName?: (string|null);
+            /** RtcSignal fromClientId */
+            fromClientId?: (string|null);
 
-            /** PresenceData avatarUrl */
-            avatarUrl?: (string|null);
+            /** RtcSignal toClientId */
+            toClientId?: (string|null);
 
-            /** PresenceData position */
-            position?: (graphwiz.core.IVector3|null);
+            /** RtcSignal sdp */
+            sdp?: (string|null);
 
-            /** PresenceData rotation */
-            rotation?: (graphwiz.core.IQuaternion|null);
+            /** RtcSignal candidate */
+            candidate?: (string|null);
 
-            /** PresenceData avatarConfig */
-            avatarConfig?: (graphwiz.core.IAvatarConfig|null);
+            /** RtcSignal sdpMid */
+            sdpMid?: (string|null);
 
-            /** PresenceData hostClientId */
-            hostClientId?: (string|null);
+            /** RtcSignal sdpMlineIndex */
+            sdpMlineIndex?: (number|null);
         }
 
-        /** Represents a PresenceData. */
-        class PresenceData implements IPresenceData {
+        /** Represents a RtcSignal. */
+        class RtcSignal implements IRtcSignal {
 
             /**
-             * Constructs a new PresenceData.
+             * Constructs a new RtcSignal.
              * @param [properties] Properties to set
              */
-            constructor(properties?: graphwiz.core.IPresenceData);
+            constructor(properties?: graphwiz.core.IRtcSignal);
 
-            /** PresenceData displayName. */
-            public displayName: string;
+            /** RtcSignal signalType. */
+            public signalType: graphwiz.core.RtcSignalType;
 
-            /** PresenceData avatarUrl. */
-            public avatarUrl: string;
+            /** RtcSignal fromClientId. */
+            public fromClientId: string;
 
-            /** PresenceData position. */
-            public position?: (graphwiz.core.IVector3|null);
+            /** RtcSignal toClientId. */
+            public toClientId: string;
 
-            /** PresenceData rotation. */
-            public rotation?: (graphwiz.core.IQuaternion|null);
+            /** RtcSignal sdp. */
+            public sdp: string;
 
-            /** PresenceData avatarConfig. */
-            public avatarConfig?: (graphwiz.core.IAvatarConfig|null);
+            /** RtcSignal candidate. */
+            public candidate: string;
 
-            /** PresenceData hostClientId. */
-            public hostClientId: string;
+            /** RtcSignal sdpMid. */
+            public sdpMid: string;
+
+            /** RtcSignal sdpMlineIndex. */
+            public sdpMlineIndex: number;
 
             /**
-             * Creates a new PresenceData instance using the specified properties.
+             * Creates a new RtcSignal instance using the specified properties.
              * @param [properties] Properties to set
-             * @returns PresenceData instance
+             * @returns RtcSignal instance
              */
-            public static create(properties?: graphwiz.core.IPresenceData): graphwiz.core.PresenceData;
+            public static create(properties?: graphwiz.core.IRtcSignal): graphwiz.core.RtcSignal;
 
             /**
-             * Encodes the specified PresenceData message. Does not implicitly {@link graphwiz.core.PresenceData.verify|verify} messages.
-             * @param message PresenceData message or plain object to encode
+             * Encodes the specified RtcSignal message. Does not implicitly {@link graphwiz.core.RtcSignal.verify|verify} messages.
+             * @param message RtcSignal message or plain object to encode
              * @param [writer] Writer to encode to
              * @returns Writer
              */
-            public static encode(message: graphwiz.core.IPresenceData, writer?: $protobuf.Writer): $protobuf.Writer;
+            public static encode(message: graphwiz.core.IRtcSignal, writer?: $protobuf.Writer): $protobuf.Writer;
 
             /**
-             * Encodes the specified PresenceData message, length delimited. Does not implicitly {@link graphwiz.core.PresenceData.verify|verify} messages.
-             * @param message PresenceData message or plain object to encode
+             * Encodes the specified RtcSignal message, length delimited. Does not implicitly {@link graphwiz.core.RtcSignal.verify|verify} messages.
+             * @param message RtcSignal message or plain object to encode
              * @param [writer] Writer to encode to
              * @returns Writer
              */
-            public static encodeDelimited(message: graphwiz.core.IPresenceData, writer?: $protobuf.Writer): $protobuf.Writer;
+            public static encodeDelimited(message: graphwiz.core.IRtcSignal, writer?: $protobuf.Writer): $protobuf.Writer;
 
             /**
-             * Decodes a PresenceData message from the specified reader or buffer.
+             * Decodes a RtcSignal message from the specified reader or buffer.
              * @param reader Reader or buffer to decode from
              * @param [length] Message length if known beforehand
-             * @returns PresenceData
+             * @returns RtcSignal
              * @throws {Error} If the payload is not a reader or valid buffer
              * @throws {$protobuf.util.ProtocolError} If required fields are missing
              */
-            public static decode(reader: ($protobuf.Reader|Uint8Array), length?: number): graphwiz.core.PresenceData;
+            public static decode(reader: ($protobuf.Reader|Uint8Array), length?: number): graphwiz.core.RtcSignal;
 
             /**
-             * Decodes a PresenceData message from the specified reader or buffer, length delimited.
+             * Decodes a RtcSignal message from the specified reader or buffer, length delimited.
              * @param reader Reader or buffer to decode from
-             * @returns PresenceData
+             * @returns RtcSignal
              * @throws {Error} If the payload is not a reader or valid buffer
              * @throws {$protobuf.util.ProtocolError} If required fields are missing
              */
-            public static decodeDelimited(reader: ($protobuf.Reader|Uint8Array)): graphwiz.core.PresenceData;
+            public static decodeDelimited(reader: ($protobuf.Reader|Uint8Array)): graphwiz.core.RtcSignal;
 
             /**
-             * Verifies a PresenceData message.
+             * Verifies a RtcSignal message.
              * @param message Plain object to verify
              * @returns `null` if valid, otherwise the reason why it is not
              */
             public static verify(message: { [k: string]: any }): (string|null);
 
             /**
-             * Creates a PresenceData message from a plain object. Also converts values to their respective internal types.
+             * Creates a RtcSignal message from a plain object. Also converts values to their respective internal types.
              * @param object Plain object
-             * @returns PresenceData
+             * @returns RtcSignal
              */
-            public static fromObject(object: { [k: string]: any }): graphwiz.core.PresenceData;
+            public static fromObject(object: { [k: string]: any }): graphwiz.core.RtcSignal;
 
             /**
-             * Creates a plain object from a PresenceData message. Also converts values to other types if specified.
-             * @param message PresenceData
+             * Creates a plain object from a RtcSignal message. Also converts values to other types if specified.
+             * @param message RtcSignal
              * @param [options] Conversion options
              * @returns Plain object
              */
-            public static toObject(message: graphwiz.core.PresenceData, options?: $protobuf.IConversionOptions): { [k: string]: any };
+            public static toObject(message: graphwiz.core.RtcSignal, options?: $protobuf.IConversionOptions): { [k: string]: any };
 
             /**
-             * Converts this PresenceData to JSON.
+             * Converts this RtcSignal to JSON.
              * @returns JSON object
              */
             public toJSON(): { [k: string]: any };
 
             /**
-             * Gets the default type url for PresenceData
+             * Gets the default type url for RtcSignal
              * @param [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
              * @returns The default type url
              */
             public static getTypeUrl(typeUrlPrefix?: string): string;
         }
 
+        /** RtcSignalType enum. */
+        enum RtcSignalType {
+            OFFER = 0,
+            ANSWER = 1,
+            ICE_CANDIDATE = 2,
+            HANGUP = 3
+        }
+
         /** Properties of a PlayerSnapshot. */
         interface IPlayerSnapshot {
 
@@ -1706,6 +2569,12 @@ export namespace graphwiz {
 
             /** ServerHello initialState */
             initialState?: (graphwiz.core.IWorldState|null);
+
+            /** ServerHello resumeToken */
+            resumeToken?: (string|null);
+
+            /** ServerHello resumed */
+            resumed?: (boolean|null);
         }
 
         /** Represents a ServerHello. */
@@ -1729,6 +2598,12 @@ export namespace graphwiz {
             /** ServerHello initialState. */
             public initialState?: (graphwiz.core.IWorldState|null);
 
+            /** ServerHello resumeToken. */
+            public resumeToken: string;
+
+            /** ServerHello resumed. */
+            public resumed: boolean;
+
             /**
              * Creates a new ServerHello instance using the specified properties.
              * @param [properties] Properties to set
@@ -1807,6 +2682,115 @@ export namespace graphwiz {
             public static getTypeUrl(typeUrlPrefix?: string): string;
         }
 
+        /** Properties of a Redirect. */
+        interface IRedirect {
+
+            /** Redirect url */
+            url?: (string|null);
+
+            /** Redirect resumeToken */
+            resumeToken?: (string|null);
+
+            /** Redirect reconnectWithinMs */
+            reconnectWithinMs?: (number|null);
+        }
+
+        /** Represents a Redirect. */
+        class Redirect implements IRedirect {
+
+            /**
+             * Constructs a new Redirect.
+             * @param [properties] Properties to set
+             */
+            constructor(properties?: graphwiz.core.IRedirect);
+
+            /** Redirect url. */
+            public url: string;
+
+            /** Redirect resumeToken. */
+            public resumeToken: string;
+
+            /** Redirect reconnectWithinMs. */
+            public reconnectWithinMs: number;
+
+            /**
+             * Creates a new Redirect instance using the specified properties.
+             * @param [properties] Properties to set
+             * @returns Redirect instance
+             */
+            public static create(properties?: graphwiz.core.IRedirect): graphwiz.core.Redirect;
+
+            /**
+             * Encodes the specified Redirect message. Does not implicitly {@link graphwiz.core.Redirect.verify|verify} messages.
+             * @param message Redirect message or plain object to encode
+             * @param [writer] Writer to encode to
+             * @returns Writer
+             */
+            public static encode(message: graphwiz.core.IRedirect, writer?: $protobuf.Writer): $protobuf.Writer;
+
+            /**
+             * Encodes the specified Redirect message, length delimited. Does not implicitly {@link graphwiz.core.Redirect.verify|verify} messages.
+             * @param message Redirect message or plain object to encode
+             * @param [writer] Writer to encode to
+             * @returns Writer
+             */
+            public static encodeDelimited(message: graphwiz.core.IRedirect, writer?: $protobuf.Writer): $protobuf.Writer;
+
+            /**
+             * Decodes a Redirect message from the specified reader or buffer.
+             * @param reader Reader or buffer to decode from
+             * @param [length] Message length if known beforehand
+             * @returns Redirect
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            public static decode(reader: ($protobuf.Reader|Uint8Array), length?: number): graphwiz.core.Redirect;
+
+            /**
+             * Decodes a Redirect message from the specified reader or buffer, length delimited.
+             * @param reader Reader or buffer to decode from
+             * @returns Redirect
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            public static decodeDelimited(reader: ($protobuf.Reader|Uint8Array)): graphwiz.core.Redirect;
+
+            /**
+             * Verifies a Redirect message.
+             * @param message Plain object to verify
+             * @returns `null` if valid, otherwise the reason why it is not
+             */
+            public static verify(message: { [k: string]: any }): (string|null);
+
+            /**
+             * Creates a Redirect message from a plain object. Also converts values to their respective internal types.
+             * @param object Plain object
+             * @returns Redirect
+             */
+            public static fromObject(object: { [k: string]: any }): graphwiz.core.Redirect;
+
+            /**
+             * Creates a plain object from a Redirect message. Also converts values to other types if specified.
+             * @param message Redirect
+             * @param [options] Conversion options
+             * @returns Plain object
+             */
+            public static toObject(message: graphwiz.core.Redirect, options?: $protobuf.IConversionOptions): { [k: string]: any };
+
+            /**
+             * Converts this Redirect to JSON.
+             * @returns JSON object
+             */
+            public toJSON(): { [k: string]: any };
+
+            /**
+             * Gets the default type url for Redirect
+             * @param [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
+             * @returns The default type url
+             */
+            public static getTypeUrl(typeUrlPrefix?: string): string;
+        }
+
         /** Properties of a WorldState. */
         interface IWorldState {
 
@@ -2634,20 +3618,6 @@ export namespace graphwiz {
              * @returns Promise
              */
             public leaveRoom(request: graphwiz.networking.ILeaveRequest): Promise<graphwiz.networking.LeaveResponse>;
-
-            /**
-             * Calls WatchPresence.
-             * @param request PresenceRequest message or plain object
-             * @param callback Node-style callback called with the error, if any, and PresenceEvent
-             */
-            public watchPresence(request: graphwiz.networking.IPresenceRequest, callback: graphwiz.networking.RoomService.WatchPresenceCallback): void;
-
-            /**
-             * Calls WatchPresence.
-             * @param request PresenceRequest message or plain object
-             * @returns Promise
-             */
-            public watchPresence(request: graphwiz.networking.IPresenceRequest): Promise<graphwiz.core.PresenceEvent>;
         }
 
         namespace RoomService {
@@ -2672,9 +3642,47 @@ export namespace graphwiz {
              * @param [response] LeaveResponse
              */
             type LeaveRoomCallback = (error: (Error|null), response?: graphwiz.networking.LeaveResponse) => void;
+        }
+
+        /** Represents a PresenceService */
+        class PresenceService extends $protobuf.rpc.Service {
+
+            /**
+             * Constructs a new PresenceService service.
+             * @param rpcImpl RPC implementation
+             * @param [requestDelimited=false] Whether requests are length-delimited
+             * @param [responseDelimited=false] Whether responses are length-delimited
+             */
+            constructor(rpcImpl: $protobuf.RPCImpl, requestDelimited?: boolean, responseDelimited?: boolean);
+
+            /**
+             * Creates new PresenceService service using the specified rpc implementation.
+             * @param rpcImpl RPC implementation
+             * @param [requestDelimited=false] Whether requests are length-delimited
+             * @param [responseDelimited=false] Whether responses are length-delimited
+             * @returns RPC service. Useful where requests and/or responses are streamed.
+             */
+            public static create(rpcImpl: $protobuf.RPCImpl, requestDelimited?: boolean, responseDelimited?: boolean): PresenceService;
+
+            /**
+             * Calls WatchPresence.
+             * @param request PresenceRequest message or plain object
+             * @param callback Node-style callback called with the error, if any, and PresenceEvent
+             */
+            public watchPresence(request: graphwiz.networking.IPresenceRequest, callback: graphwiz.networking.PresenceService.WatchPresenceCallback): void;
+
+            /**
+             * Calls WatchPresence.
+             * @param request PresenceRequest message or plain object
+             * @returns Promise
+             */
+            public watchPresence(request: graphwiz.networking.IPresenceRequest): Promise<graphwiz.core.PresenceEvent>;
+        }
+
+        namespace PresenceService {
 
             /**
-             * Callback as used by {@link graphwiz.networking.RoomService#watchPresence}.
+             * Callback as used by {@link graphwiz.networking.PresenceService#watchPresence}.
              * @param error Error, if any
              * @param [response] PresenceEvent
              */
@@ -34,15 +34,23 @@ export const graphwiz = $root.graphwiz = (() => {
              * @property {string|null} [messageId] Message messageId
              * @property {number|Long|null} [timestamp] Message timestamp
              * @property {graphwiz.core.MessageType|null} [type] Message type
+             * @property {number|null} [sequence] Message sequence
              * @property {graphwiz.core.IClientHello|null} [clientHello] Message clientHello
              * @property {graphwiz.core.IServerHello|null} [serverHello] Message serverHello
+             * @property {graphwiz.core.IRedirect|null} [redirect] Message redirect
              * @property {graphwiz.core.IPositionUpdate|null} [positionUpdate] Message positionUpdate
              * @property {graphwiz.core.IVoiceData|null} [voiceData] Message voiceData
+             * @property {graphwiz.core.IPositionBatch|null} [positionBatch] Message positionBatch
+             * @property {graphwiz.core.ISpatialAudio|null} [spatialAudio] Message spatialAudio
              * @property {graphwiz.core.IEntitySpawn|null} [entitySpawn] Message entitySpawn
              * @property {graphwiz.core.IEntityUpdate|null} [entityUpdate] Message entityUpdate
              * @property {graphwiz.core.IEntityDespawn|null} [entityDespawn] Message entityDespawn
+             * @property {graphwiz.core.IEntityHistory|null} [entityHistory] Message entityHistory
              * @property {graphwiz.core.IChatMessage|null} [chatMessage] Message chatMessage
              * @property {graphwiz.core.IPresenceEvent|null} [presenceEvent] Message presenceEvent
+             * @property {graphwiz.core.IPresenceSubscription|null} [presenceSubscription] Message presenceSubscription
+             * @property {graphwiz.core.IErrorFrame|null} [errorFrame] Message errorFrame
+             * @property {graphwiz.core.IRtcSignal|null} [rtcSignal] Message rtcSignal
              */
 
             /**
@@ -84,6 +92,14 @@ export const graphwiz = $root.graphwiz = (() => {
              */
             Message.prototype.type = 0;
 
+            /**
+             * Message sequence.
+             * @member {number} sequence
+             * @memberof graphwiz.core.Message
+             * @instance
+             */
+            Message.prototype.sequence = 0;
+
             /**
              * Message clientHello.
              * @member {graphwiz.core.IClientHello|null|undefined} clientHello
@@ -100,6 +116,14 @@ export const graphwiz = $root.graphwiz = (() => {
              */
             Message.prototype.serverHello = null;
 
+            /**
+             * Message redirect.
+             * @member {graphwiz.core.IRedirect|null|undefined} redirect
+             * @memberof graphwiz.core.Message
+             * @instance
+             */
+            Message.prototype.redirect = null;
+
             /**
              * Message positionUpdate.
              * @member {graphwiz.core.IPositionUpdate|null|undefined} positionUpdate
@@ -116,6 +140,22 @@ export const graphwiz = $root.graphwiz = (() => {
              */
             Message.prototype.voiceData = null;
 
+            /**
+             * Message positionBatch.
+             * @member {graphwiz.core.IPositionBatch|null|undefined} positionBatch
+             * @memberof graphwiz.core.Message
+             * @instance
+             */
+            Message.prototype.positionBatch = null;
+
+            /**
+             * Message spatialAudio.
+             * @member {graphwiz.core.ISpatialAudio|null|undefined} spatialAudio
+             * @memberof graphwiz.core.Message
+             * @instance
+             */
+            Message.prototype.spatialAudio = null;
+
             /**
              * Message entitySpawn.
              * @member {graphwiz.core.IEntitySpawn|null|undefined} entitySpawn
@@ -140,6 +180,14 @@ export const graphwiz = $root.graphwiz = (() => {
              */
             Message.prototype.entityDespawn = null;
 
+            /**
+             * Message entityHistory.
+             * @member {graphwiz.core.IEntityHistory|null|undefined} entityHistory
+             * @memberof graphwiz.core.Message
+             * @instance
+             */
+            Message.prototype.entityHistory = null;
+
             /**
              * Message chatMessage.
              * @member {graphwiz.core.IChatMessage|null|undefined} chatMessage
@@ -156,17 +204,41 @@ export const graphwiz = $root.graphwiz = (() => {
              */
             Message.prototype.presenceEvent = null;
 
+            /**
+             * Message presenceSubscription.
+             * @member {graphwiz.core.IPresenceSubscription|null|undefined} presenceSubscription
+             * @memberof graphwiz.core.Message
+             * @instance
+             */
+            Message.prototype.presenceSubscription = null;
+
+            /**
+             * Message errorFrame.
+             * @member {graphwiz.core.IErrorFrame|null|undefined} errorFrame
+             * @memberof graphwiz.core.Message
+             * @instance
+             */
+            Message.prototype.errorFrame = null;
+
+            /**
+             * Message rtcSignal.
+             * @member {graphwiz.core.IRtcSignal|null|undefined} rtcSignal
+             * @memberof graphwiz.core.Message
+             * @instance
+             */
+            Message.prototype.rtcSignal = null;
+
             // OneOf field names bound to virtual getters and setters
             let $oneOfFields;
 
             /**
              * Message payload.
-             * @member {"clientHello"|"serverHello"|"positionUpdate"|"voiceData"|"entitySpawn"|"entityUpdate"|"entityDespawn"|"chatMessage"|"presenceEvent"|undefined} payload
+             * @member {"clientHello"|"serverHello"|"redirect"|"positionUpdate"|"voiceData"|"positionBatch"|"spatialAudio"|"entitySpawn"|"entityUpdate"|"entityDespawn"|"entityHistory"|"chatMessage"|"presenceEvent"|"presenceSubscription"|"errorFrame"|"rtcSignal"|undefined} payload
              * @memberof graphwiz.core.Message
              * @instance
              */
             Object.defineProperty(Message.prototype, "payload", {
-                get: $util.oneOfGetter($oneOfFields = ["clientHello", "serverHello", "positionUpdate", "voiceData", "entitySpawn", "entityUpdate", "entityDespawn", "chatMessage", "presenceEvent"]),
+                get: $util.oneOfGetter($oneOfFields = ["clientHello", "serverHello", "redirect", "positionUpdate", "voiceData", "positionBatch", "spatialAudio", "entitySpawn", "entityUpdate", "entityDespawn", "entityHistory", "chatMessage", "presenceEvent", "presenceSubscription", "errorFrame", "rtcSignal"]),
                 set: $util.oneOfSetter($oneOfFields)
             });
 
@@ -200,24 +272,40 @@ export const graphwiz = $root.graphwiz = (() => {
                     writer.uint32(/* id 2, wireType 0 =*/16).int64(message.timestamp);
                 if (message.type != null && Object.hasOwnProperty.call(message, "type"))
                     writer.uint32(/* id 3, wireType 0 =*/24).int32(message.type);
+                if (message.sequence != null && Object.hasOwnProperty.call(message, "sequence"))
+                    writer.uint32(/* id 4, wireType 0 =*/32).uint32(message.sequence);
                 if (message.clientHello != null && Object.hasOwnProperty.call(message, "clientHello"))
                     $root.graphwiz.core.ClientHello.encode(message.clientHello, writer.uint32(/* id 10, wireType 2 =*/82).fork()).ldelim();
                 if (message.serverHello != null && Object.hasOwnProperty.call(message, "serverHello"))
                     $root.graphwiz.core.ServerHello.encode(message.serverHello, writer.uint32(/* id 11, wireType 2 =*/90).fork()).ldelim();
+                if (message.redirect != null && Object.hasOwnProperty.call(message, "redirect"))
+                    $root.graphwiz.core.Redirect.encode(message.redirect, writer.uint32(/* id 12, wireType 2 =*/98).fork()).ldelim();
                 if (message.positionUpdate != null && Object.hasOwnProperty.call(message, "positionUpdate"))
                     $root.graphwiz.core.PositionUpdate.encode(message.positionUpdate, writer.uint32(/* id 20, wireType 2 =*/162).fork()).ldelim();
                 if (message.voiceData != null && Object.hasOwnProperty.call(message, "voiceData"))
                     $root.graphwiz.core.VoiceData.encode(message.voiceData, writer.uint32(/* id 21, wireType 2 =*/170).fork()).ldelim();
+                if (message.positionBatch != null && Object.hasOwnProperty.call(message, "positionBatch"))
+                    $root.graphwiz.core.PositionBatch.encode(message.positionBatch, writer.uint32(/* id 22, wireType 2 =*/178).fork()).ldelim();
+                if (message.spatialAudio != null && Object.hasOwnProperty.call(message, "spatialAudio"))
+                    $root.graphwiz.core.SpatialAudio.encode(message.spatialAudio, writer.uint32(/* id 23, wireType 2 =*/186).fork()).ldelim();
                 if (message.entitySpawn != null && Object.hasOwnProperty.call(message, "entitySpawn"))
                     $root.graphwiz.core.EntitySpawn.encode(message.entitySpawn, writer.uint32(/* id 30, wireType 2 =*/242).fork()).ldelim();
                 if (message.entityUpdate != null && Object.hasOwnProperty.call(message, "entityUpdate"))
                     $root.graphwiz.core.EntityUpdate.encode(message.entityUpdate, writer.uint32(/* id 31, wireType 2 =*/250).fork()).ldelim();
                 if (message.entityDespawn != null && Object.hasOwnProperty.call(message, "entityDespawn"))
                     $root.graphwiz.core.EntityDespawn.encode(message.entityDespawn, writer.uint32(/* id 32, wireType 2 =*/258).fork()).ldelim();
+                if (message.entityHistory != null && Object.hasOwnProperty.call(message, "entityHistory"))
+                    $root.graphwiz.core.EntityHistory.encode(message.entityHistory, writer.uint32(/* id 33, wireType 2 =*/266).fork()).ldelim();
                 if (message.chatMessage != null && Object.hasOwnProperty.call(message, "chatMessage"))
                     $root.graphwiz.core.ChatMessage.encode(message.chatMessage, writer.uint32(/* id 40, wireType 2 =*/322).fork()).ldelim();
                 if (message.presenceEvent != null && Object.hasOwnProperty.call(message, "presenceEvent"))
                     $root.graphwiz.core.PresenceEvent.encode(message.presenceEvent, writer.uint32(/* id 50, wireType 2 =*/402).fork()).ldelim();
+                if (message.presenceSubscription != null && Object.hasOwnProperty.call(message, "presenceSubscription"))
+                    $root.graphwiz.core.PresenceSubscription.encode(message.presenceSubscription, writer.uint32(/* id 51, wireType 2 =*/410).fork()).ldelim();
+                if (message.errorFrame != null && Object.hasOwnProperty.call(message, "errorFrame"))
+                    $root.graphwiz.core.ErrorFrame.encode(message.errorFrame, writer.uint32(/* id 60, wireType 2 =*/482).fork()).ldelim();
+                if (message.rtcSignal != null && Object.hasOwnProperty.call(message, "rtcSignal"))
+                    $root.graphwiz.core.RtcSignal.encode(message.rtcSignal, writer.uint32(/* id 70, wireType 2 =*/562).fork()).ldelim();
                 return writer;
             };
 
@@ -266,6 +354,10 @@ export const graphwiz = $root.graphwiz = (() => {
                             message.type = reader.int32();
                             break;
                         }
+                    case 4: {
+                            message.sequence = reader.uint32();
+                            break;
+                        }
                     case 10: {
                             message.clientHello = $root.graphwiz.core.ClientHello.decode(reader, reader.uint32());
                             break;
@@ -274,6 +366,10 @@ export const graphwiz = $root.graphwiz = (() => {
                             message.serverHello = $root.graphwiz.core.ServerHello.decode(reader, reader.uint32());
                             break;
                         }
+                    case 12: {
+                            message.redirect = $root.graphwiz.core.Redirect.decode(reader, reader.uint32());
+                            break;
+                        }
                     case 20: {
                             message.positionUpdate = $root.graphwiz.core.PositionUpdate.decode(reader, reader.uint32());
                             break;
@@ -282,6 +378,14 @@ export const graphwiz = $root.graphwiz = (() => {
                             message.voiceData = $root.graphwiz.core.VoiceData.decode(reader, reader.uint32());
                             break;
                         }
+                    case 22: {
+                            message.positionBatch = $root.graphwiz.core.PositionBatch.decode(reader, reader.uint32());
+                            break;
+                        }
+                    case 23: {
+                            message.spatialAudio = $root.graphwiz.core.SpatialAudio.decode(reader, reader.uint32());
+                            break;
+                        }
                     case 30: {
                             message.entitySpawn = $root.graphwiz.core.EntitySpawn.decode(reader, reader.uint32());
                             break;
@@ -294,6 +398,10 @@ export const graphwiz = $root.graphwiz = (() => {
                             message.entityDespawn = $root.graphwiz.core.EntityDespawn.decode(reader, reader.uint32());
                             break;
                         }
+                    case 33: {
+                            message.entityHistory = $root.graphwiz.core.EntityHistory.decode(reader, reader.uint32());
+                            break;
+                        }
                     case 40: {
                             message.chatMessage = $root.graphwiz.core.ChatMessage.decode(reader, reader.uint32());
                             break;
@@ -302,6 +410,18 @@ export const graphwiz = $root.graphwiz = (() => {
                             message.presenceEvent = $root.graphwiz.core.PresenceEvent.decode(reader, reader.uint32());
                             break;
                         }
+                    case 51: {
+                            message.presenceSubscription = $root.graphwiz.core.PresenceSubscription.decode(reader, reader.uint32());
+                            break;
+                        }
+                    case 60: {
+                            message.errorFrame = $root.graphwiz.core.ErrorFrame.decode(reader, reader.uint32());
+                            break;
+                        }
+                    case 70: {
+                            message.rtcSignal = $root.graphwiz.core.RtcSignal.decode(reader, reader.uint32());
+                            break;
+                        }
                     default:
                         reader.skipType(tag & 7);
                         break;
@@ -351,17 +471,29 @@ export const graphwiz = $root.graphwiz = (() => {
                     case 0:
                     case 1:
                     case 2:
+                    case 3:
+                    case 4:
                     case 10:
                     case 11:
+                    case 12:
+                    case 13:
                     case 20:
                     case 21:
                     case 22:
+                    case 23:
                     case 30:
                     case 40:
                     case 41:
                     case 42:
+                    case 43:
+                    case 44:
+                    case 45:
+                    case 50:
                         break;
                     }
+                if (message.sequence != null && message.hasOwnProperty("sequence"))
+                    if (!$util.isInteger(message.sequence))
+                        return "sequence: integer expected";
                 if (message.clientHello != null && message.hasOwnProperty("clientHello")) {
                     properties.payload = 1;
                     {
@@ -380,6 +512,16 @@ export const graphwiz = $root.graphwiz = (() => {
                             return "serverHello." + error;
                     }
                 }
+                if (message.redirect != null && message.hasOwnProperty("redirect")) {
+                    if (properties.payload === 1)
+                        return "payload: multiple values";
+                    properties.payload = 1;
+                    {
+                        let error = $root.graphwiz.core.Redirect.verify(message.redirect);
+                        if (error)
+                            return "redirect." + error;
+                    }
+                }
                 if (message.positionUpdate != null && message.hasOwnProperty("positionUpdate")) {
                     if (properties.payload === 1)
                         return "payload: multiple values";
@@ -400,6 +542,26 @@ export const graphwiz = $root.graphwiz = (() => {
                             return "voiceData." + error;
                     }
                 }
+                if (message.positionBatch != null && message.hasOwnProperty("positionBatch")) {
+                    if (properties.payload === 1)
+                        return "payload: multiple values";
+                    properties.payload = 1;
+                    {
+                        let error = $root.graphwiz.core.PositionBatch.verify(message.positionBatch);
+                        if (error)
+                            return "positionBatch." + error;
+                    }
+                }
+                if (message.spatialAudio != null && message.hasOwnProperty("spatialAudio")) {
+                    if (properties.payload === 1)
+                        return "payload: multiple values";
+                    properties.payload = 1;
+                    {
+                        let error = $root.graphwiz.core.SpatialAudio.verify(message.spatialAudio);
+                        if (error)
+                            return "spatialAudio." + error;
+                    }
+                }
                 if (message.entitySpawn != null && message.hasOwnProperty("entitySpawn")) {
                     if (properties.payload === 1)
                         return "payload: multiple values";
@@ -430,6 +592,16 @@ export const graphwiz = $root.graphwiz = (() => {
                             return "entityDespawn." + error;
                     }
                 }
+                if (message.entityHistory != null && message.hasOwnProperty("entityHistory")) {
+                    if (properties.payload === 1)
+                        return "payload: multiple values";
+                    properties.payload = 1;
+                    {
+                        let error = $root.graphwiz.core.EntityHistory.verify(message.entityHistory);
+                        if (error)
+                            return "entityHistory." + error;
+                    }
+                }
                 if (message.chatMessage != null && message.hasOwnProperty("chatMessage")) {
                     if (properties.payload === 1)
                         return "payload: multiple values";
@@ -450,6 +622,36 @@ export const graphwiz = $root.graphwiz = (() => {
                             return "presenceEvent." + error;
                     }
                 }
+                if (message.presenceSubscription != null && message.hasOwnProperty("presenceSubscription")) {
+                    if (properties.payload === 1)
+                        return "payload: multiple values";
+                    properties.payload = 1;
+                    {
+                        let error = $root.graphwiz.core.PresenceSubscription.verify(message.presenceSubscription);
+                        if (error)
+                            return "presenceSubscription." + error;
+                    }
+                }
+                if (message.errorFrame != null && message.hasOwnProperty("errorFrame")) {
+                    if (properties.payload === 1)
+                        return "payload: multiple values";
+                    properties.payload = 1;
+                    {
+                        let error = $root.graphwiz.core.ErrorFrame.verify(message.errorFrame);
+                        if (error)
+                            return "errorFrame." + error;
+                    }
+                }
+                if (message.rtcSignal != null && message.hasOwnProperty("rtcSignal")) {
+                    if (properties.payload === 1)
+                        return "payload: multiple values";
+                    properties.payload = 1;
+                    {
+                        let error = $root.graphwiz.core.RtcSignal.verify(message.rtcSignal);
+                        if (error)
+                            return "rtcSignal." + error;
+                    }
+                }
                 return null;
             };
 
@@ -495,6 +697,14 @@ export const graphwiz = $root.graphwiz = (() => {
                 case 2:
                     message.type = 2;
                     break;
+                case "ERROR":
+                case 3:
+                    message.type = 3;
+                    break;
+                case "REDIRECT":
+                case 4:
+                    message.type = 4;
+                    break;
                 case "POSITION_UPDATE":
                 case 10:
                     message.type = 10;
@@ -503,6 +713,14 @@ export const graphwiz = $root.graphwiz = (() => {
                 case 11:
                     message.type = 11;
                     break;
+                case "POSITION_BATCH":
+                case 12:
+                    message.type = 12;
+                    break;
+                case "SPATIAL_AUDIO":
+                case 13:
+                    message.type = 13;
+                    break;
                 case "ENTITY_SPAWN":
                 case 20:
                     message.type = 20;
@@ -515,6 +733,10 @@ export const graphwiz = $root.graphwiz = (() => {
                 case 22:
                     message.type = 22;
                     break;
+                case "ENTITY_HISTORY":
+                case 23:
+                    message.type = 23;
+                    break;
                 case "CHAT_MESSAGE":
                 case 30:
                     message.type = 30;
@@ -531,7 +753,25 @@ export const graphwiz = $root.graphwiz = (() => {
                 case 42:
                     message.type = 42;
                     break;
+                case "PRESENCE_HOST_CHANGED":
+                case 43:
+                    message.type = 43;
+                    break;
+                case "PRESENCE_STATUS":
+                case 44:
+                    message.type = 44;
+                    break;
+                case "PRESENCE_SUBSCRIBE":
+                case 45:
+                    message.type = 45;
+                    break;
+                case "RTC_SIGNAL":
+                case 50:
+                    message.type = 50;
+                    break;
                 }
+                if (object.sequence != null)
+                    message.sequence = object.sequence >>> 0;
                 if (object.clientHello != null) {
                     if (typeof object.clientHello !== "object")
                         throw TypeError(".graphwiz.core.Message.clientHello: object expected");
@@ -542,6 +782,11 @@ export const graphwiz = $root.graphwiz = (() => {
                         throw TypeError(".graphwiz.core.Message.serverHello: object expected");
                     message.serverHello = $root.graphwiz.core.ServerHello.fromObject(object.serverHello);
                 }
+                if (object.redirect != null) {
+                    if (typeof object.redirect !== "object")
+                        throw TypeError(".graphwiz.core.Message.redirect: object expected");
+                    message.redirect = $root.graphwiz.core.Redirect.fromObject(object.redirect);
+                }
                 if (object.positionUpdate != null) {
                     if (typeof object.positionUpdate !== "object")
                         throw TypeError(".graphwiz.core.Message.positionUpdate: object expected");
@@ -552,6 +797,16 @@ export const graphwiz = $root.graphwiz = (() => {
                         throw TypeError(".graphwiz.core.Message.voiceData: object expected");
                     message.voiceData = $root.graphwiz.core.VoiceData.fromObject(object.voiceData);
                 }
+                if (object.positionBatch != null) {
+                    if (typeof object.positionBatch !== "object")
+                        throw TypeError(".graphwiz.core.Message.positionBatch: object expected");
+                    message.positionBatch = $root.graphwiz.core.PositionBatch.fromObject(object.positionBatch);
+                }
+                if (object.spatialAudio != null) {
+                    if (typeof object.spatialAudio !== "object")
+                        throw TypeError(".graphwiz.core.Message.spatialAudio: object expected");
+                    message.spatialAudio = $root.graphwiz.core.SpatialAudio.fromObject(object.spatialAudio);
+                }
                 if (object.entitySpawn != null) {
                     if (typeof object.entitySpawn !== "object")
                         throw TypeError(".graphwiz.core.Message.entitySpawn: object expected");
@@ -567,6 +822,11 @@ export const graphwiz = $root.graphwiz = (() => {
                         throw TypeError(".graphwiz.core.Message.entityDespawn: object expected");
                     message.entityDespawn = $root.graphwiz.core.EntityDespawn.fromObject(object.entityDespawn);
                 }
+                if (object.entityHistory != null) {
+                    if (typeof object.entityHistory !== "object")
+                        throw TypeError(".graphwiz.core.Message.entityHistory: object expected");
+                    message.entityHistory = $root.graphwiz.core.EntityHistory.fromObject(object.entityHistory);
+                }
                 if (object.chatMessage != null) {
                     if (typeof object.chatMessage !== "object")
                         throw TypeError(".graphwiz.core.Message.chatMessage: object expected");
@@ -577,6 +837,21 @@ export const graphwiz = $root.graphwiz = (() => {
                         throw TypeError(".graphwiz.core.Message.presenceEvent: object expected");
                     message.presenceEvent = $root.graphwiz.core.PresenceEvent.fromObject(object.presenceEvent);
                 }
+                if (object.presenceSubscription != null) {
+                    if (typeof object.presenceSubscription !== "object")
+                        throw TypeError(".graphwiz.core.Message.presenceSubscription: object expected");
+                    message.presenceSubscription = $root.graphwiz.core.PresenceSubscription.fromObject(object.presenceSubscription);
+                }
+                if (object.errorFrame != null) {
+                    if (typeof object.errorFrame !== "object")
+                        throw TypeError(".graphwiz.core.Message.errorFrame: object expected");
+                    message.errorFrame = $root.graphwiz.core.ErrorFrame.fromObject(object.errorFrame);
+                }
+                if (object.rtcSignal != null) {
+                    if (typeof object.rtcSignal !== "object")
+                        throw TypeError(".graphwiz.core.Message.rtcSignal: object expected");
+                    message.rtcSignal = $root.graphwiz.core.RtcSignal.fromObject(object.rtcSignal);
+                }
                 return message;
             };
 
@@ -601,6 +876,7 @@ export const graphwiz = $root.graphwiz = (() => {
                     } else
                         object.timestamp = options.longs === String ? "0" : 0;
                     object.type = options.enums === String ? "UNKNOWN" : 0;
+                    object.sequence = 0;
                 }
                 if (message.messageId != null && message.hasOwnProperty("messageId"))
                     object.messageId = message.messageId;
@@ -611,6 +887,8 @@ export const graphwiz = $root.graphwiz = (() => {
                         object.timestamp = options.longs === String ? $util.Long.prototype.toString.call(message.timestamp) : options.longs === Number ? new $util.LongBits(message.timestamp.low >>> 0, message.timestamp.high >>> 0).toNumber() : message.timestamp;
                 if (message.type != null && message.hasOwnProperty("type"))
                     object.type = options.enums === String ? $root.graphwiz.core.MessageType[message.type] === undefined ? message.type : $root.graphwiz.core.MessageType[message.type] : message.type;
+                if (message.sequence != null && message.hasOwnProperty("sequence"))
+                    object.sequence = message.sequence;
                 if (message.clientHello != null && message.hasOwnProperty("clientHello")) {
                     object.clientHello = $root.graphwiz.core.ClientHello.toObject(message.clientHello, options);
                     if (options.oneofs)
@@ -621,6 +899,11 @@ export const graphwiz = $root.graphwiz = (() => {
                     if (options.oneofs)
                         object.payload = "serverHello";
                 }
+                if (message.redirect != null && message.hasOwnProperty("redirect")) {
+                    object.redirect = $root.graphwiz.core.Redirect.toObject(message.redirect, options);
+                    if (options.oneofs)
+                        object.payload = "redirect";
+                }
                 if (message.positionUpdate != null && message.hasOwnProperty("positionUpdate")) {
                     object.positionUpdate = $root.graphwiz.core.PositionUpdate.toObject(message.positionUpdate, options);
                     if (options.oneofs)
@@ -631,6 +914,16 @@ export const graphwiz = $root.graphwiz = (() => {
                     if (options.oneofs)
                         object.payload = "voiceData";
                 }
+                if (message.positionBatch != null && message.hasOwnProperty("positionBatch")) {
+                    object.positionBatch = $root.graphwiz.core.PositionBatch.toObject(message.positionBatch, options);
+                    if (options.oneofs)
+                        object.payload = "positionBatch";
+                }
+                if (message.spatialAudio != null && message.hasOwnProperty("spatialAudio")) {
+                    object.spatialAudio = $root.graphwiz.core.SpatialAudio.toObject(message.spatialAudio, options);
+                    if (options.oneofs)
+                        object.payload = "spatialAudio";
+                }
                 if (message.entitySpawn != null && message.hasOwnProperty("entitySpawn")) {
                     object.entitySpawn = $root.graphwiz.core.EntitySpawn.toObject(message.entitySpawn, options);
                     if (options.oneofs)
@@ -646,6 +939,11 @@ export const graphwiz = $root.graphwiz = (() => {
                     if (options.oneofs)
                         object.payload = "entityDespawn";
                 }
+                if (message.entityHistory != null && message.hasOwnProperty("entityHistory")) {
+                    object.entityHistory = $root.graphwiz.core.EntityHistory.toObject(message.entityHistory, options);
+                    if (options.oneofs)
+                        object.payload = "entityHistory";
+                }
                 if (message.chatMessage != null && message.hasOwnProperty("chatMessage")) {
                     object.chatMessage = $root.graphwiz.core.ChatMessage.toObject(message.chatMessage, options);
                     if (options.oneofs)
@@ -656,6 +954,21 @@ export const graphwiz = $root.graphwiz = (() => {
                     if (options.oneofs)
                         object.payload = "presenceEvent";
                 }
+                if (message.presenceSubscription != null && message.hasOwnProperty("presenceSubscription")) {
+                    object.presenceSubscription = $root.graphwiz.core.PresenceSubscription.toObject(message.presenceSubscription, options);
+                    if (options.oneofs)
+                        object.payload = "presenceSubscription";
+                }
+                if (message.errorFrame != null && message.hasOwnProperty("errorFrame")) {
+                    object.errorFrame = $root.graphwiz.core.ErrorFrame.toObject(message.errorFrame, options);
+                    if (options.oneofs)
+                        object.payload = "errorFrame";
+                }
+                if (message.rtcSignal != null && message.hasOwnProperty("rtcSignal")) {
+                    object.rtcSignal = $root.graphwiz.core.RtcSignal.toObject(message.rtcSignal, options);
+                    if (options.oneofs)
+                        object.payload = "rtcSignal";
+                }
                 return object;
             };
 
@@ -695,30 +1008,48 @@ export const graphwiz = $root.graphwiz = (() => {
          * @property {number} UNKNOWN=0 UNKNOWN value
          * @property {number} CLIENT_HELLO=1 CLIENT_HELLO value
          * @property {number} SERVER_HELLO=2 SERVER_HELLO value
+         * @property {number} ERROR=3 ERROR value
+         * @property {number} REDIRECT=4 REDIRECT value
          * @property {number} POSITION_UPDATE=10 POSITION_UPDATE value
          * @property {number} VOICE_DATA=11 VOICE_DATA value
+         * @property {number} POSITION_BATCH=12 POSITION_BATCH value
+         * @property {number} SPATIAL_AUDIO=13 SPATIAL_AUDIO value
          * @property {number} ENTITY_SPAWN=20 ENTITY_SPAWN value
          * @property {number} ENTITY_UPDATE=21 ENTITY_UPDATE value
          * @property {number} ENTITY_DESPAWN=22 ENTITY_DESPAWN value
+         * @property {number} ENTITY_HISTORY=23 ENTITY_HISTORY value
          * @property {number} CHAT_MESSAGE=30 CHAT_MESSAGE value
          * @property {number} PRESENCE_JOIN=40 PRESENCE_JOIN value
          * @property {number} PRESENCE_LEAVE=41 PRESENCE_LEAVE value
          * @property {number} PRESENCE_UPDATE=42 PRESENCE_UPDATE value
+         * @property {number} PRESENCE_HOST_CHANGED=43 PRESENCE_HOST_CHANGED value
+         * @property {number} PRESENCE_STATUS=44 PRESENCE_STATUS value
+         * @property {number} PRESENCE_SUBSCRIBE=45 PRESENCE_SUBSCRIBE value
+         * @property {number} RTC_SIGNAL=50 RTC_SIGNAL value
          */
         core.MessageType = (function() {
             const valuesById = {}, values = Object.create(valuesById);
             values[valuesById[0] = "UNKNOWN"] = 0;
             values[valuesById[1] = "CLIENT_HELLO"] = 1;
             values[valuesById[2] = "SERVER_HELLO"] = 2;
+            values[valuesById[3] = "ERROR"] = 3;
+            values[valuesById[4] = "REDIRECT"] = 4;
             values[valuesById[10] = "POSITION_UPDATE"] = 10;
             values[valuesById[11] = "VOICE_DATA"] = 11;
+            values[valuesById[12] = "POSITION_BATCH"] = 12;
+            values[valuesById[13] = "SPATIAL_AUDIO"] = 13;
             values[valuesById[20] = "ENTITY_SPAWN"] = 20;
             values[valuesById[21] = "ENTITY_UPDATE"] = 21;
             values[valuesById[22] = "ENTITY_DESPAWN"] = 22;
+            values[valuesById[23] = "ENTITY_HISTORY"] = 23;
             values[valuesById[30] = "CHAT_MESSAGE"] = 30;
             values[valuesById[40] = "PRESENCE_JOIN"] = 40;
             values[valuesById[41] = "PRESENCE_LEAVE"] = 41;
             values[valuesById[42] = "PRESENCE_UPDATE"] = 42;
+            values[valuesById[43] = "PRESENCE_HOST_CHANGED"] = 43;
+            values[valuesById[44] = "PRESENCE_STATUS"] = 44;
+            values[valuesById[45] = "PRESENCE_SUBSCRIBE"] = 45;
+            values[valuesById[50] = "RTC_SIGNAL"] = 50;
             return values;
         })();
 
@@ -1534,27 +1865,26 @@ export const graphwiz = $root.graphwiz = (() => {
             return PositionUpdate;
         })();
 
-        core.VoiceData = (function() {
+        core.PositionBatch = (function() {
 
             /**
-             * Properties of a VoiceData.
+             * Properties of a PositionBatch.
              * @memberof graphwiz.core
-             * @interface IVoiceData
-             * @property {string|null} [fromClientId] VoiceData fromClientId
-             * @property {Uint8Array|null} [audioData] VoiceData audioData
-             * @property {number|null} [sequenceNumber] VoiceData sequenceNumber
-             * @property {graphwiz.core.VoiceCodec|null} [codec] VoiceData codec
+             * @interface IPositionBatch
+             * @property {Array.<graphwiz.core.IPositionUpdate>|null} [updates] PositionBatch updates
+            * @property {number|Long|null} [tick] PositionBatch tick
              */
 
             /**
-             * Constructs a new VoiceData.
+             * Constructs a new PositionBatch.
              * @memberof graphwiz.core
-             * @classdesc Represents a VoiceData.
-             * @implements IVoiceData
+             * @classdesc Represents a PositionBatch.
+             * @implements IPositionBatch
              * @constructor
-             * @param {graphwiz.core.IVoiceData=} [properties] Properties to set
+             * @param {graphwiz.core.IPositionBatch=} [properties] Properties to set
              */
-            function VoiceData(properties) {
+            function PositionBatch(properties) {
+                this.updates = [];
                 if (properties)
                     for (let keys = Object.keys(properties), i = 0; i < keys.length; ++i)
                         if (properties[keys[i]] != null)
@@ -1562,119 +1892,94 @@ export const graphwiz = $root.graphwiz = (() => {
             }
 
             /**
-             * VoiceData fromClientId.
-             * @member {string} fromClientId
-             * @memberof graphwiz.core.VoiceData
-             * @instance
-             */
-            VoiceData.prototype.fromClientId = "";
-
-            /**
-             * VoiceData audioData.
-             * @member {Uint8Array} audioData
-             * @memberof graphwiz.core.VoiceData
-             * @instance
-             */
-            VoiceData.prototype.audioData = $util.newBuffer([]);
-
-            /**
-             * VoiceData sequenceNumber.
-             * @member {number} sequenceNumber
-             * @memberof graphwiz.core.VoiceData
+             * PositionBatch updates.
+             * @member {Array.<graphwiz.core.IPositionUpdate>} updates
+             * @memberof graphwiz.core.PositionBatch
              * @instance
              */
-            VoiceData.prototype.sequenceNumber = 0;
+            PositionBatch.prototype.updates = $util.emptyArray;
 
             /**
-             * VoiceData codec.
-             * @member {graphwiz.core.VoiceCodec} codec
-             * @memberof graphwiz.core.VoiceData
+             * PositionBatch tick.
+             * @member {number|Long} tick
+             * @memberof graphwiz.core.PositionBatch
              * @instance
              */
-            VoiceData.prototype.codec = 0;
+            PositionBatch.prototype.tick = $util.Long ? $util.Long.fromBits(0,0,false) : 0;
 
             /**
-             * Creates a new VoiceData instance using the specified properties.
+             * Creates a new PositionBatch instance using the specified properties.
              * @function create
-             * @memberof graphwiz.core.VoiceData
+             * @memberof graphwiz.core.PositionBatch
              * @static
-             * @param {graphwiz.core.IVoiceData=} [properties] Properties to set
-             * @returns {graphwiz.core.VoiceData} VoiceData instance
+             * @param {graphwiz.core.IPositionBatch=} [properties] Properties to set
+             * @returns {graphwiz.core.PositionBatch} PositionBatch instance
              */
-            VoiceData.create = function create(properties) {
-                return new VoiceData(properties);
+            PositionBatch.create = function create(properties) {
+                return new PositionBatch(properties);
             };
 
             /**
-             * Encodes the specified VoiceData message. Does not implicitly {@link graphwiz.core.VoiceData.verify|verify} messages.
+             * Encodes the specified PositionBatch message. Does not implicitly {@link graphwiz.core.PositionBatch.verify|verify} messages.
              * @function encode
-             * @memberof graphwiz.core.VoiceData
+             * @memberof graphwiz.core.PositionBatch
              * @static
-             * @param {graphwiz.core.IVoiceData} message VoiceData message or plain object to encode
+             * @param {graphwiz.core.IPositionBatch} message PositionBatch message or plain object to encode
              * @param {$protobuf.Writer} [writer] Writer to encode to
              * @returns {$protobuf.Writer} Writer
              */
-            VoiceData.encode = function encode(message, writer) {
+            PositionBatch.encode = function encode(message, writer) {
                 if (!writer)
                     writer = $Writer.create();
-                if (message.fromClientId != null && Object.hasOwnProperty.call(message, "fromClientId"))
-                    writer.uint32(/* id 1, wireType 2 =*/10).string(message.fromClientId);
-                if (message.audioData != null && Object.hasOwnProperty.call(message, "audioData"))
-                    writer.uint32(/* id 2, wireType 2 =*/18).bytes(message.audioData);
-                if (message.sequenceNumber != null && Object.hasOwnProperty.call(message, "sequenceNumber"))
-                    writer.uint32(/* id 3, wireType 0 =*/24).int32(message.sequenceNumber);
-                if (message.codec != null && Object.hasOwnProperty.call(message, "codec"))
-                    writer.uint32(/* id 4, wireType 0 =*/32).int32(message.codec);
+                if (message.updates != null && message.updates.length)
+                    for (let i = 0; i < message.updates.length; ++i)
+                        $root.graphwiz.core.PositionUpdate.encode(message.updates[i], writer.uint32(/* id 1, wireType 2 =*/10).fork()).ldelim();
+                if (message.tick != null && Object.hasOwnProperty.call(message, "tick"))
+                    writer.uint32(/* id 2, wireType 0 =*/16).int64(message.tick);
                 return writer;
             };
 
             /**
-             * Encodes the specified VoiceData message, length delimited. Does not implicitly {@link graphwiz.core.VoiceData.verify|verify} messages.
+             * Encodes the specified PositionBatch message, length delimited. Does not implicitly {@link graphwiz.core.PositionBatch.verify|verify} messages.
              * @function encodeDelimited
-             * @memberof graphwiz.core.VoiceData
+             * @memberof graphwiz.core.PositionBatch
              * @static
-             * @param {graphwiz.core.IVoiceData} message VoiceData message or plain object to encode
+             * @param {graphwiz.core.IPositionBatch} message PositionBatch message or plain object to encode
              * @param {$protobuf.Writer} [writer] Writer to encode to
              * @returns {$protobuf.Writer} Writer
              */
-            VoiceData.encodeDelimited = function encodeDelimited(message, writer) {
+            PositionBatch.encodeDelimited = function encodeDelimited(message, writer) {
                 return this.encode(message, writer).ldelim();
             };
 
             /**
-             * Decodes a VoiceData message from the specified reader or buffer.
+             * Decodes a PositionBatch message from the specified reader or buffer.
              * @function decode
-             * @memberof graphwiz.core.VoiceData
+             * @memberof graphwiz.core.PositionBatch
              * @static
              * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
              * @param {number} [length] Message length if known beforehand
-             * @returns {graphwiz.core.VoiceData} VoiceData
+             * @returns {graphwiz.core.PositionBatch} PositionBatch
              * @throws {Error} If the payload is not a reader or valid buffer
              * @throws {$protobuf.util.ProtocolError} If required fields are missing
              */
-            VoiceData.decode = function decode(reader, length, error) {
+            PositionBatch.decode = function decode(reader, length, error) {
                 if (!(reader instanceof $Reader))
                     reader = $Reader.create(reader);
-                let end = length === undefined ? reader.len : reader.pos + length, message = new $root.graphwiz.core.VoiceData();
+                let end = length === undefined ? reader.len : reader.pos + length, message = new $root.graphwiz.core.PositionBatch();
                 while (reader.pos < end) {
                     let tag = reader.uint32();
                     if (tag === error)
                         break;
                     switch (tag >>> 3) {
                     case 1: {
-                            message.fromClientId = reader.string();
+                            if (!(message.updates && message.updates.length))
+                                message.updates = [];
+                            message.updates.push($root.graphwiz.core.PositionUpdate.decode(reader, reader.uint32()));
                             break;
                         }
                     case 2: {
-                            message.audioData = reader.bytes();
-                            break;
-                        }
-                    case 3: {
-                            message.sequenceNumber = reader.int32();
-                            break;
-                        }
-                    case 4: {
-                            message.codec = reader.int32();
+                            message.tick = reader.int64();
                             break;
                         }
                     default:
@@ -1686,200 +1991,164 @@ export const graphwiz = $root.graphwiz = (() => {
             };
 
             /**
-             * Decodes a VoiceData message from the specified reader or buffer, length delimited.
+             * Decodes a PositionBatch message from the specified reader or buffer, length delimited.
              * @function decodeDelimited
-             * @memberof graphwiz.core.VoiceData
+             * @memberof graphwiz.core.PositionBatch
              * @static
              * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
-             * @returns {graphwiz.core.VoiceData} VoiceData
+             * @returns {graphwiz.core.PositionBatch} PositionBatch
              * @throws {Error} If the payload is not a reader or valid buffer
              * @throws {$protobuf.util.ProtocolError} If required fields are missing
              */
-            VoiceData.decodeDelimited = function decodeDelimited(reader) {
+            PositionBatch.decodeDelimited = function decodeDelimited(reader) {
                 if (!(reader instanceof $Reader))
                     reader = new $Reader(reader);
                 return this.decode(reader, reader.uint32());
             };
 
             /**
-             * Verifies a VoiceData message.
+             * Verifies a PositionBatch message.
              * @function verify
-             * @memberof graphwiz.core.VoiceData
+             * @memberof graphwiz.core.PositionBatch
              * @static
              * @param {Object.<string,*>} message Plain object to verify
              * @returns {string|null} `null` if valid, otherwise the reason why it is not
              */
-            VoiceData.verify = function verify(message) {
+            PositionBatch.verify = function verify(message) {
                 if (typeof message !== "object" || message === null)
                     return "object expected";
-                if (message.fromClientId != null && message.hasOwnProperty("fromClientId"))
-                    if (!$util.isString(message.fromClientId))
-                        return "fromClientId: string expected";
-                if (message.audioData != null && message.hasOwnProperty("audioData"))
-                    if (!(message.audioData && typeof message.audioData.length === "number" || $util.isString(message.audioData)))
-                        return "audioData: buffer expected";
-                if (message.sequenceNumber != null && message.hasOwnProperty("sequenceNumber"))
-                    if (!$util.isInteger(message.sequenceNumber))
-                        return "sequenceNumber: integer expected";
-                if (message.codec != null && message.hasOwnProperty("codec"))
-                    switch (message.codec) {
-                    default:
-                        return "codec: enum value expected";
-                    case 0:
-                    case 1:
-                    case 2:
-                        break;
+                if (message.updates != null && message.hasOwnProperty("updates")) {
+                    if (!Array.isArray(message.updates))
+                        return "updates: array expected";
+                    for (let i = 0; i < message.updates.length; ++i) {
+                        let error = $root.graphwiz.core.PositionUpdate.verify(message.updates[i]);
+                        if (error)
+                            return "updates." + error;
                     }
+                }
+                if (message.tick != null && message.hasOwnProperty("tick"))
+                    if (!$util.isInteger(message.tick) && !(message.tick && $util.isInteger(message.tick.low) && $util.isInteger(message.tick.high)))
+                        return "tick: integer|Long expected";
                 return null;
             };
 
             /**
-             * Creates a VoiceData message from a plain object. Also converts values to their respective internal types.
+             * Creates a PositionBatch message from a plain object. Also converts values to their respective internal types.
              * @function fromObject
-             * @memberof graphwiz.core.VoiceData
+             * @memberof graphwiz.core.PositionBatch
              * @static
              * @param {Object.<string,*>} object Plain object
-             * @returns {graphwiz.core.VoiceData} VoiceData
+             * @returns {graphwiz.core.PositionBatch} PositionBatch
              */
-            VoiceData.fromObject = function fromObject(object) {
-                if (object instanceof $root.graphwiz.core.VoiceData)
+            PositionBatch.fromObject = function fromObject(object) {
+                if (object instanceof $root.graphwiz.core.PositionBatch)
                     return object;
-                let message = new $root.graphwiz.core.VoiceData();
-                if (object.fromClientId != null)
-                    message.fromClientId = String(object.fromClientId);
-                if (object.audioData != null)
-                    if (typeof object.audioData === "string")
-                        $util.base64.decode(object.audioData, message.audioData = $util.newBuffer($util.base64.length(object.audioData)), 0);
-                    else if (object.audioData.length >= 0)
-                        message.audioData = object.audioData;
-                if (object.sequenceNumber != null)
-                    message.sequenceNumber = object.sequenceNumber | 0;
-                switch (object.codec) {
-                default:
-                    if (typeof object.codec === "number") {
-                        message.codec = object.codec;
-                        break;
+                let message = new $root.graphwiz.core.PositionBatch();
+                if (object.updates) {
+                    if (!Array.isArray(object.updates))
+                        throw TypeError(".graphwiz.core.PositionBatch.updates: array expected");
+                    message.updates = [];
+                    for (let i = 0; i < object.updates.length; ++i) {
+                        if (typeof object.updates[i] !== "object")
+                            throw TypeError(".graphwiz.core.PositionBatch.updates: object expected");
+                        message.updates[i] = $root.graphwiz.core.PositionUpdate.fromObject(object.updates[i]);
                     }
-                    break;
-                case "OPUS":
-                case 0:
-                    message.codec = 0;
-                    break;
-                case "PCMU":
-                case 1:
-                    message.codec = 1;
-                    break;
-                case "PCMA":
-                case 2:
-                    message.codec = 2;
-                    break;
                 }
+                if (object.tick != null)
+                    if ($util.Long)
+                        (message.tick = $util.Long.fromValue(object.tick)).unsigned = false;
+                    else if (typeof object.tick === "string")
+                        message.tick = parseInt(object.tick, 10);
+                    else if (typeof object.tick === "number")
+                        message.tick = object.tick;
+                    else if (typeof object.tick === "object")
+                        message.tick = new $util.LongBits(object.tick.low >>> 0, object.tick.high >>> 0).toNumber();
                 return message;
             };
 
             /**
-             * Creates a plain object from a VoiceData message. Also converts values to other types if specified.
+             * Creates a plain object from a PositionBatch message. Also converts values to other types if specified.
              * @function toObject
-             * @memberof graphwiz.core.VoiceData
+             * @memberof graphwiz.core.PositionBatch
              * @static
-             * @param {graphwiz.core.VoiceData} message VoiceData
+             * @param {graphwiz.core.PositionBatch} message PositionBatch
              * @param {$protobuf.IConversionOptions} [options] Conversion options
              * @returns {Object.<string,*>} Plain object
              */
-            VoiceData.toObject = function toObject(message, options) {
+            PositionBatch.toObject = function toObject(message, options) {
                 if (!options)
                     options = {};
                 let object = {};
-                if (options.defaults) {
-                    object.fromClientId = "";
-                    if (options.bytes === String)
-                        object.audioData = "";
-                    else {
-                        object.audioData = [];
-                        if (options.bytes !== Array)
-                            object.audioData = $util.newBuffer(object.audioData);
-                    }
-                    object.sequenceNumber = 0;
-                    object.codec = options.enums === String ? "OPUS" : 0;
+                if (options.arrays || options.defaults) {
+                    object.updates = [];
                 }
-                if (message.fromClientId != null && message.hasOwnProperty("fromClientId"))
-                    object.fromClientId = message.fromClientId;
-                if (message.audioData != null && message.hasOwnProperty("audioData"))
-                    object.audioData = options.bytes === String ? $util.base64.encode(message.audioData, 0, message.audioData.length) : options.bytes === Array ? Array.prototype.slice.call(message.audioData) : message.audioData;
-                if (message.sequenceNumber != null && message.hasOwnProperty("sequenceNumber"))
-                    object.sequenceNumber = message.sequenceNumber;
-                if (message.codec != null && message.hasOwnProperty("codec"))
-                    object.codec = options.enums === String ? $root.graphwiz.core.VoiceCodec[message.codec] === undefined ? message.codec : $root.graphwiz.core.VoiceCodec[message.codec] : message.codec;
+                if (options.defaults)
+                    if ($util.Long) {
+                        let long = new $util.Long(0, 0, false);
+                        object.tick = options.longs === String ? long.toString() : options.longs === Number ? long.toNumber() : long;
+                    } else
+                        object.tick = options.longs === String ? "0" : 0;
+                if (message.updates && message.updates.length) {
+                    object.updates = [];
+                    for (let j = 0; j < message.updates.length; ++j)
+                        object.updates[j] = $root.graphwiz.core.PositionUpdate.toObject(message.updates[j], options);
+                }
+                if (message.tick != null && message.hasOwnProperty("tick"))
+                    if (typeof message.tick === "number")
+                        object.tick = options.longs === String ? String(message.tick) : message.tick;
+                    else
+                        object.tick = options.longs === String ? $util.Long.prototype.toString.call(message.tick) : options.longs === Number ? new $util.LongBits(message.tick.low >>> 0, message.tick.high >>> 0).toNumber() : message.tick;
                 return object;
             };
 
             /**
-             * Converts this VoiceData to JSON.
+             * Converts this PositionBatch to JSON.
              * @function toJSON
-             * @memberof graphwiz.core.VoiceData
+             * @memberof graphwiz.core.PositionBatch
              * @instance
              * @returns {Object.<string,*>} JSON object
              */
-            VoiceData.prototype.toJSON = function toJSON() {
+            PositionBatch.prototype.toJSON = function toJSON() {
                 return this.constructor.toObject(this, $protobuf.util.toJSONOptions);
             };
 
             /**
-             * Gets the default type url for VoiceData
+             * Gets the default type url for PositionBatch
              * @function getTypeUrl
-             * @memberof graphwiz.core.VoiceData
+             * @memberof graphwiz.core.PositionBatch
              * @static
              * @param {string} [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
              * @returns {string} The default type url
              */
-            VoiceData.getTypeUrl = function getTypeUrl(typeUrlPrefix) {
+            PositionBatch.getTypeUrl = function getTypeUrl(typeUrlPrefix) {
                 if (typeUrlPrefix === undefined) {
                     typeUrlPrefix = "type.googleapis.com";
                 }
-                return typeUrlPrefix + "/graphwiz.core.VoiceData";
+                return typeUrlPrefix + "/graphwiz.core.PositionBatch";
             };
 
-            return VoiceData;
-        })();
-
-        /**
-         * VoiceCodec enum.
-         * @name graphwiz.core.VoiceCodec
-         * @enum {number}
-         * @property {number} OPUS=0 OPUS value
-         * @property {number} PCMU=1 PCMU value
-         * @property {number} PCMA=2 PCMA value
-         */
-        core.VoiceCodec = (function() {
-            const valuesById = {}, values = Object.create(valuesById);
-            values[valuesById[0] = "OPUS"] = 0;
-            values[valuesById[1] = "PCMU"] = 1;
-            values[valuesById[2] = "PCMA"] = 2;
-            return values;
+            return PositionBatch;
         })();
 
-        core.EntitySpawn = (function() {
+        core.SpatialAudio = (function() {
 
             /**
-             * Properties of an EntitySpawn.
+             * Properties of a SpatialAudio.
              * @memberof graphwiz.core
-             * @interface IEntitySpawn
-             * @property {string|null} [entityId] EntitySpawn entityId
-             * @property {string|null} [templateId] EntitySpawn templateId
-             * @property {string|null} [ownerId] EntitySpawn ownerId
-             * @property {Object.<string,string>|null} [components] EntitySpawn components
+             * @interface ISpatialAudio
+             * @property {Array.<graphwiz.core.ISpeakerSpatial>|null} [speakers] SpatialAudio speakers
              */
 
             /**
-             * Constructs a new EntitySpawn.
+             * Constructs a new SpatialAudio.
              * @memberof graphwiz.core
-             * @classdesc Represents an EntitySpawn.
-             * @implements IEntitySpawn
+             * @classdesc Represents a SpatialAudio.
+             * @implements ISpatialAudio
              * @constructor
-             * @param {graphwiz.core.IEntitySpawn=} [properties] Properties to set
+             * @param {graphwiz.core.ISpatialAudio=} [properties] Properties to set
              */
-            function EntitySpawn(properties) {
-                this.components = {};
+            function SpatialAudio(properties) {
+                this.speakers = [];
                 if (properties)
                     for (let keys = Object.keys(properties), i = 0; i < keys.length; ++i)
                         if (properties[keys[i]] != null)
@@ -1887,139 +2156,80 @@ export const graphwiz = $root.graphwiz = (() => {
             }
 
             /**
-             * EntitySpawn entityId.
-             * @member {string} entityId
-             * @memberof graphwiz.core.EntitySpawn
-             * @instance
-             */
-            EntitySpawn.prototype.entityId = "";
-
-            /**
-             * EntitySpawn templateId.
-             * @member {string} templateId
-             * @memberof graphwiz.core.EntitySpawn
-             * @instance
-             */
-            EntitySpawn.prototype.templateId = "";
-
-            /**
-             * EntitySpawn ownerId.
-             * @member {string} ownerId
-             * @memberof graphwiz.core.EntitySpawn
-             * @instance
-             */
-            EntitySpawn.prototype.ownerId = "";
-
-            /**
-             * EntitySpawn components.
-             * @member {Object.<string,string>} components
-             * @memberof graphwiz.core.EntitySpawn
+             * SpatialAudio speakers.
+             * @member {Array.<graphwiz.core.ISpeakerSpatial>} speakers
+             * @memberof graphwiz.core.SpatialAudio
              * @instance
              */
-            EntitySpawn.prototype.components = $util.emptyObject;
+            SpatialAudio.prototype.speakers = $util.emptyArray;
 
             /**
-             * Creates a new EntitySpawn instance using the specified properties.
+             * Creates a new SpatialAudio instance using the specified properties.
              * @function create
-             * @memberof graphwiz.core.EntitySpawn
+             * @memberof graphwiz.core.SpatialAudio
              * @static
-             * @param {graphwiz.core.IEntitySpawn=} [properties] Properties to set
-             * @returns {graphwiz.core.EntitySpawn} EntitySpawn instance
+             * @param {graphwiz.core.ISpatialAudio=} [properties] Properties to set
+             * @returns {graphwiz.core.SpatialAudio} SpatialAudio instance
              */
-            EntitySpawn.create = function create(properties) {
-                return new EntitySpawn(properties);
+            SpatialAudio.create = function create(properties) {
+                return new SpatialAudio(properties);
             };
 
             /**
-             * Encodes the specified EntitySpawn message. Does not implicitly {@link graphwiz.core.EntitySpawn.verify|verify} messages.
+             * Encodes the specified SpatialAudio message. Does not implicitly {@link graphwiz.core.SpatialAudio.verify|verify} messages.
              * @function encode
-             * @memberof graphwiz.core.EntitySpawn
+             * @memberof graphwiz.core.SpatialAudio
              * @static
-             * @param {graphwiz.core.IEntitySpawn} message EntitySpawn message or plain object to encode
+             * @param {graphwiz.core.ISpatialAudio} message SpatialAudio message or plain object to encode
              * @param {$protobuf.Writer} [writer] Writer to encode to
              * @returns {$protobuf.Writer} Writer
              */
-            EntitySpawn.encode = function encode(message, writer) {
+            SpatialAudio.encode = function encode(message, writer) {
                 if (!writer)
                     writer = $Writer.create();
-                if (message.entityId != null && Object.hasOwnProperty.call(message, "entityId"))
-                    writer.uint32(/* id 1, wireType 2 =*/10).string(message.entityId);
-                if (message.templateId != null && Object.hasOwnProperty.call(message, "templateId"))
-                    writer.uint32(/* id 2, wireType 2 =*/18).string(message.templateId);
-                if (message.ownerId != null && Object.hasOwnProperty.call(message, "ownerId"))
-                    writer.uint32(/* id 3, wireType 2 =*/26).string(message.ownerId);
-                if (message.components != null && Object.hasOwnProperty.call(message, "components"))
-                    for (let keys = Object.keys(message.components), i = 0; i < keys.length; ++i)
-                        writer.uint32(/* id 4, wireType 2 =*/34).fork().uint32(/* id 1, wireType 2 =*/10).string(keys[i]).uint32(/* id 2, wireType 2 =*/18).string(message.components[keys[i]]).ldelim();
+                if (message.speakers != null && message.speakers.length)
+                    for (let i = 0; i < message.speakers.length; ++i)
+                        $root.graphwiz.core.SpeakerSpatial.encode(message.speakers[i], writer.uint32(/* id 1, wireType 2 =*/10).fork()).ldelim();
                 return writer;
             };
 
             /**
-             * Encodes the specified EntitySpawn message, length delimited. Does not implicitly {@link graphwiz.core.EntitySpawn.verify|verify} messages.
+             * Encodes the specified SpatialAudio message, length delimited. Does not implicitly {@link graphwiz.core.SpatialAudio.verify|verify} messages.
              * @function encodeDelimited
-             * @memberof graphwiz.core.EntitySpawn
+             * @memberof graphwiz.core.SpatialAudio
              * @static
-             * @param {graphwiz.core.IEntitySpawn} message EntitySpawn message or plain object to encode
+             * @param {graphwiz.core.ISpatialAudio} message SpatialAudio message or plain object to encode
              * @param {$protobuf.Writer} [writer] Writer to encode to
              * @returns {$protobuf.Writer} Writer
              */
-            EntitySpawn.encodeDelimited = function encodeDelimited(message, writer) {
+            SpatialAudio.encodeDelimited = function encodeDelimited(message, writer) {
                 return this.encode(message, writer).ldelim();
             };
 
             /**
-             * Decodes an EntitySpawn message from the specified reader or buffer.
+             * Decodes a SpatialAudio message from the specified reader or buffer.
              * @function decode
-             * @memberof graphwiz.core.EntitySpawn
+             * @memberof graphwiz.core.SpatialAudio
              * @static
              * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
              * @param {number} [length] Message length if known beforehand
-             * @returns {graphwiz.core.EntitySpawn} EntitySpawn
+             * @returns {graphwiz.core.SpatialAudio} SpatialAudio
              * @throws {Error} If the payload is not a reader or valid buffer
              * @throws {$protobuf.util.ProtocolError} If required fields are missing
              */
-            EntitySpawn.decode = function decode(reader, length, error) {
+            SpatialAudio.decode = function decode(reader, length, error) {
                 if (!(reader instanceof $Reader))
                     reader = $Reader.create(reader);
-                let end = length === undefined ? reader.len : reader.pos + length, message = new $root.graphwiz.core.EntitySpawn(), key, value;
+                let end = length === undefined ? reader.len : reader.pos + length, message = new $root.graphwiz.core.SpatialAudio();
                 while (reader.pos < end) {
                     let tag = reader.uint32();
                     if (tag === error)
                         break;
                     switch (tag >>> 3) {
                     case 1: {
-                            message.entityId = reader.string();
-                            break;
-                        }
-                    case 2: {
-                            message.templateId = reader.string();
-                            break;
-                        }
-                    case 3: {
-                            message.ownerId = reader.string();
-                            break;
-                        }
-                    case 4: {
-                            if (message.components === $util.emptyObject)
-                                message.components = {};
-                            let end2 = reader.uint32() + reader.pos;
-                            key = "";
-                            value = "";
-                            while (reader.pos < end2) {
-                                let tag2 = reader.uint32();
-                                switch (tag2 >>> 3) {
-                                case 1:
-                                    key = reader.string();
-                                    break;
-                                case 2:
-                                    value = reader.string();
-                                    break;
-                                default:
-                                    reader.skipType(tag2 & 7);
-                                    break;
-                                }
-                            }
-                            message.components[key] = value;
+                            if (!(message.speakers && message.speakers.length))
+                                message.speakers = [];
+                            message.speakers.push($root.graphwiz.core.SpeakerSpatial.decode(reader, reader.uint32()));
                             break;
                         }
                     default:
@@ -2031,164 +2241,142 @@ export const graphwiz = $root.graphwiz = (() => {
             };
 
             /**
-             * Decodes an EntitySpawn message from the specified reader or buffer, length delimited.
+             * Decodes a SpatialAudio message from the specified reader or buffer, length delimited.
              * @function decodeDelimited
-             * @memberof graphwiz.core.EntitySpawn
+             * @memberof graphwiz.core.SpatialAudio
              * @static
              * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
-             * @returns {graphwiz.core.EntitySpawn} EntitySpawn
+             * @returns {graphwiz.core.SpatialAudio} SpatialAudio
              * @throws {Error} If the payload is not a reader or valid buffer
              * @throws {$protobuf.util.ProtocolError} If required fields are missing
              */
-            EntitySpawn.decodeDelimited = function decodeDelimited(reader) {
+            SpatialAudio.decodeDelimited = function decodeDelimited(reader) {
                 if (!(reader instanceof $Reader))
                     reader = new $Reader(reader);
                 return this.decode(reader, reader.uint32());
             };
 
             /**
-             * Verifies an EntitySpawn message.
+             * Verifies a SpatialAudio message.
              * @function verify
-             * @memberof graphwiz.core.EntitySpawn
+             * @memberof graphwiz.core.SpatialAudio
              * @static
              * @param {Object.<string,*>} message Plain object to verify
              * @returns {string|null} `null` if valid, otherwise the reason why it is not
              */
-            EntitySpawn.verify = function verify(message) {
+            SpatialAudio.verify = function verify(message) {
                 if (typeof message !== "object" || message === null)
                     return "object expected";
-                if (message.entityId != null && message.hasOwnProperty("entityId"))
-                    if (!$util.isString(message.entityId))
-                        return "entityId: string expected";
-                if (message.templateId != null && message.hasOwnProperty("templateId"))
-                    if (!$util.isString(message.templateId))
-                        return "templateId: string expected";
-                if (message.ownerId != null && message.hasOwnProperty("ownerId"))
-                    if (!$util.isString(message.ownerId))
-                        return "ownerId: string expected";
-                if (message.components != null && message.hasOwnProperty("components")) {
-                    if (!$util.isObject(message.components))
-                        return "components: object expected";
-                    let key = Object.keys(message.components);
-                    for (let i = 0; i < key.length; ++i)
-                        if (!$util.isString(message.components[key[i]]))
-                            return "components: string{k:string} expected";
+                if (message.speakers != null && message.hasOwnProperty("speakers")) {
+                    if (!Array.isArray(message.speakers))
+                        return "speakers: array expected";
+                    for (let i = 0; i < message.speakers.length; ++i) {
+                        let error = $root.graphwiz.core.SpeakerSpatial.verify(message.speakers[i]);
+                        if (error)
+                            return "speakers." + error;
+                    }
                 }
                 return null;
             };
 
             /**
-             * Creates an EntitySpawn message from a plain object. Also converts values to their respective internal types.
+             * Creates a SpatialAudio message from a plain object. Also converts values to their respective internal types.
              * @function fromObject
-             * @memberof graphwiz.core.EntitySpawn
+             * @memberof graphwiz.core.SpatialAudio
              * @static
              * @param {Object.<string,*>} object Plain object
-             * @returns {graphwiz.core.EntitySpawn} EntitySpawn
+             * @returns {graphwiz.core.SpatialAudio} SpatialAudio
              */
-            EntitySpawn.fromObject = function fromObject(object) {
-                if (object instanceof $root.graphwiz.core.EntitySpawn)
+            SpatialAudio.fromObject = function fromObject(object) {
+                if (object instanceof $root.graphwiz.core.SpatialAudio)
                     return object;
-                let message = new $root.graphwiz.core.EntitySpawn();
-                if (object.entityId != null)
-                    message.entityId = String(object.entityId);
-                if (object.templateId != null)
-                    message.templateId = String(object.templateId);
-                if (object.ownerId != null)
-                    message.ownerId = String(object.ownerId);
-                if (object.components) {
-                    if (typeof object.components !== "object")
-                        throw TypeError(".graphwiz.core.EntitySpawn.components: object expected");
-                    message.components = {};
-                    for (let keys = Object.keys(object.components), i = 0; i < keys.length; ++i)
-                        message.components[keys[i]] = String(object.components[keys[i]]);
+                let message = new $root.graphwiz.core.SpatialAudio();
+                if (object.speakers) {
+                    if (!Array.isArray(object.speakers))
+                        throw TypeError(".graphwiz.core.SpatialAudio.speakers: array expected");
+                    message.speakers = [];
+                    for (let i = 0; i < object.speakers.length; ++i) {
+                        if (typeof object.speakers[i] !== "object")
+                            throw TypeError(".graphwiz.core.SpatialAudio.speakers: object expected");
+                        message.speakers[i] = $root.graphwiz.core.SpeakerSpatial.fromObject(object.speakers[i]);
+                    }
                 }
                 return message;
             };
 
             /**
-             * Creates a plain object from an EntitySpawn message. Also converts values to other types if specified.
+             * Creates a plain object from a SpatialAudio message. Also converts values to other types if specified.
              * @function toObject
-             * @memberof graphwiz.core.EntitySpawn
+             * @memberof graphwiz.core.SpatialAudio
              * @static
-             * @param {graphwiz.core.EntitySpawn} message EntitySpawn
+             * @param {graphwiz.core.SpatialAudio} message SpatialAudio
              * @param {$protobuf.IConversionOptions} [options] Conversion options
              * @returns {Object.<string,*>} Plain object
              */
-            EntitySpawn.toObject = function toObject(message, options) {
+            SpatialAudio.toObject = function toObject(message, options) {
                 if (!options)
                     options = {};
                 let object = {};
-                if (options.objects || options.defaults)
-                    object.components = {};
-                if (options.defaults) {
-                    object.entityId = "";
-                    object.templateId = "";
-                    object.ownerId = "";
+                if (options.arrays || options.defaults) {
+                    object.speakers = [];
                 }
-                if (message.entityId != null && message.hasOwnProperty("entityId"))
-                    object.entityId = message.entityId;
-                if (message.templateId != null && message.hasOwnProperty("templateId"))
-                    object.templateId = message.templateId;
-                if (message.ownerId != null && message.hasOwnProperty("ownerId"))
-                    object.ownerId = message.ownerId;
-                let keys2;
-                if (message.components && (keys2 = Object.keys(message.components)).length) {
-                    object.components = {};
-                    for (let j = 0; j < keys2.length; ++j)
-                        object.components[keys2[j]] = message.components[keys2[j]];
+                if (message.speakers && message.speakers.length) {
+                    object.speakers = [];
+                    for (let j = 0; j < message.speakers.length; ++j)
+                        object.speakers[j] = $root.graphwiz.core.SpeakerSpatial.toObject(message.speakers[j], options);
                 }
                 return object;
             };
 
             /**
-             * Converts this EntitySpawn to JSON.
+             * Converts this SpatialAudio to JSON.
              * @function toJSON
-             * @memberof graphwiz.core.EntitySpawn
+             * @memberof graphwiz.core.SpatialAudio
              * @instance
              * @returns {Object.<string,*>} JSON object
              */
-            EntitySpawn.prototype.toJSON = function toJSON() {
+            SpatialAudio.prototype.toJSON = function toJSON() {
                 return this.constructor.toObject(this, $protobuf.util.toJSONOptions);
             };
 
             /**
-             * Gets the default type url for EntitySpawn
+             * Gets the default type url for SpatialAudio
              * @function getTypeUrl
-             * @memberof graphwiz.core.EntitySpawn
+             * @memberof graphwiz.core.SpatialAudio
              * @static
              * @param {string} [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
              * @returns {string} The default type url
              */
-            EntitySpawn.getTypeUrl = function getTypeUrl(typeUrlPrefix) {
+            SpatialAudio.getTypeUrl = function getTypeUrl(typeUrlPrefix) {
                 if (typeUrlPrefix === undefined) {
                     typeUrlPrefix = "type.googleapis.com";
                 }
-                return typeUrlPrefix + "/graphwiz.core.EntitySpawn";
+                return typeUrlPrefix + "/graphwiz.core.SpatialAudio";
             };
 
-            return EntitySpawn;
+            return SpatialAudio;
         })();
 
-        core.EntityUpdate = (function() {
+        core.SpeakerSpatial = (function() {
 
             /**
-             * Properties of an EntityUpdate.
+             * Properties of a SpeakerSpatial.
              * @memberof graphwiz.core
-             * @interface IEntityUpdate
-             * @property {string|null} [entityId] EntityUpdate entityId
-             * @property {Object.<string,Uint8Array>|null} [components] EntityUpdate components
+             * @interface ISpeakerSpatial
+             * @property {string|null} [clientId] SpeakerSpatial clientId
+             * @property {number|null} [gain] SpeakerSpatial gain
+             * @property {number|null} [pan] SpeakerSpatial pan
              */
 
             /**
-             * Constructs a new EntityUpdate.
+             * Constructs a new SpeakerSpatial.
              * @memberof graphwiz.core
-             * @classdesc Represents an EntityUpdate.
-             * @implements IEntityUpdate
+             * @classdesc Represents a SpeakerSpatial.
+             * @implements ISpeakerSpatial
              * @constructor
-             * @param {graphwiz.core.IEntityUpdate=} [properties] Properties to set
+             * @param {graphwiz.core.ISpeakerSpatial=} [properties] Properties to set
              */
-            function EntityUpdate(properties) {
-                this.components = {};
+            function SpeakerSpatial(properties) {
                 if (properties)
                     for (let keys = Object.keys(properties), i = 0; i < keys.length; ++i)
                         if (properties[keys[i]] != null)
@@ -2196,111 +2384,105 @@ export const graphwiz = $root.graphwiz = (() => {
             }
 
             /**
-             * EntityUpdate entityId.
-             * @member {string} entityId
-             * @memberof graphwiz.core.EntityUpdate
+             * SpeakerSpatial clientId.
+             * @member {string} clientId
+             * @memberof graphwiz.core.SpeakerSpatial
              * @instance
              */
-            EntityUpdate.prototype.entityId = "";
+            SpeakerSpatial.prototype.clientId = "";
 
             /**
-             * EntityUpdate components.
-             * @member {Object.<string,Uint8Array>} components
-             * @memberof graphwiz.core.EntityUpdate
+             * SpeakerSpatial gain.
+             * @member {number} gain
+             * @memberof graphwiz.core.SpeakerSpatial
              * @instance
              */
-            EntityUpdate.prototype.components = $util.emptyObject;
+            SpeakerSpatial.prototype.gain = 0;
 
             /**
-             * Creates a new EntityUpdate instance using the specified properties.
+             * SpeakerSpatial pan.
+             * @member {number} pan
+             * @memberof graphwiz.core.SpeakerSpatial
+             * @instance
+             */
+            SpeakerSpatial.prototype.pan = 0;
+
+            /**
+             * Creates a new SpeakerSpatial instance using the specified properties.
              * @function create
-             * @memberof graphwiz.core.EntityUpdate
+             * @memberof graphwiz.core.SpeakerSpatial
              * @static
-             * @param {graphwiz.core.IEntityUpdate=} [properties] Properties to set
-             * @returns {graphwiz.core.EntityUpdate} EntityUpdate instance
+             * @param {graphwiz.core.ISpeakerSpatial=} [properties] Properties to set
+             * @returns {graphwiz.core.SpeakerSpatial} SpeakerSpatial instance
              */
-            EntityUpdate.create = function create(properties) {
-                return new EntityUpdate(properties);
+            SpeakerSpatial.create = function create(properties) {
+                return new SpeakerSpatial(properties);
             };
 
             /**
-             * Encodes the specified EntityUpdate message. Does not implicitly {@link graphwiz.core.EntityUpdate.verify|verify} messages.
+             * Encodes the specified SpeakerSpatial message. Does not implicitly {@link graphwiz.core.SpeakerSpatial.verify|verify} messages.
              * @function encode
-             * @memberof graphwiz.core.EntityUpdate
+             * @memberof graphwiz.core.SpeakerSpatial
              * @static
-             * @param {graphwiz.core.IEntityUpdate} message EntityUpdate message or plain object to encode
+             * @param {graphwiz.core.ISpeakerSpatial} message SpeakerSpatial message or plain object to encode
              * @param {$protobuf.Writer} [writer] Writer to encode to
              * @returns {$protobuf.Writer} Writer
              */
-            EntityUpdate.encode = function encode(message, writer) {
+            SpeakerSpatial.encode = function encode(message, writer) {
                 if (!writer)
                     writer = $Writer.create();
-                if (message.entityId != null && Object.hasOwnProperty.call(message, "entityId"))
-                    writer.uint32(/* id 1, wireType 2 =*/10).string(message.entityId);
-                if (message.components != null && Object.hasOwnProperty.call(message, "components"))
-                    for (let keys = Object.keys(message.components), i = 0; i < keys.length; ++i)
-                        writer.uint32(/* id 2, wireType 2 =*/18).fork().uint32(/* id 1, wireType 2 =*/10).string(keys[i]).uint32(/* id 2, wireType 2 =*/18).bytes(message.components[keys[i]]).ldelim();
+                if (message.clientId != null && Object.hasOwnProperty.call(message, "clientId"))
+                    writer.uint32(/* id 1, wireType 2 =*/10).string(message.clientId);
+                if (message.gain != null && Object.hasOwnProperty.call(message, "gain"))
+                    writer.uint32(/* id 2, wireType 5 =*/21).float(message.gain);
+                if (message.pan != null && Object.hasOwnProperty.call(message, "pan"))
+                    writer.uint32(/* id 3, wireType 5 =*/29).float(message.pan);
                 return writer;
             };
 
             /**
-             * Encodes the specified EntityUpdate message, length delimited. Does not implicitly {@link graphwiz.core.EntityUpdate.verify|verify} messages.
+             * Encodes the specified SpeakerSpatial message, length delimited. Does not implicitly {@link graphwiz.core.SpeakerSpatial.verify|verify} messages.
              * @function encodeDelimited
-             * @memberof graphwiz.core.EntityUpdate
+             * @memberof graphwiz.core.SpeakerSpatial
              * @static
-             * @param {graphwiz.core.IEntityUpdate} message EntityUpdate message or plain object to encode
+             * @param {graphwiz.core.ISpeakerSpatial} message SpeakerSpatial message or plain object to encode
              * @param {$protobuf.Writer} [writer] Writer to encode to
              * @returns {$protobuf.Writer} Writer
              */
-            EntityUpdate.encodeDelimited = function encodeDelimited(message, writer) {
+            SpeakerSpatial.encodeDelimited = function encodeDelimited(message, writer) {
                 return this.encode(message, writer).ldelim();
             };
 
             /**
-             * Decodes an EntityUpdate message from the specified reader or buffer.
+             * Decodes a SpeakerSpatial message from the specified reader or buffer.
              * @function decode
-             * @memberof graphwiz.core.EntityUpdate
+             * @memberof graphwiz.core.SpeakerSpatial
              * @static
              * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
              * @param {number} [length] Message length if known beforehand
-             * @returns {graphwiz.core.EntityUpdate} EntityUpdate
+             * @returns {graphwiz.core.SpeakerSpatial} SpeakerSpatial
              * @throws {Error} If the payload is not a reader or valid buffer
              * @throws {$protobuf.util.ProtocolError} If required fields are missing
              */
-            EntityUpdate.decode = function decode(reader, length, error) {
+            SpeakerSpatial.decode = function decode(reader, length, error) {
                 if (!(reader instanceof $Reader))
                     reader = $Reader.create(reader);
-                let end = length === undefined ? reader.len : reader.pos + length, message = new $root.graphwiz.core.EntityUpdate(), key, value;
+                let end = length === undefined ? reader.len : reader.pos + length, message = new $root.graphwiz.core.SpeakerSpatial();
                 while (reader.pos < end) {
                     let tag = reader.uint32();
                     if (tag === error)
                         break;
                     switch (tag >>> 3) {
                     case 1: {
-                            message.entityId = reader.string();
+                            message.clientId = reader.string();
                             break;
                         }
                     case 2: {
-                            if (message.components === $util.emptyObject)
-                                message.components = {};
-                            let end2 = reader.uint32() + reader.pos;
-                            key = "";
-                            value = [];
-                            while (reader.pos < end2) {
-                                let tag2 = reader.uint32();
-                                switch (tag2 >>> 3) {
-                                case 1:
-                                    key = reader.string();
-                                    break;
-                                case 2:
-                                    value = reader.bytes();
-                                    break;
-                                default:
-                                    reader.skipType(tag2 & 7);
-                                    break;
-                                }
-                            }
-                            message.components[key] = value;
+                            message.gain = reader.float();
+                            break;
+                        }
+                    case 3: {
+                            message.pan = reader.float();
                             break;
                         }
                     default:
@@ -2312,148 +2494,142 @@ export const graphwiz = $root.graphwiz = (() => {
             };
 
             /**
-             * Decodes an EntityUpdate message from the specified reader or buffer, length delimited.
+             * Decodes a SpeakerSpatial message from the specified reader or buffer, length delimited.
              * @function decodeDelimited
-             * @memberof graphwiz.core.EntityUpdate
+             * @memberof graphwiz.core.SpeakerSpatial
              * @static
              * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
-             * @returns {graphwiz.core.EntityUpdate} EntityUpdate
+             * @returns {graphwiz.core.SpeakerSpatial} SpeakerSpatial
              * @throws {Error} If the payload is not a reader or valid buffer
              * @throws {$protobuf.util.ProtocolError} If required fields are missing
              */
-            EntityUpdate.decodeDelimited = function decodeDelimited(reader) {
+            SpeakerSpatial.decodeDelimited = function decodeDelimited(reader) {
                 if (!(reader instanceof $Reader))
                     reader = new $Reader(reader);
                 return this.decode(reader, reader.uint32());
             };
 
             /**
-             * Verifies an EntityUpdate message.
+             * Verifies a SpeakerSpatial message.
              * @function verify
-             * @memberof graphwiz.core.EntityUpdate
+             * @memberof graphwiz.core.SpeakerSpatial
              * @static
              * @param {Object.<string,*>} message Plain object to verify
              * @returns {string|null} `null` if valid, otherwise the reason why it is not
              */
-            EntityUpdate.verify = function verify(message) {
+            SpeakerSpatial.verify = function verify(message) {
                 if (typeof message !== "object" || message === null)
                     return "object expected";
-                if (message.entityId != null && message.hasOwnProperty("entityId"))
-                    if (!$util.isString(message.entityId))
-                        return "entityId: string expected";
-                if (message.components != null && message.hasOwnProperty("components")) {
-                    if (!$util.isObject(message.components))
-                        return "components: object expected";
-                    let key = Object.keys(message.components);
-                    for (let i = 0; i < key.length; ++i)
-                        if (!(message.components[key[i]] && typeof message.components[key[i]].length === "number" || $util.isString(message.components[key[i]])))
-                            return "components: buffer{k:string} expected";
-                }
+                if (message.clientId != null && message.hasOwnProperty("clientId"))
+                    if (!$util.isString(message.clientId))
+                        return "clientId: string expected";
+                if (message.gain != null && message.hasOwnProperty("gain"))
+                    if (typeof message.gain !== "number")
+                        return "gain: number expected";
+                if (message.pan != null && message.hasOwnProperty("pan"))
+                    if (typeof message.pan !== "number")
+                        return "pan: number expected";
                 return null;
             };
 
             /**
-             * Creates an EntityUpdate message from a plain object. Also converts values to their respective internal types.
+             * Creates a SpeakerSpatial message from a plain object. Also converts values to their respective internal types.
              * @function fromObject
-             * @memberof graphwiz.core.EntityUpdate
+             * @memberof graphwiz.core.SpeakerSpatial
              * @static
              * @param {Object.<string,*>} object Plain object
-             * @returns {graphwiz.core.EntityUpdate} EntityUpdate
+             * @returns {graphwiz.core.SpeakerSpatial} SpeakerSpatial
              */
-            EntityUpdate.fromObject = function fromObject(object) {
-                if (object instanceof $root.graphwiz.core.EntityUpdate)
+            SpeakerSpatial.fromObject = function fromObject(object) {
+                if (object instanceof $root.graphwiz.core.SpeakerSpatial)
                     return object;
-                let message = new $root.graphwiz.core.EntityUpdate();
-                if (object.entityId != null)
-                    message.entityId = String(object.entityId);
-                if (object.components) {
-                    if (typeof object.components !== "object")
-                        throw TypeError(".graphwiz.core.EntityUpdate.components: object expected");
-                    message.components = {};
-                    for (let keys = Object.keys(object.components), i = 0; i < keys.length; ++i)
-                        if (typeof object.components[keys[i]] === "string")
-                            $util.base64.decode(object.components[keys[i]], message.components[keys[i]] = $util.newBuffer($util.base64.length(object.components[keys[i]])), 0);
-                        else if (object.components[keys[i]].length >= 0)
-                            message.components[keys[i]] = object.components[keys[i]];
-                }
+                let message = new $root.graphwiz.core.SpeakerSpatial();
+                if (object.clientId != null)
+                    message.clientId = String(object.clientId);
+                if (object.gain != null)
+                    message.gain = Number(object.gain);
+                if (object.pan != null)
+                    message.pan = Number(object.pan);
                 return message;
             };
 
             /**
-             * Creates a plain object from an EntityUpdate message. Also converts values to other types if specified.
+             * Creates a plain object from a SpeakerSpatial message. Also converts values to other types if specified.
              * @function toObject
-             * @memberof graphwiz.core.EntityUpdate
+             * @memberof graphwiz.core.SpeakerSpatial
              * @static
-             * @param {graphwiz.core.EntityUpdate} message EntityUpdate
+             * @param {graphwiz.core.SpeakerSpatial} message SpeakerSpatial
              * @param {$protobuf.IConversionOptions} [options] Conversion options
              * @returns {Object.<string,*>} Plain object
              */
-            EntityUpdate.toObject = function toObject(message, options) {
+            SpeakerSpatial.toObject = function toObject(message, options) {
                 if (!options)
                     options = {};
                 let object = {};
-                if (options.objects || options.defaults)
-                    object.components = {};
-                if (options.defaults)
-                    object.entityId = "";
-                if (message.entityId != null && message.hasOwnProperty("entityId"))
-                    object.entityId = message.entityId;
-                let keys2;
-                if (message.components && (keys2 = Object.keys(message.components)).length) {
-                    object.components = {};
-                    for (let j = 0; j < keys2.length; ++j)
-                        object.components[keys2[j]] = options.bytes === String ? $util.base64.encode(message.components[keys2[j]], 0, message.components[keys2[j]].length) : options.bytes === Array ? Array.prototype.slice.call(message.components[keys2[j]]) : message.components[keys2[j]];
+                if (options.defaults) {
+                    object.clientId = "";
+                    object.gain = 0;
+                    object.pan = 0;
                 }
+                if (message.clientId != null && message.hasOwnProperty("clientId"))
+                    object.clientId = message.clientId;
+                if (message.gain != null && message.hasOwnProperty("gain"))
+                    object.gain = options.json && !isFinite(message.gain) ? String(message.gain) : message.gain;
+                if (message.pan != null && message.hasOwnProperty("pan"))
+                    object.pan = options.json && !isFinite(message.pan) ? String(message.pan) : message.pan;
                 return object;
             };
 
             /**
-             * Converts this EntityUpdate to JSON.
+             * Converts this SpeakerSpatial to JSON.
              * @function toJSON
-             * @memberof graphwiz.core.EntityUpdate
+             * @memberof graphwiz.core.SpeakerSpatial
              * @instance
              * @returns {Object.<string,*>} JSON object
              */
-            EntityUpdate.prototype.toJSON = function toJSON() {
+            SpeakerSpatial.prototype.toJSON = function toJSON() {
                 return this.constructor.toObject(this, $protobuf.util.toJSONOptions);
             };
 
             /**
-             * Gets the default type url for EntityUpdate
+             * Gets the default type url for SpeakerSpatial
              * @function getTypeUrl
-             * @memberof graphwiz.core.EntityUpdate
+             * @memberof graphwiz.core.SpeakerSpatial
              * @static
              * @param {string} [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
              * @returns {string} The default type url
              */
-            EntityUpdate.getTypeUrl = function getTypeUrl(typeUrlPrefix) {
+            SpeakerSpatial.getTypeUrl = function getTypeUrl(typeUrlPrefix) {
                 if (typeUrlPrefix === undefined) {
                     typeUrlPrefix = "type.googleapis.com";
                 }
-                return typeUrlPrefix + "/graphwiz.core.EntityUpdate";
+                return typeUrlPrefix + "/graphwiz.core.SpeakerSpatial";
             };
 
-            return EntityUpdate;
+            return SpeakerSpatial;
         })();
 
-        core.EntityDespawn = (function() {
+        core.VoiceData = (function() {
 
             /**
-             * Properties of an EntityDespawn.
+             * Properties of a VoiceData.
              * @memberof graphwiz.core
-             * @interface IEntityDespawn
-             * @property {string|null} [entityId] EntityDespawn entityId
+             * @interface IVoiceData
+             * @property {string|null} [fromClientId] VoiceData fromClientId
+             * @property {Uint8Array|null} [audioData] VoiceData audioData
+             * @property {number|null} [sequenceNumber] VoiceData sequenceNumber
+             * @property {graphwiz.core.VoiceCodec|null} [codec] VoiceData codec
              */
 
             /**
-             * Constructs a new EntityDespawn.
+             * Constructs a new VoiceData.
              * @memberof graphwiz.core
-             * @classdesc Represents an EntityDespawn.
-             * @implements IEntityDespawn
+             * @classdesc Represents a VoiceData.
+             * @implements IVoiceData
              * @constructor
-             * @param {graphwiz.core.IEntityDespawn=} [properties] Properties to set
+             * @param {graphwiz.core.IVoiceData=} [properties] Properties to set
              */
-            function EntityDespawn(properties) {
+            function VoiceData(properties) {
                 if (properties)
                     for (let keys = Object.keys(properties), i = 0; i < keys.length; ++i)
                         if (properties[keys[i]] != null)
@@ -2461,77 +2637,119 @@ export const graphwiz = $root.graphwiz = (() => {
             }
 
             /**
-             * EntityDespawn entityId.
-             * @member {string} entityId
-             * @memberof graphwiz.core.EntityDespawn
+             * VoiceData fromClientId.
+             * @member {string} fromClientId
+             * @memberof graphwiz.core.VoiceData
              * @instance
              */
-            EntityDespawn.prototype.entityId = "";
+            VoiceData.prototype.fromClientId = "";
 
             /**
-             * Creates a new EntityDespawn instance using the specified properties.
+             * VoiceData audioData.
+             * @member {Uint8Array} audioData
+             * @memberof graphwiz.core.VoiceData
+             * @instance
+             */
+            VoiceData.prototype.audioData = $util.newBuffer([]);
+
+            /**
+             * VoiceData sequenceNumber.
+             * @member {number} sequenceNumber
+             * @memberof graphwiz.core.VoiceData
+             * @instance
+             */
+            VoiceData.prototype.sequenceNumber = 0;
+
+            /**
+             * VoiceData codec.
+             * @member {graphwiz.core.VoiceCodec} codec
+             * @memberof graphwiz.core.VoiceData
+             * @instance
+             */
+            VoiceData.prototype.codec = 0;
+
+            /**
+             * Creates a new VoiceData instance using the specified properties.
              * @function create
-             * @memberof graphwiz.core.EntityDespawn
+             * @memberof graphwiz.core.VoiceData
              * @static
-             * @param {graphwiz.core.IEntityDespawn=} [properties] Properties to set
-             * @returns {graphwiz.core.EntityDespawn} EntityDespawn instance
+             * @param {graphwiz.core.IVoiceData=} [properties] Properties to set
+             * @returns {graphwiz.core.VoiceData} VoiceData instance
              */
-            EntityDespawn.create = function create(properties) {
-                return new EntityDespawn(properties);
+            VoiceData.create = function create(properties) {
+                return new VoiceData(properties);
             };
 
             /**
-             * Encodes the specified EntityDespawn message. Does not implicitly {@link graphwiz.core.EntityDespawn.verify|verify} messages.
+             * Encodes the specified VoiceData message. Does not implicitly {@link graphwiz.core.VoiceData.verify|verify} messages.
              * @function encode
-             * @memberof graphwiz.core.EntityDespawn
+             * @memberof graphwiz.core.VoiceData
              * @static
-             * @param {graphwiz.core.IEntityDespawn} message EntityDespawn message or plain object to encode
+             * @param {graphwiz.core.IVoiceData} message VoiceData message or plain object to encode
              * @param {$protobuf.Writer} [writer] Writer to encode to
              * @returns {$protobuf.Writer} Writer
              */
-            EntityDespawn.encode = function encode(message, writer) {
+            VoiceData.encode = function encode(message, writer) {
                 if (!writer)
                     writer = $Writer.create();
-                if (message.entityId != null && Object.hasOwnProperty.call(message, "entityId"))
-                    writer.uint32(/* id 1, wireType 2 =*/10).string(message.entityId);
+                if (message.fromClientId != null && Object.hasOwnProperty.call(message, "fromClientId"))
+                    writer.uint32(/* id 1, wireType 2 =*/10).string(message.fromClientId);
+                if (message.audioData != null && Object.hasOwnProperty.call(message, "audioData"))
+                    writer.uint32(/* id 2, wireType 2 =*/18).bytes(message.audioData);
+                if (message.sequenceNumber != null && Object.hasOwnProperty.call(message, "sequenceNumber"))
+                    writer.uint32(/* id 3, wireType 0 =*/24).int32(message.sequenceNumber);
+                if (message.codec != null && Object.hasOwnProperty.call(message, "codec"))
+                    writer.uint32(/* id 4, wireType 0 =*/32).int32(message.codec);
                 return writer;
             };
 
             /**
-             * Encodes the specified EntityDespawn message, length delimited. Does not implicitly {@link graphwiz.core.EntityDespawn.verify|verify} messages.
+             * Encodes the specified VoiceData message, length delimited. Does not implicitly {@link graphwiz.core.VoiceData.verify|verify} messages.
              * @function encodeDelimited
-             * @memberof graphwiz.core.EntityDespawn
+             * @memberof graphwiz.core.VoiceData
              * @static
-             * @param {graphwiz.core.IEntityDespawn} message EntityDespawn message or plain object to encode
+             * @param {graphwiz.core.IVoiceData} message VoiceData message or plain object to encode
              * @param {$protobuf.Writer} [writer] Writer to encode to
              * @returns {$protobuf.Writer} Writer
              */
-            EntityDespawn.encodeDelimited = function encodeDelimited(message, writer) {
+            VoiceData.encodeDelimited = function encodeDelimited(message, writer) {
                 return this.encode(message, writer).ldelim();
             };
 
             /**
-             * Decodes an EntityDespawn message from the specified reader or buffer.
+             * Decodes a VoiceData message from the specified reader or buffer.
              * @function decode
-             * @memberof graphwiz.core.EntityDespawn
+             * @memberof graphwiz.core.VoiceData
              * @static
              * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
              * @param {number} [length] Message length if known beforehand
-             * @returns {graphwiz.core.EntityDespawn} EntityDespawn
+             * @returns {graphwiz.core.VoiceData} VoiceData
              * @throws {Error} If the payload is not a reader or valid buffer
              * @throws {$protobuf.util.ProtocolError} If required fields are missing
              */
-            EntityDespawn.decode = function decode(reader, length, error) {
+            VoiceData.decode = function decode(reader, length, error) {
                 if (!(reader instanceof $Reader))
                     reader = $Reader.create(reader);
-                let end = length === undefined ? reader.len : reader.pos + length, message = new $root.graphwiz.core.EntityDespawn();
+                let end = length === undefined ? reader.len : reader.pos + length, message = new $root.graphwiz.core.VoiceData();
                 while (reader.pos < end) {
                     let tag = reader.uint32();
                     if (tag === error)
                         break;
                     switch (tag >>> 3) {
                     case 1: {
-                            message.entityId = reader.string();
+                            message.fromClientId = reader.string();
+                            break;
+                        }
+                    case 2: {
+                            message.audioData = reader.bytes();
+                            break;
+                        }
+                    case 3: {
+                            message.sequenceNumber = reader.int32();
+                            break;
+                        }
+                    case 4: {
+                            message.codec = reader.int32();
                             break;
                         }
                     default:
@@ -2543,125 +2761,200 @@ export const graphwiz = $root.graphwiz = (() => {
             };
 
             /**
-             * Decodes an EntityDespawn message from the specified reader or buffer, length delimited.
+             * Decodes a VoiceData message from the specified reader or buffer, length delimited.
              * @function decodeDelimited
-             * @memberof graphwiz.core.EntityDespawn
+             * @memberof graphwiz.core.VoiceData
              * @static
              * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
-             * @returns {graphwiz.core.EntityDespawn} EntityDespawn
+             * @returns {graphwiz.core.VoiceData} VoiceData
              * @throws {Error} If the payload is not a reader or valid buffer
              * @throws {$protobuf.util.ProtocolError} If required fields are missing
              */
-            EntityDespawn.decodeDelimited = function decodeDelimited(reader) {
+            VoiceData.decodeDelimited = function decodeDelimited(reader) {
                 if (!(reader instanceof $Reader))
                     reader = new $Reader(reader);
                 return this.decode(reader, reader.uint32());
             };
 
             /**
-             * Verifies an EntityDespawn message.
+             * Verifies a VoiceData message.
              * @function verify
-             * @memberof graphwiz.core.EntityDespawn
+             * @memberof graphwiz.core.VoiceData
              * @static
              * @param {Object.<string,*>} message Plain object to verify
              * @returns {string|null} `null` if valid, otherwise the reason why it is not
              */
-            EntityDespawn.verify = function verify(message) {
+            VoiceData.verify = function verify(message) {
                 if (typeof message !== "object" || message === null)
                     return "object expected";
-                if (message.entityId != null && message.hasOwnProperty("entityId"))
-                    if (!$util.isString(message.entityId))
-                        return "entityId: string expected";
+                if (message.fromClientId != null && message.hasOwnProperty("fromClientId"))
+                    if (!$util.isString(message.fromClientId))
+                        return "fromClientId: string expected";
+                if (message.audioData != null && message.hasOwnProperty("audioData"))
+                    if (!(message.audioData && typeof message.audioData.length === "number" || $util.isString(message.audioData)))
+                        return "audioData: buffer expected";
+                if (message.sequenceNumber != null && message.hasOwnProperty("sequenceNumber"))
+                    if (!$util.isInteger(message.sequenceNumber))
+                        return "sequenceNumber: integer expected";
+                if (message.codec != null && message.hasOwnProperty("codec"))
+                    switch (message.codec) {
+                    default:
+                        return "codec: enum value expected";
+                    case 0:
+                    case 1:
+                    case 2:
+                        break;
+                    }
                 return null;
             };
 
             /**
-             * Creates an EntityDespawn message from a plain object. Also converts values to their respective internal types.
+             * Creates a VoiceData message from a plain object. Also converts values to their respective internal types.
              * @function fromObject
-             * @memberof graphwiz.core.EntityDespawn
+             * @memberof graphwiz.core.VoiceData
              * @static
              * @param {Object.<string,*>} object Plain object
-             * @returns {graphwiz.core.EntityDespawn} EntityDespawn
+             * @returns {graphwiz.core.VoiceData} VoiceData
              */
-            EntityDespawn.fromObject = function fromObject(object) {
-                if (object instanceof $root.graphwiz.core.EntityDespawn)
+            VoiceData.fromObject = function fromObject(object) {
+                if (object instanceof $root.graphwiz.core.VoiceData)
                     return object;
-                let message = new $root.graphwiz.core.EntityDespawn();
-                if (object.entityId != null)
-                    message.entityId = String(object.entityId);
+                let message = new $root.graphwiz.core.VoiceData();
+                if (object.fromClientId != null)
+                    message.fromClientId = String(object.fromClientId);
+                if (object.audioData != null)
+                    if (typeof object.audioData === "string")
+                        $util.base64.decode(object.audioData, message.audioData = $util.newBuffer($util.base64.length(object.audioData)), 0);
+                    else if (object.audioData.length >= 0)
+                        message.audioData = object.audioData;
+                if (object.sequenceNumber != null)
+                    message.sequenceNumber = object.sequenceNumber | 0;
+                switch (object.codec) {
+                default:
+                    if (typeof object.codec === "number") {
+                        message.codec = object.codec;
+                        break;
+                    }
+                    break;
+                case "OPUS":
+                case 0:
+                    message.codec = 0;
+                    break;
+                case "PCMU":
+                case 1:
+                    message.codec = 1;
+                    break;
+                case "PCMA":
+                case 2:
+                    message.codec = 2;
+                    break;
+                }
                 return message;
             };
 
             /**
-             * Creates a plain object from an EntityDespawn message. Also converts values to other types if specified.
+             * Creates a plain object from a VoiceData message. Also converts values to other types if specified.
              * @function toObject
-             * @memberof graphwiz.core.EntityDespawn
+             * @memberof graphwiz.core.VoiceData
              * @static
-             * @param {graphwiz.core.EntityDespawn} message EntityDespawn
+             * @param {graphwiz.core.VoiceData} message VoiceData
              * @param {$protobuf.IConversionOptions} [options] Conversion options
              * @returns {Object.<string,*>} Plain object
              */
-            EntityDespawn.toObject = function toObject(message, options) {
+            VoiceData.toObject = function toObject(message, options) {
                 if (!options)
                     options = {};
                 let object = {};
-                if (options.defaults)
-                    object.entityId = "";
-                if (message.entityId != null && message.hasOwnProperty("entityId"))
-                    object.entityId = message.entityId;
+                if (options.defaults) {
+                    object.fromClientId = "";
+                    if (options.bytes === String)
+                        object.audioData = "";
+                    else {
+                        object.audioData = [];
+                        if (options.bytes !== Array)
+                            object.audioData = $util.newBuffer(object.audioData);
+                    }
+                    object.sequenceNumber = 0;
+                    object.codec = options.enums === String ? "OPUS" : 0;
+                }
+                if (message.fromClientId != null && message.hasOwnProperty("fromClientId"))
+                    object.fromClientId = message.fromClientId;
+                if (message.audioData != null && message.hasOwnProperty("audioData"))
+                    object.audioData = options.bytes === String ? $util.base64.encode(message.audioData, 0, message.audioData.length) : options.bytes === Array ? Array.prototype.slice.call(message.audioData) : message.audioData;
+                if (message.sequenceNumber != null && message.hasOwnProperty("sequenceNumber"))
+                    object.sequenceNumber = message.sequenceNumber;
+                if (message.codec != null && message.hasOwnProperty("codec"))
+                    object.codec = options.enums === String ? $root.graphwiz.core.VoiceCodec[message.codec] === undefined ? message.codec : $root.graphwiz.core.VoiceCodec[message.codec] : message.codec;
                 return object;
             };
 
             /**
-             * Converts this EntityDespawn to JSON.
+             * Converts this VoiceData to JSON.
              * @function toJSON
-             * @memberof graphwiz.core.EntityDespawn
+             * @memberof graphwiz.core.VoiceData
              * @instance
              * @returns {Object.<string,*>} JSON object
              */
-            EntityDespawn.prototype.toJSON = function toJSON() {
+            VoiceData.prototype.toJSON = function toJSON() {
                 return this.constructor.toObject(this, $protobuf.util.toJSONOptions);
             };
 
             /**
-             * Gets the default type url for EntityDespawn
+             * Gets the default type url for VoiceData
              * @function getTypeUrl
-             * @memberof graphwiz.core.EntityDespawn
+             * @memberof graphwiz.core.VoiceData
              * @static
              * @param {string} [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
              * @returns {string} The default type url
              */
-            EntityDespawn.getTypeUrl = function getTypeUrl(typeUrlPrefix) {
+            VoiceData.getTypeUrl = function getTypeUrl(typeUrlPrefix) {
                 if (typeUrlPrefix === undefined) {
                     typeUrlPrefix = "type.googleapis.com";
                 }
-                return typeUrlPrefix + "/graphwiz.core.EntityDespawn";
+                return typeUrlPrefix + "/graphwiz.core.VoiceData";
             };
 
-            return EntityDespawn;
+            return VoiceData;
         })();
 
-        core.ChatMessage = (function() {
+        /**
+         * VoiceCodec enum.
+         * @name graphwiz.core.VoiceCodec
+         * @enum {number}
+         * @property {number} OPUS=0 OPUS value
+         * @property {number} PCMU=1 PCMU value
+         * @property {number} PCMA=2 PCMA value
+         */
+        core.VoiceCodec = (function() {
+            const valuesById = {}, values = Object.create(valuesById);
+            values[valuesById[0] = "OPUS"] = 0;
+            values[valuesById[1] = "PCMU"] = 1;
+            values[valuesById[2] = "PCMA"] = 2;
+            return values;
+        })();
+
+        core.EntitySpawn = (function() {
 
             /**
-             * Properties of a ChatMessage.
+             * Properties of an EntitySpawn.
              * @memberof graphwiz.core
-             * @interface IChatMessage
-             * @property {string|null} [fromClientId] ChatMessage fromClientId
-             * @property {string|null} [message] ChatMessage message
-             * @property {number|Long|null} [timestamp] ChatMessage timestamp
-             * @property {graphwiz.core.ChatMessageType|null} [type] ChatMessage type
+             * @interface IEntitySpawn
+             * @property {string|null} [entityId] EntitySpawn entityId
+             * @property {string|null} [templateId] EntitySpawn templateId
+             * @property {string|null} [ownerId] EntitySpawn ownerId
+             * @property {Object.<string,string>|null} [components] EntitySpawn components
              */
 
             /**
-             * Constructs a new ChatMessage.
+             * Constructs a new EntitySpawn.
              * @memberof graphwiz.core
-             * @classdesc Represents a ChatMessage.
-             * @implements IChatMessage
+             * @classdesc Represents an EntitySpawn.
+             * @implements IEntitySpawn
              * @constructor
-             * @param {graphwiz.core.IChatMessage=} [properties] Properties to set
+             * @param {graphwiz.core.IEntitySpawn=} [properties] Properties to set
              */
-            function ChatMessage(properties) {
+            function EntitySpawn(properties) {
+                this.components = {};
                 if (properties)
                     for (let keys = Object.keys(properties), i = 0; i < keys.length; ++i)
                         if (properties[keys[i]] != null)
@@ -2669,120 +2962,2186 @@ export const graphwiz = $root.graphwiz = (() => {
             }
 
             /**
-             * ChatMessage fromClientId.
-             * @member {string} fromClientId
-             * @memberof graphwiz.core.ChatMessage
+             * EntitySpawn entityId.
+             * @member {string} entityId
+             * @memberof graphwiz.core.EntitySpawn
              * @instance
              */
-            ChatMessage.prototype.fromClientId = "";
+            EntitySpawn.prototype.entityId = "";
 
             /**
-             * ChatMessage message.
-             * @member {string} message
-             * @memberof graphwiz.core.ChatMessage
+             * EntitySpawn templateId.
+             * @member {string} templateId
+             * @memberof graphwiz.core.EntitySpawn
              * @instance
              */
-            ChatMessage.prototype.message = "";
+            EntitySpawn.prototype.templateId = "";
 
             /**
-             * ChatMessage timestamp.
-             * @member {number|Long} timestamp
-             * @memberof graphwiz.core.ChatMessage
+             * EntitySpawn ownerId.
+             * @member {string} ownerId
+             * @memberof graphwiz.core.EntitySpawn
              * @instance
              */
-            ChatMessage.prototype.timestamp = $util.Long ? $util.Long.fromBits(0,0,false) : 0;
+            EntitySpawn.prototype.ownerId = "";
 
             /**
-             * ChatMessage type.
-             * @member {graphwiz.core.ChatMessageType} type
-             * @memberof graphwiz.core.ChatMessage
+             * EntitySpawn components.
+             * @member {Object.<string,string>} components
+             * @memberof graphwiz.core.EntitySpawn
              * @instance
              */
-            ChatMessage.prototype.type = 0;
+            EntitySpawn.prototype.components = $util.emptyObject;
 
             /**
-             * Creates a new ChatMessage instance using the specified properties.
+             * Creates a new EntitySpawn instance using the specified properties.
              * @function create
-             * @memberof graphwiz.core.ChatMessage
+             * @memberof graphwiz.core.EntitySpawn
              * @static
-             * @param {graphwiz.core.IChatMessage=} [properties] Properties to set
-             * @returns {graphwiz.core.ChatMessage} ChatMessage instance
+             * @param {graphwiz.core.IEntitySpawn=} [properties] Properties to set
+             * @returns {graphwiz.core.EntitySpawn} EntitySpawn instance
              */
-            ChatMessage.create = function create(properties) {
-                return new ChatMessage(properties);
+            EntitySpawn.create = function create(properties) {
+                return new EntitySpawn(properties);
             };
 
             /**
-             * Encodes the specified ChatMessage message. Does not implicitly {@link graphwiz.core.ChatMessage.verify|verify} messages.
+             * Encodes the specified EntitySpawn message. Does not implicitly {@link graphwiz.core.EntitySpawn.verify|verify} messages.
              * @function encode
-             * @memberof graphwiz.core.ChatMessage
+             * @memberof graphwiz.core.EntitySpawn
              * @static
-             * @param {graphwiz.core.IChatMessage} message ChatMessage message or plain object to encode
+             * @param {graphwiz.core.IEntitySpawn} message EntitySpawn message or plain object to encode
              * @param {$protobuf.Writer} [writer] Writer to encode to
              * @returns {$protobuf.Writer} Writer
              */
-            ChatMessage.encode = function encode(message, writer) {
+            EntitySpawn.encode = function encode(message, writer) {
                 if (!writer)
                     writer = $Writer.create();
-                if (message.fromClientId != null && Object.hasOwnProperty.call(message, "fromClientId"))
-                    writer.uint32(/* id 1, wireType 2 =*/10).string(message.fromClientId);
-                if (message.message != null && Object.hasOwnProperty.call(message, "message"))
-                    writer.uint32(/* id 2, wireType 2 =*/18).string(message.message);
-                if (message.timestamp != null && Object.hasOwnProperty.call(message, "timestamp"))
-                    writer.uint32(/* id 3, wireType 0 =*/24).int64(message.timestamp);
-                if (message.type != null && Object.hasOwnProperty.call(message, "type"))
-                    writer.uint32(/* id 4, wireType 0 =*/32).int32(message.type);
+                if (message.entityId != null && Object.hasOwnProperty.call(message, "entityId"))
+                    writer.uint32(/* id 1, wireType 2 =*/10).string(message.entityId);
+                if (message.templateId != null && Object.hasOwnProperty.call(message, "templateId"))
+                    writer.uint32(/* id 2, wireType 2 =*/18).string(message.templateId);
+                if (message.ownerId != null && Object.hasOwnProperty.call(message, "ownerId"))
+                    writer.uint32(/* id 3, wireType 2 =*/26).string(message.ownerId);
+                if (message.components != null && Object.hasOwnProperty.call(message, "components"))
+                    for (let keys = Object.keys(message.components), i = 0; i < keys.length; ++i)
+                        writer.uint32(/* id 4, wireType 2 =*/34).fork().uint32(/* id 1, wireType 2 =*/10).string(keys[i]).uint32(/* id 2, wireType 2 =*/18).string(message.components[keys[i]]).ldelim();
                 return writer;
             };
 
             /**
-             * Encodes the specified ChatMessage message, length delimited. Does not implicitly {@link graphwiz.core.ChatMessage.verify|verify} messages.
+             * Encodes the specified EntitySpawn message, length delimited. Does not implicitly {@link graphwiz.core.EntitySpawn.verify|verify} messages.
              * @function encodeDelimited
-             * @memberof graphwiz.core.ChatMessage
+             * @memberof graphwiz.core.EntitySpawn
              * @static
-             * @param {graphwiz.core.IChatMessage} message ChatMessage message or plain object to encode
+             * @param {graphwiz.core.IEntitySpawn} message EntitySpawn message or plain object to encode
              * @param {$protobuf.Writer} [writer] Writer to encode to
              * @returns {$protobuf.Writer} Writer
              */
-            ChatMessage.encodeDelimited = function encodeDelimited(message, writer) {
+            EntitySpawn.encodeDelimited = function encodeDelimited(message, writer) {
                 return this.encode(message, writer).ldelim();
             };
 
             /**
-             * Decodes a ChatMessage message from the specified reader or buffer.
+             * Decodes an EntitySpawn message from the specified reader or buffer.
              * @function decode
-             * @memberof graphwiz.core.ChatMessage
+             * @memberof graphwiz.core.EntitySpawn
              * @static
              * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
              * @param {number} [length] Message length if known beforehand
-             * @returns {graphwiz.core.ChatMessage} ChatMessage
+             * @returns {graphwiz.core.EntitySpawn} EntitySpawn
              * @throws {Error} If the payload is not a reader or valid buffer
              * @throws {$protobuf.util.ProtocolError} If required fields are missing
              */
-            ChatMessage.decode = function decode(reader, length, error) {
+            EntitySpawn.decode = function decode(reader, length, error) {
                 if (!(reader instanceof $Reader))
                     reader = $Reader.create(reader);
-                let end = length === undefined ? reader.len : reader.pos + length, message = new $root.graphwiz.core.ChatMessage();
+                let end = length === undefined ? reader.len : reader.pos + length, message = new $root.graphwiz.core.EntitySpawn(), key, value;
                 while (reader.pos < end) {
                     let tag = reader.uint32();
                     if (tag === error)
                         break;
                     switch (tag >>> 3) {
                     case 1: {
-                            message.fromClientId = reader.string();
+                            message.entityId = reader.string();
                             break;
                         }
                     case 2: {
-                            message.message = reader.string();
+                            message.templateId = reader.string();
                             break;
                         }
                     case 3: {
-                            message.timestamp = reader.int64();
+                            message.ownerId = reader.string();
                             break;
                         }
                     case 4: {
-                            message.type = reader.int32();
-                            break;
+                            if (message.components === $util.emptyObject)
+                                message.components = {};
+                            let end2 = reader.uint32() + reader.pos;
+                            key = "";
+                            value = "";
+                            while (reader.pos < end2) {
+                                let tag2 = reader.uint32();
+                                switch (tag2 >>> 3) {
+                                case 1:
+                                    key = reader.string();
+                                    break;
+                                case 2:
+                                    value = reader.string();
+                                    break;
+                                default:
+                                    reader.skipType(tag2 & 7);
+                                    break;
+                                }
+                            }
+                            message.components[key] = value;
+                            break;
+                        }
+                    default:
+                        reader.skipType(tag & 7);
+                        break;
+                    }
+                }
+                return message;
+            };
+
+            /**
+             * Decodes an EntitySpawn message from the specified reader or buffer, length delimited.
+             * @function decodeDelimited
+             * @memberof graphwiz.core.EntitySpawn
+             * @static
+             * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
+             * @returns {graphwiz.core.EntitySpawn} EntitySpawn
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            EntitySpawn.decodeDelimited = function decodeDelimited(reader) {
+                if (!(reader instanceof $Reader))
+                    reader = new $Reader(reader);
+                return this.decode(reader, reader.uint32());
+            };
+
+            /**
+             * Verifies an EntitySpawn message.
+             * @function verify
+             * @memberof graphwiz.core.EntitySpawn
+             * @static
+             * @param {Object.<string,*>} message Plain object to verify
+             * @returns {string|null} `null` if valid, otherwise the reason why it is not
+             */
+            EntitySpawn.verify = function verify(message) {
+                if (typeof message !== "object" || message === null)
+                    return "object expected";
+                if (message.entityId != null && message.hasOwnProperty("entityId"))
+                    if (!$util.isString(message.entityId))
+                        return "entityId: string expected";
+                if (message.templateId != null && message.hasOwnProperty("templateId"))
+                    if (!$util.isString(message.templateId))
+                        return "templateId: string expected";
+                if (message.ownerId != null && message.hasOwnProperty("ownerId"))
+                    if (!$util.isString(message.ownerId))
+                        return "ownerId: string expected";
+                if (message.components != null && message.hasOwnProperty("components")) {
+                    if (!$util.isObject(message.components))
+                        return "components: object expected";
+                    let key = Object.keys(message.components);
+                    for (let i = 0; i < key.length; ++i)
+                        if (!$util.isString(message.components[key[i]]))
+                            return "components: string{k:string} expected";
+                }
+                return null;
+            };
+
+            /**
+             * Creates an EntitySpawn message from a plain object. Also converts values to their respective internal types.
+             * @function fromObject
+             * @memberof graphwiz.core.EntitySpawn
+             * @static
+             * @param {Object.<string,*>} object Plain object
+             * @returns {graphwiz.core.EntitySpawn} EntitySpawn
+             */
+            EntitySpawn.fromObject = function fromObject(object) {
+                if (object instanceof $root.graphwiz.core.EntitySpawn)
+                    return object;
+                let message = new $root.graphwiz.core.EntitySpawn();
+                if (object.entityId != null)
+                    message.entityId = String(object.entityId);
+                if (object.templateId != null)
+                    message.templateId = String(object.templateId);
+                if (object.ownerId != null)
+                    message.ownerId = String(object.ownerId);
+                if (object.components) {
+                    if (typeof object.components !== "object")
+                        throw TypeError(".graphwiz.core.EntitySpawn.components: object expected");
+                    message.components = {};
+                    for (let keys = Object.keys(object.components), i = 0; i < keys.length; ++i)
+                        message.components[keys[i]] = String(object.components[keys[i]]);
+                }
+                return message;
+            };
+
+            /**
+             * Creates a plain object from an EntitySpawn message. Also converts values to other types if specified.
+             * @function toObject
+             * @memberof graphwiz.core.EntitySpawn
+             * @static
+             * @param {graphwiz.core.EntitySpawn} message EntitySpawn
+             * @param {$protobuf.IConversionOptions} [options] Conversion options
+             * @returns {Object.<string,*>} Plain object
+             */
+            EntitySpawn.toObject = function toObject(message, options) {
+                if (!options)
+                    options = {};
+                let object = {};
+                if (options.objects || options.defaults)
+                    object.components = {};
+                if (options.defaults) {
+                    object.entityId = "";
+                    object.templateId = "";
+                    object.ownerId = "";
+                }
+                if (message.entityId != null && message.hasOwnProperty("entityId"))
+                    object.entityId = message.entityId;
+                if (message.templateId != null && message.hasOwnProperty("templateId"))
+                    object.templateId = message.templateId;
+                if (message.ownerId != null && message.hasOwnProperty("ownerId"))
+                    object.ownerId = message.ownerId;
+                let keys2;
+                if (message.components && (keys2 = Object.keys(message.components)).length) {
+                    object.components = {};
+                    for (let j = 0; j < keys2.length; ++j)
+                        object.components[keys2[j]] = message.components[keys2[j]];
+                }
+                return object;
+            };
+
+            /**
+             * Converts this EntitySpawn to JSON.
+             * @function toJSON
+             * @memberof graphwiz.core.EntitySpawn
+             * @instance
+             * @returns {Object.<string,*>} JSON object
+             */
+            EntitySpawn.prototype.toJSON = function toJSON() {
+                return this.constructor.toObject(this, $protobuf.util.toJSONOptions);
+            };
+
+            /**
+             * Gets the default type url for EntitySpawn
+             * @function getTypeUrl
+             * @memberof graphwiz.core.EntitySpawn
+             * @static
+             * @param {string} [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
+             * @returns {string} The default type url
+             */
+            EntitySpawn.getTypeUrl = function getTypeUrl(typeUrlPrefix) {
+                if (typeUrlPrefix === undefined) {
+                    typeUrlPrefix = "type.googleapis.com";
+                }
+                return typeUrlPrefix + "/graphwiz.core.EntitySpawn";
+            };
+
+            return EntitySpawn;
+        })();
+
+        core.EntityUpdate = (function() {
+
+            /**
+             * Properties of an EntityUpdate.
+             * @memberof graphwiz.core
+             * @interface IEntityUpdate
+             * @property {string|null} [entityId] EntityUpdate entityId
+             * @property {Object.<string,Uint8Array>|null} [components] EntityUpdate components
+             */
+
+            /**
+             * Constructs a new EntityUpdate.
+             * @memberof graphwiz.core
+             * @classdesc Represents an EntityUpdate.
+             * @implements IEntityUpdate
+             * @constructor
+             * @param {graphwiz.core.IEntityUpdate=} [properties] Properties to set
+             */
+            function EntityUpdate(properties) {
+                this.components = {};
+                if (properties)
+                    for (let keys = Object.keys(properties), i = 0; i < keys.length; ++i)
+                        if (properties[keys[i]] != null)
+                            this[keys[i]] = properties[keys[i]];
+            }
+
+            /**
+             * EntityUpdate entityId.
+             * @member {string} entityId
+             * @memberof graphwiz.core.EntityUpdate
+             * @instance
+             */
+            EntityUpdate.prototype.entityId = "";
+
+            /**
+             * EntityUpdate components.
+             * @member {Object.<string,Uint8Array>} components
+             * @memberof graphwiz.core.EntityUpdate
+             * @instance
+             */
+            EntityUpdate.prototype.components = $util.emptyObject;
+
+            /**
+             * Creates a new EntityUpdate instance using the specified properties.
+             * @function create
+             * @memberof graphwiz.core.EntityUpdate
+             * @static
+             * @param {graphwiz.core.IEntityUpdate=} [properties] Properties to set
+             * @returns {graphwiz.core.EntityUpdate} EntityUpdate instance
+             */
+            EntityUpdate.create = function create(properties) {
+                return new EntityUpdate(properties);
+            };
+
+            /**
+             * Encodes the specified EntityUpdate message. Does not implicitly {@link graphwiz.core.EntityUpdate.verify|verify} messages.
+             * @function encode
+             * @memberof graphwiz.core.EntityUpdate
+             * @static
+             * @param {graphwiz.core.IEntityUpdate} message EntityUpdate message or plain object to encode
+             * @param {$protobuf.Writer} [writer] Writer to encode to
+             * @returns {$protobuf.Writer} Writer
+             */
+            EntityUpdate.encode = function encode(message, writer) {
+                if (!writer)
+                    writer = $Writer.create();
+                if (message.entityId != null && Object.hasOwnProperty.call(message, "entityId"))
+                    writer.uint32(/* id 1, wireType 2 =*/10).string(message.entityId);
+                if (message.components != null && Object.hasOwnProperty.call(message, "components"))
+                    for (let keys = Object.keys(message.components), i = 0; i < keys.length; ++i)
+                        writer.uint32(/* id 2, wireType 2 =*/18).fork().uint32(/* id 1, wireType 2 =*/10).string(keys[i]).uint32(/* id 2, wireType 2 =*/18).bytes(message.components[keys[i]]).ldelim();
+                return writer;
+            };
+
+            /**
+             * Encodes the specified EntityUpdate message, length delimited. Does not implicitly {@link graphwiz.core.EntityUpdate.verify|verify} messages.
+             * @function encodeDelimited
+             * @memberof graphwiz.core.EntityUpdate
+             * @static
+             * @param {graphwiz.core.IEntityUpdate} message EntityUpdate message or plain object to encode
+             * @param {$protobuf.Writer} [writer] Writer to encode to
+             * @returns {$protobuf.Writer} Writer
+             */
+            EntityUpdate.encodeDelimited = function encodeDelimited(message, writer) {
+                return this.encode(message, writer).ldelim();
+            };
+
+            /**
+             * Decodes an EntityUpdate message from the specified reader or buffer.
+             * @function decode
+             * @memberof graphwiz.core.EntityUpdate
+             * @static
+             * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
+             * @param {number} [length] Message length if known beforehand
+             * @returns {graphwiz.core.EntityUpdate} EntityUpdate
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            EntityUpdate.decode = function decode(reader, length, error) {
+                if (!(reader instanceof $Reader))
+                    reader = $Reader.create(reader);
+                let end = length === undefined ? reader.len : reader.pos + length, message = new $root.graphwiz.core.EntityUpdate(), key, value;
+                while (reader.pos < end) {
+                    let tag = reader.uint32();
+                    if (tag === error)
+                        break;
+                    switch (tag >>> 3) {
+                    case 1: {
+                            message.entityId = reader.string();
+                            break;
+                        }
+                    case 2: {
+                            if (message.components === $util.emptyObject)
+                                message.components = {};
+                            let end2 = reader.uint32() + reader.pos;
+                            key = "";
+                            value = [];
+                            while (reader.pos < end2) {
+                                let tag2 = reader.uint32();
+                                switch (tag2 >>> 3) {
+                                case 1:
+                                    key = reader.string();
+                                    break;
+                                case 2:
+                                    value = reader.bytes();
+                                    break;
+                                default:
+                                    reader.skipType(tag2 & 7);
+                                    break;
+                                }
+                            }
+                            message.components[key] = value;
+                            break;
+                        }
+                    default:
+                        reader.skipType(tag & 7);
+                        break;
+                    }
+                }
+                return message;
+            };
+
+            /**
+             * Decodes an EntityUpdate message from the specified reader or buffer, length delimited.
+             * @function decodeDelimited
+             * @memberof graphwiz.core.EntityUpdate
+             * @static
+             * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
+             * @returns {graphwiz.core.EntityUpdate} EntityUpdate
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            EntityUpdate.decodeDelimited = function decodeDelimited(reader) {
+                if (!(reader instanceof $Reader))
+                    reader = new $Reader(reader);
+                return this.decode(reader, reader.uint32());
+            };
+
+            /**
+             * Verifies an EntityUpdate message.
+             * @function verify
+             * @memberof graphwiz.core.EntityUpdate
+             * @static
+             * @param {Object.<string,*>} message Plain object to verify
+             * @returns {string|null} `null` if valid, otherwise the reason why it is not
+             */
+            EntityUpdate.verify = function verify(message) {
+                if (typeof message !== "object" || message === null)
+                    return "object expected";
+                if (message.entityId != null && message.hasOwnProperty("entityId"))
+                    if (!$util.isString(message.entityId))
+                        return "entityId: string expected";
+                if (message.components != null && message.hasOwnProperty("components")) {
+                    if (!$util.isObject(message.components))
+                        return "components: object expected";
+                    let key = Object.keys(message.components);
+                    for (let i = 0; i < key.length; ++i)
+                        if (!(message.components[key[i]] && typeof message.components[key[i]].length === "number" || $util.isString(message.components[key[i]])))
+                            return "components: buffer{k:string} expected";
+                }
+                return null;
+            };
+
+            /**
+             * Creates an EntityUpdate message from a plain object. Also converts values to their respective internal types.
+             * @function fromObject
+             * @memberof graphwiz.core.EntityUpdate
+             * @static
+             * @param {Object.<string,*>} object Plain object
+             * @returns {graphwiz.core.EntityUpdate} EntityUpdate
+             */
+            EntityUpdate.fromObject = function fromObject(object) {
+                if (object instanceof $root.graphwiz.core.EntityUpdate)
+                    return object;
+                let message = new $root.graphwiz.core.EntityUpdate();
+                if (object.entityId != null)
+                    message.entityId = String(object.entityId);
+                if (object.components) {
+                    if (typeof object.components !== "object")
+                        throw TypeError(".graphwiz.core.EntityUpdate.components: object expected");
+                    message.components = {};
+                    for (let keys = Object.keys(object.components), i = 0; i < keys.length; ++i)
+                        if (typeof object.components[keys[i]] === "string")
+                            $util.base64.decode(object.components[keys[i]], message.components[keys[i]] = $util.newBuffer($util.base64.length(object.components[keys[i]])), 0);
+                        else if (object.components[keys[i]].length >= 0)
+                            message.components[keys[i]] = object.components[keys[i]];
+                }
+                return message;
+            };
+
+            /**
+             * Creates a plain object from an EntityUpdate message. Also converts values to other types if specified.
+             * @function toObject
+             * @memberof graphwiz.core.EntityUpdate
+             * @static
+             * @param {graphwiz.core.EntityUpdate} message EntityUpdate
+             * @param {$protobuf.IConversionOptions} [options] Conversion options
+             * @returns {Object.<string,*>} Plain object
+             */
+            EntityUpdate.toObject = function toObject(message, options) {
+                if (!options)
+                    options = {};
+                let object = {};
+                if (options.objects || options.defaults)
+                    object.components = {};
+                if (options.defaults)
+                    object.entityId = "";
+                if (message.entityId != null && message.hasOwnProperty("entityId"))
+                    object.entityId = message.entityId;
+                let keys2;
+                if (message.components && (keys2 = Object.keys(message.components)).length) {
+                    object.components = {};
+                    for (let j = 0; j < keys2.length; ++j)
+                        object.components[keys2[j]] = options.bytes === String ? $util.base64.encode(message.components[keys2[j]], 0, message.components[keys2[j]].length) : options.bytes === Array ? Array.prototype.slice.call(message.components[keys2[j]]) : message.components[keys2[j]];
+                }
+                return object;
+            };
+
+            /**
+             * Converts this EntityUpdate to JSON.
+             * @function toJSON
+             * @memberof graphwiz.core.EntityUpdate
+             * @instance
+             * @returns {Object.<string,*>} JSON object
+             */
+            EntityUpdate.prototype.toJSON = function toJSON() {
+                return this.constructor.toObject(this, $protobuf.util.toJSONOptions);
+            };
+
+            /**
+             * Gets the default type url for EntityUpdate
+             * @function getTypeUrl
+             * @memberof graphwiz.core.EntityUpdate
+             * @static
+             * @param {string} [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
+             * @returns {string} The default type url
+             */
+            EntityUpdate.getTypeUrl = function getTypeUrl(typeUrlPrefix) {
+                if (typeUrlPrefix === undefined) {
+                    typeUrlPrefix = "type.googleapis.com";
+                }
+                return typeUrlPrefix + "/graphwiz.core.EntityUpdate";
+            };
+
+            return EntityUpdate;
+        })();
+
+        core.EntityDespawn = (function() {
+
+            /**
+             * Properties of an EntityDespawn.
+             * @memberof graphwiz.core
+             * @interface IEntityDespawn
+             * @property {string|null} [entityId] EntityDespawn entityId
+             */
+
+            /**
+             * Constructs a new EntityDespawn.
+             * @memberof graphwiz.core
+             * @classdesc Represents an EntityDespawn.
+             * @implements IEntityDespawn
+             * @constructor
+             * @param {graphwiz.core.IEntityDespawn=} [properties] Properties to set
+             */
+            function EntityDespawn(properties) {
+                if (properties)
+                    for (let keys = Object.keys(properties), i = 0; i < keys.length; ++i)
+                        if (properties[keys[i]] != null)
+                            this[keys[i]] = properties[keys[i]];
+            }
+
+            /**
+             * EntityDespawn entityId.
+             * @member {string} entityId
+             * @memberof graphwiz.core.EntityDespawn
+             * @instance
+             */
+            EntityDespawn.prototype.entityId = "";
+
+            /**
+             * Creates a new EntityDespawn instance using the specified properties.
+             * @function create
+             * @memberof graphwiz.core.EntityDespawn
+             * @static
+             * @param {graphwiz.core.IEntityDespawn=} [properties] Properties to set
+             * @returns {graphwiz.core.EntityDespawn} EntityDespawn instance
+             */
+            EntityDespawn.create = function create(properties) {
+                return new EntityDespawn(properties);
+            };
+
+            /**
+             * Encodes the specified EntityDespawn message. Does not implicitly {@link graphwiz.core.EntityDespawn.verify|verify} messages.
+             * @function encode
+             * @memberof graphwiz.core.EntityDespawn
+             * @static
+             * @param {graphwiz.core.IEntityDespawn} message EntityDespawn message or plain object to encode
+             * @param {$protobuf.Writer} [writer] Writer to encode to
+             * @returns {$protobuf.Writer} Writer
+             */
+            EntityDespawn.encode = function encode(message, writer) {
+                if (!writer)
+                    writer = $Writer.create();
+                if (message.entityId != null && Object.hasOwnProperty.call(message, "entityId"))
+                    writer.uint32(/* id 1, wireType 2 =*/10).string(message.entityId);
+                return writer;
+            };
+
+            /**
+             * Encodes the specified EntityDespawn message, length delimited. Does not implicitly {@link graphwiz.core.EntityDespawn.verify|verify} messages.
+             * @function encodeDelimited
+             * @memberof graphwiz.core.EntityDespawn
+             * @static
+             * @param {graphwiz.core.IEntityDespawn} message EntityDespawn message or plain object to encode
+             * @param {$protobuf.Writer} [writer] Writer to encode to
+             * @returns {$protobuf.Writer} Writer
+             */
+            EntityDespawn.encodeDelimited = function encodeDelimited(message, writer) {
+                return this.encode(message, writer).ldelim();
+            };
+
+            /**
+             * Decodes an EntityDespawn message from the specified reader or buffer.
+             * @function decode
+             * @memberof graphwiz.core.EntityDespawn
+             * @static
+             * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
+             * @param {number} [length] Message length if known beforehand
+             * @returns {graphwiz.core.EntityDespawn} EntityDespawn
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            EntityDespawn.decode = function decode(reader, length, error) {
+                if (!(reader instanceof $Reader))
+                    reader = $Reader.create(reader);
+                let end = length === undefined ? reader.len : reader.pos + length, message = new $root.graphwiz.core.EntityDespawn();
+                while (reader.pos < end) {
+                    let tag = reader.uint32();
+                    if (tag === error)
+                        break;
+                    switch (tag >>> 3) {
+                    case 1: {
+                            message.entityId = reader.string();
+                            break;
+                        }
+                    default:
+                        reader.skipType(tag & 7);
+                        break;
+                    }
+                }
+                return message;
+            };
+
+            /**
+             * Decodes an EntityDespawn message from the specified reader or buffer, length delimited.
+             * @function decodeDelimited
+             * @memberof graphwiz.core.EntityDespawn
+             * @static
+             * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
+             * @returns {graphwiz.core.EntityDespawn} EntityDespawn
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            EntityDespawn.decodeDelimited = function decodeDelimited(reader) {
+                if (!(reader instanceof $Reader))
+                    reader = new $Reader(reader);
+                return this.decode(reader, reader.uint32());
+            };
+
+            /**
+             * Verifies an EntityDespawn message.
+             * @function verify
+             * @memberof graphwiz.core.EntityDespawn
+             * @static
+             * @param {Object.<string,*>} message Plain object to verify
+             * @returns {string|null} `null` if valid, otherwise the reason why it is not
+             */
+            EntityDespawn.verify = function verify(message) {
+                if (typeof message !== "object" || message === null)
+                    return "object expected";
+                if (message.entityId != null && message.hasOwnProperty("entityId"))
+                    if (!$util.isString(message.entityId))
+                        return "entityId: string expected";
+                return null;
+            };
+
+            /**
+             * Creates an EntityDespawn message from a plain object. Also converts values to their respective internal types.
+             * @function fromObject
+             * @memberof graphwiz.core.EntityDespawn
+             * @static
+             * @param {Object.<string,*>} object Plain object
+             * @returns {graphwiz.core.EntityDespawn} EntityDespawn
+             */
+            EntityDespawn.fromObject = function fromObject(object) {
+                if (object instanceof $root.graphwiz.core.EntityDespawn)
+                    return object;
+                let message = new $root.graphwiz.core.EntityDespawn();
+                if (object.entityId != null)
+                    message.entityId = String(object.entityId);
+                return message;
+            };
+
+            /**
+             * Creates a plain object from an EntityDespawn message. Also converts values to other types if specified.
+             * @function toObject
+             * @memberof graphwiz.core.EntityDespawn
+             * @static
+             * @param {graphwiz.core.EntityDespawn} message EntityDespawn
+             * @param {$protobuf.IConversionOptions} [options] Conversion options
+             * @returns {Object.<string,*>} Plain object
+             */
+            EntityDespawn.toObject = function toObject(message, options) {
+                if (!options)
+                    options = {};
+                let object = {};
+                if (options.defaults)
+                    object.entityId = "";
+                if (message.entityId != null && message.hasOwnProperty("entityId"))
+                    object.entityId = message.entityId;
+                return object;
+            };
+
+            /**
+             * Converts this EntityDespawn to JSON.
+             * @function toJSON
+             * @memberof graphwiz.core.EntityDespawn
+             * @instance
+             * @returns {Object.<string,*>} JSON object
+             */
+            EntityDespawn.prototype.toJSON = function toJSON() {
+                return this.constructor.toObject(this, $protobuf.util.toJSONOptions);
+            };
+
+            /**
+             * Gets the default type url for EntityDespawn
+             * @function getTypeUrl
+             * @memberof graphwiz.core.EntityDespawn
+             * @static
+             * @param {string} [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
+             * @returns {string} The default type url
+             */
+            EntityDespawn.getTypeUrl = function getTypeUrl(typeUrlPrefix) {
+                if (typeUrlPrefix === undefined) {
+                    typeUrlPrefix = "type.googleapis.com";
+                }
+                return typeUrlPrefix + "/graphwiz.core.EntityDespawn";
+            };
+
+            return EntityDespawn;
+        })();
+
+        core.EntityHistory = (function() {
+
+            /**
+             * Properties of an EntityHistory.
+             * @memberof graphwiz.core
+             * @interface IEntityHistory
+             * @property {boolean|null} [redo] EntityHistory redo
+             */
+
+            /**
+             * Constructs a new EntityHistory.
+             * @memberof graphwiz.core
+             * @classdesc Represents an EntityHistory.
+             * @implements IEntityHistory
+             * @constructor
+             * @param {graphwiz.core.IEntityHistory=} [properties] Properties to set
+             */
+            function EntityHistory(properties) {
+                if (properties)
+                    for (let keys = Object.keys(properties), i = 0; i < keys.length; ++i)
+                        if (properties[keys[i]] != null)
+                            this[keys[i]] = properties[keys[i]];
+            }
+
+            /**
+             * EntityHistory redo.
+             * @member {boolean} redo
+             * @memberof graphwiz.core.EntityHistory
+             * @instance
+             */
+            EntityHistory.prototype.redo = false;
+
+            /**
+             * Creates a new EntityHistory instance using the specified properties.
+             * @function create
+             * @memberof graphwiz.core.EntityHistory
+             * @static
+             * @param {graphwiz.core.IEntityHistory=} [properties] Properties to set
+             * @returns {graphwiz.core.EntityHistory} EntityHistory instance
+             */
+            EntityHistory.create = function create(properties) {
+                return new EntityHistory(properties);
+            };
+
+            /**
+             * Encodes the specified EntityHistory message. Does not implicitly {@link graphwiz.core.EntityHistory.verify|verify} messages.
+             * @function encode
+             * @memberof graphwiz.core.EntityHistory
+             * @static
+             * @param {graphwiz.core.IEntityHistory} message EntityHistory message or plain object to encode
+             * @param {$protobuf.Writer} [writer] Writer to encode to
+             * @returns {$protobuf.Writer} Writer
+             */
+            EntityHistory.encode = function encode(message, writer) {
+                if (!writer)
+                    writer = $Writer.create();
+                if (message.redo != null && Object.hasOwnProperty.call(message, "redo"))
+                    writer.uint32(/* id 1, wireType 0 =*/8).bool(message.redo);
+                return writer;
+            };
+
+            /**
+             * Encodes the specified EntityHistory message, length delimited. Does not implicitly {@link graphwiz.core.EntityHistory.verify|verify} messages.
+             * @function encodeDelimited
+             * @memberof graphwiz.core.EntityHistory
+             * @static
+             * @param {graphwiz.core.IEntityHistory} message EntityHistory message or plain object to encode
+             * @param {$protobuf.Writer} [writer] Writer to encode to
+             * @returns {$protobuf.Writer} Writer
+             */
+            EntityHistory.encodeDelimited = function encodeDelimited(message, writer) {
+                return this.encode(message, writer).ldelim();
+            };
+
+            /**
+             * Decodes an EntityHistory message from the specified reader or buffer.
+             * @function decode
+             * @memberof graphwiz.core.EntityHistory
+             * @static
+             * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
+             * @param {number} [length] Message length if known beforehand
+             * @returns {graphwiz.core.EntityHistory} EntityHistory
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            EntityHistory.decode = function decode(reader, length, error) {
+                if (!(reader instanceof $Reader))
+                    reader = $Reader.create(reader);
+                let end = length === undefined ? reader.len : reader.pos + length, message = new $root.graphwiz.core.EntityHistory();
+                while (reader.pos < end) {
+                    let tag = reader.uint32();
+                    if (tag === error)
+                        break;
+                    switch (tag >>> 3) {
+                    case 1: {
+                            message.redo = reader.bool();
+                            break;
+                        }
+                    default:
+                        reader.skipType(tag & 7);
+                        break;
+                    }
+                }
+                return message;
+            };
+
+            /**
+             * Decodes an EntityHistory message from the specified reader or buffer, length delimited.
+             * @function decodeDelimited
+             * @memberof graphwiz.core.EntityHistory
+             * @static
+             * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
+             * @returns {graphwiz.core.EntityHistory} EntityHistory
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            EntityHistory.decodeDelimited = function decodeDelimited(reader) {
+                if (!(reader instanceof $Reader))
+                    reader = new $Reader(reader);
+                return this.decode(reader, reader.uint32());
+            };
+
+            /**
+             * Verifies an EntityHistory message.
+             * @function verify
+             * @memberof graphwiz.core.EntityHistory
+             * @static
+             * @param {Object.<string,*>} message Plain object to verify
+             * @returns {string|null} `null` if valid, otherwise the reason why it is not
+             */
+            EntityHistory.verify = function verify(message) {
+                if (typeof message !== "object" || message === null)
+                    return "object expected";
+                if (message.redo != null && message.hasOwnProperty("redo"))
+                    if (typeof message.redo !== "boolean")
+                        return "redo: boolean expected";
+                return null;
+            };
+
+            /**
+             * Creates an EntityHistory message from a plain object. Also converts values to their respective internal types.
+             * @function fromObject
+             * @memberof graphwiz.core.EntityHistory
+             * @static
+             * @param {Object.<string,*>} object Plain object
+             * @returns {graphwiz.core.EntityHistory} EntityHistory
+             */
+            EntityHistory.fromObject = function fromObject(object) {
+                if (object instanceof $root.graphwiz.core.EntityHistory)
+                    return object;
+                let message = new $root.graphwiz.core.EntityHistory();
+                if (object.redo != null)
+                    message.redo = Boolean(object.redo);
+                return message;
+            };
+
+            /**
+             * Creates a plain object from an EntityHistory message. Also converts values to other types if specified.
+             * @function toObject
+             * @memberof graphwiz.core.EntityHistory
+             * @static
+             * @param {graphwiz.core.EntityHistory} message EntityHistory
+             * @param {$protobuf.IConversionOptions} [options] Conversion options
+             * @returns {Object.<string,*>} Plain object
+             */
+            EntityHistory.toObject = function toObject(message, options) {
+                if (!options)
+                    options = {};
+                let object = {};
+                if (options.defaults)
+                    object.redo = false;
+                if (message.redo != null && message.hasOwnProperty("redo"))
+                    object.redo = message.redo;
+                return object;
+            };
+
+            /**
+             * Converts this EntityHistory to JSON.
+             * @function toJSON
+             * @memberof graphwiz.core.EntityHistory
+             * @instance
+             * @returns {Object.<string,*>} JSON object
+             */
+            EntityHistory.prototype.toJSON = function toJSON() {
+                return this.constructor.toObject(this, $protobuf.util.toJSONOptions);
+            };
+
+            /**
+             * Gets the default type url for EntityHistory
+             * @function getTypeUrl
+             * @memberof graphwiz.core.EntityHistory
+             * @static
+             * @param {string} [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
+             * @returns {string} The default type url
+             */
+            EntityHistory.getTypeUrl = function getTypeUrl(typeUrlPrefix) {
+                if (typeUrlPrefix === undefined) {
+                    typeUrlPrefix = "type.googleapis.com";
+                }
+                return typeUrlPrefix + "/graphwiz.core.EntityHistory";
+            };
+
+            return EntityHistory;
+        })();
+
+        core.ChatMessage = (function() {
+
+            /**
+             * Properties of a ChatMessage.
+             * @memberof graphwiz.core
+             * @interface IChatMessage
+             * @property {string|null} [fromClientId] ChatMessage fromClientId
+             * @property {string|null} [message] ChatMessage message
+             * @property {number|Long|null} [timestamp] ChatMessage timestamp
+             * @property {graphwiz.core.ChatMessageType|null} [type] ChatMessage type
+             * @property {string|null} [toUserId] ChatMessage toUserId
+             * @property {string|null} [channel] ChatMessage channel
+             * @property {string|null} [chatId] ChatMessage chatId
+             * @property {boolean|null} [deleted] ChatMessage deleted
+             */
+
+            /**
+             * Constructs a new ChatMessage.
+             * @memberof graphwiz.core
+             * @classdesc Represents a ChatMessage.
+             * @implements IChatMessage
+             * @constructor
+             * @param {graphwiz.core.IChatMessage=} [properties] Properties to set
+             */
+            function ChatMessage(properties) {
+                if (properties)
+                    for (let keys = Object.keys(properties), i = 0; i < keys.length; ++i)
+                        if (properties[keys[i]] != null)
+                            this[keys[i]] = properties[keys[i]];
+            }
+
+            /**
+             * ChatMessage fromClientId.
+             * @member {string} fromClientId
+             * @memberof graphwiz.core.ChatMessage
+             * @instance
+             */
+            ChatMessage.prototype.fromClientId = "";
+
+            /**
+             * ChatMessage message.
+             * @member {string} message
+             * @memberof graphwiz.core.ChatMessage
+             * @instance
+             */
+            ChatMessage.prototype.message = "";
+
+            /**
+             * ChatMessage timestamp.
+             * @member {number|Long} timestamp
+             * @memberof graphwiz.core.ChatMessage
+             * @instance
+             */
+            ChatMessage.prototype.timestamp = $util.Long ? $util.Long.fromBits(0,0,false) : 0;
+
+            /**
+             * ChatMessage type.
+             * @member {graphwiz.core.ChatMessageType} type
+             * @memberof graphwiz.core.ChatMessage
+             * @instance
+             */
+            ChatMessage.prototype.type = 0;
+
+            /**
+             * ChatMessage toUserId.
+             * @member {string} toUserId
+             * @memberof graphwiz.core.ChatMessage
+             * @instance
+             */
+            ChatMessage.prototype.toUserId = "";
+
+            /**
+             * ChatMessage channel.
+             * @member {string} channel
+             * @memberof graphwiz.core.ChatMessage
+             * @instance
+             */
+            ChatMessage.prototype.channel = "";
+
+            /**
+             * ChatMessage chatId.
+             * @member {string} chatId
+             * @memberof graphwiz.core.ChatMessage
+             * @instance
+             */
+            ChatMessage.prototype.chatId = "";
+
+            /**
+             * ChatMessage deleted.
+             * @member {boolean} deleted
+             * @memberof graphwiz.core.ChatMessage
+             * @instance
+             */
+            ChatMessage.prototype.deleted = false;
+
+            /**
+             * Creates a new ChatMessage instance using the specified properties.
+             * @function create
+             * @memberof graphwiz.core.ChatMessage
+             * @static
+             * @param {graphwiz.core.IChatMessage=} [properties] Properties to set
+             * @returns {graphwiz.core.ChatMessage} ChatMessage instance
+             */
+            ChatMessage.create = function create(properties) {
+                return new ChatMessage(properties);
+            };
+
+            /**
+             * Encodes the specified ChatMessage message. Does not implicitly {@link graphwiz.core.ChatMessage.verify|verify} messages.
+             * @function encode
+             * @memberof graphwiz.core.ChatMessage
+             * @static
+             * @param {graphwiz.core.IChatMessage} message ChatMessage message or plain object to encode
+             * @param {$protobuf.Writer} [writer] Writer to encode to
+             * @returns {$protobuf.Writer} Writer
+             */
+            ChatMessage.encode = function encode(message, writer) {
+                if (!writer)
+                    writer = $Writer.create();
+                if (message.fromClientId != null && Object.hasOwnProperty.call(message, "fromClientId"))
+                    writer.uint32(/* id 1, wireType 2 =*/10).string(message.fromClientId);
+                if (message.message != null && Object.hasOwnProperty.call(message, "message"))
+                    writer.uint32(/* id 2, wireType 2 =*/18).string(message.message);
+                if (message.timestamp != null && Object.hasOwnProperty.call(message, "timestamp"))
+                    writer.uint32(/* id 3, wireType 0 =*/24).int64(message.timestamp);
+                if (message.type != null && Object.hasOwnProperty.call(message, "type"))
+                    writer.uint32(/* id 4, wireType 0 =*/32).int32(message.type);
+                if (message.toUserId != null && Object.hasOwnProperty.call(message, "toUserId"))
+                    writer.uint32(/* id 5, wireType 2 =*/42).string(message.toUserId);
+                if (message.channel != null && Object.hasOwnProperty.call(message, "channel"))
+                    writer.uint32(/* id 6, wireType 2 =*/50).string(message.channel);
+                if (message.chatId != null && Object.hasOwnProperty.call(message, "chatId"))
+                    writer.uint32(/* id 7, wireType 2 =*/58).string(message.chatId);
+                if (message.deleted != null && Object.hasOwnProperty.call(message, "deleted"))
+                    writer.uint32(/* id 8, wireType 0 =*/64).bool(message.deleted);
+                return writer;
+            };
+
+            /**
+             * Encodes the specified ChatMessage message, length delimited. Does not implicitly {@link graphwiz.core.ChatMessage.verify|verify} messages.
+             * @function encodeDelimited
+             * @memberof graphwiz.core.ChatMessage
+             * @static
+             * @param {graphwiz.core.IChatMessage} message ChatMessage message or plain object to encode
+             * @param {$protobuf.Writer} [writer] Writer to encode to
+             * @returns {$protobuf.Writer} Writer
+             */
+            ChatMessage.encodeDelimited = function encodeDelimited(message, writer) {
+                return this.encode(message, writer).ldelim();
+            };
+
+            /**
+             * Decodes a ChatMessage message from the specified reader or buffer.
+             * @function decode
+             * @memberof graphwiz.core.ChatMessage
+             * @static
+             * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
+             * @param {number} [length] Message length if known beforehand
+             * @returns {graphwiz.core.ChatMessage} ChatMessage
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            ChatMessage.decode = function decode(reader, length, error) {
+                if (!(reader instanceof $Reader))
+                    reader = $Reader.create(reader);
+                let end = length === undefined ? reader.len : reader.pos + length, message = new $root.graphwiz.core.ChatMessage();
+                while (reader.pos < end) {
+                    let tag = reader.uint32();
+                    if (tag === error)
+                        break;
+                    switch (tag >>> 3) {
+                    case 1: {
+                            message.fromClientId = reader.string();
+                            break;
+                        }
+                    case 2: {
+                            message.message = reader.string();
+                            break;
+                        }
+                    case 3: {
+                            message.timestamp = reader.int64();
+                            break;
+                        }
+                    case 4: {
+                            message.type = reader.int32();
+                            break;
+                        }
+                    case 5: {
+                            message.toUserId = reader.string();
+                            break;
+                        }
+                    case 6: {
+                            message.channel = reader.string();
+                            break;
+                        }
+                    case 7: {
+                            message.chatId = reader.string();
+                            break;
+                        }
+                    case 8: {
+                            message.deleted = reader.bool();
+                            break;
+                        }
+                    default:
+                        reader.skipType(tag & 7);
+                        break;
+                    }
+                }
+                return message;
+            };
+
+            /**
+             * Decodes a ChatMessage message from the specified reader or buffer, length delimited.
+             * @function decodeDelimited
+             * @memberof graphwiz.core.ChatMessage
+             * @static
+             * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
+             * @returns {graphwiz.core.ChatMessage} ChatMessage
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            ChatMessage.decodeDelimited = function decodeDelimited(reader) {
+                if (!(reader instanceof $Reader))
+                    reader = new $Reader(reader);
+                return this.decode(reader, reader.uint32());
+            };
+
+            /**
+             * Verifies a ChatMessage message.
+             * @function verify
+             * @memberof graphwiz.core.ChatMessage
+             * @static
+             * @param {Object.<string,*>} message Plain object to verify
+             * @returns {string|null} `null` if valid, otherwise the reason why it is not
+             */
+            ChatMessage.verify = function verify(message) {
+                if (typeof message !== "object" || message === null)
+                    return "object expected";
+                if (message.fromClientId != null && message.hasOwnProperty("fromClientId"))
+                    if (!$util.isString(message.fromClientId))
+                        return "fromClientId: string expected";
+                if (message.message != null && message.hasOwnProperty("message"))
+                    if (!$util.isString(message.message))
+                        return "message: string expected";
+                if (message.timestamp != null && message.hasOwnProperty("timestamp"))
+                    if (!$util.isInteger(message.timestamp) && !(message.timestamp && $util.isInteger(message.timestamp.low) && $util.isInteger(message.timestamp.high)))
+                        return "timestamp: integer|Long expected";
+                if (message.type != null && message.hasOwnProperty("type"))
+                    switch (message.type) {
+                    default:
+                        return "type: enum value expected";
+                    case 0:
+                    case 1:
+                    case 2:
+                    case 3:
+                        break;
+                    }
+                if (message.toUserId != null && message.hasOwnProperty("toUserId"))
+                    if (!$util.isString(message.toUserId))
+                        return "toUserId: string expected";
+                if (message.channel != null && message.hasOwnProperty("channel"))
+                    if (!$util.isString(message.channel))
+                        return "channel: string expected";
+                if (message.chatId != null && message.hasOwnProperty("chatId"))
+                    if (!$util.isString(message.chatId))
+                        return "chatId: string expected";
+                if (message.deleted != null && message.hasOwnProperty("deleted"))
+                    if (typeof message.deleted !== "boolean")
+                        return "deleted: boolean expected";
+                return null;
+            };
+
+            /**
+             * Creates a ChatMessage message from a plain object. Also converts values to their respective internal types.
+             * @function fromObject
+             * @memberof graphwiz.core.ChatMessage
+             * @static
+             * @param {Object.<string,*>} object Plain object
+             * @returns {graphwiz.core.ChatMessage} ChatMessage
+             */
+            ChatMessage.fromObject = function fromObject(object) {
+                if (object instanceof $root.graphwiz.core.ChatMessage)
+                    return object;
+                let message = new $root.graphwiz.core.ChatMessage();
+                if (object.fromClientId != null)
+                    message.fromClientId = String(object.fromClientId);
+                if (object.message != null)
+                    message.message = String(object.message);
+                if (object.timestamp != null)
+                    if ($util.Long)
+                        (message.timestamp = $util.Long.fromValue(object.timestamp)).unsigned = false;
+                    else if (typeof object.timestamp === "string")
+                        message.timestamp = parseInt(object.timestamp, 10);
+                    else if (typeof object.timestamp === "number")
+                        message.timestamp = object.timestamp;
+                    else if (typeof object.timestamp === "object")
+                        message.timestamp = new $util.LongBits(object.timestamp.low >>> 0, object.timestamp.high >>> 0).toNumber();
+                switch (object.type) {
+                default:
+                    if (typeof object.type === "number") {
+                        message.type = object.type;
+                        break;
+                    }
+                    break;
+                case "NORMAL":
+                case 0:
+                    message.type = 0;
+                    break;
+                case "WHISPER":
+                case 1:
+                    message.type = 1;
+                    break;
+                case "SHOUT":
+                case 2:
+                    message.type = 2;
+                    break;
+                case "TEAM":
+                case 3:
+                    message.type = 3;
+                    break;
+                }
+                if (object.toUserId != null)
+                    message.toUserId = String(object.toUserId);
+                if (object.channel != null)
+                    message.channel = String(object.channel);
+                if (object.chatId != null)
+                    message.chatId = String(object.chatId);
+                if (object.deleted != null)
+                    message.deleted = Boolean(object.deleted);
+                return message;
+            };
+
+            /**
+             * Creates a plain object from a ChatMessage message. Also converts values to other types if specified.
+             * @function toObject
+             * @memberof graphwiz.core.ChatMessage
+             * @static
+             * @param {graphwiz.core.ChatMessage} message ChatMessage
+             * @param {$protobuf.IConversionOptions} [options] Conversion options
+             * @returns {Object.<string,*>} Plain object
+             */
+            ChatMessage.toObject = function toObject(message, options) {
+                if (!options)
+                    options = {};
+                let object = {};
+                if (options.defaults) {
+                    object.fromClientId = "";
+                    object.message = "";
+                    if ($util.Long) {
+                        let long = new $util.Long(0, 0, false);
+                        object.timestamp = options.longs === String ? long.toString() : options.longs === Number ? long.toNumber() : long;
+                    } else
+                        object.timestamp = options.longs === String ? "0" : 0;
+                    object.type = options.enums === String ? "NORMAL" : 0;
+                    object.toUserId = "";
+                    object.channel = "";
+                    object.chatId = "";
+                    object.deleted = false;
+                }
+                if (message.fromClientId != null && message.hasOwnProperty("fromClientId"))
+                    object.fromClientId = message.fromClientId;
+                if (message.message != null && message.hasOwnProperty("message"))
+                    object.message = message.message;
+                if (message.timestamp != null && message.hasOwnProperty("timestamp"))
+                    if (typeof message.timestamp === "number")
+                        object.timestamp = options.longs === String ? String(message.timestamp) : message.timestamp;
+                    else
+                        object.timestamp = options.longs === String ? $util.Long.prototype.toString.call(message.timestamp) : options.longs === Number ? new $util.LongBits(message.timestamp.low >>> 0, message.timestamp.high >>> 0).toNumber() : message.timestamp;
+                if (message.type != null && message.hasOwnProperty("type"))
+                    object.type = options.enums === String ? $root.graphwiz.core.ChatMessageType[message.type] === undefined ? message.type : $root.graphwiz.core.ChatMessageType[message.type] : message.type;
+                if (message.toUserId != null && message.hasOwnProperty("toUserId"))
+                    object.toUserId = message.toUserId;
+                if (message.channel != null && message.hasOwnProperty("channel"))
+                    object.channel = message.channel;
+                if (message.chatId != null && message.hasOwnProperty("chatId"))
+                    object.chatId = message.chatId;
+                if (message.deleted != null && message.hasOwnProperty("deleted"))
+                    object.deleted = message.deleted;
+                return object;
+            };
+
+            /**
+             * Converts this ChatMessage to JSON.
+             * @function toJSON
+             * @memberof graphwiz.core.ChatMessage
+             * @instance
+             * @returns {Object.<string,*>} JSON object
+             */
+            ChatMessage.prototype.toJSON = function toJSON() {
+                return this.constructor.toObject(this, $protobuf.util.toJSONOptions);
+            };
+
+            /**
+             * Gets the default type url for ChatMessage
+             * @function getTypeUrl
+             * @memberof graphwiz.core.ChatMessage
+             * @static
+             * @param {string} [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
+             * @returns {string} The default type url
+             */
+            ChatMessage.getTypeUrl = function getTypeUrl(typeUrlPrefix) {
+                if (typeUrlPrefix === undefined) {
+                    typeUrlPrefix = "type.googleapis.com";
+                }
+                return typeUrlPrefix + "/graphwiz.core.ChatMessage";
+            };
+
+            return ChatMessage;
+        })();
+
+        /**
+         * ChatMessageType enum.
+         * @name graphwiz.core.ChatMessageType
+         * @enum {number}
+         * @property {number} NORMAL=0 NORMAL value
+         * @property {number} WHISPER=1 WHISPER value
+         * @property {number} SHOUT=2 SHOUT value
+         */
+        core.ChatMessageType = (function() {
+            const valuesById = {}, values = Object.create(valuesById);
+            values[valuesById[0] = "NORMAL"] = 0;
+            values[valuesById[1] = "WHISPER"] = 1;
+            values[valuesById[2] = "SHOUT"] = 2;
+            values[valuesById[3] = "TEAM"] = 3;
+            return values;
+        })();
+
+        core.ErrorFrame = (function() {
+
+            /**
+             * Properties of an ErrorFrame.
+             * @memberof graphwiz.core
+             * @interface IErrorFrame
+             * @property {string|null} [code] ErrorFrame code
+             * @property {string|null} [message] ErrorFrame message
+             * @property {number|Long|null} [retryAfterMs] ErrorFrame retryAfterMs
+             */
+
+            /**
+             * Constructs a new ErrorFrame.
+             * @memberof graphwiz.core
+             * @classdesc Represents an ErrorFrame.
+             * @implements IErrorFrame
+             * @constructor
+             * @param {graphwiz.core.IErrorFrame=} [properties] Properties to set
+             */
+            function ErrorFrame(properties) {
+                if (properties)
+                    for (let keys = Object.keys(properties), i = 0; i < keys.length; ++i)
+                        if (properties[keys[i]] != null)
+                            this[keys[i]] = properties[keys[i]];
+            }
+
+            /**
+             * ErrorFrame code.
+             * @member {string} code
+             * @memberof graphwiz.core.ErrorFrame
+             * @instance
+             */
+            ErrorFrame.prototype.code = "";
+
+            /**
+             * ErrorFrame message.
+             * @member {string} message
+             * @memberof graphwiz.core.ErrorFrame
+             * @instance
+             */
+            ErrorFrame.prototype.message = "";
+
+            /**
+             * ErrorFrame retryAfterMs.
+             * @member {number|Long} retryAfterMs
+             * @memberof graphwiz.core.ErrorFrame
+             * @instance
+             */
+            ErrorFrame.prototype.retryAfterMs = $util.Long ? $util.Long.fromBits(0,0,false) : 0;
+
+            /**
+             * Creates a new ErrorFrame instance using the specified properties.
+             * @function create
+             * @memberof graphwiz.core.ErrorFrame
+             * @static
+             * @param {graphwiz.core.IErrorFrame=} [properties] Properties to set
+             * @returns {graphwiz.core.ErrorFrame} ErrorFrame instance
+             */
+            ErrorFrame.create = function create(properties) {
+                return new ErrorFrame(properties);
+            };
+
+            /**
+             * Encodes the specified ErrorFrame message. Does not implicitly {@link graphwiz.core.ErrorFrame.verify|verify} messages.
+             * @function encode
+             * @memberof graphwiz.core.ErrorFrame
+             * @static
+             * @param {graphwiz.core.IErrorFrame} message ErrorFrame message or plain object to encode
+             * @param {$protobuf.Writer} [writer] Writer to encode to
+             * @returns {$protobuf.Writer} Writer
+             */
+            ErrorFrame.encode = function encode(message, writer) {
+                if (!writer)
+                    writer = $Writer.create();
+                if (message.code != null && Object.hasOwnProperty.call(message, "code"))
+                    writer.uint32(/* id 1, wireType 2 =*/10).string(message.code);
+                if (message.message != null && Object.hasOwnProperty.call(message, "message"))
+                    writer.uint32(/* id 2, wireType 2 =*/18).string(message.message);
+                if (message.retryAfterMs != null && Object.hasOwnProperty.call(message, "retryAfterMs"))
+                    writer.uint32(/* id 3, wireType 0 =*/24).int64(message.retryAfterMs);
+                return writer;
+            };
+
+            /**
+             * Encodes the specified ErrorFrame message, length delimited. Does not implicitly {@link graphwiz.core.ErrorFrame.verify|verify} messages.
+             * @function encodeDelimited
+             * @memberof graphwiz.core.ErrorFrame
+             * @static
+             * @param {graphwiz.core.IErrorFrame} message ErrorFrame message or plain object to encode
+             * @param {$protobuf.Writer} [writer] Writer to encode to
+             * @returns {$protobuf.Writer} Writer
+             */
+            ErrorFrame.encodeDelimited = function encodeDelimited(message, writer) {
+                return this.encode(message, writer).ldelim();
+            };
+
+            /**
+             * Decodes an ErrorFrame message from the specified reader or buffer.
+             * @function decode
+             * @memberof graphwiz.core.ErrorFrame
+             * @static
+             * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
+             * @param {number} [length] Message length if known beforehand
+             * @returns {graphwiz.core.ErrorFrame} ErrorFrame
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            ErrorFrame.decode = function decode(reader, length, error) {
+                if (!(reader instanceof $Reader))
+                    reader = $Reader.create(reader);
+                let end = length === undefined ? reader.len : reader.pos + length, message = new $root.graphwiz.core.ErrorFrame();
+                while (reader.pos < end) {
+                    let tag = reader.uint32();
+                    if (tag === error)
+                        break;
+                    switch (tag >>> 3) {
+                    case 1: {
+                            message.code = reader.string();
+                            break;
+                        }
+                    case 2: {
+                            message.message = reader.string();
+                            break;
+                        }
+                    case 3: {
+                            message.retryAfterMs = reader.int64();
+                            break;
+                        }
+                    default:
+                        reader.skipType(tag & 7);
+                        break;
+                    }
+                }
+                return message;
+            };
+
+            /**
+             * Decodes an ErrorFrame message from the specified reader or buffer, length delimited.
+             * @function decodeDelimited
+             * @memberof graphwiz.core.ErrorFrame
+             * @static
+             * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
+             * @returns {graphwiz.core.ErrorFrame} ErrorFrame
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            ErrorFrame.decodeDelimited = function decodeDelimited(reader) {
+                if (!(reader instanceof $Reader))
+                    reader = new $Reader(reader);
+                return this.decode(reader, reader.uint32());
+            };
+
+            /**
+             * Verifies an ErrorFrame message.
+             * @function verify
+             * @memberof graphwiz.core.ErrorFrame
+             * @static
+             * @param {Object.<string,*>} message Plain object to verify
+             * @returns {string|null} `null` if valid, otherwise the reason why it is not
+             */
+            ErrorFrame.verify = function verify(message) {
+                if (typeof message !== "object" || message === null)
+                    return "object expected";
+                if (message.code != null && message.hasOwnProperty("code"))
+                    if (!$util.isString(message.code))
+                        return "code: string expected";
+                if (message.message != null && message.hasOwnProperty("message"))
+                    if (!$util.isString(message.message))
+                        return "message: string expected";
+                if (message.retryAfterMs != null && message.hasOwnProperty("retryAfterMs"))
+                    if (!$util.isInteger(message.retryAfterMs) && !(message.retryAfterMs && $util.isInteger(message.retryAfterMs.low) && $util.isInteger(message.retryAfterMs.high)))
+                        return "retryAfterMs: integer|Long expected";
+                return null;
+            };
+
+            /**
+             * Creates an ErrorFrame message from a plain object. Also converts values to their respective internal types.
+             * @function fromObject
+             * @memberof graphwiz.core.ErrorFrame
+             * @static
+             * @param {Object.<string,*>} object Plain object
+             * @returns {graphwiz.core.ErrorFrame} ErrorFrame
+             */
+            ErrorFrame.fromObject = function fromObject(object) {
+                if (object instanceof $root.graphwiz.core.ErrorFrame)
+                    return object;
+                let message = new $root.graphwiz.core.ErrorFrame();
+                if (object.code != null)
+                    message.code = String(object.code);
+                if (object.message != null)
+                    message.message = String(object.message);
+                if (object.retryAfterMs != null)
+                    if ($util.Long)
+                        (message.retryAfterMs = $util.Long.fromValue(object.retryAfterMs)).unsigned = false;
+                    else if (typeof object.retryAfterMs === "string")
+                        message.retryAfterMs = parseInt(object.retryAfterMs, 10);
+                    else if (typeof object.retryAfterMs === "number")
+                        message.retryAfterMs = object.retryAfterMs;
+                    else if (typeof object.retryAfterMs === "object")
+                        message.retryAfterMs = new $util.LongBits(object.retryAfterMs.low >>> 0, object.retryAfterMs.high >>> 0).toNumber();
+                return message;
+            };
+
+            /**
+             * Creates a plain object from an ErrorFrame message. Also converts values to other types if specified.
+             * @function toObject
+             * @memberof graphwiz.core.ErrorFrame
+             * @static
+             * @param {graphwiz.core.ErrorFrame} message ErrorFrame
+             * @param {$protobuf.IConversionOptions} [options] Conversion options
+             * @returns {Object.<string,*>} Plain object
+             */
+            ErrorFrame.toObject = function toObject(message, options) {
+                if (!options)
+                    options = {};
+                let object = {};
+                if (options.defaults) {
+                    object.code = "";
+                    object.message = "";
+                    if ($util.Long) {
+                        let long = new $util.Long(0, 0, false);
+                        object.retryAfterMs = options.longs === String ? long.toString() : options.longs === Number ? long.toNumber() : long;
+                    } else
+                        object.retryAfterMs = options.longs === String ? "0" : 0;
+                }
+                if (message.code != null && message.hasOwnProperty("code"))
+                    object.code = message.code;
+                if (message.message != null && message.hasOwnProperty("message"))
+                    object.message = message.message;
+                if (message.retryAfterMs != null && message.hasOwnProperty("retryAfterMs"))
+                    if (typeof message.retryAfterMs === "number")
+                        object.retryAfterMs = options.longs === String ? String(message.retryAfterMs) : message.retryAfterMs;
+                    else
+                        object.retryAfterMs = options.longs === String ? $util.Long.prototype.toString.call(message.retryAfterMs) : options.longs === Number ? new $util.LongBits(message.retryAfterMs.low >>> 0, message.retryAfterMs.high >>> 0).toNumber() : message.retryAfterMs;
+                return object;
+            };
+
+            /**
+             * Converts this ErrorFrame to JSON.
+             * @function toJSON
+             * @memberof graphwiz.core.ErrorFrame
+             * @instance
+             * @returns {Object.<string,*>} JSON object
+             */
+            ErrorFrame.prototype.toJSON = function toJSON() {
+                return this.constructor.toObject(this, $protobuf.util.toJSONOptions);
+            };
+
+            /**
+             * Gets the default type url for ErrorFrame
+             * @function getTypeUrl
+             * @memberof graphwiz.core.ErrorFrame
+             * @static
+             * @param {string} [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
+             * @returns {string} The default type url
+             */
+            ErrorFrame.getTypeUrl = function getTypeUrl(typeUrlPrefix) {
+                if (typeUrlPrefix === undefined) {
+                    typeUrlPrefix = "type.googleapis.com";
+                }
+                return typeUrlPrefix + "/graphwiz.core.ErrorFrame";
+            };
+
+            return ErrorFrame;
+        })();
+
+        core.PresenceEvent = (function() {
+
+            /**
+             * Properties of a PresenceEvent.
+             * @memberof graphwiz.core
+             * @interface IPresenceEvent
+             * @property {string|null} [clientId] PresenceEvent clientId
+             * @property {graphwiz.core.PresenceEventType|null} [eventType] PresenceEvent eventType
+             * @property {graphwiz.core.IPresenceData|null} [data] PresenceEvent data
+             */
+
+            /**
+             * Constructs a new PresenceEvent.
+             * @memberof graphwiz.core
+             * @classdesc Represents a PresenceEvent.
+             * @implements IPresenceEvent
+             * @constructor
+             * @param {graphwiz.core.IPresenceEvent=} [properties] Properties to set
+             */
+            function PresenceEvent(properties) {
+                if (properties)
+                    for (let keys = Object.keys(properties), i = 0; i < keys.length; ++i)
+                        if (properties[keys[i]] != null)
+                            this[keys[i]] = properties[keys[i]];
+            }
+
+            /**
+             * PresenceEvent clientId.
+             * @member {string} clientId
+             * @memberof graphwiz.core.PresenceEvent
+             * @instance
+             */
+            PresenceEvent.prototype.clientId = "";
+
+            /**
+             * PresenceEvent eventType.
+             * @member {graphwiz.core.PresenceEventType} eventType
+             * @memberof graphwiz.core.PresenceEvent
+             * @instance
+             */
+            PresenceEvent.prototype.eventType = 0;
+
+            /**
+             * PresenceEvent data.
+             * @member {graphwiz.core.IPresenceData|null|undefined} data
+             * @memberof graphwiz.core.PresenceEvent
+             * @instance
+             */
+            PresenceEvent.prototype.data = null;
+
+            /**
+             * Creates a new PresenceEvent instance using the specified properties.
+             * @function create
+             * @memberof graphwiz.core.PresenceEvent
+             * @static
+             * @param {graphwiz.core.IPresenceEvent=} [properties] Properties to set
+             * @returns {graphwiz.core.PresenceEvent} PresenceEvent instance
+             */
+            PresenceEvent.create = function create(properties) {
+                return new PresenceEvent(properties);
+            };
+
+            /**
+             * Encodes the specified PresenceEvent message. Does not implicitly {@link graphwiz.core.PresenceEvent.verify|verify} messages.
+             * @function encode
+             * @memberof graphwiz.core.PresenceEvent
+             * @static
+             * @param {graphwiz.core.IPresenceEvent} message PresenceEvent message or plain object to encode
+             * @param {$protobuf.Writer} [writer] Writer to encode to
+             * @returns {$protobuf.Writer} Writer
+             */
+            PresenceEvent.encode = function encode(message, writer) {
+                if (!writer)
+                    writer = $Writer.create();
+                if (message.clientId != null && Object.hasOwnProperty.call(message, "clientId"))
+                    writer.uint32(/* id 1, wireType 2 =*/10).string(message.clientId);
+                if (message.eventType != null && Object.hasOwnProperty.call(message, "eventType"))
+                    writer.uint32(/* id 2, wireType 0 =*/16).int32(message.eventType);
+                if (message.data != null && Object.hasOwnProperty.call(message, "data"))
+                    $root.graphwiz.core.PresenceData.encode(message.data, writer.uint32(/* id 3, wireType 2 =*/26).fork()).ldelim();
+                return writer;
+            };
+
+            /**
+             * Encodes the specified PresenceEvent message, length delimited. Does not implicitly {@link graphwiz.core.PresenceEvent.verify|verify} messages.
+             * @function encodeDelimited
+             * @memberof graphwiz.core.PresenceEvent
+             * @static
+             * @param {graphwiz.core.IPresenceEvent} message PresenceEvent message or plain object to encode
+             * @param {$protobuf.Writer} [writer] Writer to encode to
+             * @returns {$protobuf.Writer} Writer
+             */
+            PresenceEvent.encodeDelimited = function encodeDelimited(message, writer) {
+                return this.encode(message, writer).ldelim();
+            };
+
+            /**
+             * Decodes a PresenceEvent message from the specified reader or buffer.
+             * @function decode
+             * @memberof graphwiz.core.PresenceEvent
+             * @static
+             * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
+             * @param {number} [length] Message length if known beforehand
+             * @returns {graphwiz.core.PresenceEvent} PresenceEvent
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            PresenceEvent.decode = function decode(reader, length, error) {
+                if (!(reader instanceof $Reader))
+                    reader = $Reader.create(reader);
+                let end = length === undefined ? reader.len : reader.pos + length, message = new $root.graphwiz.core.PresenceEvent();
+                while (reader.pos < end) {
+                    let tag = reader.uint32();
+                    if (tag === error)
+                        break;
+                    switch (tag >>> 3) {
+                    case 1: {
+                            message.clientId = reader.string();
+                            break;
+                        }
+                    case 2: {
+                            message.eventType = reader.int32();
+                            break;
+                        }
+                    case 3: {
+                            message.data = $root.graphwiz.core.PresenceData.decode(reader, reader.uint32());
+                            break;
+                        }
+                    default:
+                        reader.skipType(tag & 7);
+                        break;
+                    }
+                }
+                return message;
+            };
+
+            /**
+             * Decodes a PresenceEvent message from the specified reader or buffer, length delimited.
+             * @function decodeDelimited
+             * @memberof graphwiz.core.PresenceEvent
+             * @static
+             * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
+             * @returns {graphwiz.core.PresenceEvent} PresenceEvent
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            PresenceEvent.decodeDelimited = function decodeDelimited(reader) {
+                if (!(reader instanceof $Reader))
+                    reader = new $Reader(reader);
+                return this.decode(reader, reader.uint32());
+            };
+
+            /**
+             * Verifies a PresenceEvent message.
+             * @function verify
+             * @memberof graphwiz.core.PresenceEvent
+             * @static
+             * @param {Object.<string,*>} message Plain object to verify
+             * @returns {string|null} `null` if valid, otherwise the reason why it is not
+             */
+            PresenceEvent.verify = function verify(message) {
+                if (typeof message !== "object" || message === null)
+                    return "object expected";
+                if (message.clientId != null && message.hasOwnProperty("clientId"))
+                    if (!$util.isString(message.clientId))
+                        return "clientId: string expected";
+                if (message.eventType != null && message.hasOwnProperty("eventType"))
+                    switch (message.eventType) {
+                    default:
+                        return "eventType: enum value expected";
+                    case 0:
+                    case 1:
+                    case 2:
+                    case 3:
+                    case 4:
+                        break;
+                    }
+                if (message.data != null && message.hasOwnProperty("data")) {
+                    let error = $root.graphwiz.core.PresenceData.verify(message.data);
+                    if (error)
+                        return "data." + error;
+                }
+                return null;
+            };
+
+            /**
+             * Creates a PresenceEvent message from a plain object. Also converts values to their respective internal types.
+             * @function fromObject
+             * @memberof graphwiz.core.PresenceEvent
+             * @static
+             * @param {Object.<string,*>} object Plain object
+             * @returns {graphwiz.core.PresenceEvent} PresenceEvent
+             */
+            PresenceEvent.fromObject = function fromObject(object) {
+                if (object instanceof $root.graphwiz.core.PresenceEvent)
+                    return object;
+                let message = new $root.graphwiz.core.PresenceEvent();
+                if (object.clientId != null)
+                    message.clientId = String(object.clientId);
+                switch (object.eventType) {
+                default:
+                    if (typeof object.eventType === "number") {
+                        message.eventType = object.eventType;
+                        break;
+                    }
+                    break;
+                case "JOIN":
+                case 0:
+                    message.eventType = 0;
+                    break;
+                case "LEAVE":
+                case 1:
+                    message.eventType = 1;
+                    break;
+                case "UPDATE":
+                case 2:
+                    message.eventType = 2;
+                    break;
+                case "HOST_CHANGED":
+                case 3:
+                    message.eventType = 3;
+                    break;
+                case "STATUS":
+                case 4:
+                    message.eventType = 4;
+                    break;
+                }
+                if (object.data != null) {
+                    if (typeof object.data !== "object")
+                        throw TypeError(".graphwiz.core.PresenceEvent.data: object expected");
+                    message.data = $root.graphwiz.core.PresenceData.fromObject(object.data);
+                }
+                return message;
+            };
+
+            /**
+             * Creates a plain object from a PresenceEvent message. Also converts values to other types if specified.
+             * @function toObject
+             * @memberof graphwiz.core.PresenceEvent
+             * @static
+             * @param {graphwiz.core.PresenceEvent} message PresenceEvent
+             * @param {$protobuf.IConversionOptions} [options] Conversion options
+             * @returns {Object.<string,*>} Plain object
+             */
+            PresenceEvent.toObject = function toObject(message, options) {
+                if (!options)
+                    options = {};
+                let object = {};
+                if (options.defaults) {
+                    object.clientId = "";
+                    object.eventType = options.enums === String ? "JOIN" : 0;
+                    object.data = null;
+                }
+                if (message.clientId != null && message.hasOwnProperty("clientId"))
+                    object.clientId = message.clientId;
+                if (message.eventType != null && message.hasOwnProperty("eventType"))
+                    object.eventType = options.enums === String ? $root.graphwiz.core.PresenceEventType[message.eventType] === undefined ? message.eventType : $root.graphwiz.core.PresenceEventType[message.eventType] : message.eventType;
+                if (message.data != null && message.hasOwnProperty("data"))
+                    object.data = $root.graphwiz.core.PresenceData.toObject(message.data, options);
+                return object;
+            };
+
+            /**
+             * Converts this PresenceEvent to JSON.
+             * @function toJSON
+             * @memberof graphwiz.core.PresenceEvent
+             * @instance
+             * @returns {Object.<string,*>} JSON object
+             */
+            PresenceEvent.prototype.toJSON = function toJSON() {
+                return this.constructor.toObject(this, $protobuf.util.toJSONOptions);
+            };
+
+            /**
+             * Gets the default type url for PresenceEvent
+             * @function getTypeUrl
+             * @memberof graphwiz.core.PresenceEvent
+             * @static
+             * @param {string} [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
+             * @returns {string} The default type url
+             */
+            PresenceEvent.getTypeUrl = function getTypeUrl(typeUrlPrefix) {
+                if (typeUrlPrefix === undefined) {
+                    typeUrlPrefix = "type.googleapis.com";
+                }
+                return typeUrlPrefix + "/graphwiz.core.PresenceEvent";
+            };
+
+            return PresenceEvent;
+        })();
+
+        /**
+         * PresenceEventType enum.
+         * @name graphwiz.core.PresenceEventType
+         * @enum {number}
+         * @property {number} JOIN=0 JOIN value
+         * @property {number} LEAVE=1 LEAVE value
+         * @property {number} UPDATE=2 UPDATE value
+         * @property {number} HOST_CHANGED=3 HOST_CHANGED value
+         * @property {number} STATUS=4 STATUS value
+         */
+        core.PresenceEventType = (function() {
+            const valuesById = {}, values = Object.create(valuesById);
+            values[valuesById[0] = "JOIN"] = 0;
+            values[valuesById[1] = "LEAVE"] = 1;
+            values[valuesById[2] = "UPDATE"] = 2;
+            values[valuesById[3] = "HOST_CHANGED"] = 3;
+            values[valuesById[4] = "STATUS"] = 4;
+            return values;
+        })();
+
+        core.PresenceData = (function() {
+
+            /**
+             * Properties of a PresenceData.
+             * @memberof graphwiz.core
+             * @interface IPresenceData
+             * @property {string|null} [displayName] PresenceData displayName
+             * @property {string|null} [avatarUrl] PresenceData avatarUrl
+             * @property {graphwiz.core.IVector3|null} [position] PresenceData position
+             * @property {graphwiz.core.IQuaternion|null} [rotation] PresenceData rotation
+             * @property {graphwiz.core.IAvatarConfig|null} [avatarConfig] PresenceData avatarConfig
+             * @property {string|null} [hostClientId] PresenceData hostClientId
+             * @property {string|null} [userId] PresenceData userId
+             * @property {graphwiz.core.PresenceStatus|null} [status] PresenceData status
+             * @property {string|null} [roomId] PresenceData roomId
+             */
+
+            /**
+             * Constructs a new PresenceData.
+             * @memberof graphwiz.core
+             * @classdesc Represents a PresenceData.
+             * @implements IPresenceData
+             * @constructor
+             * @param {graphwiz.core.IPresenceData=} [properties] Properties to set
+             */
+            function PresenceData(properties) {
+                if (properties)
+                    for (let keys = Object.keys(properties), i = 0; i < keys.length; ++i)
+                        if (properties[keys[i]] != null)
+                            this[keys[i]] = properties[keys[i]];
+            }
+
+            /**
+             * PresenceData displayName.
+             * @member {string} displayName
+             * @memberof graphwiz.core.PresenceData
+             * @instance
+             */
+            PresenceData.prototype.displayName = "";
+
+            /**
+             * PresenceData avatarUrl.
+             * @member {string} avatarUrl
+             * @memberof graphwiz.core.PresenceData
+             * @instance
+             */
+            PresenceData.prototype.avatarUrl = "";
+
+            /**
+             * PresenceData position.
+             * @member {graphwiz.core.IVector3|null|undefined} position
+             * @memberof graphwiz.core.PresenceData
+             * @instance
+             */
+            PresenceData.prototype.position = null;
+
+            /**
+             * PresenceData rotation.
+             * @member {graphwiz.core.IQuaternion|null|undefined} rotation
+             * @memberof graphwiz.core.PresenceData
+             * @instance
+             */
+            PresenceData.prototype.rotation = null;
+
+            /**
+             * PresenceData avatarConfig.
+             * @member {graphwiz.core.IAvatarConfig|null|undefined} avatarConfig
+             * @memberof graphwiz.core.PresenceData
+             * @instance
+             */
+            PresenceData.prototype.avatarConfig = null;
+
+            /**
+             * PresenceData hostClientId.
+             * @member {string} hostClientId
+             * @memberof graphwiz.core.PresenceData
+             * @instance
+             */
+            PresenceData.prototype.hostClientId = "";
+
+            /**
+             * PresenceData userId.
+             * @member {string} userId
+             * @memberof graphwiz.core.PresenceData
+             * @instance
+             */
+            PresenceData.prototype.userId = "";
+
+            /**
+             * PresenceData status.
+             * @member {graphwiz.core.PresenceStatus} status
+             * @memberof graphwiz.core.PresenceData
+             * @instance
+             */
+            PresenceData.prototype.status = 0;
+
+            /**
+             * PresenceData roomId.
+             * @member {string} roomId
+             * @memberof graphwiz.core.PresenceData
+             * @instance
+             */
+            PresenceData.prototype.roomId = "";
+
+            /**
+             * Creates a new PresenceData instance using the specified properties.
+             * @function create
+             * @memberof graphwiz.core.PresenceData
+             * @static
+             * @param {graphwiz.core.IPresenceData=} [properties] Properties to set
+             * @returns {graphwiz.core.PresenceData} PresenceData instance
+             */
+            PresenceData.create = function create(properties) {
+                return new PresenceData(properties);
+            };
+
+            /**
+             * Encodes the specified PresenceData message. Does not implicitly {@link graphwiz.core.PresenceData.verify|verify} messages.
+             * @function encode
+             * @memberof graphwiz.core.PresenceData
+             * @static
+             * @param {graphwiz.core.IPresenceData} message PresenceData message or plain object to encode
+             * @param {$protobuf.Writer} [writer] Writer to encode to
+             * @returns {$protobuf.Writer} Writer
+             */
+            PresenceData.encode = function encode(message, writer) {
+                if (!writer)
+                    writer = $Writer.create();
+                if (message.displayName != null && Object.hasOwnProperty.call(message, "displayName"))
+                    writer.uint32(/* id 1, wireType 2 =*/10).string(message.displayName);
+                if (message.avatarUrl != null && Object.hasOwnProperty.call(message, "avatarUrl"))
+                    writer.uint32(/* id 2, wireType 2 =*/18).string(message.avatarUrl);
+                if (message.position != null && Object.hasOwnProperty.call(message, "position"))
+                    $root.graphwiz.core.Vector3.encode(message.position, writer.uint32(/* id 3, wireType 2 =*/26).fork()).ldelim();
+                if (message.rotation != null && Object.hasOwnProperty.call(message, "rotation"))
+                    $root.graphwiz.core.Quaternion.encode(message.rotation, writer.uint32(/* id 4, wireType 2 =*/34).fork()).ldelim();
+                if (message.avatarConfig != null && Object.hasOwnProperty.call(message, "avatarConfig"))
+                    $root.graphwiz.core.AvatarConfig.encode(message.avatarConfig, writer.uint32(/* id 5, wireType 2 =*/42).fork()).ldelim();
+                if (message.hostClientId != null && Object.hasOwnProperty.call(message, "hostClientId"))
+                    writer.uint32(/* id 6, wireType 2 =*/50).string(message.hostClientId);
+                if (message.userId != null && Object.hasOwnProperty.call(message, "userId"))
+                    writer.uint32(/* id 7, wireType 2 =*/58).string(message.userId);
+                if (message.status != null && Object.hasOwnProperty.call(message, "status"))
+                    writer.uint32(/* id 8, wireType 0 =*/64).int32(message.status);
+                if (message.roomId != null && Object.hasOwnProperty.call(message, "roomId"))
+                    writer.uint32(/* id 9, wireType 2 =*/74).string(message.roomId);
+                return writer;
+            };
+
+            /**
+             * Encodes the specified PresenceData message, length delimited. Does not implicitly {@link graphwiz.core.PresenceData.verify|verify} messages.
+             * @function encodeDelimited
+             * @memberof graphwiz.core.PresenceData
+             * @static
+             * @param {graphwiz.core.IPresenceData} message PresenceData message or plain object to encode
+             * @param {$protobuf.Writer} [writer] Writer to encode to
+             * @returns {$protobuf.Writer} Writer
+             */
+            PresenceData.encodeDelimited = function encodeDelimited(message, writer) {
+                return this.encode(message, writer).ldelim();
+            };
+
+            /**
+             * Decodes a PresenceData message from the specified reader or buffer.
+             * @function decode
+             * @memberof graphwiz.core.PresenceData
+             * @static
+             * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
+             * @param {number} [length] Message length if known beforehand
+             * @returns {graphwiz.core.PresenceData} PresenceData
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            PresenceData.decode = function decode(reader, length, error) {
+                if (!(reader instanceof $Reader))
+                    reader = $Reader.create(reader);
+                let end = length === undefined ? reader.len : reader.pos + length, message = new $root.graphwiz.core.PresenceData();
+                while (reader.pos < end) {
+                    let tag = reader.uint32();
+                    if (tag === error)
+                        break;
+                    switch (tag >>> 3) {
+                    case 1: {
+                            message.displayName = reader.string();
+                            break;
+                        }
+                    case 2: {
+                            message.avatarUrl = reader.string();
+                            break;
+                        }
+                    case 3: {
+                            message.position = $root.graphwiz.core.Vector3.decode(reader, reader.uint32());
+                            break;
+                        }
+                    case 4: {
+                            message.rotation = $root.graphwiz.core.Quaternion.decode(reader, reader.uint32());
+                            break;
+                        }
+                    case 5: {
+                            message.avatarConfig = $root.graphwiz.core.AvatarConfig.decode(reader, reader.uint32());
+                            break;
+                        }
+                    case 6: {
+                            message.hostClientId = reader.string();
+                            break;
+                        }
+                    case 7: {
+                            message.userId = reader.string();
+                            break;
+                        }
+                    case 8: {
+                            message.status = reader.int32();
+                            break;
+                        }
+                    case 9: {
+                            message.roomId = reader.string();
+                            break;
                         }
                     default:
                         reader.skipType(tag & 7);
@@ -2793,203 +5152,250 @@ export const graphwiz = $root.graphwiz = (() => {
             };
 
             /**
-             * Decodes a ChatMessage message from the specified reader or buffer, length delimited.
+             * Decodes a PresenceData message from the specified reader or buffer, length delimited.
              * @function decodeDelimited
-             * @memberof graphwiz.core.ChatMessage
+             * @memberof graphwiz.core.PresenceData
              * @static
              * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
-             * @returns {graphwiz.core.ChatMessage} ChatMessage
+             * @returns {graphwiz.core.PresenceData} PresenceData
              * @throws {Error} If the payload is not a reader or valid buffer
              * @throws {$protobuf.util.ProtocolError} If required fields are missing
              */
-            ChatMessage.decodeDelimited = function decodeDelimited(reader) {
+            PresenceData.decodeDelimited = function decodeDelimited(reader) {
                 if (!(reader instanceof $Reader))
                     reader = new $Reader(reader);
                 return this.decode(reader, reader.uint32());
             };
 
             /**
-             * Verifies a ChatMessage message.
+             * Verifies a PresenceData message.
              * @function verify
-             * @memberof graphwiz.core.ChatMessage
+             * @memberof graphwiz.core.PresenceData
              * @static
              * @param {Object.<string,*>} message Plain object to verify
              * @returns {string|null} `null` if valid, otherwise the reason why it is not
              */
-            ChatMessage.verify = function verify(message) {
+            PresenceData.verify = function verify(message) {
                 if (typeof message !== "object" || message === null)
                     return "object expected";
-                if (message.fromClientId != null && message.hasOwnProperty("fromClientId"))
-                    if (!$util.isString(message.fromClientId))
-                        return "fromClientId: string expected";
-                if (message.message != null && message.hasOwnProperty("message"))
-                    if (!$util.isString(message.message))
-                        return "message: string expected";
-                if (message.timestamp != null && message.hasOwnProperty("timestamp"))
-                    if (!$util.isInteger(message.timestamp) && !(message.timestamp && $util.isInteger(message.timestamp.low) && $util.isInteger(message.timestamp.high)))
-                        return "timestamp: integer|Long expected";
-                if (message.type != null && message.hasOwnProperty("type"))
-                    switch (message.type) {
+                if (message.displayName != null && message.hasOwnProperty("displayName"))
+                    if (!$util.isString(message.displayName))
+                        return "displayName: string expected";
+                if (message.avatarUrl != null && message.hasOwnProperty("avatarUrl"))
+                    if (!$util.isString(message.avatarUrl))
+                        return "avatarUrl: string expected";
+                if (message.position != null && message.hasOwnProperty("position")) {
+                    let error = $root.graphwiz.core.Vector3.verify(message.position);
+                    if (error)
+                        return "position." + error;
+                }
+                if (message.rotation != null && message.hasOwnProperty("rotation")) {
+                    let error = $root.graphwiz.core.Quaternion.verify(message.rotation);
+                    if (error)
+                        return "rotation." + error;
+                }
+                if (message.avatarConfig != null && message.hasOwnProperty("avatarConfig")) {
+                    let error = $root.graphwiz.core.AvatarConfig.verify(message.avatarConfig);
+                    if (error)
+                        return "avatarConfig." + error;
+                }
+                if (message.hostClientId != null && message.hasOwnProperty("hostClientId"))
+                    if (!$util.isString(message.hostClientId))
+                        return "hostClientId: string expected";
+                if (message.userId != null && message.hasOwnProperty("userId"))
+                    if (!$util.isString(message.userId))
+                        return "userId: string expected";
+                if (message.status != null && message.hasOwnProperty("status"))
+                    switch (message.status) {
                     default:
-                        return "type: enum value expected";
+                        return "status: enum value expected";
                     case 0:
                     case 1:
                     case 2:
+                    case 3:
                         break;
                     }
+                if (message.roomId != null && message.hasOwnProperty("roomId"))
+                    if (!$util.isString(message.roomId))
+                        return "roomId: string expected";
                 return null;
             };
 
             /**
-             * Creates a ChatMessage message from a plain object. Also converts values to their respective internal types.
+             * Creates a PresenceData message from a plain object. Also converts values to their respective internal types.
              * @function fromObject
-             * @memberof graphwiz.core.ChatMessage
+             * @memberof graphwiz.core.PresenceData
              * @static
              * @param {Object.<string,*>} object Plain object
-             * @returns {graphwiz.core.ChatMessage} ChatMessage
+             * @returns {graphwiz.core.PresenceData} PresenceData
              */
-            ChatMessage.fromObject = function fromObject(object) {
-                if (object instanceof $root.graphwiz.core.ChatMessage)
+            PresenceData.fromObject = function fromObject(object) {
+                if (object instanceof $root.graphwiz.core.PresenceData)
                     return object;
-                let message = new $root.graphwiz.core.ChatMessage();
-                if (object.fromClientId != null)
-                    message.fromClientId = String(object.fromClientId);
-                if (object.message != null)
-                    message.message = String(object.message);
-                if (object.timestamp != null)
-                    if ($util.Long)
-                        (message.timestamp = $util.Long.fromValue(object.timestamp)).unsigned = false;
-                    else if (typeof object.timestamp === "string")
-                        message.timestamp = parseInt(object.timestamp, 10);
-                    else if (typeof object.timestamp === "number")
-                        message.timestamp = object.timestamp;
-                    else if (typeof object.timestamp === "object")
-                        message.timestamp = new $util.LongBits(object.timestamp.low >>> 0, object.timestamp.high >>> 0).toNumber();
-                switch (object.type) {
+                let message = new $root.graphwiz.core.PresenceData();
+                if (object.displayName != null)
+                    message.displayName = String(object.displayName);
+                if (object.avatarUrl != null)
+                    message.avatarUrl = String(object.avatarUrl);
+                if (object.position != null) {
+                    if (typeof object.position !== "object")
+                        throw TypeError(".graphwiz.core.PresenceData.position: object expected");
+                    message.position = $root.graphwiz.core.Vector3.fromObject(object.position);
+                }
+                if (object.rotation != null) {
+                    if (typeof object.rotation !== "object")
+                        throw TypeError(".graphwiz.core.PresenceData.rotation: object expected");
+                    message.rotation = $root.graphwiz.core.Quaternion.fromObject(object.rotation);
+                }
+                if (object.avatarConfig != null) {
+                    if (typeof object.avatarConfig !== "object")
+                        throw TypeError(".graphwiz.core.PresenceData.avatarConfig: object expected");
+                    message.avatarConfig = $root.graphwiz.core.AvatarConfig.fromObject(object.avatarConfig);
+                }
+                if (object.hostClientId != null)
+                    message.hostClientId = String(object.hostClientId);
+                if (object.userId != null)
+                    message.userId = String(object.userId);
+                switch (object.status) {
                 default:
-                    if (typeof object.type === "number") {
-                        message.type = object.type;
+                    if (typeof object.status === "number") {
+                        message.status = object.status;
                         break;
                     }
                     break;
-                case "NORMAL":
+                case "OFFLINE":
                 case 0:
-                    message.type = 0;
+                    message.status = 0;
                     break;
-                case "WHISPER":
+                case "ONLINE":
                 case 1:
-                    message.type = 1;
+                    message.status = 1;
                     break;
-                case "SHOUT":
+                case "AWAY":
                 case 2:
-                    message.type = 2;
+                    message.status = 2;
+                    break;
+                case "BUSY":
+                case 3:
+                    message.status = 3;
                     break;
                 }
+                if (object.roomId != null)
+                    message.roomId = String(object.roomId);
                 return message;
             };
 
             /**
-             * Creates a plain object from a ChatMessage message. Also converts values to other types if specified.
+             * Creates a plain object from a PresenceData message. Also converts values to other types if specified.
              * @function toObject
-             * @memberof graphwiz.core.ChatMessage
+             * @memberof graphwiz.core.PresenceData
              * @static
-             * @param {graphwiz.core.ChatMessage} message ChatMessage
+             * @param {graphwiz.core.PresenceData} message PresenceData
              * @param {$protobuf.IConversionOptions} [options] Conversion options
              * @returns {Object.<string,*>} Plain object
              */
-            ChatMessage.toObject = function toObject(message, options) {
+            PresenceData.toObject = function toObject(message, options) {
                 if (!options)
                     options = {};
                 let object = {};
                 if (options.defaults) {
-                    object.fromClientId = "";
-                    object.message = "";
-                    if ($util.Long) {
-                        let long = new $util.Long(0, 0, false);
-                        object.timestamp = options.longs === String ? long.toString() : options.longs === Number ? long.toNumber() : long;
-                    } else
-                        object.timestamp = options.longs === String ? "0" : 0;
-                    object.type = options.enums === String ? "NORMAL" : 0;
+                    object.displayName = "";
+                    object.avatarUrl = "";
+                    object.position = null;
+                    object.rotation = null;
+                    object.avatarConfig = null;
+                    object.hostClientId = "";
+                    object.userId = "";
+                    object.status = options.enums === String ? "OFFLINE" : 0;
+                    object.roomId = "";
                 }
-                if (message.fromClientId != null && message.hasOwnProperty("fromClientId"))
-                    object.fromClientId = message.fromClientId;
-                if (message.message != null && message.hasOwnProperty("message"))
-                    object.message = message.message;
-                if (message.timestamp != null && message.hasOwnProperty("timestamp"))
-                    if (typeof message.timestamp === "number")
-                        object.timestamp = options.longs === String ? String(message.timestamp) : message.timestamp;
-                    else
-                        object.timestamp = options.longs === String ? $util.Long.prototype.toString.call(message.timestamp) : options.longs === Number ? new $util.LongBits(message.timestamp.low >>> 0, message.timestamp.high >>> 0).toNumber() : message.timestamp;
-                if (message.type != null && message.hasOwnProperty("type"))
-                    object.type = options.enums === String ? $root.graphwiz.core.ChatMessageType[message.type] === undefined ? message.type : $root.graphwiz.core.ChatMessageType[message.type] : message.type;
+                if (message.displayName != null && message.hasOwnProperty("displayName"))
+                    object.displayName = message.displayName;
+                if (message.avatarUrl != null && message.hasOwnProperty("avatarUrl"))
+                    object.avatarUrl = message.avatarUrl;
+                if (message.position != null && message.hasOwnProperty("position"))
+                    object.position = $root.graphwiz.core.Vector3.toObject(message.position, options);
+                if (message.rotation != null && message.hasOwnProperty("rotation"))
+                    object.rotation = $root.graphwiz.core.Quaternion.toObject(message.rotation, options);
+                if (message.avatarConfig != null && message.hasOwnProperty("avatarConfig"))
+                    object.avatarConfig = $root.graphwiz.core.AvatarConfig.toObject(message.avatarConfig, options);
+                if (message.hostClientId != null && message.hasOwnProperty("hostClientId"))
+                    object.hostClientId = message.hostClientId;
+                if (message.userId != null && message.hasOwnProperty("userId"))
+                    object.userId = message.userId;
+                if (message.status != null && message.hasOwnProperty("status"))
+                    object.status = options.enums === String ? $root.graphwiz.core.PresenceStatus[message.status] === undefined ? message.status : $root.graphwiz.core.PresenceStatus[message.status] : message.status;
+                if (message.roomId != null && message.hasOwnProperty("roomId"))
+                    object.roomId = message.roomId;
                 return object;
             };
 
             /**
-             * Converts this ChatMessage to JSON.
+             * Converts this PresenceData to JSON.
              * @function toJSON
-             * @memberof graphwiz.core.ChatMessage
+             * @memberof graphwiz.core.PresenceData
              * @instance
              * @returns {Object.<string,*>} JSON object
              */
-            ChatMessage.prototype.toJSON = function toJSON() {
+            PresenceData.prototype.toJSON = function toJSON() {
                 return this.constructor.toObject(this, $protobuf.util.toJSONOptions);
             };
 
             /**
-             * Gets the default type url for ChatMessage
+             * Gets the default type url for PresenceData
              * @function getTypeUrl
-             * @memberof graphwiz.core.ChatMessage
+             * @memberof graphwiz.core.PresenceData
              * @static
              * @param {string} [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
              * @returns {string} The default type url
              */
-            ChatMessage.getTypeUrl = function getTypeUrl(typeUrlPrefix) {
+            PresenceData.getTypeUrl = function getTypeUrl(typeUrlPrefix) {
                 if (typeUrlPrefix === undefined) {
                     typeUrlPrefix = "type.googleapis.com";
                 }
-                return typeUrlPrefix + "/graphwiz.core.ChatMessage";
+                return typeUrlPrefix + "/graphwiz.core.PresenceData";
             };
 
-            return ChatMessage;
+            return PresenceData;
         })();
 
         /**
-         * ChatMessageType enum.
-         * @name graphwiz.core.ChatMessageType
+         * PresenceStatus enum.
+         * @name graphwiz.core.PresenceStatus
          * @enum {number}
-         * @property {number} NORMAL=0 NORMAL value
-         * @property {number} WHISPER=1 WHISPER value
-         * @property {number} SHOUT=2 SHOUT value
+         * @property {number} OFFLINE=0 OFFLINE value
+         * @property {number} ONLINE=1 ONLINE value
+         * @property {number} AWAY=2 AWAY value
+         * @property {number} BUSY=3 BUSY value
          */
-        core.ChatMessageType = (function() {
+        core.PresenceStatus = (function() {
             const valuesById = {}, values = Object.create(valuesById);
-            values[valuesById[0] = "NORMAL"] = 0;
-            values[valuesById[1] = "WHISPER"] = 1;
-            values[valuesById[2] = "SHOUT"] = 2;
+            values[valuesById[0] = "OFFLINE"] = 0;
+            values[valuesById[1] = "ONLINE"] = 1;
+            values[valuesById[2] = "AWAY"] = 2;
+            values[valuesById[3] = "BUSY"] = 3;
             return values;
         })();
 
-        core.PresenceEvent = (function() {
+        core.PresenceSubscription = (function() {
 
             /**
-             * Properties of a PresenceEvent.
+             * Properties of a PresenceSubscription.
              * @memberof graphwiz.core
-             * @interface IPresenceEvent
-             * @property {string|null} [clientId] PresenceEvent clientId
-             * @property {graphwiz.core.PresenceEventType|null} [eventType] PresenceEvent eventType
-             * @property {graphwiz.core.IPresenceData|null} [data] PresenceEvent data
+             * @interface IPresenceSubscription
+             * @property {Array.<string>|null} [userIds] PresenceSubscription userIds
              */
 
             /**
-             * Constructs a new PresenceEvent.
+             * Constructs a new PresenceSubscription.
              * @memberof graphwiz.core
-             * @classdesc Represents a PresenceEvent.
-             * @implements IPresenceEvent
+             * @classdesc Represents a PresenceSubscription.
+             * @implements IPresenceSubscription
              * @constructor
-             * @param {graphwiz.core.IPresenceEvent=} [properties] Properties to set
+             * @param {graphwiz.core.IPresenceSubscription=} [properties] Properties to set
              */
-            function PresenceEvent(properties) {
+            function PresenceSubscription(properties) {
+                this.userIds = [];
                 if (properties)
                     for (let keys = Object.keys(properties), i = 0; i < keys.length; ++i)
                         if (properties[keys[i]] != null)
@@ -2997,105 +5403,80 @@ export const graphwiz = $root.graphwiz = (() => {
             }
 
             /**
-             * PresenceEvent clientId.
-             * @member {string} clientId
-             * @memberof graphwiz.core.PresenceEvent
-             * @instance
-             */
-            PresenceEvent.prototype.clientId = "";
-
-            /**
-             * PresenceEvent eventType.
-             * @member {graphwiz.core.PresenceEventType} eventType
-             * @memberof graphwiz.core.PresenceEvent
-             * @instance
-             */
-            PresenceEvent.prototype.eventType = 0;
-
-            /**
-             * PresenceEvent data.
-             * @member {graphwiz.core.IPresenceData|null|undefined} data
-             * @memberof graphwiz.core.PresenceEvent
+             * PresenceSubscription userIds.
+             * @member {Array.<string>} userIds
+             * @memberof graphwiz.core.PresenceSubscription
              * @instance
              */
-            PresenceEvent.prototype.data = null;
+            PresenceSubscription.prototype.userIds = $util.emptyArray;
 
             /**
-             * Creates a new PresenceEvent instance using the specified properties.
+             * Creates a new PresenceSubscription instance using the specified properties.
              * @function create
-             * @memberof graphwiz.core.PresenceEvent
+             * @memberof graphwiz.core.PresenceSubscription
              * @static
-             * @param {graphwiz.core.IPresenceEvent=} [properties] Properties to set
-             * @returns {graphwiz.core.PresenceEvent} PresenceEvent instance
+             * @param {graphwiz.core.IPresenceSubscription=} [properties] Properties to set
+             * @returns {graphwiz.core.PresenceSubscription} PresenceSubscription instance
              */
-            PresenceEvent.create = function create(properties) {
-                return new PresenceEvent(properties);
+            PresenceSubscription.create = function create(properties) {
+                return new PresenceSubscription(properties);
             };
 
             /**
-             * Encodes the specified PresenceEvent message. Does not implicitly {@link graphwiz.core.PresenceEvent.verify|verify} messages.
+             * Encodes the specified PresenceSubscription message. Does not implicitly {@link graphwiz.core.PresenceSubscription.verify|verify} messages.
              * @function encode
-             * @memberof graphwiz.core.PresenceEvent
+             * @memberof graphwiz.core.PresenceSubscription
              * @static
-             * @param {graphwiz.core.IPresenceEvent} message PresenceEvent message or plain object to encode
+             * @param {graphwiz.core.IPresenceSubscription} message PresenceSubscription message or plain object to encode
              * @param {$protobuf.Writer} [writer] Writer to encode to
              * @returns {$protobuf.Writer} Writer
              */
-            PresenceEvent.encode = function encode(message, writer) {
+            PresenceSubscription.encode = function encode(message, writer) {
                 if (!writer)
                     writer = $Writer.create();
-                if (message.clientId != null && Object.hasOwnProperty.call(message, "clientId"))
-                    writer.uint32(/* id 1, wireType 2 =*/10).string(message.clientId);
-                if (message.eventType != null && Object.hasOwnProperty.call(message, "eventType"))
-                    writer.uint32(/* id 2, wireType 0 =*/16).int32(message.eventType);
-                if (message.data != null && Object.hasOwnProperty.call(message, "data"))
-                    $root.graphwiz.core.PresenceData.encode(message.data, writer.uint32(/* id 3, wireType 2 =*/26).fork()).ldelim();
+                if (message.userIds != null && message.userIds.length)
+                    for (let i = 0; i < message.userIds.length; ++i)
+                        writer.uint32(/* id 1, wireType 2 =*/10).string(message.userIds[i]);
                 return writer;
             };
 
             /**
-             * Encodes the specified PresenceEvent message, length delimited. Does not implicitly {@link graphwiz.core.PresenceEvent.verify|verify} messages.
+             * Encodes the specified PresenceSubscription message, length delimited. Does not implicitly {@link graphwiz.core.PresenceSubscription.verify|verify} messages.
              * @function encodeDelimited
-             * @memberof graphwiz.core.PresenceEvent
+             * @memberof graphwiz.core.PresenceSubscription
              * @static
-             * @param {graphwiz.core.IPresenceEvent} message PresenceEvent message or plain object to encode
+             * @param {graphwiz.core.IPresenceSubscription} message PresenceSubscription message or plain object to encode
              * @param {$protobuf.Writer} [writer] Writer to encode to
              * @returns {$protobuf.Writer} Writer
              */
-            PresenceEvent.encodeDelimited = function encodeDelimited(message, writer) {
+            PresenceSubscription.encodeDelimited = function encodeDelimited(message, writer) {
                 return this.encode(message, writer).ldelim();
             };
 
             /**
-             * Decodes a PresenceEvent message from the specified reader or buffer.
+             * Decodes a PresenceSubscription message from the specified reader or buffer.
              * @function decode
-             * @memberof graphwiz.core.PresenceEvent
+             * @memberof graphwiz.core.PresenceSubscription
              * @static
              * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
              * @param {number} [length] Message length if known beforehand
-             * @returns {graphwiz.core.PresenceEvent} PresenceEvent
+             * @returns {graphwiz.core.PresenceSubscription} PresenceSubscription
              * @throws {Error} If the payload is not a reader or valid buffer
              * @throws {$protobuf.util.ProtocolError} If required fields are missing
              */
-            PresenceEvent.decode = function decode(reader, length, error) {
+            PresenceSubscription.decode = function decode(reader, length, error) {
                 if (!(reader instanceof $Reader))
                     reader = $Reader.create(reader);
-                let end = length === undefined ? reader.len : reader.pos + length, message = new $root.graphwiz.core.PresenceEvent();
+                let end = length === undefined ? reader.len : reader.pos + length, message = new $root.graphwiz.core.PresenceSubscription();
                 while (reader.pos < end) {
                     let tag = reader.uint32();
                     if (tag === error)
                         break;
                     switch (tag >>> 3) {
                     case 1: {
-                            message.clientId = reader.string();
-                            break;
-                        }
-                    case 2: {
-                            message.eventType = reader.int32();
-                            break;
-                        }
-                    case 3: {
-                            message.data = $root.graphwiz.core.PresenceData.decode(reader, reader.uint32());
+                            if (!(message.userIds && message.userIds.length))
+                                message.userIds = [];
+                            message.userIds.push(reader.string());
                             break;
                         }
                     default:
@@ -3107,189 +5488,140 @@ export const graphwiz = $root.graphwiz = (() => {
             };
 
             /**
-             * Decodes a PresenceEvent message from the specified reader or buffer, length delimited.
+             * Decodes a PresenceSubscription message from the specified reader or buffer, length delimited.
              * @function decodeDelimited
-             * @memberof graphwiz.core.PresenceEvent
+             * @memberof graphwiz.core.PresenceSubscription
              * @static
              * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
-             * @returns {graphwiz.core.PresenceEvent} PresenceEvent
+             * @returns {graphwiz.core.PresenceSubscription} PresenceSubscription
              * @throws {Error} If the payload is not a reader or valid buffer
              * @throws {$protobuf.util.ProtocolError} If required fields are missing
              */
-            PresenceEvent.decodeDelimited = function decodeDelimited(reader) {
+            PresenceSubscription.decodeDelimited = function decodeDelimited(reader) {
                 if (!(reader instanceof $Reader))
                     reader = new $Reader(reader);
                 return this.decode(reader, reader.uint32());
             };
 
             /**
-             * Verifies a PresenceEvent message.
+             * Verifies a PresenceSubscription message.
              * @function verify
-             * @memberof graphwiz.core.PresenceEvent
+             * @memberof graphwiz.core.PresenceSubscription
              * @static
              * @param {Object.<string,*>} message Plain object to verify
              * @returns {string|null} `null` if valid, otherwise the reason why it is not
              */
-            PresenceEvent.verify = function verify(message) {
+            PresenceSubscription.verify = function verify(message) {
                 if (typeof message !== "object" || message === null)
                     return "object expected";
-                if (message.clientId != null && message.hasOwnProperty("clientId"))
-                    if (!$util.isString(message.clientId))
-                        return "clientId: string expected";
-                if (message.eventType != null && message.hasOwnProperty("eventType"))
-                    switch (message.eventType) {
-                    default:
-                        return "eventType: enum value expected";
-                    case 0:
-                    case 1:
-                    case 2:
-                        break;
-                    }
-                if (message.data != null && message.hasOwnProperty("data")) {
-                    let error = $root.graphwiz.core.PresenceData.verify(message.data);
-                    if (error)
-                        return "data." + error;
-                }
-                return null;
-            };
-
-            /**
-             * Creates a PresenceEvent message from a plain object. Also converts values to their respective internal types.
-             * @function fromObject
-             * @memberof graphwiz.core.PresenceEvent
-             * @static
-             * @param {Object.<string,*>} object Plain object
-             * @returns {graphwiz.core.PresenceEvent} PresenceEvent
-             */
-            PresenceEvent.fromObject = function fromObject(object) {
-                if (object instanceof $root.graphwiz.core.PresenceEvent)
-                    return object;
-                let message = new $root.graphwiz.core.PresenceEvent();
-                if (object.clientId != null)
-                    message.clientId = String(object.clientId);
-                switch (object.eventType) {
-                default:
-                    if (typeof object.eventType === "number") {
-                        message.eventType = object.eventType;
-                        break;
-                    }
-                    break;
-                case "JOIN":
-                case 0:
-                    message.eventType = 0;
-                    break;
-                case "LEAVE":
-                case 1:
-                    message.eventType = 1;
-                    break;
-                case "UPDATE":
-                case 2:
-                    message.eventType = 2;
-                    break;
+                if (message.userIds != null && message.hasOwnProperty("userIds")) {
+                    if (!Array.isArray(message.userIds))
+                        return "userIds: array expected";
+                    for (let i = 0; i < message.userIds.length; ++i)
+                        if (!$util.isString(message.userIds[i]))
+                            return "userIds: string[] expected";
                 }
-                if (object.data != null) {
-                    if (typeof object.data !== "object")
-                        throw TypeError(".graphwiz.core.PresenceEvent.data: object expected");
-                    message.data = $root.graphwiz.core.PresenceData.fromObject(object.data);
+                return null;
+            };
+
+            /**
+             * Creates a PresenceSubscription message from a plain object. Also converts values to their respective internal types.
+             * @function fromObject
+             * @memberof graphwiz.core.PresenceSubscription
+             * @static
+             * @param {Object.<string,*>} object Plain object
+             * @returns {graphwiz.core.PresenceSubscription} PresenceSubscription
+             */
+            PresenceSubscription.fromObject = function fromObject(object) {
+                if (object instanceof $root.graphwiz.core.PresenceSubscription)
+                    return object;
+                let message = new $root.graphwiz.core.PresenceSubscription();
+                if (object.userIds) {
+                    if (!Array.isArray(object.userIds))
+                        throw TypeError(".graphwiz.core.PresenceSubscription.userIds: array expected");
+                    message.userIds = [];
+                    for (let i = 0; i < object.userIds.length; ++i)
+                        message.userIds[i] = String(object.userIds[i]);
                 }
                 return message;
             };
 
             /**
-             * Creates a plain object from a PresenceEvent message. Also converts values to other types if specified.
+             * Creates a plain object from a PresenceSubscription message. Also converts values to other types if specified.
              * @function toObject
-             * @memberof graphwiz.core.PresenceEvent
+             * @memberof graphwiz.core.PresenceSubscription
              * @static
-             * @param {graphwiz.core.PresenceEvent} message PresenceEvent
+             * @param {graphwiz.core.PresenceSubscription} message PresenceSubscription
              * @param {$protobuf.IConversionOptions} [options] Conversion options
              * @returns {Object.<string,*>} Plain object
              */
-            PresenceEvent.toObject = function toObject(message, options) {
+            PresenceSubscription.toObject = function toObject(message, options) {
                 if (!options)
                     options = {};
                 let object = {};
-                if (options.defaults) {
-                    object.clientId = "";
-                    object.eventType = options.enums === String ? "JOIN" : 0;
-                    object.data = null;
+                if (options.arrays || options.defaults)
+                    object.userIds = [];
+                if (message.userIds && message.userIds.length) {
+                    object.userIds = [];
+                    for (let j = 0; j < message.userIds.length; ++j)
+                        object.userIds[j] = message.userIds[j];
                 }
-                if (message.clientId != null && message.hasOwnProperty("clientId"))
-                    object.clientId = message.clientId;
-                if (message.eventType != null && message.hasOwnProperty("eventType"))
-                    object.eventType = options.enums === String ? $root.graphwiz.core.PresenceEventType[message.eventType] === undefined ? message.eventType : $root.graphwiz.core.PresenceEventType[message.eventType] : message.eventType;
-                if (message.data != null && message.hasOwnProperty("data"))
-                    object.data = $root.graphwiz.core.PresenceData.toObject(message.data, options);
                 return object;
             };
 
             /**
-             * Converts this PresenceEvent to JSON.
+             * Converts this PresenceSubscription to JSON.
              * @function toJSON
-             * @memberof graphwiz.core.PresenceEvent
+             * @memberof graphwiz.core.PresenceSubscription
              * @instance
              * @returns {Object.<string,*>} JSON object
              */
-            PresenceEvent.prototype.toJSON = function toJSON() {
+            PresenceSubscription.prototype.toJSON = function toJSON() {
                 return this.constructor.toObject(this, $protobuf.util.toJSONOptions);
             };
 
             /**
-             * Gets the default type url for PresenceEvent
+             * Gets the default type url for PresenceSubscription
              * @function getTypeUrl
-             * @memberof graphwiz.core.PresenceEvent
+             * @memberof graphwiz.core.PresenceSubscription
              * @static
              * @param {string} [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
              * @returns {string} The default type url
              */
-            PresenceEvent.getTypeUrl = function getTypeUrl(typeUrlPrefix) {
+            PresenceSubscription.getTypeUrl = function getTypeUrl(typeUrlPrefix) {
                 if (typeUrlPrefix === undefined) {
                     typeUrlPrefix = "type.googleapis.com";
                 }
-                return typeUrlPrefix + "/graphwiz.core.PresenceEvent";
+                return typeUrlPrefix + "/graphwiz.core.PresenceSubscription";
             };
 
-            return PresenceEvent;
-        })();
-
-        /**
-         * PresenceEventType enum.
-         * @name graphwiz.core.PresenceEventType
-         * @enum {number}
-         * @property {number} JOIN=0 JOIN value
-         * @property {number} LEAVE=1 LEAVE value
-         * @property {number} UPDATE=2 UPDATE value
-         */
-        core.PresenceEventType = (function() {
-            const valuesById = {}, values = Object.create(valuesById);
-            values[valuesById[0] = "JOIN"] = 0;
-            values[valuesById[1] = "LEAVE"] = 1;
-            values[valuesById[2] = "UPDATE"] = 2;
-            return values;
+            return PresenceSubscription;
         })();
 
-        core.PresenceData = (function() {
+        core.RtcSignal = (function() {
 
             /**
-             * Properties of a PresenceData.
+             * Properties of a RtcSignal.
              * @memberof graphwiz.core
-             * @interface IPresenceData
-             * @property {string|null} [displayName] PresenceData displayName
-             * @property {string|null} [avatarUrl] PresenceData avatarUrl
-             * @property {graphwiz.core.IVector3|null} [position] PresenceData position
-             * @property {graphwiz.core.IQuaternion|null} [rotation] PresenceData rotation
-             * @property {graphwiz.core.IAvatarConfig|null} [avatarConfig] PresenceData avatarConfig
-             * @property {string|null} [hostClientId] PresenceData hostClientId
+             * @interface IRtcSignal
+             * @property {graphwiz.core.RtcSignalType|null} [signalType] RtcSignal signalType
+             * @property {string|null} [fromClientId] RtcSignal fromClientId
+             * @property {string|null} [toClientId] RtcSignal toClientId
+             * @property {string|null} [sdp] RtcSignal sdp
+             * @property {string|null} [candidate] RtcSignal candidate
+             * @property {string|null} [sdpMid] RtcSignal sdpMid
+             * @property {number|null} [sdpMlineIndex] RtcSignal sdpMlineIndex
              */
 
             /**
-             * Constructs a new PresenceData.
+             * Constructs a new RtcSignal.
              * @memberof graphwiz.core
-             * @classdesc Represents a PresenceData.
-             * @implements IPresenceData
+             * @classdesc Represents a RtcSignal.
+             * @implements IRtcSignal
              * @constructor
-             * @param {graphwiz.core.IPresenceData=} [properties] Properties to set
+             * @param {graphwiz.core.IRtcSignal=} [properties] Properties to set
              */
-            function PresenceData(properties) {
+            function RtcSignal(properties) {
                 if (properties)
                     for (let keys = Object.keys(properties), i = 0; i < keys.length; ++i)
                         if (properties[keys[i]] != null)
@@ -3297,147 +5629,161 @@ export const graphwiz = $root.graphwiz = (() => {
             }
 
             /**
-             * PresenceData displayName.
-             * @member {string} displayName
-             * @memberof graphwiz.core.PresenceData
+             * RtcSignal signalType.
+             * @member {graphwiz.core.RtcSignalType} signalType
+             * @memberof graphwiz.core.RtcSignal
              * @instance
              */
-            PresenceData.prototype.displayName = "";
+            RtcSignal.prototype.signalType = 0;
 
             /**
-             * PresenceData avatarUrl.
-             * @member {string} avatarUrl
-             * @memberof graphwiz.core.PresenceData
+             * RtcSignal fromClientId.
+             * @member {string} fromClientId
+             * @memberof graphwiz.core.RtcSignal
              * @instance
              */
-            PresenceData.prototype.avatarUrl = "";
+            RtcSignal.prototype.fromClientId = "";
 
             /**
-             * PresenceData position.
-             * @member {graphwiz.core.IVector3|null|undefined} position
-             * @memberof graphwiz.core.PresenceData
+             * RtcSignal toClientId.
+             * @member {string} toClientId
+             * @memberof graphwiz.core.RtcSignal
              * @instance
              */
-            PresenceData.prototype.position = null;
+            RtcSignal.prototype.toClientId = "";
 
             /**
-             * PresenceData rotation.
-             * @member {graphwiz.core.IQuaternion|null|undefined} rotation
-             * @memberof graphwiz.core.PresenceData
+             * RtcSignal sdp.
+             * @member {string} sdp
+             * @memberof graphwiz.core.RtcSignal
              * @instance
              */
-            PresenceData.prototype.rotation = null;
+            RtcSignal.prototype.sdp = "";
 
             /**
-             * PresenceData avatarConfig.
-             * @member {graphwiz.core.IAvatarConfig|null|undefined} avatarConfig
-             * @memberof graphwiz.core.PresenceData
+             * RtcSignal candidate.
+             * @member {string} candidate
+             * @memberof graphwiz.core.RtcSignal
              * @instance
              */
-            PresenceData.prototype.avatarConfig = null;
+            RtcSignal.prototype.candidate = "";
 
             /**
-             * PresenceData hostClientId.
-             * @member {string} hostClientId
-             * @memberof graphwiz.core.PresenceData
+             * RtcSignal sdpMid.
+             * @member {string} sdpMid
+             * @memberof graphwiz.core.RtcSignal
              * @instance
              */
-            PresenceData.prototype.hostClientId = "";
+            RtcSignal.prototype.sdpMid = "";
 
             /**
-             * Creates a new PresenceData instance using the specified properties.
+             * RtcSignal sdpMlineIndex.
+             * @member {number} sdpMlineIndex
+             * @memberof graphwiz.core.RtcSignal
+             * @instance
+             */
+            RtcSignal.prototype.sdpMlineIndex = 0;
+
+            /**
+             * Creates a new RtcSignal instance using the specified properties.
              * @function create
-             * @memberof graphwiz.core.PresenceData
+             * @memberof graphwiz.core.RtcSignal
              * @static
-             * @param {graphwiz.core.IPresenceData=} [properties] Properties to set
-             * @returns {graphwiz.core.PresenceData} PresenceData instance
+             * @param {graphwiz.core.IRtcSignal=} [properties] Properties to set
+             * @returns {graphwiz.core.RtcSignal} RtcSignal instance
              */
-            PresenceData.create = function create(properties) {
-                return new PresenceData(properties);
+            RtcSignal.create = function create(properties) {
+                return new RtcSignal(properties);
             };
 
             /**
-             * Encodes the specified PresenceData message. Does not implicitly {@link graphwiz.core.PresenceData.verify|verify} messages.
+             * Encodes the specified RtcSignal message. Does not implicitly {@link graphwiz.core.RtcSignal.verify|verify} messages.
              * @function encode
-             * @memberof graphwiz.core.PresenceData
+             * @memberof graphwiz.core.RtcSignal
              * @static
-             * @param {graphwiz.core.IPresenceData} message PresenceData message or plain object to encode
+             * @param {graphwiz.core.IRtcSignal} message RtcSignal message or plain object to encode
              * @param {$protobuf.Writer} [writer] Writer to encode to
              * @returns {$protobuf.Writer} Writer
              */
-            PresenceData.encode = function encode(message, writer) {
+            RtcSignal.encode = function encode(message, writer) {
                 if (!writer)
                     writer = $Writer.create();
-                if (message.displayName != null && Object.hasOwnProperty.call(message, "displayName"))
-                    writer.uint32(/* id 1, wireType 2 =*/10).string(message.displayName);
-                if (message.avatarUrl != null && Object.hasOwnProperty.call(message, "avatarUrl"))
-                    writer.uint32(/* id 2, wireType 2 =*/18).string(message.avatarUrl);
-                if (message.position != null && Object.hasOwnProperty.call(message, "position"))
-                    $root.graphwiz.core.Vector3.encode(message.position, writer.uint32(/* id 3, wireType 2 =*/26).fork()).ldelim();
-                if (message.rotation != null && Object.hasOwnProperty.call(message, "rotation"))
-                    $root.graphwiz.core.Quaternion.encode(message.rotation, writer.uint32(/* id 4, wireType 2 =*/34).fork()).ldelim();
-                if (message.avatarConfig != null && Object.hasOwnProperty.call(message, "avatarConfig"))
-                    $root.graphwiz.core.AvatarConfig.encode(message.avatarConfig, writer.uint32(/* id 5, wireType 2 =*/42).fork()).ldelim();
-                if (message.hostClientId != null && Object.hasOwnProperty.call(message, "hostClientId"))
-                    writer.uint32(/* id 6, wireType 2 =*/50).string(message.hostClientId);
+                if (message.signalType != null && Object.hasOwnProperty.call(message, "signalType"))
+                    writer.uint32(/* id 1, wireType 0 =*/8).int32(message.signalType);
+                if (message.fromClientId != null && Object.hasOwnProperty.call(message, "fromClientId"))
+                    writer.uint32(/* id 2, wireType 2 =*/18).string(message.fromClientId);
+                if (message.toClientId != null && Object.hasOwnProperty.call(message, "toClientId"))
+                    writer.uint32(/* id 3, wireType 2 =*/26).string(message.toClientId);
+                if (message.sdp != null && Object.hasOwnProperty.call(message, "sdp"))
+                    writer.uint32(/* id 4, wireType 2 =*/34).string(message.sdp);
+                if (message.candidate != null && Object.hasOwnProperty.call(message, "candidate"))
+                    writer.uint32(/* id 5, wireType 2 =*/42).string(message.candidate);
+                if (message.sdpMid != null && Object.hasOwnProperty.call(message, "sdpMid"))
+                    writer.uint32(/* id 6, wireType 2 =*/50).string(message.sdpMid);
+                if (message.sdpMlineIndex != null && Object.hasOwnProperty.call(message, "sdpMlineIndex"))
+                    writer.uint32(/* id 7, wireType 0 =*/56).uint32(message.sdpMlineIndex);
                 return writer;
             };
 
             /**
-             * Encodes the specified PresenceData message, length delimited. Does not implicitly {@link graphwiz.core.PresenceData.verify|verify} messages.
+             * Encodes the specified RtcSignal message, length delimited. Does not implicitly {@link graphwiz.core.RtcSignal.verify|verify} messages.
              * @function encodeDelimited
-             * @memberof graphwiz.core.PresenceData
+             * @memberof graphwiz.core.RtcSignal
              * @static
-             * @param {graphwiz.core.IPresenceData} message PresenceData message or plain object to encode
+             * @param {graphwiz.core.IRtcSignal} message RtcSignal message or plain object to encode
              * @param {$protobuf.Writer} [writer] Writer to encode to
              * @returns {$protobuf.Writer} Writer
              */
-            PresenceData.encodeDelimited = function encodeDelimited(message, writer) {
+            RtcSignal.encodeDelimited = function encodeDelimited(message, writer) {
                 return this.encode(message, writer).ldelim();
             };
 
             /**
-             * Decodes a PresenceData message from the specified reader or buffer.
+             * Decodes a RtcSignal message from the specified reader or buffer.
              * @function decode
-             * @memberof graphwiz.core.PresenceData
+             * @memberof graphwiz.core.RtcSignal
              * @static
              * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
              * @param {number} [length] Message length if known beforehand
-             * @returns {graphwiz.core.PresenceData} PresenceData
+             * @returns {graphwiz.core.RtcSignal} RtcSignal
              * @throws {Error} If the payload is not a reader or valid buffer
              * @throws {$protobuf.util.ProtocolError} If required fields are missing
              */
-            PresenceData.decode = function decode(reader, length, error) {
+            RtcSignal.decode = function decode(reader, length, error) {
                 if (!(reader instanceof $Reader))
                     reader = $Reader.create(reader);
-                let end = length === undefined ? reader.len : reader.pos + length, message = new $root.graphwiz.core.PresenceData();
+                let end = length === undefined ? reader.len : reader.pos + length, message = new $root.graphwiz.core.RtcSignal();
                 while (reader.pos < end) {
                     let tag = reader.uint32();
                     if (tag === error)
                         break;
                     switch (tag >>> 3) {
                     case 1: {
-                            message.displayName = reader.string();
+                            message.signalType = reader.int32();
                             break;
                         }
                     case 2: {
-                            message.avatarUrl = reader.string();
+                            message.fromClientId = reader.string();
                             break;
                         }
                     case 3: {
-                            message.position = $root.graphwiz.core.Vector3.decode(reader, reader.uint32());
+                            message.toClientId = reader.string();
                             break;
                         }
                     case 4: {
-                            message.rotation = $root.graphwiz.core.Quaternion.decode(reader, reader.uint32());
+                            message.sdp = reader.string();
                             break;
                         }
                     case 5: {
-                            message.avatarConfig = $root.graphwiz.core.AvatarConfig.decode(reader, reader.uint32());
+                            message.candidate = reader.string();
                             break;
                         }
                     case 6: {
-                            message.hostClientId = reader.string();
+                            message.sdpMid = reader.string();
+                            break;
+                        }
+                    case 7: {
+                            message.sdpMlineIndex = reader.uint32();
                             break;
                         }
                     default:
@@ -3449,158 +5795,198 @@ export const graphwiz = $root.graphwiz = (() => {
             };
 
             /**
-             * Decodes a PresenceData message from the specified reader or buffer, length delimited.
+             * Decodes a RtcSignal message from the specified reader or buffer, length delimited.
              * @function decodeDelimited
-             * @memberof graphwiz.core.PresenceData
+             * @memberof graphwiz.core.RtcSignal
              * @static
              * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
-             * @returns {graphwiz.core.PresenceData} PresenceData
+             * @returns {graphwiz.core.RtcSignal} RtcSignal
              * @throws {Error} If the payload is not a reader or valid buffer
              * @throws {$protobuf.util.ProtocolError} If required fields are missing
              */
-            PresenceData.decodeDelimited = function decodeDelimited(reader) {
+            RtcSignal.decodeDelimited = function decodeDelimited(reader) {
                 if (!(reader instanceof $Reader))
                     reader = new $Reader(reader);
                 return this.decode(reader, reader.uint32());
             };
 
             /**
-             * Verifies a PresenceData message.
+             * Verifies a RtcSignal message.
              * @function verify
-             * @memberof graphwiz.core.PresenceData
+             * @memberof graphwiz.core.RtcSignal
              * @static
              * @param {Object.<string,*>} message Plain object to verify
              * @returns {string|null} `null` if valid, otherwise the reason why it is not
              */
-            PresenceData.verify = function verify(message) {
+            RtcSignal.verify = function verify(message) {
                 if (typeof message !== "object" || message === null)
                     return "object expected";
-                if (message.displayName != null && message.hasOwnProperty("displayName"))
-                    if (!$util.isString(message.displayName))
-                        return "displayName: string expected";
-                if (message.avatarUrl != null && message.hasOwnProperty("avatarUrl"))
-                    if (!$util.isString(message.avatarUrl))
-                        return "avatarUrl: string expected";
-                if (message.position != null && message.hasOwnProperty("position")) {
-                    let error = $root.graphwiz.core.Vector3.verify(message.position);
-                    if (error)
-                        return "position." + error;
-                }
-                if (message.rotation != null && message.hasOwnProperty("rotation")) {
-                    let error = $root.graphwiz.core.Quaternion.verify(message.rotation);
-                    if (error)
-                        return "rotation." + error;
-                }
-                if (message.avatarConfig != null && message.hasOwnProperty("avatarConfig")) {
-                    let error = $root.graphwiz.core.AvatarConfig.verify(message.avatarConfig);
-                    if (error)
-                        return "avatarConfig." + error;
-                }
-                if (message.hostClientId != null && message.hasOwnProperty("hostClientId"))
-                    if (!$util.isString(message.hostClientId))
-                        return "hostClientId: string expected";
+                if (message.signalType != null && message.hasOwnProperty("signalType"))
+                    switch (message.signalType) {
+                    default:
+                        return "signalType: enum value expected";
+                    case 0:
+                    case 1:
+                    case 2:
+                    case 3:
+                        break;
+                    }
+                if (message.fromClientId != null && message.hasOwnProperty("fromClientId"))
+                    if (!$util.isString(message.fromClientId))
+                        return "fromClientId: string expected";
+                if (message.toClientId != null && message.hasOwnProperty("toClientId"))
+                    if (!$util.isString(message.toClientId))
+                        return "toClientId: string expected";
+                if (message.sdp != null && message.hasOwnProperty("sdp"))
+                    if (!$util.isString(message.sdp))
+                        return "sdp: string expected";
+                if (message.candidate != null && message.hasOwnProperty("candidate"))
+                    if (!$util.isString(message.candidate))
+                        return "candidate: string expected";
+                if (message.sdpMid != null && message.hasOwnProperty("sdpMid"))
+                    if (!$util.isString(message.sdpMid))
+                        return "sdpMid: string expected";
+                if (message.sdpMlineIndex != null && message.hasOwnProperty("sdpMlineIndex"))
+                    if (!$util.isInteger(message.sdpMlineIndex))
+                        return "sdpMlineIndex: integer expected";
                 return null;
             };
 
             /**
-             * Creates a PresenceData message from a plain object. Also converts values to their respective internal types.
+             * Creates a RtcSignal message from a plain object. Also converts values to their respective internal types.
              * @function fromObject
-             * @memberof graphwiz.core.PresenceData
+             * @memberof graphwiz.core.RtcSignal
              * @static
              * @param {Object.<string,*>} object Plain object
-             * @returns {graphwiz.core.PresenceData} PresenceData
+             * @returns {graphwiz.core.RtcSignal} RtcSignal
              */
-            PresenceData.fromObject = function fromObject(object) {
-                if (object instanceof $root.graphwiz.core.PresenceData)
+            RtcSignal.fromObject = function fromObject(object) {
+                if (object instanceof $root.graphwiz.core.RtcSignal)
                     return object;
-                let message = new $root.graphwiz.core.PresenceData();
-                if (object.displayName != null)
-                    message.displayName = String(object.displayName);
-                if (object.avatarUrl != null)
-                    message.avatarUrl = String(object.avatarUrl);
-                if (object.position != null) {
-                    if (typeof object.position !== "object")
-                        throw TypeError(".graphwiz.core.PresenceData.position: object expected");
-                    message.position = $root.graphwiz.core.Vector3.fromObject(object.position);
-                }
-                if (object.rotation != null) {
-                    if (typeof object.rotation !== "object")
-                        throw TypeError(".graphwiz.core.PresenceData.rotation: object expected");
-                    message.rotation = $root.graphwiz.core.Quaternion.fromObject(object.rotation);
-                }
-                if (object.avatarConfig != null) {
-                    if (typeof object.avatarConfig !== "object")
-                        throw TypeError(".graphwiz.core.PresenceData.avatarConfig: object expected");
-                    message.avatarConfig = $root.graphwiz.core.AvatarConfig.fromObject(object.avatarConfig);
+                let message = new $root.graphwiz.core.RtcSignal();
+                switch (object.signalType) {
+                default:
+                    if (typeof object.signalType === "number") {
+                        message.signalType = object.signalType;
+                        break;
+                    }
+                    break;
+                case "OFFER":
+                case 0:
+                    message.signalType = 0;
+                    break;
+                case "ANSWER":
+                case 1:
+                    message.signalType = 1;
+                    break;
+                case "ICE_CANDIDATE":
+                case 2:
+                    message.signalType = 2;
+                    break;
+                case "HANGUP":
+                case 3:
+                    message.signalType = 3;
+                    break;
                 }
-                if (object.hostClientId != null)
-                    message.hostClientId = String(object.hostClientId);
+                if (object.fromClientId != null)
+                    message.fromClientId = String(object.fromClientId);
+                if (object.toClientId != null)
+                    message.toClientId = String(object.toClientId);
+                if (object.sdp != null)
+                    message.sdp = String(object.sdp);
+                if (object.candidate != null)
+                    message.candidate = String(object.candidate);
+                if (object.sdpMid != null)
+                    message.sdpMid = String(object.sdpMid);
+                if (object.sdpMlineIndex != null)
+                    message.sdpMlineIndex = object.sdpMlineIndex >>> 0;
                 return message;
             };
 
             /**
-             * Creates a plain object from a PresenceData message. Also converts values to other types if specified.
+             * Creates a plain object from a RtcSignal message. Also converts values to other types if specified.
              * @function toObject
-             * @memberof graphwiz.core.PresenceData
+             * @memberof graphwiz.core.RtcSignal
              * @static
-             * @param {graphwiz.core.PresenceData} message PresenceData
+             * @param {graphwiz.core.RtcSignal} message RtcSignal
              * @param {$protobuf.IConversionOptions} [options] Conversion options
              * @returns {Object.<string,*>} Plain object
              */
-            PresenceData.toObject = function toObject(message, options) {
+            RtcSignal.toObject = function toObject(message, options) {
                 if (!options)
                     options = {};
                 let object = {};
                 if (options.defaults) {
-                    object.displayName = "";
-                    object.avatarUrl = "";
-                    object.position = null;
-                    object.rotation = null;
-                    object.avatarConfig = null;
-                    object.hostClientId = "";
+                    object.signalType = options.enums === String ? "OFFER" : 0;
+                    object.fromClientId = "";
+                    object.toClientId = "";
+                    object.sdp = "";
+                    object.candidate = "";
+                    object.sdpMid = "";
+                    object.sdpMlineIndex = 0;
                 }
-                if (message.displayName != null && message.hasOwnProperty("displayName"))
-                    object.displayName = message.displayName;
-                if (message.avatarUrl != null && message.hasOwnProperty("avatarUrl"))
-                    object.avatarUrl = message.avatarUrl;
-                if (message.position != null && message.hasOwnProperty("position"))
-                    object.position = $root.graphwiz.core.Vector3.toObject(message.position, options);
-                if (message.rotation != null && message.hasOwnProperty("rotation"))
-                    object.rotation = $root.graphwiz.core.Quaternion.toObject(message.rotation, options);
-                if (message.avatarConfig != null && message.hasOwnProperty("avatarConfig"))
-                    object.avatarConfig = $root.graphwiz.core.AvatarConfig.toObject(message.avatarConfig, options);
-                if (message.hostClientId != null && message.hasOwnProperty("hostClientId"))
-                    object.hostClientId = message.hostClientId;
+                if (message.signalType != null && message.hasOwnProperty("signalType"))
+                    object.signalType = options.enums === String ? $root.graphwiz.core.RtcSignalType[message.signalType] === undefined ? message.signalType : $root.graphwiz.core.RtcSignalType[message.signalType] : message.signalType;
+                if (message.fromClientId != null && message.hasOwnProperty("fromClientId"))
+                    object.fromClientId = message.fromClientId;
+                if (message.toClientId != null && message.hasOwnProperty("toClientId"))
+                    object.toClientId = message.toClientId;
+                if (message.sdp != null && message.hasOwnProperty("sdp"))
+                    object.sdp = message.sdp;
+                if (message.candidate != null && message.hasOwnProperty("candidate"))
+                    object.candidate = message.candidate;
+                if (message.sdpMid != null && message.hasOwnProperty("sdpMid"))
+                    object.sdpMid = message.sdpMid;
+                if (message.sdpMlineIndex != null && message.hasOwnProperty("sdpMlineIndex"))
+                    object.sdpMlineIndex = message.sdpMlineIndex;
                 return object;
             };
 
             /**
-             * Converts this PresenceData to JSON.
+             * Converts this RtcSignal to JSON.
              * @function toJSON
-             * @memberof graphwiz.core.PresenceData
+             * @memberof graphwiz.core.RtcSignal
              * @instance
              * @returns {Object.<string,*>} JSON object
              */
-            PresenceData.prototype.toJSON = function toJSON() {
+            RtcSignal.prototype.toJSON = function toJSON() {
                 return this.constructor.toObject(this, $protobuf.util.toJSONOptions);
             };
 
             /**
-             * Gets the default type url for PresenceData
+             * Gets the default type url for RtcSignal
              * @function getTypeUrl
-             * @memberof graphwiz.core.PresenceData
+             * @memberof graphwiz.core.RtcSignal
              * @static
              * @param {string} [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
              * @returns {string} The default type url
              */
-            PresenceData.getTypeUrl = function getTypeUrl(typeUrlPrefix) {
+            RtcSignal.getTypeUrl = function getTypeUrl(typeUrlPrefix) {
                 if (typeUrlPrefix === undefined) {
                     typeUrlPrefix = "type.googleapis.com";
                 }
-                return typeUrlPrefix + "/graphwiz.core.PresenceData";
+                return typeUrlPrefix + "/graphwiz.core.RtcSignal";
             };
 
-            return PresenceData;
+            return RtcSignal;
+        })();
+
+        /**
+         * RtcSignalType enum.
+         * @name graphwiz.core.RtcSignalType
+         * @enum {number}
+         * @property {number} OFFER=0 OFFER value
+         * @property {number} ANSWER=1 ANSWER value
+         * @property {number} ICE_CANDIDATE=2 ICE_CANDIDATE value
+         * @property {number} HANGUP=3 HANGUP value
+         */
+        core.RtcSignalType = (function() {
+            const valuesById = {}, values = Object.create(valuesById);
+            values[valuesById[0] = "OFFER"] = 0;
+            values[valuesById[1] = "ANSWER"] = 1;
+            values[valuesById[2] = "ICE_CANDIDATE"] = 2;
+            values[valuesById[3] = "HANGUP"] = 3;
+            return values;
         })();
 
         core.PlayerSnapshot = (function() {
@@ -4522,6 +6908,8 @@ export const graphwiz = $root.graphwiz = (() => {
              * @property {string|null} [assignedClientId] ServerHello assignedClientId
              * @property {string|null} [roomId] ServerHello roomId
              * @property {graphwiz.core.IWorldState|null} [initialState] ServerHello initialState
+             * @property {string|null} [resumeToken] ServerHello resumeToken
+             * @property {boolean|null} [resumed] ServerHello resumed
              */
 
             /**
@@ -4571,6 +6959,22 @@ export const graphwiz = $root.graphwiz = (() => {
              */
             ServerHello.prototype.initialState = null;
 
+            /**
+             * ServerHello resumeToken.
+             * @member {string} resumeToken
+             * @memberof graphwiz.core.ServerHello
+             * @instance
+             */
+            ServerHello.prototype.resumeToken = "";
+
+            /**
+             * ServerHello resumed.
+             * @member {boolean} resumed
+             * @memberof graphwiz.core.ServerHello
+             * @instance
+             */
+            ServerHello.prototype.resumed = false;
+
             /**
              * Creates a new ServerHello instance using the specified properties.
              * @function create
@@ -4603,6 +7007,10 @@ export const graphwiz = $root.graphwiz = (() => {
                     writer.uint32(/* id 3, wireType 2 =*/26).string(message.roomId);
                 if (message.initialState != null && Object.hasOwnProperty.call(message, "initialState"))
                     $root.graphwiz.core.WorldState.encode(message.initialState, writer.uint32(/* id 4, wireType 2 =*/34).fork()).ldelim();
+                if (message.resumeToken != null && Object.hasOwnProperty.call(message, "resumeToken"))
+                    writer.uint32(/* id 5, wireType 2 =*/42).string(message.resumeToken);
+                if (message.resumed != null && Object.hasOwnProperty.call(message, "resumed"))
+                    writer.uint32(/* id 6, wireType 0 =*/48).bool(message.resumed);
                 return writer;
             };
 
@@ -4655,6 +7063,14 @@ export const graphwiz = $root.graphwiz = (() => {
                             message.initialState = $root.graphwiz.core.WorldState.decode(reader, reader.uint32());
                             break;
                         }
+                    case 5: {
+                            message.resumeToken = reader.string();
+                            break;
+                        }
+                    case 6: {
+                            message.resumed = reader.bool();
+                            break;
+                        }
                     default:
                         reader.skipType(tag & 7);
                         break;
@@ -4704,6 +7120,12 @@ export const graphwiz = $root.graphwiz = (() => {
                     if (error)
                         return "initialState." + error;
                 }
+                if (message.resumeToken != null && message.hasOwnProperty("resumeToken"))
+                    if (!$util.isString(message.resumeToken))
+                        return "resumeToken: string expected";
+                if (message.resumed != null && message.hasOwnProperty("resumed"))
+                    if (typeof message.resumed !== "boolean")
+                        return "resumed: boolean expected";
                 return null;
             };
 
@@ -4730,6 +7152,10 @@ export const graphwiz = $root.graphwiz = (() => {
                         throw TypeError(".graphwiz.core.ServerHello.initialState: object expected");
                     message.initialState = $root.graphwiz.core.WorldState.fromObject(object.initialState);
                 }
+                if (object.resumeToken != null)
+                    message.resumeToken = String(object.resumeToken);
+                if (object.resumed != null)
+                    message.resumed = Boolean(object.resumed);
                 return message;
             };
 
@@ -4751,6 +7177,8 @@ export const graphwiz = $root.graphwiz = (() => {
                     object.assignedClientId = "";
                     object.roomId = "";
                     object.initialState = null;
+                    object.resumeToken = "";
+                    object.resumed = false;
                 }
                 if (message.serverVersion != null && message.hasOwnProperty("serverVersion"))
                     object.serverVersion = message.serverVersion;
@@ -4760,6 +7188,10 @@ export const graphwiz = $root.graphwiz = (() => {
                     object.roomId = message.roomId;
                 if (message.initialState != null && message.hasOwnProperty("initialState"))
                     object.initialState = $root.graphwiz.core.WorldState.toObject(message.initialState, options);
+                if (message.resumeToken != null && message.hasOwnProperty("resumeToken"))
+                    object.resumeToken = message.resumeToken;
+                if (message.resumed != null && message.hasOwnProperty("resumed"))
+                    object.resumed = message.resumed;
                 return object;
             };
 
@@ -4792,6 +7224,258 @@ export const graphwiz = $root.graphwiz = (() => {
             return ServerHello;
         })();
 
+        core.Redirect = (function() {
+
+            /**
+             * Properties of a Redirect.
+             * @memberof graphwiz.core
+             * @interface IRedirect
+             * @property {string|null} [url] Redirect url
+             * @property {string|null} [resumeToken] Redirect resumeToken
+             * @property {number|null} [reconnectWithinMs] Redirect reconnectWithinMs
+             */
+
+            /**
+             * Constructs a new Redirect.
+             * @memberof graphwiz.core
+             * @classdesc Represents a Redirect.
+             * @implements IRedirect
+             * @constructor
+             * @param {graphwiz.core.IRedirect=} [properties] Properties to set
+             */
+            function Redirect(properties) {
+                if (properties)
+                    for (let keys = Object.keys(properties), i = 0; i < keys.length; ++i)
+                        if (properties[keys[i]] != null)
+                            this[keys[i]] = properties[keys[i]];
+            }
+
+            /**
+             * Redirect url.
+             * @member {string} url
+             * @memberof graphwiz.core.Redirect
+             * @instance
+             */
+            Redirect.prototype.url = "";
+
+            /**
+             * Redirect resumeToken.
+             * @member {string} resumeToken
+             * @memberof graphwiz.core.Redirect
+             * @instance
+             */
+            Redirect.prototype.resumeToken = "";
+
+            /**
+             * Redirect reconnectWithinMs.
+             * @member {number} reconnectWithinMs
+             * @memberof graphwiz.core.Redirect
+             * @instance
+             */
+            Redirect.prototype.reconnectWithinMs = 0;
+
+            /**
+             * Creates a new Redirect instance using the specified properties.
+             * @function create
+             * @memberof graphwiz.core.Redirect
+             * @static
+             * @param {graphwiz.core.IRedirect=} [properties] Properties to set
+             * @returns {graphwiz.core.Redirect} Redirect instance
+             */
+            Redirect.create = function create(properties) {
+                return new Redirect(properties);
+            };
+
+            /**
+             * Encodes the specified Redirect message. Does not implicitly {@link graphwiz.core.Redirect.verify|verify} messages.
+             * @function encode
+             * @memberof graphwiz.core.Redirect
+             * @static
+             * @param {graphwiz.core.IRedirect} message Redirect message or plain object to encode
+             * @param {$protobuf.Writer} [writer] Writer to encode to
+             * @returns {$protobuf.Writer} Writer
+             */
+            Redirect.encode = function encode(message, writer) {
+                if (!writer)
+                    writer = $Writer.create();
+                if (message.url != null && Object.hasOwnProperty.call(message, "url"))
+                    writer.uint32(/* id 1, wireType 2 =*/10).string(message.url);
+                if (message.resumeToken != null && Object.hasOwnProperty.call(message, "resumeToken"))
+                    writer.uint32(/* id 2, wireType 2 =*/18).string(message.resumeToken);
+                if (message.reconnectWithinMs != null && Object.hasOwnProperty.call(message, "reconnectWithinMs"))
+                    writer.uint32(/* id 3, wireType 0 =*/24).uint32(message.reconnectWithinMs);
+                return writer;
+            };
+
+            /**
+             * Encodes the specified Redirect message, length delimited. Does not implicitly {@link graphwiz.core.Redirect.verify|verify} messages.
+             * @function encodeDelimited
+             * @memberof graphwiz.core.Redirect
+             * @static
+             * @param {graphwiz.core.IRedirect} message Redirect message or plain object to encode
+             * @param {$protobuf.Writer} [writer] Writer to encode to
+             * @returns {$protobuf.Writer} Writer
+             */
+            Redirect.encodeDelimited = function encodeDelimited(message, writer) {
+                return this.encode(message, writer).ldelim();
+            };
+
+            /**
+             * Decodes a Redirect message from the specified reader or buffer.
+             * @function decode
+             * @memberof graphwiz.core.Redirect
+             * @static
+             * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
+             * @param {number} [length] Message length if known beforehand
+             * @returns {graphwiz.core.Redirect} Redirect
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            Redirect.decode = function decode(reader, length, error) {
+                if (!(reader instanceof $Reader))
+                    reader = $Reader.create(reader);
+                let end = length === undefined ? reader.len : reader.pos + length, message = new $root.graphwiz.core.Redirect();
+                while (reader.pos < end) {
+                    let tag = reader.uint32();
+                    if (tag === error)
+                        break;
+                    switch (tag >>> 3) {
+                    case 1: {
+                            message.url = reader.string();
+                            break;
+                        }
+                    case 2: {
+                            message.resumeToken = reader.string();
+                            break;
+                        }
+                    case 3: {
+                            message.reconnectWithinMs = reader.uint32();
+                            break;
+                        }
+                    default:
+                        reader.skipType(tag & 7);
+                        break;
+                    }
+                }
+                return message;
+            };
+
+            /**
+             * Decodes a Redirect message from the specified reader or buffer, length delimited.
+             * @function decodeDelimited
+             * @memberof graphwiz.core.Redirect
+             * @static
+             * @param {$protobuf.Reader|Uint8Array} reader Reader or buffer to decode from
+             * @returns {graphwiz.core.Redirect} Redirect
+             * @throws {Error} If the payload is not a reader or valid buffer
+             * @throws {$protobuf.util.ProtocolError} If required fields are missing
+             */
+            Redirect.decodeDelimited = function decodeDelimited(reader) {
+                if (!(reader instanceof $Reader))
+                    reader = new $Reader(reader);
+                return this.decode(reader, reader.uint32());
+            };
+
+            /**
+             * Verifies a Redirect message.
+             * @function verify
+             * @memberof graphwiz.core.Redirect
+             * @static
+             * @param {Object.<string,*>} message Plain object to verify
+             * @returns {string|null} `null` if valid, otherwise the reason why it is not
+             */
+            Redirect.verify = function verify(message) {
+                if (typeof message !== "object" || message === null)
+                    return "object expected";
+                if (message.url != null && message.hasOwnProperty("url"))
+                    if (!$util.isString(message.url))
+                        return "url: string expected";
+                if (message.resumeToken != null && message.hasOwnProperty("resumeToken"))
+                    if (!$util.isString(message.resumeToken))
+                        return "resumeToken: string expected";
+                if (message.reconnectWithinMs != null && message.hasOwnProperty("reconnectWithinMs"))
+                    if (!$util.isInteger(message.reconnectWithinMs))
+                        return "reconnectWithinMs: integer expected";
+                return null;
+            };
+
+            /**
+             * Creates a Redirect message from a plain object. Also converts values to their respective internal types.
+             * @function fromObject
+             * @memberof graphwiz.core.Redirect
+             * @static
+             * @param {Object.<string,*>} object Plain object
+             * @returns {graphwiz.core.Redirect} Redirect
+             */
+            Redirect.fromObject = function fromObject(object) {
+                if (object instanceof $root.graphwiz.core.Redirect)
+                    return object;
+                let message = new $root.graphwiz.core.Redirect();
+                if (object.url != null)
+                    message.url = String(object.url);
+                if (object.resumeToken != null)
+                    message.resumeToken = String(object.resumeToken);
+                if (object.reconnectWithinMs != null)
+                    message.reconnectWithinMs = object.reconnectWithinMs >>> 0;
+                return message;
+            };
+
+            /**
+             * Creates a plain object from a Redirect message. Also converts values to other types if specified.
+             * @function toObject
+             * @memberof graphwiz.core.Redirect
+             * @static
+             * @param {graphwiz.core.Redirect} message Redirect
+             * @param {$protobuf.IConversionOptions} [options] Conversion options
+             * @returns {Object.<string,*>} Plain object
+             */
+            Redirect.toObject = function toObject(message, options) {
+                if (!options)
+                    options = {};
+                let object = {};
+                if (options.defaults) {
+                    object.url = "";
+                    object.resumeToken = "";
+                    object.reconnectWithinMs = 0;
+                }
+                if (message.url != null && message.hasOwnProperty("url"))
+                    object.url = message.url;
+                if (message.resumeToken != null && message.hasOwnProperty("resumeToken"))
+                    object.resumeToken = message.resumeToken;
+                if (message.reconnectWithinMs != null && message.hasOwnProperty("reconnectWithinMs"))
+                    object.reconnectWithinMs = message.reconnectWithinMs;
+                return object;
+            };
+
+            /**
+             * Converts this Redirect to JSON.
+             * @function toJSON
+             * @memberof graphwiz.core.Redirect
+             * @instance
+             * @returns {Object.<string,*>} JSON object
+             */
+            Redirect.prototype.toJSON = function toJSON() {
+                return this.constructor.toObject(this, $protobuf.util.toJSONOptions);
+            };
+
+            /**
+             * Gets the default type url for Redirect
+             * @function getTypeUrl
+             * @memberof graphwiz.core.Redirect
+             * @static
+             * @param {string} [typeUrlPrefix] your custom typeUrlPrefix(default "type.googleapis.com")
+             * @returns {string} The default type url
+             */
+            Redirect.getTypeUrl = function getTypeUrl(typeUrlPrefix) {
+                if (typeUrlPrefix === undefined) {
+                    typeUrlPrefix = "type.googleapis.com";
+                }
+                return typeUrlPrefix + "/graphwiz.core.Redirect";
+            };
+
+            return Redirect;
+        })();
+
         core.WorldState = (function() {
 
             /**
@@ -7131,9 +9815,44 @@ export const graphwiz = $root.graphwiz = (() => {
              * @variation 2
              */
 
+            return RoomService;
+        })();
+
+        networking.PresenceService = (function() {
+
             /**
-             * Callback as used by {@link graphwiz.networking.RoomService#watchPresence}.
-             * @memberof graphwiz.networking.RoomService
+             * Constructs a new PresenceService service.
+             * @memberof graphwiz.networking
+             * @classdesc Represents a PresenceService
+             * @extends $protobuf.rpc.Service
+             * @constructor
+             * @param {$protobuf.RPCImpl} rpcImpl RPC implementation
+             * @param {boolean} [requestDelimited=false] Whether requests are length-delimited
+             * @param {boolean} [responseDelimited=false] Whether responses are length-delimited
+             */
+            function PresenceService(rpcImpl, requestDelimited, responseDelimited) {
+                $protobuf.rpc.Service.call(this, rpcImpl, requestDelimited, responseDelimited);
+            }
+
+            (PresenceService.prototype = Object.create($protobuf.rpc.Service.prototype)).constructor = PresenceService;
+
+            /**
+             * Creates new PresenceService service using the specified rpc implementation.
+             * @function create
+             * @memberof graphwiz.networking.PresenceService
+             * @static
+             * @param {$protobuf.RPCImpl} rpcImpl RPC implementation
+             * @param {boolean} [requestDelimited=false] Whether requests are length-delimited
+             * @param {boolean} [responseDelimited=false] Whether responses are length-delimited
+             * @returns {PresenceService} RPC service. Useful where requests and/or responses are streamed.
+             */
+            PresenceService.create = function create(rpcImpl, requestDelimited, responseDelimited) {
+                return new this(rpcImpl, requestDelimited, responseDelimited);
+            };
+
+            /**
+             * Callback as used by {@link graphwiz.networking.PresenceService#watchPresence}.
+             * @memberof graphwiz.networking.PresenceService
              * @typedef WatchPresenceCallback
              * @type {function}
              * @param {Error|null} error Error, if any
@@ -7143,28 +9862,28 @@ export const graphwiz = $root.graphwiz = (() => {
             /**
              * Calls WatchPresence.
              * @function watchPresence
-             * @memberof graphwiz.networking.RoomService
+             * @memberof graphwiz.networking.PresenceService
              * @instance
              * @param {graphwiz.networking.IPresenceRequest} request PresenceRequest message or plain object
-             * @param {graphwiz.networking.RoomService.WatchPresenceCallback} callback Node-style callback called with the error, if any, and PresenceEvent
+             * @param {graphwiz.networking.PresenceService.WatchPresenceCallback} callback Node-style callback called with the error, if any, and PresenceEvent
              * @returns {undefined}
              * @variation 1
              */
-            Object.defineProperty(RoomService.prototype.watchPresence = function watchPresence(request, callback) {
+            Object.defineProperty(PresenceService.prototype.watchPresence = function watchPresence(request, callback) {
                 return this.rpcCall(watchPresence, $root.graphwiz.networking.PresenceRequest, $root.graphwiz.core.PresenceEvent, request, callback);
             }, "name", { value: "WatchPresence" });
 
             /**
              * Calls WatchPresence.
              * @function watchPresence
-             * @memberof graphwiz.networking.RoomService
+             * @memberof graphwiz.networking.PresenceService
              * @instance
              * @param {graphwiz.networking.IPresenceRequest} request PresenceRequest message or plain object
              * @returns {Promise<graphwiz.core.PresenceEvent>} Promise
              * @variation 2
              */
 
-            return RoomService;
+            return PresenceService;
         })();
 
         networking.ClientMessage = (function() {
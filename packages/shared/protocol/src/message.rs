@@ -14,6 +14,7 @@ impl MessageBuilder {
             message_id: Uuid::new_v4().to_string(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             r#type: MessageType::PositionUpdate as i32,
+            sequence: 0,
             payload: Some(message::Payload::PositionUpdate(PositionUpdate {
                 entity_id,
                 position: Some(position),
@@ -23,6 +24,28 @@ impl MessageBuilder {
         }
     }
 
+    /// Create a batch of position updates for one server tick
+    pub fn position_batch(updates: Vec<PositionUpdate>, tick: i64) -> Message {
+        Message {
+            message_id: Uuid::new_v4().to_string(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            r#type: MessageType::PositionBatch as i32,
+            sequence: 0,
+            payload: Some(message::Payload::PositionBatch(PositionBatch { updates, tick })),
+        }
+    }
+
+    /// Create the gain and pan of the speakers one listener hears
+    pub fn spatial_audio(speakers: Vec<SpeakerSpatial>) -> Message {
+        Message {
+            message_id: Uuid::new_v4().to_string(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            r#type: MessageType::SpatialAudio as i32,
+            sequence: 0,
+            payload: Some(message::Payload::SpatialAudio(SpatialAudio { speakers })),
+        }
+    }
+
     /// Create a new voice data message
     pub fn voice_data(
         from_client_id: String,
@@ -34,6 +57,7 @@ impl MessageBuilder {
             message_id: Uuid::new_v4().to_string(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             r#type: MessageType::VoiceData as i32,
+            sequence: 0,
             payload: Some(message::Payload::VoiceData(VoiceData {
                 from_client_id,
                 audio_data,
@@ -54,6 +78,7 @@ impl MessageBuilder {
             message_id: Uuid::new_v4().to_string(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             r#type: MessageType::EntitySpawn as i32,
+            sequence: 0,
             payload: Some(message::Payload::EntitySpawn(EntitySpawn {
                 entity_id,
                 template_id,
@@ -63,16 +88,46 @@ impl MessageBuilder {
         }
     }
 
+    /// Create a new entity update message
+    pub fn entity_update(
+        entity_id: String,
+        components: std::collections::HashMap<String, Vec<u8>>,
+    ) -> Message {
+        Message {
+            message_id: Uuid::new_v4().to_string(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            r#type: MessageType::EntityUpdate as i32,
+            sequence: 0,
+            payload: Some(message::Payload::EntityUpdate(EntityUpdate {
+                entity_id,
+                components,
+            })),
+        }
+    }
+
     /// Create a new entity despawn message
     pub fn entity_despawn(entity_id: String) -> Message {
         Message {
             message_id: Uuid::new_v4().to_string(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             r#type: MessageType::EntityDespawn as i32,
+            sequence: 0,
             payload: Some(message::Payload::EntityDespawn(EntityDespawn { entity_id })),
         }
     }
 
+    /// Create a message undoing the sender's last entity change, or redoing
+    /// their last undone one
+    pub fn entity_history(redo: bool) -> Message {
+        Message {
+            message_id: Uuid::new_v4().to_string(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            r#type: MessageType::EntityHistory as i32,
+            sequence: 0,
+            payload: Some(message::Payload::EntityHistory(EntityHistory { redo })),
+        }
+    }
+
     /// Create a new chat message
     pub fn chat_message(
         from_client_id: String,
@@ -83,11 +138,29 @@ impl MessageBuilder {
             message_id: Uuid::new_v4().to_string(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             r#type: MessageType::ChatMessage as i32,
+            sequence: 0,
             payload: Some(message::Payload::ChatMessage(ChatMessage {
                 from_client_id,
                 message,
                 timestamp: chrono::Utc::now().timestamp_millis(),
                 r#type: msg_type as i32,
+                ..Default::default()
+            })),
+        }
+    }
+
+    /// Create a notice that the chat message `chat_id` was removed
+    pub fn chat_deleted(chat_id: String) -> Message {
+        Message {
+            message_id: Uuid::new_v4().to_string(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            r#type: MessageType::ChatMessage as i32,
+            sequence: 0,
+            payload: Some(message::Payload::ChatMessage(ChatMessage {
+                chat_id,
+                deleted: true,
+                timestamp: chrono::Utc::now().timestamp_millis(),
+                ..Default::default()
             })),
         }
     }
@@ -102,6 +175,7 @@ impl MessageBuilder {
             message_id: Uuid::new_v4().to_string(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             r#type: MessageType::PresenceUpdate as i32,
+            sequence: 0,
             payload: Some(message::Payload::PresenceEvent(PresenceEvent {
                 client_id,
                 event_type: event_type as i32,
@@ -109,6 +183,142 @@ impl MessageBuilder {
             })),
         }
     }
+
+    /// Create a status event for `user_id`; `room_id` is empty when the user is
+    /// in no room
+    pub fn presence_status(user_id: String, status: PresenceStatus, room_id: String) -> Message {
+        Message {
+            message_id: Uuid::new_v4().to_string(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            r#type: MessageType::PresenceStatus as i32,
+            sequence: 0,
+            payload: Some(message::Payload::PresenceEvent(PresenceEvent {
+                client_id: String::new(),
+                event_type: PresenceEventType::Status as i32,
+                data: Some(PresenceData {
+                    user_id,
+                    status: status as i32,
+                    room_id,
+                    ..Default::default()
+                }),
+            })),
+        }
+    }
+
+    /// Create a subscription to the status of `user_ids`
+    pub fn presence_subscribe(user_ids: Vec<String>) -> Message {
+        Message {
+            message_id: Uuid::new_v4().to_string(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            r#type: MessageType::PresenceSubscribe as i32,
+            sequence: 0,
+            payload: Some(message::Payload::PresenceSubscription(PresenceSubscription { user_ids })),
+        }
+    }
+
+    /// Create a WebRTC signaling message for one peer
+    pub fn rtc_signal(signal: RtcSignal) -> Message {
+        Message {
+            message_id: Uuid::new_v4().to_string(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            r#type: MessageType::RtcSignal as i32,
+            sequence: 0,
+            payload: Some(message::Payload::RtcSignal(signal)),
+        }
+    }
+
+    /// Create the hello a client sends when its connection opens
+    pub fn client_hello(
+        client_id: String,
+        display_name: String,
+        auth_token: String,
+        requested_room: String,
+    ) -> Message {
+        Message {
+            message_id: Uuid::new_v4().to_string(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            r#type: MessageType::ClientHello as i32,
+            sequence: 0,
+            payload: Some(message::Payload::ClientHello(ClientHello {
+                client_id,
+                display_name,
+                auth_token,
+                requested_room,
+            })),
+        }
+    }
+
+    /// Create a server hello for a new or resumed connection
+    pub fn server_hello(
+        assigned_client_id: String,
+        room_id: String,
+        resume_token: String,
+        resumed: bool,
+    ) -> Message {
+        Message {
+            message_id: Uuid::new_v4().to_string(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            r#type: MessageType::ServerHello as i32,
+            sequence: 0,
+            payload: Some(message::Payload::ServerHello(ServerHello {
+                server_version: env!("CARGO_PKG_VERSION").to_string(),
+                assigned_client_id,
+                room_id,
+                initial_state: None,
+                resume_token,
+                resumed,
+            })),
+        }
+    }
+
+    /// Create a redirect telling a client to reconnect elsewhere before the
+    /// server shuts down
+    pub fn redirect(url: String, resume_token: String, reconnect_within_ms: u32) -> Message {
+        Message {
+            message_id: Uuid::new_v4().to_string(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            r#type: MessageType::Redirect as i32,
+            sequence: 0,
+            payload: Some(message::Payload::Redirect(Redirect {
+                url,
+                resume_token,
+                reconnect_within_ms,
+            })),
+        }
+    }
+
+    /// Create an error frame; `retry_after_ms` is 0 when retrying will not help
+    pub fn error_frame(code: &str, message: String, retry_after_ms: i64) -> Message {
+        Message {
+            message_id: Uuid::new_v4().to_string(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            r#type: MessageType::Error as i32,
+            sequence: 0,
+            payload: Some(message::Payload::ErrorFrame(ErrorFrame {
+                code: code.to_string(),
+                message,
+                retry_after_ms,
+            })),
+        }
+    }
+
+    /// Create a host-changed presence event; `previous_host` is the departed host
+    pub fn host_changed(previous_host: String, new_host: String) -> Message {
+        Message {
+            message_id: Uuid::new_v4().to_string(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            r#type: MessageType::PresenceHostChanged as i32,
+            sequence: 0,
+            payload: Some(message::Payload::PresenceEvent(PresenceEvent {
+                client_id: previous_host,
+                event_type: PresenceEventType::HostChanged as i32,
+                data: Some(PresenceData {
+                    host_client_id: new_host,
+                    ..Default::default()
+                }),
+            })),
+        }
+    }
 }
 
 /// Parser for GraphWiz-XR protocol messages
@@ -20,13 +20,18 @@ export enum MessageType {
   // Connection lifecycle
   CLIENT_HELLO = 1,
   SERVER_HELLO = 2,
+  ERROR = 3,
+  REDIRECT = 4,
   // Real-time updates
   POSITION_UPDATE = 10,
   VOICE_DATA = 11,
+  POSITION_BATCH = 12,
+  SPATIAL_AUDIO = 13,
   // Entity management
   ENTITY_SPAWN = 20,
   ENTITY_UPDATE = 21,
   ENTITY_DESPAWN = 22,
+  ENTITY_HISTORY = 23,
   // Communication
   CHAT_MESSAGE = 30,
   EMOJI_REACTION = 31,
@@ -37,6 +42,11 @@ export enum MessageType {
   PRESENCE_JOIN = 40,
   PRESENCE_LEAVE = 41,
   PRESENCE_UPDATE = 42,
+  PRESENCE_HOST_CHANGED = 43,
+  PRESENCE_STATUS = 44,
+  PRESENCE_SUBSCRIBE = 45,
+  // Peer-to-peer media
+  RTC_SIGNAL = 50,
 }
 
 export interface PositionUpdate {
@@ -47,6 +57,21 @@ export interface PositionUpdate {
   timestamp: number;
 }
 
+export interface PositionBatch {
+  updates: PositionUpdate[];
+  tick: number;
+}
+
+export interface SpeakerSpatial {
+  clientId: string;
+  gain: number;
+  pan: number;
+}
+
+export interface SpatialAudio {
+  speakers: SpeakerSpatial[];
+}
+
 export interface VoiceData {
   fromClientId: string;
   audioData: ArrayBuffer;
@@ -76,17 +101,26 @@ export interface EntityDespawn {
   entityId: string;
 }
 
+export interface EntityHistory {
+  redo: boolean; // False to undo
+}
+
 export interface ChatMessage {
   fromClientId: string;
   message: string;
   timestamp: number;
   type: ChatMessageType;
+  toUserId?: string; // WHISPER recipient, a signed-in user
+  channel?: string; // TEAM channel, set by the server
+  chatId?: string; // Assigned by the server
+  deleted?: boolean; // Removed by a moderator
 }
 
 export enum ChatMessageType {
   NORMAL = 0,
   WHISPER = 1,
   SHOUT = 2,
+  TEAM = 3,
 }
 
 export interface EmojiReaction {
@@ -118,10 +152,18 @@ export interface ObjectRelease {
   timestamp: number;
 }
 
+export interface ErrorFrame {
+  code: string;
+  message: string;
+  retryAfterMs: number;
+}
+
 export enum PresenceEventType {
   JOIN = 0,
   LEAVE = 1,
   UPDATE = 2,
+  HOST_CHANGED = 3,
+  STATUS = 4,
 }
 
 export interface PresenceData {
@@ -129,25 +171,65 @@ export interface PresenceData {
   avatarUrl?: string;
   position?: Vector3;
   rotation?: Quaternion;
+  hostClientId?: string;
+  userId?: string;
+  status?: PresenceStatus;
+  roomId?: string;
+}
+
+export enum PresenceStatus {
+  OFFLINE = 0,
+  ONLINE = 1,
+  AWAY = 2,
+  BUSY = 3,
+}
+
+export interface PresenceSubscription {
+  userIds: string[];
+}
+
+export enum RtcSignalType {
+  OFFER = 0,
+  ANSWER = 1,
+  ICE_CANDIDATE = 2,
+  HANGUP = 3,
+}
+
+export interface RtcSignal {
+  signalType: RtcSignalType;
+  fromClientId: string;
+  toClientId: string;
+  sdp?: string;
+  candidate?: string;
+  sdpMid?: string;
+  sdpMlineIndex?: number;
 }
 
 export interface Message {
   messageId: string;
   timestamp: number;
   type: MessageType;
+  sequence?: number;
   payload:
     | ClientHello
     | ServerHello
+    | Redirect
     | PositionUpdate
     | VoiceData
+    | PositionBatch
+    | SpatialAudio
     | EntitySpawn
     | EntityUpdate
     | EntityDespawn
+    | EntityHistory
     | ChatMessage
     | EmojiReaction
     | ObjectGrab
     | ObjectRelease
-    | PresenceEvent;
+    | PresenceEvent
+    | PresenceSubscription
+    | ErrorFrame
+    | RtcSignal;
 }
 
 export interface ClientHello {
@@ -162,6 +244,14 @@ export interface ServerHello {
   assignedClientId: string;
   roomId: string;
   initialState?: WorldState;
+  resumeToken?: string;
+  resumed?: boolean;
+}
+
+export interface Redirect {
+  url: string;
+  resumeToken: string;
+  reconnectWithinMs: number;
 }
 
 export interface WorldState {